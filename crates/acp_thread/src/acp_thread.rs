@@ -17,6 +17,9 @@ pub const TOOL_NAME_META_KEY: &str = "tool_name";
 /// The tool name for subagent spawning
 pub const SUBAGENT_TOOL_NAME: &str = "subagent";
 
+/// Key used in ACP session meta to store the session a thread was forked from.
+pub const PARENT_SESSION_ID_META_KEY: &str = "parent_session_id";
+
 /// Clamps a title to 2-3 words and removes punctuation.
 fn clamp_title_words(title: &str) -> String {
     let trimmed = title.trim();
@@ -42,6 +45,22 @@ pub fn tool_name_from_meta(meta: &Option<acp::Meta>) -> Option<SharedString> {
 pub fn meta_with_tool_name(tool_name: &str) -> acp::Meta {
     acp::Meta::from_iter([(TOOL_NAME_META_KEY.into(), tool_name.into())])
 }
+
+/// Helper to extract the session a thread was forked from, from ACP meta
+pub fn parent_session_id_from_meta(meta: &Option<acp::Meta>) -> Option<acp::SessionId> {
+    meta.as_ref()
+        .and_then(|m| m.get(PARENT_SESSION_ID_META_KEY))
+        .and_then(|v| v.as_str())
+        .map(|s| acp::SessionId::new(s.to_owned()))
+}
+
+/// Helper to create meta with the session a thread was forked from
+pub fn meta_with_parent_session_id(parent_session_id: &acp::SessionId) -> acp::Meta {
+    acp::Meta::from_iter([(
+        PARENT_SESSION_ID_META_KEY.into(),
+        parent_session_id.0.to_string().into(),
+    )])
+}
 use collections::HashSet;
 pub use connection::*;
 pub use diff::*;
@@ -2304,6 +2323,28 @@ impl AcpThread {
         })
     }
 
+    /// Restores the git working tree to the checkpoint taken before the given
+    /// message (if one exists), without rewinding this thread.
+    pub fn restore_git_checkpoint(
+        &mut self,
+        id: &UserMessageId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some((_, message)) = self.user_message_mut(id) else {
+            return Task::ready(Err(anyhow!("message not found")));
+        };
+        let Some(checkpoint) = message
+            .checkpoint
+            .as_ref()
+            .map(|c| c.git_checkpoint.clone())
+        else {
+            return Task::ready(Ok(()));
+        };
+
+        let git_store = self.project.read(cx).git_store().clone();
+        git_store.update(cx, |git, cx| git.restore_checkpoint(checkpoint, cx))
+    }
+
     /// Rewinds this thread to before the entry at `index`, removing it and all
     /// subsequent entries while rejecting any action_log changes made from that point.
     /// Unlike `restore_checkpoint`, this method does not restore from git.
@@ -48,6 +48,12 @@ pub enum MentionUri {
     Fetch {
         url: Url,
     },
+    /// A resource exposed by an MCP server.
+    McpResource {
+        server_id: String,
+        uri: String,
+        name: String,
+    },
 }
 
 impl MentionUri {
@@ -170,6 +176,15 @@ impl MentionUri {
                     Ok(Self::Directory {
                         abs_path: path.into(),
                     })
+                } else if let Some(server_id) = path.strip_prefix("/agent/mcp-resource/") {
+                    let server_id = decode(server_id)?.into_owned();
+                    let uri = query_param(&url, "uri").context("Missing resource URI")?;
+                    let name = query_param(&url, "name").unwrap_or_else(|| uri.clone());
+                    Ok(Self::McpResource {
+                        server_id,
+                        uri,
+                        name,
+                    })
                 } else if path.starts_with("/agent/selection") {
                     let fragment = url.fragment().context("Missing fragment for selection")?;
                     let line_range = parse_line_range(fragment)?;
@@ -206,6 +221,7 @@ impl MentionUri {
                 ..
             } => selection_name(path.as_deref(), line_range),
             MentionUri::Fetch { url } => url.to_string(),
+            MentionUri::McpResource { name, .. } => name.clone(),
         }
     }
 
@@ -223,6 +239,7 @@ impl MentionUri {
             MentionUri::Rule { .. } => IconName::Reader.path().into(),
             MentionUri::Selection { .. } => IconName::Reader.path().into(),
             MentionUri::Fetch { .. } => IconName::ToolWeb.path().into(),
+            MentionUri::McpResource { .. } => IconName::DatabaseZap.path().into(),
         }
     }
 
@@ -300,6 +317,22 @@ impl MentionUri {
                 url
             }
             MentionUri::Fetch { url } => url.clone(),
+            MentionUri::McpResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                let mut url = Url::parse("zed:///").unwrap();
+                url.path_segments_mut().unwrap().pop_if_empty().extend([
+                    "agent",
+                    "mcp-resource",
+                    server_id.as_str(),
+                ]);
+                url.query_pairs_mut()
+                    .append_pair("uri", uri)
+                    .append_pair("name", name);
+                url
+            }
         }
     }
 }
@@ -327,6 +360,12 @@ fn single_query_param(url: &Url, name: &'static str) -> Result<Option<String>> {
     }
 }
 
+fn query_param(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
 pub fn selection_name(path: Option<&Path>, line_range: &RangeInclusive<u32>) -> String {
     format!(
         "{} ({}:{})",
@@ -504,6 +543,25 @@ mod tests {
         assert_eq!(parsed.to_uri().to_string(), https_uri);
     }
 
+    #[test]
+    fn test_parse_mcp_resource_uri() {
+        let resource_uri = "zed:///agent/mcp-resource/my%20server?uri=postgres%3A%2F%2Fmain%2Fusers%2Fschema&name=users+schema";
+        let parsed = MentionUri::parse(resource_uri, PathStyle::local()).unwrap();
+        match &parsed {
+            MentionUri::McpResource {
+                server_id,
+                uri,
+                name,
+            } => {
+                assert_eq!(server_id, "my server");
+                assert_eq!(uri, "postgres://main/users/schema");
+                assert_eq!(name, "users schema");
+            }
+            _ => panic!("Expected McpResource variant"),
+        }
+        assert_eq!(parsed.to_uri().to_string(), resource_uri);
+    }
+
     #[test]
     fn test_invalid_scheme() {
         assert!(MentionUri::parse("ftp://example.com", PathStyle::local()).is_err());
@@ -1,5 +1,6 @@
 mod db;
 mod edit_agent;
+mod hooks;
 mod legacy_thread;
 mod native_agent_server;
 pub mod outline;
@@ -14,6 +15,7 @@ mod tools;
 
 use context_server::ContextServerId;
 pub use db::*;
+pub use hooks::*;
 pub use native_agent_server::NativeAgentServer;
 pub use pattern_extraction::*;
 pub use templates::*;
@@ -678,7 +680,9 @@ impl NativeAgent {
         cx: &mut Context<Self>,
     ) {
         match event {
-            ContextServerRegistryEvent::ToolsChanged => {}
+            ContextServerRegistryEvent::ToolsChanged
+            | ContextServerRegistryEvent::ResourcesChanged
+            | ContextServerRegistryEvent::ResourceUpdated { .. } => {}
             ContextServerRegistryEvent::PromptsChanged => {
                 self.update_available_commands(cx);
             }
@@ -964,6 +968,42 @@ impl NativeAgentConnection {
         self.0.update(cx, |this, cx| this.load_thread(id, cx))
     }
 
+    /// Saves a copy of the given thread's history before `message_id` as a new thread.
+    pub fn fork_thread(
+        &self,
+        session_id: &acp::SessionId,
+        message_id: &UserMessageId,
+        cx: &mut App,
+    ) -> Task<Result<AgentSessionInfo>> {
+        let Some(thread) = self.thread(session_id, cx) else {
+            return Task::ready(Err(anyhow!("Session not found")));
+        };
+        let fork = match thread.read(cx).fork(message_id, cx) {
+            Ok(fork) => fork,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        let thread_store = self.0.read(cx).thread_store.clone();
+
+        cx.spawn(async move |cx| {
+            let db_thread = fork.await;
+            let session_id = acp::SessionId::new(uuid::Uuid::new_v4().to_string());
+            let session_info = AgentSessionInfo {
+                session_id: session_id.clone(),
+                cwd: None,
+                title: Some(db_thread.title.clone()),
+                updated_at: Some(db_thread.updated_at),
+                meta: db_thread
+                    .parent_session_id
+                    .as_ref()
+                    .map(acp_thread::meta_with_parent_session_id),
+            };
+            thread_store
+                .update(cx, |store, cx| store.save_thread(session_id, db_thread, cx))
+                .await?;
+            Ok(session_info)
+        })
+    }
+
     fn run_turn(
         &self,
         session_id: acp::SessionId,
@@ -1452,7 +1492,10 @@ impl NativeAgentSessionList {
             cwd: None,
             title: Some(entry.title),
             updated_at: Some(entry.updated_at),
-            meta: None,
+            meta: entry
+                .parent_session_id
+                .as_ref()
+                .map(acp_thread::meta_with_parent_session_id),
         }
     }
 
@@ -29,6 +29,9 @@ pub struct DbThreadMetadata {
     #[serde(alias = "summary")]
     pub title: SharedString,
     pub updated_at: DateTime<Utc>,
+    /// The thread this one was forked from, if any.
+    #[serde(default)]
+    pub parent_session_id: Option<acp::SessionId>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -52,6 +55,9 @@ pub struct DbThread {
     pub profile: Option<AgentProfileId>,
     #[serde(default)]
     pub imported: bool,
+    /// The thread this one was forked from, if any.
+    #[serde(default)]
+    pub parent_session_id: Option<acp::SessionId>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +96,7 @@ impl SharedThread {
             model: self.model,
             profile: None,
             imported: true,
+            parent_session_id: None,
         }
     }
 
@@ -109,6 +116,21 @@ impl SharedThread {
 impl DbThread {
     pub const VERSION: &'static str = "0.3.0";
 
+    /// Serializes the thread in the versioned format understood by [`DbThread::from_json`].
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct SerializedThread<'a> {
+            #[serde(flatten)]
+            thread: &'a DbThread,
+            version: &'static str,
+        }
+
+        Ok(serde_json::to_string(&SerializedThread {
+            thread: self,
+            version: Self::VERSION,
+        })?)
+    }
+
     pub fn from_json(json: &[u8]) -> Result<Self> {
         let saved_thread_json = serde_json::from_slice::<serde_json::Value>(json)?;
         match saved_thread_json.get("version") {
@@ -264,6 +286,7 @@ impl DbThread {
             model: thread.model,
             profile: thread.profile,
             imported: false,
+            parent_session_id: None,
         })
     }
 }
@@ -361,6 +384,17 @@ impl ThreadsDatabase {
         "})?()
         .map_err(|e| anyhow!("Failed to create threads table: {}", e))?;
 
+        let has_parent_id = connection.select_row::<bool>(indoc! {"
+            SELECT COUNT(*) > 0 FROM pragma_table_info('threads') WHERE name = 'parent_id'
+        "})?()?
+        .unwrap_or(false);
+        if !has_parent_id {
+            connection.exec(indoc! {"
+                ALTER TABLE threads ADD COLUMN parent_id TEXT
+            "})?()
+            .map_err(|e| anyhow!("Failed to add parent_id to threads table: {}", e))?;
+        }
+
         let db = Self {
             executor,
             connection: Arc::new(Mutex::new(connection)),
@@ -376,19 +410,13 @@ impl ThreadsDatabase {
     ) -> Result<()> {
         const COMPRESSION_LEVEL: i32 = 3;
 
-        #[derive(Serialize)]
-        struct SerializedThread {
-            #[serde(flatten)]
-            thread: DbThread,
-            version: &'static str,
-        }
-
         let title = thread.title.to_string();
         let updated_at = thread.updated_at.to_rfc3339();
-        let json_data = serde_json::to_string(&SerializedThread {
-            thread,
-            version: DbThread::VERSION,
-        })?;
+        let parent_id = thread
+            .parent_session_id
+            .as_ref()
+            .map(|parent_id| parent_id.0.clone());
+        let json_data = thread.to_json()?;
 
         let connection = connection.lock();
 
@@ -396,11 +424,11 @@ impl ThreadsDatabase {
         let data_type = DataType::Zstd;
         let data = compressed;
 
-        let mut insert = connection.exec_bound::<(Arc<str>, String, String, DataType, Vec<u8>)>(indoc! {"
-            INSERT OR REPLACE INTO threads (id, summary, updated_at, data_type, data) VALUES (?, ?, ?, ?, ?)
+        let mut insert = connection.exec_bound::<(Arc<str>, String, String, DataType, Vec<u8>, Option<Arc<str>>)>(indoc! {"
+            INSERT OR REPLACE INTO threads (id, summary, updated_at, data_type, data, parent_id) VALUES (?, ?, ?, ?, ?, ?)
         "})?;
 
-        insert((id.0, title, updated_at, data_type, data))?;
+        insert((id.0, title, updated_at, data_type, data, parent_id))?;
 
         Ok(())
     }
@@ -411,19 +439,20 @@ impl ThreadsDatabase {
         self.executor.spawn(async move {
             let connection = connection.lock();
 
-            let mut select =
-                connection.select_bound::<(), (Arc<str>, String, String)>(indoc! {"
-                SELECT id, summary, updated_at FROM threads ORDER BY updated_at DESC
+            let mut select = connection
+                .select_bound::<(), (Arc<str>, String, String, Option<Arc<str>>)>(indoc! {"
+                SELECT id, summary, updated_at, parent_id FROM threads ORDER BY updated_at DESC
             "})?;
 
             let rows = select(())?;
             let mut threads = Vec::new();
 
-            for (id, summary, updated_at) in rows {
+            for (id, summary, updated_at, parent_id) in rows {
                 threads.push(DbThreadMetadata {
                     id: acp::SessionId::new(id),
                     title: summary.into(),
                     updated_at: DateTime::parse_from_rfc3339(&updated_at)?.with_timezone(&Utc),
+                    parent_session_id: parent_id.map(acp::SessionId::new),
                 });
             }
 
@@ -500,9 +529,14 @@ impl ThreadsDatabase {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{ContextServerRegistry, Templates, Thread};
     use chrono::{DateTime, TimeZone, Utc};
     use collections::HashMap;
-    use gpui::TestAppContext;
+    use gpui::{AppContext as _, TestAppContext};
+    use language_model::{LanguageModelRegistry, TokenUsage};
+    use project::{FakeFs, Project};
+    use prompt_store::ProjectContext;
+    use settings::SettingsStore;
     use std::sync::Arc;
 
     #[test]
@@ -540,6 +574,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_thread_json_roundtrip() {
+        let mut thread = make_thread(
+            "Exported Thread",
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        );
+        thread.detailed_summary = Some("A detailed summary".into());
+        thread.messages.push(crate::Message::User(UserMessage {
+            id: UserMessageId::new(),
+            content: vec![UserMessageContent::Text("Hello".into())],
+        }));
+        thread.messages.push(crate::Message::Agent(AgentMessage {
+            content: vec![AgentMessageContent::Text("Hi!".into())],
+            tool_results: IndexMap::default(),
+            reasoning_details: None,
+        }));
+
+        let json = thread.to_json().expect("Failed to serialize");
+        let restored = DbThread::from_json(json.as_bytes()).expect("Failed to deserialize");
+
+        assert_eq!(restored.title, thread.title);
+        assert_eq!(restored.updated_at, thread.updated_at);
+        assert_eq!(restored.detailed_summary, thread.detailed_summary);
+        assert_eq!(restored.messages, thread.messages);
+    }
+
     fn session_id(value: &str) -> acp::SessionId {
         acp::SessionId::new(Arc::<str>::from(value))
     }
@@ -557,6 +617,7 @@ mod tests {
             model: None,
             profile: None,
             imported: false,
+            parent_session_id: None,
         }
     }
 
@@ -623,4 +684,128 @@ mod tests {
             Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()
         );
     }
+
+    #[gpui::test]
+    async fn test_list_threads_includes_parent(cx: &mut TestAppContext) {
+        let database = ThreadsDatabase::new(cx.executor()).unwrap();
+
+        let parent_id = session_id("thread-a");
+        let fork_id = session_id("thread-b");
+        let parent = make_thread(
+            "Thread A",
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        );
+        let mut fork = make_thread(
+            "Thread A (fork)",
+            Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap(),
+        );
+        fork.parent_session_id = Some(parent_id.clone());
+
+        database
+            .save_thread(parent_id.clone(), parent)
+            .await
+            .unwrap();
+        database.save_thread(fork_id.clone(), fork).await.unwrap();
+
+        let entries = database.list_threads().await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, fork_id);
+        assert_eq!(entries[0].parent_session_id, Some(parent_id.clone()));
+        assert_eq!(entries[1].id, parent_id);
+        assert_eq!(entries[1].parent_session_id, None);
+
+        let loaded = database.load_thread(fork_id).await.unwrap().unwrap();
+        assert_eq!(loaded.parent_session_id, Some(parent_id));
+    }
+
+    #[gpui::test]
+    async fn test_fork_thread(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            LanguageModelRegistry::test(cx);
+        });
+
+        let first_message_id = UserMessageId::new();
+        let second_message_id = UserMessageId::new();
+        let first_usage = TokenUsage {
+            input_tokens: 100,
+            output_tokens: 50,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        };
+        let second_usage = TokenUsage {
+            input_tokens: 200,
+            output_tokens: 80,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        };
+
+        let mut db_thread = make_thread(
+            "Thread A",
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        );
+        db_thread.short_title = Some("A".into());
+        db_thread.detailed_summary = Some("A detailed summary".into());
+        db_thread.messages = vec![
+            crate::Message::User(UserMessage {
+                id: first_message_id.clone(),
+                content: vec![UserMessageContent::Text("Hello".into())],
+            }),
+            crate::Message::Agent(AgentMessage {
+                content: vec![AgentMessageContent::Text("Hi!".into())],
+                tool_results: IndexMap::default(),
+                reasoning_details: None,
+            }),
+            crate::Message::User(UserMessage {
+                id: second_message_id.clone(),
+                content: vec![UserMessageContent::Text("Goodbye".into())],
+            }),
+            crate::Message::Agent(AgentMessage {
+                content: vec![AgentMessageContent::Text("Bye!".into())],
+                tool_results: IndexMap::default(),
+                reasoning_details: None,
+            }),
+        ];
+        db_thread.cumulative_token_usage = first_usage + second_usage;
+        db_thread.request_token_usage = HashMap::from_iter([
+            (first_message_id, first_usage),
+            (second_message_id.clone(), second_usage),
+        ]);
+        let expected_messages = db_thread.messages[..2].to_vec();
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let parent_id = session_id("thread-a");
+        let thread = cx.new(|cx| {
+            let context_server_registry = cx
+                .new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+            Thread::from_db(
+                parent_id.clone(),
+                db_thread,
+                project.clone(),
+                cx.new(|_| ProjectContext::default()),
+                context_server_registry,
+                Templates::new(),
+                cx,
+            )
+        });
+
+        let fork = thread
+            .read_with(cx, |thread, cx| thread.fork(&second_message_id, cx))
+            .unwrap()
+            .await;
+        assert_eq!(fork.messages, expected_messages);
+        assert_eq!(fork.title.as_ref(), "Thread A (fork)");
+        assert_eq!(fork.short_title, None);
+        assert_eq!(fork.detailed_summary, None);
+        assert_eq!(fork.cumulative_token_usage, first_usage + second_usage);
+        assert_eq!(fork.request_token_usage.len(), 1);
+        assert_eq!(fork.request_token_usage.values().next(), Some(&first_usage));
+        assert_eq!(fork.parent_session_id, Some(parent_id));
+
+        thread.read_with(cx, |thread, cx| {
+            assert!(thread.fork(&UserMessageId::new(), cx).is_err());
+        });
+    }
 }
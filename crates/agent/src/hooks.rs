@@ -0,0 +1,309 @@
+use agent_settings::AgentHook;
+use anyhow::{Context as _, Result, anyhow};
+use futures::{AsyncWriteExt as _, FutureExt as _, future};
+use gpui::BackgroundExecutor;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, process::Stdio};
+use util::{shell::Shell, shell_builder::ShellBuilder};
+
+/// The exit status with which a hook blocks the action it was run for.
+///
+/// The hook's stderr is used as the reason.
+const BLOCKING_EXIT_CODE: i32 = 2;
+
+/// An agent lifecycle event, written as JSON to the stdin of each hook registered for it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "hook_event_name", rename_all = "snake_case")]
+pub enum HookEvent {
+    PreToolUse {
+        tool_name: String,
+        tool_input: serde_json::Value,
+    },
+    PostToolUse {
+        tool_name: String,
+        tool_input: serde_json::Value,
+        tool_output: String,
+        is_error: bool,
+    },
+    UserPromptSubmit {
+        prompt: String,
+    },
+    Stop {
+        /// Whether the turn is already continuing because a stop hook blocked it.
+        /// Hooks should check this to avoid keeping the agent running forever.
+        stop_hook_active: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct HookInput<'a> {
+    session_id: &'a str,
+    cwd: Option<&'a PathBuf>,
+    #[serde(flatten)]
+    event: &'a HookEvent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum HookDecision {
+    Allow,
+    #[serde(alias = "block")]
+    Deny,
+}
+
+/// The JSON object a hook may print to stdout to control the action.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+struct HookResponse {
+    #[serde(default)]
+    decision: Option<HookDecision>,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    updated_input: Option<serde_json::Value>,
+    #[serde(default)]
+    additional_context: Option<String>,
+}
+
+/// The combined result of running every hook registered for an event.
+#[derive(Debug, Default, PartialEq)]
+pub struct HookOutcome {
+    /// Whether a hook explicitly allowed the action, skipping confirmation.
+    pub allowed: bool,
+    /// The reason given by the first hook that denied the action, if any.
+    pub denied: Option<String>,
+    /// Replacement tool input provided by a `pre_tool_use` hook.
+    pub updated_input: Option<serde_json::Value>,
+    /// Output from the hooks that should be fed back to the model.
+    pub context: Vec<String>,
+}
+
+impl HookOutcome {
+    pub fn context_message(&self) -> Option<String> {
+        if self.context.is_empty() {
+            None
+        } else {
+            Some(format!(
+                "<hook_output>\n{}\n</hook_output>",
+                self.context.join("\n")
+            ))
+        }
+    }
+}
+
+/// Runs hook commands for a thread, in the thread's project directory.
+#[derive(Clone)]
+pub struct HookRunner {
+    session_id: String,
+    cwd: Option<PathBuf>,
+    executor: BackgroundExecutor,
+}
+
+impl HookRunner {
+    pub fn new(session_id: String, cwd: Option<PathBuf>, executor: BackgroundExecutor) -> Self {
+        Self {
+            session_id,
+            cwd,
+            executor,
+        }
+    }
+
+    /// Runs the given hooks in order, stopping at the first one that denies the action.
+    ///
+    /// Hooks that fail to run, time out or exit with an unexpected status are logged
+    /// and otherwise ignored.
+    pub async fn run(&self, hooks: &[AgentHook], mut event: HookEvent) -> HookOutcome {
+        let mut outcome = HookOutcome::default();
+        for hook in hooks {
+            let input = HookInput {
+                session_id: &self.session_id,
+                cwd: self.cwd.as_ref(),
+                event: &event,
+            };
+            let response = match serde_json::to_vec(&input) {
+                Ok(input) => self.run_hook(hook, input).await,
+                Err(error) => Err(error.into()),
+            };
+            let response = match response {
+                Ok(response) => response,
+                Err(error) => {
+                    log::warn!("Agent hook `{}` failed: {error:#}", hook.command);
+                    continue;
+                }
+            };
+
+            if let Some(context) = response.additional_context {
+                outcome.context.push(context);
+            }
+            match response.decision {
+                Some(HookDecision::Deny) => {
+                    outcome.denied = Some(
+                        response
+                            .reason
+                            .unwrap_or_else(|| format!("Blocked by hook `{}`", hook.command)),
+                    );
+                    break;
+                }
+                Some(HookDecision::Allow) => outcome.allowed = true,
+                None => {}
+            }
+            if let Some(updated_input) = response.updated_input
+                && let HookEvent::PreToolUse { tool_input, .. } = &mut event
+            {
+                *tool_input = updated_input.clone();
+                outcome.updated_input = Some(updated_input);
+            }
+        }
+        outcome
+    }
+
+    async fn run_hook(&self, hook: &AgentHook, input: Vec<u8>) -> Result<HookResponse> {
+        let (program, args) = ShellBuilder::new(&Shell::System, cfg!(windows))
+            .non_interactive()
+            .build(Some(hook.command.clone()), &[]);
+        let mut command = util::command::new_smol_command(program);
+        command
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(cwd) = &self.cwd {
+            command.current_dir(cwd);
+        }
+
+        let mut child = command.spawn().context("failed to spawn hook")?;
+        let stdin = child.stdin.take();
+        // The input is written while the output is read, and within the timeout, so that a hook
+        // that doesn't read its input can neither block on a full output pipe nor hang us.
+        let write_input = async move {
+            if let Some(mut stdin) = stdin {
+                // Hooks aren't required to read their input, so a closed pipe isn't an error.
+                stdin.write_all(&input).await.ok();
+            }
+        };
+        let output = future::join(write_input, child.output()).map(|((), output)| output);
+
+        let output = futures::select_biased! {
+            output = output.fuse() => output.context("failed to read hook output")?,
+            _ = self.executor.timer(hook.timeout).fuse() => {
+                return Err(anyhow!("timed out after {:?}", hook.timeout));
+            }
+        };
+
+        parse_hook_output(
+            output.status.code(),
+            &String::from_utf8_lossy(&output.stdout),
+            &String::from_utf8_lossy(&output.stderr),
+        )
+    }
+}
+
+fn parse_hook_output(exit_code: Option<i32>, stdout: &str, stderr: &str) -> Result<HookResponse> {
+    let stdout = stdout.trim();
+    let stderr = stderr.trim();
+    match exit_code {
+        Some(0) => {
+            if stdout.starts_with('{')
+                && let Ok(response) = serde_json::from_str::<HookResponse>(stdout)
+            {
+                return Ok(response);
+            }
+            Ok(HookResponse {
+                additional_context: (!stdout.is_empty()).then(|| stdout.to_string()),
+                ..Default::default()
+            })
+        }
+        Some(BLOCKING_EXIT_CODE) => Ok(HookResponse {
+            decision: Some(HookDecision::Deny),
+            reason: (!stderr.is_empty()).then(|| stderr.to_string()),
+            ..Default::default()
+        }),
+        Some(code) => Err(anyhow!("exited with status {code}: {stderr}")),
+        None => Err(anyhow!("terminated by signal: {stderr}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hook_input_serialization() {
+        let event = HookEvent::PreToolUse {
+            tool_name: "edit_file".into(),
+            tool_input: json!({ "path": "src/generated/schema.rs" }),
+        };
+        let cwd = PathBuf::from("/project");
+        let input = HookInput {
+            session_id: "session-1",
+            cwd: Some(&cwd),
+            event: &event,
+        };
+        assert_eq!(
+            serde_json::to_value(&input).unwrap(),
+            json!({
+                "session_id": "session-1",
+                "cwd": "/project",
+                "hook_event_name": "pre_tool_use",
+                "tool_name": "edit_file",
+                "tool_input": { "path": "src/generated/schema.rs" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_plain_output_as_context() {
+        assert_eq!(
+            parse_hook_output(Some(0), "formatted 2 files\n", "").unwrap(),
+            HookResponse {
+                additional_context: Some("formatted 2 files".into()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            parse_hook_output(Some(0), "  \n", "").unwrap(),
+            HookResponse::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_json_response() {
+        let response = parse_hook_output(
+            Some(0),
+            r#"{"decision": "block", "reason": "generated files are read-only"}"#,
+            "",
+        )
+        .unwrap();
+        assert_eq!(response.decision, Some(HookDecision::Deny));
+        assert_eq!(
+            response.reason.as_deref(),
+            Some("generated files are read-only")
+        );
+
+        let response = parse_hook_output(
+            Some(0),
+            r#"{"decision": "allow", "updated_input": {"command": "cargo test -q"}}"#,
+            "",
+        )
+        .unwrap();
+        assert_eq!(response.decision, Some(HookDecision::Allow));
+        assert_eq!(
+            response.updated_input,
+            Some(json!({ "command": "cargo test -q" }))
+        );
+    }
+
+    #[test]
+    fn test_parse_blocking_exit_code() {
+        let response = parse_hook_output(Some(2), "", "no writes to target/\n").unwrap();
+        assert_eq!(response.decision, Some(HookDecision::Deny));
+        assert_eq!(response.reason.as_deref(), Some("no writes to target/"));
+    }
+
+    #[test]
+    fn test_parse_failed_hook() {
+        assert!(parse_hook_output(Some(1), "", "command not found").is_err());
+        assert!(parse_hook_output(None, "", "").is_err());
+    }
+}
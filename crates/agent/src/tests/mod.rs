@@ -877,6 +877,146 @@ async fn test_tool_authorization(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+#[cfg(not(target_os = "windows"))]
+async fn test_pre_tool_use_hook_authorization(cx: &mut TestAppContext) {
+    let ThreadTest {
+        model, thread, fs, ..
+    } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    // Hooks are real processes run in the project's directory, so it has to exist on disk.
+    let project_dir = tempfile::tempdir().unwrap();
+    fs.insert_tree(project_dir.path(), json!({})).await;
+    let project = thread.read_with(cx, |thread, _| thread.project().clone());
+    let test_worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    project.update(cx, |project, cx| {
+        project.remove_worktree(test_worktree_id, cx)
+    });
+    project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(project_dir.path(), true, cx)
+        })
+        .await
+        .unwrap();
+
+    cx.update(|cx| {
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings.hooks.pre_tool_use = vec![agent_settings::AgentHook {
+            command: r#"echo '{"decision": "allow"}'"#.into(),
+            matcher: None,
+            timeout: agent_settings::AgentHook::DEFAULT_TIMEOUT,
+        }];
+        agent_settings::AgentSettings::override_global(settings, cx);
+    });
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(ToolRequiringPermission);
+            thread.send(UserMessageId::new(), ["abc"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    // A hook that allows the call runs the tool without asking the user.
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_1".into(),
+            name: ToolRequiringPermission::name().into(),
+            raw_input: "{}".into(),
+            input: json!({}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    assert_eq!(
+        next_tool_call_status(&mut events).await,
+        acp::ToolCallStatus::Completed
+    );
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        completion.messages.last().unwrap().content,
+        vec![language_model::MessageContent::ToolResult(
+            LanguageModelToolResult {
+                tool_use_id: "tool_id_1".into(),
+                tool_name: ToolRequiringPermission::name().into(),
+                is_error: false,
+                content: "Allowed".into(),
+                output: Some("Allowed".into())
+            }
+        )]
+    );
+
+    // A deny rule in the settings still wins over a hook that allows the call.
+    cx.update(|cx| {
+        let mut settings = agent_settings::AgentSettings::get_global(cx).clone();
+        settings.tool_permissions.tools.insert(
+            ToolRequiringPermission::name().into(),
+            agent_settings::ToolRules {
+                default_mode: settings::ToolPermissionMode::Deny,
+                always_allow: vec![],
+                always_deny: vec![],
+                always_confirm: vec![],
+                invalid_patterns: vec![],
+            },
+        );
+        agent_settings::AgentSettings::override_global(settings, cx);
+    });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "tool_id_2".into(),
+            name: ToolRequiringPermission::name().into(),
+            raw_input: "{}".into(),
+            input: json!({}),
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    assert_eq!(
+        next_tool_call_status(&mut events).await,
+        acp::ToolCallStatus::Failed
+    );
+    cx.run_until_parked();
+    let completion = fake_model.pending_completions().pop().unwrap();
+    let Some(language_model::MessageContent::ToolResult(result)) =
+        completion.messages.last().unwrap().content.first()
+    else {
+        panic!("expected a tool result");
+    };
+    assert_eq!(result.tool_use_id, "tool_id_2".into());
+    assert!(result.is_error);
+}
+
+/// Waits for the running tool call to finish, failing if the user is asked to authorize it.
+async fn next_tool_call_status(
+    events: &mut UnboundedReceiver<Result<ThreadEvent>>,
+) -> acp::ToolCallStatus {
+    loop {
+        let event = events
+            .next()
+            .await
+            .expect("no tool call update received")
+            .unwrap();
+        match event {
+            ThreadEvent::ToolCallAuthorization(_) => panic!("unexpected tool call authorization"),
+            ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(update)) => {
+                if let Some(
+                    status @ (acp::ToolCallStatus::Completed | acp::ToolCallStatus::Failed),
+                ) = update.fields.status
+                {
+                    return status;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 #[gpui::test]
 async fn test_tool_hallucination(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -3459,6 +3599,165 @@ fn setup_context_server(
     mcp_tool_calls_rx
 }
 
+#[gpui::test]
+async fn test_mcp_resource_subscriptions(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/test"), json!({})).await;
+    let project = Project::test(fs, [path!("/test").as_ref()], cx).await;
+    let context_server_store = project.read_with(cx, |project, _| project.context_server_store());
+    let registry = cx.new(|cx| ContextServerRegistry::new(context_server_store.clone(), cx));
+
+    let server_name = "resource_server";
+    let server_id = ContextServerId(server_name.into());
+    let uri = "file:///notes.md";
+    cx.update(|cx| {
+        let mut settings = ProjectSettings::get_global(cx).clone();
+        settings.context_servers.insert(
+            server_name.into(),
+            project::project_settings::ContextServerSettings::Stdio {
+                enabled: true,
+                remote: false,
+                command: ContextServerCommand {
+                    path: "somebinary".into(),
+                    args: Vec::new(),
+                    env: None,
+                    timeout: None,
+                },
+            },
+        );
+        ProjectSettings::override_global(settings, cx);
+    });
+
+    let resource_text = Arc::new(parking_lot::Mutex::new("first".to_string()));
+    let (subscribe_tx, mut subscribe_rx) = mpsc::unbounded();
+    let (unsubscribe_tx, mut unsubscribe_rx) = mpsc::unbounded();
+    let fake_transport = context_server::test::create_fake_transport(server_name, cx.executor())
+        .on_request::<context_server::types::requests::Initialize, _>(move |_params| async move {
+            context_server::types::InitializeResponse {
+                protocol_version: context_server::types::ProtocolVersion(
+                    context_server::types::LATEST_PROTOCOL_VERSION.to_string(),
+                ),
+                server_info: context_server::types::Implementation {
+                    name: server_name.into(),
+                    version: "1.0.0".to_string(),
+                },
+                capabilities: context_server::types::ServerCapabilities {
+                    resources: Some(context_server::types::ResourcesCapabilities {
+                        subscribe: Some(true),
+                        list_changed: None,
+                    }),
+                    ..Default::default()
+                },
+                meta: None,
+            }
+        })
+        .on_request::<context_server::types::requests::ResourcesRead, _>({
+            let resource_text = resource_text.clone();
+            move |params| {
+                let text = resource_text.lock().clone();
+                async move {
+                    context_server::types::ResourcesReadResponse {
+                        contents: vec![context_server::types::ResourceContentsType::Text(
+                            context_server::types::TextResourceContents {
+                                uri: params.uri,
+                                mime_type: None,
+                                text,
+                            },
+                        )],
+                        meta: None,
+                    }
+                }
+            }
+        })
+        .on_request::<context_server::types::requests::ResourcesSubscribe, _>(move |params| {
+            subscribe_tx.unbounded_send(params.uri.to_string()).unwrap();
+            async {}
+        })
+        .on_request::<context_server::types::requests::ResourcesUnsubscribe, _>(move |params| {
+            unsubscribe_tx
+                .unbounded_send(params.uri.to_string())
+                .unwrap();
+            async {}
+        });
+    let server_messages = fake_transport.message_sender();
+    context_server_store.update(cx, |store, cx| {
+        store.start_server(
+            Arc::new(ContextServer::new(
+                server_id.clone(),
+                Arc::new(fake_transport),
+            )),
+            cx,
+        );
+    });
+    cx.run_until_parked();
+
+    let updated_resources = Arc::new(parking_lot::Mutex::new(Vec::new()));
+    let _subscription = cx.update(|cx| {
+        let updated_resources = updated_resources.clone();
+        cx.subscribe(&registry, move |_, event, _| {
+            if let ContextServerRegistryEvent::ResourceUpdated { server_id, uri } = event {
+                updated_resources
+                    .lock()
+                    .push((server_id.clone(), uri.clone()));
+            }
+        })
+    });
+
+    let read_text = async |cx: &mut TestAppContext| {
+        let response = cx
+            .update(|cx| read_resource(&context_server_store, &server_id, uri, cx))
+            .await
+            .unwrap();
+        match response.contents.into_iter().next().unwrap() {
+            context_server::types::ResourceContentsType::Text(contents) => contents.text,
+            context_server::types::ResourceContentsType::Blob(_) => panic!("expected text"),
+        }
+    };
+
+    // Two mentions of the same resource share a single subscription.
+    registry.update(cx, |registry, cx| {
+        registry.subscribe_to_resource(&server_id, uri, cx);
+        registry.subscribe_to_resource(&server_id, uri, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(subscribe_rx.next().await.unwrap(), uri);
+    assert!(subscribe_rx.try_next().is_err());
+    assert_eq!(read_text(cx).await, "first");
+
+    // The server reports a change, and reading the resource again yields the new content.
+    *resource_text.lock() = "second".to_string();
+    server_messages
+        .unbounded_send(
+            json!({
+                "jsonrpc": "2.0",
+                "method": "notifications/resources/updated",
+                "params": { "uri": uri },
+            })
+            .to_string(),
+        )
+        .unwrap();
+    cx.run_until_parked();
+    assert_eq!(
+        updated_resources.lock().as_slice(),
+        &[(server_id.clone(), uri.to_string())]
+    );
+    assert_eq!(read_text(cx).await, "second");
+
+    // Unsubscribing only happens once the last mention is gone.
+    registry.update(cx, |registry, cx| {
+        registry.unsubscribe_from_resource(&server_id, uri, cx);
+    });
+    cx.run_until_parked();
+    assert!(unsubscribe_rx.try_next().is_err());
+
+    registry.update(cx, |registry, cx| {
+        registry.unsubscribe_from_resource(&server_id, uri, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(unsubscribe_rx.next().await.unwrap(), uri);
+}
+
 #[gpui::test]
 async fn test_tokens_before_message(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
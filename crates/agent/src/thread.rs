@@ -1,9 +1,10 @@
 use crate::{
     ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel, DbThread,
-    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, GrepTool,
-    ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
+    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, FindReferencesTool,
+    GoToDefinitionTool, GrepTool, HookEvent, HookRunner, HoverTool, ListDirectoryTool,
+    MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool, RenameSymbolTool,
     RestoreFileFromDiskTool, SaveFileTool, SubagentTool, SystemPromptTemplate, Template, Templates,
-    TerminalTool, ThinkingTool, ToolPermissionDecision, WebSearchTool,
+    TerminalTool, ThinkingTool, ToolPermissionDecision, WebSearchTool, WorkspaceSymbolsTool,
     decide_permission_from_settings,
 };
 use acp_thread::{MentionUri, UserMessageId};
@@ -236,6 +237,7 @@ impl UserMessage {
         const OPEN_SELECTIONS_TAG: &str = "<selections>";
         const OPEN_THREADS_TAG: &str = "<threads>";
         const OPEN_FETCH_TAG: &str = "<fetched_urls>";
+        const OPEN_MCP_RESOURCES_TAG: &str = "<mcp_resources>";
         const OPEN_RULES_TAG: &str =
             "<rules>\nThe user has specified the following rules that should be applied:\n";
 
@@ -245,6 +247,7 @@ impl UserMessage {
         let mut selection_context = OPEN_SELECTIONS_TAG.to_string();
         let mut thread_context = OPEN_THREADS_TAG.to_string();
         let mut fetch_context = OPEN_FETCH_TAG.to_string();
+        let mut mcp_resource_context = OPEN_MCP_RESOURCES_TAG.to_string();
         let mut rules_context = OPEN_RULES_TAG.to_string();
 
         for chunk in &self.content {
@@ -327,6 +330,14 @@ impl UserMessage {
                         MentionUri::Fetch { url } => {
                             write!(&mut fetch_context, "\nFetch: {}\n\n{}", url, content).ok();
                         }
+                        MentionUri::McpResource { server_id, uri, .. } => {
+                            write!(
+                                &mut mcp_resource_context,
+                                "\nResource {} from MCP server {}:\n\n{}\n",
+                                uri, server_id, content
+                            )
+                            .ok();
+                        }
                     }
 
                     language_model::MessageContent::Text(uri.as_link().to_string())
@@ -380,6 +391,13 @@ impl UserMessage {
                 .push(language_model::MessageContent::Text(fetch_context));
         }
 
+        if mcp_resource_context.len() > OPEN_MCP_RESOURCES_TAG.len() {
+            mcp_resource_context.push_str("</mcp_resources>\n");
+            message
+                .content
+                .push(language_model::MessageContent::Text(mcp_resource_context));
+        }
+
         if rules_context.len() > OPEN_RULES_TAG.len() {
             rules_context.push_str("</user_rules>\n");
             message
@@ -762,6 +780,8 @@ pub struct Thread {
     pub(crate) file_read_times: HashMap<PathBuf, fs::MTime>,
     /// True if this thread was imported from a shared thread and can be synced.
     imported: bool,
+    /// The thread this one was forked from, if any.
+    parent_session_id: Option<acp::SessionId>,
     /// If this is a subagent thread, contains context about the parent
     subagent_context: Option<SubagentContext>,
     /// Weak references to running subagent threads for cancellation propagation
@@ -824,6 +844,7 @@ impl Thread {
             action_log,
             file_read_times: HashMap::default(),
             imported: false,
+            parent_session_id: None,
             subagent_context: None,
             running_subagents: Vec::new(),
         }
@@ -874,6 +895,7 @@ impl Thread {
             action_log,
             file_read_times: HashMap::default(),
             imported: false,
+            parent_session_id: None,
             subagent_context: Some(subagent_context),
             running_subagents: Vec::new(),
         }
@@ -888,6 +910,42 @@ impl Thread {
         self.imported
     }
 
+    /// Returns the ID of the thread this one was forked from, if any.
+    pub fn parent_session_id(&self) -> Option<&acp::SessionId> {
+        self.parent_session_id.as_ref()
+    }
+
+    /// Copies this thread's history before the given user message into a new
+    /// thread, so that a different approach can be tried from that point while
+    /// keeping this thread intact.
+    pub fn fork(&self, message_id: &UserMessageId, cx: &App) -> Result<Task<DbThread>> {
+        let position = self
+            .messages
+            .iter()
+            .position(
+                |msg| matches!(msg, Message::User(UserMessage { id, .. }) if id == message_id),
+            )
+            .context("Message not found")?;
+
+        let parent_session_id = self.id.clone();
+        let db_thread = self.to_db(cx);
+        Ok(cx.background_spawn(async move {
+            let mut thread = db_thread.await;
+            for message in thread.messages.drain(position..) {
+                if let Message::User(message) = message {
+                    thread.request_token_usage.remove(&message.id);
+                }
+            }
+            thread.title = format!("{} (fork)", thread.title).into();
+            thread.short_title = None;
+            thread.detailed_summary = None;
+            thread.updated_at = Utc::now();
+            thread.imported = false;
+            thread.parent_session_id = Some(parent_session_id);
+            thread
+        }))
+    }
+
     pub fn replay(
         &mut self,
         cx: &mut Context<Self>,
@@ -1074,6 +1132,7 @@ impl Thread {
             prompt_capabilities_rx,
             file_read_times: HashMap::default(),
             imported: db_thread.imported,
+            parent_session_id: db_thread.parent_session_id,
             subagent_context: None,
             running_subagents: Vec::new(),
         }
@@ -1096,6 +1155,7 @@ impl Thread {
             }),
             profile: Some(self.profile_id.clone()),
             imported: self.imported,
+            parent_session_id: self.parent_session_id.clone(),
         };
 
         cx.background_spawn(async move {
@@ -1195,7 +1255,10 @@ impl Thread {
         ));
         self.add_tool(FetchTool::new(self.project.read(cx).client().http_client()));
         self.add_tool(FindPathTool::new(self.project.clone()));
+        self.add_tool(FindReferencesTool::new(self.project.clone()));
+        self.add_tool(GoToDefinitionTool::new(self.project.clone()));
         self.add_tool(GrepTool::new(self.project.clone()));
+        self.add_tool(HoverTool::new(self.project.clone()));
         self.add_tool(ListDirectoryTool::new(self.project.clone()));
         self.add_tool(MovePathTool::new(self.project.clone()));
         self.add_tool(NowTool);
@@ -1207,9 +1270,14 @@ impl Thread {
         ));
         self.add_tool(SaveFileTool::new(self.project.clone()));
         self.add_tool(RestoreFileFromDiskTool::new(self.project.clone()));
+        self.add_tool(RenameSymbolTool::new(
+            self.project.clone(),
+            self.action_log.clone(),
+        ));
         self.add_tool(TerminalTool::new(self.project.clone(), environment));
         self.add_tool(ThinkingTool);
         self.add_tool(WebSearchTool);
+        self.add_tool(WorkspaceSymbolsTool::new(self.project.clone()));
 
         if cx.has_flag::<SubagentsFeatureFlag>() && self.depth() < MAX_SUBAGENT_DEPTH {
             let parent_tools = self.tools.clone();
@@ -1529,14 +1597,20 @@ impl Thread {
             _task: cx.spawn(async move |this, cx| {
                 log::debug!("Starting agent turn execution");
 
-                let turn_result = Self::run_turn_internal(
-                    &this,
-                    model,
-                    &event_stream,
-                    cancellation_rx.clone(),
-                    cx,
-                )
-                .await;
+                let turn_result =
+                    match Self::run_user_prompt_submit_hooks(&this, message_ix, cx).await {
+                        Ok(()) => {
+                            Self::run_turn_internal(
+                                &this,
+                                model,
+                                &event_stream,
+                                cancellation_rx.clone(),
+                                cx,
+                            )
+                            .await
+                        }
+                        Err(error) => Err(error),
+                    };
 
                 // Check if we were cancelled - if so, cancel() already took running_turn
                 // and we shouldn't touch it (it might be a NEW turn now)
@@ -1576,6 +1650,78 @@ impl Thread {
         Ok(events_rx)
     }
 
+    /// Runs the `user_prompt_submit` hooks if the turn was started by a user message.
+    ///
+    /// Context returned by the hooks is appended to the message. If a hook blocks the
+    /// prompt, the message is removed so that it is never sent to the model.
+    async fn run_user_prompt_submit_hooks(
+        this: &WeakEntity<Self>,
+        message_ix: usize,
+        cx: &mut AsyncApp,
+    ) -> Result<()> {
+        let Some((hooks, hook_runner, prompt)) = this.read_with(cx, |this, cx| {
+            let hooks = &AgentSettings::get_global(cx).hooks.user_prompt_submit;
+            if hooks.is_empty() {
+                return None;
+            }
+            let Some(Message::User(message)) = this.messages.get(message_ix) else {
+                return None;
+            };
+            let prompt = message
+                .content
+                .iter()
+                .filter_map(|content| match content {
+                    UserMessageContent::Text(text) => Some(text.as_str()),
+                    UserMessageContent::Mention { .. } | UserMessageContent::Image(_) => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some((hooks.clone(), this.hook_runner(cx), prompt))
+        })?
+        else {
+            return Ok(());
+        };
+
+        let outcome = hook_runner
+            .run(&hooks, HookEvent::UserPromptSubmit { prompt })
+            .await;
+        this.update(cx, |this, cx| {
+            if let Some(reason) = outcome.denied {
+                this.messages.truncate(message_ix);
+                cx.notify();
+                return Err(anyhow!("Prompt blocked by hook: {reason}"));
+            }
+            if let Some(context) = outcome.context_message()
+                && let Some(Message::User(message)) = this.messages.get_mut(message_ix)
+            {
+                message.content.push(UserMessageContent::Text(context));
+                cx.notify();
+            }
+            Ok(())
+        })?
+    }
+
+    /// Runs the `stop` hooks when the model ends its turn, returning the reason
+    /// given by a hook that wants the agent to keep going.
+    async fn run_stop_hooks(
+        this: &WeakEntity<Self>,
+        stop_hook_active: bool,
+        cx: &mut AsyncApp,
+    ) -> Result<Option<String>> {
+        let Some((hooks, hook_runner)) = this.read_with(cx, |this, cx| {
+            let hooks = &AgentSettings::get_global(cx).hooks.stop;
+            (!hooks.is_empty()).then(|| (hooks.clone(), this.hook_runner(cx)))
+        })?
+        else {
+            return Ok(None);
+        };
+
+        let outcome = hook_runner
+            .run(&hooks, HookEvent::Stop { stop_hook_active })
+            .await;
+        Ok(outcome.denied)
+    }
+
     async fn run_turn_internal(
         this: &WeakEntity<Self>,
         model: Arc<dyn LanguageModel>,
@@ -1585,6 +1731,7 @@ impl Thread {
     ) -> Result<()> {
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
+        let mut stop_hook_active = false;
         loop {
             let request =
                 this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
@@ -1717,7 +1864,22 @@ impl Thread {
                     }
                 })?;
             } else if end_turn {
-                return Ok(());
+                let Some(reason) = Self::run_stop_hooks(this, stop_hook_active, cx).await? else {
+                    return Ok(());
+                };
+                log::debug!("Stop hook blocked the turn from ending: {reason}");
+                stop_hook_active = true;
+                this.update(cx, |this, cx| {
+                    let message = UserMessage {
+                        id: UserMessageId::new(),
+                        content: vec![UserMessageContent::Text(reason)],
+                    };
+                    event_stream.send_user_message(&message);
+                    this.messages.push(Message::User(message));
+                    cx.notify();
+                })?;
+                intent = CompletionIntent::UserPrompt;
+                attempt = 0;
             } else {
                 let has_queued = this.update(cx, |this, _| this.has_queued_messages())?;
                 if has_queued {
@@ -1990,10 +2152,38 @@ impl Thread {
             acp::ToolCallUpdateFields::new().status(acp::ToolCallStatus::InProgress),
         );
         let supports_images = self.model().is_some_and(|model| model.supports_images());
-        let tool_result = tool.run(tool_use.input, tool_event_stream, cx);
+        let hooks = &AgentSettings::get_global(cx).hooks;
+        let pre_tool_use_hooks = hooks
+            .pre_tool_use
+            .iter()
+            .filter(|hook| hook.matches_tool(&tool_use.name))
+            .cloned()
+            .collect::<Vec<_>>();
+        let post_tool_use_hooks = hooks
+            .post_tool_use
+            .iter()
+            .filter(|hook| hook.matches_tool(&tool_use.name))
+            .cloned()
+            .collect::<Vec<_>>();
+        let hook_runner = self.hook_runner(cx);
+        let tool_result = if pre_tool_use_hooks.is_empty() {
+            let tool_result = tool.run(tool_use.input.clone(), tool_event_stream, cx);
+            cx.foreground_executor()
+                .spawn(async move { (tool_result.await, None) })
+        } else {
+            Self::run_tool_with_pre_tool_use_hooks(
+                tool,
+                tool_use.clone(),
+                tool_event_stream,
+                pre_tool_use_hooks,
+                hook_runner.clone(),
+                cx,
+            )
+        };
         log::debug!("Running tool {}", tool_use.name);
         Some(cx.foreground_executor().spawn(async move {
-            let tool_result = tool_result.await.and_then(|output| {
+            let (tool_result, pre_tool_use_context) = tool_result.await;
+            let tool_result = tool_result.and_then(|output| {
                 if let LanguageModelToolResultContent::Image(_) = &output.llm_output
                     && !supports_images
                 {
@@ -2004,7 +2194,7 @@ impl Thread {
                 Ok(output)
             });
 
-            match tool_result {
+            let mut result = match tool_result {
                 Ok(output) => LanguageModelToolResult {
                     tool_use_id: tool_use.id,
                     tool_name: tool_use.name,
@@ -2019,10 +2209,96 @@ impl Thread {
                     content: LanguageModelToolResultContent::Text(Arc::from(error.to_string())),
                     output: Some(error.to_string().into()),
                 },
+            };
+
+            if let Some(context) = pre_tool_use_context {
+                append_hook_context(&mut result.content, &context);
+            }
+
+            if !post_tool_use_hooks.is_empty() {
+                let tool_output = match &result.content {
+                    LanguageModelToolResultContent::Text(text) => text.to_string(),
+                    LanguageModelToolResultContent::Image(_) => "[image]".to_string(),
+                };
+                let mut outcome = hook_runner
+                    .run(
+                        &post_tool_use_hooks,
+                        HookEvent::PostToolUse {
+                            tool_name: result.tool_name.to_string(),
+                            tool_input: tool_use.input,
+                            tool_output,
+                            is_error: result.is_error,
+                        },
+                    )
+                    .await;
+                // Tools have already run, so a denial is only reported back to the model.
+                outcome.context.extend(outcome.denied.take());
+                if let Some(context) = outcome.context_message() {
+                    append_hook_context(&mut result.content, &context);
+                }
             }
+
+            result
         }))
     }
 
+    /// Runs the `pre_tool_use` hooks for a tool call before running the tool itself.
+    ///
+    /// Hooks can deny the call, replace its input, or allow it without asking the user.
+    /// Any context they return is passed along so it can be attached to the tool's result.
+    fn run_tool_with_pre_tool_use_hooks(
+        tool: Arc<dyn AnyAgentTool>,
+        tool_use: LanguageModelToolUse,
+        tool_event_stream: ToolCallEventStream,
+        hooks: Vec<agent_settings::AgentHook>,
+        hook_runner: HookRunner,
+        cx: &mut Context<Self>,
+    ) -> Task<(Result<AgentToolOutput>, Option<String>)> {
+        cx.spawn(async move |_, cx| {
+            let outcome = hook_runner
+                .run(
+                    &hooks,
+                    HookEvent::PreToolUse {
+                        tool_name: tool_use.name.to_string(),
+                        tool_input: tool_use.input.clone(),
+                    },
+                )
+                .await;
+            let context = outcome.context_message();
+
+            if let Some(reason) = outcome.denied {
+                return (Err(anyhow!("Tool call blocked by hook: {reason}")), context);
+            }
+
+            let input = match outcome.updated_input {
+                Some(input) => {
+                    tool_event_stream
+                        .update_fields(acp::ToolCallUpdateFields::new().raw_input(input.clone()));
+                    input
+                }
+                None => tool_use.input,
+            };
+            let tool_event_stream = if outcome.allowed {
+                tool_event_stream.authorized_by_hook()
+            } else {
+                tool_event_stream
+            };
+
+            let tool_result = cx.update(|cx| tool.run(input, tool_event_stream, cx)).await;
+            (tool_result, context)
+        })
+    }
+
+    fn hook_runner(&self, cx: &App) -> HookRunner {
+        let cwd = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+        HookRunner::new(self.id.to_string(), cwd, cx.background_executor().clone())
+    }
+
     fn handle_tool_use_json_parse_error_event(
         &mut self,
         tool_use_id: LanguageModelToolUseId,
@@ -2950,12 +3226,26 @@ impl ThreadEventStream {
     }
 }
 
+/// Appends output from agent hooks to a tool result, so that the model sees it.
+fn append_hook_context(content: &mut LanguageModelToolResultContent, context: &str) {
+    match content {
+        LanguageModelToolResultContent::Text(text) => {
+            *text = format!("{text}\n\n{context}").into();
+        }
+        LanguageModelToolResultContent::Image(_) => {
+            log::warn!("Dropping hook output for tool result containing an image");
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct ToolCallEventStream {
     tool_use_id: LanguageModelToolUseId,
     stream: ThreadEventStream,
     fs: Option<Arc<dyn Fs>>,
     cancellation_rx: watch::Receiver<bool>,
+    /// Set when a `pre_tool_use` hook allowed this call, so the user isn't asked to confirm it.
+    authorized_by_hook: bool,
 }
 
 impl ToolCallEventStream {
@@ -3001,9 +3291,15 @@ impl ToolCallEventStream {
             stream,
             fs,
             cancellation_rx,
+            authorized_by_hook: false,
         }
     }
 
+    fn authorized_by_hook(mut self) -> Self {
+        self.authorized_by_hook = true;
+        self
+    }
+
     /// Returns a future that resolves when the user cancels the tool call.
     /// Tools should select on this alongside their main work to detect user cancellation.
     pub fn cancelled_by_user(&self) -> impl std::future::Future<Output = ()> + '_ {
@@ -3088,6 +3384,10 @@ impl ToolCallEventStream {
             ToolPermissionDecision::Confirm => {}
         }
 
+        if self.authorized_by_hook {
+            return Task::ready(Ok(()));
+        }
+
         let (response_tx, response_rx) = oneshot::channel();
         self.stream
             .0
@@ -3182,6 +3482,10 @@ impl ToolCallEventStream {
     ) -> Task<Result<()>> {
         use settings::ToolPermissionMode;
 
+        if self.authorized_by_hook {
+            return Task::ready(Ok(()));
+        }
+
         let options = context.build_permission_options();
 
         let (response_tx, response_rx) = oneshot::channel();
@@ -144,6 +144,7 @@ mod tests {
             model: None,
             profile: None,
             imported: false,
+            parent_session_id: None,
         }
     }
 
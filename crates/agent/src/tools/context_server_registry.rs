@@ -21,9 +21,25 @@ pub struct ContextServerPrompt {
     pub prompt: context_server::types::Prompt,
 }
 
+pub struct ContextServerResource {
+    pub server_id: ContextServerId,
+    pub resource: context_server::types::Resource,
+}
+
+pub struct ContextServerResourceTemplate {
+    pub server_id: ContextServerId,
+    pub template: context_server::types::ResourceTemplate,
+}
+
 pub enum ContextServerRegistryEvent {
     ToolsChanged,
     PromptsChanged,
+    ResourcesChanged,
+    /// A resource the registry subscribed to has changed on the server.
+    ResourceUpdated {
+        server_id: ContextServerId,
+        uri: String,
+    },
 }
 
 impl EventEmitter<ContextServerRegistryEvent> for ContextServerRegistry {}
@@ -37,9 +53,16 @@ pub struct ContextServerRegistry {
 struct RegisteredContextServer {
     tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
     prompts: BTreeMap<SharedString, ContextServerPrompt>,
+    resources: Vec<ContextServerResource>,
+    resource_templates: Vec<ContextServerResourceTemplate>,
+    /// Resources we've subscribed to, with the number of mentions referring to each.
+    subscribed_resources: HashMap<String, usize>,
     load_tools: Task<Result<()>>,
     load_prompts: Task<Result<()>>,
+    load_resources: Task<Result<()>>,
     _tools_updated_subscription: Option<NotificationSubscription>,
+    _resources_changed_subscription: Option<NotificationSubscription>,
+    _resource_updated_subscription: Option<NotificationSubscription>,
 }
 
 impl ContextServerRegistry {
@@ -52,6 +75,7 @@ impl ContextServerRegistry {
         for server in server_store.read(cx).running_servers() {
             this.reload_tools_for_server(server.id(), cx);
             this.reload_prompts_for_server(server.id(), cx);
+            this.reload_resources_for_server(server.id(), cx);
         }
         this
     }
@@ -102,6 +126,132 @@ impl ContextServerRegistry {
         }
     }
 
+    pub fn resources(&self) -> impl Iterator<Item = &ContextServerResource> {
+        self.registered_servers
+            .values()
+            .flat_map(|server| server.resources.iter())
+    }
+
+    pub fn resource_templates(&self) -> impl Iterator<Item = &ContextServerResourceTemplate> {
+        self.registered_servers
+            .values()
+            .flat_map(|server| server.resource_templates.iter())
+    }
+
+    pub fn resources_for_server(
+        &self,
+        server_id: &ContextServerId,
+    ) -> impl Iterator<Item = &ContextServerResource> {
+        self.registered_servers
+            .get(server_id)
+            .map(|server| server.resources.iter())
+            .into_iter()
+            .flatten()
+    }
+
+    pub fn resource_templates_for_server(
+        &self,
+        server_id: &ContextServerId,
+    ) -> impl Iterator<Item = &ContextServerResourceTemplate> {
+        self.registered_servers
+            .get(server_id)
+            .map(|server| server.resource_templates.iter())
+            .into_iter()
+            .flatten()
+    }
+
+    /// Asks the server to notify us when the resource changes, if it supports subscriptions.
+    ///
+    /// Changes are reported through [`ContextServerRegistryEvent::ResourceUpdated`].
+    pub fn subscribe_to_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(server) = self.server_store.read(cx).get_running_server(server_id) else {
+            return;
+        };
+        let Some(client) = server.client() else {
+            return;
+        };
+        let supports_subscriptions = client
+            .initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .is_some_and(|resources| resources.subscribe == Some(true));
+        if !supports_subscriptions {
+            return;
+        }
+        let Ok(parsed_uri) = url::Url::parse(uri) else {
+            return;
+        };
+
+        let registered_server = self.get_or_register_server(server_id, cx);
+        let count = registered_server
+            .subscribed_resources
+            .entry(uri.to_string())
+            .or_default();
+        *count += 1;
+        if *count > 1 {
+            return;
+        }
+        cx.background_spawn(async move {
+            client
+                .request::<context_server::types::requests::ResourcesSubscribe>(
+                    context_server::types::ResourcesSubscribeParams {
+                        uri: parsed_uri,
+                        meta: None,
+                    },
+                )
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Releases one reference to a resource subscription, sending
+    /// `resources/unsubscribe` once nothing refers to it anymore.
+    pub fn unsubscribe_from_resource(
+        &mut self,
+        server_id: &ContextServerId,
+        uri: &str,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(registered_server) = self.registered_servers.get_mut(server_id) else {
+            return;
+        };
+        let Some(count) = registered_server.subscribed_resources.get_mut(uri) else {
+            return;
+        };
+        *count -= 1;
+        if *count > 0 {
+            return;
+        }
+        registered_server.subscribed_resources.remove(uri);
+
+        let Some(server) = self.server_store.read(cx).get_running_server(server_id) else {
+            return;
+        };
+        let Some(client) = server.client() else {
+            return;
+        };
+        let Ok(parsed_uri) = url::Url::parse(uri) else {
+            return;
+        };
+        cx.background_spawn(async move {
+            client
+                .request::<context_server::types::requests::ResourcesUnsubscribe>(
+                    context_server::types::ResourcesUnsubscribeParams {
+                        uri: parsed_uri,
+                        meta: None,
+                    },
+                )
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn server_store(&self) -> &Entity<ContextServerStore> {
         &self.server_store
     }
@@ -153,12 +303,78 @@ impl ContextServerRegistry {
                 ))
             });
 
+        let (resources_changed_subscription, resource_updated_subscription) = server_store
+            .read(cx)
+            .get_running_server(server_id)
+            .and_then(|server| {
+                let client = server.client()?;
+
+                if !client.capable(context_server::protocol::ServerCapability::Resources) {
+                    return None;
+                }
+
+                let server_id = server.id();
+                let this = cx.entity().downgrade();
+
+                let resources_changed_subscription = client.on_notification(
+                    "notifications/resources/list_changed",
+                    Box::new({
+                        let server_id = server_id.clone();
+                        let this = this.clone();
+                        move |_params, cx: AsyncApp| {
+                            let server_id = server_id.clone();
+                            let this = this.clone();
+                            cx.spawn(async move |cx| {
+                                this.update(cx, |this, cx| {
+                                    log::info!(
+                                        "Received resources/list_changed notification for server {}",
+                                        server_id
+                                    );
+                                    this.reload_resources_for_server(server_id, cx);
+                                })
+                            })
+                            .detach();
+                        }
+                    }),
+                );
+                let resource_updated_subscription = client.on_notification(
+                    "notifications/resources/updated",
+                    Box::new(move |params, cx: AsyncApp| {
+                        let Some(params) = serde_json::from_value::<
+                            context_server::types::ResourcesUpdatedParams,
+                        >(params)
+                        .log_err() else {
+                            return;
+                        };
+                        let server_id = server_id.clone();
+                        let this = this.clone();
+                        cx.spawn(async move |cx| {
+                            this.update(cx, |_, cx| {
+                                cx.emit(ContextServerRegistryEvent::ResourceUpdated {
+                                    server_id,
+                                    uri: params.uri,
+                                });
+                            })
+                        })
+                        .detach();
+                    }),
+                );
+                Some((resources_changed_subscription, resource_updated_subscription))
+            })
+            .unzip();
+
         RegisteredContextServer {
             tools: BTreeMap::default(),
             prompts: BTreeMap::default(),
+            resources: Vec::new(),
+            resource_templates: Vec::new(),
+            subscribed_resources: HashMap::default(),
             load_tools: Task::ready(Ok(())),
             load_prompts: Task::ready(Ok(())),
+            load_resources: Task::ready(Ok(())),
             _tools_updated_subscription: tools_updated_subscription,
+            _resources_changed_subscription: resources_changed_subscription,
+            _resource_updated_subscription: resource_updated_subscription,
         }
     }
 
@@ -244,6 +460,61 @@ impl ContextServerRegistry {
         });
     }
 
+    fn reload_resources_for_server(&mut self, server_id: ContextServerId, cx: &mut Context<Self>) {
+        let Some(server) = self.server_store.read(cx).get_running_server(&server_id) else {
+            return;
+        };
+        let Some(client) = server.client() else {
+            return;
+        };
+        if !client.capable(context_server::protocol::ServerCapability::Resources) {
+            return;
+        }
+
+        let registered_server = self.get_or_register_server(&server_id, cx);
+
+        registered_server.load_resources = cx.spawn(async move |this, cx| {
+            let resources = client
+                .request::<context_server::types::requests::ResourcesList>(())
+                .await;
+            // Resource templates are optional, servers that don't have any may not implement the request.
+            let resource_templates = client
+                .request::<context_server::types::requests::ListResourceTemplates>(())
+                .await;
+
+            this.update(cx, |this, cx| {
+                let Some(registered_server) = this.registered_servers.get_mut(&server_id) else {
+                    return;
+                };
+
+                registered_server.resources.clear();
+                if let Some(response) = resources.log_err() {
+                    registered_server
+                        .resources
+                        .extend(response.resources.into_iter().map(|resource| {
+                            ContextServerResource {
+                                server_id: server_id.clone(),
+                                resource,
+                            }
+                        }));
+                }
+                registered_server.resource_templates.clear();
+                if let Ok(response) = resource_templates {
+                    registered_server.resource_templates.extend(
+                        response.resource_templates.into_iter().map(|template| {
+                            ContextServerResourceTemplate {
+                                server_id: server_id.clone(),
+                                template,
+                            }
+                        }),
+                    );
+                }
+                cx.emit(ContextServerRegistryEvent::ResourcesChanged);
+                cx.notify();
+            })
+        });
+    }
+
     fn handle_context_server_store_event(
         &mut self,
         _: Entity<ContextServerStore>,
@@ -257,6 +528,7 @@ impl ContextServerRegistry {
                     ContextServerStatus::Running => {
                         self.reload_tools_for_server(server_id.clone(), cx);
                         self.reload_prompts_for_server(server_id.clone(), cx);
+                        self.reload_resources_for_server(server_id.clone(), cx);
                     }
                     ContextServerStatus::Stopped | ContextServerStatus::Error(_) => {
                         if let Some(registered_server) = self.registered_servers.remove(server_id) {
@@ -266,6 +538,11 @@ impl ContextServerRegistry {
                             if !registered_server.prompts.is_empty() {
                                 cx.emit(ContextServerRegistryEvent::PromptsChanged);
                             }
+                            if !registered_server.resources.is_empty()
+                                || !registered_server.resource_templates.is_empty()
+                            {
+                                cx.emit(ContextServerRegistryEvent::ResourcesChanged);
+                            }
                         }
                         cx.notify();
                     }
@@ -450,6 +727,69 @@ pub fn get_prompt(
     })
 }
 
+pub fn read_resource(
+    server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    uri: &str,
+    cx: &mut App,
+) -> Task<Result<context_server::types::ResourcesReadResponse>> {
+    let Some(server) = server_store.read(cx).get_running_server(server_id) else {
+        return Task::ready(Err(anyhow!("Context server not found")));
+    };
+    let Some(protocol) = server.client() else {
+        return Task::ready(Err(anyhow!("Context server not initialized")));
+    };
+    let uri = match url::Url::parse(uri) {
+        Ok(uri) => uri,
+        Err(error) => return Task::ready(Err(anyhow!("Invalid resource URI {uri:?}: {error}"))),
+    };
+
+    cx.background_spawn(async move {
+        protocol
+            .request::<context_server::types::requests::ResourcesRead>(
+                context_server::types::ResourcesReadParams { uri, meta: None },
+            )
+            .await
+    })
+}
+
+/// Asks the server for the possible values of an argument of a resource template.
+pub fn complete_resource_template_argument(
+    server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    uri_template: &str,
+    argument_name: &str,
+    argument_value: &str,
+    cx: &mut App,
+) -> Task<Result<Vec<String>>> {
+    let Some(server) = server_store.read(cx).get_running_server(server_id) else {
+        return Task::ready(Err(anyhow!("Context server not found")));
+    };
+    let Some(protocol) = server.client() else {
+        return Task::ready(Err(anyhow!("Context server not initialized")));
+    };
+    let params = context_server::types::CompletionCompleteParams {
+        reference: context_server::types::CompletionReference::Resource(
+            context_server::types::ResourceReference {
+                ty: context_server::types::PromptReferenceType::Resource,
+                uri: uri_template.to_string(),
+            },
+        ),
+        argument: context_server::types::CompletionArgument {
+            name: argument_name.to_string(),
+            value: argument_value.to_string(),
+        },
+        meta: None,
+    };
+
+    cx.background_spawn(async move {
+        let response = protocol
+            .request::<context_server::types::requests::CompletionComplete>(params)
+            .await?;
+        Ok(response.completion.values)
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
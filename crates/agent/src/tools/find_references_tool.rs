@@ -0,0 +1,154 @@
+use crate::tools::symbol_locator::{locate_symbol, write_locations};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Uses the project's language servers to find every reference to a symbol.
+///
+/// Prefer this tool over grep when looking for usages of a specific function, type or variable,
+/// because it only returns real references and not unrelated text that happens to match.
+///
+/// Returns one `path:line: text` entry per reference, including the definition itself.
+///
+/// <example>
+/// To find all callers of `decide_permission`, defined on line 38 of `zed/crates/agent/src/tool_permissions.rs`:
+/// {
+///     "path": "zed/crates/agent/src/tool_permissions.rs",
+///     "line": 38,
+///     "symbol": "decide_permission"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct FindReferencesToolInput {
+    /// The path of a file in which the symbol appears.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+    /// The symbol's name, exactly as it appears on that line.
+    pub symbol: String,
+}
+
+pub struct FindReferencesTool {
+    project: Entity<Project>,
+}
+
+impl FindReferencesTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for FindReferencesTool {
+    type Input = FindReferencesToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "find_references"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Find references to {}", MarkdownInlineCode(&input.symbol)).into(),
+            Err(_) => "Find references".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let located = locate_symbol(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let located = located.await?;
+            let references = project.update(cx, |project, cx| {
+                project.references(&located.buffer, located.position, cx)
+            });
+            let references = futures::select! {
+                result = references.fuse() => result?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    anyhow::bail!("Find references cancelled by user");
+                }
+            };
+
+            let mut output = String::new();
+            let count = write_locations(&mut output, references.unwrap_or_default(), cx)?;
+            if count == 0 {
+                Ok(format!(
+                    "No references found for `{}`. The language server may not support this request or may still be indexing; fall back to grep if needed.",
+                    input.symbol
+                ))
+            } else {
+                Ok(format!("Found {count} references:\n{output}"))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::symbol_locator::tests::{fake_rust_project, lsp_location, run_tool};
+    use gpui::TestAppContext;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_find_references(cx: &mut TestAppContext) {
+        let fake = fake_rust_project(cx).await;
+        let input = || FindReferencesToolInput {
+            path: "root/src/main.rs".into(),
+            line: 2,
+            symbol: "Thread".into(),
+        };
+
+        fake.server
+            .set_request_handler::<lsp::request::References, _, _>(|params, _| async move {
+                assert_eq!(
+                    params.text_document_position.position,
+                    lsp::Position::new(1, 17)
+                );
+                Ok(Some(vec![
+                    lsp_location(path!("/root/src/main.rs"), 1, 17..23),
+                    lsp_location(path!("/root/src/lib.rs"), 4, 8..14),
+                    lsp_location(path!("/root/src/lib.rs"), 0, 11..17),
+                    lsp_location(path!("/root/src/lib.rs"), 0, 11..17),
+                ]))
+            });
+        let output = run_tool(FindReferencesTool::new(fake.project.clone()), input(), cx).await;
+        assert_eq!(
+            output,
+            "Found 3 references:\n\
+             root/src/lib.rs:1: pub struct Thread;\n\
+             root/src/lib.rs:5: Thread\n\
+             root/src/main.rs:2: let thread = Thread::new();\n"
+        );
+
+        fake.server
+            .set_request_handler::<lsp::request::References, _, _>(|_, _| async move { Ok(None) });
+        let output = run_tool(FindReferencesTool::new(fake.project.clone()), input(), cx).await;
+        assert!(
+            output.starts_with("No references found for `Thread`."),
+            "unexpected output: {output}"
+        );
+    }
+}
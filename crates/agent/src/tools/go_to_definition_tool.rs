@@ -0,0 +1,198 @@
+use crate::tools::symbol_locator::{locate_symbol, write_locations};
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::MarkdownInlineCode;
+
+/// Uses the project's language servers to find where a symbol is defined.
+///
+/// Prefer this tool over grep when you know where a symbol is used and want to jump to its definition,
+/// because the language server resolves it precisely, even across crates and dependencies.
+///
+/// Returns one `path:line: text` entry per location found.
+///
+/// <example>
+/// To find the definition of `Thread` used on line 42 of `zed/crates/agent/src/agent.rs`:
+/// {
+///     "path": "zed/crates/agent/src/agent.rs",
+///     "line": 42,
+///     "symbol": "Thread"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct GoToDefinitionToolInput {
+    /// The path of a file in which the symbol is used.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+    /// The symbol's name, exactly as it appears on that line.
+    pub symbol: String,
+    /// What kind of definition to look for.
+    #[serde(default)]
+    pub kind: DefinitionKind,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionKind {
+    /// Where the symbol is defined.
+    #[default]
+    Definition,
+    /// Where the symbol is declared, for languages that distinguish declarations from definitions.
+    Declaration,
+    /// Where the type of the symbol is defined.
+    TypeDefinition,
+    /// The implementations of a trait, interface or abstract method.
+    Implementation,
+}
+
+pub struct GoToDefinitionTool {
+    project: Entity<Project>,
+}
+
+impl GoToDefinitionTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for GoToDefinitionTool {
+    type Input = GoToDefinitionToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "go_to_definition"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => {
+                let symbol = MarkdownInlineCode(&input.symbol);
+                match input.kind {
+                    DefinitionKind::Definition => format!("Go to definition of {symbol}"),
+                    DefinitionKind::Declaration => format!("Go to declaration of {symbol}"),
+                    DefinitionKind::TypeDefinition => {
+                        format!("Go to type definition of {symbol}")
+                    }
+                    DefinitionKind::Implementation => {
+                        format!("Find implementations of {symbol}")
+                    }
+                }
+                .into()
+            }
+            Err(_) => "Go to definition".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let located = locate_symbol(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let located = located.await?;
+            let definitions = project.update(cx, |project, cx| match input.kind {
+                DefinitionKind::Definition => {
+                    project.definitions(&located.buffer, located.position, cx)
+                }
+                DefinitionKind::Declaration => {
+                    project.declarations(&located.buffer, located.position, cx)
+                }
+                DefinitionKind::TypeDefinition => {
+                    project.type_definitions(&located.buffer, located.position, cx)
+                }
+                DefinitionKind::Implementation => {
+                    project.implementations(&located.buffer, located.position, cx)
+                }
+            });
+            let definitions = futures::select! {
+                result = definitions.fuse() => result?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    anyhow::bail!("Go to definition cancelled by user");
+                }
+            };
+
+            let mut output = String::new();
+            let count = write_locations(
+                &mut output,
+                definitions
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|link| link.target),
+                cx,
+            )?;
+            if count == 0 {
+                Ok(format!(
+                    "No definition found for `{}`. The language server may not support this request or may still be indexing; fall back to grep if needed.",
+                    input.symbol
+                ))
+            } else {
+                Ok(output)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::symbol_locator::tests::{fake_rust_project, lsp_location, run_tool};
+    use gpui::TestAppContext;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_go_to_definition(cx: &mut TestAppContext) {
+        let fake = fake_rust_project(cx).await;
+        let input = || GoToDefinitionToolInput {
+            path: "root/src/main.rs".into(),
+            line: 2,
+            symbol: "Thread".into(),
+            kind: DefinitionKind::Definition,
+        };
+
+        fake.server
+            .set_request_handler::<lsp::request::GotoDefinition, _, _>(|params, _| async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 17)
+                );
+                Ok(Some(lsp::GotoDefinitionResponse::Scalar(lsp_location(
+                    path!("/root/src/lib.rs"),
+                    0,
+                    11..17,
+                ))))
+            });
+        let output = run_tool(GoToDefinitionTool::new(fake.project.clone()), input(), cx).await;
+        assert_eq!(output, "root/src/lib.rs:1: pub struct Thread;\n");
+
+        fake.server
+            .set_request_handler::<lsp::request::GotoDefinition, _, _>(
+                |_, _| async move { Ok(None) },
+            );
+        let output = run_tool(GoToDefinitionTool::new(fake.project.clone()), input(), cx).await;
+        assert!(
+            output.starts_with("No definition found for `Thread`."),
+            "unexpected output: {output}"
+        );
+    }
+}
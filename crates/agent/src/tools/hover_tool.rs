@@ -0,0 +1,167 @@
+use crate::tools::symbol_locator::locate_symbol;
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::{HoverBlockKind, Project};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use util::markdown::{MarkdownCodeBlock, MarkdownInlineCode};
+
+/// Uses the project's language servers to show type information and documentation for a symbol.
+///
+/// Use this tool to learn a symbol's type, signature or documentation without reading its definition,
+/// for example to see the inferred type of a variable or the signature of a method.
+///
+/// <example>
+/// To see the type of `events` on line 120 of `zed/crates/agent/src/agent.rs`:
+/// {
+///     "path": "zed/crates/agent/src/agent.rs",
+///     "line": 120,
+///     "symbol": "events"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HoverToolInput {
+    /// The path of a file in which the symbol appears.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+    /// The symbol's name, exactly as it appears on that line.
+    pub symbol: String,
+}
+
+pub struct HoverTool {
+    project: Entity<Project>,
+}
+
+impl HoverTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for HoverTool {
+    type Input = HoverToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "hover"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Read
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Get type info for {}", MarkdownInlineCode(&input.symbol)).into(),
+            Err(_) => "Get type info".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let located = locate_symbol(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let located = located.await?;
+            let hovers = project.update(cx, |project, cx| {
+                project.hover(&located.buffer, located.position, cx)
+            });
+            let hovers = futures::select! {
+                hovers = hovers.fuse() => hovers,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    anyhow::bail!("Hover cancelled by user");
+                }
+            };
+
+            let blocks = hovers
+                .unwrap_or_default()
+                .into_iter()
+                .flat_map(|hover| hover.contents)
+                .filter(|block| !block.text.trim().is_empty())
+                .map(|block| match block.kind {
+                    HoverBlockKind::Code { language } => MarkdownCodeBlock {
+                        tag: &language,
+                        text: &block.text,
+                    }
+                    .to_string(),
+                    HoverBlockKind::PlainText | HoverBlockKind::Markdown => block.text,
+                })
+                .collect::<Vec<_>>();
+
+            if blocks.is_empty() {
+                Ok(format!(
+                    "No type information found for `{}`. The language server may not support this request or may still be indexing.",
+                    input.symbol
+                ))
+            } else {
+                Ok(blocks.join("\n\n"))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::symbol_locator::tests::{fake_rust_project, run_tool};
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_hover(cx: &mut TestAppContext) {
+        let fake = fake_rust_project(cx).await;
+        let input = || HoverToolInput {
+            path: "root/src/main.rs".into(),
+            line: 2,
+            symbol: "Thread".into(),
+        };
+
+        fake.server
+            .set_request_handler::<lsp::request::HoverRequest, _, _>(|params, _| async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 17)
+                );
+                Ok(Some(lsp::Hover {
+                    contents: lsp::HoverContents::Array(vec![
+                        lsp::MarkedString::LanguageString(lsp::LanguageString {
+                            language: "rust".into(),
+                            value: "pub struct Thread".into(),
+                        }),
+                        lsp::MarkedString::String("A conversation with the model.".into()),
+                    ]),
+                    range: None,
+                }))
+            });
+        let output = run_tool(HoverTool::new(fake.project.clone()), input(), cx).await;
+        assert_eq!(
+            output,
+            "```rust\npub struct Thread\n```\n\n\nA conversation with the model."
+        );
+
+        fake.server
+            .set_request_handler::<lsp::request::HoverRequest, _, _>(
+                |_, _| async move { Ok(None) },
+            );
+        let output = run_tool(HoverTool::new(fake.project.clone()), input(), cx).await;
+        assert!(
+            output.starts_with("No type information found for `Thread`."),
+            "unexpected output: {output}"
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use crate::tools::symbol_locator::locate_symbol;
+use crate::{
+    AgentTool, ToolCallEventStream, ToolPermissionDecision, decide_permission_from_settings,
+};
+use action_log::ActionLog;
+use agent_client_protocol as acp;
+use agent_settings::AgentSettings;
+use anyhow::{Result, anyhow};
+use collections::HashSet;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::Project;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Uses the project's language servers to rename a symbol everywhere it is used, and saves the affected files
+/// unless they already had unsaved changes.
+///
+/// Prefer this tool over editing files one at a time when renaming a function, type, field or variable,
+/// because the language server updates every reference, including ones that grep would miss or match wrongly.
+///
+/// <example>
+/// To rename the `Thread::send_existing` method, declared on line 1447 of `zed/crates/agent/src/thread.rs`:
+/// {
+///     "path": "zed/crates/agent/src/thread.rs",
+///     "line": 1447,
+///     "symbol": "send_existing",
+///     "new_name": "resend"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RenameSymbolToolInput {
+    /// The path of a file in which the symbol appears.
+    ///
+    /// This path should never be absolute, and the first component
+    /// of the path should always be a root directory in a project.
+    pub path: String,
+    /// The 1-based line number on which the symbol appears.
+    pub line: u32,
+    /// The symbol's current name, exactly as it appears on that line.
+    pub symbol: String,
+    /// The new name for the symbol.
+    pub new_name: String,
+}
+
+pub struct RenameSymbolTool {
+    project: Entity<Project>,
+    action_log: Entity<ActionLog>,
+}
+
+impl RenameSymbolTool {
+    pub fn new(project: Entity<Project>, action_log: Entity<ActionLog>) -> Self {
+        Self {
+            project,
+            action_log,
+        }
+    }
+}
+
+impl AgentTool for RenameSymbolTool {
+    type Input = RenameSymbolToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "rename_symbol"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Edit
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!(
+                "Rename {} to {}",
+                MarkdownInlineCode(&input.symbol),
+                MarkdownInlineCode(&input.new_name)
+            )
+            .into(),
+            Err(_) => "Rename symbol".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let settings = AgentSettings::get_global(cx);
+        let decision = decide_permission_from_settings(Self::name(), &input.path, settings);
+
+        let authorize = match decision {
+            ToolPermissionDecision::Allow => None,
+            ToolPermissionDecision::Deny(reason) => {
+                return Task::ready(Err(anyhow!("{}", reason)));
+            }
+            ToolPermissionDecision::Confirm => {
+                let context = crate::ToolPermissionContext {
+                    tool_name: "rename_symbol".to_string(),
+                    input_value: input.path.clone(),
+                };
+                Some(event_stream.authorize(
+                    format!(
+                        "Rename {} to {}",
+                        MarkdownInlineCode(&input.symbol),
+                        MarkdownInlineCode(&input.new_name)
+                    ),
+                    context,
+                    cx,
+                ))
+            }
+        };
+
+        let located = locate_symbol(&self.project, &input.path, input.line, &input.symbol, cx);
+        let project = self.project.clone();
+        let action_log = self.action_log.clone();
+        cx.spawn(async move |cx| {
+            if let Some(authorize) = authorize {
+                authorize.await?;
+            }
+            let located = located.await?;
+
+            // Track the files that reference the symbol before renaming it,
+            // so that the language server's edits show up for review.
+            let references = project.update(cx, |project, cx| {
+                project.references(&located.buffer, located.position, cx)
+            });
+            let references = references.await.ok().flatten().unwrap_or_default();
+            action_log.update(cx, |log, cx| {
+                log.buffer_read(located.buffer.clone(), cx);
+                for reference in references {
+                    log.buffer_read(reference.buffer, cx);
+                }
+            });
+
+            // Files with unsaved changes are left unsaved, so that the user's changes aren't
+            // written to disk without them reviewing them.
+            let dirty_buffers = project.read_with(cx, |project, cx| {
+                project
+                    .opened_buffers(cx)
+                    .into_iter()
+                    .filter(|buffer| buffer.read(cx).is_dirty())
+                    .map(|buffer| buffer.entity_id())
+                    .collect::<HashSet<_>>()
+            });
+
+            let rename = project.update(cx, |project, cx| {
+                project.perform_rename(
+                    located.buffer.clone(),
+                    located.position,
+                    input.new_name.clone(),
+                    cx,
+                )
+            });
+            let transaction = futures::select! {
+                result = rename.fuse() => result?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    anyhow::bail!("Rename cancelled by user");
+                }
+            };
+
+            if transaction.0.is_empty() {
+                return Ok(format!(
+                    "The language server made no changes when renaming `{}`. It may not support renaming this symbol.",
+                    input.symbol
+                ));
+            }
+
+            let mut paths = Vec::new();
+            let mut unsaved_paths = Vec::new();
+            for buffer in transaction.0.into_keys() {
+                action_log.update(cx, |log, cx| log.buffer_edited(buffer.clone(), cx));
+                let was_dirty = dirty_buffers.contains(&buffer.entity_id());
+                if !was_dirty {
+                    project
+                        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+                        .await?;
+                }
+                if let Some(path) =
+                    buffer.read_with(cx, |buffer, cx| buffer.file().map(|file| file.full_path(cx)))
+                {
+                    if was_dirty {
+                        unsaved_paths.push(path.clone());
+                    }
+                    paths.push(path);
+                }
+            }
+            paths.sort();
+            unsaved_paths.sort();
+
+            let mut output = format!(
+                "Renamed `{}` to `{}` in {} files:\n",
+                input.symbol,
+                input.new_name,
+                paths.len()
+            );
+            for path in paths {
+                writeln!(output, "- {}", path.display())?;
+            }
+            if !unsaved_paths.is_empty() {
+                writeln!(
+                    output,
+                    "\nThese files had unsaved changes, so they were left unsaved:"
+                )?;
+                for path in unsaved_paths {
+                    writeln!(output, "- {}", path.display())?;
+                }
+            }
+            Ok(output)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::symbol_locator::tests::{LIB_RS, fake_rust_project, run_tool};
+    use fs::Fs as _;
+    use gpui::{AppContext as _, TestAppContext};
+    use util::path;
+
+    #[gpui::test]
+    async fn test_rename_symbol(cx: &mut TestAppContext) {
+        let fake = fake_rust_project(cx).await;
+        cx.update(|cx| {
+            let mut settings = AgentSettings::get_global(cx).clone();
+            settings.always_allow_tool_actions = true;
+            AgentSettings::override_global(settings, cx);
+        });
+        let action_log = cx.new(|_| ActionLog::new(fake.project.clone()));
+
+        // Unsaved changes in lib.rs must not be written to disk by the rename.
+        let lib_rs = fake
+            .project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/root/src/lib.rs"), cx)
+            })
+            .await
+            .unwrap();
+        lib_rs.update(cx, |buffer, cx| {
+            buffer.edit([(LIB_RS.len()..LIB_RS.len(), "// edited\n")], None, cx)
+        });
+
+        fake.server
+            .set_request_handler::<lsp::request::References, _, _>(|_, _| async move { Ok(None) });
+        fake.server
+            .set_request_handler::<lsp::request::Rename, _, _>(|params, _| async move {
+                assert_eq!(
+                    params.text_document_position.position,
+                    lsp::Position::new(1, 17)
+                );
+                assert_eq!(params.new_name, "Task");
+                let edit = |row, columns: std::ops::Range<u32>| {
+                    lsp::TextEdit::new(
+                        lsp::Range::new(
+                            lsp::Position::new(row, columns.start),
+                            lsp::Position::new(row, columns.end),
+                        ),
+                        "Task".to_string(),
+                    )
+                };
+                Ok(Some(lsp::WorkspaceEdit {
+                    changes: Some(
+                        [
+                            (
+                                lsp::Uri::from_file_path(path!("/root/src/main.rs")).unwrap(),
+                                vec![edit(1, 17..23)],
+                            ),
+                            (
+                                lsp::Uri::from_file_path(path!("/root/src/lib.rs")).unwrap(),
+                                vec![edit(0, 11..17), edit(2, 5..11), edit(4, 8..14)],
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    ..Default::default()
+                }))
+            });
+
+        let output = run_tool(
+            RenameSymbolTool::new(fake.project.clone(), action_log),
+            RenameSymbolToolInput {
+                path: "root/src/main.rs".into(),
+                line: 2,
+                symbol: "Thread".into(),
+                new_name: "Task".into(),
+            },
+            cx,
+        )
+        .await;
+        assert_eq!(
+            output,
+            "Renamed `Thread` to `Task` in 2 files:\n\
+             - root/src/lib.rs\n\
+             - root/src/main.rs\n\
+             \n\
+             These files had unsaved changes, so they were left unsaved:\n\
+             - root/src/lib.rs\n"
+        );
+
+        assert_eq!(
+            fake.fs
+                .load(path!("/root/src/main.rs").as_ref())
+                .await
+                .unwrap(),
+            "fn main() {\n    let thread = Task::new();\n}\n"
+        );
+        assert_eq!(
+            fake.fs
+                .load(path!("/root/src/lib.rs").as_ref())
+                .await
+                .unwrap(),
+            LIB_RS
+        );
+        lib_rs.read_with(cx, |buffer, _| {
+            assert_eq!(
+                buffer.text(),
+                "pub struct Task;\n\nimpl Task {\n    pub fn new() -> Self {\n        Task\n    }\n}\n// edited\n"
+            );
+            assert!(buffer.is_dirty());
+        });
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use gpui::{App, AsyncApp, Entity, Task};
+use language::{Buffer, Location, OffsetRangeExt as _, Point};
+use project::{Project, lsp_store::OpenLspBufferHandle};
+use std::{fmt::Write, path::PathBuf};
+
+/// The maximum number of locations included in the output of a symbol tool.
+pub(crate) const MAX_LOCATIONS: usize = 50;
+
+/// A symbol occurrence in a buffer, resolved from the path, line and name given by the model.
+pub(crate) struct LocatedSymbol {
+    pub buffer: Entity<Buffer>,
+    pub position: Point,
+    /// Keeps the buffer registered with its language servers while they are queried.
+    pub _lsp_handle: OpenLspBufferHandle,
+}
+
+/// Opens the file at `path` and finds `symbol` on the given 1-based `line`,
+/// registering the buffer with its language servers so they can be queried.
+pub(crate) fn locate_symbol(
+    project: &Entity<Project>,
+    path: &str,
+    line: u32,
+    symbol: &str,
+    cx: &mut App,
+) -> Task<Result<LocatedSymbol>> {
+    let Some(project_path) = project.read(cx).find_project_path(path, cx) else {
+        return Task::ready(Err(anyhow!("Could not find path {path} in project")));
+    };
+    let open_buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    let project = project.clone();
+    let path = path.to_string();
+    let symbol = symbol.to_string();
+    cx.spawn(async move |cx| {
+        let buffer = open_buffer.await?;
+        let row = line.checked_sub(1).context("Line numbers start at 1")?;
+        let position = buffer.read_with(cx, |buffer, _| {
+            if row > buffer.max_point().row {
+                anyhow::bail!(
+                    "Line {line} is past the end of {path}, which has {} lines",
+                    buffer.max_point().row + 1
+                );
+            }
+            let line_text = buffer
+                .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                .collect::<String>();
+            let column = find_symbol_column(&line_text, &symbol).with_context(|| {
+                format!(
+                    "Could not find `{symbol}` on line {line} of {path}. The line is: `{}`",
+                    line_text.trim()
+                )
+            })?;
+            Ok(Point::new(row, column))
+        })?;
+        let lsp_handle = project.update(cx, |project, cx| {
+            project.register_buffer_with_language_servers(&buffer, cx)
+        });
+        Ok(LocatedSymbol {
+            buffer,
+            position,
+            _lsp_handle: lsp_handle,
+        })
+    })
+}
+
+/// Returns the byte column of the first occurrence of `symbol` in `line` that isn't
+/// part of a longer identifier, falling back to the first occurrence of any kind.
+fn find_symbol_column(line: &str, symbol: &str) -> Option<u32> {
+    if symbol.is_empty() {
+        return None;
+    }
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut first_match = None;
+    for (start, _) in line.match_indices(symbol) {
+        first_match.get_or_insert(start);
+        let end = start + symbol.len();
+        let bounded_before = line[..start]
+            .chars()
+            .next_back()
+            .is_none_or(|c| !is_identifier_char(c));
+        let bounded_after = line[end..]
+            .chars()
+            .next()
+            .is_none_or(|c| !is_identifier_char(c));
+        if bounded_before && bounded_after {
+            return Some(start as u32);
+        }
+    }
+    first_match.map(|start| start as u32)
+}
+
+/// Writes one `path:line: text` entry per location, deduplicated and sorted by path.
+pub(crate) fn write_locations(
+    output: &mut String,
+    locations: impl IntoIterator<Item = Location>,
+    cx: &AsyncApp,
+) -> Result<usize> {
+    let mut entries = Vec::new();
+    let mut seen = HashSet::default();
+    for location in locations {
+        let entry = location.buffer.read_with(cx, |buffer, cx| {
+            let path = buffer
+                .file()
+                .map(|file| file.full_path(cx))
+                .unwrap_or_else(|| PathBuf::from("untitled"));
+            let range = location.range.to_point(&buffer.snapshot());
+            let row = range.start.row;
+            let text = buffer
+                .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                .collect::<String>();
+            (path, row, text.trim().to_string())
+        });
+        if seen.insert((entry.0.clone(), entry.1)) {
+            entries.push(entry);
+        }
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    let count = entries.len();
+    for (path, row, text) in entries.iter().take(MAX_LOCATIONS) {
+        writeln!(output, "{}:{}: {}", path.display(), row + 1, text)?;
+    }
+    if count > MAX_LOCATIONS {
+        writeln!(output, "... and {} more", count - MAX_LOCATIONS)?;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{AgentTool, ToolCallEventStream};
+    use futures::StreamExt as _;
+    use gpui::TestAppContext;
+    use language::FakeLspAdapter;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use std::{ops::Range, sync::Arc};
+    use util::path;
+
+    pub(crate) const MAIN_RS: &str = "fn main() {\n    let thread = Thread::new();\n}\n";
+    pub(crate) const LIB_RS: &str = "pub struct Thread;\n\nimpl Thread {\n    pub fn new() -> Self {\n        Thread\n    }\n}\n";
+
+    /// A project with a Rust crate whose `src/main.rs` is open with a fake language server.
+    pub(crate) struct FakeRustProject {
+        pub project: Entity<Project>,
+        pub fs: Arc<FakeFs>,
+        pub server: lsp::FakeLanguageServer,
+        _main_rs: (Entity<Buffer>, OpenLspBufferHandle),
+    }
+
+    pub(crate) async fn fake_rust_project(cx: &mut TestAppContext) -> FakeRustProject {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "src": {
+                    "main.rs": MAIN_RS,
+                    "lib.rs": LIB_RS,
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+
+        let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+        language_registry.add(language::rust_lang());
+        let mut fake_servers = language_registry.register_fake_lsp(
+            "Rust",
+            FakeLspAdapter {
+                capabilities: lsp::ServerCapabilities {
+                    definition_provider: Some(lsp::OneOf::Left(true)),
+                    references_provider: Some(lsp::OneOf::Left(true)),
+                    hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                    workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+                    rename_provider: Some(lsp::OneOf::Left(true)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+
+        let main_rs = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer_with_lsp(path!("/root/src/main.rs"), cx)
+            })
+            .await
+            .unwrap();
+        let server = fake_servers.next().await.unwrap();
+        cx.executor().run_until_parked();
+
+        FakeRustProject {
+            project,
+            fs,
+            server,
+            _main_rs: main_rs,
+        }
+    }
+
+    /// Returns a location on a single 0-based row of the file at the given absolute path.
+    pub(crate) fn lsp_location(path: &str, row: u32, columns: Range<u32>) -> lsp::Location {
+        lsp::Location::new(
+            lsp::Uri::from_file_path(path).unwrap(),
+            lsp::Range::new(
+                lsp::Position::new(row, columns.start),
+                lsp::Position::new(row, columns.end),
+            ),
+        )
+    }
+
+    pub(crate) async fn run_tool<T: AgentTool<Output = String>>(
+        tool: T,
+        input: T::Input,
+        cx: &mut TestAppContext,
+    ) -> String {
+        let output = cx
+            .update(|cx| Arc::new(tool).run(input, ToolCallEventStream::test().0, cx))
+            .await
+            .unwrap();
+        if cfg!(windows) {
+            output.replace("root\\src\\", "root/src/")
+        } else {
+            output
+        }
+    }
+
+    #[test]
+    fn test_find_symbol_column() {
+        assert_eq!(
+            find_symbol_column("fn run(thread: Thread)", "Thread"),
+            Some(15)
+        );
+        assert_eq!(
+            find_symbol_column("let thread = thread_id;", "thread"),
+            Some(4)
+        );
+        assert_eq!(find_symbol_column("    self.thread_id", "thread"), Some(9));
+        assert_eq!(find_symbol_column("fn main() {}", "missing"), None);
+        assert_eq!(find_symbol_column("fn main() {}", ""), None);
+    }
+}
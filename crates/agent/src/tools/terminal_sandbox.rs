@@ -0,0 +1,358 @@
+//! Confines commands run by the terminal tool using bubblewrap (`bwrap`).
+//!
+//! Sandboxed commands run in fresh user, mount, PID, IPC and (unless network
+//! access is allowed) network namespaces. Only the system directories are
+//! mounted from the host, read-only and without files holding credentials.
+//! The home directory and `/tmp` are replaced with empty tmpfs mounts, and the
+//! project's worktrees and any extra writable paths from the settings are
+//! mounted read-write on top of them.
+
+use agent_settings::TerminalSandbox;
+use anyhow::{Context as _, Result};
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+use util::shell::{ShellKind, get_system_shell};
+
+const BWRAP: &str = "bwrap";
+
+/// The host directories that sandboxed commands can read, when they exist.
+const SYSTEM_DIRS: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/libx32",
+    "/etc",
+    "/opt",
+    "/nix/store",
+    "/run/current-system",
+];
+
+/// Files and directories under the system directories that hold credentials,
+/// and are hidden from sandboxed commands.
+const SECRET_PATHS: &[&str] = &[
+    "/etc/shadow",
+    "/etc/gshadow",
+    "/etc/sudoers",
+    "/etc/sudoers.d",
+    "/etc/ssh",
+    "/etc/ssl/private",
+    "/etc/NetworkManager/system-connections",
+    "/etc/wireguard",
+];
+
+/// Output from `bwrap` itself starts with this prefix, rather than output from the command.
+const BWRAP_ERROR_PREFIX: &str = "bwrap: ";
+
+/// Errors in a command's output that are likely caused by the sandbox's restrictions.
+const READ_ONLY_ERROR: &str = "Read-only file system";
+const NOT_FOUND_ERROR: &str = "No such file or directory";
+const NETWORK_ERRORS: &[&str] = &[
+    "Network is unreachable",
+    "Temporary failure in name resolution",
+    "Could not resolve host",
+];
+
+pub(crate) struct SandboxedCommand {
+    pub command: String,
+    pub writable_roots: Vec<PathBuf>,
+    pub allow_network: bool,
+    pub home_dir: PathBuf,
+}
+
+impl SandboxedCommand {
+    /// Wraps `command` so that it runs inside the sandbox described by `settings`.
+    pub fn new(
+        command: &str,
+        worktree_roots: Vec<PathBuf>,
+        settings: &TerminalSandbox,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            cfg!(target_os = "linux"),
+            "The terminal sandbox is only supported on Linux. Disable `agent.terminal_sandbox` to run commands on this platform."
+        );
+        find_in_path(BWRAP).with_context(|| {
+            format!(
+                "The terminal sandbox is enabled, but `{BWRAP}` (bubblewrap) was not found on the PATH. Install it or disable `agent.terminal_sandbox`."
+            )
+        })?;
+
+        let mut writable_roots = worktree_roots;
+        writable_roots.extend(settings.writable_paths.iter().cloned());
+        writable_roots.sort();
+        writable_roots.dedup();
+
+        let host = SandboxHost {
+            home_dir: util::paths::home_dir().clone(),
+            shell: sandbox_shell(),
+            system_dirs: existing_paths(SYSTEM_DIRS),
+            secret_paths: existing_paths(SECRET_PATHS),
+        };
+
+        Ok(Self {
+            command: build_command(command, &host, &writable_roots, settings.allow_network)?,
+            writable_roots,
+            allow_network: settings.allow_network,
+            home_dir: host.home_dir,
+        })
+    }
+
+    /// Explains why a command may have failed because of the sandbox, so the
+    /// model can tell such failures apart from failures of the command itself.
+    ///
+    /// Returns `None` when the output doesn't show that the sandbox was involved.
+    pub fn failure_note(&self, output: &str) -> Option<String> {
+        if let Some(error) = output
+            .lines()
+            .find_map(|line| line.strip_prefix(BWRAP_ERROR_PREFIX))
+        {
+            return Some(format!(
+                "The sandbox for this command could not be started ({error}), so the command didn't run. \
+                Don't retry it; tell the user that the terminal sandbox failed to start."
+            ));
+        }
+
+        // Files in the home directory are hidden, unless they are in a writable path.
+        let filesystem_error = output.contains(READ_ONLY_ERROR)
+            || (output.contains(NOT_FOUND_ERROR)
+                && output.contains(&*self.home_dir.to_string_lossy()));
+        let network_error =
+            !self.allow_network && NETWORK_ERRORS.iter().any(|error| output.contains(error));
+        if !filesystem_error && !network_error {
+            return None;
+        }
+
+        let mut note = String::from(
+            "This command ran in a sandbox, where only system directories are readable, the home directory is empty, and only these paths are writable:",
+        );
+        for root in &self.writable_roots {
+            write!(note, "\n- {}", root.display()).ok();
+        }
+        if !self.allow_network {
+            note.push_str("\nNetwork access is also disabled in the sandbox.");
+        }
+        note.push_str(
+            "\nIf the command failed because of these restrictions, don't try to work around them; tell the user which access the command needs.",
+        );
+        Some(note)
+    }
+}
+
+/// The parts of the host that the sandbox is built from.
+struct SandboxHost {
+    home_dir: PathBuf,
+    shell: String,
+    system_dirs: Vec<PathBuf>,
+    secret_paths: Vec<PathBuf>,
+}
+
+/// Returns the user's shell if it is visible inside the sandbox, or `/bin/sh` otherwise.
+fn sandbox_shell() -> String {
+    let shell = get_system_shell();
+    let visible = SYSTEM_DIRS
+        .iter()
+        .any(|dir| Path::new(&shell).starts_with(dir));
+    if visible {
+        shell
+    } else {
+        "/bin/sh".to_string()
+    }
+}
+
+fn existing_paths(paths: &[&str]) -> Vec<PathBuf> {
+    paths
+        .iter()
+        .map(PathBuf::from)
+        .filter(|path| path.symlink_metadata().is_ok())
+        .collect()
+}
+
+fn build_command(
+    command: &str,
+    host: &SandboxHost,
+    writable_roots: &[PathBuf],
+    allow_network: bool,
+) -> Result<String> {
+    let mut args = vec![BWRAP.to_string()];
+    for dir in &host.system_dirs {
+        let dir = dir.to_string_lossy().into_owned();
+        args.extend(["--ro-bind".into(), dir.clone(), dir]);
+    }
+    for path in &host.secret_paths {
+        let path = path.to_string_lossy().into_owned();
+        if Path::new(&path).is_dir() {
+            args.extend(["--tmpfs".into(), path]);
+        } else {
+            args.extend(["--ro-bind".into(), "/dev/null".into(), path]);
+        }
+    }
+    args.extend([
+        "--dev".into(),
+        "/dev".into(),
+        "--proc".into(),
+        "/proc".into(),
+        "--tmpfs".into(),
+        "/tmp".into(),
+        "--tmpfs".into(),
+        host.home_dir.to_string_lossy().into_owned(),
+    ]);
+    // Binds come after the tmpfs mounts so that worktrees inside of `/tmp` or
+    // the home directory stay visible.
+    for root in writable_roots {
+        let root = root.to_string_lossy().into_owned();
+        args.extend(["--bind-try".into(), root.clone(), root]);
+    }
+    args.push("--unshare-all".into());
+    if allow_network {
+        args.extend([
+            "--share-net".into(),
+            "--ro-bind-try".into(),
+            "/run/systemd/resolve".into(),
+            "/run/systemd/resolve".into(),
+        ]);
+    }
+    args.extend([
+        "--die-with-parent".into(),
+        "--".into(),
+        host.shell.clone(),
+        "-c".into(),
+        command.to_string(),
+    ]);
+
+    let mut quoted = Vec::with_capacity(args.len());
+    for arg in &args {
+        let arg = ShellKind::Posix
+            .try_quote(arg)
+            .with_context(|| format!("Failed to quote {arg:?} for the terminal sandbox"))?;
+        quoted.push(arg.into_owned());
+    }
+    Ok(quoted.join(" "))
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(program))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt as _;
+        path.metadata()
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_host() -> SandboxHost {
+        SandboxHost {
+            home_dir: PathBuf::from("/home/user"),
+            shell: "/usr/bin/zsh".into(),
+            system_dirs: vec![PathBuf::from("/usr"), PathBuf::from("/etc")],
+            secret_paths: vec![PathBuf::from("/etc/shadow")],
+        }
+    }
+
+    #[test]
+    fn test_build_command() {
+        let command = build_command(
+            "cargo test && echo 'done'",
+            &test_host(),
+            &[PathBuf::from("/home/user/my project")],
+            false,
+        )
+        .unwrap();
+        let args = ShellKind::Posix.split(&command).unwrap();
+
+        assert_eq!(
+            args,
+            [
+                "bwrap",
+                "--ro-bind",
+                "/usr",
+                "/usr",
+                "--ro-bind",
+                "/etc",
+                "/etc",
+                "--ro-bind",
+                "/dev/null",
+                "/etc/shadow",
+                "--dev",
+                "/dev",
+                "--proc",
+                "/proc",
+                "--tmpfs",
+                "/tmp",
+                "--tmpfs",
+                "/home/user",
+                "--bind-try",
+                "/home/user/my project",
+                "/home/user/my project",
+                "--unshare-all",
+                "--die-with-parent",
+                "--",
+                "/usr/bin/zsh",
+                "-c",
+                "cargo test && echo 'done'",
+            ]
+        );
+
+        let command = build_command("curl example.com", &test_host(), &[], true).unwrap();
+        let args = ShellKind::Posix.split(&command).unwrap();
+        assert!(args.contains(&"--share-net".to_string()));
+    }
+
+    #[test]
+    fn test_failure_note() {
+        let sandbox = SandboxedCommand {
+            command: String::new(),
+            writable_roots: vec![PathBuf::from("/home/user/project")],
+            allow_network: false,
+            home_dir: PathBuf::from("/home/user"),
+        };
+
+        assert_eq!(
+            sandbox.failure_note("error: test failed, to rerun pass `--lib`"),
+            None
+        );
+        assert_eq!(
+            sandbox.failure_note("cat: missing.txt: No such file or directory"),
+            None
+        );
+
+        let note = sandbox
+            .failure_note("bwrap: No permissions to create new namespace")
+            .unwrap();
+        assert!(note.contains("could not be started (No permissions to create new namespace)"));
+
+        let note = sandbox
+            .failure_note("touch: cannot touch '/etc/hosts': Read-only file system")
+            .unwrap();
+        assert!(note.contains("- /home/user/project"));
+        assert!(note.contains("Network access is also disabled"));
+
+        assert!(
+            sandbox
+                .failure_note("ls: cannot access '/home/user/.cargo': No such file or directory")
+                .is_some()
+        );
+        assert!(
+            sandbox
+                .failure_note("curl: (6) Could not resolve host: example.com")
+                .is_some()
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use agent_client_protocol as acp;
-use agent_settings::AgentSettings;
+use agent_settings::{AgentSettings, TerminalSandbox};
 use anyhow::Result;
 use futures::FutureExt as _;
 use gpui::{App, Entity, SharedString, Task};
@@ -16,7 +16,7 @@ use std::{
 
 use crate::{
     AgentTool, ThreadEnvironment, ToolCallEventStream, ToolPermissionDecision,
-    decide_permission_from_settings,
+    decide_permission_from_settings, tools::terminal_sandbox::SandboxedCommand,
 };
 
 const COMMAND_OUTPUT_LIMIT: u64 = 16 * 1024;
@@ -98,6 +98,14 @@ impl AgentTool for TerminalTool {
 
         let settings = AgentSettings::get_global(cx);
         let decision = decide_permission_from_settings(Self::name(), &input.command, settings);
+        let sandbox = if settings.terminal_sandbox.enabled {
+            match sandboxed_command(&input, &self.project, &settings.terminal_sandbox, cx) {
+                Ok(sandbox) => Some(sandbox),
+                Err(err) => return Task::ready(Err(err)),
+            }
+        } else {
+            None
+        };
 
         let authorize = match decision {
             ToolPermissionDecision::Allow => None,
@@ -112,19 +120,19 @@ impl AgentTool for TerminalTool {
                 Some(event_stream.authorize(self.initial_title(Ok(input.clone()), cx), context, cx))
             }
         };
+
         cx.spawn(async move |cx| {
             if let Some(authorize) = authorize {
                 authorize.await?;
             }
 
+            let command = match &sandbox {
+                Some(sandbox) => sandbox.command.clone(),
+                None => input.command.clone(),
+            };
             let terminal = self
                 .environment
-                .create_terminal(
-                    input.command.clone(),
-                    working_dir,
-                    Some(COMMAND_OUTPUT_LIMIT),
-                    cx,
-                )
+                .create_terminal(command, working_dir, Some(COMMAND_OUTPUT_LIMIT), cx)
                 .await?;
 
             let terminal_id = terminal.id(cx)?;
@@ -179,13 +187,19 @@ impl AgentTool for TerminalTool {
             let user_stopped = user_stopped_via_signal || user_stopped_via_terminal;
 
             let output = terminal.current_output(cx)?;
-
-            Ok(process_content(
-                output,
-                &input.command,
-                timed_out,
-                user_stopped,
-            ))
+            let succeeded = output
+                .exit_status
+                .as_ref()
+                .is_some_and(|status| status.exit_code == Some(0));
+            let sandbox_note = sandbox
+                .filter(|_| !succeeded && !user_stopped)
+                .and_then(|sandbox| sandbox.failure_note(&output.output));
+
+            let content = process_content(output, &input.command, timed_out, user_stopped);
+            match sandbox_note {
+                Some(note) => Ok(format!("{content}\n\n{note}")),
+                None => Ok(content),
+            }
         })
     }
 }
@@ -266,6 +280,33 @@ fn process_content(
     content
 }
 
+fn sandboxed_command(
+    input: &TerminalToolInput,
+    project: &Entity<Project>,
+    settings: &TerminalSandbox,
+    cx: &App,
+) -> Result<SandboxedCommand> {
+    let project = project.read(cx);
+    anyhow::ensure!(
+        project.is_local(),
+        "The terminal sandbox is only supported for local projects. Disable `agent.terminal_sandbox` to run commands in remote projects."
+    );
+    SandboxedCommand::new(
+        &input.command,
+        sandbox_writable_roots(project, cx),
+        settings,
+    )
+}
+
+/// Returns the directories a sandboxed command may write to. Invisible worktrees are left out,
+/// as they are files opened from outside the project, such as the user's settings file.
+fn sandbox_writable_roots(project: &Project, cx: &App) -> Vec<PathBuf> {
+    project
+        .visible_worktrees(cx)
+        .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+        .collect()
+}
+
 fn working_dir(
     input: &TerminalToolInput,
     project: &Entity<Project>,
@@ -310,6 +351,38 @@ fn working_dir(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_sandbox_writable_roots_exclude_invisible_worktrees(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/root"),
+            json!({
+                "project": { "main.rs": "" },
+                "settings.json": "{}",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/root/project").as_ref()], cx).await;
+        project
+            .update(cx, |project, cx| {
+                project.find_or_create_worktree(path!("/root/settings.json"), false, cx)
+            })
+            .await
+            .unwrap();
+
+        let roots = project.read_with(cx, |project, cx| sandbox_writable_roots(project, cx));
+        assert_eq!(roots, [PathBuf::from(path!("/root/project"))]);
+    }
 
     #[test]
     fn test_initial_title_shows_full_multiline_command() {
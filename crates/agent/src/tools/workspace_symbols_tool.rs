@@ -0,0 +1,172 @@
+use crate::tools::symbol_locator::MAX_LOCATIONS;
+use crate::{AgentTool, ToolCallEventStream};
+use agent_client_protocol as acp;
+use anyhow::Result;
+use futures::FutureExt as _;
+use gpui::{App, Entity, SharedString, Task};
+use project::{Project, lsp_store::SymbolLocation};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+/// Searches the project's language servers for symbols (functions, types, constants, etc.) by name.
+///
+/// Prefer this tool over grep or path search when you know the name of a symbol but not where it is defined.
+/// The query is fuzzy-matched by the language server, so partial names work.
+///
+/// Returns one `path:line: kind name` entry per symbol.
+///
+/// <example>
+/// To find where `ThreadsDatabase` is defined:
+/// {
+///     "query": "ThreadsDatabase"
+/// }
+/// </example>
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WorkspaceSymbolsToolInput {
+    /// The name, or part of the name, of the symbol to search for.
+    pub query: String,
+}
+
+pub struct WorkspaceSymbolsTool {
+    project: Entity<Project>,
+}
+
+impl WorkspaceSymbolsTool {
+    pub fn new(project: Entity<Project>) -> Self {
+        Self { project }
+    }
+}
+
+impl AgentTool for WorkspaceSymbolsTool {
+    type Input = WorkspaceSymbolsToolInput;
+    type Output = String;
+
+    fn name() -> &'static str {
+        "workspace_symbols"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Search
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Search symbols for {}", MarkdownInlineCode(&input.query)).into(),
+            Err(_) => "Search symbols".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let symbols = self
+            .project
+            .update(cx, |project, cx| project.symbols(&input.query, cx));
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let symbols = futures::select! {
+                result = symbols.fuse() => result?,
+                _ = event_stream.cancelled_by_user().fuse() => {
+                    anyhow::bail!("Symbol search cancelled by user");
+                }
+            };
+
+            let mut entries = project.read_with(cx, |project, cx| {
+                symbols
+                    .iter()
+                    .filter_map(|symbol| {
+                        let path = match &symbol.path {
+                            SymbolLocation::InProject(project_path) => project
+                                .worktree_for_id(project_path.worktree_id, cx)?
+                                .read(cx)
+                                .full_path(&project_path.path),
+                            SymbolLocation::OutsideProject { abs_path, .. } => {
+                                abs_path.to_path_buf()
+                            }
+                        };
+                        let kind = format!("{:?}", symbol.kind).to_lowercase();
+                        Some((path, symbol.range.start.0.row, kind, symbol.name.clone()))
+                    })
+                    .collect::<Vec<_>>()
+            });
+            entries.sort();
+            entries.dedup();
+
+            if entries.is_empty() {
+                return Ok(format!(
+                    "No symbols found for `{}`. The project may not have a language server that supports symbol search; fall back to grep if needed.",
+                    input.query
+                ));
+            }
+
+            let mut output = format!("Found {} symbols:\n", entries.len());
+            for (path, row, kind, name) in entries.iter().take(MAX_LOCATIONS) {
+                writeln!(output, "{}:{}: {kind} {name}", path.display(), row + 1)?;
+            }
+            if entries.len() > MAX_LOCATIONS {
+                writeln!(
+                    output,
+                    "... and {} more. Use a more specific query to narrow the results.",
+                    entries.len() - MAX_LOCATIONS
+                )?;
+            }
+            Ok(output)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::symbol_locator::tests::{fake_rust_project, lsp_location, run_tool};
+    use gpui::TestAppContext;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_workspace_symbols(cx: &mut TestAppContext) {
+        let fake = fake_rust_project(cx).await;
+        let input = || WorkspaceSymbolsToolInput {
+            query: "Thread".into(),
+        };
+
+        fake.server
+            .set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(|params, _| async move {
+                assert_eq!(params.query, "Thread");
+                Ok(Some(lsp::WorkspaceSymbolResponse::Flat(vec![
+                    #[allow(deprecated)]
+                    lsp::SymbolInformation {
+                        name: "Thread".into(),
+                        kind: lsp::SymbolKind::STRUCT,
+                        location: lsp_location(path!("/root/src/lib.rs"), 0, 11..17),
+                        tags: None,
+                        container_name: None,
+                        deprecated: None,
+                    },
+                ])))
+            });
+        let output = run_tool(WorkspaceSymbolsTool::new(fake.project.clone()), input(), cx).await;
+        assert_eq!(
+            output,
+            "Found 1 symbols:\nroot/src/lib.rs:1: struct Thread\n"
+        );
+
+        fake.server
+            .set_request_handler::<lsp::WorkspaceSymbolRequest, _, _>(
+                |_, _| async move { Ok(None) },
+            );
+        let output = run_tool(WorkspaceSymbolsTool::new(fake.project.clone()), input(), cx).await;
+        assert!(
+            output.starts_with("No symbols found for `Thread`."),
+            "unexpected output: {output}"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 mod agent_profile;
 
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use agent_client_protocol::ModelId;
 use collections::{HashSet, IndexMap};
@@ -51,6 +51,8 @@ pub struct AgentSettings {
     pub message_editor_min_lines: usize,
     pub show_turn_stats: bool,
     pub tool_permissions: ToolPermissions,
+    pub hooks: AgentHooks,
+    pub terminal_sandbox: TerminalSandbox,
 }
 
 impl AgentSettings {
@@ -185,6 +187,48 @@ impl Default for ToolRules {
     }
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct AgentHooks {
+    pub pre_tool_use: Vec<AgentHook>,
+    pub post_tool_use: Vec<AgentHook>,
+    pub user_prompt_submit: Vec<AgentHook>,
+    pub stop: Vec<AgentHook>,
+}
+
+impl AgentHooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre_tool_use.is_empty()
+            && self.post_tool_use.is_empty()
+            && self.user_prompt_submit.is_empty()
+            && self.stop.is_empty()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AgentHook {
+    pub command: String,
+    /// Restricts a tool hook to matching tool names. `None` matches every tool.
+    pub matcher: Option<CompiledRegex>,
+    pub timeout: Duration,
+}
+
+impl AgentHook {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub fn matches_tool(&self, tool_name: &str) -> bool {
+        self.matcher
+            .as_ref()
+            .is_none_or(|matcher| matcher.is_match(tool_name))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TerminalSandbox {
+    pub enabled: bool,
+    pub allow_network: bool,
+    pub writable_paths: Vec<PathBuf>,
+}
+
 #[derive(Clone)]
 pub struct CompiledRegex {
     pub pattern: String,
@@ -261,6 +305,8 @@ impl Settings for AgentSettings {
             message_editor_min_lines: agent.message_editor_min_lines.unwrap(),
             show_turn_stats: agent.show_turn_stats.unwrap(),
             tool_permissions: compile_tool_permissions(agent.tool_permissions),
+            hooks: compile_hooks(agent.hooks),
+            terminal_sandbox: compile_terminal_sandbox(agent.terminal_sandbox),
         }
     }
 }
@@ -323,6 +369,79 @@ fn compile_tool_permissions(content: Option<settings::ToolPermissionsContent>) -
     ToolPermissions { tools }
 }
 
+fn compile_hooks(content: Option<settings::AgentHooksContent>) -> AgentHooks {
+    let Some(content) = content else {
+        return AgentHooks::default();
+    };
+
+    fn compile(
+        event: &str,
+        hooks: Option<settings::ExtendingVec<settings::AgentHookContent>>,
+    ) -> Vec<AgentHook> {
+        hooks
+            .map(|hooks| hooks.0)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|hook| !hook.command.trim().is_empty())
+            .map(|hook| {
+                // An invalid matcher runs the hook for every tool rather than
+                // silently skipping it, since hooks are often used to deny actions.
+                let matcher = hook.matcher.and_then(|pattern| {
+                    CompiledRegex::try_new(&pattern, true)
+                        .inspect_err(|error| {
+                            log::error!(
+                                "Invalid matcher in {} hook '{}': '{}' - {}",
+                                event,
+                                hook.command,
+                                pattern,
+                                error
+                            );
+                        })
+                        .ok()
+                });
+                AgentHook {
+                    command: hook.command,
+                    matcher,
+                    timeout: hook
+                        .timeout
+                        .map(Duration::from_secs)
+                        .unwrap_or(AgentHook::DEFAULT_TIMEOUT),
+                }
+            })
+            .collect()
+    }
+
+    AgentHooks {
+        pre_tool_use: compile("pre_tool_use", content.pre_tool_use),
+        post_tool_use: compile("post_tool_use", content.post_tool_use),
+        user_prompt_submit: compile("user_prompt_submit", content.user_prompt_submit),
+        stop: compile("stop", content.stop),
+    }
+}
+
+fn compile_terminal_sandbox(content: Option<settings::TerminalSandboxContent>) -> TerminalSandbox {
+    let Some(content) = content else {
+        return TerminalSandbox::default();
+    };
+
+    TerminalSandbox {
+        enabled: content.enabled.unwrap_or(false),
+        allow_network: content.allow_network.unwrap_or(false),
+        writable_paths: content
+            .writable_paths
+            .map(|paths| paths.0)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|path| !path.trim().is_empty())
+            .map(|path| match path.strip_prefix("~/") {
+                Some(rest) => util::paths::home_dir().join(rest),
+                None if path == "~" => util::paths::home_dir().clone(),
+                None => PathBuf::from(path),
+            })
+            .collect(),
+    }
+}
+
 fn compile_regex_rules(
     rules: Vec<settings::ToolRegexRule>,
     rule_type: &str,
@@ -418,6 +537,65 @@ mod tests {
         assert_eq!(rules.default_mode, ToolPermissionMode::Deny);
     }
 
+    #[test]
+    fn test_hooks_parsing() {
+        let json = json!({
+            "pre_tool_use": [
+                { "matcher": "^edit_file$", "command": "./check-generated.sh" },
+                { "matcher": "[invalid", "command": "./audit.sh", "timeout": 5 }
+            ],
+            "post_tool_use": [
+                { "command": "cargo fmt" },
+                { "command": "   " }
+            ]
+        });
+
+        let content: settings::AgentHooksContent = serde_json::from_value(json).unwrap();
+        let hooks = compile_hooks(Some(content));
+
+        assert_eq!(hooks.pre_tool_use.len(), 2);
+        assert!(hooks.pre_tool_use[0].matches_tool("edit_file"));
+        assert!(!hooks.pre_tool_use[0].matches_tool("terminal"));
+        assert_eq!(hooks.pre_tool_use[0].timeout, AgentHook::DEFAULT_TIMEOUT);
+        assert!(
+            hooks.pre_tool_use[1].matches_tool("terminal"),
+            "Hooks with invalid matchers should run for every tool"
+        );
+        assert_eq!(hooks.pre_tool_use[1].timeout, Duration::from_secs(5));
+
+        assert_eq!(
+            hooks.post_tool_use.len(),
+            1,
+            "Hooks with empty commands should be ignored"
+        );
+        assert!(hooks.post_tool_use[0].matches_tool("read_file"));
+        assert!(hooks.user_prompt_submit.is_empty());
+        assert!(hooks.stop.is_empty());
+        assert!(compile_hooks(None).is_empty());
+    }
+
+    #[test]
+    fn test_terminal_sandbox_parsing() {
+        let json = json!({
+            "enabled": true,
+            "writable_paths": ["~/.cargo", "/var/cache/build", ""]
+        });
+
+        let content: settings::TerminalSandboxContent = serde_json::from_value(json).unwrap();
+        let sandbox = compile_terminal_sandbox(Some(content));
+
+        assert!(sandbox.enabled);
+        assert!(!sandbox.allow_network);
+        assert_eq!(
+            sandbox.writable_paths,
+            vec![
+                util::paths::home_dir().join(".cargo"),
+                PathBuf::from("/var/cache/build"),
+            ]
+        );
+        assert_eq!(compile_terminal_sandbox(None), TerminalSandbox::default());
+    }
+
     #[test]
     fn test_tool_permissions_empty() {
         let permissions = compile_tool_permissions(None);
@@ -15,6 +15,7 @@ use agent::ThreadStore;
 use agent_client_protocol as acp;
 use anyhow::{Result, anyhow};
 use collections::HashSet;
+use context_server::ContextServerId;
 use editor::{
     Addon, AnchorRangeExt, ContextMenuOptions, ContextMenuPlacement, Editor, EditorElement,
     EditorEvent, EditorMode, EditorStyle, Inlay, MultiBuffer, MultiBufferOffset,
@@ -74,7 +75,11 @@ impl PromptCompletionProviderDelegate for Entity<MessageEditor> {
             if self.read(cx).thread_store.is_some() {
                 supported.push(PromptContextType::Thread);
             }
-            supported.extend(&[PromptContextType::Fetch, PromptContextType::Rules]);
+            supported.extend(&[
+                PromptContextType::Fetch,
+                PromptContextType::Rules,
+                PromptContextType::McpResource,
+            ]);
         }
         supported
     }
@@ -186,8 +191,9 @@ impl MessageEditor {
                 {
                     editor.update(cx, |editor, cx| {
                         let snapshot = editor.snapshot(window, cx);
-                        this.mention_set
-                            .update(cx, |mention_set, _cx| mention_set.remove_invalid(&snapshot));
+                        this.mention_set.update(cx, |mention_set, cx| {
+                            mention_set.remove_invalid(&snapshot, cx)
+                        });
 
                         let new_hints = this
                             .command_hint(snapshot.buffer())
@@ -802,6 +808,60 @@ impl MessageEditor {
         .detach();
     }
 
+    /// Appends a mention of an MCP server's resource to the message.
+    pub fn insert_mcp_resource(
+        &mut self,
+        server_id: ContextServerId,
+        uri: String,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let buffer = self.editor.read(cx).buffer().clone();
+        let Some(buffer) = buffer.read(cx).as_singleton() else {
+            return;
+        };
+
+        let crease_text = SharedString::from(name.clone());
+        let uri = MentionUri::McpResource {
+            server_id: server_id.0.to_string(),
+            uri,
+            name,
+        };
+        let new_text = format!("{} ", uri.as_link());
+        let content_len = new_text.len() - 1;
+
+        let anchor = buffer.update(cx, |buffer, _cx| buffer.anchor_before(buffer.len()));
+        self.editor.update(cx, |message_editor, cx| {
+            message_editor.edit(
+                [(
+                    multi_buffer::Anchor::max()..multi_buffer::Anchor::max(),
+                    new_text,
+                )],
+                cx,
+            );
+        });
+        let supports_images = self.prompt_capabilities.borrow().image;
+        self.mention_set
+            .update(cx, |mention_set, cx| {
+                mention_set.confirm_mention_completion(
+                    crease_text,
+                    anchor,
+                    content_len,
+                    uri,
+                    supports_images,
+                    self.editor.clone(),
+                    &workspace,
+                    window,
+                    cx,
+                )
+            })
+            .detach();
+    }
+
     /// Inserts code snippets as creases into the editor.
     /// Each tuple contains (code_text, crease_title).
     pub fn insert_code_creases(
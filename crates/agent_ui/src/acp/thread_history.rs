@@ -571,6 +571,30 @@ impl AcpThreadHistory {
             })
             .unwrap_or_else(|| "Unknown".to_string());
 
+        let parent_title = acp_thread::parent_session_id_from_meta(&entry.meta).map(|parent_id| {
+            self.sessions
+                .iter()
+                .find(|session| session.session_id == parent_id)
+                .map_or_else(
+                    || DEFAULT_TITLE.clone(),
+                    |parent| thread_title(parent).clone(),
+                )
+        });
+        let fork_count = self
+            .sessions
+            .iter()
+            .filter(|session| {
+                acp_thread::parent_session_id_from_meta(&session.meta).as_ref()
+                    == Some(&entry.session_id)
+            })
+            .count();
+        let tooltip_meta = match (&parent_title, fork_count) {
+            (Some(parent_title), _) => format!("Forked from \"{parent_title}\" · {full_date}"),
+            (None, 0) => full_date,
+            (None, 1) => format!("1 fork · {full_date}"),
+            (None, count) => format!("{count} forks · {full_date}"),
+        };
+
         h_flex()
             .w_full()
             .pb_1()
@@ -585,9 +609,24 @@ impl AcpThreadHistory {
                             .gap_2()
                             .justify_between()
                             .child(
-                                HighlightedLabel::new(thread_title(entry), highlight_positions)
-                                    .size(LabelSize::Small)
-                                    .truncate(),
+                                h_flex()
+                                    .min_w_0()
+                                    .gap_1()
+                                    .when(parent_title.is_some(), |this| {
+                                        this.child(
+                                            Icon::new(IconName::GitBranchAlt)
+                                                .size(IconSize::XSmall)
+                                                .color(Color::Muted),
+                                        )
+                                    })
+                                    .child(
+                                        HighlightedLabel::new(
+                                            thread_title(entry),
+                                            highlight_positions,
+                                        )
+                                        .size(LabelSize::Small)
+                                        .truncate(),
+                                    ),
                             )
                             .child(
                                 Label::new(display_text)
@@ -596,7 +635,7 @@ impl AcpThreadHistory {
                             ),
                     )
                     .tooltip(move |_, cx| {
-                        Tooltip::with_meta(title.clone(), None, full_date.clone(), cx)
+                        Tooltip::with_meta(title.clone(), None, tooltip_meta.clone(), cx)
                     })
                     .on_hover(cx.listener(move |this, is_hovered, _window, cx| {
                         if *is_hovered {
@@ -10,12 +10,13 @@ use agent::{NativeAgentServer, NativeAgentSessionList, SharedThread, ThreadStore
 use agent_client_protocol::{self as acp, PromptCapabilities};
 use agent_servers::{AgentServer, AgentServerDelegate};
 use agent_settings::{AgentProfileId, AgentSettings};
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use arrayvec::ArrayVec;
 use audio::{Audio, Sound};
 use buffer_diff::BufferDiff;
 use client::zed_urls;
 use collections::{HashMap, HashSet};
+use context_server::ContextServerId;
 use editor::scroll::Autoscroll;
 use editor::{
     Editor, EditorEvent, EditorMode, MultiBuffer, PathKey, SelectionEffects, SizingBehavior,
@@ -27,10 +28,10 @@ use futures::FutureExt as _;
 use gpui::{
     Action, Animation, AnimationExt, AnyView, App, BorderStyle, ClickEvent, ClipboardItem,
     CursorStyle, EdgesRefinement, ElementId, Empty, Entity, FocusHandle, Focusable, Hsla, Length,
-    ListOffset, ListState, ObjectFit, PlatformDisplay, ScrollHandle, SharedString, StyleRefinement,
-    Subscription, Task, TextStyle, TextStyleRefinement, UnderlineStyle, WeakEntity, Window,
-    WindowHandle, div, ease_in_out, img, linear_color_stop, linear_gradient, list, point,
-    pulsating_between,
+    ListOffset, ListState, ObjectFit, PlatformDisplay, PromptLevel, ScrollHandle, SharedString,
+    StyleRefinement, Subscription, Task, TextStyle, TextStyleRefinement, UnderlineStyle,
+    WeakEntity, Window, WindowHandle, div, ease_in_out, img, linear_color_stop, linear_gradient,
+    list, point, pulsating_between,
 };
 use language::Buffer;
 use language_model::LanguageModelRegistry;
@@ -55,7 +56,10 @@ use ui::{
 };
 use util::defer;
 use util::{ResultExt, size::format_file_size, time::duration_alt_display};
-use workspace::{CollaboratorId, NewTerminal, Toast, Workspace, notifications::NotificationId};
+use workspace::{
+    CollaboratorId, NewTerminal, Toast, Workspace,
+    notifications::{NotificationId, NotifyTaskExt as _},
+};
 use zed_actions::agent::{Chat, ToggleModelSelector};
 use zed_actions::assistant::OpenRulesLibrary;
 
@@ -2434,6 +2438,70 @@ impl AcpThreadView {
             .detach_and_log_err(cx);
     }
 
+    /// Forks the thread into a new one that starts from the given message, and opens it.
+    ///
+    /// When `restore_checkpoint` is true, the user is asked to confirm that the files should
+    /// also be restored to their state before the message. The fork is opened even if
+    /// restoring them fails.
+    fn fork_thread(
+        &mut self,
+        message_id: &UserMessageId,
+        restore_checkpoint: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(thread) = self.thread().cloned() else {
+            return;
+        };
+        let Some(connection) = self.as_native_connection(cx) else {
+            return;
+        };
+        let session_id = thread.read(cx).session_id().clone();
+        let message_id = message_id.clone();
+        let workspace = self.workspace.clone();
+        let confirmation = restore_checkpoint.then(|| {
+            window.prompt(
+                PromptLevel::Warning,
+                "Fork the thread and restore the checkpoint?",
+                Some(
+                    "Your files will be restored to their state before this message, \
+                    discarding any changes made since then, including your own.",
+                ),
+                &["Fork and Restore", "Cancel"],
+                cx,
+            )
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            if let Some(confirmation) = confirmation
+                && confirmation.await.ok() != Some(0)
+            {
+                return Ok(());
+            }
+
+            let fork = this.update(cx, |_, cx| {
+                connection.fork_thread(&session_id, &message_id, cx)
+            })?;
+            let session_info = fork.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                if let Some(panel) = workspace.panel::<AgentPanel>(cx) {
+                    panel.update(cx, |panel, cx| panel.open_thread(session_info, window, cx));
+                }
+            })?;
+
+            if restore_checkpoint {
+                thread
+                    .update(cx, |thread, cx| {
+                        thread.restore_git_checkpoint(&message_id, cx)
+                    })
+                    .await
+                    .context("The thread was forked, but restoring the checkpoint failed")?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_notify_err(window, cx);
+    }
+
     fn render_entry(
         &self,
         entry_ix: usize,
@@ -2561,9 +2629,42 @@ impl AcpThreadView {
                                     .bg(cx.theme().colors().editor_background)
                                     .overflow_hidden();
 
-                                if message.id.is_some() {
+                                if let Some(message_id) = message.id.clone() {
+                                    let can_fork = self.as_native_connection(cx).is_some();
+                                    let has_checkpoint = message.checkpoint.is_some();
                                     this.child(
                                         base_container
+                                            .when(can_fork, |this| {
+                                                this.child(
+                                                    IconButton::new("fork", IconName::GitBranchAlt)
+                                                        .disabled(self.is_loading_contents)
+                                                        .icon_color(Color::Muted)
+                                                        .icon_size(IconSize::XSmall)
+                                                        .tooltip(Tooltip::text(
+                                                            "Fork the thread into a new one that starts from this message."
+                                                        ))
+                                                        .on_click(cx.listener({
+                                                            let message_id = message_id.clone();
+                                                            move |this, _, window, cx| {
+                                                                this.fork_thread(&message_id, false, window, cx);
+                                                            }
+                                                        }))
+                                                )
+                                            })
+                                            .when(can_fork && has_checkpoint, |this| {
+                                                this.child(
+                                                    IconButton::new("fork-and-restore", IconName::RotateCcw)
+                                                        .disabled(self.is_loading_contents)
+                                                        .icon_color(Color::Muted)
+                                                        .icon_size(IconSize::XSmall)
+                                                        .tooltip(Tooltip::text(
+                                                            "Fork the thread from this message and restore the files to their state before it."
+                                                        ))
+                                                        .on_click(cx.listener(move |this, _, window, cx| {
+                                                            this.fork_thread(&message_id, true, window, cx);
+                                                        }))
+                                                )
+                                            })
                                             .child(
                                                 IconButton::new("cancel", IconName::Close)
                                                     .disabled(self.is_loading_contents)
@@ -6593,6 +6694,11 @@ impl AcpThreadView {
                 MentionUri::Fetch { url } => {
                     cx.open_url(url.as_str());
                 }
+                MentionUri::McpResource { uri, .. } => {
+                    if uri.starts_with("http://") || uri.starts_with("https://") {
+                        cx.open_url(&uri);
+                    }
+                }
             })
         } else {
             cx.open_url(&url);
@@ -6712,6 +6818,79 @@ impl AcpThreadView {
         })
     }
 
+    pub fn export_thread_as_markdown(
+        &self,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let Some(thread) = self.thread() else {
+            return Task::ready(Ok(()));
+        };
+        let thread = thread.read(cx);
+        let title = thread.title().to_string();
+        let markdown = thread.to_markdown(cx);
+
+        Self::export_thread(title, "md", Task::ready(Ok(markdown)), workspace, cx)
+    }
+
+    pub fn export_thread_as_json(
+        &self,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let Some(thread) = self.as_native_thread(cx) else {
+            return Task::ready(Err(anyhow!(
+                "Only Zed Agent threads can be exported as JSON"
+            )));
+        };
+        let title = thread.read(cx).title().to_string();
+        let db_thread = thread.read(cx).to_db(cx);
+        let json = cx.background_spawn(async move { db_thread.await.to_json() });
+
+        Self::export_thread(title, "json", json, workspace, cx)
+    }
+
+    fn export_thread(
+        title: String,
+        extension: &str,
+        contents: Task<Result<String>>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let fs = workspace.read(cx).app_state().fs.clone();
+        let directory = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .unwrap_or_else(|| paths::home_dir().clone());
+        let file_name = format!("{}.{extension}", export_file_stem(&title));
+        let path = cx.prompt_for_new_path(&directory, Some(&file_name));
+
+        cx.spawn(async move |cx| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            let contents = contents.await?;
+            fs.atomic_write(path.clone(), contents).await?;
+
+            workspace.update(cx, |workspace, cx| {
+                struct ThreadExportedToast;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<ThreadExportedToast>(),
+                        format!("Thread exported to {}", path.display()),
+                    )
+                    .autohide(),
+                    cx,
+                );
+            });
+            anyhow::Ok(())
+        })
+    }
+
     fn scroll_to_top(&mut self, cx: &mut Context<Self>) {
         self.list_state.scroll_to(ListOffset::default());
         cx.notify();
@@ -7322,6 +7501,19 @@ impl AcpThreadView {
         })
     }
 
+    pub(crate) fn insert_mcp_resource(
+        &self,
+        server_id: ContextServerId,
+        uri: String,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.message_editor.update(cx, |message_editor, cx| {
+            message_editor.insert_mcp_resource(server_id, uri, name, window, cx);
+        })
+    }
+
     /// Inserts the selected text into the message editor or the message being
     /// edited, if any.
     pub(crate) fn insert_selections(&self, window: &mut Window, cx: &mut Context<Self>) {
@@ -8162,6 +8354,26 @@ fn plan_label_markdown_style(
     }
 }
 
+/// Turns a thread title into a file name, keeping it readable but free of path separators.
+fn export_file_stem(title: &str) -> String {
+    let stem = title
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let stem = stem.trim_matches(|c: char| c.is_whitespace() || c == '-');
+    if stem.is_empty() {
+        "thread".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
 #[cfg(test)]
 pub(crate) mod tests {
     use acp_thread::{
@@ -10510,4 +10722,14 @@ pub(crate) mod tests {
             "Missing deny pattern option"
         );
     }
+
+    #[test]
+    fn test_export_file_stem() {
+        assert_eq!(
+            export_file_stem("Fix ../crash in src/main.rs"),
+            "Fix ---crash in src-main-rs"
+        );
+        assert_eq!(export_file_stem("  Debug: tests  "), "Debug- tests");
+        assert_eq!(export_file_stem("???"), "thread");
+    }
 }
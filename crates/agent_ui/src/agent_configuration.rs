@@ -1,6 +1,7 @@
 mod add_llm_provider_modal;
 pub mod configure_context_server_modal;
 mod configure_context_server_tools_modal;
+mod context_server_resources_modal;
 mod manage_profiles_modal;
 mod tool_picker;
 
@@ -45,6 +46,7 @@ use zed_actions::{ExtensionCategoryFilter, OpenBrowser};
 
 pub(crate) use configure_context_server_modal::ConfigureContextServerModal;
 pub(crate) use configure_context_server_tools_modal::ConfigureContextServerToolsModal;
+pub(crate) use context_server_resources_modal::ContextServerResourcesModal;
 pub(crate) use manage_profiles_modal::ManageProfilesModal;
 
 use crate::agent_configuration::add_llm_provider_modal::{
@@ -668,6 +670,17 @@ impl AgentConfiguration {
             .read(cx)
             .tools_for_server(&context_server_id)
             .count();
+        let has_resources = {
+            let registry = self.context_server_registry.read(cx);
+            registry
+                .resources_for_server(&context_server_id)
+                .next()
+                .is_some()
+                || registry
+                    .resource_templates_for_server(&context_server_id)
+                    .next()
+                    .is_some()
+        };
 
         let (source_icon, source_tooltip) = if provided_by_extension {
             (
@@ -770,6 +783,24 @@ impl AgentConfiguration {
                                 .ok();
                             }
                         }))
+                        .when(has_resources, |this| this.entry("View Resources", None, {
+                            let context_server_id = context_server_id.clone();
+                            let context_server_registry = context_server_registry.clone();
+                            let workspace = workspace.clone();
+                            move |window, cx| {
+                                let context_server_id = context_server_id.clone();
+                                workspace.update(cx, |workspace, cx| {
+                                    ContextServerResourcesModal::toggle(
+                                        context_server_id,
+                                        context_server_registry.clone(),
+                                        workspace,
+                                        window,
+                                        cx,
+                                    );
+                                })
+                                .ok();
+                            }
+                        }))
                         .separator()
                         .entry("Uninstall", None, {
                             let fs = fs.clone();
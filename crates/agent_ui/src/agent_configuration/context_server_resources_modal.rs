@@ -0,0 +1,263 @@
+use agent::{ContextServerRegistry, ContextServerRegistryEvent};
+use context_server::ContextServerId;
+use gpui::{
+    DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollHandle, Subscription,
+    WeakEntity, Window, prelude::*,
+};
+use ui::{Divider, DividerColor, Modal, ModalHeader, Tooltip, WithScrollbar, prelude::*};
+use workspace::{ModalView, Workspace};
+
+use crate::AgentPanel;
+
+pub struct ContextServerResourcesModal {
+    context_server_id: ContextServerId,
+    context_server_registry: Entity<ContextServerRegistry>,
+    workspace: WeakEntity<Workspace>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    _registry_subscription: Subscription,
+}
+
+impl ContextServerResourcesModal {
+    fn new(
+        context_server_id: ContextServerId,
+        context_server_registry: Entity<ContextServerRegistry>,
+        workspace: WeakEntity<Workspace>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let registry_subscription =
+            cx.subscribe(&context_server_registry, |_, _, event, cx| match event {
+                ContextServerRegistryEvent::ResourcesChanged => cx.notify(),
+                ContextServerRegistryEvent::ToolsChanged
+                | ContextServerRegistryEvent::PromptsChanged
+                | ContextServerRegistryEvent::ResourceUpdated { .. } => {}
+            });
+
+        Self {
+            context_server_id,
+            context_server_registry,
+            workspace,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+            _registry_subscription: registry_subscription,
+        }
+    }
+
+    pub fn toggle(
+        context_server_id: ContextServerId,
+        context_server_registry: Entity<ContextServerRegistry>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let weak_workspace = cx.entity().downgrade();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(
+                context_server_id,
+                context_server_registry,
+                weak_workspace,
+                window,
+                cx,
+            )
+        });
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent)
+    }
+
+    fn mention_resource(
+        &mut self,
+        uri: String,
+        name: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let server_id = self.context_server_id.clone();
+        workspace.update(cx, |workspace, cx| {
+            let Some(panel) = workspace.focus_panel::<AgentPanel>(window, cx) else {
+                return;
+            };
+            if let Some(thread_view) = panel.read(cx).active_thread_view().cloned() {
+                thread_view.update(cx, |thread_view, cx| {
+                    thread_view.insert_mcp_resource(server_id, uri, name, window, cx);
+                });
+            }
+        });
+        cx.emit(DismissEvent);
+    }
+
+    fn render_entry(
+        &self,
+        index: usize,
+        title: SharedString,
+        uri: SharedString,
+        description: Option<SharedString>,
+        is_template: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        h_flex()
+            .id(("resource", index))
+            .py_1()
+            .pl_1()
+            .pr_2()
+            .w_full()
+            .gap_2()
+            .justify_between()
+            .rounded_sm()
+            .hover(|s| s.bg(cx.theme().colors().element_hover))
+            .child(
+                v_flex()
+                    .min_w_0()
+                    .child(Label::new(title.clone()).size(LabelSize::Small))
+                    .child(
+                        Label::new(uri.clone())
+                            .buffer_font(cx)
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted)
+                            .truncate(),
+                    )
+                    .when_some(description, |this, description| {
+                        this.child(
+                            Label::new(description)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+            .map(|this| {
+                if is_template {
+                    this.child(
+                        Label::new("Template")
+                            .size(LabelSize::XSmall)
+                            .color(Color::Muted),
+                    )
+                } else {
+                    this.child(
+                        IconButton::new(("mention-resource", index), IconName::Plus)
+                            .icon_size(IconSize::Small)
+                            .tooltip(Tooltip::text("Mention in Thread"))
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.mention_resource(
+                                    uri.to_string(),
+                                    title.to_string(),
+                                    window,
+                                    cx,
+                                );
+                            })),
+                    )
+                }
+            })
+    }
+
+    fn render_modal_content(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let registry = self.context_server_registry.read(cx);
+        let mut entries = registry
+            .resources_for_server(&self.context_server_id)
+            .map(|resource| {
+                let resource = &resource.resource;
+                (
+                    SharedString::from(resource.name.clone()),
+                    SharedString::from(resource.uri.to_string()),
+                    resource.description.clone().map(SharedString::from),
+                    false,
+                )
+            })
+            .collect::<Vec<_>>();
+        entries.extend(
+            registry
+                .resource_templates_for_server(&self.context_server_id)
+                .map(|template| {
+                    let template = &template.template;
+                    (
+                        SharedString::from(template.name.clone()),
+                        SharedString::from(template.uri_template.clone()),
+                        template.description.clone().map(SharedString::from),
+                        true,
+                    )
+                }),
+        );
+        let entry_count = entries.len();
+
+        div()
+            .size_full()
+            .pb_2()
+            .child(
+                v_flex()
+                    .id("modal_content")
+                    .px_2()
+                    .gap_1()
+                    .max_h_128()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .when(entry_count == 0, |this| {
+                        this.child(
+                            Label::new("This server doesn't provide any resources.")
+                                .color(Color::Muted)
+                                .mx_1(),
+                        )
+                    })
+                    .children(entries.into_iter().enumerate().flat_map(
+                        |(index, (title, uri, description, is_template))| {
+                            let mut items = vec![
+                                self.render_entry(index, title, uri, description, is_template, cx)
+                                    .into_any_element(),
+                            ];
+                            if index < entry_count - 1 {
+                                items.push(
+                                    h_flex()
+                                        .w_full()
+                                        .child(
+                                            Divider::horizontal()
+                                                .color(DividerColor::BorderVariant),
+                                        )
+                                        .into_any_element(),
+                                );
+                            }
+                            items
+                        },
+                    )),
+            )
+            .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+            .into_any_element()
+    }
+}
+
+impl ModalView for ContextServerResourcesModal {}
+
+impl Focusable for ContextServerResourcesModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for ContextServerResourcesModal {}
+
+impl Render for ContextServerResourcesModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .key_context("ContextServerResourcesModal")
+            .occlude()
+            .elevation_3(cx)
+            .w(rems(34.))
+            .on_action(cx.listener(Self::cancel))
+            .track_focus(&self.focus_handle)
+            .child(
+                Modal::new("context-server-resources", None::<ScrollHandle>)
+                    .header(
+                        ModalHeader::new()
+                            .headline(format!("Resources from {}", self.context_server_id.0))
+                            .show_dismiss_button(true),
+                    )
+                    .child(self.render_modal_content(window, cx)),
+            )
+    }
+}
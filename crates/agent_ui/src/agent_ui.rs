@@ -9,6 +9,7 @@ mod buffer_codegen;
 mod completion_provider;
 mod context;
 mod context_server_configuration;
+mod context_server_requests;
 mod favorite_models;
 mod inline_assistant;
 mod inline_prompt_editor;
@@ -98,6 +99,12 @@ actions!(
         FocusRight,
         /// Opens the active thread as a markdown file.
         OpenActiveThreadAsMarkdown,
+        /// Exports the active thread to a Markdown file.
+        ExportThreadAsMarkdown,
+        /// Exports the active thread to a JSON file that can be imported again.
+        ExportThreadAsJson,
+        /// Imports a thread from a JSON file exported by Zed.
+        ImportThread,
         /// Opens the agent diff view to review changes.
         OpenAgentDiff,
         /// Keeps the current suggestion or change.
@@ -262,6 +269,7 @@ pub fn init(
     agent_panel::init(cx);
     agent_chat_view::register_serializable_item(cx);
     context_server_configuration::init(language_registry.clone(), fs.clone(), cx);
+    context_server_requests::init(cx);
     TextThreadEditor::init(cx);
 
     register_slash_commands(cx);
@@ -536,6 +544,8 @@ mod tests {
             message_editor_min_lines: 1,
             tool_permissions: Default::default(),
             show_turn_stats: false,
+            hooks: Default::default(),
+            terminal_sandbox: Default::default(),
         };
 
         cx.update(|cx| {
@@ -6,7 +6,10 @@ use std::sync::atomic::AtomicBool;
 
 use crate::acp::AcpThreadHistory;
 use acp_thread::{AgentSessionInfo, MentionUri};
+use agent::ContextServerRegistry;
 use anyhow::Result;
+use context_server::ContextServerId;
+use context_server::uri_template::UriTemplate;
 use editor::{
     CompletionProvider, Editor, ExcerptId, code_context_menus::COMPLETION_MENU_MAX_WIDTH,
 };
@@ -55,6 +58,7 @@ pub(crate) enum PromptContextType {
     Fetch,
     Thread,
     Rules,
+    McpResource,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -92,6 +96,7 @@ impl TryFrom<&str> for PromptContextType {
             "fetch" => Ok(Self::Fetch),
             "thread" => Ok(Self::Thread),
             "rule" => Ok(Self::Rules),
+            "resource" => Ok(Self::McpResource),
             _ => Err(format!("Invalid context picker mode: {}", value)),
         }
     }
@@ -105,6 +110,7 @@ impl PromptContextType {
             Self::Fetch => "fetch",
             Self::Thread => "thread",
             Self::Rules => "rule",
+            Self::McpResource => "resource",
         }
     }
 
@@ -115,6 +121,7 @@ impl PromptContextType {
             Self::Fetch => "Fetch",
             Self::Thread => "Threads",
             Self::Rules => "Rules",
+            Self::McpResource => "MCP Resources",
         }
     }
 
@@ -125,6 +132,7 @@ impl PromptContextType {
             Self::Fetch => IconName::ToolWeb,
             Self::Thread => IconName::Thread,
             Self::Rules => IconName::Reader,
+            Self::McpResource => IconName::DatabaseZap,
         }
     }
 }
@@ -136,6 +144,8 @@ pub(crate) enum Match {
     RecentThread(AgentSessionInfo),
     Fetch(SharedString),
     Rules(RulesContextEntry),
+    McpResource(McpResourceMatch),
+    McpResourceTemplate(McpResourceTemplateMatch),
     Entry(EntryMatch),
 }
 
@@ -149,6 +159,8 @@ impl Match {
             Match::Symbol(_) => 1.,
             Match::Rules(_) => 1.,
             Match::Fetch(_) => 1.,
+            Match::McpResource(_) => 1.,
+            Match::McpResourceTemplate(_) => 1.,
         }
     }
 }
@@ -172,6 +184,26 @@ pub struct RulesContextEntry {
     pub title: SharedString,
 }
 
+#[derive(Debug, Clone)]
+pub struct McpResourceMatch {
+    pub server_id: ContextServerId,
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+/// A step towards mentioning a resource of an MCP resource template.
+#[derive(Debug, Clone)]
+pub struct McpResourceTemplateMatch {
+    pub server_id: ContextServerId,
+    pub label: String,
+    pub description: Option<String>,
+    /// The resource URI entered so far, with the values of some of the template's variables.
+    pub uri: String,
+    /// Whether `uri` provides values for all of the template's variables.
+    pub is_complete: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct AvailableCommand {
     pub name: Arc<str>,
@@ -511,6 +543,96 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
         })
     }
 
+    fn completion_for_mcp_resource(
+        resource: McpResourceMatch,
+        source_range: Range<Anchor>,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Completion {
+        let uri = MentionUri::McpResource {
+            server_id: resource.server_id.0.to_string(),
+            uri: resource.uri,
+            name: resource.name.clone(),
+        };
+        let new_text = format!("{} ", uri.as_link());
+        let new_text_len = new_text.len();
+        let icon_path = uri.icon_path(cx);
+        Completion {
+            replace_range: source_range.clone(),
+            new_text,
+            label: CodeLabel::plain(resource.name.clone(), None),
+            documentation: resource
+                .description
+                .map(|description| CompletionDocumentation::MultiLinePlainText(description.into())),
+            insert_text_mode: None,
+            source: project::CompletionSource::Custom,
+            match_start: None,
+            snippet_deduplication_key: None,
+            icon_path: Some(icon_path),
+            confirm: Some(confirm_completion_callback(
+                resource.name.into(),
+                source_range.start,
+                new_text_len - 1,
+                uri,
+                source,
+                editor,
+                mention_set,
+                workspace,
+            )),
+        }
+    }
+
+    fn completion_for_mcp_resource_template(
+        template: McpResourceTemplateMatch,
+        source_range: Range<Anchor>,
+        source: Arc<T>,
+        editor: WeakEntity<Editor>,
+        mention_set: WeakEntity<MentionSet>,
+        workspace: Entity<Workspace>,
+        cx: &mut App,
+    ) -> Completion {
+        if template.is_complete {
+            return Self::completion_for_mcp_resource(
+                McpResourceMatch {
+                    server_id: template.server_id,
+                    name: template.uri.clone(),
+                    uri: template.uri,
+                    description: template.description,
+                },
+                source_range,
+                source,
+                editor,
+                mention_set,
+                workspace,
+                cx,
+            );
+        }
+
+        Completion {
+            replace_range: source_range,
+            new_text: format!(
+                "@{} {}",
+                PromptContextType::McpResource.keyword(),
+                template.uri
+            ),
+            label: CodeLabel::plain(template.label, None),
+            documentation: template
+                .description
+                .map(|description| CompletionDocumentation::MultiLinePlainText(description.into())),
+            insert_text_mode: None,
+            source: project::CompletionSource::Custom,
+            match_start: None,
+            snippet_deduplication_key: None,
+            icon_path: Some(PromptContextType::McpResource.icon().path().into()),
+            // Keep the completion menu open, so that the values of the
+            // template's remaining variables can be completed.
+            confirm: Some(Arc::new(|_, _, _| true)),
+        }
+    }
+
     pub(crate) fn completion_for_action(
         action: PromptContextAction,
         source_range: Range<Anchor>,
@@ -684,6 +806,14 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
                 }
             }
 
+            Some(PromptContextType::McpResource) => {
+                if let Some(registry) = context_server_registry(&workspace, cx) {
+                    search_mcp_resources(query, cancellation_flag, &registry, cx)
+                } else {
+                    Task::ready(Vec::new())
+                }
+            }
+
             None if query.is_empty() => {
                 let recent_task = self.recent_context_picker_entries(&workspace, cx);
                 let entries = self
@@ -879,6 +1009,18 @@ impl<T: PromptCompletionProviderDelegate> PromptCompletionProvider<T> {
             entries.push(PromptContextEntry::Mode(PromptContextType::Fetch));
         }
 
+        if self
+            .source
+            .supports_context(PromptContextType::McpResource, cx)
+            && context_server_registry(workspace, cx).is_some_and(|registry| {
+                let registry = registry.read(cx);
+                registry.resources().next().is_some()
+                    || registry.resource_templates().next().is_some()
+            })
+        {
+            entries.push(PromptContextEntry::Mode(PromptContextType::McpResource));
+        }
+
         entries
     }
 }
@@ -1107,6 +1249,30 @@ impl<T: PromptCompletionProviderDelegate> CompletionProvider for PromptCompletio
                                     cx,
                                 ),
 
+                                Match::McpResource(resource) => {
+                                    Some(Self::completion_for_mcp_resource(
+                                        resource,
+                                        source_range.clone(),
+                                        source.clone(),
+                                        editor.clone(),
+                                        mention_set.clone(),
+                                        workspace.clone(),
+                                        cx,
+                                    ))
+                                }
+
+                                Match::McpResourceTemplate(template) => {
+                                    Some(Self::completion_for_mcp_resource_template(
+                                        template,
+                                        source_range.clone(),
+                                        source.clone(),
+                                        editor.clone(),
+                                        mention_set.clone(),
+                                        workspace.clone(),
+                                        cx,
+                                    ))
+                                }
+
                                 Match::Entry(EntryMatch { entry, .. }) => {
                                     Self::completion_for_entry(
                                         entry,
@@ -1631,6 +1797,158 @@ pub(crate) fn search_rules(
     })
 }
 
+fn context_server_registry(
+    workspace: &Entity<Workspace>,
+    cx: &App,
+) -> Option<Entity<ContextServerRegistry>> {
+    let panel = workspace.read(cx).panel::<AgentPanel>(cx)?;
+    Some(panel.read(cx).context_server_registry().clone())
+}
+
+/// Searches the resources of the running MCP servers by name and URI.
+///
+/// When the query is a partially entered URI of a resource template, the values of the
+/// variable being entered are completed by the server instead.
+fn search_mcp_resources(
+    query: String,
+    cancellation_flag: Arc<AtomicBool>,
+    registry: &Entity<ContextServerRegistry>,
+    cx: &mut App,
+) -> Task<Vec<Match>> {
+    let registry = registry.read(cx);
+    let server_store = registry.server_store().clone();
+    let resources = registry
+        .resources()
+        .map(|resource| McpResourceMatch {
+            server_id: resource.server_id.clone(),
+            uri: resource.resource.uri.to_string(),
+            name: resource.resource.name.clone(),
+            description: resource.resource.description.clone(),
+        })
+        .collect::<Vec<_>>();
+    let resource_templates = registry
+        .resource_templates()
+        .map(|template| (template.server_id.clone(), template.template.clone()))
+        .collect::<Vec<_>>();
+
+    let mut template_matches = Vec::new();
+    let mut argument_completions = Vec::new();
+    for (server_id, template) in &resource_templates {
+        let server_id = server_id.clone();
+        let uri_template = UriTemplate::parse(&template.uri_template);
+
+        let Some(template_match) = uri_template.match_partial(&query) else {
+            if uri_template.prefix().starts_with(query.as_str())
+                || template.name.to_lowercase().contains(&query.to_lowercase())
+            {
+                let is_complete = uri_template.variables().next().is_none();
+                template_matches.push(McpResourceTemplateMatch {
+                    server_id,
+                    label: template.name.clone(),
+                    description: Some(template.uri_template.clone()),
+                    uri: uri_template.prefix().to_string(),
+                    is_complete,
+                });
+            }
+            continue;
+        };
+
+        let Some(pending) = template_match.pending else {
+            template_matches.push(McpResourceTemplateMatch {
+                server_id,
+                label: query.clone(),
+                description: template.description.clone(),
+                uri: query.clone(),
+                is_complete: true,
+            });
+            continue;
+        };
+
+        // Allow mentioning values the server doesn't suggest.
+        if !pending.value.is_empty() {
+            let (uri, is_complete) = uri_template.complete_pending(&query, &pending, pending.value);
+            template_matches.push(McpResourceTemplateMatch {
+                server_id: server_id.clone(),
+                label: uri.clone(),
+                description: template.description.clone(),
+                uri,
+                is_complete,
+            });
+        }
+
+        let values = agent::complete_resource_template_argument(
+            &server_store,
+            &server_id,
+            &template.uri_template,
+            pending.name,
+            pending.value,
+            cx,
+        );
+        argument_completions.push((
+            server_id,
+            uri_template.clone(),
+            template.description.clone(),
+            values,
+        ));
+    }
+
+    let executor = cx.background_executor().clone();
+    cx.background_spawn(async move {
+        let mut matches = if query.is_empty() {
+            resources.into_iter().map(Match::McpResource).collect()
+        } else {
+            let candidates = resources
+                .iter()
+                .enumerate()
+                .map(|(id, resource)| {
+                    StringMatchCandidate::new(id, &format!("{} {}", resource.name, resource.uri))
+                })
+                .collect::<Vec<_>>();
+            fuzzy::match_strings(
+                &candidates,
+                &query,
+                false,
+                true,
+                100,
+                &cancellation_flag,
+                executor,
+            )
+            .await
+            .into_iter()
+            .map(|mat| Match::McpResource(resources[mat.candidate_id].clone()))
+            .collect::<Vec<_>>()
+        };
+
+        for (server_id, uri_template, description, values) in argument_completions {
+            let Some(values) = values.await.log_err() else {
+                continue;
+            };
+            let Some(pending) = uri_template
+                .match_partial(&query)
+                .and_then(|template_match| template_match.pending)
+            else {
+                continue;
+            };
+            for value in values {
+                if value == pending.value {
+                    continue;
+                }
+                let (uri, is_complete) = uri_template.complete_pending(&query, &pending, &value);
+                template_matches.push(McpResourceTemplateMatch {
+                    server_id: server_id.clone(),
+                    label: uri.clone(),
+                    description: description.clone(),
+                    uri,
+                    is_complete,
+                });
+            }
+        }
+
+        matches.extend(template_matches.into_iter().map(Match::McpResourceTemplate));
+        matches
+    })
+}
+
 pub struct SymbolMatch {
     pub symbol: Symbol,
 }
@@ -0,0 +1,264 @@
+//! Handles the requests MCP servers send to Zed.
+//!
+//! Sampling requests are completed with the user's configured language model once the user has
+//! reviewed and approved them. Elicitation requests are shown as a form generated from the schema
+//! the server requested.
+
+mod elicitation_modal;
+mod sampling_modal;
+
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use context_server::{
+    ContextServer, ContextServerId,
+    client::{RequestRejected, RequestSubscription},
+    types::{
+        CreateMessageRequest, CreateMessageResult, ElicitAction, ElicitParams, ElicitResult,
+        MessageContent, ModelPreferences, Role, requests,
+    },
+};
+use futures::{StreamExt as _, channel::oneshot};
+use gpui::{AnyWindowHandle, App, AsyncApp, Task, WeakEntity, Window};
+use language_model::{
+    LanguageModel, LanguageModelImage, LanguageModelRegistry, LanguageModelRequest,
+    LanguageModelRequestMessage,
+};
+use project::context_server_store::{self, ContextServerStatus};
+use workspace::{ModalView, Workspace};
+
+use elicitation_modal::{ElicitationModal, parse_requested_schema};
+use sampling_modal::SamplingModal;
+
+/// Rough number of bytes per token, used to enforce `maxTokens` for providers that only
+/// report token usage once the response is complete.
+const BYTES_PER_TOKEN_ESTIMATE: usize = 4;
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        let window_handle = window.window_handle();
+        let workspace_handle = cx.weak_entity();
+        let context_server_store = workspace.project().read(cx).context_server_store();
+
+        let mut subscriptions = HashMap::default();
+        for server in context_server_store.read(cx).running_servers() {
+            if let Some(server_subscriptions) =
+                register_request_handlers(&server, workspace_handle.clone(), window_handle)
+            {
+                subscriptions.insert(server.id(), server_subscriptions);
+            }
+        }
+
+        cx.subscribe(
+            &context_server_store,
+            move |_, context_server_store, event, cx| {
+                let context_server_store::Event::ServerStatusChanged { server_id, status } = event;
+                subscriptions.remove(server_id);
+                if *status == ContextServerStatus::Running
+                    && let Some(server) =
+                        context_server_store.read(cx).get_running_server(server_id)
+                    && let Some(server_subscriptions) =
+                        register_request_handlers(&server, workspace_handle.clone(), window_handle)
+                {
+                    subscriptions.insert(server_id.clone(), server_subscriptions);
+                }
+            },
+        )
+        .detach();
+    })
+    .detach();
+}
+
+fn register_request_handlers(
+    server: &Arc<ContextServer>,
+    workspace: WeakEntity<Workspace>,
+    window: AnyWindowHandle,
+) -> Option<Vec<RequestSubscription>> {
+    let client = server.client()?;
+    let server_id = server.id();
+
+    let sampling_subscription = client.on_request::<requests::CreateMessage>({
+        let server_id = server_id.clone();
+        let workspace = workspace.clone();
+        move |params, cx| {
+            handle_sampling_request(server_id.clone(), params, workspace.clone(), window, cx)
+        }
+    });
+    let elicitation_subscription = client.on_request::<requests::Elicit>(move |params, cx| {
+        handle_elicitation_request(server_id.clone(), params, workspace.clone(), window, cx)
+    });
+
+    Some(vec![sampling_subscription, elicitation_subscription])
+}
+
+fn handle_sampling_request(
+    server_id: ContextServerId,
+    params: CreateMessageRequest,
+    workspace: WeakEntity<Workspace>,
+    window: AnyWindowHandle,
+    cx: AsyncApp,
+) -> Task<Result<CreateMessageResult>> {
+    cx.spawn(async move |cx| {
+        let model = cx
+            .update(|cx| sampling_model(params.model_preferences.as_ref(), cx))
+            .context("No language model is configured")?;
+        let params = Arc::new(params);
+
+        let (approval_tx, approval_rx) = oneshot::channel();
+        show_modal(&workspace, window, cx, {
+            let params = params.clone();
+            let model_name = model.name().0;
+            move |_window, cx| SamplingModal::new(server_id, params, model_name, approval_tx, cx)
+        })?;
+        if !approval_rx.await.unwrap_or(false) {
+            return Err(RequestRejected.into());
+        }
+
+        let request = sampling_completion_request(&params);
+        let mut response = model.stream_completion_text(request, cx).await?;
+        let max_tokens = u64::from(params.max_tokens);
+        let mut text = String::new();
+        let mut stop_reason = "endTurn";
+        while let Some(chunk) = response.stream.next().await {
+            text.push_str(&chunk?);
+            let output_tokens = response
+                .last_token_usage
+                .lock()
+                .output_tokens
+                .max((text.len() / BYTES_PER_TOKEN_ESTIMATE) as u64);
+            if output_tokens >= max_tokens {
+                stop_reason = "maxTokens";
+                break;
+            }
+        }
+
+        Ok(CreateMessageResult {
+            role: Role::Assistant,
+            content: MessageContent::Text {
+                text,
+                annotations: None,
+            },
+            model: model.id().0.to_string(),
+            stop_reason: Some(stop_reason.to_string()),
+        })
+    })
+}
+
+fn handle_elicitation_request(
+    server_id: ContextServerId,
+    params: ElicitParams,
+    workspace: WeakEntity<Workspace>,
+    window: AnyWindowHandle,
+    cx: AsyncApp,
+) -> Task<Result<ElicitResult>> {
+    cx.spawn(async move |cx| {
+        let fields = parse_requested_schema(&params.requested_schema)?;
+
+        let (result_tx, result_rx) = oneshot::channel();
+        show_modal(&workspace, window, cx, move |window, cx| {
+            ElicitationModal::new(server_id, params.message, fields, result_tx, window, cx)
+        })?;
+
+        Ok(result_rx.await.unwrap_or(ElicitResult {
+            action: ElicitAction::Cancel,
+            content: None,
+        }))
+    })
+}
+
+/// Shows a modal for a server request, unless one of the same kind is already waiting for the
+/// user, in which case the request fails.
+fn show_modal<V: ModalView>(
+    workspace: &WeakEntity<Workspace>,
+    window: AnyWindowHandle,
+    cx: &mut AsyncApp,
+    build: impl FnOnce(&mut Window, &mut gpui::Context<V>) -> V,
+) -> Result<()> {
+    window.update(cx, |_, window, cx| {
+        workspace.update(cx, |workspace, cx| {
+            anyhow::ensure!(
+                workspace.active_modal::<V>(cx).is_none(),
+                "Another request from an MCP server is waiting for the user"
+            );
+            workspace.toggle_modal(window, cx, build);
+            Ok(())
+        })
+    })??
+}
+
+/// Picks the first configured model matching the server's hints, falling back to the default
+/// model. As in the MCP spec, a hint matches any model whose name contains it.
+fn sampling_model(
+    preferences: Option<&ModelPreferences>,
+    cx: &App,
+) -> Option<Arc<dyn LanguageModel>> {
+    let registry = LanguageModelRegistry::read_global(cx);
+    let hints = preferences
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref());
+    for hint in hints {
+        let hint = hint.to_lowercase();
+        if let Some(model) = registry.available_models(cx).find(|model| {
+            model.name().0.to_lowercase().contains(&hint)
+                || model.id().0.to_lowercase().contains(&hint)
+        }) {
+            return Some(model);
+        }
+    }
+    registry.default_model().map(|configured| configured.model)
+}
+
+fn sampling_completion_request(params: &CreateMessageRequest) -> LanguageModelRequest {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = &params.system_prompt {
+        messages.push(LanguageModelRequestMessage {
+            role: language_model::Role::System,
+            content: vec![system_prompt.clone().into()],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+    for message in &params.messages {
+        let role = match message.role {
+            Role::User => language_model::Role::User,
+            Role::Assistant => language_model::Role::Assistant,
+        };
+        let content = match &message.content {
+            MessageContent::Text { text, .. } => text.clone().into(),
+            // Language models only accept PNG images.
+            MessageContent::Image {
+                data, mime_type, ..
+            } if mime_type == "image/png" => {
+                language_model::MessageContent::Image(LanguageModelImage {
+                    source: data.clone().into(),
+                    size: None,
+                })
+            }
+            MessageContent::Image { mime_type, .. } | MessageContent::Audio { mime_type, .. } => {
+                format!("[{mime_type} content omitted]").into()
+            }
+            MessageContent::Resource { resource, .. } => {
+                format!("[resource {} omitted]", resource.uri).into()
+            }
+        };
+        messages.push(LanguageModelRequestMessage {
+            role,
+            content: vec![content],
+            cache: false,
+            reasoning_details: None,
+        });
+    }
+
+    LanguageModelRequest {
+        messages,
+        stop: params.stop_sequences.clone().unwrap_or_default(),
+        temperature: params.temperature.map(|temperature| temperature as f32),
+        ..Default::default()
+    }
+}
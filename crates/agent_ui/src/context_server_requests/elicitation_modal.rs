@@ -0,0 +1,679 @@
+use anyhow::{Context as _, Result, anyhow};
+use context_server::{
+    ContextServerId,
+    types::{ElicitAction, ElicitResult},
+};
+use futures::channel::oneshot;
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ScrollHandle, prelude::*};
+use serde_json::{Map, Value};
+use ui::{
+    Banner, Checkbox, KeyBinding, Modal, ModalFooter, ModalHeader, Section, WithScrollbar,
+    prelude::*,
+};
+use ui_input::InputField;
+use workspace::ModalView;
+
+/// A field of the form requested by an MCP server.
+///
+/// Elicitation schemas are restricted to flat objects whose properties have primitive types.
+#[derive(Debug, PartialEq)]
+pub(super) struct FieldSchema {
+    name: String,
+    title: Option<String>,
+    description: Option<String>,
+    required: bool,
+    kind: FieldKind,
+}
+
+#[derive(Debug, PartialEq)]
+enum FieldKind {
+    String {
+        format: Option<String>,
+        min_length: Option<usize>,
+        max_length: Option<usize>,
+        default: Option<String>,
+    },
+    Number {
+        integer: bool,
+        minimum: Option<f64>,
+        maximum: Option<f64>,
+        default: Option<f64>,
+    },
+    Boolean {
+        default: bool,
+    },
+    Enum {
+        values: Vec<String>,
+        labels: Vec<String>,
+        default: Option<String>,
+    },
+}
+
+pub(super) fn parse_requested_schema(schema: &Value) -> Result<Vec<FieldSchema>> {
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|required| {
+            required
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .context("requested schema has no properties")?;
+
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let string = |key: &str| {
+                property
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+            };
+            let number = |key: &str| property.get(key).and_then(Value::as_f64);
+            let length = |key: &str| {
+                property
+                    .get(key)
+                    .and_then(Value::as_u64)
+                    .map(|length| length as usize)
+            };
+
+            let kind = if let Some(values) = property.get("enum").and_then(Value::as_array) {
+                let values = values
+                    .iter()
+                    .map(|value| value.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+                    .with_context(|| format!("enum values of {name:?} must be strings"))?;
+                let labels = property
+                    .get("enumNames")
+                    .and_then(Value::as_array)
+                    .map(|labels| {
+                        labels
+                            .iter()
+                            .filter_map(|label| label.as_str().map(str::to_string))
+                            .collect::<Vec<_>>()
+                    })
+                    .filter(|labels| labels.len() == values.len())
+                    .unwrap_or_else(|| values.clone());
+                FieldKind::Enum {
+                    values,
+                    labels,
+                    default: string("default"),
+                }
+            } else {
+                match property.get("type").and_then(Value::as_str) {
+                    Some("string") => FieldKind::String {
+                        format: string("format"),
+                        min_length: length("minLength"),
+                        max_length: length("maxLength"),
+                        default: string("default"),
+                    },
+                    Some(ty @ ("number" | "integer")) => FieldKind::Number {
+                        integer: ty == "integer",
+                        minimum: number("minimum"),
+                        maximum: number("maximum"),
+                        default: number("default"),
+                    },
+                    Some("boolean") => FieldKind::Boolean {
+                        default: property
+                            .get("default")
+                            .and_then(Value::as_bool)
+                            .unwrap_or(false),
+                    },
+                    ty => anyhow::bail!("unsupported type {ty:?} for property {name:?}"),
+                }
+            };
+
+            Ok(FieldSchema {
+                name: name.clone(),
+                title: string("title"),
+                description: string("description"),
+                required: required.contains(&name.as_str()),
+                kind,
+            })
+        })
+        .collect()
+}
+
+impl FieldSchema {
+    fn label(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Converts the text entered for a string or number field into its value.
+    fn value_from_text(&self, text: &str) -> Result<Option<Value>> {
+        let label = self.label();
+        if text.is_empty() {
+            anyhow::ensure!(!self.required, "{label} is required");
+            return Ok(None);
+        }
+
+        match &self.kind {
+            FieldKind::String {
+                format,
+                min_length,
+                max_length,
+                ..
+            } => {
+                let length = text.chars().count();
+                if let Some(min_length) = min_length {
+                    anyhow::ensure!(
+                        length >= *min_length,
+                        "{label} must be at least {min_length} characters long"
+                    );
+                }
+                if let Some(max_length) = max_length {
+                    anyhow::ensure!(
+                        length <= *max_length,
+                        "{label} must be at most {max_length} characters long"
+                    );
+                }
+                match format.as_deref() {
+                    Some("email") => anyhow::ensure!(
+                        text.split_once('@')
+                            .is_some_and(|(user, domain)| !user.is_empty() && !domain.is_empty()),
+                        "{label} must be an email address"
+                    ),
+                    Some("uri") => {
+                        url::Url::parse(text).map_err(|_| anyhow!("{label} must be a URI"))?;
+                    }
+                    _ => {}
+                }
+                Ok(Some(Value::String(text.to_string())))
+            }
+            FieldKind::Number {
+                integer,
+                minimum,
+                maximum,
+                ..
+            } => {
+                let value = if *integer {
+                    text.trim()
+                        .parse::<i64>()
+                        .map(Value::from)
+                        .map_err(|_| anyhow!("{label} must be a whole number"))?
+                } else {
+                    text.trim()
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|number| serde_json::Number::from_f64(number).map(Value::Number))
+                        .with_context(|| format!("{label} must be a number"))?
+                };
+                let number = value.as_f64().unwrap_or_default();
+                if let Some(minimum) = minimum {
+                    anyhow::ensure!(number >= *minimum, "{label} must be at least {minimum}");
+                }
+                if let Some(maximum) = maximum {
+                    anyhow::ensure!(number <= *maximum, "{label} must be at most {maximum}");
+                }
+                Ok(Some(value))
+            }
+            FieldKind::Boolean { .. } | FieldKind::Enum { .. } => {
+                anyhow::bail!("{label} is not a text field")
+            }
+        }
+    }
+}
+
+enum FieldInput {
+    Text(Entity<InputField>),
+    Boolean(bool),
+    Enum(Option<usize>),
+}
+
+/// Asks the user for the information an MCP server requested.
+pub(super) struct ElicitationModal {
+    server_id: ContextServerId,
+    message: SharedString,
+    fields: Vec<(FieldSchema, FieldInput)>,
+    last_error: Option<SharedString>,
+    result_tx: Option<oneshot::Sender<ElicitResult>>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+}
+
+impl ElicitationModal {
+    pub(super) fn new(
+        server_id: ContextServerId,
+        message: String,
+        fields: Vec<FieldSchema>,
+        result_tx: oneshot::Sender<ElicitResult>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let fields = fields
+            .into_iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let input = match &field.kind {
+                    FieldKind::Boolean { default } => FieldInput::Boolean(*default),
+                    FieldKind::Enum {
+                        values, default, ..
+                    } => FieldInput::Enum(
+                        default
+                            .as_ref()
+                            .and_then(|default| values.iter().position(|value| value == default)),
+                    ),
+                    FieldKind::String { default, .. } => {
+                        let default = default.clone();
+                        FieldInput::Text(text_input(&field, default, index, window, cx))
+                    }
+                    FieldKind::Number { default, .. } => {
+                        let default = default.map(|default| default.to_string());
+                        FieldInput::Text(text_input(&field, default, index, window, cx))
+                    }
+                };
+                (field, input)
+            })
+            .collect();
+
+        Self {
+            server_id,
+            message: message.into(),
+            fields,
+            last_error: None,
+            result_tx: Some(result_tx),
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    fn respond(&mut self, result: ElicitResult, cx: &mut Context<Self>) {
+        if let Some(result_tx) = self.result_tx.take() {
+            result_tx.send(result).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn content(&self, cx: &App) -> Result<Map<String, Value>> {
+        let mut content = Map::new();
+        for (field, input) in &self.fields {
+            let value = match input {
+                FieldInput::Text(input) => field.value_from_text(&input.read(cx).text(cx))?,
+                FieldInput::Boolean(value) => Some(Value::Bool(*value)),
+                FieldInput::Enum(selection) => {
+                    let FieldKind::Enum { values, .. } = &field.kind else {
+                        continue;
+                    };
+                    let value = selection.and_then(|ix| values.get(ix)).cloned();
+                    anyhow::ensure!(
+                        value.is_some() || !field.required,
+                        "{} is required",
+                        field.label()
+                    );
+                    value.map(Value::String)
+                }
+            };
+            if let Some(value) = value {
+                content.insert(field.name.clone(), value);
+            }
+        }
+        Ok(content)
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        match self.content(cx) {
+            Ok(content) => self.respond(
+                ElicitResult {
+                    action: ElicitAction::Accept,
+                    content: Some(content),
+                },
+                cx,
+            ),
+            Err(error) => {
+                self.last_error = Some(error.to_string().into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn decline(&mut self, cx: &mut Context<Self>) {
+        self.respond(
+            ElicitResult {
+                action: ElicitAction::Decline,
+                content: None,
+            },
+            cx,
+        );
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        self.respond(
+            ElicitResult {
+                action: ElicitAction::Cancel,
+                content: None,
+            },
+            cx,
+        );
+    }
+
+    fn render_field(
+        &self,
+        index: usize,
+        field: &FieldSchema,
+        input: &FieldInput,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let description = field.description.clone().map(|description| {
+            Label::new(description)
+                .size(LabelSize::Small)
+                .color(Color::Muted)
+        });
+
+        match input {
+            FieldInput::Text(input) => v_flex()
+                .gap_1()
+                .child(input.clone())
+                .children(description)
+                .into_any_element(),
+            FieldInput::Boolean(value) => v_flex()
+                .gap_1()
+                .child(
+                    Checkbox::new(("elicitation-field", index), (*value).into())
+                        .label(field.label().to_string())
+                        .on_click(cx.listener(move |this, checked: &ToggleState, _, cx| {
+                            if let Some((_, FieldInput::Boolean(value))) =
+                                this.fields.get_mut(index)
+                            {
+                                *value = checked.selected();
+                            }
+                            cx.notify();
+                        })),
+                )
+                .children(description)
+                .into_any_element(),
+            FieldInput::Enum(selection) => {
+                let FieldKind::Enum { labels, .. } = &field.kind else {
+                    return div().into_any_element();
+                };
+                v_flex()
+                    .gap_1()
+                    .child(Label::new(field.label().to_string()).size(LabelSize::Small))
+                    .child(
+                        h_flex()
+                            .flex_wrap()
+                            .gap_1()
+                            .children(labels.iter().enumerate().map(|(option_ix, label)| {
+                                Button::new(
+                                    SharedString::from(format!(
+                                        "elicitation-option-{index}-{option_ix}"
+                                    )),
+                                    label.clone(),
+                                )
+                                .style(ButtonStyle::Outlined)
+                                .toggle_state(*selection == Some(option_ix))
+                                .on_click(cx.listener(
+                                    move |this, _, _, cx| {
+                                        if let Some((_, FieldInput::Enum(selection))) =
+                                            this.fields.get_mut(index)
+                                        {
+                                            *selection = Some(option_ix);
+                                        }
+                                        cx.notify();
+                                    },
+                                ))
+                            })),
+                    )
+                    .children(description)
+                    .into_any_element()
+            }
+        }
+    }
+}
+
+fn text_input(
+    field: &FieldSchema,
+    default: Option<String>,
+    index: usize,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<InputField> {
+    let label = if field.required {
+        format!("{} (required)", field.label())
+    } else {
+        field.label().to_string()
+    };
+    let placeholder = match &field.kind {
+        FieldKind::String {
+            format: Some(format),
+            ..
+        } => format.clone(),
+        _ => String::new(),
+    };
+
+    cx.new(|cx| {
+        let input = InputField::new(window, cx, placeholder)
+            .label(label)
+            .tab_index(index as isize)
+            .tab_stop(true);
+        if let Some(default) = default {
+            input
+                .editor()
+                .update(cx, |editor, cx| editor.set_text(default, window, cx));
+        }
+        input
+    })
+}
+
+impl ModalView for ElicitationModal {}
+
+impl Focusable for ElicitationModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for ElicitationModal {}
+
+impl Render for ElicitationModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let fields = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, (field, input))| self.render_field(index, field, input, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context("ElicitationModal")
+            .occlude()
+            .elevation_3(cx)
+            .w(rems(34.))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .track_focus(&self.focus_handle)
+            .child(
+                Modal::new("context-server-elicitation", None::<ScrollHandle>)
+                    .header(
+                        ModalHeader::new()
+                            .headline(format!("{} needs more information", self.server_id))
+                            .description(self.message.clone()),
+                    )
+                    .when_some(self.last_error.clone(), |this, error| {
+                        this.section(
+                            Section::new().child(
+                                Banner::new()
+                                    .severity(Severity::Warning)
+                                    .child(div().text_xs().child(error)),
+                            ),
+                        )
+                    })
+                    .section(
+                        Section::new().child(
+                            div()
+                                .size_full()
+                                .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+                                .child(
+                                    v_flex()
+                                        .id("elicitation-form")
+                                        .max_h_96()
+                                        .gap_3()
+                                        .tab_group()
+                                        .overflow_y_scroll()
+                                        .track_scroll(&self.scroll_handle)
+                                        .children(fields),
+                                ),
+                        ),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Button::new("cancel", "Cancel")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Cancel,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.cancel(&menu::Cancel, window, cx)
+                                        })),
+                                )
+                                .child(Button::new("decline", "Decline").on_click(cx.listener(
+                                    |this, _, _, cx| {
+                                        this.decline(cx);
+                                    },
+                                )))
+                                .child(
+                                    Button::new("submit", "Submit")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_requested_schema() {
+        let fields = parse_requested_schema(&json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "title": "Name", "maxLength": 10 },
+                "age": { "type": "integer", "minimum": 0 },
+                "subscribe": { "type": "boolean", "default": true },
+                "plan": { "type": "string", "enum": ["free", "pro"], "enumNames": ["Free", "Pro"] }
+            },
+            "required": ["name"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            fields,
+            vec![
+                FieldSchema {
+                    name: "name".into(),
+                    title: Some("Name".into()),
+                    description: None,
+                    required: true,
+                    kind: FieldKind::String {
+                        format: None,
+                        min_length: None,
+                        max_length: Some(10),
+                        default: None,
+                    },
+                },
+                FieldSchema {
+                    name: "age".into(),
+                    title: None,
+                    description: None,
+                    required: false,
+                    kind: FieldKind::Number {
+                        integer: true,
+                        minimum: Some(0.),
+                        maximum: None,
+                        default: None,
+                    },
+                },
+                FieldSchema {
+                    name: "subscribe".into(),
+                    title: None,
+                    description: None,
+                    required: false,
+                    kind: FieldKind::Boolean { default: true },
+                },
+                FieldSchema {
+                    name: "plan".into(),
+                    title: None,
+                    description: None,
+                    required: false,
+                    kind: FieldKind::Enum {
+                        values: vec!["free".into(), "pro".into()],
+                        labels: vec!["Free".into(), "Pro".into()],
+                        default: None,
+                    },
+                },
+            ]
+        );
+
+        assert!(
+            parse_requested_schema(&json!({
+                "type": "object",
+                "properties": { "address": { "type": "object" } }
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_value_from_text() {
+        let fields = parse_requested_schema(&json!({
+            "type": "object",
+            "properties": {
+                "age": { "type": "integer", "minimum": 0, "maximum": 150 },
+                "email": { "type": "string", "format": "email" },
+                "name": { "type": "string", "title": "Name", "minLength": 2 },
+            },
+            "required": ["name"]
+        }))
+        .unwrap();
+        let [age, email, name] = fields.as_slice() else {
+            panic!("unexpected fields: {fields:?}");
+        };
+
+        assert_eq!(age.value_from_text("").unwrap(), None);
+        assert_eq!(age.value_from_text(" 42").unwrap(), Some(json!(42)));
+        assert!(age.value_from_text("4.2").is_err());
+        assert!(age.value_from_text("200").is_err());
+
+        assert_eq!(
+            email.value_from_text("me@example.com").unwrap(),
+            Some(json!("me@example.com"))
+        );
+        assert!(email.value_from_text("me").is_err());
+
+        assert_eq!(
+            name.value_from_text("").unwrap_err().to_string(),
+            "Name is required"
+        );
+        assert_eq!(
+            name.value_from_text("J").unwrap_err().to_string(),
+            "Name must be at least 2 characters long"
+        );
+        assert_eq!(name.value_from_text("Jo").unwrap(), Some(json!("Jo")));
+    }
+}
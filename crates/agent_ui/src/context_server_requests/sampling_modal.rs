@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use context_server::{
+    ContextServerId,
+    types::{CreateMessageRequest, MessageContent, Role},
+};
+use futures::channel::oneshot;
+use gpui::{DismissEvent, EventEmitter, FocusHandle, Focusable, ScrollHandle, prelude::*};
+use ui::{KeyBinding, Modal, ModalFooter, ModalHeader, Section, WithScrollbar, prelude::*};
+use workspace::ModalView;
+
+/// Asks the user to approve a completion requested by an MCP server.
+pub(super) struct SamplingModal {
+    server_id: ContextServerId,
+    request: Arc<CreateMessageRequest>,
+    model_name: SharedString,
+    approval_tx: Option<oneshot::Sender<bool>>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+}
+
+impl SamplingModal {
+    pub(super) fn new(
+        server_id: ContextServerId,
+        request: Arc<CreateMessageRequest>,
+        model_name: SharedString,
+        approval_tx: oneshot::Sender<bool>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            server_id,
+            request,
+            model_name,
+            approval_tx: Some(approval_tx),
+            focus_handle: cx.focus_handle(),
+            scroll_handle: ScrollHandle::new(),
+        }
+    }
+
+    fn respond(&mut self, approved: bool, cx: &mut Context<Self>) {
+        if let Some(approval_tx) = self.approval_tx.take() {
+            approval_tx.send(approved).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        self.respond(true, cx);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        self.respond(false, cx);
+    }
+
+    fn render_message(
+        &self,
+        index: usize,
+        role: &Role,
+        content: &MessageContent,
+        cx: &App,
+    ) -> impl IntoElement {
+        let role = match role {
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+        };
+        let content: SharedString = match content {
+            MessageContent::Text { text, .. } => text.clone().into(),
+            MessageContent::Image { mime_type, .. } => format!("Image ({mime_type})").into(),
+            MessageContent::Audio { mime_type, .. } => format!("Audio ({mime_type})").into(),
+            MessageContent::Resource { resource, .. } => {
+                format!("Resource ({})", resource.uri).into()
+            }
+        };
+
+        v_flex()
+            .id(("sampling-message", index))
+            .gap_0p5()
+            .p_2()
+            .rounded_sm()
+            .border_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(role).size(LabelSize::XSmall).color(Color::Muted))
+            .child(div().text_sm().child(content))
+    }
+}
+
+impl ModalView for SamplingModal {}
+
+impl Focusable for SamplingModal {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<DismissEvent> for SamplingModal {}
+
+impl Render for SamplingModal {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let focus_handle = self.focus_handle.clone();
+        let request = self.request.clone();
+
+        v_flex()
+            .key_context("SamplingModal")
+            .occlude()
+            .elevation_3(cx)
+            .w(rems(34.))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .track_focus(&self.focus_handle)
+            .child(
+                Modal::new("context-server-sampling", None::<ScrollHandle>)
+                    .header(
+                        ModalHeader::new()
+                            .headline(format!(
+                                "{} wants to use {}",
+                                self.server_id, self.model_name
+                            ))
+                            .description(
+                                "This MCP server requested a completion from your language model.",
+                            ),
+                    )
+                    .section(
+                        Section::new().child(
+                            div()
+                                .size_full()
+                                .vertical_scrollbar_for(&self.scroll_handle, window, cx)
+                                .child(
+                                    v_flex()
+                                        .id("sampling-request")
+                                        .max_h_96()
+                                        .gap_2()
+                                        .overflow_y_scroll()
+                                        .track_scroll(&self.scroll_handle)
+                                        .when_some(
+                                            request.system_prompt.clone(),
+                                            |this, system_prompt| {
+                                                this.child(
+                                                    v_flex()
+                                                        .gap_0p5()
+                                                        .child(
+                                                            Label::new("System Prompt")
+                                                                .size(LabelSize::XSmall)
+                                                                .color(Color::Muted),
+                                                        )
+                                                        .child(
+                                                            div().text_sm().child(system_prompt),
+                                                        ),
+                                                )
+                                            },
+                                        )
+                                        .children(request.messages.iter().enumerate().map(
+                                            |(index, message)| {
+                                                self.render_message(
+                                                    index,
+                                                    &message.role,
+                                                    &message.content,
+                                                    cx,
+                                                )
+                                            },
+                                        ))
+                                        .child(
+                                            Label::new(format!(
+                                                "Maximum response length: {} tokens",
+                                                request.max_tokens
+                                            ))
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                        ),
+                                ),
+                        ),
+                    )
+                    .footer(
+                        ModalFooter::new().end_slot(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Button::new("deny", "Deny")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Cancel,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.cancel(&menu::Cancel, window, cx)
+                                        })),
+                                )
+                                .child(
+                                    Button::new("allow", "Allow")
+                                        .key_binding(
+                                            KeyBinding::for_action_in(
+                                                &menu::Confirm,
+                                                &focus_handle,
+                                                cx,
+                                            )
+                                            .map(|kb| kb.size(rems_from_px(12.))),
+                                        )
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.confirm(&menu::Confirm, window, cx)
+                                        })),
+                                ),
+                        ),
+                    ),
+            )
+    }
+}
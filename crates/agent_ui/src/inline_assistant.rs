@@ -1507,6 +1507,7 @@ impl InlineAssistant {
                     editor.disable_scrollbars_and_minimap(window, cx);
                     editor.set_soft_wrap_mode(language::language_settings::SoftWrap::None, cx);
                     editor.set_show_wrap_guides(false, cx);
+                    editor.set_show_rulers(false, cx);
                     editor.set_show_gutter(false, cx);
                     editor.set_offset_content(false, cx);
                     editor.scroll_manager.set_forbid_vertical_scroll(true);
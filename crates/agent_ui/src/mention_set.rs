@@ -1,10 +1,11 @@
 use acp_thread::{MentionUri, selection_name};
-use agent::{ThreadStore, outline};
+use agent::{ContextServerRegistry, ContextServerRegistryEvent, ThreadStore, outline};
 use agent_client_protocol as acp;
 use agent_servers::{AgentServer, AgentServerDelegate};
 use anyhow::{Context as _, Result, anyhow};
 use assistant_slash_commands::codeblock_fence_for_path;
 use collections::{HashMap, HashSet};
+use context_server::ContextServerId;
 use editor::{
     Anchor, Editor, EditorSnapshot, ExcerptId, FoldPlaceholder, ToOffset,
     display_map::{Crease, CreaseId, CreaseMetadata, FoldId},
@@ -13,7 +14,7 @@ use editor::{
 use futures::{AsyncReadExt as _, FutureExt as _, future::Shared};
 use gpui::{
     AppContext, ClipboardEntry, Context, Empty, Entity, EntityId, Image, ImageFormat, Img,
-    SharedString, Task, WeakEntity,
+    SharedString, Subscription, Task, WeakEntity,
 };
 use http_client::{AsyncBody, HttpClientWithUrl};
 use itertools::Either;
@@ -21,7 +22,9 @@ use language::Buffer;
 use language_model::LanguageModelImage;
 use multi_buffer::MultiBufferRow;
 use postage::stream::Stream as _;
-use project::{Project, ProjectItem, ProjectPath, Worktree};
+use project::{
+    Project, ProjectItem, ProjectPath, Worktree, context_server_store::ContextServerStore,
+};
 use prompt_store::{PromptId, PromptStore};
 use rope::Point;
 use std::{
@@ -38,6 +41,7 @@ use ui::{Disclosure, Toggleable, prelude::*};
 use util::{ResultExt, debug_panic, rel_path::RelPath};
 use workspace::{Workspace, notifications::NotifyResultExt as _};
 
+use crate::AgentPanel;
 use crate::ui::MentionCrease;
 
 pub type MentionTask = Shared<Task<Result<Mention, String>>>;
@@ -63,6 +67,12 @@ pub struct MentionSet {
     thread_store: Option<Entity<ThreadStore>>,
     prompt_store: Option<Entity<PromptStore>>,
     mentions: HashMap<CreaseId, (MentionUri, MentionTask)>,
+    /// Whether MCP resource mentions can be loaded as images.
+    resource_images_supported: bool,
+    context_server_registry: Option<WeakEntity<ContextServerRegistry>>,
+    /// MCP resources this set holds a registry subscription for.
+    subscribed_resources: HashSet<(ContextServerId, String)>,
+    context_server_registry_subscription: Option<Subscription>,
 }
 
 impl MentionSet {
@@ -76,6 +86,10 @@ impl MentionSet {
             thread_store,
             prompt_store,
             mentions: HashMap::default(),
+            resource_images_supported: false,
+            context_server_registry: None,
+            subscribed_resources: HashSet::default(),
+            context_server_registry_subscription: None,
         }
     }
 
@@ -106,20 +120,60 @@ impl MentionSet {
         })
     }
 
-    pub fn remove_invalid(&mut self, snapshot: &EditorSnapshot) {
+    pub fn remove_invalid(&mut self, snapshot: &EditorSnapshot, cx: &mut App) {
         for (crease_id, crease) in snapshot.crease_snapshot.creases() {
             if !crease.range().start.is_valid(snapshot.buffer_snapshot()) {
                 self.mentions.remove(&crease_id);
             }
         }
+        self.release_unmentioned_resources(cx);
     }
 
     pub fn insert_mention(&mut self, crease_id: CreaseId, uri: MentionUri, task: MentionTask) {
         self.mentions.insert(crease_id, (uri, task));
     }
 
-    pub fn remove_mention(&mut self, crease_id: &CreaseId) {
+    pub fn remove_mention(&mut self, crease_id: &CreaseId, cx: &mut App) {
         self.mentions.remove(crease_id);
+        self.release_unmentioned_resources(cx);
+    }
+
+    /// Unsubscribes from MCP resources that are no longer mentioned.
+    fn release_unmentioned_resources(&mut self, cx: &mut App) {
+        let mentioned_resources = self
+            .mentions
+            .values()
+            .filter_map(|(mention_uri, _)| match mention_uri {
+                MentionUri::McpResource { server_id, uri, .. } => {
+                    Some((ContextServerId(server_id.as_str().into()), uri.clone()))
+                }
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        let released_resources = self
+            .subscribed_resources
+            .extract_if(|resource| !mentioned_resources.contains(resource))
+            .collect::<Vec<_>>();
+        Self::unsubscribe_from_resources(
+            self.context_server_registry.as_ref(),
+            released_resources,
+            cx,
+        );
+    }
+
+    fn unsubscribe_from_resources(
+        registry: Option<&WeakEntity<ContextServerRegistry>>,
+        resources: impl IntoIterator<Item = (ContextServerId, String)>,
+        cx: &mut App,
+    ) {
+        let Some(registry) = registry.and_then(|registry| registry.upgrade()) else {
+            return;
+        };
+        registry.update(cx, |registry, cx| {
+            for (server_id, uri) in resources {
+                registry.unsubscribe_from_resource(&server_id, &uri, cx);
+            }
+        });
     }
 
     pub fn creases(&self) -> HashSet<CreaseId> {
@@ -235,6 +289,14 @@ impl MentionSet {
                 ..
             } => self.confirm_mention_for_symbol(abs_path, line_range, cx),
             MentionUri::Rule { id, .. } => self.confirm_mention_for_rule(id, cx),
+            MentionUri::McpResource { server_id, uri, .. } => self
+                .confirm_mention_for_mcp_resource(
+                    ContextServerId(server_id.into()),
+                    uri,
+                    supports_images,
+                    workspace,
+                    cx,
+                ),
             MentionUri::PastedImage => {
                 debug_panic!("pasted image URI should not be included in completions");
                 Task::ready(Err(anyhow!(
@@ -261,7 +323,7 @@ impl MentionSet {
                         // Remove mention
                         editor.edit([(start_anchor..end_anchor, "")], cx);
                     });
-                    this.mentions.remove(&crease_id);
+                    this.remove_mention(&crease_id, cx);
                 })
                 .ok();
             }
@@ -392,6 +454,85 @@ impl MentionSet {
         })
     }
 
+    fn confirm_mention_for_mcp_resource(
+        &mut self,
+        server_id: ContextServerId,
+        uri: String,
+        supports_images: bool,
+        workspace: &Entity<Workspace>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Mention>> {
+        let Some(project) = self.project.upgrade() else {
+            return Task::ready(Err(anyhow!("project not found")));
+        };
+
+        // Ask the server to tell us about changes to the resource, so that the message
+        // includes its latest contents when it gets sent.
+        if let Some(registry) = workspace
+            .read(cx)
+            .panel::<AgentPanel>(cx)
+            .map(|panel| panel.read(cx).context_server_registry().clone())
+        {
+            if self
+                .subscribed_resources
+                .insert((server_id.clone(), uri.clone()))
+            {
+                registry.update(cx, |registry, cx| {
+                    registry.subscribe_to_resource(&server_id, &uri, cx)
+                });
+            }
+            if self.context_server_registry_subscription.is_none() {
+                self.context_server_registry = Some(registry.downgrade());
+                self.context_server_registry_subscription =
+                    Some(cx.subscribe(&registry, Self::handle_context_server_registry_event));
+                cx.on_release(|this, cx| {
+                    Self::unsubscribe_from_resources(
+                        this.context_server_registry.as_ref(),
+                        this.subscribed_resources.drain(),
+                        cx,
+                    );
+                })
+                .detach();
+            }
+        }
+        self.resource_images_supported = supports_images;
+
+        let server_store = project.read(cx).context_server_store();
+        read_mcp_resource(&server_store, &server_id, &uri, supports_images, cx)
+    }
+
+    fn handle_context_server_registry_event(
+        &mut self,
+        _: Entity<ContextServerRegistry>,
+        event: &ContextServerRegistryEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let ContextServerRegistryEvent::ResourceUpdated {
+            server_id: updated_server_id,
+            uri: updated_uri,
+        } = event
+        else {
+            return;
+        };
+        let Some(project) = self.project.upgrade() else {
+            return;
+        };
+        let server_store = project.read(cx).context_server_store();
+        let supports_images = self.resource_images_supported;
+        for (mention_uri, task) in self.mentions.values_mut() {
+            if let MentionUri::McpResource { server_id, uri, .. } = mention_uri
+                && *server_id == *updated_server_id.0
+                && uri == updated_uri
+            {
+                let content =
+                    read_mcp_resource(&server_store, updated_server_id, uri, supports_images, cx);
+                *task = cx
+                    .spawn(async move |_, _| content.await.map_err(|e| e.to_string()))
+                    .shared();
+            }
+        }
+    }
+
     pub fn confirm_mention_for_selection(
         &mut self,
         source_range: Range<text::Anchor>,
@@ -695,8 +836,8 @@ pub(crate) fn paste_images_as_context(
                 editor.update(cx, |editor, cx| {
                     editor.edit([(start_anchor..end_anchor, "")], cx);
                 });
-                mention_set.update(cx, |mention_set, _cx| {
-                    mention_set.remove_mention(&crease_id)
+                mention_set.update(cx, |mention_set, cx| {
+                    mention_set.remove_mention(&crease_id, cx)
                 });
             }
         }
@@ -1010,6 +1151,60 @@ impl Render for ImageHover {
     }
 }
 
+fn read_mcp_resource(
+    server_store: &Entity<ContextServerStore>,
+    server_id: &ContextServerId,
+    uri: &str,
+    supports_images: bool,
+    cx: &mut App,
+) -> Task<Result<Mention>> {
+    let response = agent::read_resource(server_store, server_id, uri, cx);
+    cx.background_spawn(async move {
+        let contents = response.await?.contents;
+        if let [context_server::types::ResourceContentsType::Blob(blob)] = contents.as_slice()
+            && supports_images
+            && let Some(format) = blob
+                .mime_type
+                .as_deref()
+                .and_then(ImageFormat::from_mime_type)
+        {
+            return Ok(Mention::Image(MentionImage {
+                data: blob.blob.clone().into(),
+                format,
+            }));
+        }
+
+        let mut content = String::new();
+        let include_uris = contents.len() > 1;
+        for resource in contents {
+            match resource {
+                context_server::types::ResourceContentsType::Text(text) => {
+                    if include_uris {
+                        writeln!(content, "{}:", text.uri).ok();
+                    }
+                    content.push_str(&text.text);
+                    if !content.ends_with('\n') {
+                        content.push('\n');
+                    }
+                }
+                context_server::types::ResourceContentsType::Blob(blob) => {
+                    writeln!(
+                        content,
+                        "[binary content of {} ({}) omitted]",
+                        blob.uri,
+                        blob.mime_type.as_deref().unwrap_or("unknown type")
+                    )
+                    .ok();
+                }
+            }
+        }
+        Ok(Mention::Text {
+            content,
+            tracked_buffers: Vec::new(),
+        })
+    })
+}
+
 async fn fetch_url_content(http_client: Arc<HttpClientWithUrl>, url: String) -> Result<String> {
     #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
     enum ContentType {
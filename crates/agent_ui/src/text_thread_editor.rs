@@ -252,6 +252,7 @@ impl TextThreadEditor {
             editor.set_show_runnables(false, cx);
             editor.set_show_breakpoints(false, cx);
             editor.set_show_wrap_guides(false, cx);
+            editor.set_show_rulers(false, cx);
             editor.set_show_indent_guides(false, cx);
             editor.set_completion_provider(Some(Rc::new(completion_provider)));
             editor.set_menu_edit_predictions_policy(MenuEditPredictionsPolicy::Never);
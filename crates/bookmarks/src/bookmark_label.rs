@@ -0,0 +1,101 @@
+use editor::{Editor, EditorEvent, actions::SelectAll};
+use gpui::{App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription};
+use language::Buffer;
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::BookmarkStore;
+
+/// Edits the label of the bookmark on a row of a buffer.
+pub(crate) struct BookmarkLabel {
+    label_editor: Entity<Editor>,
+    bookmark_store: Entity<BookmarkStore>,
+    buffer: Entity<Buffer>,
+    row: u32,
+    _subscription: Subscription,
+}
+
+impl BookmarkLabel {
+    pub(crate) fn new(
+        bookmark_store: Entity<BookmarkStore>,
+        buffer: Entity<Buffer>,
+        row: u32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let label = bookmark_store
+            .read(cx)
+            .bookmark_at(&buffer, row, cx)
+            .and_then(|bookmark| bookmark.label.clone());
+        let label_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bookmark label…", window, cx);
+            if let Some(label) = label {
+                editor.set_text(label.as_ref(), window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe(&label_editor, |_, _, event, cx| {
+            if let EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+
+        Self {
+            label_editor,
+            bookmark_store,
+            buffer,
+            row,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let label = self.label_editor.read(cx).text(cx).trim().to_string();
+        let label = (!label.is_empty()).then(|| label.into());
+        self.bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.set_label(&self.buffer, self.row, label, cx)
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for BookmarkLabel {}
+
+impl Focusable for BookmarkLabel {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkLabel {}
+
+impl Render for BookmarkLabel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("BookmarkLabel")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.label_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new(format!("Label the bookmark on line {}", self.row + 1))
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
@@ -0,0 +1,249 @@
+use std::sync::Arc;
+
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Task,
+    WeakEntity, Window,
+};
+use language::Point;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, OpenOptions, OpenVisible, Workspace};
+
+use crate::BookmarkEntry;
+
+/// Lists the bookmarks in all files of the project.
+pub(crate) struct BookmarkList {
+    picker: Entity<Picker<BookmarkListDelegate>>,
+}
+
+impl BookmarkList {
+    pub(crate) fn new(
+        workspace: WeakEntity<Workspace>,
+        project: Entity<Project>,
+        bookmarks: Vec<BookmarkEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let project = project.read(cx);
+        let path_style = project.path_style(cx);
+        let candidates = bookmarks
+            .iter()
+            .enumerate()
+            .map(|(id, bookmark)| {
+                let path = project
+                    .find_project_path(&bookmark.path, cx)
+                    .map(|project_path| project_path.path.display(path_style).into_owned())
+                    .unwrap_or_else(|| bookmark.path.to_string_lossy().into_owned());
+                let location = format!("{path}:{}", bookmark.row + 1);
+                let text = match &bookmark.label {
+                    Some(label) => format!("{label}  {location}"),
+                    None => location,
+                };
+                StringMatchCandidate::new(id, &text)
+            })
+            .collect();
+
+        let delegate = BookmarkListDelegate {
+            bookmark_list: cx.entity().downgrade(),
+            workspace,
+            bookmarks,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| Picker::list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for BookmarkList {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BookmarkList")
+            .w(rems(40.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for BookmarkList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkList {}
+impl ModalView for BookmarkList {}
+
+pub(crate) struct BookmarkListDelegate {
+    bookmark_list: WeakEntity<BookmarkList>,
+    workspace: WeakEntity<Workspace>,
+    bookmarks: Vec<BookmarkEntry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for BookmarkListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Search bookmarks…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        if self.candidates.is_empty() {
+            Some("No bookmarks".into())
+        } else {
+            Some("No matches".into())
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                matches.sort_unstable_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(bookmark) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|mat| self.bookmarks.get(mat.candidate_id))
+        else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let point = Point::new(bookmark.row, 0);
+        let open_task = workspace.update(cx, |workspace, cx| {
+            workspace.open_abs_path(
+                bookmark.path.to_path_buf(),
+                OpenOptions {
+                    visible: Some(OpenVisible::All),
+                    focus: Some(true),
+                    ..Default::default()
+                },
+                window,
+                cx,
+            )
+        });
+        cx.spawn_in(window, async move |_, cx| {
+            let item = open_task.await?;
+            cx.update(|window, cx| {
+                if let Some(editor) = item.act_as::<Editor>(cx) {
+                    editor.update(cx, |editor, cx| {
+                        editor.change_selections(
+                            SelectionEffects::scroll(Autoscroll::center()),
+                            window,
+                            cx,
+                            |selections| selections.select_ranges([point..point]),
+                        );
+                    });
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.bookmark_list
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let bookmark = self.bookmarks.get(mat.candidate_id)?;
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    v_flex()
+                        .child(HighlightedLabel::new(
+                            mat.string.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .when_some(bookmark.line.clone(), |this, line| {
+                            this.child(
+                                Label::new(line)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            )
+                        }),
+                ),
+        )
+    }
+}
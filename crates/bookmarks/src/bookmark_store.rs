@@ -0,0 +1,437 @@
+use std::{path::Path, sync::Arc};
+
+use collections::HashMap;
+use editor::{Anchor, Editor, EditorEvent};
+use gpui::{
+    App, AppContext as _, Context, Entity, EntityId, SharedString, Subscription, WeakEntity,
+};
+use language::{Bias, Buffer, BufferEvent, BufferId, Point};
+use project::{Project, ProjectItem as _, buffer_store::BufferStoreEvent};
+use theme::ActiveTheme as _;
+use util::ResultExt as _;
+use workspace::{Workspace, WorkspaceId};
+
+use crate::persistence::DB;
+
+/// The gutter highlight type of bookmarked lines.
+enum BookmarkGutterHighlight {}
+
+/// A bookmarked line of an open buffer, which moves along with edits.
+#[derive(Clone, Debug)]
+pub struct Bookmark {
+    pub anchor: text::Anchor,
+    pub label: Option<SharedString>,
+}
+
+/// A bookmarked line as it is persisted, which is also used for the files that aren't open.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SerializedBookmark {
+    pub row: u32,
+    pub label: Option<SharedString>,
+}
+
+/// A bookmark in any file of the project, as listed in the bookmark list.
+#[derive(Clone, Debug)]
+pub struct BookmarkEntry {
+    pub path: Arc<Path>,
+    pub row: u32,
+    pub label: Option<SharedString>,
+    /// The text of the bookmarked line, if the file is open.
+    pub line: Option<String>,
+}
+
+struct BufferBookmarks {
+    path: Arc<Path>,
+    bookmarks: Vec<Bookmark>,
+    _subscriptions: [Subscription; 2],
+}
+
+/// The bookmarks of a workspace, which are persisted per file and shown in the gutter of the
+/// workspace's editors.
+pub struct BookmarkStore {
+    workspace_id: Option<WorkspaceId>,
+    project: Entity<Project>,
+    buffers: HashMap<BufferId, BufferBookmarks>,
+    serialized_bookmarks: HashMap<Arc<Path>, Vec<SerializedBookmark>>,
+    editors: HashMap<EntityId, (WeakEntity<Editor>, Subscription)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BookmarkStore {
+    pub fn new(workspace: &Workspace, cx: &mut Context<Workspace>) -> Entity<Self> {
+        let workspace_handle = cx.entity();
+        let workspace_id = workspace.database_id();
+        let project = workspace.project().clone();
+        cx.new(|cx| {
+            let mut this = Self::for_project(workspace_id, project, cx);
+            this._subscriptions
+                .push(cx.subscribe(&workspace_handle, |this, _, event, cx| {
+                    if let workspace::Event::ItemAdded { item } = event
+                        && let Some(editor) = item.act_as::<Editor>(cx)
+                    {
+                        this.register_editor(editor, cx);
+                    }
+                }));
+            this
+        })
+    }
+
+    /// Creates a store tracking the bookmarks of the project's buffers, without showing them in
+    /// any editor.
+    pub(crate) fn for_project(
+        workspace_id: Option<WorkspaceId>,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let buffer_store = project.read(cx).buffer_store().clone();
+        let subscriptions = vec![cx.subscribe(&buffer_store, |this, _, event, cx| {
+            if let BufferStoreEvent::BufferAdded(buffer) = event {
+                this.buffer_added(buffer, cx);
+            }
+        })];
+
+        let mut this = Self {
+            workspace_id,
+            project,
+            buffers: HashMap::default(),
+            serialized_bookmarks: HashMap::default(),
+            editors: HashMap::default(),
+            _subscriptions: subscriptions,
+        };
+        this.load(cx);
+        this
+    }
+
+    fn load(&mut self, cx: &mut Context<Self>) {
+        let Some(workspace_id) = self.workspace_id else {
+            return;
+        };
+        cx.spawn(async move |this, cx| {
+            let bookmarks = cx
+                .background_spawn(async move { DB.get_bookmarks(workspace_id) })
+                .await
+                .log_err()?;
+            this.update(cx, |this, cx| this.loaded(bookmarks, cx)).ok()
+        })
+        .detach();
+    }
+
+    fn loaded(&mut self, bookmarks: Vec<(Arc<Path>, SerializedBookmark)>, cx: &mut Context<Self>) {
+        for (path, bookmark) in bookmarks {
+            self.serialized_bookmarks
+                .entry(path)
+                .or_default()
+                .push(bookmark);
+        }
+
+        let buffers = self
+            .project
+            .read(cx)
+            .buffer_store()
+            .read(cx)
+            .buffers()
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.buffer_added(&buffer, cx);
+        }
+    }
+
+    fn abs_path(&self, buffer: &Entity<Buffer>, cx: &App) -> Option<Arc<Path>> {
+        let project_path = buffer.read(cx).project_path(cx)?;
+        let abs_path = self.project.read(cx).absolute_path(&project_path, cx)?;
+        Some(abs_path.into())
+    }
+
+    fn buffer_added(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        if self.buffers.contains_key(&buffer.read(cx).remote_id()) {
+            return;
+        }
+        let Some(path) = self.abs_path(buffer, cx) else {
+            return;
+        };
+        let Some(serialized_bookmarks) = self.serialized_bookmarks.get(&path) else {
+            return;
+        };
+
+        let snapshot = buffer.read(cx).snapshot();
+        let bookmarks = serialized_bookmarks
+            .iter()
+            .map(|bookmark| Bookmark {
+                anchor: snapshot
+                    .anchor_after(snapshot.clip_point(Point::new(bookmark.row, 0), Bias::Left)),
+                label: bookmark.label.clone(),
+            })
+            .collect();
+        self.track_buffer(buffer, path, bookmarks, cx);
+        self.refresh_editors(cx);
+    }
+
+    fn track_buffer(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        path: Arc<Path>,
+        bookmarks: Vec<Bookmark>,
+        cx: &mut Context<Self>,
+    ) {
+        // Rows are only persisted once they match the file on disk, so that unsaved edits don't
+        // leave the bookmarks of a file on the wrong lines.
+        let on_change = cx.subscribe(buffer, |this, buffer, event, cx| match event {
+            BufferEvent::Saved | BufferEvent::Reloaded => this.save_buffer_bookmarks(&buffer, cx),
+            BufferEvent::FileHandleChanged => {
+                let buffer_id = buffer.read(cx).remote_id();
+                if let Some(new_path) = this.abs_path(&buffer, cx)
+                    && let Some(buffer_bookmarks) = this.buffers.get_mut(&buffer_id)
+                    && buffer_bookmarks.path != new_path
+                {
+                    let old_path = std::mem::replace(&mut buffer_bookmarks.path, new_path);
+                    this.save_bookmarks(old_path, Vec::new(), cx);
+                    this.save_buffer_bookmarks(&buffer, cx);
+                }
+            }
+            _ => {}
+        });
+        let on_release = cx.observe_release(buffer, |this, buffer, _| {
+            this.buffers.remove(&buffer.remote_id());
+        });
+
+        self.buffers.insert(
+            buffer.read(cx).remote_id(),
+            BufferBookmarks {
+                path,
+                bookmarks,
+                _subscriptions: [on_change, on_release],
+            },
+        );
+    }
+
+    /// Returns the bookmarks of the given buffer, starting to track it if it has none yet.
+    fn bookmarks_mut(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<&mut Vec<Bookmark>> {
+        let buffer_id = buffer.read(cx).remote_id();
+        if !self.buffers.contains_key(&buffer_id) {
+            let path = self.abs_path(buffer, cx)?;
+            self.track_buffer(buffer, path, Vec::new(), cx);
+        }
+        Some(&mut self.buffers.get_mut(&buffer_id)?.bookmarks)
+    }
+
+    /// Returns the bookmark on the given row of the buffer.
+    pub fn bookmark_at(&self, buffer: &Entity<Buffer>, row: u32, cx: &App) -> Option<&Bookmark> {
+        let buffer = buffer.read(cx);
+        let snapshot = buffer.snapshot();
+        self.buffers
+            .get(&buffer.remote_id())?
+            .bookmarks
+            .iter()
+            .find(|bookmark| snapshot.summary_for_anchor::<Point>(&bookmark.anchor).row == row)
+    }
+
+    /// Adds a bookmark on the given row of the buffer, or removes the one that's there.
+    pub fn toggle_bookmark(&mut self, buffer: &Entity<Buffer>, row: u32, cx: &mut Context<Self>) {
+        let snapshot = buffer.read(cx).snapshot();
+        let Some(bookmarks) = self.bookmarks_mut(buffer, cx) else {
+            return;
+        };
+        if let Some(ix) = bookmarks
+            .iter()
+            .position(|bookmark| snapshot.summary_for_anchor::<Point>(&bookmark.anchor).row == row)
+        {
+            bookmarks.remove(ix);
+        } else {
+            bookmarks.push(Bookmark {
+                anchor: snapshot.anchor_after(Point::new(row, 0)),
+                label: None,
+            });
+            bookmarks.sort_by(|a, b| a.anchor.cmp(&b.anchor, &snapshot));
+        }
+        self.save_buffer_bookmarks(buffer, cx);
+        self.refresh_editors(cx);
+    }
+
+    /// Sets the label of the bookmark on the given row of the buffer, adding a bookmark if there's
+    /// none.
+    pub fn set_label(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        row: u32,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let Some(bookmarks) = self.bookmarks_mut(buffer, cx) else {
+            return;
+        };
+        if let Some(bookmark) = bookmarks
+            .iter_mut()
+            .find(|bookmark| snapshot.summary_for_anchor::<Point>(&bookmark.anchor).row == row)
+        {
+            bookmark.label = label;
+        } else {
+            bookmarks.push(Bookmark {
+                anchor: snapshot.anchor_after(Point::new(row, 0)),
+                label,
+            });
+            bookmarks.sort_by(|a, b| a.anchor.cmp(&b.anchor, &snapshot));
+        }
+        self.save_buffer_bookmarks(buffer, cx);
+        self.refresh_editors(cx);
+    }
+
+    /// Returns all bookmarks of the workspace, ordered by path and row.
+    pub fn all_bookmarks(&self, cx: &App) -> Vec<BookmarkEntry> {
+        let mut entries = Vec::new();
+        for (buffer_id, buffer_bookmarks) in &self.buffers {
+            let Some(buffer) = self.project.read(cx).buffer_for_id(*buffer_id, cx) else {
+                continue;
+            };
+            let snapshot = buffer.read(cx).snapshot();
+            for bookmark in &buffer_bookmarks.bookmarks {
+                let row = snapshot.summary_for_anchor::<Point>(&bookmark.anchor).row;
+                let line = snapshot
+                    .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+                    .collect::<String>();
+                entries.push(BookmarkEntry {
+                    path: buffer_bookmarks.path.clone(),
+                    row,
+                    label: bookmark.label.clone(),
+                    line: Some(line.trim().to_string()),
+                });
+            }
+        }
+
+        for (path, bookmarks) in &self.serialized_bookmarks {
+            if self
+                .buffers
+                .values()
+                .any(|buffer_bookmarks| &buffer_bookmarks.path == path)
+            {
+                continue;
+            }
+            entries.extend(bookmarks.iter().map(|bookmark| BookmarkEntry {
+                path: path.clone(),
+                row: bookmark.row,
+                label: bookmark.label.clone(),
+                line: None,
+            }));
+        }
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path).then(a.row.cmp(&b.row)));
+        entries
+    }
+
+    /// Returns the positions of the bookmarks that are visible in the editor, in order.
+    pub fn editor_bookmarks(&self, editor: &Editor, cx: &App) -> Vec<Anchor> {
+        let multi_buffer = editor.buffer().read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut anchors = Vec::new();
+        for (buffer_id, buffer_bookmarks) in &self.buffers {
+            for (excerpt_id, _) in multi_buffer.excerpts_for_buffer(*buffer_id, cx) {
+                anchors.extend(buffer_bookmarks.bookmarks.iter().filter_map(|bookmark| {
+                    snapshot.anchor_in_excerpt(excerpt_id, bookmark.anchor)
+                }));
+            }
+        }
+        anchors.sort_by(|a, b| a.cmp(b, &snapshot));
+        anchors
+    }
+
+    fn register_editor(&mut self, editor: Entity<Editor>, cx: &mut Context<Self>) {
+        let subscription = cx.subscribe(&editor, |this, editor, event, cx| match event {
+            EditorEvent::ExcerptsAdded { .. } | EditorEvent::ExcerptsRemoved { .. } => {
+                this.refresh_editor(&editor, cx)
+            }
+            _ => {}
+        });
+        self.refresh_editor(&editor, cx);
+        self.editors
+            .insert(editor.entity_id(), (editor.downgrade(), subscription));
+    }
+
+    fn refresh_editors(&mut self, cx: &mut Context<Self>) {
+        self.editors
+            .retain(|_, (editor, _)| editor.upgrade().is_some());
+        let editors = self
+            .editors
+            .values()
+            .filter_map(|(editor, _)| editor.upgrade())
+            .collect::<Vec<_>>();
+        for editor in editors {
+            self.refresh_editor(&editor, cx);
+        }
+    }
+
+    fn refresh_editor(&self, editor: &Entity<Editor>, cx: &mut App) {
+        editor.update(cx, |editor, cx| {
+            let ranges = self
+                .editor_bookmarks(editor, cx)
+                .into_iter()
+                .map(|anchor| anchor..anchor)
+                .collect::<Vec<_>>();
+            editor.highlight_gutter::<BookmarkGutterHighlight>(
+                ranges,
+                |cx| cx.theme().status().info,
+                cx,
+            );
+        });
+    }
+
+    /// Persists the bookmarks of the buffer, unless it has unsaved changes or their rows are
+    /// unchanged.
+    fn save_buffer_bookmarks(&mut self, buffer: &Entity<Buffer>, cx: &mut Context<Self>) {
+        if buffer.read(cx).is_dirty() {
+            return;
+        }
+        let snapshot = buffer.read(cx).snapshot();
+        let Some(buffer_bookmarks) = self.buffers.get_mut(&snapshot.remote_id()) else {
+            return;
+        };
+
+        // Bookmarks whose lines were joined end up on the same row, so only keep the first.
+        let mut serialized_bookmarks = Vec::<SerializedBookmark>::new();
+        buffer_bookmarks.bookmarks.retain(|bookmark| {
+            let row = snapshot.summary_for_anchor::<Point>(&bookmark.anchor).row;
+            if serialized_bookmarks
+                .last()
+                .is_some_and(|last| last.row == row)
+            {
+                return false;
+            }
+            serialized_bookmarks.push(SerializedBookmark {
+                row,
+                label: bookmark.label.clone(),
+            });
+            true
+        });
+
+        let path = buffer_bookmarks.path.clone();
+        self.save_bookmarks(path, serialized_bookmarks, cx);
+    }
+
+    fn save_bookmarks(
+        &mut self,
+        path: Arc<Path>,
+        bookmarks: Vec<SerializedBookmark>,
+        cx: &mut Context<Self>,
+    ) {
+        let old_bookmarks = self.serialized_bookmarks.get(&path);
+        if old_bookmarks.map_or(bookmarks.is_empty(), |old| *old == bookmarks) {
+            return;
+        }
+        if bookmarks.is_empty() {
+            self.serialized_bookmarks.remove(&path);
+        } else {
+            self.serialized_bookmarks
+                .insert(path.clone(), bookmarks.clone());
+        }
+
+        if let Some(workspace_id) = self.workspace_id {
+            cx.background_spawn(
+                async move { DB.set_bookmarks(workspace_id, path, bookmarks).await },
+            )
+            .detach_and_log_err(cx);
+        }
+    }
+}
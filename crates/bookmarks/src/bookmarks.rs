@@ -0,0 +1,341 @@
+mod bookmark_label;
+mod bookmark_list;
+mod bookmark_store;
+mod persistence;
+
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{App, Context, Entity, Window, actions};
+use language::{Buffer, Point};
+use workspace::{Workspace, searchable::Direction};
+
+pub use crate::bookmark_store::{Bookmark, BookmarkEntry, BookmarkStore, SerializedBookmark};
+use crate::{bookmark_label::BookmarkLabel, bookmark_list::BookmarkList};
+
+actions!(
+    bookmarks,
+    [
+        /// Adds or removes a bookmark on the line of the cursor.
+        ToggleBookmark,
+        /// Edits the label of the bookmark on the line of the cursor, adding a bookmark if there is none.
+        EditBookmarkLabel,
+        /// Moves the cursor to the next bookmark in the editor.
+        GoToNextBookmark,
+        /// Moves the cursor to the previous bookmark in the editor.
+        GoToPreviousBookmark,
+        /// Toggles the list of all bookmarks in the project.
+        ToggleBookmarkList,
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(register).detach();
+}
+
+fn register(workspace: &mut Workspace, _: Option<&mut Window>, cx: &mut Context<Workspace>) {
+    let bookmark_store = BookmarkStore::new(workspace, cx);
+
+    workspace.register_action({
+        let bookmark_store = bookmark_store.clone();
+        move |workspace, _: &ToggleBookmark, _, cx| {
+            let Some((buffer, row)) = cursor_row(workspace, cx) else {
+                return;
+            };
+            bookmark_store.update(cx, |bookmark_store, cx| {
+                bookmark_store.toggle_bookmark(&buffer, row, cx)
+            });
+        }
+    });
+    workspace.register_action({
+        let bookmark_store = bookmark_store.clone();
+        move |workspace, _: &EditBookmarkLabel, window, cx| {
+            let Some((buffer, row)) = cursor_row(workspace, cx) else {
+                return;
+            };
+            let bookmark_store = bookmark_store.clone();
+            workspace.toggle_modal(window, cx, |window, cx| {
+                BookmarkLabel::new(bookmark_store, buffer, row, window, cx)
+            });
+        }
+    });
+    workspace.register_action({
+        let bookmark_store = bookmark_store.clone();
+        move |workspace, _: &GoToNextBookmark, window, cx| {
+            go_to_bookmark(&bookmark_store, Direction::Next, workspace, window, cx);
+        }
+    });
+    workspace.register_action({
+        let bookmark_store = bookmark_store.clone();
+        move |workspace, _: &GoToPreviousBookmark, window, cx| {
+            go_to_bookmark(&bookmark_store, Direction::Prev, workspace, window, cx);
+        }
+    });
+    workspace.register_action(move |workspace, _: &ToggleBookmarkList, window, cx| {
+        let bookmarks = bookmark_store.read(cx).all_bookmarks(cx);
+        let project = workspace.project().clone();
+        let workspace_handle = cx.weak_entity();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            BookmarkList::new(workspace_handle, project, bookmarks, window, cx)
+        });
+    });
+}
+
+/// Returns the buffer and row of the newest cursor in the active editor.
+fn cursor_row(workspace: &Workspace, cx: &mut App) -> Option<(Entity<Buffer>, u32)> {
+    let editor = workspace.active_item_as::<Editor>(cx)?;
+    editor.update(cx, |editor, cx| {
+        let head = editor
+            .selections
+            .newest::<Point>(&editor.display_snapshot(cx))
+            .head();
+        let (buffer, point, _) = editor.buffer().read(cx).point_to_buffer_point(head, cx)?;
+        Some((buffer, point.row))
+    })
+}
+
+fn go_to_bookmark(
+    bookmark_store: &Entity<BookmarkStore>,
+    direction: Direction,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let bookmarks = bookmark_store
+        .read(cx)
+        .editor_bookmarks(editor.read(cx), cx);
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let cursor_row = editor
+            .selections
+            .newest::<Point>(&editor.display_snapshot(cx))
+            .head()
+            .row;
+        let mut rows = bookmarks
+            .iter()
+            .map(|anchor| anchor.to_point(&snapshot).row)
+            .collect::<Vec<_>>();
+        rows.dedup();
+
+        // Wrap around at the start and end of the editor.
+        let row = match direction {
+            Direction::Next => rows.iter().find(|row| **row > cursor_row).or(rows.first()),
+            Direction::Prev => rows
+                .iter()
+                .rev()
+                .find(|row| **row < cursor_row)
+                .or(rows.last()),
+        };
+        let Some(&row) = row else {
+            return;
+        };
+
+        let point = Point::new(row, 0);
+        editor.change_selections(
+            SelectionEffects::scroll(Autoscroll::center()),
+            window,
+            cx,
+            |selections| selections.select_ranges([point..point]),
+        );
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::Anchor;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use std::{path::Path, sync::Arc};
+    use util::{path, rel_path::rel_path};
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_toggle_and_navigate_bookmarks(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.txt": "zero\none\ntwo\nthree\nfour\nfive\n",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.txt")), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+
+        set_cursor_row(&editor, 1, cx);
+        cx.dispatch_action(ToggleBookmark);
+        set_cursor_row(&editor, 4, cx);
+        cx.dispatch_action(ToggleBookmark);
+        assert_eq!(bookmarked_rows(&editor, cx), [1, 4]);
+
+        // Bookmarks move along with the lines they're on.
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |selections| {
+                selections.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+            });
+            editor.insert("new line\n", window, cx);
+        });
+        assert_eq!(bookmarked_rows(&editor, cx), [2, 5]);
+
+        cx.dispatch_action(GoToNextBookmark);
+        assert_eq!(cursor_row_of(&editor, cx), 2);
+        cx.dispatch_action(GoToNextBookmark);
+        assert_eq!(cursor_row_of(&editor, cx), 5);
+        cx.dispatch_action(GoToNextBookmark);
+        assert_eq!(cursor_row_of(&editor, cx), 2);
+        cx.dispatch_action(GoToPreviousBookmark);
+        assert_eq!(cursor_row_of(&editor, cx), 5);
+
+        cx.dispatch_action(ToggleBookmark);
+        assert_eq!(bookmarked_rows(&editor, cx), [2]);
+    }
+
+    #[gpui::test]
+    async fn test_persist_and_restore_bookmarks(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.txt": "zero\none\ntwo\nthree\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let persisted_rows = || {
+            crate::persistence::DB
+                .get_bookmarks(workspace_id)
+                .unwrap()
+                .into_iter()
+                .map(|(path, bookmark)| (path, bookmark.row, bookmark.label))
+                .collect::<Vec<_>>()
+        };
+        let path: Arc<Path> = Path::new(path!("/dir/a.txt")).into();
+
+        let bookmark_store =
+            cx.new(|cx| BookmarkStore::for_project(Some(workspace_id), project.clone(), cx));
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/a.txt"), cx)
+            })
+            .await
+            .unwrap();
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.toggle_bookmark(&buffer, 1, cx);
+            bookmark_store.set_label(&buffer, 3, Some("three".into()), cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            persisted_rows(),
+            [
+                (path.clone(), 1, None),
+                (path.clone(), 3, Some("three".into()))
+            ]
+        );
+
+        // Unsaved edits don't move the persisted bookmarks, saving does.
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit(
+                [(Point::new(1, 0)..Point::new(1, 0), "new line\n")],
+                None,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            persisted_rows(),
+            [
+                (path.clone(), 1, None),
+                (path.clone(), 3, Some("three".into()))
+            ]
+        );
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(
+            persisted_rows(),
+            [
+                (path.clone(), 2, None),
+                (path.clone(), 4, Some("three".into()))
+            ]
+        );
+
+        // Another store restores the bookmarks onto the lines they were on.
+        let restored_store =
+            cx.new(|cx| BookmarkStore::for_project(Some(workspace_id), project.clone(), cx));
+        cx.run_until_parked();
+        restored_store.read_with(cx, |bookmark_store, cx| {
+            let entries = bookmark_store
+                .all_bookmarks(cx)
+                .into_iter()
+                .map(|entry| (entry.row, entry.label, entry.line))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                entries,
+                [
+                    (2, None, Some("one".to_string())),
+                    (4, Some("three".into()), Some("three".to_string()))
+                ]
+            );
+        });
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            crate::init(cx);
+            editor::init(cx);
+            state
+        })
+    }
+
+    fn set_cursor_row(editor: &Entity<Editor>, row: u32, cx: &mut VisualTestContext) {
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |selections| {
+                selections.select_ranges([Point::new(row, 0)..Point::new(row, 0)])
+            });
+        });
+    }
+
+    fn cursor_row_of(editor: &Entity<Editor>, cx: &mut VisualTestContext) -> u32 {
+        editor.update(cx, |editor, cx| {
+            editor
+                .selections
+                .newest::<Point>(&editor.display_snapshot(cx))
+                .head()
+                .row
+        })
+    }
+
+    /// Returns the rows highlighted as bookmarked in the editor's gutter.
+    fn bookmarked_rows(editor: &Entity<Editor>, cx: &mut VisualTestContext) -> Vec<u32> {
+        editor.update(cx, |editor, cx| {
+            let display_snapshot = editor.display_snapshot(cx);
+            editor
+                .gutter_highlights_in_range(Anchor::min()..Anchor::max(), &display_snapshot, cx)
+                .into_iter()
+                .map(|(range, _)| range.start.row().0)
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,88 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Result;
+use db::{
+    sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection},
+    sqlez_macros::sql,
+};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+use crate::bookmark_store::SerializedBookmark;
+
+pub struct BookmarksDb(ThreadSafeConnection);
+
+impl Domain for BookmarksDb {
+    const NAME: &str = stringify!(BookmarksDb);
+
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE bookmarks (
+            workspace_id INTEGER,
+            path BLOB,
+            row INTEGER,
+            label TEXT,
+            PRIMARY KEY(workspace_id, path, row),
+            FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+            ON DELETE CASCADE
+        ) STRICT;
+    )];
+}
+
+db::static_connection!(DB, BookmarksDb, [WorkspaceDb]);
+
+impl BookmarksDb {
+    /// Replaces the bookmarks of the file at the given path.
+    pub(crate) async fn set_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        path: Arc<Path>,
+        bookmarks: Vec<SerializedBookmark>,
+    ) -> Result<()> {
+        log::debug!("Setting {} bookmarks for path {path:?}", bookmarks.len());
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM bookmarks
+                WHERE workspace_id = ? AND path = ?
+            ))?((workspace_id, path.clone()))?;
+
+            let mut insert = conn.exec_bound(sql!(
+                INSERT INTO bookmarks
+                    (workspace_id, path, row, label)
+                VALUES
+                    (?, ?, ?, ?)
+            ))?;
+            for bookmark in bookmarks {
+                insert((
+                    workspace_id,
+                    path.clone(),
+                    bookmark.row,
+                    bookmark.label.map(|label| label.to_string()),
+                ))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    pub(crate) fn get_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+    ) -> Result<Vec<(Arc<Path>, SerializedBookmark)>> {
+        let rows: Vec<(Arc<Path>, u32, Option<String>)> = self.select_bound(sql!(
+            SELECT path, row, label FROM bookmarks
+                WHERE workspace_id = ?
+                ORDER BY path, row
+        ))?(workspace_id)?;
+        Ok(rows
+            .into_iter()
+            .map(|(path, row, label)| {
+                (
+                    path,
+                    SerializedBookmark {
+                        row,
+                        label: label.map(Into::into),
+                    },
+                )
+            })
+            .collect())
+    }
+}
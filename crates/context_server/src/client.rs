@@ -22,7 +22,10 @@ use util::{ResultExt, TryFutureExt};
 
 use crate::{
     transport::{StdioTransport, Transport},
-    types::{CancelledParams, ClientNotification, Notification as _, notifications::Cancelled},
+    types::{
+        CancelledParams, ClientNotification, Notification as _, Request as _,
+        notifications::Cancelled, requests::Ping,
+    },
 };
 
 const JSON_RPC_VERSION: &str = "2.0";
@@ -35,9 +38,12 @@ pub const METHOD_NOT_FOUND: i32 = -32601;
 pub const INVALID_PARAMS: i32 = -32602;
 pub const INTERNAL_ERROR: i32 = -32603;
 
+// Error code MCP uses when the user declines a request from the server
+pub const USER_REJECTED: i32 = -1;
+
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
 type NotificationHandler = Box<dyn Send + FnMut(Value, AsyncApp)>;
-type RequestHandler = Box<dyn Send + FnMut(RequestId, &RawValue, AsyncApp)>;
+type RequestHandler = Box<dyn Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -52,6 +58,7 @@ pub(crate) struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
+    request_handlers: Arc<Mutex<RequestHandlerSet>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -101,7 +108,6 @@ struct AnyResponse<'a> {
 }
 
 #[derive(Serialize, Deserialize)]
-#[allow(dead_code)]
 pub(crate) struct Response<T> {
     pub jsonrpc: &'static str,
     pub id: RequestId,
@@ -195,19 +201,21 @@ impl Client {
         let subscription_set = Arc::new(Mutex::new(NotificationSubscriptionSet::default()));
         let response_handlers =
             Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
-        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(RequestHandlerSet::default()));
 
         let receive_input_task = cx.spawn({
             let subscription_set = subscription_set.clone();
             let response_handlers = response_handlers.clone();
             let request_handlers = request_handlers.clone();
             let transport = transport.clone();
+            let outbound_tx = outbound_tx.clone();
             async move |cx| {
                 Self::handle_input(
                     transport,
                     subscription_set,
                     request_handlers,
                     response_handlers,
+                    outbound_tx,
                     cx,
                 )
                 .log_err()
@@ -237,6 +245,7 @@ impl Client {
         Ok(Self {
             server_id,
             subscription_set,
+            request_handlers,
             response_handlers,
             name: server_name,
             next_id: Default::default(),
@@ -252,14 +261,16 @@ impl Client {
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
-    /// parses them as JSON-RPC responses or notifications, and dispatches them
-    /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// parses them as JSON-RPC requests, responses or notifications, and dispatches
+    /// them to the appropriate handlers. It processes requests (which are answered
+    /// by registered handlers), responses (which are matched to pending requests)
+    /// and notifications (which trigger registered handlers).
     async fn handle_input(
         transport: Arc<dyn Transport>,
         subscription_set: Arc<Mutex<NotificationSubscriptionSet>>,
-        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+        request_handlers: Arc<Mutex<RequestHandlerSet>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: &mut AsyncApp,
     ) -> anyhow::Result<()> {
         let mut receiver = transport.receive();
@@ -267,14 +278,7 @@ impl Client {
         while let Some(message) = receiver.next().await {
             log::trace!("recv: {}", &message);
             if let Ok(request) = serde_json::from_str::<AnyRequest>(&message) {
-                let mut request_handlers = request_handlers.lock();
-                if let Some(handler) = request_handlers.get_mut(request.method) {
-                    handler(
-                        request.id,
-                        request.params.unwrap_or(RawValue::NULL),
-                        cx.clone(),
-                    );
-                }
+                Self::handle_request(request, &request_handlers, outbound_tx.clone(), cx);
             } else if let Ok(response) = serde_json::from_str::<AnyResponse>(&message) {
                 if let Some(handlers) = response_handlers.lock().as_mut()
                     && let Some(handler) = handlers.remove(&response.id)
@@ -297,6 +301,62 @@ impl Client {
         Ok(())
     }
 
+    /// Answers a request sent by the context server, using the handler registered for its method.
+    fn handle_request(
+        request: AnyRequest,
+        request_handlers: &Mutex<RequestHandlerSet>,
+        outbound_tx: channel::Sender<String>,
+        cx: &mut AsyncApp,
+    ) {
+        let id = request.id;
+        let method = request.method.to_string();
+        let params = request
+            .params
+            .map_or(Ok(Value::Null), |params| serde_json::from_str(params.get()));
+        let response = match params {
+            Ok(params) => request_handlers
+                .lock()
+                .handlers
+                .get_mut(request.method)
+                .map(|(_, handler)| handler(params, cx.clone())),
+            Err(error) => Some(Task::ready(Err(anyhow!(error)))),
+        };
+
+        cx.spawn(async move |_| {
+            let value = match response {
+                Some(response) => match response.await {
+                    Ok(result) => CspResult::Ok(Some(result)),
+                    Err(error) if error.is::<RequestRejected>() => CspResult::Error(Some(Error {
+                        message: error.to_string(),
+                        code: USER_REJECTED,
+                    })),
+                    Err(error) => {
+                        log::warn!("failed to handle context server request {method:?}: {error:#}");
+                        CspResult::Error(Some(Error {
+                            message: error.to_string(),
+                            code: INTERNAL_ERROR,
+                        }))
+                    }
+                },
+                None if method == Ping::METHOD => {
+                    CspResult::Ok(Some(Value::Object(Default::default())))
+                }
+                None => CspResult::Error(Some(Error {
+                    message: format!("Method not found: {method}"),
+                    code: METHOD_NOT_FOUND,
+                })),
+            };
+            let response = serde_json::to_string(&Response {
+                jsonrpc: JSON_RPC_VERSION,
+                id,
+                value,
+            })
+            .unwrap();
+            outbound_tx.try_send(response).log_err();
+        })
+        .detach();
+    }
+
     /// Handles the stderr output from the context server.
     /// Continuously reads and logs any error messages from the server.
     async fn handle_err(transport: Arc<dyn Transport>) -> anyhow::Result<()> {
@@ -439,6 +499,11 @@ impl Client {
         }
     }
 
+    /// Forwards the protocol version negotiated during initialization to the transport.
+    pub(crate) fn set_protocol_version(&self, version: &str) {
+        self.transport.set_protocol_version(version);
+    }
+
     /// Sends a notification to the context server without expecting a response.
     /// This function serializes the notification and sends it through the outbound channel.
     pub fn notify(&self, method: &str, params: impl Serialize) -> Result<()> {
@@ -465,6 +530,27 @@ impl Client {
             set: self.subscription_set.clone(),
         }
     }
+
+    /// Registers a handler for requests sent by the context server.
+    ///
+    /// Only one handler can be registered for a method; registering another one replaces it.
+    /// Requests without a handler are answered with a "method not found" error.
+    #[must_use]
+    pub fn on_request(
+        &self,
+        method: &'static str,
+        f: Box<dyn 'static + Send + FnMut(Value, AsyncApp) -> Task<Result<Value>>>,
+    ) -> RequestSubscription {
+        let mut request_handlers = self.request_handlers.lock();
+        request_handlers.next_id += 1;
+        let id = request_handlers.next_id;
+        request_handlers.handlers.insert(method, (id, f));
+        RequestSubscription {
+            method,
+            id,
+            set: self.request_handlers.clone(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -478,6 +564,18 @@ impl std::fmt::Display for RequestCanceled {
     }
 }
 
+/// Returned by request handlers when the user declined a request from the server.
+#[derive(Debug)]
+pub struct RequestRejected;
+
+impl std::error::Error for RequestRejected {}
+
+impl std::fmt::Display for RequestRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("User rejected the request")
+    }
+}
+
 impl fmt::Display for ContextServerId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
@@ -552,6 +650,31 @@ pub struct NotificationSubscription {
     set: Arc<Mutex<NotificationSubscriptionSet>>,
 }
 
+#[derive(Default)]
+struct RequestHandlerSet {
+    next_id: usize,
+    handlers: HashMap<&'static str, (usize, RequestHandler)>,
+}
+
+pub struct RequestSubscription {
+    method: &'static str,
+    id: usize,
+    set: Arc<Mutex<RequestHandlerSet>>,
+}
+
+impl Drop for RequestSubscription {
+    fn drop(&mut self) {
+        let mut set = self.set.lock();
+        if set
+            .handlers
+            .get(self.method)
+            .is_some_and(|(id, _)| *id == self.id)
+        {
+            set.handlers.remove(self.method);
+        }
+    }
+}
+
 impl Drop for NotificationSubscription {
     fn drop(&mut self) {
         let mut set = self.set.lock();
@@ -562,3 +685,105 @@ impl Drop for NotificationSubscription {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::FakeTransport;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_handle_request(cx: &mut TestAppContext) {
+        let (responses_tx, mut responses_rx) = futures::channel::mpsc::unbounded();
+        let transport = FakeTransport::new(cx.executor())
+            .on_response(move |response| responses_tx.unbounded_send(response).unwrap());
+        let server_messages = transport.message_sender();
+        let client = Client::new(
+            ContextServerId("test".into()),
+            "test".into(),
+            Arc::new(transport),
+            None,
+            cx.to_async(),
+        )
+        .unwrap();
+
+        let _echo = client.on_request(
+            "sampling/createMessage",
+            Box::new(|params: Value, _: AsyncApp| Task::ready(Ok(json!({ "echo": params })))),
+        );
+        let _reject = client.on_request(
+            "elicitation/create",
+            Box::new(|_: Value, _: AsyncApp| Task::ready(Err(RequestRejected.into()))),
+        );
+        let _fail = client.on_request(
+            "roots/list",
+            Box::new(|_: Value, _: AsyncApp| Task::ready(Err(anyhow!("no roots")))),
+        );
+
+        let requests = [
+            (1, "sampling/createMessage", json!({ "maxTokens": 10 })),
+            (2, "elicitation/create", json!({})),
+            (3, "roots/list", json!({})),
+            (4, "ping", json!({})),
+            (5, "unknown/method", json!({})),
+        ];
+        for (id, method, params) in requests {
+            server_messages
+                .unbounded_send(
+                    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+                        .to_string(),
+                )
+                .unwrap();
+        }
+        cx.run_until_parked();
+
+        let mut responses = HashMap::default();
+        while let Ok(Some(response)) = responses_rx.try_next() {
+            responses.insert(response["id"].as_u64().unwrap(), response);
+        }
+        assert_eq!(responses.len(), 5);
+        assert_eq!(
+            responses[&1]["result"],
+            json!({ "echo": { "maxTokens": 10 } })
+        );
+        assert_eq!(responses[&2]["error"]["code"], json!(USER_REJECTED));
+        assert_eq!(responses[&3]["error"]["code"], json!(INTERNAL_ERROR));
+        assert_eq!(responses[&3]["error"]["message"], json!("no roots"));
+        assert_eq!(responses[&4]["result"], json!({}));
+        assert_eq!(responses[&5]["error"]["code"], json!(METHOD_NOT_FOUND));
+    }
+
+    #[gpui::test]
+    async fn test_request_handler_unregistered_on_drop(cx: &mut TestAppContext) {
+        let (responses_tx, mut responses_rx) = futures::channel::mpsc::unbounded();
+        let transport = FakeTransport::new(cx.executor())
+            .on_response(move |response| responses_tx.unbounded_send(response).unwrap());
+        let server_messages = transport.message_sender();
+        let client = Client::new(
+            ContextServerId("test".into()),
+            "test".into(),
+            Arc::new(transport),
+            None,
+            cx.to_async(),
+        )
+        .unwrap();
+
+        let subscription = client.on_request(
+            "sampling/createMessage",
+            Box::new(|_: Value, _: AsyncApp| Task::ready(Ok(json!({})))),
+        );
+        drop(subscription);
+
+        server_messages
+            .unbounded_send(
+                json!({ "jsonrpc": "2.0", "id": 1, "method": "sampling/createMessage" })
+                    .to_string(),
+            )
+            .unwrap();
+        cx.run_until_parked();
+
+        let response = responses_rx.try_next().unwrap().unwrap();
+        assert_eq!(response["error"]["code"], json!(METHOD_NOT_FOUND));
+    }
+}
@@ -5,6 +5,7 @@ pub mod protocol;
 pub mod test;
 pub mod transport;
 pub mod types;
+pub mod uri_template;
 
 use collections::HashMap;
 use http_client::HttpClient;
@@ -9,10 +9,10 @@ use std::time::Duration;
 
 use anyhow::Result;
 use futures::channel::oneshot;
-use gpui::AsyncApp;
+use gpui::{AsyncApp, Task};
 use serde_json::Value;
 
-use crate::client::{Client, NotificationSubscription};
+use crate::client::{Client, NotificationSubscription, RequestSubscription};
 use crate::types::{self, Notification, Request};
 
 pub struct ModelContextProtocol {
@@ -27,6 +27,7 @@ impl ModelContextProtocol {
     fn supported_protocols() -> Vec<types::ProtocolVersion> {
         vec![
             types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
+            types::ProtocolVersion(types::VERSION_2025_03_26.to_string()),
             types::ProtocolVersion(types::VERSION_2024_11_05.to_string()),
         ]
     }
@@ -39,7 +40,8 @@ impl ModelContextProtocol {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
+                sampling: Some(Value::Object(Default::default())),
+                elicitation: Some(Value::Object(Default::default())),
                 roots: None,
             },
             meta: None,
@@ -58,6 +60,8 @@ impl ModelContextProtocol {
         );
 
         log::trace!("mcp server info {:?}", response.server_info);
+        self.inner
+            .set_protocol_version(&response.protocol_version.0);
 
         let initialized_protocol = InitializedContextServerProtocol {
             inner: self.inner,
@@ -122,4 +126,23 @@ impl InitializedContextServerProtocol {
     ) -> NotificationSubscription {
         self.inner.on_notification(method, f)
     }
+
+    /// Registers a handler for requests of type `T` sent by the server, such as sampling or
+    /// elicitation requests. The handler stays registered until the subscription is dropped.
+    pub fn on_request<T: Request>(
+        &self,
+        mut f: impl 'static + Send + FnMut(T::Params, AsyncApp) -> Task<Result<T::Response>>,
+    ) -> RequestSubscription {
+        self.inner.on_request(
+            T::METHOD,
+            Box::new(move |params, cx| {
+                let params = match serde_json::from_value(params) {
+                    Ok(params) => params,
+                    Err(error) => return Task::ready(Err(error.into())),
+                };
+                let response = f(params, cx.clone());
+                cx.spawn(async move |_| Ok(serde_json::to_value(response.await?)?))
+            }),
+        )
+    }
 }
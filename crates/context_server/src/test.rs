@@ -39,6 +39,7 @@ pub struct FakeTransport {
         &'static str,
         Arc<dyn Send + Sync + Fn(serde_json::Value) -> BoxFuture<'static, serde_json::Value>>,
     >,
+    response_handler: Option<Arc<dyn Send + Sync + Fn(serde_json::Value)>>,
     tx: futures::channel::mpsc::UnboundedSender<String>,
     rx: Arc<Mutex<futures::channel::mpsc::UnboundedReceiver<String>>>,
     executor: BackgroundExecutor,
@@ -49,6 +50,7 @@ impl FakeTransport {
         let (tx, rx) = futures::channel::mpsc::unbounded();
         Self {
             request_handlers: Default::default(),
+            response_handler: None,
             tx,
             rx: Arc::new(Mutex::new(rx)),
             executor,
@@ -78,6 +80,20 @@ impl FakeTransport {
         );
         self
     }
+
+    /// Calls `handler` with every response the client sends to a request from the server.
+    pub fn on_response(
+        mut self,
+        handler: impl 'static + Send + Sync + Fn(serde_json::Value),
+    ) -> Self {
+        self.response_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Returns a sender for pushing raw messages, such as notifications, to the client.
+    pub fn message_sender(&self) -> futures::channel::mpsc::UnboundedSender<String> {
+        self.tx.clone()
+    }
 }
 
 #[async_trait::async_trait]
@@ -101,6 +117,8 @@ impl Transport for FakeTransport {
                 } else {
                     log::debug!("No handler registered for MCP request '{method}'");
                 }
+            } else if let Some(handler) = &self.response_handler {
+                handler(msg);
             }
         }
         Ok(())
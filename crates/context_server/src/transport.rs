@@ -14,4 +14,7 @@ pub trait Transport: Send + Sync {
     async fn send(&self, message: String) -> Result<()>;
     fn receive(&self) -> Pin<Box<dyn Stream<Item = String> + Send>>;
     fn receive_err(&self) -> Pin<Box<dyn Stream<Item = String> + Send>>;
+
+    /// Called with the protocol version negotiated during initialization.
+    fn set_protocol_version(&self, _version: &str) {}
 }
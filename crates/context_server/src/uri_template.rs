@@ -0,0 +1,191 @@
+//! Matching of user input against the URI templates of MCP resource templates.
+//!
+//! Resource templates use [RFC 6570](https://datatracker.ietf.org/doc/html/rfc6570) URI
+//! templates. Only simple string expansion (`{name}`) is supported, which is what servers use in
+//! practice. Operators such as `{+path}` are treated as simple expansion of the named variable.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Variable(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriTemplate {
+    segments: Vec<Segment>,
+}
+
+/// The result of matching a partially typed URI against a [`UriTemplate`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct UriTemplateMatch<'a> {
+    /// The variables whose values have been entered completely, with their values.
+    pub arguments: Vec<(&'a str, &'a str)>,
+    /// The variable whose value is currently being entered, or `None` if the input expands the
+    /// whole template.
+    pub pending: Option<PendingVariable<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct PendingVariable<'a> {
+    pub name: &'a str,
+    /// The part of the value that has been entered so far.
+    pub value: &'a str,
+    segment_ix: usize,
+    offset: usize,
+}
+
+impl UriTemplate {
+    pub fn parse(template: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut rest = template;
+        while !rest.is_empty() {
+            let variable = rest.find('{').and_then(|start| {
+                let end = start + rest[start..].find('}')?;
+                Some((start, end))
+            });
+            let Some((start, end)) = variable else {
+                push_literal(&mut segments, rest);
+                break;
+            };
+            push_literal(&mut segments, &rest[..start]);
+            let name = rest[start + 1..end].trim_start_matches(['+', '#', '.', '/', ';', '?', '&']);
+            segments.push(Segment::Variable(name.to_string()));
+            rest = &rest[end + 1..];
+        }
+        Self { segments }
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = &str> {
+        self.segments.iter().filter_map(|segment| match segment {
+            Segment::Variable(name) => Some(name.as_str()),
+            Segment::Literal(_) => None,
+        })
+    }
+
+    /// The literal text preceding the first variable of the template.
+    pub fn prefix(&self) -> &str {
+        match self.segments.first() {
+            Some(Segment::Literal(literal)) => literal,
+            _ => "",
+        }
+    }
+
+    /// Matches a partially typed URI against the template.
+    ///
+    /// Returns `None` if the input doesn't follow the template, or hasn't reached the first
+    /// variable yet.
+    pub fn match_partial<'a>(&'a self, input: &'a str) -> Option<UriTemplateMatch<'a>> {
+        let mut arguments = Vec::new();
+        let mut rest = input;
+        for (segment_ix, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Literal(literal) => {
+                    rest = rest.strip_prefix(literal.as_str())?;
+                }
+                Segment::Variable(name) => {
+                    let value_end = match self.segments.get(segment_ix + 1) {
+                        Some(Segment::Literal(literal)) => rest.find(literal.as_str()),
+                        _ => None,
+                    };
+                    let Some(value_end) = value_end else {
+                        return Some(UriTemplateMatch {
+                            arguments,
+                            pending: Some(PendingVariable {
+                                name,
+                                value: rest,
+                                segment_ix,
+                                offset: input.len() - rest.len(),
+                            }),
+                        });
+                    };
+                    arguments.push((name.as_str(), &rest[..value_end]));
+                    rest = &rest[value_end..];
+                }
+            }
+        }
+        rest.is_empty().then_some(UriTemplateMatch {
+            arguments,
+            pending: None,
+        })
+    }
+
+    /// Replaces the value of the pending variable in `input` with `value`, appending the literal
+    /// text that follows it in the template.
+    ///
+    /// Returns the new input, and whether it expands the whole template.
+    pub fn complete_pending(
+        &self,
+        input: &str,
+        pending: &PendingVariable,
+        value: &str,
+    ) -> (String, bool) {
+        let mut result = format!("{}{}", &input[..pending.offset], value);
+        for segment in &self.segments[pending.segment_ix + 1..] {
+            match segment {
+                Segment::Literal(literal) => result.push_str(literal),
+                Segment::Variable(_) => return (result, false),
+            }
+        }
+        (result, true)
+    }
+}
+
+fn push_literal(segments: &mut Vec<Segment>, literal: &str) {
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let template = UriTemplate::parse("postgres://{database}/tables/{+table}/schema");
+        assert_eq!(template.prefix(), "postgres://");
+        assert_eq!(
+            template.variables().collect::<Vec<_>>(),
+            vec!["database", "table"]
+        );
+        assert_eq!(UriTemplate::parse("{path}").prefix(), "");
+        assert_eq!(UriTemplate::parse("file:///{path").variables().count(), 0);
+    }
+
+    #[test]
+    fn test_match_partial() {
+        let template = UriTemplate::parse("postgres://{database}/tables/{table}/schema");
+
+        assert_eq!(template.match_partial("postgres:"), None);
+        assert_eq!(template.match_partial("mysql://"), None);
+
+        let input = "postgres://";
+        let result = template.match_partial(input).unwrap();
+        assert!(result.arguments.is_empty());
+        let pending = result.pending.unwrap();
+        assert_eq!((pending.name, pending.value), ("database", ""));
+        assert_eq!(
+            template.complete_pending(input, &pending, "main"),
+            ("postgres://main/tables/".to_string(), false)
+        );
+
+        let input = "postgres://main/tables/us";
+        let result = template.match_partial(input).unwrap();
+        assert_eq!(result.arguments, vec![("database", "main")]);
+        let pending = result.pending.unwrap();
+        assert_eq!((pending.name, pending.value), ("table", "us"));
+        assert_eq!(
+            template.complete_pending(input, &pending, "users"),
+            ("postgres://main/tables/users/schema".to_string(), true)
+        );
+
+        let result = template
+            .match_partial("postgres://main/tables/users/schema")
+            .unwrap();
+        assert_eq!(
+            result.arguments,
+            vec![("database", "main"), ("table", "users")]
+        );
+        assert_eq!(result.pending, None);
+    }
+}
@@ -164,6 +164,7 @@ impl DebugTaskDefinition {
             build: None,
             tcp_connection: self.tcp_connection.clone(),
             config: self.config.clone(),
+            inputs: Vec::new(),
         }
     }
 
@@ -448,6 +449,7 @@ impl DebugAdapter for FakeAdapter {
             build: None,
             config,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
@@ -130,6 +130,7 @@ impl DebugAdapter for CodeLldbDebugAdapter {
             config: configuration,
             build: None,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
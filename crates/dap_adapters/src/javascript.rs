@@ -230,6 +230,7 @@ impl DebugAdapter for JsDebugAdapter {
             build: None,
             config: args,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
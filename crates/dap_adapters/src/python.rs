@@ -465,6 +465,7 @@ impl DebugAdapter for PythonDebugAdapter {
             config: args,
             build: None,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
@@ -986,12 +986,27 @@ impl RunningState {
             .and_then(|remote| remote.read(cx).shell());
 
         cx.spawn_in(window, async move |this, cx| {
+            let inputs = scenario.inputs();
+            let scenario = if inputs.is_empty() {
+                scenario
+            } else {
+                let values = weak_workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        workspace.prompt_for_task_inputs(inputs, window, cx)
+                    })?
+                    .await;
+                let Some(values) = values else {
+                    bail!("No values entered for the inputs of debug scenario `{}`", scenario.label)
+                };
+                scenario.with_input_values(&values)
+            };
             let DebugScenario {
                 adapter,
                 label,
                 build,
                 mut config,
                 tcp_connection,
+                inputs: _,
             } = scenario;
             Self::relativize_paths(None, &mut config, &task_context);
             Self::substitute_variables_in_config(&mut config, &task_context);
@@ -1275,6 +1290,7 @@ impl RunningState {
             show_summary: false,
             show_command: false,
             show_rerun: false,
+            problem_matchers: Vec::new(),
         };
 
         let workspace = self.workspace.clone();
@@ -80,6 +80,7 @@ impl Console {
             editor.set_input_enabled(false);
             editor.set_use_autoclose(false);
             editor.set_show_wrap_guides(false, cx);
+            editor.set_show_rulers(false, cx);
             editor.set_show_indent_guides(false, cx);
             editor.set_show_edit_predictions(Some(false), window, cx);
             editor.set_use_modal_editing(false);
@@ -95,6 +96,7 @@ impl Console {
             editor.set_use_autoclose(false);
             editor.set_show_gutter(false, cx);
             editor.set_show_wrap_guides(false, cx);
+            editor.set_show_rulers(false, cx);
             editor.set_show_indent_guides(false, cx);
             editor.set_completion_provider(Some(Rc::new(ConsoleQueryBarCompletionProvider(this))));
 
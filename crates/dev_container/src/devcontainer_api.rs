@@ -12,7 +12,11 @@ use settings::{DevContainerConnection, Settings as _};
 use smol::{fs, process::Command};
 use workspace::Workspace;
 
-use crate::{DevContainerFeature, DevContainerSettings, DevContainerTemplate};
+use crate::{
+    DevContainerFeature, DevContainerSettings, DevContainerTemplate,
+    devcontainer_json::DevContainerConfig,
+    docker::{StartedDevContainer, devcontainer_up},
+};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -29,17 +33,6 @@ pub(crate) struct DevContainerApply {
     pub(crate) files: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct DevContainerConfiguration {
-    name: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-pub(crate) struct DevContainerConfigurationOutput {
-    configuration: DevContainerConfiguration,
-}
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DevContainerError {
     DockerNotAvailable,
@@ -82,22 +75,12 @@ impl Display for DevContainerError {
 
 pub(crate) async fn read_devcontainer_configuration_for_project(
     cx: &mut AsyncWindowContext,
-    node_runtime: &NodeRuntime,
-) -> Result<DevContainerConfigurationOutput, DevContainerError> {
-    let (path_to_devcontainer_cli, found_in_path) = ensure_devcontainer_cli(&node_runtime).await?;
-
+) -> Result<DevContainerConfig, DevContainerError> {
     let Some(directory) = project_directory(cx) else {
         return Err(DevContainerError::NotInValidProject);
     };
 
-    devcontainer_read_configuration(
-        &path_to_devcontainer_cli,
-        found_in_path,
-        node_runtime,
-        &directory,
-        use_podman(cx),
-    )
-    .await
+    DevContainerConfig::read(&directory).await
 }
 
 pub(crate) async fn apply_dev_container_template(
@@ -138,43 +121,44 @@ pub async fn start_dev_container(
     let use_podman = use_podman(cx);
     check_for_docker(use_podman).await?;
 
-    let (path_to_devcontainer_cli, found_in_path) = ensure_devcontainer_cli(&node_runtime).await?;
-
     let Some(directory) = project_directory(cx) else {
         return Err(DevContainerError::NotInValidProject);
     };
 
-    match devcontainer_up(
-        &path_to_devcontainer_cli,
-        found_in_path,
-        &node_runtime,
-        directory.clone(),
-        use_podman,
-    )
-    .await
-    {
-        Ok(DevContainerUp {
+    let config = DevContainerConfig::read(&directory).await?;
+
+    // Installing features requires fetching them from OCI registries and building
+    // an image on top of them, which is left to the devcontainer CLI.
+    let up_result = if config.json.features.is_empty() {
+        devcontainer_up(&config, use_podman).await
+    } else {
+        log::info!(
+            "devcontainer.json uses features, starting the dev container with the devcontainer CLI"
+        );
+        let (path_to_devcontainer_cli, found_in_path) =
+            ensure_devcontainer_cli(&node_runtime).await?;
+        devcontainer_cli_up(
+            &path_to_devcontainer_cli,
+            found_in_path,
+            &node_runtime,
+            directory,
+            use_podman,
+        )
+        .await
+        .map(|up| StartedDevContainer {
+            container_id: up.container_id,
+            remote_workspace_folder: up.remote_workspace_folder,
+        })
+    };
+
+    match up_result {
+        Ok(StartedDevContainer {
             container_id,
             remote_workspace_folder,
-            ..
         }) => {
-            let project_name = match devcontainer_read_configuration(
-                &path_to_devcontainer_cli,
-                found_in_path,
-                &node_runtime,
-                &directory,
-                use_podman,
-            )
-            .await
-            {
-                Ok(DevContainerConfigurationOutput {
-                    configuration:
-                        DevContainerConfiguration {
-                            name: Some(project_name),
-                        },
-                }) => project_name,
-                _ => get_backup_project_name(&remote_workspace_folder, &container_id),
-            };
+            let project_name = config.json.name.clone().unwrap_or_else(|| {
+                get_backup_project_name(&remote_workspace_folder, &container_id)
+            });
 
             let connection = DevContainerConnection {
                 name: project_name,
@@ -306,7 +290,7 @@ async fn ensure_devcontainer_cli(
     }
 }
 
-async fn devcontainer_up(
+async fn devcontainer_cli_up(
     path_to_cli: &PathBuf,
     found_in_path: bool,
     node_runtime: &NodeRuntime,
@@ -355,52 +339,6 @@ async fn devcontainer_up(
         }
     }
 }
-async fn devcontainer_read_configuration(
-    path_to_cli: &PathBuf,
-    found_in_path: bool,
-    node_runtime: &NodeRuntime,
-    path: &Arc<Path>,
-    use_podman: bool,
-) -> Result<DevContainerConfigurationOutput, DevContainerError> {
-    let Ok(node_runtime_path) = node_runtime.binary_path().await else {
-        log::error!("Unable to find node runtime path");
-        return Err(DevContainerError::NodeRuntimeNotAvailable);
-    };
-
-    let mut command =
-        devcontainer_cli_command(path_to_cli, found_in_path, &node_runtime_path, use_podman);
-    command.arg("read-configuration");
-    command.arg("--workspace-folder");
-    command.arg(path.display().to_string());
-
-    match command.output().await {
-        Ok(output) => {
-            if output.status.success() {
-                let raw = String::from_utf8_lossy(&output.stdout);
-                serde_json::from_str::<DevContainerConfigurationOutput>(&raw).map_err(|e| {
-                    log::error!(
-                        "Unable to parse response from 'devcontainer read-configuration' command, error: {:?}",
-                        e
-                    );
-                    DevContainerError::DevContainerParseFailed
-                })
-            } else {
-                let message = format!(
-                    "Non-success status running devcontainer read-configuration for workspace: out: {:?}, err: {:?}",
-                    String::from_utf8_lossy(&output.stdout),
-                    String::from_utf8_lossy(&output.stderr)
-                );
-                log::error!("{}", &message);
-                Err(DevContainerError::DevContainerNotFound)
-            }
-        }
-        Err(e) => {
-            let message = format!("Error running devcontainer read-configuration: {:?}", e);
-            log::error!("{}", &message);
-            Err(DevContainerError::DevContainerNotFound)
-        }
-    }
-}
 
 async fn devcontainer_template_apply(
     template: &DevContainerTemplate,
@@ -0,0 +1,582 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use serde_json::Value;
+use smol::{fs, stream::StreamExt as _};
+
+use crate::devcontainer_api::DevContainerError;
+
+/// The subset of `devcontainer.json` needed to start a dev container without the devcontainer CLI.
+/// See https://containers.dev/implementors/json_reference/
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DevContainerJson {
+    pub(crate) name: Option<String>,
+    pub(crate) image: Option<String>,
+    pub(crate) build: Option<DevContainerBuild>,
+    /// Legacy spelling of `build.dockerfile`.
+    #[serde(rename = "dockerFile")]
+    pub(crate) docker_file: Option<String>,
+    /// Legacy spelling of `build.context`.
+    pub(crate) context: Option<String>,
+    pub(crate) docker_compose_file: Option<ComposeFiles>,
+    pub(crate) service: Option<String>,
+    #[serde(default)]
+    pub(crate) run_services: Vec<String>,
+    pub(crate) workspace_mount: Option<String>,
+    #[serde(default)]
+    pub(crate) mounts: Vec<Mount>,
+    #[serde(default)]
+    pub(crate) forward_ports: Vec<ForwardPort>,
+    pub(crate) app_port: Option<AppPorts>,
+    #[serde(default)]
+    pub(crate) container_env: BTreeMap<String, String>,
+    #[serde(default)]
+    pub(crate) remote_env: BTreeMap<String, Option<String>>,
+    pub(crate) container_user: Option<String>,
+    pub(crate) remote_user: Option<String>,
+    #[serde(default)]
+    pub(crate) run_args: Vec<String>,
+    pub(crate) override_command: Option<bool>,
+    #[serde(default)]
+    pub(crate) init: bool,
+    #[serde(default)]
+    pub(crate) privileged: bool,
+    #[serde(default)]
+    pub(crate) cap_add: Vec<String>,
+    #[serde(default)]
+    pub(crate) security_opt: Vec<String>,
+    #[serde(default)]
+    pub(crate) features: HashMap<String, Value>,
+    pub(crate) initialize_command: Option<LifecycleCommand>,
+    pub(crate) on_create_command: Option<LifecycleCommand>,
+    pub(crate) update_content_command: Option<LifecycleCommand>,
+    pub(crate) post_create_command: Option<LifecycleCommand>,
+    pub(crate) post_start_command: Option<LifecycleCommand>,
+    pub(crate) post_attach_command: Option<LifecycleCommand>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DevContainerBuild {
+    pub(crate) dockerfile: Option<String>,
+    pub(crate) context: Option<String>,
+    #[serde(default)]
+    pub(crate) args: BTreeMap<String, String>,
+    pub(crate) target: Option<String>,
+    #[serde(default)]
+    pub(crate) options: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ComposeFiles {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Mount {
+    /// A mount in the `--mount` flag syntax, e.g. `source=cache,target=/cache,type=volume`.
+    Spec(String),
+    Object {
+        source: Option<String>,
+        target: String,
+        #[serde(rename = "type")]
+        kind: Option<String>,
+    },
+}
+
+impl Mount {
+    /// The mount as an argument to the `--mount` flag.
+    pub(crate) fn to_mount_arg(&self) -> String {
+        match self {
+            Mount::Spec(spec) => spec.clone(),
+            Mount::Object {
+                source,
+                target,
+                kind,
+            } => {
+                let mut arg = format!("type={}", kind.as_deref().unwrap_or("bind"));
+                if let Some(source) = source {
+                    arg.push_str(&format!(",source={source}"));
+                }
+                arg.push_str(&format!(",target={target}"));
+                arg
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ForwardPort {
+    Port(u16),
+    /// Either a port number, or a `host:port` pair referring to another compose service.
+    Named(String),
+}
+
+impl ForwardPort {
+    /// The port of the dev container itself, if this entry refers to one.
+    pub(crate) fn container_port(&self) -> Option<u16> {
+        match self {
+            ForwardPort::Port(port) => Some(*port),
+            ForwardPort::Named(name) => name.parse().ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum AppPorts {
+    One(AppPort),
+    Many(Vec<AppPort>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum AppPort {
+    Port(u16),
+    /// A port mapping in the `--publish` flag syntax, e.g. `8000:8010`.
+    Mapping(String),
+}
+
+impl AppPorts {
+    /// The ports as arguments to the `--publish` flag. Single ports are only published on the
+    /// loopback interface, like the devcontainer CLI does, rather than to the whole network.
+    pub(crate) fn to_publish_args(&self) -> Vec<String> {
+        let ports = match self {
+            AppPorts::One(port) => std::slice::from_ref(port),
+            AppPorts::Many(ports) => ports.as_slice(),
+        };
+        ports
+            .iter()
+            .map(|port| match port {
+                AppPort::Port(port) => format!("127.0.0.1:{port}:{port}"),
+                AppPort::Mapping(mapping) => mapping.clone(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum LifecycleCommand {
+    /// A command run through a shell.
+    Shell(String),
+    /// A program with its arguments, run without a shell.
+    Exec(Vec<String>),
+    /// Named commands run in parallel.
+    Parallel(BTreeMap<String, LifecycleCommand>),
+}
+
+/// Where the image of the dev container comes from.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum ContainerSource {
+    Image(String),
+    Dockerfile {
+        dockerfile: PathBuf,
+        context: PathBuf,
+    },
+    Compose {
+        files: Vec<PathBuf>,
+        service: String,
+    },
+}
+
+/// A `devcontainer.json` found in a project, with its variables substituted.
+#[derive(Debug)]
+pub(crate) struct DevContainerConfig {
+    pub(crate) config_file: PathBuf,
+    pub(crate) local_workspace_folder: PathBuf,
+    pub(crate) container_workspace_folder: String,
+    pub(crate) json: DevContainerJson,
+}
+
+impl DevContainerConfig {
+    pub(crate) async fn read(
+        local_workspace_folder: &Path,
+    ) -> Result<DevContainerConfig, DevContainerError> {
+        let Some(config_file) = find_config_file(local_workspace_folder).await else {
+            return Err(DevContainerError::DevContainerNotFound);
+        };
+        let contents = fs::read_to_string(&config_file).await.map_err(|e| {
+            log::error!("Unable to read {}: {:?}", config_file.display(), e);
+            DevContainerError::DevContainerNotFound
+        })?;
+        Self::parse(&contents, config_file, local_workspace_folder)
+    }
+
+    pub(crate) fn parse(
+        contents: &str,
+        config_file: PathBuf,
+        local_workspace_folder: &Path,
+    ) -> Result<DevContainerConfig, DevContainerError> {
+        let mut value = settings::parse_json_with_comments::<Value>(contents).map_err(|e| {
+            log::error!("Unable to parse {}: {:?}", config_file.display(), e);
+            DevContainerError::DevContainerParseFailed
+        })?;
+
+        let mut variables = Variables {
+            local_workspace_folder,
+            container_workspace_folder: None,
+        };
+        let container_workspace_folder = match value.get("workspaceFolder") {
+            Some(Value::String(folder)) => variables.substitute(folder),
+            _ if value.get("dockerComposeFile").is_some() => "/".to_string(),
+            _ => format!("/workspaces/{}", basename(local_workspace_folder)),
+        };
+        variables.container_workspace_folder = Some(&container_workspace_folder);
+        substitute_variables(&mut value, &variables);
+
+        let json = serde_json::from_value::<DevContainerJson>(value).map_err(|e| {
+            log::error!("Unexpected contents in {}: {:?}", config_file.display(), e);
+            DevContainerError::DevContainerParseFailed
+        })?;
+        Ok(DevContainerConfig {
+            config_file,
+            local_workspace_folder: local_workspace_folder.to_path_buf(),
+            container_workspace_folder,
+            json,
+        })
+    }
+
+    fn config_dir(&self) -> &Path {
+        self.config_file
+            .parent()
+            .unwrap_or(&self.local_workspace_folder)
+    }
+
+    pub(crate) fn source(&self) -> Result<ContainerSource, DevContainerError> {
+        let config_dir = self.config_dir();
+        if let Some(compose_files) = &self.json.docker_compose_file {
+            let Some(service) = self.json.service.clone() else {
+                return Err(DevContainerError::DevContainerUpFailed(
+                    "`service` is required when `dockerComposeFile` is set".to_string(),
+                ));
+            };
+            let files = match compose_files {
+                ComposeFiles::One(file) => vec![config_dir.join(file)],
+                ComposeFiles::Many(files) => {
+                    files.iter().map(|file| config_dir.join(file)).collect()
+                }
+            };
+            return Ok(ContainerSource::Compose { files, service });
+        }
+
+        let build = self.json.build.as_ref();
+        let dockerfile = build
+            .and_then(|build| build.dockerfile.as_ref())
+            .or(self.json.docker_file.as_ref());
+        if let Some(dockerfile) = dockerfile {
+            let context = build
+                .and_then(|build| build.context.as_ref())
+                .or(self.json.context.as_ref())
+                .map_or_else(
+                    || config_dir.to_path_buf(),
+                    |context| config_dir.join(context),
+                );
+            return Ok(ContainerSource::Dockerfile {
+                dockerfile: config_dir.join(dockerfile),
+                context,
+            });
+        }
+
+        match &self.json.image {
+            Some(image) => Ok(ContainerSource::Image(image.clone())),
+            None => Err(DevContainerError::DevContainerUpFailed(
+                "devcontainer.json needs one of `image`, `build.dockerfile` or `dockerComposeFile`"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// The mount of the project into the container, unless a compose file takes care of it.
+    pub(crate) fn workspace_mount(&self) -> String {
+        self.json.workspace_mount.clone().unwrap_or_else(|| {
+            format!(
+                "type=bind,source={},target={},consistency=cached",
+                self.local_workspace_folder.display(),
+                self.container_workspace_folder
+            )
+        })
+    }
+
+    pub(crate) fn project_name(&self) -> String {
+        basename(&self.local_workspace_folder)
+    }
+}
+
+/// Looks for the configuration in the places the devcontainer CLI looks at, in the same order.
+async fn find_config_file(local_workspace_folder: &Path) -> Option<PathBuf> {
+    let devcontainer_dir = local_workspace_folder.join(".devcontainer");
+    for candidate in [
+        devcontainer_dir.join("devcontainer.json"),
+        local_workspace_folder.join(".devcontainer.json"),
+    ] {
+        if fs::metadata(&candidate).await.is_ok_and(|m| m.is_file()) {
+            return Some(candidate);
+        }
+    }
+
+    let mut nested_configs = Vec::new();
+    if let Ok(mut entries) = fs::read_dir(&devcontainer_dir).await {
+        while let Some(Ok(entry)) = entries.next().await {
+            let candidate = entry.path().join("devcontainer.json");
+            if fs::metadata(&candidate).await.is_ok_and(|m| m.is_file()) {
+                nested_configs.push(candidate);
+            }
+        }
+    }
+    nested_configs.sort();
+    nested_configs.into_iter().next()
+}
+
+fn basename(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+struct Variables<'a> {
+    local_workspace_folder: &'a Path,
+    container_workspace_folder: Option<&'a str>,
+}
+
+impl Variables<'_> {
+    /// Replaces the `${variable}` references that can be resolved on the host,
+    /// keeping the other ones (e.g. `${containerEnv:PATH}`) as they are.
+    fn substitute(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find("${") {
+            let Some(len) = rest[start..].find('}') else {
+                break;
+            };
+            let name = &rest[start + 2..start + len];
+            result.push_str(&rest[..start]);
+            match self.resolve(name) {
+                Some(value) => result.push_str(&value),
+                None => result.push_str(&rest[start..=start + len]),
+            }
+            rest = &rest[start + len + 1..];
+        }
+        result.push_str(rest);
+        result
+    }
+
+    fn resolve(&self, name: &str) -> Option<String> {
+        match name {
+            "localWorkspaceFolder" => Some(self.local_workspace_folder.display().to_string()),
+            "localWorkspaceFolderBasename" => Some(basename(self.local_workspace_folder)),
+            "containerWorkspaceFolder" => self.container_workspace_folder.map(str::to_string),
+            "containerWorkspaceFolderBasename" => self
+                .container_workspace_folder
+                .map(|folder| basename(Path::new(folder))),
+            _ => {
+                let (kind, variable) = name.split_once(':')?;
+                if kind != "localEnv" && kind != "env" {
+                    return None;
+                }
+                let (variable, default) = match variable.split_once(':') {
+                    Some((variable, default)) => (variable, Some(default)),
+                    None => (variable, None),
+                };
+                Some(
+                    std::env::var(variable)
+                        .unwrap_or_else(|_| default.unwrap_or_default().to_string()),
+                )
+            }
+        }
+    }
+}
+
+fn substitute_variables(value: &mut Value, variables: &Variables) {
+    match value {
+        Value::String(text) => *text = variables.substitute(text),
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| substitute_variables(value, variables)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|value| substitute_variables(value, variables)),
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+/// Replaces `${containerEnv:NAME}` references with the values of the container's environment.
+pub(crate) fn substitute_container_env(
+    text: &str,
+    container_env: &HashMap<String, String>,
+) -> String {
+    const PREFIX: &str = "${containerEnv:";
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(PREFIX) {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + PREFIX.len()..start + len];
+        let (name, default) = name.split_once(':').unwrap_or((name, ""));
+        result.push_str(&rest[..start]);
+        result.push_str(container_env.get(name).map_or(default, String::as_str));
+        rest = &rest[start + len + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_image_config() {
+        let config = DevContainerConfig::parse(
+            r#"{
+                // Comments are allowed, as are trailing commas.
+                "name": "Rust",
+                "image": "mcr.microsoft.com/devcontainers/rust:1",
+                "mounts": [
+                    "source=cargo-cache,target=/usr/local/cargo/registry,type=volume",
+                    { "source": "${localWorkspaceFolder}/.cache", "target": "/cache" },
+                ],
+                "forwardPorts": [3000, "8080", "db:5432"],
+                "containerEnv": { "PROJECT": "${localWorkspaceFolderBasename}" },
+                "remoteEnv": { "PATH": "${containerEnv:PATH}:${containerWorkspaceFolder}/bin" },
+                "postCreateCommand": "cargo fetch",
+                "postStartCommand": ["git", "status"],
+                "postAttachCommand": { "server": "cargo run", "watch": ["cargo", "watch"] },
+            }"#,
+            PathBuf::from("/projects/zed/.devcontainer/devcontainer.json"),
+            Path::new("/projects/zed"),
+        )
+        .unwrap();
+
+        assert_eq!(config.container_workspace_folder, "/workspaces/zed");
+        assert_eq!(
+            config.source().unwrap(),
+            ContainerSource::Image("mcr.microsoft.com/devcontainers/rust:1".to_string())
+        );
+        assert_eq!(
+            config
+                .json
+                .mounts
+                .iter()
+                .map(Mount::to_mount_arg)
+                .collect::<Vec<_>>(),
+            vec![
+                "source=cargo-cache,target=/usr/local/cargo/registry,type=volume",
+                "type=bind,source=/projects/zed/.cache,target=/cache",
+            ]
+        );
+        assert_eq!(
+            config
+                .json
+                .forward_ports
+                .iter()
+                .filter_map(ForwardPort::container_port)
+                .collect::<Vec<_>>(),
+            vec![3000, 8080]
+        );
+        assert_eq!(config.json.container_env["PROJECT"], "zed");
+        assert_eq!(
+            config.json.remote_env["PATH"].as_deref(),
+            Some("${containerEnv:PATH}:/workspaces/zed/bin")
+        );
+        assert_eq!(
+            config.json.post_create_command,
+            Some(LifecycleCommand::Shell("cargo fetch".to_string()))
+        );
+        assert_eq!(
+            config.json.post_start_command,
+            Some(LifecycleCommand::Exec(vec![
+                "git".to_string(),
+                "status".to_string()
+            ]))
+        );
+        assert_eq!(
+            config.json.post_attach_command,
+            Some(LifecycleCommand::Parallel(BTreeMap::from_iter([
+                (
+                    "server".to_string(),
+                    LifecycleCommand::Shell("cargo run".to_string())
+                ),
+                (
+                    "watch".to_string(),
+                    LifecycleCommand::Exec(vec!["cargo".to_string(), "watch".to_string()])
+                ),
+            ])))
+        );
+        assert_eq!(
+            config.workspace_mount(),
+            "type=bind,source=/projects/zed,target=/workspaces/zed,consistency=cached"
+        );
+    }
+
+    #[test]
+    fn test_parse_build_and_compose_configs() {
+        let config = DevContainerConfig::parse(
+            r#"{
+                "build": { "dockerfile": "Dockerfile", "context": "..", "args": { "VARIANT": "bookworm" } },
+                "workspaceFolder": "/src/${localWorkspaceFolderBasename}",
+            }"#,
+            PathBuf::from("/projects/zed/.devcontainer/devcontainer.json"),
+            Path::new("/projects/zed"),
+        )
+        .unwrap();
+        assert_eq!(config.container_workspace_folder, "/src/zed");
+        assert_eq!(
+            config.source().unwrap(),
+            ContainerSource::Dockerfile {
+                dockerfile: PathBuf::from("/projects/zed/.devcontainer/Dockerfile"),
+                context: PathBuf::from("/projects/zed/.devcontainer/.."),
+            }
+        );
+
+        let config = DevContainerConfig::parse(
+            r#"{
+                "dockerComposeFile": ["../docker-compose.yml", "docker-compose.extend.yml"],
+                "service": "app",
+            }"#,
+            PathBuf::from("/projects/zed/.devcontainer/devcontainer.json"),
+            Path::new("/projects/zed"),
+        )
+        .unwrap();
+        assert_eq!(config.container_workspace_folder, "/");
+        assert_eq!(
+            config.source().unwrap(),
+            ContainerSource::Compose {
+                files: vec![
+                    PathBuf::from("/projects/zed/.devcontainer/../docker-compose.yml"),
+                    PathBuf::from("/projects/zed/.devcontainer/docker-compose.extend.yml"),
+                ],
+                service: "app".to_string(),
+            }
+        );
+
+        let config = DevContainerConfig::parse(
+            r#"{ "dockerComposeFile": "docker-compose.yml" }"#,
+            PathBuf::from("/projects/zed/.devcontainer/devcontainer.json"),
+            Path::new("/projects/zed"),
+        )
+        .unwrap();
+        assert!(config.source().is_err());
+    }
+
+    #[test]
+    fn test_substitute_container_env() {
+        let container_env = HashMap::from_iter([("PATH".to_string(), "/usr/bin".to_string())]);
+        assert_eq!(
+            substitute_container_env(
+                "${containerEnv:PATH}:/opt/bin:${containerEnv:MISSING:fallback}",
+                &container_env
+            ),
+            "/usr/bin:/opt/bin:fallback"
+        );
+    }
+}
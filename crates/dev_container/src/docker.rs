@@ -0,0 +1,665 @@
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    net::{Ipv4Addr, TcpListener},
+    path::Path,
+};
+
+use futures::future::try_join_all;
+use sha2::{Digest as _, Sha256};
+use smol::process::Command;
+
+use crate::{
+    devcontainer_api::DevContainerError,
+    devcontainer_json::{
+        AppPorts, ContainerSource, DevContainerConfig, LifecycleCommand, substitute_container_env,
+    },
+};
+
+/// Labels the devcontainer CLI puts on the containers it creates, so that the ones it created are reused.
+const LOCAL_FOLDER_LABEL: &str = "devcontainer.local_folder";
+const CONFIG_FILE_LABEL: &str = "devcontainer.config_file";
+
+/// Keeps the container running when `overrideCommand` is not disabled, like the devcontainer CLI does.
+const KEEP_ALIVE_SCRIPT: &str =
+    "echo Container started\ntrap \"exit 0\" 15\nwhile sleep 1 & wait $!; do :; done";
+
+pub(crate) struct StartedDevContainer {
+    pub(crate) container_id: String,
+    pub(crate) remote_workspace_folder: String,
+}
+
+/// Starts the dev container described by the configuration with docker or podman directly,
+/// creating it first if needed and running its lifecycle commands.
+pub(crate) async fn devcontainer_up(
+    config: &DevContainerConfig,
+    use_podman: bool,
+) -> Result<StartedDevContainer, DevContainerError> {
+    let engine = ContainerEngine { use_podman };
+
+    if let Some(command) = &config.json.initialize_command {
+        let shell = if cfg!(target_os = "windows") {
+            ["cmd", "/C"]
+        } else {
+            ["sh", "-c"]
+        };
+        run_lifecycle_command("initializeCommand", command, shell, &|program| {
+            let mut command = util::command::new_smol_command(program);
+            command.current_dir(&config.local_workspace_folder);
+            command
+        })
+        .await?;
+    }
+
+    let source = config.source()?;
+    let (container_id, created) = match engine.find_container(config, &source).await? {
+        Some(container_id) => (container_id, false),
+        None => (engine.create_container(config, &source).await?, true),
+    };
+
+    let running = engine
+        .run([
+            "inspect",
+            "--format",
+            "{{.State.Running}}",
+            container_id.as_str(),
+        ])
+        .await?;
+    let was_running = running.trim() == "true";
+    if !was_running {
+        log::info!("Starting dev container {container_id}");
+        engine.run(["start", container_id.as_str()]).await?;
+    }
+
+    let remote_env = engine.remote_env(config, &container_id).await?;
+    let exec = |program: &OsStr| {
+        let mut command = engine.command();
+        command.arg("exec");
+        command.arg("-w").arg(&config.container_workspace_folder);
+        if let Some(user) = config
+            .json
+            .remote_user
+            .as_ref()
+            .or(config.json.container_user.as_ref())
+        {
+            command.arg("-u").arg(user);
+        }
+        for (name, value) in &remote_env {
+            command.arg("-e").arg(format!("{name}={value}"));
+        }
+        command.arg(&container_id).arg(program);
+        command
+    };
+
+    let json = &config.json;
+    let mut commands = Vec::new();
+    if created {
+        commands.extend([
+            ("onCreateCommand", &json.on_create_command),
+            ("updateContentCommand", &json.update_content_command),
+            ("postCreateCommand", &json.post_create_command),
+        ]);
+    }
+    if created || !was_running {
+        commands.push(("postStartCommand", &json.post_start_command));
+    }
+    commands.push(("postAttachCommand", &json.post_attach_command));
+    for (name, command) in commands {
+        if let Some(command) = command {
+            run_lifecycle_command(name, command, ["sh", "-c"], &exec).await?;
+        }
+    }
+
+    Ok(StartedDevContainer {
+        container_id,
+        remote_workspace_folder: config.container_workspace_folder.clone(),
+    })
+}
+
+struct ContainerEngine {
+    use_podman: bool,
+}
+
+impl ContainerEngine {
+    fn command(&self) -> Command {
+        util::command::new_smol_command(if self.use_podman { "podman" } else { "docker" })
+    }
+
+    async fn run<I, S>(&self, args: I) -> Result<String, DevContainerError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = self.command();
+        command.args(args);
+        output(command).await
+    }
+
+    async fn find_container(
+        &self,
+        config: &DevContainerConfig,
+        source: &ContainerSource,
+    ) -> Result<Option<String>, DevContainerError> {
+        let labels = match source {
+            ContainerSource::Compose { service, .. } => vec![
+                format!(
+                    "com.docker.compose.project={}",
+                    compose_project_name(config)
+                ),
+                format!("com.docker.compose.service={service}"),
+            ],
+            ContainerSource::Image(_) | ContainerSource::Dockerfile { .. } => {
+                container_labels(config)
+            }
+        };
+        let mut args = vec!["ps".to_string(), "-a".to_string(), "-q".to_string()];
+        for label in labels {
+            args.push("--filter".to_string());
+            args.push(format!("label={label}"));
+        }
+        let ids = self.run(args).await?;
+        Ok(ids.lines().next().map(|id| id.trim().to_string()))
+    }
+
+    async fn create_container(
+        &self,
+        config: &DevContainerConfig,
+        source: &ContainerSource,
+    ) -> Result<String, DevContainerError> {
+        let image = match source {
+            ContainerSource::Image(image) => image.clone(),
+            ContainerSource::Dockerfile {
+                dockerfile,
+                context,
+            } => {
+                let image = image_name(config);
+                log::info!("Building dev container image {image}");
+                self.run(build_args(config, dockerfile, context, &image))
+                    .await?;
+                image
+            }
+            ContainerSource::Compose { files, service } => {
+                let mut files = files.clone();
+                if config.json.override_command.unwrap_or(false) {
+                    let override_file =
+                        paths::temp_dir().join(format!("{}.yml", compose_project_name(config)));
+                    smol::fs::create_dir_all(paths::temp_dir())
+                        .await
+                        .map_err(|e| {
+                            DevContainerError::DevContainerUpFailed(format!(
+                                "Failed to create {}: {e}",
+                                paths::temp_dir().display()
+                            ))
+                        })?;
+                    smol::fs::write(&override_file, compose_override(service))
+                        .await
+                        .map_err(|e| {
+                            DevContainerError::DevContainerUpFailed(format!(
+                                "Failed to write {}: {e}",
+                                override_file.display()
+                            ))
+                        })?;
+                    files.push(override_file);
+                }
+                log::info!("Starting dev container compose project");
+                self.run(compose_up_args(config, &files, service)).await?;
+                return self.find_container(config, source).await?.ok_or_else(|| {
+                    DevContainerError::DevContainerUpFailed(format!(
+                        "No container found for compose service `{service}`"
+                    ))
+                });
+            }
+        };
+
+        log::info!("Creating dev container from image {image}");
+        let forward_ports = available_forward_ports(config);
+        let container_id = self.run(run_args(config, &image, &forward_ports)).await?;
+        Ok(container_id.trim().to_string())
+    }
+
+    /// The `remoteEnv` of the configuration, with `${containerEnv:NAME}` references resolved.
+    async fn remote_env(
+        &self,
+        config: &DevContainerConfig,
+        container_id: &str,
+    ) -> Result<Vec<(String, String)>, DevContainerError> {
+        let remote_env = config
+            .json
+            .remote_env
+            .iter()
+            .filter_map(|(name, value)| Some((name, value.as_ref()?)))
+            .collect::<Vec<_>>();
+        let container_env = if remote_env
+            .iter()
+            .any(|(_, value)| value.contains("${containerEnv:"))
+        {
+            self.run(["exec", container_id, "env"])
+                .await?
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        } else {
+            HashMap::default()
+        };
+        Ok(remote_env
+            .into_iter()
+            .map(|(name, value)| {
+                (
+                    name.clone(),
+                    substitute_container_env(value, &container_env),
+                )
+            })
+            .collect())
+    }
+}
+
+async fn output(mut command: Command) -> Result<String, DevContainerError> {
+    log::debug!("Running {:?}", command);
+    match command.output().await {
+        Ok(output) if output.status.success() => {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Ok(output) => {
+            let message = format!(
+                "Non-success status running {:?}: out: {:?}, err: {:?}",
+                command,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            log::error!("{}", &message);
+            Err(DevContainerError::DevContainerUpFailed(message))
+        }
+        Err(e) => {
+            let message = format!("Error running {:?}: {:?}", command, e);
+            log::error!("{}", &message);
+            Err(DevContainerError::DevContainerUpFailed(message))
+        }
+    }
+}
+
+/// Runs a lifecycle command, where `new_command` creates the command running the given program,
+/// either on the host or in the container, and `shell` runs the commands given as a single string.
+async fn run_lifecycle_command(
+    name: &str,
+    command: &LifecycleCommand,
+    shell: [&str; 2],
+    new_command: &dyn Fn(&OsStr) -> Command,
+) -> Result<(), DevContainerError> {
+    let command =
+        match command {
+            LifecycleCommand::Shell(script) => {
+                let [program, flag] = shell;
+                let mut command = new_command(OsStr::new(program));
+                command.arg(flag).arg(script);
+                command
+            }
+            LifecycleCommand::Exec(args) => {
+                let Some((program, args)) = args.split_first() else {
+                    return Ok(());
+                };
+                let mut command = new_command(OsStr::new(program));
+                command.args(args);
+                command
+            }
+            LifecycleCommand::Parallel(commands) => {
+                try_join_all(commands.values().map(|command| {
+                    Box::pin(run_lifecycle_command(name, command, shell, new_command))
+                }))
+                .await?;
+                return Ok(());
+            }
+        };
+
+    log::info!("Running {name} of the dev container");
+    output(command)
+        .await
+        .map(|_| ())
+        .map_err(|e| DevContainerError::DevContainerUpFailed(format!("{name} failed: {e}")))
+}
+
+fn container_labels(config: &DevContainerConfig) -> Vec<String> {
+    vec![
+        format!(
+            "{LOCAL_FOLDER_LABEL}={}",
+            config.local_workspace_folder.display()
+        ),
+        format!("{CONFIG_FILE_LABEL}={}", config.config_file.display()),
+    ]
+}
+
+/// The name of the image built for the configuration, which stays the same across Zed versions
+/// so that the image is reused.
+fn image_name(config: &DevContainerConfig) -> String {
+    let hash = Sha256::digest(config.config_file.to_string_lossy().as_bytes());
+    format!(
+        "zed-dev-container-{}-{:x}",
+        sanitize_name(&config.project_name()),
+        u64::from_be_bytes(hash[..8].try_into().unwrap())
+    )
+}
+
+/// The compose project name the devcontainer CLI uses, so that its containers are reused.
+fn compose_project_name(config: &DevContainerConfig) -> String {
+    format!("{}_devcontainer", sanitize_name(&config.project_name()))
+}
+
+fn sanitize_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+fn build_args(
+    config: &DevContainerConfig,
+    dockerfile: &Path,
+    context: &Path,
+    image: &str,
+) -> Vec<String> {
+    let mut args = vec![
+        "build".to_string(),
+        "-f".to_string(),
+        dockerfile.display().to_string(),
+        "-t".to_string(),
+        image.to_string(),
+    ];
+    if let Some(build) = &config.json.build {
+        for (name, value) in &build.args {
+            args.push("--build-arg".to_string());
+            args.push(format!("{name}={value}"));
+        }
+        if let Some(target) = &build.target {
+            args.push("--target".to_string());
+            args.push(target.clone());
+        }
+        args.extend(build.options.iter().cloned());
+    }
+    args.push(context.display().to_string());
+    args
+}
+
+fn compose_up_args(
+    config: &DevContainerConfig,
+    files: &[impl AsRef<Path>],
+    service: &str,
+) -> Vec<String> {
+    let mut args = vec![
+        "compose".to_string(),
+        "-p".to_string(),
+        compose_project_name(config),
+    ];
+    for file in files {
+        args.push("-f".to_string());
+        args.push(file.as_ref().display().to_string());
+    }
+    args.extend(["up".to_string(), "-d".to_string(), "--build".to_string()]);
+    if !config.json.run_services.is_empty() {
+        args.extend(config.json.run_services.iter().cloned());
+        if !config.json.run_services.iter().any(|s| s == service) {
+            args.push(service.to_string());
+        }
+    }
+    args
+}
+
+/// A compose file replacing the command of the dev container's service with one that keeps it
+/// running, for `overrideCommand`, which compose services don't enable by default.
+/// It's written as JSON, which compose reads as YAML.
+fn compose_override(service: &str) -> String {
+    serde_json::json!({
+        "services": {
+            service: {
+                "entrypoint": ["/bin/sh", "-c", KEEP_ALIVE_SCRIPT],
+                "command": [],
+            }
+        }
+    })
+    .to_string()
+}
+
+/// The ports of the dev container listed in `forwardPorts` that are free on the host. Publishing
+/// a port that's already in use would fail to create the container, so those are skipped.
+fn available_forward_ports(config: &DevContainerConfig) -> Vec<u16> {
+    config
+        .json
+        .forward_ports
+        .iter()
+        .filter_map(|port| {
+            let Some(container_port) = port.container_port() else {
+                log::warn!(
+                    "Not forwarding {port:?}, as it does not refer to a port of the dev container"
+                );
+                return None;
+            };
+            if let Err(error) = TcpListener::bind((Ipv4Addr::LOCALHOST, container_port)) {
+                log::warn!("Not forwarding port {container_port}, as it's unavailable: {error}");
+                return None;
+            }
+            Some(container_port)
+        })
+        .collect()
+}
+
+fn run_args(config: &DevContainerConfig, image: &str, forward_ports: &[u16]) -> Vec<String> {
+    let json = &config.json;
+    let mut args = vec!["run".to_string(), "-d".to_string()];
+    for label in container_labels(config) {
+        args.push("--label".to_string());
+        args.push(label);
+    }
+    args.push("--mount".to_string());
+    args.push(config.workspace_mount());
+    for mount in &json.mounts {
+        args.push("--mount".to_string());
+        args.push(mount.to_mount_arg());
+    }
+    for port in forward_ports {
+        args.push("-p".to_string());
+        args.push(format!("127.0.0.1:{port}:{port}"));
+    }
+    for port in json.app_port.iter().flat_map(AppPorts::to_publish_args) {
+        args.push("-p".to_string());
+        args.push(port);
+    }
+    for (name, value) in &json.container_env {
+        args.push("-e".to_string());
+        args.push(format!("{name}={value}"));
+    }
+    if let Some(user) = &json.container_user {
+        args.push("-u".to_string());
+        args.push(user.clone());
+    }
+    if json.init {
+        args.push("--init".to_string());
+    }
+    if json.privileged {
+        args.push("--privileged".to_string());
+    }
+    for capability in &json.cap_add {
+        args.push("--cap-add".to_string());
+        args.push(capability.clone());
+    }
+    for option in &json.security_opt {
+        args.push("--security-opt".to_string());
+        args.push(option.clone());
+    }
+    args.extend(json.run_args.iter().cloned());
+
+    let override_command = json.override_command.unwrap_or(true);
+    if override_command {
+        args.push("--entrypoint".to_string());
+        args.push("/bin/sh".to_string());
+    }
+    args.push(image.to_string());
+    if override_command {
+        args.push("-c".to_string());
+        args.push(KEEP_ALIVE_SCRIPT.to_string());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::devcontainer_json::{ContainerSource, DevContainerConfig};
+
+    use super::{
+        KEEP_ALIVE_SCRIPT, available_forward_ports, build_args, compose_override, compose_up_args,
+        image_name, run_args,
+    };
+
+    fn parse(json: &str) -> DevContainerConfig {
+        DevContainerConfig::parse(
+            json,
+            PathBuf::from("/projects/Zed/.devcontainer/devcontainer.json"),
+            Path::new("/projects/Zed"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_run_args() {
+        let config = parse(
+            r#"{
+                "image": "ubuntu",
+                "mounts": ["source=cache,target=/cache,type=volume"],
+                "forwardPorts": [3000, "db:5432"],
+                "appPort": ["8000:8010", 9000],
+                "containerEnv": { "RUST_LOG": "info" },
+                "capAdd": ["SYS_PTRACE"],
+                "runArgs": ["--network=host"],
+            }"#,
+        );
+        assert_eq!(
+            run_args(&config, "ubuntu", &[3000]),
+            vec![
+                "run",
+                "-d",
+                "--label",
+                "devcontainer.local_folder=/projects/Zed",
+                "--label",
+                "devcontainer.config_file=/projects/Zed/.devcontainer/devcontainer.json",
+                "--mount",
+                "type=bind,source=/projects/Zed,target=/workspaces/Zed,consistency=cached",
+                "--mount",
+                "source=cache,target=/cache,type=volume",
+                "-p",
+                "127.0.0.1:3000:3000",
+                "-p",
+                "8000:8010",
+                "-p",
+                "127.0.0.1:9000:9000",
+                "-e",
+                "RUST_LOG=info",
+                "--cap-add",
+                "SYS_PTRACE",
+                "--network=host",
+                "--entrypoint",
+                "/bin/sh",
+                "ubuntu",
+                "-c",
+                KEEP_ALIVE_SCRIPT,
+            ]
+        );
+
+        let config = parse(r#"{ "image": "ubuntu", "overrideCommand": false }"#);
+        assert_eq!(run_args(&config, "ubuntu", &[]).last().unwrap(), "ubuntu");
+    }
+
+    #[test]
+    fn test_available_forward_ports() {
+        let busy = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let busy_port = busy.local_addr().unwrap().port();
+        let free_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let config = parse(&format!(
+            r#"{{ "image": "ubuntu", "forwardPorts": [{busy_port}, {free_port}, "db:5432"] }}"#
+        ));
+        assert_eq!(available_forward_ports(&config), vec![free_port]);
+    }
+
+    #[test]
+    fn test_image_name() {
+        let config = parse(r#"{ "build": { "dockerfile": "Dockerfile" } }"#);
+        assert_eq!(
+            image_name(&config),
+            "zed-dev-container-zed-61ea3cab93e29ef5"
+        );
+    }
+
+    #[test]
+    fn test_build_and_compose_args() {
+        let config = parse(
+            r#"{
+                "build": { "dockerfile": "Dockerfile", "args": { "VARIANT": "bookworm" }, "target": "dev" },
+            }"#,
+        );
+        let ContainerSource::Dockerfile {
+            dockerfile,
+            context,
+        } = config.source().unwrap()
+        else {
+            panic!("expected a Dockerfile source");
+        };
+        assert_eq!(
+            build_args(&config, &dockerfile, &context, "zed-dev-container"),
+            vec![
+                "build",
+                "-f",
+                "/projects/Zed/.devcontainer/Dockerfile",
+                "-t",
+                "zed-dev-container",
+                "--build-arg",
+                "VARIANT=bookworm",
+                "--target",
+                "dev",
+                "/projects/Zed/.devcontainer",
+            ]
+        );
+
+        let config = parse(
+            r#"{
+                "dockerComposeFile": "docker-compose.yml",
+                "service": "app",
+                "runServices": ["db"],
+            }"#,
+        );
+        let ContainerSource::Compose { files, service } = config.source().unwrap() else {
+            panic!("expected a compose source");
+        };
+        assert_eq!(
+            compose_up_args(&config, &files, &service),
+            vec![
+                "compose",
+                "-p",
+                "zed_devcontainer",
+                "-f",
+                "/projects/Zed/.devcontainer/docker-compose.yml",
+                "up",
+                "-d",
+                "--build",
+                "db",
+                "app",
+            ]
+        );
+
+        let compose_override: serde_json::Value =
+            serde_json::from_str(&compose_override(&service)).unwrap();
+        assert_eq!(
+            compose_override,
+            serde_json::json!({
+                "services": {
+                    "app": {
+                        "entrypoint": ["/bin/sh", "-c", KEEP_ALIVE_SCRIPT],
+                        "command": [],
+                    }
+                }
+            })
+        );
+    }
+}
@@ -39,6 +39,8 @@ use http::Request;
 use http_client::{AsyncBody, HttpClient};
 
 mod devcontainer_api;
+mod devcontainer_json;
+mod docker;
 
 use devcontainer_api::read_devcontainer_configuration_for_project;
 
@@ -1427,7 +1429,7 @@ fn dispatch_apply_templates(
             });
 
             if check_for_existing
-                && read_devcontainer_configuration_for_project(cx, &node_runtime)
+                && read_devcontainer_configuration_for_project(cx)
                     .await
                     .is_ok()
             {
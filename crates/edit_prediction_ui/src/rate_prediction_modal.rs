@@ -416,6 +416,7 @@ impl RatePredictionsModal {
                     editor.set_show_runnables(false, cx);
                     editor.set_show_breakpoints(false, cx);
                     editor.set_show_wrap_guides(false, cx);
+                    editor.set_show_rulers(false, cx);
                     editor.set_show_indent_guides(false, cx);
                     editor.set_show_edit_predictions(Some(false), window, cx);
                     editor.set_placeholder_text("Add your feedback…", window, cx);
@@ -19,6 +19,7 @@ pub mod code_context_menus;
 pub mod display_map;
 mod editor_settings;
 mod element;
+mod emmet;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
@@ -33,6 +34,7 @@ mod lsp_ext;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+pub mod replay;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
@@ -1162,6 +1164,7 @@ pub struct Editor {
     show_breakpoints: Option<bool>,
     show_diff_review_button: bool,
     show_wrap_guides: Option<bool>,
+    show_rulers: Option<bool>,
     show_indent_guides: Option<bool>,
     buffers_with_disabled_indent_guides: HashSet<BufferId>,
     highlight_order: usize,
@@ -2347,6 +2350,7 @@ impl Editor {
             show_breakpoints: None,
             show_diff_review_button: false,
             show_wrap_guides: None,
+            show_rulers: None,
             show_indent_guides,
             buffers_with_disabled_indent_guides: HashSet::default(),
             highlight_order: 0,
@@ -5948,6 +5952,14 @@ impl Editor {
             }))
         };
 
+        let emmet = if let Some(provider) = &provider
+            && provider.show_snippets()
+        {
+            emmet::emmet_completions(&buffer, buffer_position, cx)
+        } else {
+            Vec::new()
+        };
+
         let snippet_sort_order = EditorSettings::get_global(cx).snippet_sort_order;
 
         let id = post_inc(&mut self.next_completion_id);
@@ -6011,6 +6023,12 @@ impl Editor {
                     .into_iter()
                     .flat_map(|response| response.completions),
             );
+            if !emmet.is_empty() {
+                // An abbreviation's expansion depends on all of it, so it has
+                // to be expanded again as the abbreviation is typed.
+                is_incomplete = true;
+                completions.extend(emmet);
+            }
 
             let menu = if completions.is_empty() {
                 None
@@ -13705,6 +13723,41 @@ impl Editor {
         }
     }
 
+    /// Moves a singleton buffer to a state of its undo tree, see
+    /// [`language::Buffer::go_to_undo_state`].
+    pub fn go_to_undo_state(
+        &mut self,
+        target: Option<TransactionId>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.read_only(cx) {
+            return false;
+        }
+        let Some(buffer) = self.buffer.read(cx).as_singleton() else {
+            return false;
+        };
+        if !buffer.update(cx, |buffer, cx| buffer.go_to_undo_state(target, cx)) {
+            return false;
+        }
+
+        if let Some(transaction_id) = target
+            && let Some((_, Some(selections))) =
+                self.selection_history.transaction(transaction_id).cloned()
+        {
+            self.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_anchors(selections.to_vec());
+            });
+        }
+        self.request_autoscroll(Autoscroll::fit(), cx);
+        self.unmark_text(window, cx);
+        self.refresh_edit_prediction(true, false, window, cx);
+        if let Some(transaction_id) = target {
+            cx.emit(EditorEvent::Edited { transaction_id });
+        }
+        true
+    }
+
     pub fn finalize_last_transaction(&mut self, cx: &mut Context<Self>) {
         self.buffer
             .update(cx, |buffer, cx| buffer.finalize_last_transaction(cx));
@@ -20756,6 +20809,18 @@ impl Editor {
         wrap_guides
     }
 
+    /// Returns the rulers to show, which unlike wrap guides don't depend on the soft wrap mode.
+    pub fn rulers(&self, cx: &App) -> Vec<language_settings::Ruler> {
+        if self.show_rulers == Some(false) {
+            return Vec::new();
+        }
+        let settings = self.buffer.read(cx).language_settings(cx);
+        if !settings.show_rulers {
+            return Vec::new();
+        }
+        settings.rulers.clone()
+    }
+
     pub fn soft_wrap_mode(&self, cx: &App) -> SoftWrap {
         let settings = self.buffer.read(cx).language_settings(cx);
         let mode = self.soft_wrap_mode_override.unwrap_or(settings.soft_wrap);
@@ -22163,6 +22228,11 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn set_show_rulers(&mut self, show_rulers: bool, cx: &mut Context<Self>) {
+        self.show_rulers = Some(show_rulers);
+        cx.notify();
+    }
+
     pub fn set_show_indent_guides(&mut self, show_indent_guides: bool, cx: &mut Context<Self>) {
         self.show_indent_guides = Some(show_indent_guides);
         cx.notify();
@@ -12020,6 +12020,44 @@ async fn test_snippet_with_multi_word_prefix(cx: &mut TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_emmet_abbreviation_completion(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            name: "HTML".into(),
+            emmet: Some(language::EmmetSyntax::Html),
+            ..LanguageConfig::default()
+        },
+        None,
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state("ˇ");
+    cx.simulate_input("ul>li*2");
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, _, _| {
+        let Some(CodeContextMenu::Completions(menu)) = &*editor.context_menu.borrow() else {
+            panic!("expected completion menu to be open");
+        };
+        assert_eq!(completion_menu_entries(menu), &["ul>li*2"]);
+    });
+
+    let apply_additional_edits = cx.update_editor(|editor, window, cx| {
+        editor
+            .confirm_completion(&ConfirmCompletion::default(), window, cx)
+            .unwrap()
+    });
+    cx.assert_editor_state(indoc! {"
+        <ul>
+            <li>ˇ</li>
+            <li></li>
+        </ul>"});
+    apply_additional_edits.await.unwrap();
+}
+
 #[gpui::test]
 async fn test_document_format_during_save(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -21605,6 +21643,48 @@ async fn test_stage_and_unstage_added_file_hunk(
     cx.assert_index_text(None);
 }
 
+#[gpui::test]
+fn test_rulers(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.rulers = Some(vec![
+            settings::RulerContent::Column(72),
+            settings::RulerContent::Colored {
+                column: 100,
+                color: Some("#ff0000".into()),
+            },
+        ]);
+    });
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple("", cx));
+    let editor = cx.add_window(|window, cx| build_editor(buffer, window, cx));
+    _ = editor.update(cx, |editor, _, cx| {
+        assert_eq!(
+            editor.rulers(cx),
+            [
+                language_settings::Ruler {
+                    column: 72,
+                    color: None,
+                },
+                language_settings::Ruler {
+                    column: 100,
+                    color: Some(gpui::rgb(0xff0000).into()),
+                },
+            ]
+        );
+
+        editor.set_show_rulers(false, cx);
+        assert!(editor.rulers(cx).is_empty());
+        editor.set_show_rulers(true, cx);
+    });
+
+    update_test_language_settings(cx, |settings| {
+        settings.defaults.show_rulers = Some(false);
+    });
+    _ = editor.update(cx, |editor, _, cx| {
+        assert!(editor.rulers(cx).is_empty());
+    });
+}
+
 async fn setup_indent_guides_editor(
     text: &str,
     cx: &mut TestAppContext,
@@ -2941,7 +2941,7 @@ impl EditorElement {
         hitbox: &Hitbox,
         window: &Window,
         cx: &App,
-    ) -> SmallVec<[(Pixels, bool); 2]> {
+    ) -> SmallVec<[(Pixels, Hsla); 2]> {
         let scroll_left = scroll_position.x as f32 * em_advance;
         let content_origin = content_origin.x;
         let horizontal_offset = content_origin - scroll_left;
@@ -2949,17 +2949,32 @@ impl EditorElement {
             .and_then(|layout| layout.visible.then_some(vertical_scrollbar_width))
             .unwrap_or_default();
 
-        self.editor
-            .read(cx)
-            .wrap_guides(cx)
-            .into_iter()
-            .flat_map(|(guide, active)| {
+        let editor = self.editor.read(cx);
+        let colors = cx.theme().colors();
+        let wrap_guides = editor.wrap_guides(cx).into_iter().map(|(guide, active)| {
+            let color = if active {
+                colors.editor_active_wrap_guide
+            } else {
+                colors.editor_wrap_guide
+            };
+            (guide, color)
+        });
+        let rulers = editor.rulers(cx).into_iter().map(|ruler| {
+            (
+                ruler.column,
+                ruler.color.unwrap_or(colors.editor_wrap_guide),
+            )
+        });
+
+        wrap_guides
+            .chain(rulers)
+            .flat_map(|(guide, color)| {
                 let wrap_position = column_pixels(&self.style, guide, window);
                 let wrap_guide_x = wrap_position + horizontal_offset;
                 let display_wrap_guide = wrap_guide_x >= content_origin
                     && wrap_guide_x <= hitbox.bounds.right() - vertical_scrollbar_width;
 
-                display_wrap_guide.then_some((wrap_guide_x, active))
+                display_wrap_guide.then_some((wrap_guide_x, color))
             })
             .collect()
     }
@@ -6209,18 +6224,13 @@ impl EditorElement {
                     paint_highlight(range.start, range.end, color, edges);
                 }
 
-                for (guide_x, active) in layout.wrap_guides.iter() {
-                    let color = if *active {
-                        cx.theme().colors().editor_active_wrap_guide
-                    } else {
-                        cx.theme().colors().editor_wrap_guide
-                    };
+                for (guide_x, color) in layout.wrap_guides.iter() {
                     window.paint_quad(fill(
                         Bounds {
                             origin: point(*guide_x, layout.position_map.text_hitbox.origin.y),
                             size: size(px(1.), layout.position_map.text_hitbox.size.height),
                         },
-                        color,
+                        *color,
                     ));
                 }
             }
@@ -10827,7 +10837,8 @@ pub struct EditorLayout {
     scrollbars_layout: Option<EditorScrollbars>,
     minimap: Option<MinimapLayout>,
     mode: EditorMode,
-    wrap_guides: SmallVec<[(Pixels, bool); 2]>,
+    /// The positions and colors of both wrap guides and rulers.
+    wrap_guides: SmallVec<[(Pixels, Hsla); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
     visible_display_row_range: Range<DisplayRow>,
     active_rows: BTreeMap<DisplayRow, LineHighlightSpec>,
@@ -0,0 +1,236 @@
+//! Expansion of [Emmet](https://emmet.io) abbreviations, offered as completions in
+//! languages whose config sets `emmet`. The language is taken at the cursor, so
+//! abbreviations also expand in injected languages, such as CSS in a `<style>` element.
+
+mod css;
+mod markup;
+
+use std::ops::Range;
+
+use gpui::{App, Entity};
+use language::{
+    Bias, Buffer, BufferSnapshot, CodeLabel, EmmetSyntax, LanguageServerName, point_to_lsp,
+};
+use lsp::{CompletionItemKind, InsertTextFormat, LanguageServerId};
+use project::{Completion, CompletionSource, lsp_store::CompletionDocumentation};
+use snippet::Snippet;
+use text::{ToOffset as _, ToPointUtf16 as _};
+
+/// Zed's language configs opt into the Emmet language server only in the scopes
+/// where abbreviations make sense, such as JSX elements, which is reused here.
+const EMMET_LANGUAGE_SERVER: LanguageServerName =
+    LanguageServerName::new_static("emmet-language-server");
+
+/// How much of the text before the cursor is searched for an abbreviation.
+const MAX_CONTEXT_LEN: usize = 1024;
+
+/// Returns the expansion of the abbreviation before the cursor, if there is one.
+pub(crate) fn emmet_completions(
+    buffer: &Entity<Buffer>,
+    position: text::Anchor,
+    cx: &App,
+) -> Vec<Completion> {
+    let snapshot = buffer.read(cx).snapshot();
+    let offset = position.to_offset(&snapshot);
+    let Some(syntax) = snapshot
+        .language_at(offset)
+        .and_then(|language| language.config().emmet)
+    else {
+        return Vec::new();
+    };
+    let settings = snapshot.settings_at(offset, cx);
+    if !settings.completions.emmet {
+        return Vec::new();
+    }
+    if let Some(scope) = snapshot.language_scope_at(offset)
+        && (matches!(scope.override_name(), Some("string" | "comment"))
+            || !scope.language_allowed(&EMMET_LANGUAGE_SERVER))
+    {
+        return Vec::new();
+    }
+
+    let context_start = snapshot.clip_offset(offset.saturating_sub(MAX_CONTEXT_LEN), Bias::Left);
+    let context = snapshot
+        .text_for_range(context_start..offset)
+        .collect::<String>();
+    let Some(abbreviation_start) = abbreviation_start(&context, syntax) else {
+        return Vec::new();
+    };
+    let abbreviation = &context[abbreviation_start..];
+
+    let indent = if settings.hard_tabs {
+        "\t".to_string()
+    } else {
+        " ".repeat(settings.tab_size.get() as usize)
+    };
+    let expansion = match syntax {
+        EmmetSyntax::Html => markup::expand(abbreviation, false, &indent),
+        EmmetSyntax::Jsx => markup::expand(abbreviation, true, &indent),
+        EmmetSyntax::Css => css::expand(abbreviation),
+    };
+    let Some(expansion) = expansion else {
+        return Vec::new();
+    };
+
+    let start = snapshot.anchor_before(context_start + abbreviation_start);
+    vec![completion(
+        abbreviation,
+        expansion,
+        start..position,
+        &snapshot,
+    )]
+}
+
+/// Returns the offset in `text` where the abbreviation ending at the end of it starts.
+fn abbreviation_start(text: &str, syntax: EmmetSyntax) -> Option<usize> {
+    let start = match syntax {
+        EmmetSyntax::Html | EmmetSyntax::Jsx => markup_abbreviation_start(text)?,
+        EmmetSyntax::Css => css_abbreviation_start(text)?,
+    };
+    (start < text.len()).then_some(start)
+}
+
+/// Markup abbreviations extend back to the first whitespace outside of
+/// attributes and text, or to the `>` closing a tag.
+fn markup_abbreviation_start(text: &str) -> Option<usize> {
+    let mut closers = Vec::new();
+    let mut start = text.len();
+    for (ix, char) in text.char_indices().rev() {
+        match char {
+            ']' | '}' | ')' => closers.push(char),
+            '[' | '{' | '(' => {
+                let closer = match char {
+                    '[' => ']',
+                    '{' => '}',
+                    _ => ')',
+                };
+                if closers.last() == Some(&closer) {
+                    closers.pop();
+                } else if closers.is_empty() {
+                    break;
+                }
+            }
+            _ if !closers.is_empty() => {}
+            '>' if text[..ix].rfind('<') > text[..ix].rfind('>') => break,
+            _ if char.is_alphanumeric() || "-_:.#*$@!+>^/".contains(char) => {}
+            _ => break,
+        }
+        start = ix;
+    }
+    closers.is_empty().then_some(start)
+}
+
+/// CSS abbreviations are words at the start of a declaration.
+fn css_abbreviation_start(text: &str) -> Option<usize> {
+    let start = text
+        .char_indices()
+        .rev()
+        .find(|(_, char)| !char.is_ascii_alphanumeric() && !"#.-!%".contains(*char))
+        .map_or(0, |(ix, char)| ix + char.len_utf8());
+    let before = text[..start].trim_end();
+    let starts_declaration = before.is_empty() || before.ends_with(['{', ';']);
+    (starts_declaration && text[start..].starts_with(|char: char| char.is_ascii_alphabetic()))
+        .then_some(start)
+}
+
+fn completion(
+    abbreviation: &str,
+    expansion: String,
+    range: Range<text::Anchor>,
+    snapshot: &BufferSnapshot,
+) -> Completion {
+    let lsp_range = lsp::Range {
+        start: point_to_lsp(range.start.to_point_utf16(snapshot)),
+        end: point_to_lsp(range.end.to_point_utf16(snapshot)),
+    };
+    let preview = Snippet::parse(&expansion)
+        .map(|snippet| snippet.text)
+        .unwrap_or_else(|_| expansion.clone());
+
+    Completion {
+        replace_range: range.clone(),
+        new_text: expansion.clone(),
+        source: CompletionSource::Lsp {
+            insert_range: None,
+            server_id: LanguageServerId(usize::MAX),
+            resolved: true,
+            lsp_completion: Box::new(lsp::CompletionItem {
+                label: abbreviation.to_string(),
+                kind: Some(CompletionItemKind::SNIPPET),
+                label_details: Some(lsp::CompletionItemLabelDetails {
+                    detail: Some("Emmet Abbreviation".to_string()),
+                    description: None,
+                }),
+                insert_text_format: Some(InsertTextFormat::SNIPPET),
+                text_edit: Some(lsp::CompletionTextEdit::InsertAndReplace(
+                    lsp::InsertReplaceEdit {
+                        new_text: expansion,
+                        insert: lsp_range,
+                        replace: lsp_range,
+                    },
+                )),
+                filter_text: Some(abbreviation.to_string()),
+                sort_text: Some(char::MAX.to_string()),
+                ..lsp::CompletionItem::default()
+            }),
+            lsp_defaults: None,
+        },
+        label: CodeLabel {
+            text: abbreviation.to_string(),
+            runs: Vec::new(),
+            filter_range: 0..abbreviation.len(),
+        },
+        icon_path: None,
+        documentation: Some(CompletionDocumentation::SingleLineAndMultiLinePlainText {
+            single_line: "Emmet Abbreviation".into(),
+            plain_text: Some(preview.into()),
+        }),
+        insert_text_mode: None,
+        confirm: None,
+        match_start: Some(range.start),
+        snippet_deduplication_key: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn assert_abbreviation(text: &str, syntax: EmmetSyntax, expected: Option<&str>) {
+        assert_eq!(
+            abbreviation_start(text, syntax).map(|start| &text[start..]),
+            expected,
+            "abbreviation in {text:?}"
+        );
+    }
+
+    #[test]
+    fn test_markup_abbreviation_start() {
+        use EmmetSyntax::Html;
+
+        assert_abbreviation("ul>li.item$*3", Html, Some("ul>li.item$*3"));
+        assert_abbreviation("  <div>nav>a", Html, Some("nav>a"));
+        assert_abbreviation(
+            "text a[title=\"a > b\"]{Click me}",
+            Html,
+            Some("a[title=\"a > b\"]{Click me}"),
+        );
+        assert_abbreviation("(dt+dd)*2", Html, Some("(dt+dd)*2"));
+        assert_abbreviation("foo(div", Html, Some("div"));
+        assert_abbreviation("<div\n  class=\"a\"\n>p.note", Html, Some("p.note"));
+        assert_abbreviation("a[href=\"#\"", Html, None);
+        assert_abbreviation("div ", Html, None);
+    }
+
+    #[test]
+    fn test_css_abbreviation_start() {
+        use EmmetSyntax::Css;
+
+        assert_abbreviation(".a {\n  m10-a", Css, Some("m10-a"));
+        assert_abbreviation(".a { df; jcc!", Css, Some("jcc!"));
+        assert_abbreviation("bgc#fff", Css, Some("bgc#fff"));
+        assert_abbreviation(".a { color: red", Css, None);
+        assert_abbreviation(".a { -m10", Css, None);
+    }
+}
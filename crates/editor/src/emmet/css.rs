@@ -0,0 +1,273 @@
+//! Expansion of Emmet abbreviations such as `m10-a` or `bgc#f` into CSS declarations.
+
+/// Abbreviations that expand to a whole declaration.
+const DECLARATIONS: &[(&str, &str)] = &[
+    ("aic", "align-items: center"),
+    ("aife", "align-items: flex-end"),
+    ("aifs", "align-items: flex-start"),
+    ("bxzbb", "box-sizing: border-box"),
+    ("cp", "cursor: pointer"),
+    ("db", "display: block"),
+    ("df", "display: flex"),
+    ("dg", "display: grid"),
+    ("di", "display: inline"),
+    ("dib", "display: inline-block"),
+    ("dif", "display: inline-flex"),
+    ("dn", "display: none"),
+    ("fdc", "flex-direction: column"),
+    ("fdr", "flex-direction: row"),
+    ("fl", "float: left"),
+    ("fr", "float: right"),
+    ("fsi", "font-style: italic"),
+    ("fwb", "font-weight: bold"),
+    ("fwn", "font-weight: normal"),
+    ("fxww", "flex-wrap: wrap"),
+    ("jcc", "justify-content: center"),
+    ("jcfe", "justify-content: flex-end"),
+    ("jcfs", "justify-content: flex-start"),
+    ("jcsa", "justify-content: space-around"),
+    ("jcsb", "justify-content: space-between"),
+    ("ovh", "overflow: hidden"),
+    ("ova", "overflow: auto"),
+    ("posa", "position: absolute"),
+    ("posf", "position: fixed"),
+    ("posr", "position: relative"),
+    ("poss", "position: sticky"),
+    ("tac", "text-align: center"),
+    ("tal", "text-align: left"),
+    ("tar", "text-align: right"),
+    ("tdn", "text-decoration: none"),
+    ("tdu", "text-decoration: underline"),
+    ("ttu", "text-transform: uppercase"),
+    ("vh", "visibility: hidden"),
+    ("wsnw", "white-space: nowrap"),
+];
+
+/// Abbreviations of properties, which can be followed by a value.
+const PROPERTIES: &[(&str, &str)] = &[
+    ("b", "bottom"),
+    ("bd", "border"),
+    ("bdb", "border-bottom"),
+    ("bdc", "border-color"),
+    ("bdl", "border-left"),
+    ("bdr", "border-right"),
+    ("bdrs", "border-radius"),
+    ("bdt", "border-top"),
+    ("bdw", "border-width"),
+    ("bg", "background"),
+    ("bgc", "background-color"),
+    ("bgi", "background-image"),
+    ("c", "color"),
+    ("cl", "clear"),
+    ("cnt", "content"),
+    ("cur", "cursor"),
+    ("d", "display"),
+    ("ff", "font-family"),
+    ("fx", "flex"),
+    ("fxb", "flex-basis"),
+    ("fxg", "flex-grow"),
+    ("fxs", "flex-shrink"),
+    ("fz", "font-size"),
+    ("fw", "font-weight"),
+    ("g", "gap"),
+    ("gtc", "grid-template-columns"),
+    ("gtr", "grid-template-rows"),
+    ("h", "height"),
+    ("l", "left"),
+    ("lh", "line-height"),
+    ("ls", "letter-spacing"),
+    ("m", "margin"),
+    ("mah", "max-height"),
+    ("maw", "max-width"),
+    ("mb", "margin-bottom"),
+    ("mih", "min-height"),
+    ("miw", "min-width"),
+    ("ml", "margin-left"),
+    ("mr", "margin-right"),
+    ("mt", "margin-top"),
+    ("o", "outline"),
+    ("op", "opacity"),
+    ("ord", "order"),
+    ("ov", "overflow"),
+    ("p", "padding"),
+    ("pb", "padding-bottom"),
+    ("pl", "padding-left"),
+    ("pos", "position"),
+    ("pr", "padding-right"),
+    ("pt", "padding-top"),
+    ("r", "right"),
+    ("t", "top"),
+    ("ta", "text-align"),
+    ("td", "text-decoration"),
+    ("ti", "text-indent"),
+    ("trf", "transform"),
+    ("trs", "transition"),
+    ("v", "visibility"),
+    ("va", "vertical-align"),
+    ("w", "width"),
+    ("z", "z-index"),
+];
+
+/// Properties whose numeric values don't get a default unit.
+const UNITLESS_PROPERTIES: &[&str] = &[
+    "flex",
+    "flex-grow",
+    "flex-shrink",
+    "font-weight",
+    "line-height",
+    "opacity",
+    "order",
+    "z-index",
+];
+
+/// Single letter values, as in `m0-a` or `bd-n`.
+const KEYWORDS: &[(&str, &str)] = &[
+    ("a", "auto"),
+    ("d", "dashed"),
+    ("dt", "dotted"),
+    ("i", "inherit"),
+    ("n", "none"),
+    ("s", "solid"),
+];
+
+/// Expands an abbreviation into a snippet for a declaration, or returns `None`
+/// if it isn't a valid abbreviation.
+pub(super) fn expand(abbreviation: &str) -> Option<String> {
+    let (abbreviation, important) = match abbreviation.strip_suffix('!') {
+        Some(abbreviation) => (abbreviation, " !important"),
+        None => (abbreviation, ""),
+    };
+
+    if let Some((_, declaration)) = DECLARATIONS
+        .iter()
+        .find(|(declaration, _)| *declaration == abbreviation)
+    {
+        return Some(format!("{declaration}{important};"));
+    }
+
+    // The property abbreviation is the longest known prefix, so that `bdrs5`
+    // is read as `border-radius: 5px` rather than `border-right: s5`.
+    let (property, value) = PROPERTIES
+        .iter()
+        .filter(|(property, _)| abbreviation.starts_with(property))
+        .max_by_key(|(property, _)| property.len())
+        .map(|(property, name)| (*name, &abbreviation[property.len()..]))?;
+
+    if value.is_empty() {
+        return Some(format!("{property}: ${{1}}{important};"));
+    }
+    let value = expand_value(property, value)?;
+    Some(format!("{property}: {value}{important};"))
+}
+
+/// Expands values separated by `-`, such as `10-a` or `-5--10`, where a
+/// second `-` makes a number negative.
+fn expand_value(property: &str, value: &str) -> Option<String> {
+    if let Some(color) = value.strip_prefix('#') {
+        return expand_color(color);
+    }
+
+    let mut values = Vec::new();
+    let mut rest = value;
+    loop {
+        let negative = rest.starts_with('-')
+            && rest[1..].starts_with(|char: char| char.is_ascii_digit() || char == '.');
+        let start = if negative { 1 } else { 0 };
+        let end = rest[start..].find('-').map_or(rest.len(), |ix| ix + start);
+        values.push(expand_token(property, &rest[start..end], negative)?);
+        match rest[end..].strip_prefix('-') {
+            Some(next) => rest = next,
+            None => break,
+        }
+    }
+    Some(values.join(" "))
+}
+
+fn expand_token(property: &str, token: &str, negative: bool) -> Option<String> {
+    if token.is_empty() {
+        return None;
+    }
+    if let Some((_, keyword)) = KEYWORDS.iter().find(|(keyword, _)| *keyword == token) {
+        return (!negative).then(|| keyword.to_string());
+    }
+
+    let number_len = token
+        .find(|char: char| !char.is_ascii_digit() && char != '.')
+        .unwrap_or(token.len());
+    if number_len == 0 {
+        return (!negative).then(|| token.to_string());
+    }
+    let (number, unit) = token.split_at(number_len);
+    let is_zero = number.parse::<f64>().ok()? == 0.0;
+
+    let unit = match unit {
+        "" if is_zero => "",
+        "" if UNITLESS_PROPERTIES.contains(&property) => "",
+        "" => "px",
+        "p" => "%",
+        "e" => "em",
+        "r" => "rem",
+        "x" => "ex",
+        unit if unit.chars().all(|char| char.is_ascii_alphabetic()) => unit,
+        _ => return None,
+    };
+    let sign = if negative { "-" } else { "" };
+    Some(format!("{sign}{number}{unit}"))
+}
+
+/// Expands the shorthand colors `#f` and `#ab` to `#ffffff` and `#ababab`.
+fn expand_color(color: &str) -> Option<String> {
+    if color.is_empty() || !color.chars().all(|char| char.is_ascii_hexdigit()) {
+        return None;
+    }
+    let color = match color.len() {
+        1 => color.repeat(6),
+        2 => color.repeat(3),
+        3 | 4 | 6 | 8 => color.to_string(),
+        _ => return None,
+    };
+    Some(format!("#{}", color.to_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_expand() {
+        assert_eq!(expand("df").as_deref(), Some("display: flex;"));
+        assert_eq!(
+            expand("posa!").as_deref(),
+            Some("position: absolute !important;")
+        );
+        assert_eq!(expand("m").as_deref(), Some("margin: ${1};"));
+        assert_eq!(expand("m10").as_deref(), Some("margin: 10px;"));
+        assert_eq!(expand("m0-a").as_deref(), Some("margin: 0 auto;"));
+        assert_eq!(
+            expand("p10-2e-5p").as_deref(),
+            Some("padding: 10px 2em 5%;")
+        );
+        assert_eq!(expand("mt-5").as_deref(), Some("margin-top: -5px;"));
+        assert_eq!(expand("m5--10").as_deref(), Some("margin: 5px -10px;"));
+        assert_eq!(
+            expand("bdrs1.5r").as_deref(),
+            Some("border-radius: 1.5rem;")
+        );
+        assert_eq!(expand("lh1.5").as_deref(), Some("line-height: 1.5;"));
+        assert_eq!(expand("z10").as_deref(), Some("z-index: 10;"));
+        assert_eq!(
+            expand("bd1-s-n").as_deref(),
+            Some("border: 1px solid none;")
+        );
+        assert_eq!(expand("c#f").as_deref(), Some("color: #ffffff;"));
+        assert_eq!(
+            expand("bgc#A0B").as_deref(),
+            Some("background-color: #a0b;")
+        );
+
+        assert_eq!(expand("c#ggg"), None);
+        assert_eq!(expand("qq"), None);
+        assert_eq!(expand("m10-"), None);
+    }
+}
@@ -0,0 +1,786 @@
+//! Expansion of Emmet abbreviations such as `ul>li.item$*3` into HTML or JSX elements.
+
+use std::{borrow::Cow, fmt::Write as _};
+
+/// Abbreviations expanding to more elements than this aren't expanded, so that
+/// typing `div*100000` doesn't freeze the editor.
+const MAX_ELEMENTS: usize = 1000;
+
+/// Abbreviations of elements, with the tag and attributes they expand to. Empty
+/// attribute values become tab stops.
+const ALIASES: &[(&str, &str, &[(&str, &str)])] = &[
+    ("a", "a", &[("href", "")]),
+    ("a:link", "a", &[("href", "http://")]),
+    ("a:mail", "a", &[("href", "mailto:")]),
+    ("art", "article", &[]),
+    ("bq", "blockquote", &[]),
+    ("btn", "button", &[]),
+    ("btn:r", "button", &[("type", "reset")]),
+    ("btn:s", "button", &[("type", "submit")]),
+    ("cap", "caption", &[]),
+    ("fig", "figure", &[]),
+    ("figc", "figcaption", &[]),
+    ("form", "form", &[("action", "")]),
+    ("ftr", "footer", &[]),
+    ("hdr", "header", &[]),
+    ("img", "img", &[("src", ""), ("alt", "")]),
+    ("input", "input", &[("type", "text")]),
+    ("label", "label", &[("for", "")]),
+    ("link", "link", &[("rel", "stylesheet"), ("href", "")]),
+    (
+        "link:css",
+        "link",
+        &[("rel", "stylesheet"), ("href", "style.css")],
+    ),
+    ("meta:utf", "meta", &[("charset", "UTF-8")]),
+    (
+        "meta:vp",
+        "meta",
+        &[
+            ("name", "viewport"),
+            ("content", "width=device-width, initial-scale=1.0"),
+        ],
+    ),
+    ("opt", "option", &[("value", "")]),
+    ("option", "option", &[("value", "")]),
+    ("script:src", "script", &[("src", "")]),
+    ("sect", "section", &[]),
+    ("select", "select", &[("name", ""), ("id", "")]),
+    ("str", "strong", &[]),
+    (
+        "tarea",
+        "textarea",
+        &[("name", ""), ("id", ""), ("cols", "30"), ("rows", "10")],
+    ),
+];
+
+/// Abbreviations ending with `+` that expand to an element with its usual children.
+const SHORTHANDS: &[(&str, &str)] = &[
+    ("dl+", "dl>dt+dd"),
+    ("ol+", "ol>li"),
+    ("select+", "select>option"),
+    ("table+", "table>tr>td"),
+    ("tr+", "tr>td"),
+    ("ul+", "ul>li"),
+];
+
+/// Tags that abbreviations consisting of a single name are expanded for. Other
+/// names need an operator, such as in `custom-element.class`, to be expanded.
+const KNOWN_TAGS: &str = "\
+    abbr address area article aside audio b base blockquote body br button canvas caption \
+    cite code col colgroup data datalist dd del details dfn dialog div dl dt em embed \
+    fieldset figcaption figure footer h1 h2 h3 h4 h5 h6 head header hr html i iframe ins kbd \
+    legend li main map mark menu meta meter nav noscript object ol optgroup output p picture \
+    pre progress q s samp script section slot small source span strong style sub summary sup \
+    svg table tbody td template textarea tfoot th thead time title tr track u ul var video \
+    wbr";
+
+const VOID_ELEMENTS: &str = "\
+    area base br col embed hr img input link meta source track wbr";
+
+/// Elements that are kept on the same line as their siblings and parent.
+const INLINE_ELEMENTS: &str = "\
+    a abbr b bdi bdo br button cite code data del dfn em i img input ins kbd label mark q s \
+    samp select small span strong sub sup textarea time u var";
+
+/// Expands an abbreviation into a snippet, or returns `None` if it isn't a
+/// valid abbreviation.
+pub(super) fn expand(abbreviation: &str, jsx: bool, indent: &str) -> Option<String> {
+    if abbreviation == "!" || abbreviation == "html:5" {
+        return (!jsx).then(|| boilerplate(indent));
+    }
+    if !abbreviation.contains(['.', '#', '[', '{', '>', '+', '^', '*', '(', '/'])
+        && !is_listed(KNOWN_TAGS, abbreviation)
+        && !is_alias(abbreviation)
+    {
+        return None;
+    }
+
+    let abbreviation = expand_shorthand(abbreviation);
+    let nodes = Parser::parse(&abbreviation)?;
+    let mut expander = Expander { element_count: 0 };
+    let mut elements = Vec::new();
+    expander.expand_nodes(&nodes, None, Numbering::default(), &mut elements)?;
+
+    let mut renderer = Renderer {
+        indent,
+        jsx,
+        next_tab_stop: 1,
+        output: String::new(),
+    };
+    renderer.render_list(&elements, 0);
+    Some(renderer.output)
+}
+
+/// Returns whether a whitespace-separated list of names contains the given one.
+fn is_listed(list: &str, name: &str) -> bool {
+    list.split_whitespace().any(|listed| listed == name)
+}
+
+fn is_alias(name: &str) -> bool {
+    name.starts_with("input:") || ALIASES.iter().any(|(alias, _, _)| *alias == name)
+}
+
+fn expand_shorthand(abbreviation: &str) -> Cow<'_, str> {
+    for (shorthand, expansion) in SHORTHANDS {
+        if let Some(prefix) = abbreviation.strip_suffix(shorthand)
+            && (prefix.is_empty() || prefix.ends_with(['>', '+', '^', '(']))
+        {
+            return Cow::Owned(format!("{prefix}{expansion}"));
+        }
+    }
+    Cow::Borrowed(abbreviation)
+}
+
+fn boilerplate(indent: &str) -> String {
+    [
+        "<!DOCTYPE html>",
+        "<html lang=\"en\">",
+        "<head>",
+        &format!("{indent}<meta charset=\"UTF-8\">"),
+        &format!(
+            "{indent}<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">"
+        ),
+        &format!("{indent}<title>${{1:Document}}</title>"),
+        "</head>",
+        "<body>",
+        &format!("{indent}${{2}}"),
+        "</body>",
+        "</html>",
+    ]
+    .join("\n")
+}
+
+#[derive(Debug)]
+struct Node {
+    kind: NodeKind,
+    repeat: usize,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Element(Element),
+    Group(Vec<Node>),
+}
+
+#[derive(Debug, Default)]
+struct Element {
+    /// The element's name, which is empty if it is implied by its parent.
+    name: String,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, String)>,
+    text: Option<String>,
+    self_closing: bool,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn is_text(&self) -> bool {
+        self.name.is_empty()
+            && self.id.is_none()
+            && self.classes.is_empty()
+            && self.attributes.is_empty()
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    offset: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse(source: &'a str) -> Option<Vec<Node>> {
+        let mut parser = Self { source, offset: 0 };
+        let nodes = parser.parse_sequence()?;
+        (parser.offset == source.len()).then_some(nodes)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.offset..].chars().next()
+    }
+
+    fn eat(&mut self, char: char) -> bool {
+        let is_next = self.peek() == Some(char);
+        if is_next {
+            self.offset += char.len_utf8();
+        }
+        is_next
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let rest = &self.source[self.offset..];
+        let len = rest.find(|char| !predicate(char)).unwrap_or(rest.len());
+        self.offset += len;
+        &rest[..len]
+    }
+
+    /// Takes the text up to the `close` character matching an `open` character
+    /// that was just consumed.
+    fn take_balanced(&mut self, open: char, close: char) -> Option<&'a str> {
+        let rest = &self.source[self.offset..];
+        let mut depth = 0;
+        for (ix, char) in rest.char_indices() {
+            if char == open {
+                depth += 1;
+            } else if char == close {
+                if depth == 0 {
+                    self.offset += ix + close.len_utf8();
+                    return Some(&rest[..ix]);
+                }
+                depth -= 1;
+            }
+        }
+        None
+    }
+
+    /// Parses terms joined by the `>` (child), `+` (sibling) and `^` (climb up) operators.
+    fn parse_sequence(&mut self) -> Option<Vec<Node>> {
+        // The lists of siblings from the outermost to the one currently being
+        // added to, whose parent is the last node of the previous list.
+        let mut levels = vec![Vec::new()];
+        loop {
+            let node = self.parse_term()?;
+            levels.last_mut()?.push(node);
+            if self.eat('>') {
+                let parent = levels.last()?.last()?;
+                if !matches!(parent.kind, NodeKind::Element(_)) {
+                    return None;
+                }
+                levels.push(Vec::new());
+            } else if self.eat('+') {
+                continue;
+            } else if self.peek() == Some('^') {
+                while self.eat('^') {
+                    if levels.len() > 1 {
+                        close_level(&mut levels);
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+        while levels.len() > 1 {
+            close_level(&mut levels);
+        }
+        levels.pop()
+    }
+
+    fn parse_term(&mut self) -> Option<Node> {
+        let kind = if self.eat('(') {
+            let children = self.parse_sequence()?;
+            if !self.eat(')') {
+                return None;
+            }
+            NodeKind::Group(children)
+        } else {
+            NodeKind::Element(self.parse_element()?)
+        };
+        let repeat = if self.eat('*') {
+            self.take_while(|char| char.is_ascii_digit())
+                .parse()
+                .ok()
+                .filter(|repeat| *repeat > 0)?
+        } else {
+            1
+        };
+        Some(Node { kind, repeat })
+    }
+
+    fn parse_element(&mut self) -> Option<Element> {
+        let mut element = Element {
+            name: self.take_while(is_name_char).to_string(),
+            ..Element::default()
+        };
+        loop {
+            if self.eat('#') {
+                let id = self.take_while(is_name_char);
+                if id.is_empty() {
+                    return None;
+                }
+                element.id = Some(id.to_string());
+            } else if self.eat('.') {
+                let class = self.take_while(is_name_char);
+                if class.is_empty() {
+                    return None;
+                }
+                element.classes.push(class.to_string());
+            } else if self.eat('[') {
+                self.parse_attributes(&mut element.attributes)?;
+            } else if self.eat('{') {
+                element.text = Some(self.take_balanced('{', '}')?.to_string());
+            } else if self.eat('/') {
+                element.self_closing = true;
+                break;
+            } else {
+                break;
+            }
+        }
+        if element.is_text() && element.text.is_none() {
+            return None;
+        }
+        Some(element)
+    }
+
+    /// Parses attributes such as `[href="#" target=_blank disabled]`, after the opening bracket.
+    fn parse_attributes(&mut self, attributes: &mut Vec<(String, String)>) -> Option<()> {
+        loop {
+            self.take_while(char::is_whitespace);
+            if self.eat(']') {
+                return Some(());
+            }
+            let name = self.take_while(|char| !char.is_whitespace() && !matches!(char, '=' | ']'));
+            if name.is_empty() {
+                return None;
+            }
+            let value = if self.eat('=') {
+                match self.peek()? {
+                    quote @ ('"' | '\'') => {
+                        self.eat(quote);
+                        let rest = &self.source[self.offset..];
+                        let end = rest.find(quote)?;
+                        self.offset += end + 1;
+                        rest[..end].to_string()
+                    }
+                    '{' => {
+                        self.eat('{');
+                        format!("{{{}}}", self.take_balanced('{', '}')?)
+                    }
+                    _ => self
+                        .take_while(|char| !char.is_whitespace() && char != ']')
+                        .to_string(),
+                }
+            } else {
+                String::new()
+            };
+            attributes.push((name.to_string(), value));
+        }
+    }
+}
+
+fn is_name_char(char: char) -> bool {
+    char.is_alphanumeric() || matches!(char, '-' | '_' | ':' | '$' | '@' | '!')
+}
+
+fn close_level(levels: &mut Vec<Vec<Node>>) {
+    let Some(children) = levels.pop() else {
+        return;
+    };
+    if let Some(Node {
+        kind: NodeKind::Element(parent),
+        ..
+    }) = levels.last_mut().and_then(|level| level.last_mut())
+    {
+        parent.children.extend(children);
+    }
+}
+
+/// The position of an element among the copies made by the innermost
+/// multiplication, which `$` is replaced with.
+#[derive(Clone, Copy, Default)]
+struct Numbering {
+    index: usize,
+    count: usize,
+}
+
+impl Numbering {
+    /// Replaces runs of `$` with the element's number, padded with zeros to the
+    /// length of the run. `$@-` counts down, and `$@3` starts counting at 3.
+    fn apply(&self, text: &str) -> String {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(ix) = rest.find('$') {
+            result.push_str(&rest[..ix]);
+            let run = &rest[ix..];
+            let width = run.len() - run.trim_start_matches('$').len();
+            rest = &run[width..];
+
+            let mut reverse = false;
+            let mut start = 1;
+            if let Some(modifier) = rest.strip_prefix('@') {
+                let modifier = match modifier.strip_prefix('-') {
+                    Some(modifier) => {
+                        reverse = true;
+                        modifier
+                    }
+                    None => modifier,
+                };
+                let digits = modifier.len()
+                    - modifier
+                        .trim_start_matches(|char: char| char.is_ascii_digit())
+                        .len();
+                start = modifier[..digits].parse().unwrap_or(1);
+                rest = &modifier[digits..];
+            }
+
+            let count = self.count.max(1);
+            let number = if reverse {
+                start + count - 1 - self.index
+            } else {
+                start + self.index
+            };
+            write!(result, "{number:0width$}").ok();
+        }
+        result.push_str(rest);
+        result
+    }
+}
+
+enum Output {
+    Element {
+        name: String,
+        /// Attributes with empty values are rendered as tab stops.
+        attributes: Vec<(String, String)>,
+        children: Vec<Output>,
+        self_closing: bool,
+    },
+    Text(String),
+}
+
+impl Output {
+    fn is_inline(&self) -> bool {
+        match self {
+            Output::Text(_) => true,
+            Output::Element { name, children, .. } => {
+                is_listed(INLINE_ELEMENTS, name) && children.iter().all(Self::is_inline)
+            }
+        }
+    }
+}
+
+struct Expander {
+    element_count: usize,
+}
+
+impl Expander {
+    fn expand_nodes(
+        &mut self,
+        nodes: &[Node],
+        parent: Option<&str>,
+        numbering: Numbering,
+        output: &mut Vec<Output>,
+    ) -> Option<()> {
+        for node in nodes {
+            for index in 0..node.repeat {
+                let numbering = if node.repeat > 1 {
+                    Numbering {
+                        index,
+                        count: node.repeat,
+                    }
+                } else {
+                    numbering
+                };
+                match &node.kind {
+                    NodeKind::Group(children) => {
+                        self.expand_nodes(children, parent, numbering, output)?
+                    }
+                    NodeKind::Element(element) => {
+                        output.push(self.expand_element(element, parent, numbering)?)
+                    }
+                }
+            }
+        }
+        Some(())
+    }
+
+    fn expand_element(
+        &mut self,
+        element: &Element,
+        parent: Option<&str>,
+        numbering: Numbering,
+    ) -> Option<Output> {
+        self.element_count += 1;
+        if self.element_count > MAX_ELEMENTS {
+            return None;
+        }
+        if element.is_text() {
+            return Some(Output::Text(
+                numbering.apply(element.text.as_deref().unwrap_or_default()),
+            ));
+        }
+
+        let name = numbering.apply(&element.name);
+        let (name, mut attributes) = if name.is_empty() {
+            (implicit_name(parent).to_string(), Vec::new())
+        } else if let Some(input_type) = name.strip_prefix("input:") {
+            (
+                "input".to_string(),
+                vec![
+                    ("type".to_string(), input_type.to_string()),
+                    ("name".to_string(), String::new()),
+                    ("id".to_string(), String::new()),
+                ],
+            )
+        } else if let Some((_, tag, defaults)) = ALIASES.iter().find(|(alias, _, _)| *alias == name)
+        {
+            (
+                tag.to_string(),
+                defaults
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            )
+        } else {
+            (name, Vec::new())
+        };
+
+        let mut set_attribute = |name: &str, value: String| match attributes
+            .iter_mut()
+            .find(|(existing, _)| existing == name)
+        {
+            Some((_, existing_value)) => *existing_value = value,
+            None => attributes.push((name.to_string(), value)),
+        };
+        if let Some(id) = &element.id {
+            set_attribute("id", numbering.apply(id));
+        }
+        if !element.classes.is_empty() {
+            let classes = element
+                .classes
+                .iter()
+                .map(|class| numbering.apply(class))
+                .collect::<Vec<_>>();
+            set_attribute("class", classes.join(" "));
+        }
+        for (name, value) in &element.attributes {
+            set_attribute(name, numbering.apply(value));
+        }
+
+        let mut children = Vec::new();
+        if let Some(text) = &element.text {
+            children.push(Output::Text(numbering.apply(text)));
+        }
+        self.expand_nodes(&element.children, Some(&name), numbering, &mut children)?;
+
+        Some(Output::Element {
+            self_closing: element.self_closing || is_listed(VOID_ELEMENTS, name),
+            name,
+            attributes,
+            children,
+        })
+    }
+}
+
+/// Returns the name of elements whose name is omitted, such as in `ul>.item`.
+fn implicit_name(parent: Option<&str>) -> &'static str {
+    match parent {
+        Some("ul" | "ol") => "li",
+        Some("table" | "thead" | "tbody" | "tfoot") => "tr",
+        Some("tr") => "td",
+        Some("select" | "optgroup") => "option",
+        Some(parent) if is_listed(INLINE_ELEMENTS, parent) => "span",
+        _ => "div",
+    }
+}
+
+struct Renderer<'a> {
+    indent: &'a str,
+    jsx: bool,
+    next_tab_stop: usize,
+    output: String,
+}
+
+impl Renderer<'_> {
+    /// Renders siblings on one line if they're all inline elements, or on separate lines otherwise.
+    fn render_list(&mut self, nodes: &[Output], depth: usize) {
+        let multiline = nodes.iter().any(|node| !node.is_inline());
+        for (ix, node) in nodes.iter().enumerate() {
+            if multiline && ix > 0 {
+                self.newline(depth);
+            }
+            self.render(node, depth);
+        }
+    }
+
+    fn render(&mut self, node: &Output, depth: usize) {
+        let (name, attributes, children, self_closing) = match node {
+            Output::Text(text) => {
+                self.push_text(text);
+                return;
+            }
+            Output::Element {
+                name,
+                attributes,
+                children,
+                self_closing,
+            } => (name, attributes, children, *self_closing),
+        };
+
+        self.output.push('<');
+        self.push_text(name);
+        for (attribute, value) in attributes {
+            let attribute = match attribute.as_str() {
+                "class" if self.jsx => "className",
+                "for" if self.jsx => "htmlFor",
+                attribute => attribute,
+            };
+            self.output.push(' ');
+            self.push_text(attribute);
+            self.output.push('=');
+            if value.is_empty() {
+                self.output.push('"');
+                self.push_tab_stop();
+                self.output.push('"');
+            } else if self.jsx && value.starts_with('{') && value.ends_with('}') {
+                self.push_text(value);
+            } else {
+                self.output.push('"');
+                self.push_text(value);
+                self.output.push('"');
+            }
+        }
+
+        if self_closing && children.is_empty() {
+            if self.jsx || !is_listed(VOID_ELEMENTS, name) {
+                self.output.push_str(" />");
+            } else {
+                self.output.push('>');
+            }
+            return;
+        }
+
+        self.output.push('>');
+        if children.is_empty() {
+            self.push_tab_stop();
+        } else if children.iter().all(Output::is_inline) {
+            self.render_list(children, depth);
+        } else {
+            self.newline(depth + 1);
+            self.render_list(children, depth + 1);
+            self.newline(depth);
+        }
+        self.output.push_str("</");
+        self.push_text(name);
+        self.output.push('>');
+    }
+
+    fn newline(&mut self, depth: usize) {
+        self.output.push('\n');
+        for _ in 0..depth {
+            self.output.push_str(self.indent);
+        }
+    }
+
+    fn push_tab_stop(&mut self) {
+        write!(self.output, "${{{}}}", self.next_tab_stop).ok();
+        self.next_tab_stop += 1;
+    }
+
+    /// Pushes text, escaping the characters that have a meaning in snippets.
+    fn push_text(&mut self, text: &str) {
+        for char in text.chars() {
+            if matches!(char, '$' | '}' | '\\') {
+                self.output.push('\\');
+            }
+            self.output.push(char);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    #[track_caller]
+    fn assert_expands(abbreviation: &str, expected: &str) {
+        assert_eq!(
+            expand(abbreviation, false, "  ").as_deref(),
+            Some(expected),
+            "expanding {abbreviation:?}"
+        );
+    }
+
+    #[test]
+    fn test_expand_elements() {
+        assert_expands("div", "<div>${1}</div>");
+        assert_expands("a", "<a href=\"${1}\">${2}</a>");
+        assert_expands("br", "<br>");
+        assert_expands(
+            "div#main.container.wide",
+            "<div id=\"main\" class=\"container wide\">${1}</div>",
+        );
+        assert_expands(".card", "<div class=\"card\">${1}</div>");
+        assert_expands(
+            "a[href=\"#top\" target=_blank]{Back to top}",
+            "<a href=\"#top\" target=\"_blank\">Back to top</a>",
+        );
+        assert_expands(
+            "input:email",
+            "<input type=\"email\" name=\"${1}\" id=\"${2}\">",
+        );
+        assert_expands("p>a", "<p><a href=\"${1}\">${2}</a></p>");
+        assert_expands(
+            "li{Item $}*2",
+            indoc! {"
+                <li>Item 1</li>
+                <li>Item 2</li>"},
+        );
+
+        assert_eq!(expand("hello", false, "  "), None);
+        assert_eq!(expand("div>", false, "  "), None);
+        assert_eq!(expand("div*0", false, "  "), None);
+        assert_eq!(expand("div*100000", false, "  "), None);
+    }
+
+    #[test]
+    fn test_expand_operators() {
+        assert_expands(
+            "ul>li.item$*3",
+            indoc! {r#"
+                <ul>
+                  <li class="item1">${1}</li>
+                  <li class="item2">${2}</li>
+                  <li class="item3">${3}</li>
+                </ul>"#},
+        );
+        assert_expands(
+            "header>nav>a+a^main",
+            indoc! {r#"
+                <header>
+                  <nav><a href="${1}">${2}</a><a href="${3}">${4}</a></nav>
+                  <main>${5}</main>
+                </header>"#},
+        );
+        assert_expands(
+            "(dt+dd)*2",
+            indoc! {"
+                <dt>${1}</dt>
+                <dd>${2}</dd>
+                <dt>${3}</dt>
+                <dd>${4}</dd>"},
+        );
+        assert_expands(
+            "h$$@-3*2",
+            indoc! {"
+                <h04>${1}</h04>
+                <h03>${2}</h03>"},
+        );
+        assert_expands(
+            "table+",
+            indoc! {"
+                <table>
+                  <tr>
+                    <td>${1}</td>
+                  </tr>
+                </table>"},
+        );
+    }
+
+    #[test]
+    fn test_expand_jsx() {
+        assert_eq!(
+            expand(
+                "label.field[for=name onClick={() => focus()}]+br",
+                true,
+                "  "
+            )
+            .as_deref(),
+            Some(
+                "<label htmlFor=\"name\" className=\"field\" onClick={() => focus()\\}>${1}</label><br />"
+            )
+        );
+        assert_eq!(expand("!", true, "  "), None);
+    }
+}
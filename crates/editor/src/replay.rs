@@ -0,0 +1,126 @@
+//! Replays recorded input, as used by keyboard macros and vim's repeat and registers.
+
+use std::{cell::RefCell, ops::Range, rc::Rc};
+
+use gpui::{Action, App, Entity, Window};
+use workspace::Workspace;
+
+use crate::Editor;
+
+/// Guards against replays that would take forever, e.g. when replaying a macro a huge number of
+/// times.
+pub const MAX_REPLAYED_STEPS: usize = 10000;
+
+/// A recorded input that can be replayed.
+pub trait Replayable: Clone + 'static {
+    fn replay(self, window: &mut Window, cx: &mut App);
+}
+
+struct ReplayerState<T> {
+    steps: Vec<T>,
+    running: bool,
+    ix: usize,
+}
+
+/// Replays steps one at a time, letting the effects of each step settle before the next one is
+/// dispatched.
+pub struct Replayer<T> {
+    state: Rc<RefCell<ReplayerState<T>>>,
+    on_finish: Rc<dyn Fn(&mut App)>,
+}
+
+impl<T> Clone for Replayer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            on_finish: self.on_finish.clone(),
+        }
+    }
+}
+
+impl<T: Replayable> Replayer<T> {
+    /// Creates a replayer that calls `on_finish` once it ran out of steps to replay.
+    pub fn new(on_finish: impl Fn(&mut App) + 'static) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(ReplayerState {
+                steps: Vec::new(),
+                running: false,
+                ix: 0,
+            })),
+            on_finish: Rc::new(on_finish),
+        }
+    }
+
+    /// Replays the steps right after the current one. Returns whether this started replaying,
+    /// rather than adding to a replay in progress.
+    pub fn replay(&self, steps: Vec<T>, window: &mut Window, cx: &mut App) -> bool {
+        let mut state = self.state.borrow_mut();
+        let ix = state.ix;
+        state.steps.splice(ix..ix, steps);
+        if state.running {
+            return false;
+        }
+        state.running = true;
+        let this = self.clone();
+        window.defer(cx, move |window, cx| this.next(window, cx));
+        true
+    }
+
+    /// Drops the steps that haven't been replayed yet.
+    pub fn stop(&self) {
+        self.state.borrow_mut().steps.clear();
+    }
+
+    /// Returns how many more steps can be replayed before the replay is aborted.
+    pub fn remaining_steps(&self) -> usize {
+        MAX_REPLAYED_STEPS.saturating_sub(self.state.borrow().steps.len())
+    }
+
+    fn next(self, window: &mut Window, cx: &mut App) {
+        let mut state = self.state.borrow_mut();
+        let step = if state.ix < MAX_REPLAYED_STEPS {
+            state.steps.get(state.ix).cloned()
+        } else {
+            log::error!("Aborting replay after {MAX_REPLAYED_STEPS} steps");
+            None
+        };
+        state.ix += 1;
+        drop(state);
+
+        let Some(step) = step else {
+            (self.on_finish)(cx);
+            return;
+        };
+        step.replay(window, cx);
+        window.defer(cx, move |window, cx| self.next(window, cx));
+    }
+}
+
+/// Returns whether a recorded action should be dispatched when replaying.
+pub fn should_replay(action: &dyn Action) -> bool {
+    // Skip so that we don't leave the character palette open.
+    !crate::actions::ShowCharacterPalette.partial_eq(action)
+}
+
+/// Types recorded text into the active editor of the window.
+pub fn replay_insertion(
+    text: &str,
+    utf16_range_to_replace: Option<Range<isize>>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    if let Some(editor) = active_editor(window, cx) {
+        editor.update(cx, |editor, cx| {
+            editor.replay_insert_event(text, utf16_range_to_replace, window, cx)
+        });
+    }
+}
+
+/// Returns the active editor of the window's workspace.
+pub fn active_editor(window: &mut Window, cx: &mut App) -> Option<Entity<Editor>> {
+    let workspace = window.root::<Workspace>().flatten()?;
+    workspace
+        .read(cx)
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+}
@@ -287,6 +287,7 @@ impl TryFrom<DebugScenario> for extension::DebugScenario {
             build: value.build.map(Into::into),
             config: serde_json::Value::from_str(&value.config)?,
             tcp_connection: value.tcp_connection.map(Into::into),
+            inputs: Vec::new(),
         })
     }
 }
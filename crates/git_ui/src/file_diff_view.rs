@@ -5,8 +5,8 @@ use buffer_diff::BufferDiff;
 use editor::{Editor, EditorEvent, MultiBuffer};
 use futures::{FutureExt, select_biased};
 use gpui::{
-    AnyElement, App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, FocusHandle,
-    Focusable, IntoElement, Render, Task, Window,
+    AnyElement, App, AppContext as _, AsyncApp, AsyncWindowContext, Context, Entity, EventEmitter,
+    FocusHandle, Focusable, IntoElement, Render, Task, WeakEntity, Window,
 };
 use language::{Buffer, LanguageRegistry};
 use project::Project;
@@ -49,32 +49,57 @@ impl FileDiffView {
             let old_buffer = project
                 .update(cx, |project, cx| project.open_local_buffer(&old_path, cx))
                 .await?;
-            let new_buffer = project
-                .update(cx, |project, cx| project.open_local_buffer(&new_path, cx))
-                .await?;
-            let languages = project.update(cx, |project, _| project.languages().clone());
-
-            let buffer_diff = build_buffer_diff(&old_buffer, &new_buffer, languages, cx).await?;
-
-            workspace.update_in(cx, |workspace, window, cx| {
-                let diff_view = cx.new(|cx| {
-                    FileDiffView::new(
-                        old_buffer,
-                        new_buffer,
-                        buffer_diff,
-                        project.clone(),
-                        window,
-                        cx,
-                    )
-                });
+            Self::add_to_workspace(old_buffer, new_path, workspace, cx).await
+        })
+    }
+
+    /// Compares a file with a buffer that was already opened, such as one that was made
+    /// read-only.
+    pub fn open_with_old_buffer(
+        old_buffer: Entity<Buffer>,
+        new_path: PathBuf,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace = workspace.weak_handle();
+        window.spawn(cx, async move |cx| {
+            Self::add_to_workspace(old_buffer, new_path, workspace, cx).await
+        })
+    }
+
+    async fn add_to_workspace(
+        old_buffer: Entity<Buffer>,
+        new_path: PathBuf,
+        workspace: WeakEntity<Workspace>,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<Entity<Self>> {
+        let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+        let new_buffer = project
+            .update(cx, |project, cx| project.open_local_buffer(&new_path, cx))
+            .await?;
+        let languages = project.update(cx, |project, _| project.languages().clone());
+
+        let buffer_diff = build_buffer_diff(&old_buffer, &new_buffer, languages, cx).await?;
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let diff_view = cx.new(|cx| {
+                FileDiffView::new(
+                    old_buffer,
+                    new_buffer,
+                    buffer_diff,
+                    project.clone(),
+                    window,
+                    cx,
+                )
+            });
 
-                let pane = workspace.active_pane();
-                pane.update(cx, |pane, cx| {
-                    pane.add_item(Box::new(diff_view.clone()), true, true, None, window, cx);
-                });
+            let pane = workspace.active_pane();
+            pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(diff_view.clone()), true, true, None, window, cx);
+            });
 
-                diff_view
-            })
+            diff_view
         })
     }
 
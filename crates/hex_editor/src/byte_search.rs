@@ -0,0 +1,113 @@
+use std::ops::Range;
+
+use project::search::SearchQuery;
+use smol::future::yield_now;
+
+/// Searching stops once this many matches were found, as the search bar can't
+/// present more than that in a useful way anyway.
+const MAX_MATCHES: usize = 10_000;
+
+/// The number of positions checked between two yields, so that an outdated
+/// search can be dropped quickly.
+const CHUNK_LEN: usize = 1024 * 1024;
+
+/// Parses a pattern of hex bytes such as `DE AD ?? EF`, where `??` matches any
+/// byte. Whitespace is ignored. Returns `None` if the text isn't such a pattern.
+pub fn parse_hex_pattern(text: &str) -> Option<Vec<Option<u8>>> {
+    let digits = text
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    digits
+        .chunks(2)
+        .map(|pair| match pair {
+            ['?', '?'] => Some(None),
+            [high, low] => {
+                let byte = (high.to_digit(16)? << 4) | low.to_digit(16)?;
+                Some(Some(byte as u8))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Finds the ranges of the bytes matching a pattern parsed by [`parse_hex_pattern`].
+pub async fn find_hex_pattern(bytes: &[u8], pattern: &[Option<u8>]) -> Vec<Range<usize>> {
+    let mut matches = Vec::new();
+    if pattern.is_empty() || pattern.len() > bytes.len() {
+        return matches;
+    }
+
+    let last_start = bytes.len() - pattern.len();
+    let mut chunk_start = 0;
+    while chunk_start <= last_start && matches.len() < MAX_MATCHES {
+        let chunk_end = (chunk_start + CHUNK_LEN).min(last_start + 1);
+        for start in chunk_start..chunk_end {
+            let candidate = &bytes[start..start + pattern.len()];
+            let is_match = candidate
+                .iter()
+                .zip(pattern)
+                .all(|(byte, expected)| expected.is_none_or(|expected| expected == *byte));
+            if is_match {
+                matches.push(start..start + pattern.len());
+                if matches.len() == MAX_MATCHES {
+                    break;
+                }
+            }
+        }
+        chunk_start = chunk_end;
+        yield_now().await;
+    }
+    matches
+}
+
+/// Finds the ranges of the bytes matching the query's text.
+pub fn find_text(bytes: &[u8], query: &SearchQuery) -> Vec<Range<usize>> {
+    match query {
+        SearchQuery::Text { search, .. } if !query.is_empty() => search
+            .find_iter(bytes)
+            .take(MAX_MATCHES)
+            .map(|mat| mat.start()..mat.end())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_pattern() {
+        assert_eq!(
+            parse_hex_pattern("DE ad ?? 0f"),
+            Some(vec![Some(0xDE), Some(0xAD), None, Some(0x0F)])
+        );
+        assert_eq!(
+            parse_hex_pattern("DEADBEEF").map(|pattern| pattern.len()),
+            Some(4)
+        );
+        assert_eq!(parse_hex_pattern("ABC"), None);
+        assert_eq!(parse_hex_pattern("hello!"), None);
+        assert_eq!(parse_hex_pattern("?A"), None);
+        assert_eq!(parse_hex_pattern(""), None);
+    }
+
+    #[test]
+    fn test_find_hex_pattern() {
+        let bytes = [0x00, 0xDE, 0xAD, 0x01, 0xDE, 0xAD, 0x02, 0xDE];
+        let pattern = parse_hex_pattern("DE AD ??").unwrap();
+        assert_eq!(
+            smol::block_on(find_hex_pattern(&bytes, &pattern)),
+            [1..4, 4..7]
+        );
+        let pattern = parse_hex_pattern("DE").unwrap();
+        assert_eq!(
+            smol::block_on(find_hex_pattern(&bytes, &pattern)),
+            [1..2, 4..5, 7..8]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use editor::Editor;
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity,
+};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::HexEditor;
+
+/// Moves the cursor of a [`HexEditor`] to a byte offset.
+pub(crate) struct GoToOffset {
+    offset_editor: Entity<Editor>,
+    hex_editor: WeakEntity<HexEditor>,
+    current_text: SharedString,
+    _subscription: Subscription,
+}
+
+impl GoToOffset {
+    pub(crate) fn new(
+        hex_editor: WeakEntity<HexEditor>,
+        current_offset: usize,
+        len: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let offset_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(&format!("{current_offset:#x}"), window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&offset_editor, |_, _, event, cx| {
+            if let editor::EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+
+        Self {
+            offset_editor,
+            hex_editor,
+            current_text: format!("Current offset: {current_offset:#x} of {len:#x}").into(),
+            _subscription: subscription,
+        }
+    }
+
+    fn offset_from_query(&self, cx: &App) -> Option<usize> {
+        parse_offset(&self.offset_editor.read(cx).text(cx))
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(offset) = self.offset_from_query(cx) {
+            self.hex_editor
+                .update(cx, |hex_editor, cx| {
+                    hex_editor.go_to_offset(offset, cx);
+                    hex_editor.focus_handle(cx).focus(window, cx);
+                })
+                .ok();
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+/// Parses a hexadecimal offset prefixed with `0x`, or a decimal one.
+fn parse_offset(text: &str) -> Option<usize> {
+    let text = text.trim();
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+impl ModalView for GoToOffset {}
+
+impl Focusable for GoToOffset {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.offset_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for GoToOffset {}
+
+impl Render for GoToOffset {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = match self.offset_from_query(cx) {
+            Some(offset) => format!("Go to offset {offset:#x} ({offset})").into(),
+            None => self.current_text.clone(),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("GoToOffset")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.offset_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(Color::Muted)),
+            )
+    }
+}
@@ -0,0 +1,293 @@
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use fs::{Fs, MTime};
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Task, WeakEntity};
+use project::{Project, ProjectEntryId, ProjectPath};
+
+/// Files with these extensions are almost always binary, so they're opened in the hex editor
+/// directly instead of trying to open them as text first. Other files are opened in the hex
+/// editor once they failed to open as text and their contents turn out to be binary.
+const BINARY_EXTENSIONS: &[&str] = &[
+    "a", "bin", "class", "dfu", "dll", "dylib", "elf", "exe", "img", "iso", "o", "obj", "pcap",
+    "pcapng", "so", "wasm",
+];
+
+/// Files larger than this aren't opened in the hex editor, as it keeps the whole file in memory.
+/// They are left to the large file viewer instead.
+pub(crate) const MAX_FILE_SIZE: u64 = 128 * 1024 * 1024;
+
+/// Returns whether the file is too large to be opened in the hex editor.
+pub(crate) fn is_too_large(project: &Project, project_path: &ProjectPath, cx: &App) -> bool {
+    project
+        .entry_for_path(project_path, cx)
+        .is_some_and(|entry| entry.size > MAX_FILE_SIZE)
+}
+
+/// How many bytes at the start of a file are inspected to tell whether it is binary.
+pub(crate) const BINARY_DETECTION_LEN: usize = 8000;
+
+/// Returns whether the given file contents are binary, i.e. contain NUL bytes or invalid UTF-8
+/// near the start of the file.
+pub(crate) fn is_binary(bytes: &[u8]) -> bool {
+    let prefix = &bytes[..bytes.len().min(BINARY_DETECTION_LEN)];
+    if prefix.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(prefix) {
+        Ok(_) => false,
+        // Invalid UTF-8 with no error length is a character cut off by the end of the prefix.
+        Err(error) => error.error_len().is_some(),
+    }
+}
+
+pub enum HexBufferEvent {
+    Edited,
+    Saved,
+    Reloaded,
+}
+
+/// An edit that can be undone, replacing `old` at `offset` with `new`.
+struct ByteEdit {
+    id: usize,
+    offset: usize,
+    old: Vec<u8>,
+    new: Vec<u8>,
+}
+
+/// The contents of a file being edited in the hex editor.
+///
+/// Unlike a text buffer, the whole file is kept in memory as plain bytes, and
+/// edits are only written back to disk when saving.
+pub struct HexBuffer {
+    project_path: ProjectPath,
+    abs_path: PathBuf,
+    entry_id: Option<ProjectEntryId>,
+    project: WeakEntity<Project>,
+    fs: Arc<dyn Fs>,
+    bytes: Vec<u8>,
+    undo_stack: Vec<ByteEdit>,
+    redo_stack: Vec<ByteEdit>,
+    next_edit_id: usize,
+    /// The id of the last edit that was applied when the file was last saved or loaded.
+    saved_edit_id: Option<usize>,
+    /// The modification time of the file when it was last saved or loaded.
+    saved_mtime: Option<MTime>,
+}
+
+impl HexBuffer {
+    /// Loads the file at the given project path, regardless of its contents.
+    pub fn open(
+        project: &Entity<Project>,
+        project_path: &ProjectPath,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let project_handle = project.downgrade();
+        let project = project.read(cx);
+        if is_too_large(project, project_path, cx) {
+            return Task::ready(Err(anyhow::anyhow!(
+                "{:?} is too large to open in the hex editor",
+                project_path.path
+            )));
+        }
+        let Some(abs_path) = project.absolute_path(project_path, cx) else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "{:?} is not in the project",
+                project_path.path
+            )));
+        };
+        let entry_id = project
+            .entry_for_path(project_path, cx)
+            .map(|entry| entry.id);
+        let fs = project.fs().clone();
+        let project_path = project_path.clone();
+
+        cx.spawn(async move |cx| {
+            let (bytes, mtime) = load(fs.as_ref(), &abs_path)
+                .await
+                .with_context(|| format!("loading {abs_path:?}"))?;
+            Ok(cx.new(|_| Self {
+                project_path,
+                abs_path,
+                entry_id,
+                project: project_handle,
+                fs,
+                bytes,
+                undo_stack: Vec::new(),
+                redo_stack: Vec::new(),
+                next_edit_id: 0,
+                saved_edit_id: None,
+                saved_mtime: mtime,
+            }))
+        })
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    pub fn project_path(&self) -> &ProjectPath {
+        &self.project_path
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.undo_stack.last().map(|edit| edit.id) != self.saved_edit_id
+    }
+
+    /// Returns whether the file was changed on disk since it was last saved or loaded, while
+    /// there are edits that saving would overwrite those changes with.
+    pub fn has_conflict(&self, cx: &App) -> bool {
+        if !self.is_dirty() {
+            return false;
+        }
+        let Some(project) = self.project.upgrade() else {
+            return false;
+        };
+        project
+            .read(cx)
+            .entry_for_path(&self.project_path, cx)
+            .and_then(|entry| entry.mtime)
+            .is_some_and(|mtime| Some(mtime) != self.saved_mtime)
+    }
+
+    /// Replaces the bytes in the given range, which may be empty to insert bytes.
+    pub fn edit(&mut self, range: Range<usize>, new: &[u8], cx: &mut Context<Self>) {
+        let range = range.start.min(self.bytes.len())..range.end.min(self.bytes.len());
+        if self.bytes[range.clone()] == *new {
+            return;
+        }
+        let old = self
+            .bytes
+            .splice(range.clone(), new.iter().copied())
+            .collect();
+        let id = self.next_edit_id;
+        self.next_edit_id += 1;
+        self.undo_stack.push(ByteEdit {
+            id,
+            offset: range.start,
+            old,
+            new: new.to_vec(),
+        });
+        self.redo_stack.clear();
+        cx.emit(HexBufferEvent::Edited);
+        cx.notify();
+    }
+
+    /// Undoes the last edit, returning the range of the restored bytes.
+    pub fn undo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let edit = self.undo_stack.pop()?;
+        let range = edit.offset..edit.offset + edit.new.len();
+        self.bytes.splice(range, edit.old.iter().copied());
+        let restored = edit.offset..edit.offset + edit.old.len();
+        self.redo_stack.push(edit);
+        cx.emit(HexBufferEvent::Edited);
+        cx.notify();
+        Some(restored)
+    }
+
+    /// Redoes the last undone edit, returning the range of the new bytes.
+    pub fn redo(&mut self, cx: &mut Context<Self>) -> Option<Range<usize>> {
+        let edit = self.redo_stack.pop()?;
+        let range = edit.offset..edit.offset + edit.old.len();
+        self.bytes.splice(range, edit.new.iter().copied());
+        let redone = edit.offset..edit.offset + edit.new.len();
+        self.undo_stack.push(edit);
+        cx.emit(HexBufferEvent::Edited);
+        cx.notify();
+        Some(redone)
+    }
+
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let bytes = self.bytes.clone();
+        let saved_edit_id = self.undo_stack.last().map(|edit| edit.id);
+        cx.spawn(async move |this, cx| {
+            fs.write(&abs_path, &bytes)
+                .await
+                .with_context(|| format!("saving {abs_path:?}"))?;
+            let mtime = fs.metadata(&abs_path).await?.map(|metadata| metadata.mtime);
+            this.update(cx, |this, cx| {
+                this.saved_edit_id = saved_edit_id;
+                this.saved_mtime = mtime;
+                cx.emit(HexBufferEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Discards all edits and loads the file from disk again.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let (bytes, mtime) = load(fs.as_ref(), &abs_path)
+                .await
+                .with_context(|| format!("reloading {abs_path:?}"))?;
+            this.update(cx, |this, cx| {
+                this.bytes = bytes;
+                this.saved_mtime = mtime;
+                this.undo_stack.clear();
+                this.redo_stack.clear();
+                this.saved_edit_id = None;
+                cx.emit(HexBufferEvent::Reloaded);
+                cx.notify();
+            })
+        })
+    }
+}
+
+/// Reads the bytes of the file along with its modification time.
+async fn load(fs: &dyn Fs, abs_path: &Path) -> Result<(Vec<u8>, Option<MTime>)> {
+    let mtime = fs.metadata(abs_path).await?.map(|metadata| metadata.mtime);
+    let bytes = fs.load_bytes(abs_path).await?;
+    Ok((bytes, mtime))
+}
+
+impl EventEmitter<HexBufferEvent> for HexBuffer {}
+
+impl project::ProjectItem for HexBuffer {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let extension = path.path.extension()?;
+        let project_ref = project.read(cx);
+        if !BINARY_EXTENSIONS.contains(&extension)
+            || !project_ref.is_local()
+            || is_too_large(project_ref, path, cx)
+        {
+            return None;
+        }
+        Some(Self::open(project, path, cx))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        HexBuffer::is_dirty(self)
+    }
+}
@@ -0,0 +1,1278 @@
+mod byte_search;
+mod go_to_offset;
+mod hex_buffer;
+mod inspector;
+
+use std::{ops::Range, sync::Arc};
+
+use editor::{EditorSettings, items::entry_git_aware_label_color};
+use file_icons::FileIcons;
+use gpui::{
+    Action, AnyElement, App, ClipboardItem, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    KeyDownEvent, MouseButton, MouseDownEvent, ScrollStrategy, Task, UniformListScrollHandle,
+    Window, actions, uniform_list,
+};
+use project::{Project, ProjectPath, search::SearchQuery};
+use settings::Settings;
+use theme::{Theme, ThemeSettings};
+use ui::{WithScrollbar, prelude::*};
+use util::paths::PathExt;
+use workspace::{
+    ItemSettings, Pane, ToolbarItemLocation, Workspace,
+    invalid_item_view::InvalidItemView,
+    item::{BreadcrumbText, Item, ItemEvent, ProjectItem, SaveOptions, TabContentParams},
+    notifications::NotifyTaskExt as _,
+    searchable::{Direction, SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
+};
+use zed_actions::hex_editor::OpenInHexEditor;
+
+use crate::{
+    go_to_offset::GoToOffset,
+    hex_buffer::{is_binary, is_too_large},
+    inspector::interpret,
+};
+
+pub use crate::hex_buffer::{HexBuffer, HexBufferEvent};
+
+const BYTES_PER_ROW: usize = 16;
+const ROWS_PER_PAGE: usize = 32;
+
+actions!(
+    hex_editor,
+    [
+        /// Toggles between inserting typed bytes and overwriting the bytes under the cursor.
+        ToggleInsertMode,
+        /// Moves the cursor between the hex and the text column.
+        SwitchColumn,
+        /// Opens a modal to move the cursor to a byte offset.
+        ToggleGoToOffset
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<HexEditor>(cx);
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
+        workspace.register_action(open_in_hex_editor);
+
+        let Some(window) = window else {
+            return;
+        };
+        cx.subscribe_in(&cx.entity(), window, |workspace, _, event, window, cx| {
+            if let workspace::Event::ItemAdded { item } = event
+                && let Some(invalid_item) = item.downcast::<InvalidItemView>()
+            {
+                let project = workspace.project().read(cx);
+                if let Some(project_path) =
+                    project.find_project_path(&invalid_item.read(cx).abs_path, cx)
+                {
+                    open_hex_editor(
+                        workspace,
+                        project_path,
+                        Some(invalid_item),
+                        true,
+                        window,
+                        cx,
+                    );
+                }
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+fn open_in_hex_editor(
+    workspace: &mut Workspace,
+    _: &OpenInHexEditor,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let Some(item) = workspace.active_item(cx) else {
+        return;
+    };
+    // Files that failed to open as text are only known by their absolute path.
+    let invalid_item = item.downcast::<InvalidItemView>();
+    let project_path = match &invalid_item {
+        Some(invalid_item) => project
+            .read(cx)
+            .find_project_path(&invalid_item.read(cx).abs_path, cx),
+        None => item.project_path(cx),
+    };
+    let Some(project_path) = project_path else {
+        return;
+    };
+
+    if let Some(hex_editor) = workspace
+        .items_of_type::<HexEditor>(cx)
+        .find(|hex_editor| hex_editor.read(cx).buffer.read(cx).project_path() == &project_path)
+    {
+        workspace.activate_item(&hex_editor, true, true, window, cx);
+        return;
+    }
+
+    open_hex_editor(workspace, project_path, invalid_item, false, window, cx);
+}
+
+/// Opens the file in a hex editor, taking the place of the item that failed to open it as text.
+/// With `only_binary`, the file is only opened if its contents are binary.
+fn open_hex_editor(
+    workspace: &mut Workspace,
+    project_path: ProjectPath,
+    invalid_item: Option<Entity<InvalidItemView>>,
+    only_binary: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let project = workspace.project().clone();
+    if !project.read(cx).is_local()
+        || only_binary && is_too_large(project.read(cx), &project_path, cx)
+    {
+        return;
+    }
+
+    let open_buffer = HexBuffer::open(&project, &project_path, cx);
+    let task = cx.spawn_in(window, async move |workspace, cx| {
+        let buffer = open_buffer.await?;
+        if only_binary && !buffer.read_with(cx, |buffer, _| is_binary(buffer.bytes())) {
+            return Ok(());
+        }
+        workspace.update_in(cx, |workspace, window, cx| {
+            let hex_editor = Box::new(cx.new(|cx| HexEditor::new(buffer, project, cx)));
+            let pane = invalid_item
+                .as_ref()
+                .and_then(|invalid_item| workspace.pane_for(invalid_item))
+                .unwrap_or_else(|| workspace.active_pane().clone());
+            pane.update(cx, |pane, cx| {
+                let index = invalid_item
+                    .as_ref()
+                    .and_then(|invalid_item| pane.index_for_item(invalid_item));
+                pane.add_item(hex_editor, true, true, index, window, cx);
+                if let Some(invalid_item) = invalid_item {
+                    pane.remove_item(invalid_item.entity_id(), false, false, window, cx);
+                }
+            });
+        })
+    });
+    // Only report errors when the user asked for the hex editor.
+    if only_binary {
+        task.detach_and_log_err(cx);
+    } else {
+        task.detach_and_notify_err(window, cx);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Text,
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    Saved,
+}
+
+/// Shows the bytes of a [`HexBuffer`] as rows of offsets, hex digits and
+/// printable characters, and allows editing them in either column.
+pub struct HexEditor {
+    buffer: Entity<HexBuffer>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// The offset of the byte under the cursor, which is the length of the
+    /// buffer when the cursor is past the last byte to append new ones.
+    cursor: usize,
+    /// The other end of the selection, which always includes the byte under the cursor.
+    selection_tail: usize,
+    column: Column,
+    /// Whether the high nibble of the byte under the cursor was just typed.
+    low_nibble: bool,
+    insert_mode: bool,
+    search_matches: Vec<Range<usize>>,
+    active_match_index: Option<usize>,
+}
+
+impl HexEditor {
+    pub fn new(
+        buffer: Entity<HexBuffer>,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        cx.subscribe(&buffer, |this, buffer, event, cx| {
+            let len = buffer.read(cx).len();
+            this.cursor = this.cursor.min(len);
+            this.selection_tail = this.selection_tail.min(len);
+            match event {
+                HexBufferEvent::Edited => cx.emit(HexEditorEvent::Edited),
+                HexBufferEvent::Saved | HexBufferEvent::Reloaded => cx.emit(HexEditorEvent::Saved),
+            }
+            cx.notify();
+        })
+        .detach();
+
+        Self {
+            buffer,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            selection_tail: 0,
+            column: Column::Hex,
+            low_nibble: false,
+            insert_mode: false,
+            search_matches: Vec::new(),
+            active_match_index: None,
+        }
+    }
+
+    pub fn buffer(&self) -> &Entity<HexBuffer> {
+        &self.buffer
+    }
+
+    /// Moves the cursor to the given offset and reveals it.
+    pub fn go_to_offset(&mut self, offset: usize, cx: &mut Context<Self>) {
+        self.move_cursor(offset, false, cx);
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW, ScrollStrategy::Center);
+    }
+
+    /// Returns the selected bytes' range, which is empty when the cursor is past the last byte.
+    fn selection(&self, cx: &App) -> Range<usize> {
+        let len = self.buffer.read(cx).len();
+        let start = self.cursor.min(self.selection_tail).min(len);
+        let end = (self.cursor.max(self.selection_tail) + 1).min(len);
+        start..end.max(start)
+    }
+
+    fn move_cursor(&mut self, offset: usize, select: bool, cx: &mut Context<Self>) {
+        self.cursor = offset.min(self.buffer.read(cx).len());
+        if !select {
+            self.selection_tail = self.cursor;
+        }
+        self.low_nibble = false;
+        self.scroll_handle
+            .scroll_to_item(self.cursor / BYTES_PER_ROW, ScrollStrategy::Nearest);
+        cx.notify();
+    }
+
+    fn move_by(&mut self, delta: isize, select: bool, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        // Moving down past the last row goes to its end rather than staying put.
+        let offset = if delta > 0 {
+            offset.min(self.buffer.read(cx).len())
+        } else if self.cursor < delta.unsigned_abs() {
+            self.cursor
+        } else {
+            offset
+        };
+        self.move_cursor(offset, select, cx);
+    }
+
+    fn move_left(&mut self, _: &editor::actions::MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, false, cx);
+    }
+
+    fn move_right(
+        &mut self,
+        _: &editor::actions::MoveRight,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_by(1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &editor::actions::MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as isize), false, cx);
+    }
+
+    fn move_down(&mut self, _: &editor::actions::MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as isize, false, cx);
+    }
+
+    fn move_page_up(
+        &mut self,
+        _: &editor::actions::MovePageUp,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_by(-((BYTES_PER_ROW * ROWS_PER_PAGE) as isize), false, cx);
+    }
+
+    fn move_page_down(
+        &mut self,
+        _: &editor::actions::MovePageDown,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_by((BYTES_PER_ROW * ROWS_PER_PAGE) as isize, false, cx);
+    }
+
+    fn move_to_beginning_of_line(
+        &mut self,
+        _: &editor::actions::MoveToBeginningOfLine,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor(self.cursor - self.cursor % BYTES_PER_ROW, false, cx);
+    }
+
+    fn move_to_end_of_line(
+        &mut self,
+        _: &editor::actions::MoveToEndOfLine,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let row_start = self.cursor - self.cursor % BYTES_PER_ROW;
+        self.move_cursor(row_start + BYTES_PER_ROW - 1, false, cx);
+    }
+
+    fn move_to_beginning(
+        &mut self,
+        _: &editor::actions::MoveToBeginning,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor(0, false, cx);
+    }
+
+    fn move_to_end(
+        &mut self,
+        _: &editor::actions::MoveToEnd,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor(usize::MAX, false, cx);
+    }
+
+    fn select_left(
+        &mut self,
+        _: &editor::actions::SelectLeft,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_by(-1, true, cx);
+    }
+
+    fn select_right(
+        &mut self,
+        _: &editor::actions::SelectRight,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_by(1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &editor::actions::SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as isize), true, cx);
+    }
+
+    fn select_down(
+        &mut self,
+        _: &editor::actions::SelectDown,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_by(BYTES_PER_ROW as isize, true, cx);
+    }
+
+    fn select_all(
+        &mut self,
+        _: &editor::actions::SelectAll,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let len = self.buffer.read(cx).len();
+        self.selection_tail = 0;
+        self.move_cursor(len.saturating_sub(1), true, cx);
+    }
+
+    fn cancel(&mut self, _: &editor::actions::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        if self.selection_tail != self.cursor {
+            self.move_cursor(self.cursor, false, cx);
+        } else {
+            cx.propagate();
+        }
+    }
+
+    fn toggle_insert_mode(&mut self, _: &ToggleInsertMode, _: &mut Window, cx: &mut Context<Self>) {
+        self.insert_mode = !self.insert_mode;
+        cx.notify();
+    }
+
+    fn switch_column(&mut self, _: &SwitchColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Text,
+            Column::Text => Column::Hex,
+        };
+        self.low_nibble = false;
+        cx.notify();
+    }
+
+    fn toggle_go_to_offset(
+        &mut self,
+        _: &ToggleGoToOffset,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        let current_offset = self.cursor;
+        let len = self.buffer.read(cx).len();
+        let view = cx.weak_entity();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                GoToOffset::new(view, current_offset, len, window, cx)
+            });
+        });
+    }
+
+    /// Replaces the given range, and places the cursor after the new bytes.
+    fn edit(&mut self, range: Range<usize>, new: &[u8], cx: &mut Context<Self>) {
+        let cursor = range.start + new.len();
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(range, new, cx));
+        self.move_cursor(cursor, false, cx);
+    }
+
+    /// Returns the range that is replaced when writing `len` bytes at the cursor.
+    fn range_to_replace(&self, len: usize, cx: &App) -> Range<usize> {
+        let selection = self.selection(cx);
+        if selection.len() > 1 {
+            selection
+        } else if self.insert_mode {
+            self.cursor..self.cursor
+        } else {
+            self.cursor..(self.cursor + len).min(self.buffer.read(cx).len())
+        }
+    }
+
+    fn selected_text(&self, cx: &App) -> Option<String> {
+        let selection = self.selection(cx);
+        if selection.is_empty() {
+            return None;
+        }
+        let bytes = &self.buffer.read(cx).bytes()[selection];
+        Some(match self.column {
+            Column::Hex => format_hex(bytes),
+            Column::Text => String::from_utf8_lossy(bytes).into_owned(),
+        })
+    }
+
+    fn copy(&mut self, _: &editor::actions::Copy, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = self.selected_text(cx) {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+        }
+    }
+
+    fn cut(&mut self, _: &editor::actions::Cut, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(text) = self.selected_text(cx) {
+            cx.write_to_clipboard(ClipboardItem::new_string(text));
+            let selection = self.selection(cx);
+            self.edit(selection, &[], cx);
+        }
+    }
+
+    fn paste(&mut self, _: &editor::actions::Paste, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        // Hex digits pasted into the hex column are taken as the bytes they
+        // describe, anything else is pasted as text.
+        let hex_bytes = (self.column == Column::Hex)
+            .then(|| byte_search::parse_hex_pattern(&text))
+            .flatten()
+            .and_then(|pattern| pattern.into_iter().collect::<Option<Vec<_>>>());
+        let bytes = hex_bytes.unwrap_or_else(|| text.into_bytes());
+        let range = self.range_to_replace(bytes.len(), cx);
+        self.edit(range, &bytes, cx);
+    }
+
+    fn undo(&mut self, _: &editor::actions::Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.buffer.update(cx, |buffer, cx| buffer.undo(cx)) {
+            self.select_range(range, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &editor::actions::Redo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(range) = self.buffer.update(cx, |buffer, cx| buffer.redo(cx)) {
+            self.select_range(range, cx);
+        }
+    }
+
+    fn select_range(&mut self, range: Range<usize>, cx: &mut Context<Self>) {
+        self.move_cursor(range.start, false, cx);
+        self.selection_tail = range.end.saturating_sub(1).max(range.start);
+    }
+
+    fn backspace(
+        &mut self,
+        _: &editor::actions::Backspace,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let selection = self.selection(cx);
+        if selection.len() > 1 {
+            self.edit(selection, &[], cx);
+        } else if self.cursor > 0 {
+            self.edit(self.cursor - 1..self.cursor, &[], cx);
+        }
+    }
+
+    fn delete(&mut self, _: &editor::actions::Delete, _: &mut Window, cx: &mut Context<Self>) {
+        let selection = self.selection(cx);
+        if !selection.is_empty() {
+            self.edit(selection, &[], cx);
+        }
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let keystroke = &event.keystroke;
+        if keystroke.modifiers.control || keystroke.modifiers.platform {
+            return;
+        }
+        let Some(text) = keystroke.key_char.as_deref() else {
+            return;
+        };
+        let handled = match self.column {
+            Column::Hex => self.type_hex_digit(text, cx),
+            Column::Text => self.type_text(text, cx),
+        };
+        if handled {
+            cx.stop_propagation();
+        }
+    }
+
+    /// Writes the high nibble of the byte at the cursor, or its low nibble
+    /// right after the high one was typed.
+    fn type_hex_digit(&mut self, text: &str, cx: &mut Context<Self>) -> bool {
+        let mut chars = text.chars();
+        let (Some(digit), None) = (
+            chars.next().and_then(|char| char.to_digit(16)),
+            chars.next(),
+        ) else {
+            return false;
+        };
+        let digit = digit as u8;
+        let offset = self.cursor;
+        let current_byte = self.buffer.read(cx).bytes().get(offset).copied();
+
+        if self.low_nibble
+            && let Some(byte) = current_byte
+        {
+            self.edit(offset..offset + 1, &[(byte & 0xF0) | digit], cx);
+        } else {
+            let (range, low_nibble) = match current_byte {
+                Some(byte) if !self.insert_mode => (offset..offset + 1, byte & 0x0F),
+                _ => (offset..offset, 0),
+            };
+            self.edit(range, &[(digit << 4) | low_nibble], cx);
+            self.move_cursor(offset, false, cx);
+            self.low_nibble = true;
+        }
+        true
+    }
+
+    fn type_text(&mut self, text: &str, cx: &mut Context<Self>) -> bool {
+        if text.is_empty() || text.chars().any(char::is_control) {
+            return false;
+        }
+        let range = self.range_to_replace(text.len(), cx);
+        self.edit(range, text.as_bytes(), cx);
+        true
+    }
+
+    fn click_byte(
+        &mut self,
+        offset: usize,
+        column: Column,
+        event: &MouseDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        self.move_cursor(offset, event.modifiers.shift, cx);
+        self.focus_handle.focus(window, cx);
+    }
+
+    fn render_rows(
+        &self,
+        rows: Range<usize>,
+        line_height: Pixels,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let bytes = self.buffer.read(cx).bytes();
+        let selection = self.selection(cx);
+        let colors = cx.theme().colors();
+        let player = cx.theme().players().local();
+        let active_match = self
+            .active_match_index
+            .and_then(|ix| self.search_matches.get(ix))
+            .cloned();
+
+        rows.map(|row| {
+            let row_start = row * BYTES_PER_ROW;
+            let first_match = self
+                .search_matches
+                .partition_point(|range| range.end <= row_start);
+            let row_matches = &self.search_matches[first_match..];
+
+            let cell = |offset: usize, column: Column| {
+                let byte = bytes.get(offset).copied();
+                let (text, is_muted) = match (column, byte) {
+                    (Column::Hex, Some(byte)) => (format!("{byte:02X}"), byte == 0),
+                    (Column::Hex, None) => ("  ".to_string(), false),
+                    (Column::Text, Some(byte)) if byte.is_ascii_graphic() || byte == b' ' => {
+                        ((byte as char).to_string(), false)
+                    }
+                    (Column::Text, Some(_)) => (".".to_string(), true),
+                    (Column::Text, None) => (" ".to_string(), false),
+                };
+                let search_match = row_matches
+                    .iter()
+                    .take_while(|range| range.start <= offset)
+                    .find(|range| range.contains(&offset));
+                let background = if offset == self.cursor && column == self.column {
+                    Some(player.cursor.opacity(0.5))
+                } else if selection.contains(&offset) {
+                    Some(player.selection)
+                } else if let Some(search_match) = search_match {
+                    Some(if active_match.as_ref() == Some(search_match) {
+                        colors.search_active_match_background
+                    } else {
+                        colors.search_match_background
+                    })
+                } else {
+                    None
+                };
+                let id = match column {
+                    Column::Hex => ("hex", offset),
+                    Column::Text => ("text", offset),
+                };
+
+                div()
+                    .id(id)
+                    .when(column == Column::Hex, |this| this.px_0p5())
+                    .when_some(background, |this, background| this.bg(background))
+                    .when(is_muted, |this| this.text_color(colors.text_muted))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(move |this, event, window, cx| {
+                            this.click_byte(offset, column, event, window, cx)
+                        }),
+                    )
+                    .child(text)
+            };
+
+            h_flex()
+                .id(("row", row))
+                .h(line_height)
+                .px_2()
+                .gap_4()
+                .whitespace_nowrap()
+                .child(
+                    div()
+                        .flex_none()
+                        .text_color(colors.editor_line_number)
+                        .child(format!("{row_start:08X}")),
+                )
+                .child(h_flex().flex_none().children((0..BYTES_PER_ROW).map(|ix| {
+                    cell(row_start + ix, Column::Hex)
+                        .when(ix == BYTES_PER_ROW / 2 - 1, |this| this.mr_2())
+                })))
+                .child(
+                    h_flex()
+                        .flex_none()
+                        .children((0..BYTES_PER_ROW).map(|ix| cell(row_start + ix, Column::Text))),
+                )
+                .into_any_element()
+        })
+        .collect()
+    }
+
+    fn render_inspector(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let buffer = self.buffer.read(cx);
+        let selection = self.selection(cx);
+        let size = util::size::format_file_size(buffer.len() as u64, false);
+        let mut status = format!("Offset {:#010X} ({})", self.cursor, self.cursor);
+        if selection.len() > 1 {
+            status.push_str(&format!(", {} bytes selected", selection.len()));
+        }
+        status.push_str(&format!(" of {size}"));
+
+        let interpretations = interpret(&buffer.bytes()[selection.start..]);
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "—".to_string());
+        let rows = interpretations.chunks(2).map(|pair| {
+            let line = pair
+                .iter()
+                .map(|interpretation| {
+                    format!(
+                        "{:<4}{:>26}{:>26}",
+                        interpretation.label,
+                        value(&interpretation.little_endian),
+                        value(&interpretation.big_endian),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("    ");
+            div().whitespace_nowrap().child(line)
+        });
+        let header = format!("{:<4}{:>26}{:>26}", "", "Little endian", "Big endian");
+
+        v_flex()
+            .flex_none()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Button::new(
+                            "toggle-insert-mode",
+                            if self.insert_mode {
+                                "Insert"
+                            } else {
+                                "Overwrite"
+                            },
+                        )
+                        .label_size(LabelSize::Small)
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(ToggleInsertMode.boxed_clone(), cx)
+                        }),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .text_color(cx.theme().colors().text_muted)
+                    .child(div().whitespace_nowrap().child(header))
+                    .children(rows),
+            )
+    }
+}
+
+/// Formats bytes the way the hex column shows them, e.g. `DE AD BE EF`.
+fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+impl EventEmitter<SearchEvent> for HexEditor {}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::Saved => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.buffer.entity_id(), self.buffer.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.buffer.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        let project_path = self.buffer.read(cx).project_path();
+
+        let label_color = if ItemSettings::get_global(cx).git_status {
+            let git_status = self
+                .project
+                .read(cx)
+                .project_path_git_status(project_path, cx)
+                .map(|status| status.summary())
+                .unwrap_or_default();
+
+            self.project
+                .read(cx)
+                .entry_for_path(project_path, cx)
+                .map(|entry| {
+                    entry_git_aware_label_color(git_status, entry.is_ignored, params.selected)
+                })
+                .unwrap_or_else(|| params.text_color())
+        } else {
+            params.text_color()
+        };
+
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .single_line()
+            .color(label_color)
+            .when(params.preview, |this| this.italic())
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.buffer
+            .read(cx)
+            .project_path()
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string()
+            .into()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.buffer.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        let show_breadcrumb = EditorSettings::get_global(cx).toolbar.breadcrumbs;
+        if show_breadcrumb {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, _theme: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        let project = self.project.read(cx);
+        let project_path = self.buffer.read(cx).project_path();
+        let mut path = project_path.path.clone();
+        if project.visible_worktrees(cx).count() > 1
+            && let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+        {
+            path = worktree.read(cx).root_name().join(&path);
+        }
+
+        Some(vec![BreadcrumbText {
+            text: path.display(project.path_style(cx)).to_string(),
+            highlights: None,
+            font: Some(ThemeSettings::get_global(cx).buffer_font.clone()),
+        }])
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.buffer.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.buffer.update(cx, |buffer, cx| buffer.save(cx))
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.buffer.update(cx, |buffer, cx| buffer.reload(cx))
+    }
+
+    fn as_searchable(
+        &self,
+        handle: &Entity<Self>,
+        _: &App,
+    ) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Singleton
+    }
+}
+
+impl SearchableItem for HexEditor {
+    type Match = Range<usize>;
+
+    fn supported_options(&self) -> SearchOptions {
+        SearchOptions {
+            case: true,
+            word: false,
+            regex: false,
+            replacement: false,
+            selection: false,
+            find_in_results: false,
+        }
+    }
+
+    fn clear_matches(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        self.search_matches.clear();
+        self.active_match_index = None;
+        cx.notify();
+    }
+
+    fn update_matches(
+        &mut self,
+        matches: &[Self::Match],
+        active_match_index: Option<usize>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.search_matches = matches.to_vec();
+        self.active_match_index = active_match_index;
+        cx.notify();
+    }
+
+    fn query_suggestion(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> String {
+        let selection = self.selection(cx);
+        if selection.len() > 1 && self.column == Column::Hex {
+            format_hex(&self.buffer.read(cx).bytes()[selection])
+        } else {
+            String::new()
+        }
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(range) = matches.get(index) else {
+            return;
+        };
+        self.active_match_index = Some(index);
+        self.select_range(range.clone(), cx);
+        self.scroll_handle
+            .scroll_to_item(range.start / BYTES_PER_ROW, ScrollStrategy::Center);
+        cx.emit(SearchEvent::ActiveMatchChanged);
+    }
+
+    fn select_matches(&mut self, _: &[Self::Match], _: &mut Window, _: &mut Context<Self>) {
+        // There is a single selection, so all matches can't be selected at once.
+    }
+
+    fn replace(
+        &mut self,
+        _: &Self::Match,
+        _: &SearchQuery,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+        // Replacement is not supported, so this is a no-op.
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<Self::Match>> {
+        let bytes = self.buffer.read(cx).bytes().to_vec();
+        // In the hex column, queries such as `DE AD ?? EF` search for bytes.
+        let hex_pattern = (self.column == Column::Hex)
+            .then(|| byte_search::parse_hex_pattern(query.as_str()))
+            .flatten();
+        cx.background_spawn(async move {
+            match hex_pattern {
+                Some(pattern) => byte_search::find_hex_pattern(&bytes, &pattern).await,
+                None => byte_search::find_text(&bytes, &query),
+            }
+        })
+    }
+
+    fn active_match_index(
+        &mut self,
+        direction: Direction,
+        matches: &[Self::Match],
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Option<usize> {
+        if matches.is_empty() {
+            return None;
+        }
+        let ix = match direction {
+            Direction::Next => {
+                let ix = matches.partition_point(|range| range.start <= self.cursor);
+                if ix == matches.len() { 0 } else { ix }
+            }
+            Direction::Prev => matches
+                .partition_point(|range| range.start < self.cursor)
+                .checked_sub(1)
+                .unwrap_or(matches.len() - 1),
+        };
+        Some(ix)
+    }
+}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let font_size = settings.buffer_font_size(cx);
+        let line_height = font_size * settings.line_height();
+        let buffer_font = settings.buffer_font.clone();
+        // The row after the last byte holds the cursor when appending.
+        let row_count = self.buffer.read(cx).len() / BYTES_PER_ROW + 1;
+
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning_of_line))
+            .on_action(cx.listener(Self::move_to_end_of_line))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::cut))
+            .on_action(cx.listener(Self::paste))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
+            .on_action(cx.listener(Self::backspace))
+            .on_action(cx.listener(Self::delete))
+            .on_action(cx.listener(Self::toggle_insert_mode))
+            .on_action(cx.listener(Self::switch_column))
+            .on_action(cx.listener(Self::toggle_go_to_offset))
+            .on_key_down(cx.listener(Self::key_down))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .font(buffer_font)
+            .text_size(font_size)
+            .child(
+                div()
+                    .relative()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        uniform_list(
+                            "hex-editor-rows",
+                            row_count,
+                            cx.processor(move |this, rows: Range<usize>, _, cx| {
+                                this.render_rows(rows, line_height, cx)
+                            }),
+                        )
+                        .size_full()
+                        .track_scroll(&self.scroll_handle),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+            .child(self.render_inspector(cx))
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexBuffer;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hex_buffer::BINARY_DETECTION_LEN;
+    use fs::Fs as _;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use util::{path, rel_path::rel_path};
+    use workspace::AppState;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            init(cx);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_edit_and_save(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/root/data.bin"), vec![0x00, 0xFF, 0x10])
+            .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("data.bin")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        let hex_editor = item.downcast::<HexEditor>().unwrap();
+
+        cx.simulate_input("ab");
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.buffer.read(cx).bytes(), [0xAB, 0xFF, 0x10]);
+            assert_eq!(hex_editor.cursor, 1);
+            assert!(hex_editor.is_dirty(cx));
+        });
+
+        cx.dispatch_action(ToggleInsertMode);
+        cx.simulate_input("1");
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.buffer.read(cx).bytes(), [0xAB, 0x10, 0xFF, 0x10]);
+        });
+        cx.dispatch_action(editor::actions::Undo);
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.buffer.read(cx).bytes(), [0xAB, 0xFF, 0x10]);
+        });
+
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.save_active_item(workspace::SaveIntent::Save, window, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            fs.load_bytes(path!("/root/data.bin").as_ref())
+                .await
+                .unwrap(),
+            [0xAB, 0xFF, 0x10]
+        );
+        hex_editor.update(cx, |hex_editor, cx| assert!(!hex_editor.is_dirty(cx)));
+    }
+
+    #[gpui::test]
+    async fn test_conflict_with_changes_on_disk(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_file(path!("/root/data.bin"), vec![0x00, 0xFF, 0x10])
+            .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let item = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("data.bin")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        let hex_editor = item.downcast::<HexEditor>().unwrap();
+
+        fs.insert_file(path!("/root/data.bin"), vec![0x00, 0x01])
+            .await;
+        cx.run_until_parked();
+        hex_editor.update(cx, |hex_editor, cx| assert!(!hex_editor.has_conflict(cx)));
+
+        cx.simulate_input("ab");
+        hex_editor.update(cx, |hex_editor, cx| assert!(hex_editor.has_conflict(cx)));
+
+        hex_editor
+            .update_in(cx, |hex_editor, window, cx| {
+                hex_editor.reload(project.clone(), window, cx)
+            })
+            .await
+            .unwrap();
+        hex_editor.update(cx, |hex_editor, cx| {
+            assert_eq!(hex_editor.buffer.read(cx).bytes(), [0x00, 0x01]);
+            assert!(!hex_editor.has_conflict(cx));
+        });
+    }
+
+    #[gpui::test]
+    async fn test_open_binary_file_without_known_extension(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        let mut program = b"\x7fELF".to_vec();
+        program.resize(64, 0);
+        fs.insert_file(path!("/root/program"), program.clone())
+            .await;
+        let project = Project::test(fs.clone(), [path!("/root").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("program")), None, true, window, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        workspace.update(cx, |workspace, cx| {
+            let hex_editor = workspace
+                .active_item_as::<HexEditor>(cx)
+                .expect("binary file should be opened in the hex editor");
+            assert_eq!(hex_editor.read(cx).buffer.read(cx).bytes(), program);
+            assert_eq!(workspace.active_pane().read(cx).items_len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(!is_binary(b""));
+        assert!(!is_binary("plain text, with ünïcödé".as_bytes()));
+        assert!(is_binary(b"text\0with a NUL byte"));
+        assert!(is_binary(b"invalid \xFF\xFE UTF-8"));
+
+        // A character cut off by the end of the inspected prefix doesn't make a file binary.
+        let mut text = "a".repeat(BINARY_DETECTION_LEN - 1);
+        text.push('ü');
+        assert!(!is_binary(text.as_bytes()));
+    }
+}
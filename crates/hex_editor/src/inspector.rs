@@ -0,0 +1,86 @@
+/// The value of the bytes at the start of the selection, read as a number type.
+#[derive(Debug, PartialEq)]
+pub struct Interpretation {
+    pub label: &'static str,
+    /// `None` when there are fewer bytes left than the type is large.
+    pub little_endian: Option<String>,
+    pub big_endian: Option<String>,
+}
+
+macro_rules! interpretation {
+    ($label:literal, $ty:ty, $bytes:expr, $format:expr) => {{
+        const LEN: usize = std::mem::size_of::<$ty>();
+        let bytes = $bytes
+            .get(..LEN)
+            .and_then(|bytes| <[u8; LEN]>::try_from(bytes).ok());
+        Interpretation {
+            label: $label,
+            little_endian: bytes.map(|bytes| $format(<$ty>::from_le_bytes(bytes))),
+            big_endian: bytes.map(|bytes| $format(<$ty>::from_be_bytes(bytes))),
+        }
+    }};
+}
+
+/// Reads the given bytes as each of the supported number types.
+pub fn interpret(bytes: &[u8]) -> Vec<Interpretation> {
+    vec![
+        interpretation!("u8", u8, bytes, |value: u8| value.to_string()),
+        interpretation!("i8", i8, bytes, |value: i8| value.to_string()),
+        interpretation!("u16", u16, bytes, |value: u16| value.to_string()),
+        interpretation!("i16", i16, bytes, |value: i16| value.to_string()),
+        interpretation!("u32", u32, bytes, |value: u32| value.to_string()),
+        interpretation!("i32", i32, bytes, |value: i32| value.to_string()),
+        interpretation!("u64", u64, bytes, |value: u64| value.to_string()),
+        interpretation!("i64", i64, bytes, |value: i64| value.to_string()),
+        interpretation!("f32", f32, bytes, format_float),
+        interpretation!("f64", f64, bytes, format_float),
+    ]
+}
+
+/// Formats very large and very small floats in scientific notation, as their
+/// plain representation can be hundreds of digits long.
+fn format_float<F: Copy + Into<f64> + std::fmt::Display + std::fmt::LowerExp>(value: F) -> String {
+    let magnitude = value.into().abs();
+    if magnitude != 0.0 && !(1e-6..1e16).contains(&magnitude) {
+        format!("{value:e}")
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpret() {
+        let interpretations = interpret(&[0xFE, 0xFF, 0x00, 0x00]);
+        let find = |label| {
+            interpretations
+                .iter()
+                .find(|interpretation| interpretation.label == label)
+                .unwrap()
+        };
+
+        assert_eq!(find("u8").little_endian.as_deref(), Some("254"));
+        assert_eq!(find("i8").big_endian.as_deref(), Some("-2"));
+        assert_eq!(find("u16").little_endian.as_deref(), Some("65534"));
+        assert_eq!(find("u16").big_endian.as_deref(), Some("65279"));
+        assert_eq!(find("i16").little_endian.as_deref(), Some("-2"));
+        assert_eq!(find("u32").little_endian.as_deref(), Some("65534"));
+        assert_eq!(find("u32").big_endian.as_deref(), Some("4278124544"));
+        assert_eq!(find("u64").little_endian, None);
+        assert_eq!(find("f64").big_endian, None);
+
+        let interpretations = interpret(&1e300f64.to_le_bytes());
+        assert_eq!(
+            interpretations
+                .iter()
+                .find(|interpretation| interpretation.label == "f64")
+                .unwrap()
+                .little_endian
+                .as_deref(),
+            Some("1e300")
+        );
+    }
+}
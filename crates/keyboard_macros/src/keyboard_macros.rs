@@ -0,0 +1,548 @@
+mod macro_list;
+mod persistence;
+mod recording_indicator;
+mod replayer;
+mod save_macro;
+
+use std::{collections::BTreeMap, iter, ops::Range, sync::Arc};
+
+use anyhow::Result;
+use editor::{
+    Editor, EditorEvent,
+    replay::{MAX_REPLAYED_STEPS, Replayer},
+};
+use gpui::{Action, App, Context, Global, KeyContext, Window, actions};
+use language::Point;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use util::ResultExt as _;
+use workspace::Workspace;
+
+pub use crate::recording_indicator::RecordingIndicator;
+use crate::{
+    macro_list::MacroList,
+    persistence::SerializedMacroStep,
+    replayer::{ReplayStep, new_replayer},
+    save_macro::SaveMacro,
+};
+
+actions!(
+    keyboard_macros,
+    [
+        /// Starts recording a keyboard macro, or stops the recording in progress.
+        ToggleRecording,
+        /// Replays the last recorded macro at each cursor, and at the start of every line
+        /// spanned by a non-empty selection.
+        ReplayLastMacroOnSelections,
+        /// Saves the last recorded macro under a name, so that it can be bound to a key.
+        SaveLastMacro,
+        /// Toggles the list of saved macros.
+        ToggleMacroList,
+    ]
+);
+
+/// Replays the last recorded keyboard macro.
+#[derive(Clone, PartialEq, Debug, Deserialize, JsonSchema, Default, Action)]
+#[action(namespace = keyboard_macros)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayLastMacro {
+    /// The number of times to replay the macro. Defaults to once.
+    #[serde(default)]
+    pub times: Option<usize>,
+}
+
+/// Runs a keyboard macro that was saved under a name.
+#[derive(Clone, PartialEq, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = keyboard_macros)]
+#[serde(deny_unknown_fields)]
+pub struct RunMacro {
+    /// The name the macro was saved under.
+    pub name: String,
+    /// The number of times to run the macro. Defaults to once.
+    #[serde(default)]
+    pub times: Option<usize>,
+}
+
+/// An input captured while recording a keyboard macro.
+pub enum MacroStep {
+    /// An action dispatched by a keystroke.
+    Action(Box<dyn Action>),
+    /// Text typed into an editor.
+    Insertion {
+        text: Arc<str>,
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+impl Clone for MacroStep {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Action(action) => Self::Action(action.boxed_clone()),
+            Self::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Self::Insertion {
+                text: text.clone(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            },
+        }
+    }
+}
+
+/// Records keyboard macros in any editor, independently of vim mode.
+#[derive(Default)]
+pub struct KeyboardMacros {
+    recording: Option<Vec<MacroStep>>,
+    last_macro: Option<Arc<[MacroStep]>>,
+    saved_macros: BTreeMap<String, Vec<SerializedMacroStep>>,
+    replayer: Option<Replayer<ReplayStep>>,
+}
+
+impl Global for KeyboardMacros {}
+
+pub fn init(cx: &mut App) {
+    cx.set_global(KeyboardMacros {
+        saved_macros: persistence::load_saved_macros(),
+        ..Default::default()
+    });
+
+    cx.observe_keystrokes(|event, _, cx| {
+        if let Some(action) = event.action.as_ref()
+            && KeyboardMacros::is_recording(cx)
+            && dispatched_to_full_editor(&event.context_stack)
+        {
+            cx.global_mut::<KeyboardMacros>()
+                .observe_action(action.as_ref());
+        }
+    })
+    .detach();
+
+    cx.observe_new(|editor: &mut Editor, _, cx| {
+        // Text typed into pickers and other single-line editors can't be replayed
+        // once they're gone, so only text typed into full editors is recorded.
+        if !editor.mode().is_full() {
+            return;
+        }
+        cx.subscribe_self(|_, event: &EditorEvent, cx| {
+            if !KeyboardMacros::is_recording(cx) {
+                return;
+            }
+            match event {
+                EditorEvent::InputIgnored { text } => cx
+                    .global_mut::<KeyboardMacros>()
+                    .observe_insertion(text, None),
+                EditorEvent::InputHandled {
+                    text,
+                    utf16_range_to_replace,
+                } => cx
+                    .global_mut::<KeyboardMacros>()
+                    .observe_insertion(text, utf16_range_to_replace.clone()),
+                _ => {}
+            }
+        })
+        .detach();
+    })
+    .detach();
+
+    cx.observe_new(register).detach();
+}
+
+/// Returns whether a keystroke was dispatched to a full editor. Actions dispatched to pickers,
+/// menus or panels can't be replayed, as those are gone by the time the macro is replayed.
+fn dispatched_to_full_editor(context_stack: &[KeyContext]) -> bool {
+    context_stack
+        .iter()
+        .rev()
+        .find(|context| context.contains("Editor"))
+        .is_some_and(|context| context.get("mode").is_some_and(|mode| mode == "full"))
+}
+
+fn register(workspace: &mut Workspace, _: Option<&mut Window>, _: &mut Context<Workspace>) {
+    workspace.register_action(|_, _: &ToggleRecording, _, cx| {
+        cx.global_mut::<KeyboardMacros>().toggle_recording();
+    });
+    workspace.register_action(|workspace, action: &ReplayLastMacro, window, cx| {
+        // Like in Emacs, replaying while recording ends the recording first.
+        let macros = cx.global_mut::<KeyboardMacros>();
+        if macros.recording.is_some() {
+            macros.toggle_recording();
+        }
+        let Some(steps) = macros.last_macro.clone() else {
+            return;
+        };
+        if let Err(error) = KeyboardMacros::replay(&steps, action.times, window, cx) {
+            workspace.show_error(&error, cx);
+        }
+    });
+    workspace.register_action(replay_last_macro_on_selections);
+    workspace.register_action(|workspace, _: &SaveLastMacro, window, cx| {
+        if cx.global::<KeyboardMacros>().last_macro.is_none() {
+            return;
+        }
+        workspace.toggle_modal(window, cx, SaveMacro::new);
+    });
+    workspace.register_action(|workspace, _: &ToggleMacroList, window, cx| {
+        let names = cx
+            .global::<KeyboardMacros>()
+            .saved_macros
+            .keys()
+            .cloned()
+            .collect();
+        workspace.toggle_modal(window, cx, |window, cx| MacroList::new(names, window, cx));
+    });
+    workspace.register_action(|workspace, action: &RunMacro, window, cx| {
+        if let Err(error) = KeyboardMacros::run_saved_macro(&action.name, action.times, window, cx)
+        {
+            workspace.show_error(&error, cx);
+        }
+    });
+}
+
+impl KeyboardMacros {
+    pub fn is_recording(cx: &App) -> bool {
+        cx.try_global::<Self>()
+            .is_some_and(|this| this.recording.is_some())
+    }
+
+    fn observe_action(&mut self, action: &dyn Action) {
+        // Recording the macro actions themselves would make macros replay each other.
+        if self.replayer.is_some() || action.name().starts_with("keyboard_macros::") {
+            return;
+        }
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(MacroStep::Action(action.boxed_clone()));
+        }
+    }
+
+    fn observe_insertion(&mut self, text: &Arc<str>, utf16_range_to_replace: Option<Range<isize>>) {
+        if self.replayer.is_some() {
+            return;
+        }
+        if let Some(recording) = self.recording.as_mut() {
+            recording.push(MacroStep::Insertion {
+                text: text.clone(),
+                utf16_range_to_replace,
+            });
+        }
+    }
+
+    fn toggle_recording(&mut self) {
+        if let Some(mut recording) = self.recording.take() {
+            // The recording is often stopped from the command palette, which shouldn't be
+            // opened again when replaying.
+            while let Some(MacroStep::Action(action)) = recording.last()
+                && action.name().starts_with("command_palette::")
+            {
+                recording.pop();
+            }
+            if !recording.is_empty() {
+                self.last_macro = Some(recording.into());
+            }
+        } else if self.replayer.is_none() {
+            self.recording = Some(Vec::new());
+        }
+    }
+
+    /// Saves the last recorded macro under the given name, replacing any macro saved under it.
+    fn save_last_macro(name: String, cx: &mut App) {
+        let Some(steps) = cx.global::<Self>().last_macro.clone() else {
+            return;
+        };
+        let steps = steps
+            .iter()
+            .filter_map(|step| SerializedMacroStep::new(step, cx))
+            .collect();
+        let this = cx.global_mut::<Self>();
+        this.saved_macros.insert(name, steps);
+        let saved_macros = this.saved_macros.clone();
+        persistence::write_saved_macros(saved_macros, cx);
+    }
+
+    fn run_saved_macro(
+        name: &str,
+        times: Option<usize>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<()> {
+        let Some(steps) = cx.global::<Self>().saved_macros.get(name) else {
+            anyhow::bail!("No keyboard macro is saved as {name:?}");
+        };
+        let steps = steps
+            .iter()
+            .filter_map(|step| step.to_macro_step(cx).log_err())
+            .collect::<Vec<_>>();
+        Self::replay(&steps, times, window, cx)
+    }
+
+    fn replay(
+        steps: &[MacroStep],
+        times: Option<usize>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Result<()> {
+        let times = times.unwrap_or(1);
+        Self::ensure_replayable(steps.len().saturating_mul(times), cx)?;
+        let steps = iter::repeat_n(steps, times)
+            .flatten()
+            .cloned()
+            .map(ReplayStep::Step)
+            .collect();
+        Self::replay_steps(steps, window, cx);
+        Ok(())
+    }
+
+    /// Fails when replaying the given number of steps would exceed the limit, which would
+    /// otherwise stop the replay partway.
+    fn ensure_replayable(step_count: usize, cx: &App) -> Result<()> {
+        let remaining_steps = cx
+            .global::<Self>()
+            .replayer
+            .as_ref()
+            .map_or(MAX_REPLAYED_STEPS, |replayer| replayer.remaining_steps());
+        anyhow::ensure!(
+            step_count <= remaining_steps,
+            "Can't replay {step_count} keyboard macro steps, the limit is {MAX_REPLAYED_STEPS}"
+        );
+        Ok(())
+    }
+
+    fn replay_steps(steps: Vec<ReplayStep>, window: &mut Window, cx: &mut App) {
+        let replayer = cx
+            .global_mut::<Self>()
+            .replayer
+            .get_or_insert_with(new_replayer)
+            .clone();
+        replayer.replay(steps, window, cx);
+    }
+}
+
+fn replay_last_macro_on_selections(
+    workspace: &mut Workspace,
+    _: &ReplayLastMacroOnSelections,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(steps) = cx.global::<KeyboardMacros>().last_macro.clone() else {
+        return;
+    };
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+
+    let positions = editor.update(cx, |editor, cx| {
+        let display_snapshot = editor.display_snapshot(cx);
+        let mut points = Vec::new();
+        for selection in editor.selections.all::<Point>(&display_snapshot) {
+            if selection.is_empty() {
+                points.push(selection.head());
+                continue;
+            }
+            // A selection ending at the start of a line doesn't span that line.
+            let mut end_row = selection.end.row;
+            if selection.end.column == 0 && end_row > selection.start.row {
+                end_row -= 1;
+            }
+            points.extend((selection.start.row..=end_row).map(|row| Point::new(row, 0)));
+        }
+        points.dedup();
+
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        points
+            .into_iter()
+            .map(|point| snapshot.anchor_before(point))
+            .collect::<Vec<_>>()
+    });
+
+    let step_count = positions.len().saturating_mul(steps.len() + 1);
+    if let Err(error) = KeyboardMacros::ensure_replayable(step_count, cx) {
+        workspace.show_error(&error, cx);
+        return;
+    }
+
+    let editor = editor.downgrade();
+    let steps = positions
+        .into_iter()
+        .flat_map(|position| {
+            iter::once(ReplayStep::MoveCursor {
+                editor: editor.clone(),
+                position,
+            })
+            .chain(steps.iter().cloned().map(ReplayStep::Step))
+        })
+        .collect();
+    KeyboardMacros::replay_steps(steps, window, cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::SelectionEffects;
+    use gpui::{Entity, Focusable as _, KeyBinding, TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use util::{path, rel_path::rel_path};
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_record_and_replay_macro(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.txt": "one\ntwo\nthree\nfour\n",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.txt")), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+
+        cx.dispatch_action(ToggleRecording);
+        cx.simulate_input("> ");
+        cx.dispatch_action(ToggleRecording);
+        assert_eq!(text(&editor, cx), "> one\ntwo\nthree\nfour\n");
+
+        cx.dispatch_action(ReplayLastMacro { times: Some(2) });
+        cx.run_until_parked();
+        assert_eq!(text(&editor, cx), "> > > one\ntwo\nthree\nfour\n");
+
+        // Replays at the start of every line spanned by the selection, except for a
+        // trailing line the selection only touches at its start.
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |selections| {
+                selections.select_ranges([Point::new(1, 1)..Point::new(3, 0)])
+            });
+        });
+        cx.dispatch_action(ReplayLastMacroOnSelections);
+        cx.run_until_parked();
+        assert_eq!(text(&editor, cx), "> > > one\n> two\n> three\nfour\n");
+    }
+
+    // Stands in for the command palette, which this crate doesn't depend on.
+    actions!(command_palette, [Toggle]);
+
+    #[gpui::test]
+    async fn test_recording_skips_actions_outside_editors(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.txt": "one\ntwo\n",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.txt")), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        cx.update(|_, cx| {
+            cx.bind_keys([
+                KeyBinding::new("ctrl-n", editor::actions::MoveDown, None),
+                KeyBinding::new("ctrl-p", Toggle, None),
+                KeyBinding::new("enter", menu::Confirm, None),
+            ])
+        });
+
+        cx.dispatch_action(ToggleRecording);
+        cx.simulate_keystrokes("ctrl-n");
+        // Actions dispatched outside of an editor, e.g. to a picker, aren't recorded.
+        cx.update(|window, _| window.blur());
+        cx.simulate_keystrokes("enter");
+        editor.update_in(cx, |editor, window, cx| {
+            window.focus(&editor.focus_handle(cx), cx)
+        });
+        // Neither is opening the command palette to stop the recording.
+        cx.simulate_keystrokes("ctrl-p");
+        cx.dispatch_action(ToggleRecording);
+
+        let recorded_actions = cx.update(|_, cx| {
+            cx.global::<KeyboardMacros>()
+                .last_macro
+                .iter()
+                .flat_map(|steps| steps.iter())
+                .filter_map(|step| match step {
+                    MacroStep::Action(action) => Some(action.name()),
+                    MacroStep::Insertion { .. } => None,
+                })
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(recorded_actions, ["editor::MoveDown"]);
+    }
+
+    #[gpui::test]
+    async fn test_replay_over_step_limit(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.txt": "one\n",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let worktree_id = project.update(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let editor = workspace
+            .update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path("a.txt")), None, true, window, cx)
+            })
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+
+        cx.dispatch_action(ToggleRecording);
+        cx.simulate_input("x");
+        cx.dispatch_action(ToggleRecording);
+
+        // A replay that would be cut off doesn't start at all.
+        cx.dispatch_action(ReplayLastMacro {
+            times: Some(MAX_REPLAYED_STEPS + 1),
+        });
+        cx.run_until_parked();
+        assert_eq!(text(&editor, cx), "xone\n");
+    }
+
+    fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
+        cx.update(|cx| {
+            let state = AppState::test(cx);
+            crate::init(cx);
+            editor::init(cx);
+            state
+        })
+    }
+
+    fn text(editor: &Entity<Editor>, cx: &mut VisualTestContext) -> String {
+        editor.update(cx, |editor, cx| editor.text(cx))
+    }
+}
@@ -0,0 +1,188 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use anyhow::{Context as _, Result};
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{Action, App};
+use serde::{Deserialize, Serialize};
+use util::ResultExt as _;
+
+use crate::MacroStep;
+
+const SAVED_MACROS_KEY: &str = "keyboard_macros";
+
+/// A step of a saved macro, stored the way actions are written in the keymap.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SerializedMacroStep {
+    Action {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input: Option<serde_json::Value>,
+    },
+    Insertion {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        utf16_range_to_replace: Option<Range<isize>>,
+    },
+}
+
+impl SerializedMacroStep {
+    /// Returns `None` for actions whose arguments can't be recovered.
+    pub(crate) fn new(step: &MacroStep, cx: &App) -> Option<Self> {
+        match step {
+            MacroStep::Action(action) => {
+                let Some(input) = action_input(action.as_ref(), cx) else {
+                    log::warn!(
+                        "Leaving {} out of the saved macro, as its arguments are unknown",
+                        action.name()
+                    );
+                    return None;
+                };
+                Some(Self::Action {
+                    name: action.name().to_string(),
+                    input,
+                })
+            }
+            MacroStep::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Some(Self::Insertion {
+                text: text.to_string(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            }),
+        }
+    }
+
+    pub(crate) fn to_macro_step(&self, cx: &App) -> Result<MacroStep> {
+        match self {
+            Self::Action { name, input } => {
+                let action = cx
+                    .build_action(name, input.clone())
+                    .with_context(|| format!("building {name} for keyboard macro"))?;
+                Ok(MacroStep::Action(action))
+            }
+            Self::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => Ok(MacroStep::Insertion {
+                text: text.as_str().into(),
+                utf16_range_to_replace: utf16_range_to_replace.clone(),
+            }),
+        }
+    }
+}
+
+/// Actions don't serialize their arguments, so they're recovered from the key
+/// binding that dispatched the action. Returns `Some(None)` for actions that
+/// are fully described by their name.
+fn action_input(action: &dyn Action, cx: &App) -> Option<Option<serde_json::Value>> {
+    if cx
+        .build_action(action.name(), None)
+        .is_ok_and(|built| built.partial_eq(action))
+    {
+        return Some(None);
+    }
+
+    let keymap = cx.key_bindings();
+    let keymap = keymap.borrow();
+    keymap.bindings_for_action(action).find_map(|binding| {
+        let input = binding.action_input()?;
+        serde_json::from_str(&input).log_err().map(Some)
+    })
+}
+
+pub(crate) fn load_saved_macros() -> BTreeMap<String, Vec<SerializedMacroStep>> {
+    KEY_VALUE_STORE
+        .read_kvp(SAVED_MACROS_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).log_err())
+        .unwrap_or_default()
+}
+
+pub(crate) fn write_saved_macros(
+    saved_macros: BTreeMap<String, Vec<SerializedMacroStep>>,
+    cx: &App,
+) {
+    db::write_and_log(cx, move || async move {
+        let json = serde_json::to_string(&saved_macros)?;
+        KEY_VALUE_STORE
+            .write_kvp(SAVED_MACROS_KEY.to_string(), json)
+            .await
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::actions::{MoveDown, MoveToBeginningOfLine};
+    use gpui::{KeyBinding, TestAppContext};
+
+    #[gpui::test]
+    fn test_serialize_macro_steps(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let binding = KeyBinding::load(
+                "home",
+                Box::new(MoveToBeginningOfLine {
+                    stop_at_soft_wraps: true,
+                    stop_at_indent: true,
+                }),
+                None,
+                false,
+                Some(r#"{"stop_at_soft_wraps": true, "stop_at_indent": true}"#.into()),
+                cx.keyboard_mapper().as_ref(),
+            )
+            .unwrap();
+            cx.bind_keys([binding]);
+
+            let steps = [
+                MacroStep::Action(MoveDown.boxed_clone()),
+                MacroStep::Action(
+                    MoveToBeginningOfLine {
+                        stop_at_soft_wraps: true,
+                        stop_at_indent: true,
+                    }
+                    .boxed_clone(),
+                ),
+                // Without a binding, the arguments of this action are unknown.
+                MacroStep::Action(
+                    MoveToBeginningOfLine {
+                        stop_at_soft_wraps: false,
+                        stop_at_indent: true,
+                    }
+                    .boxed_clone(),
+                ),
+                MacroStep::Insertion {
+                    text: "hello".into(),
+                    utf16_range_to_replace: Some(-1..0),
+                },
+            ];
+            let serialized = steps
+                .iter()
+                .filter_map(|step| SerializedMacroStep::new(step, cx))
+                .collect::<Vec<_>>();
+            assert_eq!(serialized.len(), 3);
+
+            let deserialized = serialized
+                .iter()
+                .map(|step| step.to_macro_step(cx).unwrap())
+                .collect::<Vec<_>>();
+            let MacroStep::Action(action) = &deserialized[1] else {
+                panic!("expected an action");
+            };
+            assert!(action.partial_eq(&MoveToBeginningOfLine {
+                stop_at_soft_wraps: true,
+                stop_at_indent: true,
+            }));
+            let MacroStep::Insertion {
+                text,
+                utf16_range_to_replace,
+            } = &deserialized[2]
+            else {
+                panic!("expected an insertion");
+            };
+            assert_eq!(text.as_ref(), "hello");
+            assert_eq!(utf16_range_to_replace, &Some(-1..0));
+        });
+    }
+}
@@ -0,0 +1,46 @@
+use gpui::{Action, Subscription};
+use ui::{Tooltip, prelude::*};
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{KeyboardMacros, ToggleRecording};
+
+/// Shows in the status bar that a keyboard macro is being recorded.
+pub struct RecordingIndicator {
+    _observe_keyboard_macros: Subscription,
+}
+
+impl RecordingIndicator {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            _observe_keyboard_macros: cx.observe_global::<KeyboardMacros>(|_, cx| cx.notify()),
+        }
+    }
+}
+
+impl Render for RecordingIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div().when(KeyboardMacros::is_recording(cx), |el| {
+            el.child(
+                Button::new("stop-recording-macro", "Recording Macro")
+                    .label_size(LabelSize::Small)
+                    .color(Color::Error)
+                    .on_click(|_, window, cx| {
+                        window.dispatch_action(ToggleRecording.boxed_clone(), cx)
+                    })
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Stop Recording", &ToggleRecording, cx)
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for RecordingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
@@ -0,0 +1,52 @@
+use editor::{
+    Anchor, Editor, SelectionEffects,
+    replay::{self, Replayable, Replayer},
+    scroll::Autoscroll,
+};
+use gpui::{App, WeakEntity, Window};
+
+use crate::{KeyboardMacros, MacroStep};
+
+#[derive(Clone)]
+pub(crate) enum ReplayStep {
+    Step(MacroStep),
+    /// Moves the cursor of an editor to where the following steps should be replayed.
+    MoveCursor {
+        editor: WeakEntity<Editor>,
+        position: Anchor,
+    },
+}
+
+impl Replayable for ReplayStep {
+    fn replay(self, window: &mut Window, cx: &mut App) {
+        match self {
+            ReplayStep::Step(MacroStep::Action(action)) => {
+                if replay::should_replay(action.as_ref()) {
+                    window.dispatch_action(action, cx);
+                }
+            }
+            ReplayStep::Step(MacroStep::Insertion {
+                text,
+                utf16_range_to_replace,
+            }) => replay::replay_insertion(&text, utf16_range_to_replace, window, cx),
+            ReplayStep::MoveCursor { editor, position } => {
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.change_selections(
+                            SelectionEffects::scroll(Autoscroll::fit()),
+                            window,
+                            cx,
+                            |selections| selections.select_anchor_ranges([position..position]),
+                        );
+                    })
+                    .ok();
+            }
+        }
+    }
+}
+
+pub(crate) fn new_replayer() -> Replayer<ReplayStep> {
+    Replayer::new(|cx| {
+        cx.global_mut::<KeyboardMacros>().replayer.take();
+    })
+}
@@ -0,0 +1,79 @@
+use editor::{Editor, EditorEvent};
+use gpui::{App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::KeyboardMacros;
+
+/// Asks for the name to save the last recorded macro under.
+pub(crate) struct SaveMacro {
+    name_editor: Entity<Editor>,
+    _subscription: Subscription,
+}
+
+impl SaveMacro {
+    pub(crate) fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Macro name…", window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&name_editor, |_, _, event, cx| {
+            if let EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+
+        Self {
+            name_editor,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        KeyboardMacros::save_last_macro(name, cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for SaveMacro {}
+
+impl Focusable for SaveMacro {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SaveMacro {}
+
+impl Render for SaveMacro {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("SaveMacro")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().child(
+                    Label::new("Save the last recorded macro under a name").color(Color::Muted),
+                ),
+            )
+    }
+}
@@ -3032,6 +3032,28 @@ impl Buffer {
         cx.notify();
     }
 
+    /// Restores undo and redo history captured with [`TextBuffer::plain_history`] while
+    /// the buffer had the same text, such as in an earlier session. The text doesn't change,
+    /// and the buffer stays clean if it was saved before.
+    ///
+    /// The operations of the restored transactions aren't sent to other replicas, so this
+    /// must only be used on buffers that aren't shared.
+    pub fn restore_plain_history(
+        &mut self,
+        history: &PlainHistory,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let was_saved = self.saved_version == self.version;
+        self.text.restore_plain_history(history)?;
+        if was_saved {
+            self.saved_version = self.version.clone();
+            self.has_unsaved_edits.set((self.version.clone(), false));
+        }
+        self.was_changed();
+        cx.notify();
+        Ok(())
+    }
+
     /// Undoes the most recent transaction.
     pub fn undo(&mut self, cx: &mut Context<Self>) -> Option<TransactionId> {
         let was_dirty = self.is_dirty();
@@ -3125,6 +3147,27 @@ impl Buffer {
         redone
     }
 
+    /// Undoes and redoes transactions to get to a state of the buffer's undo tree, which may
+    /// be on a branch that can't be reached with undo and redo alone.
+    pub fn go_to_undo_state(
+        &mut self,
+        target: Option<TransactionId>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let was_dirty = self.is_dirty();
+        let old_version = self.version.clone();
+
+        let operations = self.text.go_to_undo_state(target);
+        let changed = !operations.is_empty();
+        for operation in operations {
+            self.send_operation(Operation::Buffer(operation), true, cx);
+        }
+        if changed {
+            self.did_edit(&old_version, was_dirty, cx)
+        }
+        changed
+    }
+
     /// Override current completion triggers with the user-provided completion triggers.
     pub fn set_completion_triggers(
         &mut self,
@@ -489,6 +489,35 @@ fn test_edit_events(cx: &mut gpui::App) {
     );
 }
 
+#[gpui::test]
+fn test_restore_plain_history(cx: &mut gpui::App) {
+    let original = cx.new(|cx| Buffer::local("abc", cx));
+    let history = original.update(cx, |buffer, cx| {
+        buffer.edit([(3..3, "def")], None, cx);
+        buffer.plain_history()
+    });
+
+    let buffer = cx.new(|cx| Buffer::local("abcdef", cx));
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let _subscription = cx.subscribe(&buffer, {
+        let events = events.clone();
+        move |_, event: &BufferEvent, _| events.lock().push(event.clone())
+    });
+
+    // The restored transactions are neither sent to other replicas nor change the text.
+    buffer.update(cx, |buffer, cx| {
+        buffer.restore_plain_history(&history, cx).unwrap();
+        assert_eq!(buffer.text(), "abcdef");
+        assert!(!buffer.is_dirty());
+    });
+    assert!(events.lock().is_empty());
+
+    buffer.update(cx, |buffer, cx| {
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "abc");
+    });
+}
+
 #[gpui::test]
 async fn test_apply_diff(cx: &mut TestAppContext) {
     let (text, offsets) = marked_text_offsets(
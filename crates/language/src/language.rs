@@ -904,6 +904,10 @@ pub struct LanguageConfig {
     /// If configured, this language contains JSX style tags, and should support auto-closing of those tags.
     #[serde(default)]
     pub jsx_tag_auto_close: Option<JsxTagAutoCloseConfig>,
+    /// If configured, Emmet abbreviations typed in this language are offered as completions,
+    /// expanded with the given syntax.
+    #[serde(default)]
+    pub emmet: Option<EmmetSyntax>,
     /// A list of characters that Zed should treat as word characters for completion queries.
     #[serde(default)]
     pub completion_query_characters: HashSet<char>,
@@ -966,6 +970,18 @@ pub struct LanguageMatcher {
     pub first_line_pattern: Option<Regex>,
 }
 
+/// How Emmet abbreviations are expanded in a language.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmmetSyntax {
+    /// Abbreviations expand to HTML elements, e.g. in HTML or template languages.
+    Html,
+    /// Abbreviations expand to JSX elements, using `className` and self-closing void elements.
+    Jsx,
+    /// Abbreviations expand to CSS declarations.
+    Css,
+}
+
 /// The configuration for JSX tag auto-closing.
 #[derive(Clone, Deserialize, JsonSchema, Debug)]
 pub struct JsxTagAutoCloseConfig {
@@ -1136,6 +1152,7 @@ impl Default for LanguageConfig {
             prettier_parser_name: None,
             hidden: false,
             jsx_tag_auto_close: None,
+            emmet: None,
             completion_query_characters: Default::default(),
             linked_edit_characters: Default::default(),
             debuggers: Default::default(),
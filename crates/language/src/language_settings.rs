@@ -7,14 +7,14 @@ use ec4rs::{
     property::{FinalNewline, IndentSize, IndentStyle, MaxLineLen, TabWidth, TrimTrailingWs},
 };
 use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
-use gpui::{App, Modifiers, SharedString};
+use gpui::{App, Hsla, Modifiers, Rgba, SharedString};
 use itertools::{Either, Itertools};
 use settings::IntoGpui;
 
 pub use settings::{
     CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FormatOnSave,
     Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LspInsertMode,
-    RewrapBehavior, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
+    RewrapBehavior, RulerContent, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
 };
 use settings::{RegisterSetting, Settings, SettingsLocation, SettingsStore};
 use shellexpand;
@@ -55,6 +55,34 @@ pub struct AllLanguageSettings {
     pub file_types: FxHashMap<Arc<str>, (GlobSet, Vec<String>)>,
 }
 
+/// A vertical ruler in the editor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ruler {
+    pub column: usize,
+    /// The color of the ruler, or `None` to use the theme's wrap guide color.
+    pub color: Option<Hsla>,
+}
+
+impl From<RulerContent> for Ruler {
+    fn from(content: RulerContent) -> Self {
+        match content {
+            RulerContent::Column(column) => Ruler {
+                column,
+                color: None,
+            },
+            RulerContent::Colored { column, color } => Ruler {
+                column,
+                color: color.and_then(|color| {
+                    Rgba::try_from(color.as_str())
+                        .map(Hsla::from)
+                        .inspect_err(|error| log::warn!("invalid ruler color: {error}"))
+                        .ok()
+                }),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct WhitespaceMap {
     pub space: SharedString,
@@ -81,6 +109,12 @@ pub struct LanguageSettings {
     pub show_wrap_guides: bool,
     /// Character counts at which to show wrap guides (vertical rulers) in the editor.
     pub wrap_guides: Vec<usize>,
+    /// Vertical rulers to show in the editor, regardless of the soft wrap mode.
+    pub rulers: Vec<Ruler>,
+    /// Whether to show the rulers in the editor.
+    pub show_rulers: bool,
+    /// Whether to show a ruler at the `max_line_length` set in `.editorconfig`.
+    pub show_editorconfig_ruler: bool,
     /// Indent guide related settings.
     pub indent_guides: IndentGuideSettings,
     /// Whether or not to perform a buffer format before saving.
@@ -194,6 +228,10 @@ pub struct CompletionSettings {
     ///
     /// Default: "replace_suffix"
     pub lsp_insert_mode: LspInsertMode,
+    /// Whether to offer expansions of Emmet abbreviations as completions.
+    ///
+    /// Default: true
+    pub emmet: bool,
 }
 
 /// The settings for indent guides.
@@ -514,6 +552,16 @@ fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigPr
         }
     }
     merge(&mut settings.preferred_line_length, preferred_line_length);
+    if let Some(column) = preferred_line_length.map(|length| length as usize)
+        && settings.show_editorconfig_ruler
+        && !settings.rulers.iter().any(|ruler| ruler.column == column)
+        && !has_wrap_guide_at(settings, column)
+    {
+        settings.rulers.push(Ruler {
+            column,
+            color: None,
+        });
+    }
     merge(&mut settings.tab_size, tab_size);
     merge(&mut settings.hard_tabs, hard_tabs);
     merge(
@@ -526,6 +574,19 @@ fn merge_with_editorconfig(settings: &mut LanguageSettings, cfg: &EditorconfigPr
     );
 }
 
+/// Returns whether a wrap guide is shown at the given column, mirroring `Editor::wrap_guides`.
+fn has_wrap_guide_at(settings: &LanguageSettings, column: usize) -> bool {
+    if !settings.show_wrap_guides {
+        return false;
+    }
+    let wraps_at_preferred_line_length = matches!(
+        settings.soft_wrap,
+        SoftWrap::PreferredLineLength | SoftWrap::Bounded
+    );
+    (wraps_at_preferred_line_length && settings.preferred_line_length as usize == column)
+        || settings.wrap_guides.contains(&column)
+}
+
 impl settings::Settings for AllLanguageSettings {
     fn from_settings(content: &settings::SettingsContent) -> Self {
         let all_languages = &content.project.all_languages;
@@ -545,6 +606,14 @@ impl settings::Settings for AllLanguageSettings {
                 preferred_line_length: settings.preferred_line_length.unwrap(),
                 show_wrap_guides: settings.show_wrap_guides.unwrap(),
                 wrap_guides: settings.wrap_guides.unwrap(),
+                rulers: settings
+                    .rulers
+                    .unwrap()
+                    .into_iter()
+                    .map(Ruler::from)
+                    .collect(),
+                show_rulers: settings.show_rulers.unwrap(),
+                show_editorconfig_ruler: settings.show_editorconfig_ruler.unwrap(),
                 indent_guides: IndentGuideSettings {
                     enabled: indent_guides.enabled.unwrap(),
                     line_width: indent_guides.line_width.unwrap(),
@@ -615,6 +684,7 @@ impl settings::Settings for AllLanguageSettings {
                     lsp: completions.lsp.unwrap(),
                     lsp_fetch_timeout_ms: completions.lsp_fetch_timeout_ms.unwrap(),
                     lsp_insert_mode: completions.lsp_insert_mode.unwrap(),
+                    emmet: completions.emmet.unwrap(),
                 },
                 debuggers: settings.debuggers.unwrap(),
                 word_diff_enabled: settings.word_diff_enabled.unwrap(),
@@ -0,0 +1,6 @@
+[
+  (code_span)
+  (link_destination)
+  (uri_autolink)
+  (email_autolink)
+] @code
@@ -0,0 +1,105 @@
+use editor::Editor;
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription, WeakEntity,
+};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::LargeFileView;
+
+/// Jumps to a line of a [`LargeFileView`], the counterpart of the editor's go to line modal.
+pub(crate) struct GoToLine {
+    line_editor: Entity<Editor>,
+    view: WeakEntity<LargeFileView>,
+    current_text: SharedString,
+    _subscription: Subscription,
+}
+
+impl GoToLine {
+    pub(crate) fn new(
+        view: WeakEntity<LargeFileView>,
+        current_row: usize,
+        line_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let line_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text(&(current_row + 1).to_string(), window, cx);
+            editor
+        });
+        let subscription = cx.subscribe(&line_editor, |_, _, event, cx| {
+            if let editor::EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+
+        Self {
+            line_editor,
+            view,
+            current_text: format!("Current Line: {} of {}", current_row + 1, line_count).into(),
+            _subscription: subscription,
+        }
+    }
+
+    fn line_from_query(&self, cx: &App) -> Option<usize> {
+        self.line_editor.read(cx).text(cx).trim().parse().ok()
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(line) = self.line_from_query(cx) {
+            self.view
+                .update(cx, |view, cx| {
+                    view.go_to_row(line.saturating_sub(1), cx);
+                    view.focus_handle(cx).focus(window, cx);
+                })
+                .ok();
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for GoToLine {}
+
+impl Focusable for GoToLine {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.line_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for GoToLine {}
+
+impl Render for GoToLine {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = match self.line_from_query(cx) {
+            Some(line) => format!("Go to line {line}").into(),
+            None => self.current_text.clone(),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("GoToLine")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.line_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(Color::Muted)),
+            )
+    }
+}
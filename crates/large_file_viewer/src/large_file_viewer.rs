@@ -0,0 +1,706 @@
+mod go_to_line;
+mod large_file_viewer_settings;
+mod line_index;
+mod paged_file;
+mod search;
+
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use editor::{EditorSettings, items::entry_git_aware_label_color};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, ClipboardItem, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    HighlightStyle, ScrollStrategy, StyledText, Task, UniformListScrollHandle, Window,
+    uniform_list,
+};
+use project::{Project, ProjectEntryId, ProjectPath, search::SearchQuery};
+use settings::Settings;
+use theme::{Theme, ThemeSettings};
+use ui::{WithScrollbar, prelude::*};
+use util::{ResultExt as _, paths::PathExt};
+use workspace::{
+    ItemSettings, Pane, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, Item, ProjectItem, TabContentParams},
+    searchable::{Direction, SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
+};
+
+use crate::{go_to_line::GoToLine, line_index::LineIndex, paged_file::PagedFile};
+
+pub use crate::large_file_viewer_settings::*;
+
+/// Lines are cut off after this many bytes, so that files consisting of a few
+/// very long lines can still be rendered.
+const MAX_DISPLAYED_LINE_LEN: usize = 4096;
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<LargeFileView>(cx);
+}
+
+/// A file that is too large to be loaded into a buffer, whose lines are read on demand instead.
+pub struct LargeFile {
+    project_path: ProjectPath,
+    abs_path: PathBuf,
+    entry_id: Option<ProjectEntryId>,
+    file: Arc<PagedFile>,
+    line_index: Option<Arc<LineIndex>>,
+    /// Why the file's lines couldn't be indexed, such as because it was truncated meanwhile.
+    index_error: Option<SharedString>,
+    _index_task: Task<()>,
+}
+
+impl LargeFile {
+    fn new(
+        project_path: ProjectPath,
+        abs_path: PathBuf,
+        entry_id: Option<ProjectEntryId>,
+        file: Arc<PagedFile>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let index_task = cx.spawn({
+            let file = file.clone();
+            async move |this, cx| {
+                let line_index = cx
+                    .background_spawn(async move { LineIndex::new(&file) })
+                    .await;
+                this.update(cx, |this, cx| {
+                    match line_index {
+                        Ok(line_index) => this.line_index = Some(Arc::new(line_index)),
+                        Err(error) => this.index_error = Some(format!("{error:#}").into()),
+                    }
+                    cx.notify();
+                })
+                .ok();
+            }
+        });
+
+        Self {
+            project_path,
+            abs_path,
+            entry_id,
+            file,
+            line_index: None,
+            index_error: None,
+            _index_task: index_task,
+        }
+    }
+
+    pub fn abs_path(&self) -> &Path {
+        &self.abs_path
+    }
+
+    /// Returns the size of the file in bytes.
+    pub fn size(&self) -> usize {
+        self.file.len()
+    }
+
+    /// Returns the number of lines in the file, or `None` while the file is still being indexed.
+    pub fn line_count(&self) -> Option<usize> {
+        self.line_index.as_ref().map(|index| index.line_count())
+    }
+}
+
+impl project::ProjectItem for LargeFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Entity<Self>>>> {
+        let min_file_size = LargeFileViewerSettings::get_global(cx).min_file_size;
+        let project = project.read(cx);
+        if !project.is_local() {
+            return None;
+        }
+        let entry = project.entry_for_path(path, cx)?;
+        if !entry.is_file() || entry.size < min_file_size {
+            return None;
+        }
+        let entry_id = entry.id;
+        let abs_path = project.absolute_path(path, cx)?;
+        let project_path = path.clone();
+
+        Some(cx.spawn(async move |cx| {
+            let (abs_path, file) = cx
+                .background_spawn(async move {
+                    let file = PagedFile::open(&abs_path)?;
+                    anyhow::Ok((abs_path, file))
+                })
+                .await?;
+            Ok(cx.new(|cx| {
+                LargeFile::new(project_path, abs_path, Some(entry_id), Arc::new(file), cx)
+            }))
+        }))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+/// A read-only view of a [`LargeFile`].
+///
+/// The view never loads the whole file: only the visible lines are read from disk, so
+/// syntax highlighting, language servers and inlay hints are not available.
+pub struct LargeFileView {
+    large_file: Entity<LargeFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    /// The offset search navigation starts from, which follows the selected line and the active
+    /// match.
+    cursor_offset: usize,
+    selected_row: Option<usize>,
+    search_matches: Vec<Range<usize>>,
+    active_match_index: Option<usize>,
+}
+
+impl LargeFileView {
+    pub fn new(
+        large_file: Entity<LargeFile>,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        cx.observe(&large_file, |_, _, cx| cx.notify()).detach();
+        Self {
+            large_file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor_offset: 0,
+            selected_row: None,
+            search_matches: Vec::new(),
+            active_match_index: None,
+        }
+    }
+
+    pub fn line_count(&self, cx: &App) -> Option<usize> {
+        self.large_file.read(cx).line_count()
+    }
+
+    /// Selects and reveals the given zero-based row.
+    pub fn go_to_row(&mut self, row: usize, cx: &mut Context<Self>) {
+        let large_file = self.large_file.read(cx);
+        let Some(line_index) = large_file.line_index.as_ref() else {
+            return;
+        };
+        let row = row.min(line_index.line_count() - 1);
+        let Some(line_range) = line_index.line_range(&large_file.file, row).log_err() else {
+            return;
+        };
+        self.cursor_offset = line_range.start;
+        self.selected_row = Some(row);
+        self.scroll_handle
+            .scroll_to_item(row, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn select_row(&mut self, row: usize, cx: &mut Context<Self>) {
+        let large_file = self.large_file.read(cx);
+        if let Some(line_index) = large_file.line_index.as_ref()
+            && let Some(line_range) = line_index.line_range(&large_file.file, row).log_err()
+        {
+            self.cursor_offset = line_range.start;
+            self.selected_row = Some(row);
+            cx.notify();
+        }
+    }
+
+    fn toggle_go_to_line(
+        &mut self,
+        _: &editor::actions::ToggleGoToLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(line_count) = self.line_count(cx) else {
+            return;
+        };
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        let current_row = self.selected_row.unwrap_or(0);
+        let view = cx.weak_entity();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                GoToLine::new(view, current_row, line_count, window, cx)
+            });
+        });
+    }
+
+    fn copy(&mut self, _: &editor::actions::Copy, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(row) = self.selected_row else {
+            return;
+        };
+        let large_file = self.large_file.read(cx);
+        let Some(line_index) = large_file.line_index.as_ref() else {
+            return;
+        };
+        let Some(line) = line_index
+            .line_range(&large_file.file, row)
+            .and_then(|line_range| large_file.file.read(line_range))
+            .log_err()
+        else {
+            return;
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(
+            String::from_utf8_lossy(&line).into_owned(),
+        ));
+    }
+
+    fn render_lines(
+        &self,
+        rows: Range<usize>,
+        line_number_width: usize,
+        line_height: Pixels,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let large_file = self.large_file.read(cx);
+        let Some(line_index) = large_file.line_index.clone() else {
+            return Vec::new();
+        };
+        let file = &large_file.file;
+        let colors = cx.theme().colors();
+        let active_match = self
+            .active_match_index
+            .and_then(|ix| self.search_matches.get(ix))
+            .cloned();
+
+        rows.map(|row| {
+            let (line_range, displayed_end, line_bytes) =
+                match read_displayed_line(file, &line_index, row) {
+                    Ok(line) => line,
+                    Err(error) => {
+                        return h_flex()
+                            .id(("line", row))
+                            .h(line_height)
+                            .pl_2()
+                            .text_color(colors.text_muted)
+                            .child(format!("Couldn't read line {}: {error:#}", row + 1))
+                            .into_any_element();
+                    }
+                };
+            let is_truncated = displayed_end < line_range.end;
+
+            let line = String::from_utf8_lossy(&line_bytes);
+            // Matches can only be highlighted if the line is valid UTF-8, as
+            // otherwise the displayed text doesn't have the same offsets.
+            let highlights = if matches!(line, std::borrow::Cow::Borrowed(_)) {
+                let first_match = self
+                    .search_matches
+                    .partition_point(|range| range.end <= line_range.start);
+                self.search_matches[first_match..]
+                    .iter()
+                    .take_while(|range| range.start < displayed_end)
+                    .filter_map(|range| {
+                        let start = range.start.max(line_range.start) - line_range.start;
+                        let end = range.end.min(displayed_end) - line_range.start;
+                        if start >= end
+                            || !line.is_char_boundary(start)
+                            || !line.is_char_boundary(end)
+                        {
+                            return None;
+                        }
+                        let background_color = if active_match.as_ref() == Some(range) {
+                            colors.search_active_match_background
+                        } else {
+                            colors.search_match_background
+                        };
+                        Some((
+                            start..end,
+                            HighlightStyle {
+                                background_color: Some(background_color),
+                                ..Default::default()
+                            },
+                        ))
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                Vec::new()
+            };
+            let is_selected = self.selected_row == Some(row);
+
+            h_flex()
+                .id(("line", row))
+                .w_full()
+                .h(line_height)
+                .gap_4()
+                .when(is_selected, |this| {
+                    this.bg(colors.editor_active_line_background)
+                })
+                .on_click(cx.listener(move |this, _, _, cx| this.select_row(row, cx)))
+                .child(
+                    div()
+                        .flex_none()
+                        .pl_2()
+                        .text_color(if is_selected {
+                            colors.editor_active_line_number
+                        } else {
+                            colors.editor_line_number
+                        })
+                        .child(format!("{:>line_number_width$}", row + 1)),
+                )
+                .child(
+                    h_flex()
+                        .min_w_0()
+                        .whitespace_nowrap()
+                        .child(StyledText::new(line.into_owned()).with_highlights(highlights))
+                        .when(is_truncated, |this| {
+                            this.child(
+                                div().pl_2().text_color(colors.text_muted).child(format!(
+                                    "… {} more bytes",
+                                    line_range.end - displayed_end
+                                )),
+                            )
+                        }),
+                )
+                .into_any_element()
+        })
+        .collect()
+    }
+}
+
+/// Reads the part of a line that is displayed, returning the line's range, where the displayed
+/// part ends, and the displayed bytes.
+fn read_displayed_line(
+    file: &PagedFile,
+    line_index: &LineIndex,
+    row: usize,
+) -> anyhow::Result<(Range<usize>, usize, Vec<u8>)> {
+    let line_range = line_index.line_range(file, row)?;
+    let mut displayed_end = line_range.end;
+    if line_range.len() > MAX_DISPLAYED_LINE_LEN {
+        displayed_end = line_range.start + MAX_DISPLAYED_LINE_LEN;
+        // Avoid cutting a UTF-8 character in half.
+        while displayed_end > line_range.start && file.byte_at(displayed_end)? & 0xC0 == 0x80 {
+            displayed_end -= 1;
+        }
+    }
+    let bytes = file.read(line_range.start..displayed_end)?;
+    Ok((line_range, displayed_end, bytes))
+}
+
+impl EventEmitter<()> for LargeFileView {}
+impl EventEmitter<SearchEvent> for LargeFileView {}
+
+impl Item for LargeFileView {
+    type Event = ();
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.large_file.entity_id(), self.large_file.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.large_file.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        let project_path = &self.large_file.read(cx).project_path;
+
+        let label_color = if ItemSettings::get_global(cx).git_status {
+            let git_status = self
+                .project
+                .read(cx)
+                .project_path_git_status(project_path, cx)
+                .map(|status| status.summary())
+                .unwrap_or_default();
+
+            self.project
+                .read(cx)
+                .entry_for_path(project_path, cx)
+                .map(|entry| {
+                    entry_git_aware_label_color(git_status, entry.is_ignored, params.selected)
+                })
+                .unwrap_or_else(|| params.text_color())
+        } else {
+            params.text_color()
+        };
+
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .single_line()
+            .color(label_color)
+            .when(params.preview, |this| this.italic())
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.large_file
+            .read(cx)
+            .project_path
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string()
+            .into()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.large_file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        let show_breadcrumb = EditorSettings::get_global(cx).toolbar.breadcrumbs;
+        if show_breadcrumb {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, _theme: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        let project = self.project.read(cx);
+        let project_path = &self.large_file.read(cx).project_path;
+        let mut path = project_path.path.clone();
+        if project.visible_worktrees(cx).count() > 1
+            && let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+        {
+            path = worktree.read(cx).root_name().join(&path);
+        }
+
+        Some(vec![BreadcrumbText {
+            text: path.display(project.path_style(cx)).to_string(),
+            highlights: None,
+            font: Some(ThemeSettings::get_global(cx).buffer_font.clone()),
+        }])
+    }
+
+    fn as_searchable(
+        &self,
+        handle: &Entity<Self>,
+        _: &App,
+    ) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Singleton
+    }
+}
+
+impl SearchableItem for LargeFileView {
+    type Match = Range<usize>;
+
+    fn supported_options(&self) -> SearchOptions {
+        SearchOptions {
+            case: true,
+            word: true,
+            regex: true,
+            replacement: false,
+            selection: false,
+            find_in_results: false,
+        }
+    }
+
+    fn clear_matches(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        self.search_matches.clear();
+        self.active_match_index = None;
+        cx.notify();
+    }
+
+    fn update_matches(
+        &mut self,
+        matches: &[Self::Match],
+        active_match_index: Option<usize>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.search_matches = matches.to_vec();
+        self.active_match_index = active_match_index;
+        cx.notify();
+    }
+
+    fn query_suggestion(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> String {
+        String::new()
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(range) = matches.get(index) else {
+            return;
+        };
+        self.cursor_offset = range.start;
+        self.active_match_index = Some(index);
+        let large_file = self.large_file.read(cx);
+        if let Some(line_index) = large_file.line_index.as_ref() {
+            if let Some(row) = line_index
+                .row_for_offset(&large_file.file, range.start)
+                .log_err()
+            {
+                self.selected_row = Some(row);
+                self.scroll_handle
+                    .scroll_to_item(row, ScrollStrategy::Center);
+            }
+        }
+        cx.emit(SearchEvent::ActiveMatchChanged);
+        cx.notify();
+    }
+
+    fn select_matches(&mut self, _: &[Self::Match], _: &mut Window, _: &mut Context<Self>) {
+        // The view is read-only and has no selections.
+    }
+
+    fn replace(
+        &mut self,
+        _: &Self::Match,
+        _: &SearchQuery,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+        // Replacement is not supported in the read-only view, so this is a no-op.
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<Self::Match>> {
+        let file = self.large_file.read(cx).file.clone();
+        cx.background_spawn(async move {
+            search::find_matches(&file, &query)
+                .await
+                .log_err()
+                .unwrap_or_default()
+        })
+    }
+
+    fn active_match_index(
+        &mut self,
+        direction: Direction,
+        matches: &[Self::Match],
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Option<usize> {
+        if matches.is_empty() {
+            return None;
+        }
+        let ix = match direction {
+            Direction::Next => {
+                let ix = matches.partition_point(|range| range.start < self.cursor_offset);
+                if ix == matches.len() { 0 } else { ix }
+            }
+            Direction::Prev => matches
+                .partition_point(|range| range.start <= self.cursor_offset)
+                .checked_sub(1)
+                .unwrap_or(matches.len() - 1),
+        };
+        Some(ix)
+    }
+}
+
+impl Focusable for LargeFileView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LargeFileView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let font_size = settings.buffer_font_size(cx);
+        let line_height = font_size * settings.line_height();
+        let buffer_font = settings.buffer_font.clone();
+
+        let large_file = self.large_file.read(cx);
+        let file_size = util::size::format_file_size(large_file.size() as u64, false);
+        let line_count = large_file.line_count();
+        let status = match (line_count, &large_file.index_error) {
+            (Some(line_count), _) => format!(
+                "{file_size}, {line_count} lines. This file is too large to edit, so it is shown read-only without syntax highlighting or language servers."
+            ),
+            (None, Some(error)) => format!("{file_size}. Couldn't index lines: {error}"),
+            (None, None) => format!("{file_size}. Indexing lines…"),
+        };
+        let line_number_width = line_count.unwrap_or(0).to_string().len();
+
+        v_flex()
+            .key_context("LargeFileView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_go_to_line))
+            .on_action(cx.listener(Self::copy))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .flex_none()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Icon::new(IconName::Info)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                div()
+                    .relative()
+                    .flex_1()
+                    .min_h_0()
+                    .font(buffer_font)
+                    .text_size(font_size)
+                    .when_some(line_count, |this, line_count| {
+                        this.child(
+                            uniform_list(
+                                "large-file-lines",
+                                line_count,
+                                cx.processor(move |this, rows: Range<usize>, _, cx| {
+                                    this.render_lines(rows, line_number_width, line_height, cx)
+                                }),
+                            )
+                            .size_full()
+                            .track_scroll(&self.scroll_handle),
+                        )
+                    })
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+    }
+}
+
+impl ProjectItem for LargeFileView {
+    type Item = LargeFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, cx)
+    }
+}
@@ -0,0 +1,19 @@
+use settings::{RegisterSetting, Settings};
+
+/// The settings for the large file viewer.
+#[derive(Clone, Debug, RegisterSetting)]
+pub struct LargeFileViewerSettings {
+    /// Files at least this many bytes large are opened in the large file viewer.
+    ///
+    /// Default: 256 MiB
+    pub min_file_size: u64,
+}
+
+impl Settings for LargeFileViewerSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let large_file_viewer = content.large_file_viewer.clone().unwrap();
+        Self {
+            min_file_size: large_file_viewer.min_file_size_mb.unwrap() * 1024 * 1024,
+        }
+    }
+}
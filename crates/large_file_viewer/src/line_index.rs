@@ -0,0 +1,137 @@
+use std::ops::Range;
+
+use anyhow::Result;
+
+use crate::paged_file::PagedFile;
+
+/// How many lines lie between two consecutive checkpoints of a [`LineIndex`].
+const LINES_PER_CHECKPOINT: usize = 256;
+
+/// The number of bytes read at once while indexing a file.
+const INDEX_CHUNK_LEN: usize = 4 * 1024 * 1024;
+
+/// A sparse index of line starts within a file.
+///
+/// Only every [`LINES_PER_CHECKPOINT`]th line start is stored, so the index stays small even for
+/// files with hundreds of millions of lines, while finding any line requires scanning at most
+/// [`LINES_PER_CHECKPOINT`] lines.
+pub struct LineIndex {
+    checkpoints: Vec<usize>,
+    line_count: usize,
+}
+
+impl LineIndex {
+    pub fn new(file: &PagedFile) -> Result<Self> {
+        let mut checkpoints = vec![0];
+        let mut line_count = 1;
+        let mut chunk_start = 0;
+        while chunk_start < file.len() {
+            let chunk_end = (chunk_start + INDEX_CHUNK_LEN).min(file.len());
+            let chunk = file.read(chunk_start..chunk_end)?;
+            for (ix, byte) in chunk.iter().enumerate() {
+                if *byte == b'\n' {
+                    if line_count % LINES_PER_CHECKPOINT == 0 {
+                        checkpoints.push(chunk_start + ix + 1);
+                    }
+                    line_count += 1;
+                }
+            }
+            chunk_start = chunk_end;
+        }
+        Ok(Self {
+            checkpoints,
+            line_count,
+        })
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_count
+    }
+
+    /// Returns the byte range of the given row, excluding its line ending.
+    pub fn line_range(&self, file: &PagedFile, row: usize) -> Result<Range<usize>> {
+        let row = row.min(self.line_count - 1);
+        let mut start = self.checkpoints[row / LINES_PER_CHECKPOINT];
+        for _ in 0..row % LINES_PER_CHECKPOINT {
+            start = next_line_start(file, start)?;
+        }
+
+        let mut end = file.find_byte(start, b'\n')?.unwrap_or(file.len());
+        if end > start && file.byte_at(end - 1)? == b'\r' {
+            end -= 1;
+        }
+        Ok(start..end)
+    }
+
+    /// Returns the row containing the given byte offset.
+    pub fn row_for_offset(&self, file: &PagedFile, offset: usize) -> Result<usize> {
+        let offset = offset.min(file.len());
+        let checkpoint_ix = self
+            .checkpoints
+            .partition_point(|start| *start <= offset)
+            .saturating_sub(1);
+        let mut row = checkpoint_ix * LINES_PER_CHECKPOINT;
+        let mut line_start = self.checkpoints[checkpoint_ix];
+        while let Some(newline) = file.find_byte(line_start, b'\n')?
+            && newline < offset
+        {
+            row += 1;
+            line_start = newline + 1;
+        }
+        Ok(row)
+    }
+}
+
+fn next_line_start(file: &PagedFile, offset: usize) -> Result<usize> {
+    Ok(file
+        .find_byte(offset, b'\n')?
+        .map_or(file.len(), |newline| newline + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paged_file::tests::paged_file;
+
+    #[test]
+    fn test_line_ranges() {
+        let text = b"one\r\ntwo\n\nfour";
+        let (_dir, file) = paged_file(text);
+        let index = LineIndex::new(&file).unwrap();
+        assert_eq!(index.line_count(), 4);
+        let lines = (0..index.line_count())
+            .map(|row| &text[index.line_range(&file, row).unwrap()])
+            .collect::<Vec<_>>();
+        assert_eq!(lines, [&b"one"[..], b"two", b"", b"four"]);
+
+        let (_dir, file) = paged_file(b"trailing newline\n");
+        let index = LineIndex::new(&file).unwrap();
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(index.line_range(&file, 1).unwrap(), 17..17);
+        assert_eq!(index.line_range(&file, 5).unwrap(), 17..17);
+    }
+
+    #[test]
+    fn test_lines_across_checkpoints() {
+        let last_row = LINES_PER_CHECKPOINT * 3 + 6;
+        let text = (0..=last_row)
+            .map(|row| format!("line {row}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let text = text.as_bytes();
+        let (_dir, file) = paged_file(text);
+        let index = LineIndex::new(&file).unwrap();
+        assert_eq!(index.line_count(), last_row + 1);
+
+        for row in [0, 1, 255, 256, 257, 511, 512, 700, last_row] {
+            let range = index.line_range(&file, row).unwrap();
+            assert_eq!(&text[range.clone()], format!("line {row}").as_bytes());
+            assert_eq!(index.row_for_offset(&file, range.start).unwrap(), row);
+            assert_eq!(index.row_for_offset(&file, range.end).unwrap(), row);
+        }
+        assert_eq!(
+            index.row_for_offset(&file, text.len() + 10).unwrap(),
+            last_row
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::{collections::VecDeque, fs::File, io, ops::Range, path::Path, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use parking_lot::Mutex;
+
+/// The number of bytes that are read at once when looking for a byte.
+const PAGE_LEN: usize = 64 * 1024;
+
+/// How many of the most recently read pages are kept in memory.
+const CACHED_PAGE_COUNT: usize = 64;
+
+/// A file that is read on demand, keeping the most recently read pages in memory.
+///
+/// The file is read with positioned reads rather than memory-mapped: when another process
+/// truncates a mapped file, for example when rotating a log, accessing the pages past its new
+/// end crashes the whole process, whereas reading it just returns an error.
+pub struct PagedFile {
+    file: File,
+    len: usize,
+    cached_pages: Mutex<VecDeque<(usize, Arc<[u8]>)>>,
+}
+
+impl PagedFile {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("opening {path:?}"))?;
+        let len = file
+            .metadata()
+            .with_context(|| format!("reading metadata of {path:?}"))?
+            .len() as usize;
+        Ok(Self {
+            file,
+            len,
+            cached_pages: Mutex::default(),
+        })
+    }
+
+    /// Returns the size of the file in bytes, as of when it was opened.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Reads the bytes in the given range, failing if the file no longer contains all of them.
+    pub fn read(&self, range: Range<usize>) -> Result<Vec<u8>> {
+        let mut bytes = vec![0; range.len()];
+        read_exact_at(&self.file, &mut bytes, range.start as u64)
+            .with_context(|| format!("reading bytes {range:?}"))?;
+        Ok(bytes)
+    }
+
+    pub fn byte_at(&self, offset: usize) -> Result<u8> {
+        let page = self.page(offset / PAGE_LEN)?;
+        page.get(offset % PAGE_LEN)
+            .copied()
+            .with_context(|| format!("offset {offset} is past the end of the file"))
+    }
+
+    /// Returns the offset of the first occurrence of `byte` at or after `offset`.
+    pub fn find_byte(&self, mut offset: usize, byte: u8) -> Result<Option<usize>> {
+        while offset < self.len {
+            let page_ix = offset / PAGE_LEN;
+            let page_start = page_ix * PAGE_LEN;
+            let page = self.page(page_ix)?;
+            if let Some(ix) = page[offset - page_start..]
+                .iter()
+                .position(|page_byte| *page_byte == byte)
+            {
+                return Ok(Some(offset + ix));
+            }
+            offset = page_start + page.len();
+        }
+        Ok(None)
+    }
+
+    fn page(&self, page_ix: usize) -> Result<Arc<[u8]>> {
+        {
+            let mut cached_pages = self.cached_pages.lock();
+            if let Some(position) = cached_pages.iter().position(|(ix, _)| *ix == page_ix) {
+                // Keep the most recently used pages at the back.
+                let cached_page = cached_pages.remove(position).unwrap();
+                let page = cached_page.1.clone();
+                cached_pages.push_back(cached_page);
+                return Ok(page);
+            }
+        }
+
+        let start = page_ix * PAGE_LEN;
+        let page: Arc<[u8]> = self.read(start..(start + PAGE_LEN).min(self.len))?.into();
+        let mut cached_pages = self.cached_pages.lock();
+        if cached_pages.len() == CACHED_PAGE_COUNT {
+            cached_pages.pop_front();
+        }
+        cached_pages.push_back((page_ix, page.clone()));
+        Ok(page)
+    }
+}
+
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    while !buf.is_empty() {
+        #[cfg(unix)]
+        let result = std::os::unix::fs::FileExt::read_at(file, buf, offset);
+        #[cfg(windows)]
+        let result = std::os::windows::fs::FileExt::seek_read(file, buf, offset);
+        match result {
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "the file was truncated",
+                ));
+            }
+            Ok(read) => {
+                buf = &mut buf[read..];
+                offset += read as u64;
+            }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Writes the text to a temporary file and opens it, returning the directory that must be
+    /// kept alive while the file is used.
+    pub(crate) fn paged_file(text: &[u8]) -> (TempDir, PagedFile) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file");
+        std::fs::write(&path, text).unwrap();
+        let file = PagedFile::open(&path).unwrap();
+        (dir, file)
+    }
+
+    #[test]
+    fn test_find_byte_across_pages() {
+        let mut text = vec![b'a'; PAGE_LEN * 2 + 10];
+        text[PAGE_LEN + 5] = b'\n';
+        let (_dir, file) = paged_file(&text);
+        assert_eq!(file.find_byte(0, b'\n').unwrap(), Some(PAGE_LEN + 5));
+        assert_eq!(file.find_byte(PAGE_LEN + 6, b'\n').unwrap(), None);
+        assert_eq!(file.byte_at(PAGE_LEN * 2 + 9).unwrap(), b'a');
+        assert!(file.byte_at(PAGE_LEN * 2 + 10).is_err());
+    }
+
+    #[test]
+    fn test_read_truncated_file() {
+        let (dir, file) = paged_file(&vec![b'a'; PAGE_LEN * 3]);
+        assert_eq!(file.byte_at(0).unwrap(), b'a');
+
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(dir.path().join("file"))
+            .unwrap()
+            .set_len(10)
+            .unwrap();
+        // Pages that were read before the file was truncated can still be accessed.
+        assert_eq!(file.byte_at(5).unwrap(), b'a');
+        assert!(file.byte_at(PAGE_LEN * 2).is_err());
+        assert!(file.read(0..20).is_err());
+        assert!(file.find_byte(PAGE_LEN, b'\n').is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use project::search::SearchQuery;
+use smol::future::yield_now;
+
+use crate::paged_file::PagedFile;
+
+/// Searching stops once this many matches were found, as the search bar can't
+/// present more than that in a useful way anyway.
+pub const MAX_MATCHES: usize = 10_000;
+
+/// The approximate number of bytes searched between two yields, so that an
+/// outdated search can be dropped quickly.
+const CHUNK_LEN: usize = 4 * 1024 * 1024;
+
+/// Finds the byte ranges matching the query.
+///
+/// Matches never span multiple lines, because the file is searched one
+/// chunk of lines at a time.
+pub async fn find_matches(file: &PagedFile, query: &SearchQuery) -> Result<Vec<Range<usize>>> {
+    let mut matches = Vec::new();
+    if query.is_empty() {
+        return Ok(matches);
+    }
+
+    let mut chunk_start = 0;
+    while chunk_start < file.len() && matches.len() < MAX_MATCHES {
+        let chunk_end = if chunk_start + CHUNK_LEN < file.len() {
+            file.find_byte(chunk_start + CHUNK_LEN, b'\n')?
+                .map_or(file.len(), |newline| newline + 1)
+        } else {
+            file.len()
+        };
+        let chunk = file.read(chunk_start..chunk_end)?;
+
+        match query {
+            SearchQuery::Text {
+                search, whole_word, ..
+            } => {
+                for mat in search.find_iter(chunk.as_slice()) {
+                    // Chunks start and end at line boundaries, so a match's surrounding
+                    // characters are within its chunk.
+                    if *whole_word && !is_whole_word(&chunk, &(mat.start()..mat.end())) {
+                        continue;
+                    }
+                    matches.push(chunk_start + mat.start()..chunk_start + mat.end());
+                }
+            }
+            SearchQuery::Regex { regex, .. } => {
+                let mut line_start = chunk_start;
+                for line in chunk.split(|byte| *byte == b'\n') {
+                    // Lines that aren't valid UTF-8 can't be searched with a regex.
+                    if let Ok(line) = std::str::from_utf8(line) {
+                        for mat in regex.find_iter(line).flatten() {
+                            matches.push(line_start + mat.start()..line_start + mat.end());
+                        }
+                    }
+                    line_start += line.len() + 1;
+                }
+            }
+        }
+
+        chunk_start = chunk_end;
+        yield_now().await;
+    }
+
+    matches.truncate(MAX_MATCHES);
+    Ok(matches)
+}
+
+fn is_whole_word(text: &[u8], range: &Range<usize>) -> bool {
+    let starts_word =
+        range.start > 0 && is_word_byte(text[range.start - 1]) && is_word_byte(text[range.start]);
+    let ends_word = range.end < text.len()
+        && is_word_byte(text[range.end - 1])
+        && is_word_byte(text[range.end]);
+    !starts_word && !ends_word
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    // Bytes of non-ASCII characters are treated as word characters, like
+    // letters in most scripts are.
+    byte.is_ascii_alphanumeric() || byte == b'_' || !byte.is_ascii()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paged_file::tests::paged_file;
+
+    fn text_query(query: &str, whole_word: bool, case_sensitive: bool) -> SearchQuery {
+        SearchQuery::text(
+            query,
+            whole_word,
+            case_sensitive,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn regex_query(query: &str) -> SearchQuery {
+        SearchQuery::regex(
+            query,
+            false,
+            true,
+            false,
+            false,
+            Default::default(),
+            Default::default(),
+            false,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_text_search() {
+        let (_dir, file) = paged_file(b"Error: disk full\nerror again\nterrors");
+        let find = |query| smol::block_on(find_matches(&file, &query)).unwrap();
+        assert_eq!(
+            find(text_query("error", false, false)),
+            [0..5, 17..22, 30..35]
+        );
+        assert_eq!(find(text_query("error", false, true)), [17..22, 30..35]);
+        assert_eq!(find(text_query("error", true, false)), [0..5, 17..22]);
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let (_dir, file) = paged_file(b"id=12\n\xff id=3\nid=456");
+        let matches = smol::block_on(find_matches(&file, &regex_query(r"id=\d+"))).unwrap();
+        assert_eq!(matches, [0..5, 13..19]);
+    }
+}
@@ -0,0 +1,415 @@
+//! Keeps snapshots of files when they are opened, saved or changed on disk, as revisions that
+//! can be compared with the file's current content and restored. The content of each revision
+//! is stored in the data directory under its hash, so identical revisions are stored once, and
+//! the revisions of each file are listed in the database.
+
+mod persistence;
+mod revision_list;
+
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use editor::Editor;
+use fs::{Fs, RemoveOptions};
+use gpui::{App, AppContext as _, Context, Entity, Global, Window, actions};
+use language::Buffer;
+use project::{
+    Project,
+    local_files::{self, LocalFileEvent, hash_text, local_abs_path},
+};
+use settings::Settings as _;
+use text::{LineEnding, Rope};
+use time::OffsetDateTime;
+use workspace::{Workspace, WorkspaceSettings};
+
+use crate::{
+    persistence::{DB, LocalHistoryDb},
+    revision_list::RevisionList,
+};
+
+actions!(
+    local_history,
+    [
+        /// Lists the snapshots of the active file, to compare it with or restore one of them.
+        ShowLocalHistory
+    ]
+);
+
+/// Files larger than this aren't snapshotted.
+const MAX_REVISION_SIZE: usize = 10 * 1024 * 1024;
+
+/// Once the stored revisions take up more space than this, the oldest ones are removed.
+const MAX_HISTORY_SIZE: u64 = 256 * 1024 * 1024;
+
+const MAX_REVISIONS_PER_FILE: usize = 100;
+
+const MAX_REVISION_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// The number of snapshots after which old revisions are removed again.
+const PRUNE_INTERVAL: usize = 50;
+
+struct LocalHistory {
+    fs: Arc<dyn Fs>,
+    snapshots_since_pruning: usize,
+}
+
+impl Global for LocalHistory {}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    cx.set_global(LocalHistory {
+        fs: fs.clone(),
+        snapshots_since_pruning: 0,
+    });
+    cx.background_spawn(async move { prune_history(&DB, &fs).await })
+        .detach_and_log_err(cx);
+
+    local_files::observe_local_files(cx, |buffer, event, cx| match event {
+        // Captures the content the file had before it is first saved, which only differs from
+        // its latest revision if the file was changed outside of Zed in the meantime.
+        LocalFileEvent::Opened => snapshot_buffer(buffer, true, cx),
+        LocalFileEvent::Saved => snapshot_buffer(buffer, false, cx),
+        LocalFileEvent::Reloaded => snapshot_buffer(buffer, true, cx),
+    });
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ShowLocalHistory, window, cx| {
+            show_local_history(workspace, window, cx);
+        });
+    })
+    .detach();
+}
+
+/// A snapshot of a file.
+#[derive(Clone, Debug)]
+pub(crate) struct Revision {
+    pub(crate) content_hash: String,
+    pub(crate) size: u64,
+    /// Whether the file was changed on disk, rather than saved.
+    pub(crate) external_change: bool,
+    pub(crate) created_at: OffsetDateTime,
+}
+
+/// Returns where the content of a file's revision with the given hash is stored. The file
+/// keeps its name, so that the revision is opened with the file's language.
+pub(crate) fn revision_path(path: &Path, content_hash: &str) -> Option<PathBuf> {
+    Some(
+        paths::local_history_dir()
+            .join(content_hash)
+            .join(path.file_name()?),
+    )
+}
+
+/// Stores the content of a buffer that was just written to or read from disk.
+fn snapshot_buffer(buffer: &Entity<Buffer>, external_change: bool, cx: &mut Context<Project>) {
+    if !WorkspaceSettings::get_global(cx).local_history {
+        return;
+    }
+    let buffer = buffer.read(cx);
+    let Some(path) = local_abs_path(buffer, cx) else {
+        return;
+    };
+    // Revisions that were opened to compare them aren't files of their own.
+    if path.starts_with(paths::local_history_dir()) {
+        return;
+    }
+    let text = buffer.as_rope().clone();
+    if text.len() > MAX_REVISION_SIZE {
+        return;
+    }
+    let line_ending = buffer.line_ending();
+
+    let local_history = cx.global_mut::<LocalHistory>();
+    let fs = local_history.fs.clone();
+    local_history.snapshots_since_pruning += 1;
+    let prune = local_history.snapshots_since_pruning >= PRUNE_INTERVAL;
+    if prune {
+        local_history.snapshots_since_pruning = 0;
+    }
+
+    cx.background_spawn(async move {
+        add_revision(&DB, &fs, path, &text, line_ending, external_change).await?;
+        if prune {
+            prune_history(&DB, &fs).await?;
+        }
+        Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+async fn add_revision(
+    db: &LocalHistoryDb,
+    fs: &Arc<dyn Fs>,
+    path: PathBuf,
+    text: &Rope,
+    line_ending: LineEnding,
+    external_change: bool,
+) -> Result<()> {
+    let content_hash = hash_text(text);
+    if db.latest_content_hash(&path)?.as_ref() == Some(&content_hash) {
+        return Ok(());
+    }
+    let Some(revision_path) = revision_path(&path, &content_hash) else {
+        return Ok(());
+    };
+    if !fs.is_file(&revision_path).await {
+        if let Some(dir) = revision_path.parent() {
+            fs.create_dir(dir).await?;
+        }
+        fs.save(&revision_path, text, line_ending).await?;
+    }
+    db.insert_revision(path, content_hash, text.len() as i64, external_change)
+        .await
+}
+
+#[derive(Debug)]
+struct StoredRevision {
+    id: i64,
+    path: PathBuf,
+    content_hash: String,
+    size: u64,
+    created_at: i64,
+}
+
+/// Removes the revisions that are too old, beyond the maximum count of their file, or
+/// beyond the maximum size of the history, together with their content once no other
+/// revision refers to it.
+async fn prune_history(db: &LocalHistoryDb, fs: &Arc<dyn Fs>) -> Result<()> {
+    let revisions = db
+        .all_revisions()?
+        .into_iter()
+        .map(
+            |(id, path, content_hash, size, created_at)| StoredRevision {
+                id,
+                path,
+                content_hash,
+                size: size as u64,
+                created_at,
+            },
+        )
+        .collect::<Vec<_>>();
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let pruned_ids = revisions_to_prune(&revisions, now);
+    if pruned_ids.is_empty() {
+        return Ok(());
+    }
+
+    let kept_paths = revisions
+        .iter()
+        .filter(|revision| !pruned_ids.contains(&revision.id))
+        .filter_map(|revision| revision_path(&revision.path, &revision.content_hash))
+        .collect::<HashSet<_>>();
+    for revision in &revisions {
+        if !pruned_ids.contains(&revision.id) {
+            continue;
+        }
+        db.delete_revision(revision.id).await?;
+        let Some(revision_path) = revision_path(&revision.path, &revision.content_hash) else {
+            continue;
+        };
+        if kept_paths.contains(&revision_path) {
+            continue;
+        }
+        fs.remove_file(
+            &revision_path,
+            RemoveOptions {
+                recursive: false,
+                ignore_if_not_exists: true,
+            },
+        )
+        .await?;
+        // The directory of the content hash is only empty once no other file's revision
+        // has this content.
+        if let Some(dir) = revision_path.parent() {
+            fs.remove_dir(
+                dir,
+                RemoveOptions {
+                    recursive: false,
+                    ignore_if_not_exists: true,
+                },
+            )
+            .await
+            .ok();
+        }
+    }
+    Ok(())
+}
+
+/// Returns the ids of the revisions to remove, given all revisions starting with the most
+/// recent one, and the current time in seconds since the Unix epoch.
+fn revisions_to_prune(revisions: &[StoredRevision], now: i64) -> HashSet<i64> {
+    let min_created_at = now - MAX_REVISION_AGE.as_secs() as i64;
+    let mut revision_counts = HashMap::<&Path, usize>::default();
+    let mut stored_paths = HashSet::default();
+    let mut history_size = 0;
+    revisions
+        .iter()
+        .filter(|revision| {
+            let count = revision_counts.entry(&revision.path).or_default();
+            *count += 1;
+            if revision.created_at < min_created_at || *count > MAX_REVISIONS_PER_FILE {
+                return true;
+            }
+            if stored_paths.insert(revision_path(&revision.path, &revision.content_hash)) {
+                history_size += revision.size;
+            }
+            history_size > MAX_HISTORY_SIZE
+        })
+        .map(|revision| revision.id)
+        .collect()
+}
+
+fn show_local_history(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let Some(path) = workspace
+        .active_item_as::<Editor>(cx)
+        .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+        .and_then(|buffer| local_abs_path(buffer.read(cx), cx))
+    else {
+        return;
+    };
+    let fs = workspace.project().read(cx).fs().clone();
+
+    cx.spawn_in(window, async move |workspace, cx| {
+        let revisions = cx
+            .background_spawn({
+                let path = path.clone();
+                async move { DB.revisions(&path) }
+            })
+            .await?
+            .into_iter()
+            .filter_map(|(_, content_hash, size, external_change, created_at)| {
+                Some(Revision {
+                    content_hash,
+                    size: size as u64,
+                    external_change,
+                    created_at: OffsetDateTime::from_unix_timestamp(created_at).ok()?,
+                })
+            })
+            .collect();
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let workspace_handle = cx.weak_entity();
+            workspace.toggle_modal(window, cx, |window, cx| {
+                RevisionList::new(workspace_handle, fs, path, revisions, window, cx)
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use util::path;
+
+    fn stored_revision(id: i64, path: &str, content_hash: &str, size: u64) -> StoredRevision {
+        StoredRevision {
+            id,
+            path: PathBuf::from(path),
+            content_hash: content_hash.to_string(),
+            size,
+            created_at: 1000 * 24 * 60 * 60 + id,
+        }
+    }
+
+    #[test]
+    fn test_revisions_to_prune() {
+        let now = 1000 * 24 * 60 * 60 + 100;
+
+        // Revisions with the same content share their size.
+        let large = MAX_HISTORY_SIZE / 2;
+        let revisions = [
+            stored_revision(5, "/a.txt", "1", 10),
+            stored_revision(4, "/a.txt", "2", large),
+            stored_revision(3, "/b.txt", "3", large),
+            stored_revision(2, "/a.txt", "2", large),
+            stored_revision(1, "/a.txt", "4", 10),
+        ];
+        assert_eq!(
+            revisions_to_prune(&revisions, now),
+            HashSet::from_iter([3, 2, 1])
+        );
+
+        let mut old_revision = stored_revision(1, "/a.txt", "1", 10);
+        old_revision.created_at = now - MAX_REVISION_AGE.as_secs() as i64 - 1;
+        assert_eq!(
+            revisions_to_prune(&[old_revision], now),
+            HashSet::from_iter([1])
+        );
+
+        let revisions = (0..MAX_REVISIONS_PER_FILE as i64 + 2)
+            .rev()
+            .map(|id| stored_revision(id, "/a.txt", &id.to_string(), 10))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            revisions_to_prune(&revisions, now),
+            HashSet::from_iter([1, 0])
+        );
+    }
+
+    #[gpui::test]
+    async fn test_add_and_prune_revisions(cx: &mut TestAppContext) {
+        let db = LocalHistoryDb::open_test_db("test_add_and_prune_revisions").await;
+        let fs: Arc<dyn Fs> = FakeFs::new(cx.executor());
+        let a_path = PathBuf::from(path!("/root/a.txt"));
+        let b_path = PathBuf::from(path!("/root/b.txt"));
+        let add = async |path: &Path, text: &str| {
+            add_revision(
+                &db,
+                &fs,
+                path.to_path_buf(),
+                &Rope::from(text),
+                LineEnding::Unix,
+                false,
+            )
+            .await
+            .unwrap();
+        };
+        let content_hashes = |path: &Path| {
+            db.revisions(path)
+                .unwrap()
+                .into_iter()
+                .map(|(_, content_hash, ..)| content_hash)
+                .collect::<Vec<_>>()
+        };
+        let one_hash = hash_text(&Rope::from("one"));
+        let two_hash = hash_text(&Rope::from("two"));
+
+        add(&a_path, "one").await;
+        // Content that didn't change since the latest revision isn't stored again.
+        add(&a_path, "one").await;
+        add(&b_path, "one").await;
+        add(&a_path, "two").await;
+        assert_eq!(
+            content_hashes(&a_path),
+            [two_hash.clone(), one_hash.clone()]
+        );
+        assert_eq!(content_hashes(&b_path), [one_hash.clone()]);
+        let a_one_path = revision_path(&a_path, &one_hash).unwrap();
+        let b_one_path = revision_path(&b_path, &one_hash).unwrap();
+        let a_two_path = revision_path(&a_path, &two_hash).unwrap();
+        assert_eq!(fs.load(&a_one_path).await.unwrap(), "one");
+        assert_eq!(fs.load(&b_one_path).await.unwrap(), "one");
+        assert_eq!(fs.load(&a_two_path).await.unwrap(), "two");
+
+        for ix in 0..MAX_REVISIONS_PER_FILE - 1 {
+            add(&a_path, &ix.to_string()).await;
+        }
+        prune_history(&db, &fs).await.unwrap();
+        let a_hashes = content_hashes(&a_path);
+        assert_eq!(a_hashes.len(), MAX_REVISIONS_PER_FILE);
+        assert_eq!(a_hashes.last(), Some(&two_hash));
+        assert_eq!(content_hashes(&b_path), [one_hash]);
+        // The pruned revision's content is removed, but the directory of its hash is kept for
+        // the other file's revision with the same content.
+        assert!(!fs.is_file(&a_one_path).await);
+        assert!(fs.is_file(&b_one_path).await);
+        assert!(fs.is_file(&a_two_path).await);
+    }
+}
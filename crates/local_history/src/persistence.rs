@@ -0,0 +1,74 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use db::{
+    query,
+    sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection},
+    sqlez_macros::sql,
+};
+
+pub struct LocalHistoryDb(ThreadSafeConnection);
+
+impl Domain for LocalHistoryDb {
+    const NAME: &str = stringify!(LocalHistoryDb);
+
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE local_history_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            path BLOB NOT NULL,
+            content_hash TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            external_change INTEGER NOT NULL,
+            created_at INTEGER DEFAULT (unixepoch()) NOT NULL
+        ) STRICT;
+        CREATE INDEX idx_local_history_revisions ON local_history_revisions (path, id);
+    )];
+}
+
+db::static_connection!(DB, LocalHistoryDb, []);
+
+impl LocalHistoryDb {
+    query! {
+        pub(crate) fn latest_content_hash(path: &Path) -> Result<Option<String>> {
+            SELECT content_hash FROM local_history_revisions
+            WHERE path = ?
+            ORDER BY id DESC
+            LIMIT 1
+        }
+    }
+
+    query! {
+        pub(crate) async fn insert_revision(path: PathBuf, content_hash: String, size: i64, external_change: bool) -> Result<()> {
+            INSERT INTO local_history_revisions
+                (path, content_hash, size, external_change)
+            VALUES
+                (?1, ?2, ?3, ?4)
+        }
+    }
+
+    // Returns the id, content hash, size, whether it was changed on disk, and creation time
+    // of the file's revisions, starting with the most recent one.
+    query! {
+        pub(crate) fn revisions(path: &Path) -> Result<Vec<(i64, String, i64, bool, i64)>> {
+            SELECT id, content_hash, size, external_change, created_at FROM local_history_revisions
+            WHERE path = ?
+            ORDER BY id DESC
+        }
+    }
+
+    // Returns the id, path, content hash, size and creation time of all revisions, starting
+    // with the most recent one.
+    query! {
+        pub(crate) fn all_revisions() -> Result<Vec<(i64, PathBuf, String, i64, i64)>> {
+            SELECT id, path, content_hash, size, created_at FROM local_history_revisions
+            ORDER BY id DESC
+        }
+    }
+
+    query! {
+        pub(crate) async fn delete_revision(id: i64) -> Result<()> {
+            DELETE FROM local_history_revisions
+            WHERE id = ?
+        }
+    }
+}
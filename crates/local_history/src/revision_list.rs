@@ -0,0 +1,293 @@
+use std::{path::PathBuf, sync::Arc};
+
+use editor::Editor;
+use fs::Fs;
+use git_ui::file_diff_view::FileDiffView;
+use gpui::{
+    Action, AnyElement, App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable,
+    Render, Task, WeakEntity, Window,
+};
+use language::Capability;
+use picker::{Picker, PickerDelegate};
+use time::{OffsetDateTime, UtcOffset};
+use time_format::TimestampFormat;
+use ui::{KeyBinding, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt as _;
+use workspace::{ModalView, OpenOptions, OpenVisible, Workspace};
+
+use crate::{Revision, revision_path};
+
+/// Lists the revisions of a file, to compare it with or restore one of them.
+pub(crate) struct RevisionList {
+    picker: Entity<Picker<RevisionListDelegate>>,
+}
+
+impl RevisionList {
+    pub(crate) fn new(
+        workspace: WeakEntity<Workspace>,
+        fs: Arc<dyn Fs>,
+        path: PathBuf,
+        revisions: Vec<Revision>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = RevisionListDelegate {
+            revision_list: cx.entity().downgrade(),
+            workspace,
+            fs,
+            path,
+            revisions,
+            selected_index: 0,
+        };
+        let picker = cx.new(|cx| Picker::nonsearchable_uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for RevisionList {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("RevisionList")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for RevisionList {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for RevisionList {}
+impl ModalView for RevisionList {}
+
+pub(crate) struct RevisionListDelegate {
+    revision_list: WeakEntity<RevisionList>,
+    workspace: WeakEntity<Workspace>,
+    fs: Arc<dyn Fs>,
+    path: PathBuf,
+    revisions: Vec<Revision>,
+    selected_index: usize,
+}
+
+impl RevisionListDelegate {
+    fn selected_revision_path(&self) -> Option<PathBuf> {
+        let revision = self.revisions.get(self.selected_index)?;
+        revision_path(&self.path, &revision.content_hash)
+    }
+
+    fn compare(&self, revision_path: PathBuf, window: &mut Window, cx: &mut App) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let path = self.path.clone();
+        let project = workspace.read(cx).project().clone();
+        let workspace = workspace.downgrade();
+        window
+            .spawn(cx, async move |cx| {
+                let revision_buffer = project
+                    .update(cx, |project, cx| {
+                        project.open_local_buffer(&revision_path, cx)
+                    })
+                    .await?;
+                // The revision's content is shared by every revision with the same hash.
+                revision_buffer.update(cx, |buffer, cx| {
+                    buffer.set_capability(Capability::ReadOnly, cx)
+                });
+                workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        FileDiffView::open_with_old_buffer(
+                            revision_buffer,
+                            path,
+                            workspace,
+                            window,
+                            cx,
+                        )
+                    })?
+                    .await?;
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    /// Replaces the content of the file's buffer with the revision, as a single transaction
+    /// that can be undone.
+    fn restore(&self, revision_path: PathBuf, window: &mut Window, cx: &mut App) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let fs = self.fs.clone();
+        let open_task = workspace.update(cx, |workspace, cx| {
+            workspace.open_abs_path(
+                self.path.clone(),
+                OpenOptions {
+                    visible: Some(OpenVisible::All),
+                    focus: Some(true),
+                    ..Default::default()
+                },
+                window,
+                cx,
+            )
+        });
+        window
+            .spawn(cx, async move |cx| {
+                let text = fs.load(&revision_path).await?;
+                let item = open_task.await?;
+                let Some(buffer) = cx.update(|_, cx| {
+                    item.act_as::<Editor>(cx)
+                        .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+                })?
+                else {
+                    return Ok(());
+                };
+                let diff = buffer.update(cx, |buffer, cx| buffer.diff(text, cx)).await;
+                buffer.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.apply_diff(diff, cx);
+                    buffer.finalize_last_transaction();
+                });
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for RevisionListDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Select a revision…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some("No local history for this file".into())
+    }
+
+    fn match_count(&self) -> usize {
+        self.revisions.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        _query: String,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(revision_path) = self.selected_revision_path() else {
+            return;
+        };
+        if secondary {
+            self.restore(revision_path, window, cx);
+        } else {
+            self.compare(revision_path, window, cx);
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.revision_list
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let revision = self.revisions.get(ix)?;
+        let now = OffsetDateTime::now_utc();
+        let local_offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC);
+        let relative_timestamp = time_format::format_localized_timestamp(
+            revision.created_at,
+            now,
+            local_offset,
+            TimestampFormat::Relative,
+        );
+        let absolute_timestamp = time_format::format_localized_timestamp(
+            revision.created_at,
+            now,
+            local_offset,
+            TimestampFormat::MediumAbsolute,
+        );
+        let kind = if revision.external_change {
+            "Changed on disk"
+        } else {
+            "Saved"
+        };
+        let size = util::size::format_file_size(revision.size, false);
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .gap_2()
+                        .justify_between()
+                        .child(Label::new(relative_timestamp))
+                        .child(
+                            Label::new(format!("{absolute_timestamp} · {kind} · {size}"))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                ),
+        )
+    }
+
+    fn render_footer(
+        &self,
+        _window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<AnyElement> {
+        if self.revisions.is_empty() {
+            return None;
+        }
+        Some(
+            h_flex()
+                .w_full()
+                .p_1p5()
+                .gap_1()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("compare-revision", "Compare")
+                        .key_binding(KeyBinding::for_action(&menu::Confirm, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::Confirm.boxed_clone(), cx)
+                        }),
+                )
+                .child(
+                    Button::new("restore-revision", "Restore")
+                        .key_binding(KeyBinding::for_action(&menu::SecondaryConfirm, cx))
+                        .on_click(|_, window, cx| {
+                            window.dispatch_action(menu::SecondaryConfirm.boxed_clone(), cx)
+                        }),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -17,7 +17,7 @@ use ui::{
 use vim_mode_setting::VimModeSetting;
 
 use crate::{
-    ImportCursorSettings, ImportVsCodeSettings, SettingsImportState,
+    ImportCursorSettings, ImportJetBrainsSettings, ImportVsCodeSettings, SettingsImportState,
     theme_preview::{ThemePreviewStyle, ThemePreviewTile},
 };
 
@@ -480,7 +480,7 @@ fn render_setting_import_button(
 
 fn render_import_settings_section(tab_index: &mut isize, cx: &mut App) -> impl IntoElement {
     let import_state = SettingsImportState::global(cx);
-    let imports: [(SharedString, &dyn Action, bool); 2] = [
+    let imports: [(SharedString, &dyn Action, bool); 3] = [
         (
             "VS Code".into(),
             &ImportVsCodeSettings { skip_prompt: false },
@@ -491,9 +491,14 @@ fn render_import_settings_section(tab_index: &mut isize, cx: &mut App) -> impl I
             &ImportCursorSettings { skip_prompt: false },
             import_state.cursor,
         ),
+        (
+            "JetBrains".into(),
+            &ImportJetBrainsSettings { skip_prompt: false },
+            import_state.jetbrains,
+        ),
     ];
 
-    let [vscode, cursor] = imports.map(|(label, action, imported)| {
+    let [vscode, cursor, jetbrains] = imports.map(|(label, action, imported)| {
         *tab_index += 1;
         render_setting_import_button(*tab_index - 1, label, action, imported)
     });
@@ -512,7 +517,13 @@ fn render_import_settings_section(tab_index: &mut isize, cx: &mut App) -> impl I
                         .color(Color::Muted),
                 ),
         )
-        .child(h_flex().gap_1().child(vscode).child(cursor))
+        .child(
+            h_flex()
+                .gap_1()
+                .child(vscode)
+                .child(cursor)
+                .child(jetbrains),
+        )
 }
 
 pub(crate) fn render_basics_page(cx: &mut App) -> impl IntoElement {
@@ -1,16 +1,17 @@
 use crate::multibuffer_hint::MultibufferHint;
 use client::{Client, UserStore, zed_urls};
 use db::kvp::KEY_VALUE_STORE;
+use editor::{Editor, MultiBuffer};
 use fs::Fs;
 use gpui::{
     Action, AnyElement, App, AppContext, AsyncWindowContext, Context, Entity, EventEmitter,
-    FocusHandle, Focusable, Global, IntoElement, KeyContext, Render, ScrollHandle, SharedString,
-    Subscription, Task, WeakEntity, Window, actions,
+    FocusHandle, Focusable, Global, IntoElement, KeyContext, PathPromptOptions, Render,
+    ScrollHandle, SharedString, Subscription, Task, WeakEntity, Window, actions,
 };
 use notifications::status_toast::{StatusToast, ToastIcon};
 use schemars::JsonSchema;
 use serde::Deserialize;
-use settings::{SettingsStore, VsCodeSettingsSource};
+use settings::{JetBrainsSettings, SettingsStore, VsCodeSettingsSource};
 use std::sync::Arc;
 use ui::{
     Divider, KeyBinding, ParentElement as _, StatefulInteractiveElement, Vector, VectorName,
@@ -50,6 +51,33 @@ pub struct ImportCursorSettings {
     pub skip_prompt: bool,
 }
 
+/// Imports keybindings from Visual Studio Code.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = zed)]
+#[serde(deny_unknown_fields)]
+pub struct ImportVsCodeKeymap {
+    #[serde(default)]
+    pub skip_prompt: bool,
+}
+
+/// Imports keybindings from Cursor editor.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = zed)]
+#[serde(deny_unknown_fields)]
+pub struct ImportCursorKeymap {
+    #[serde(default)]
+    pub skip_prompt: bool,
+}
+
+/// Imports settings, keybindings, and code style from a JetBrains IDE.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, JsonSchema, Action)]
+#[action(namespace = zed)]
+#[serde(deny_unknown_fields)]
+pub struct ImportJetBrainsSettings {
+    #[serde(default)]
+    pub skip_prompt: bool,
+}
+
 pub const FIRST_OPEN: &str = "first_open";
 pub const DOCS_URL: &str = "https://zed.dev/docs/";
 
@@ -169,6 +197,59 @@ pub fn init(cx: &mut App) {
                 })
                 .detach();
         });
+
+        workspace.register_action(|_workspace, action: &ImportVsCodeKeymap, window, cx| {
+            let fs = <dyn Fs>::global(cx);
+            let action = *action;
+
+            let workspace = cx.weak_entity();
+
+            window
+                .spawn(cx, async move |cx: &mut AsyncWindowContext| {
+                    handle_import_vscode_keymap(
+                        workspace,
+                        VsCodeSettingsSource::VsCode,
+                        action.skip_prompt,
+                        fs,
+                        cx,
+                    )
+                    .await
+                })
+                .detach();
+        });
+
+        workspace.register_action(|_workspace, action: &ImportCursorKeymap, window, cx| {
+            let fs = <dyn Fs>::global(cx);
+            let action = *action;
+
+            let workspace = cx.weak_entity();
+
+            window
+                .spawn(cx, async move |cx: &mut AsyncWindowContext| {
+                    handle_import_vscode_keymap(
+                        workspace,
+                        VsCodeSettingsSource::Cursor,
+                        action.skip_prompt,
+                        fs,
+                        cx,
+                    )
+                    .await
+                })
+                .detach();
+        });
+
+        workspace.register_action(|_workspace, action: &ImportJetBrainsSettings, window, cx| {
+            let fs = <dyn Fs>::global(cx);
+            let action = *action;
+
+            let workspace = cx.weak_entity();
+
+            window
+                .spawn(cx, async move |cx: &mut AsyncWindowContext| {
+                    handle_import_jetbrains_settings(workspace, action.skip_prompt, fs, cx).await
+                })
+                .detach();
+        });
     })
     .detach();
 
@@ -526,10 +607,326 @@ pub async fn handle_import_vscode_settings(
         .ok();
 }
 
+pub async fn handle_import_vscode_keymap(
+    workspace: WeakEntity<Workspace>,
+    source: VsCodeSettingsSource,
+    skip_prompt: bool,
+    fs: Arc<dyn Fs>,
+    cx: &mut AsyncWindowContext,
+) {
+    use util::truncate_and_remove_front;
+
+    let vscode_keymap = match settings::VsCodeKeymap::load_user_keymap(source, fs.clone()).await {
+        Ok(vscode_keymap) => vscode_keymap,
+        Err(err) => {
+            zlog::error!("{err:?}");
+            let _ = cx.prompt(
+                gpui::PromptLevel::Info,
+                &format!("Could not find or load a {source} keybindings file"),
+                None,
+                &["Ok"],
+            );
+            return;
+        }
+    };
+
+    if !skip_prompt {
+        let prompt = cx.prompt(
+            gpui::PromptLevel::Warning,
+            &format!(
+                "Importing {} keybindings will add them to your keymap. \
+                Will import keybindings from {}",
+                vscode_keymap.source,
+                truncate_and_remove_front(&vscode_keymap.path.to_string_lossy(), 128),
+            ),
+            None,
+            &["Ok", "Cancel"],
+        );
+        let result = cx.spawn(async move |_| prompt.await.ok()).await;
+        if result != Some(0) {
+            return;
+        }
+    };
+
+    let translated = vscode_keymap.translate();
+    let untranslated = translated
+        .untranslated
+        .iter()
+        .map(|untranslated| {
+            let binding = &untranslated.binding;
+            format!(
+                "`{}` for `{}`{}: {}",
+                binding.key,
+                binding.command,
+                binding
+                    .when
+                    .as_ref()
+                    .map(|when| format!(" when `{when}`"))
+                    .unwrap_or_default(),
+                untranslated.reason
+            )
+        })
+        .collect::<Vec<_>>();
+    for untranslated in &untranslated {
+        zlog::warn!("Skipped {source} keybinding {untranslated}");
+    }
+    let result = translated.write_to_user_keymap(fs).await;
+    zlog::info!(
+        "Imported {} {source} keybindings from {}",
+        translated.translated_count(),
+        vscode_keymap.path.display()
+    );
+
+    workspace
+        .update_in(cx, |workspace, _, cx| match result {
+            Ok(()) if translated.untranslated.is_empty() => {
+                let confirmation_toast = StatusToast::new(
+                    format!("Your {} keybindings were successfully imported.", source),
+                    cx,
+                    |this, _| {
+                        this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+                            .dismiss_button(true)
+                    },
+                );
+                workspace.toggle_status_toast(confirmation_toast, cx);
+            }
+            Ok(()) => {
+                let workspace_handle = cx.weak_entity();
+                let partial_toast = StatusToast::new(
+                    format!(
+                        "Imported {} {} keybindings, {} could not be translated",
+                        translated.translated_count(),
+                        source,
+                        translated.untranslated.len()
+                    ),
+                    cx,
+                    |this, _| {
+                        this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning))
+                            .action("Show Skipped", move |window, cx| {
+                                workspace_handle
+                                    .update(cx, |workspace, cx| {
+                                        open_untranslated_keybindings(
+                                            workspace,
+                                            source,
+                                            &untranslated,
+                                            window,
+                                            cx,
+                                        )
+                                    })
+                                    .ok();
+                            })
+                            .dismiss_button(true)
+                    },
+                );
+                workspace.toggle_status_toast(partial_toast, cx);
+            }
+            Err(err) => {
+                zlog::error!("{err:?}");
+                let error_toast = StatusToast::new(
+                    "Failed to import keybindings. See log for details",
+                    cx,
+                    |this, _| {
+                        this.icon(ToastIcon::new(IconName::Close).color(Color::Error))
+                            .action("Open Log", |window, cx| {
+                                window.dispatch_action(workspace::OpenLog.boxed_clone(), cx)
+                            })
+                            .dismiss_button(true)
+                    },
+                );
+                workspace.toggle_status_toast(error_toast, cx);
+            }
+        })
+        .ok();
+}
+
+/// Lists the keybindings that could not be imported in a new read-only buffer.
+fn open_untranslated_keybindings(
+    workspace: &mut Workspace,
+    source: VsCodeSettingsSource,
+    untranslated: &[String],
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let mut text =
+        format!("These {source} keybindings could not be translated into Zed keybindings:\n\n");
+    for untranslated in untranslated {
+        text.push_str("- ");
+        text.push_str(untranslated);
+        text.push('\n');
+    }
+    let project = workspace.project().clone();
+    let buffer = project.update(cx, |project, cx| project.create_buffer(None, false, cx));
+    cx.spawn_in(window, async move |workspace, cx| {
+        let buffer = buffer.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            buffer.update(cx, |buffer, cx| buffer.set_text(text, cx));
+            let title = format!("Skipped {source} Keybindings");
+            let multibuffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title));
+            workspace.add_item_to_active_pane(
+                Box::new(cx.new(|cx| {
+                    let mut editor = Editor::for_multibuffer(multibuffer, None, window, cx);
+                    editor.set_read_only(true);
+                    editor
+                })),
+                None,
+                true,
+                window,
+                cx,
+            );
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+pub async fn handle_import_jetbrains_settings(
+    workspace: WeakEntity<Workspace>,
+    skip_prompt: bool,
+    fs: Arc<dyn Fs>,
+    cx: &mut AsyncWindowContext,
+) {
+    use util::truncate_and_remove_front;
+
+    const MAX_PREVIEW_LINES: usize = 40;
+
+    let jetbrains_settings = match JetBrainsSettings::load_user_settings(fs.clone()).await {
+        Ok(jetbrains_settings) => jetbrains_settings,
+        Err(err) => {
+            zlog::info!("{err:?}");
+            // Fall back to an exported `settings.zip`, extracted by the user.
+            let Ok(paths) = cx.update(|_, cx| {
+                cx.prompt_for_paths(PathPromptOptions {
+                    files: false,
+                    directories: true,
+                    multiple: false,
+                    prompt: Some("Select JetBrains Settings Directory".into()),
+                })
+            }) else {
+                return;
+            };
+            let Some(path) = paths
+                .await
+                .ok()
+                .and_then(Result::ok)
+                .flatten()
+                .and_then(|paths| paths.into_iter().next())
+            else {
+                return;
+            };
+            match JetBrainsSettings::load(&path, fs.clone()).await {
+                Ok(jetbrains_settings) => jetbrains_settings,
+                Err(err) => {
+                    zlog::error!("{err:?}");
+                    let _ = cx.prompt(
+                        gpui::PromptLevel::Info,
+                        "Could not find or load JetBrains IDE settings",
+                        Some(format!("{err:#}").as_str()),
+                        &["Ok"],
+                    );
+                    return;
+                }
+            }
+        }
+    };
+
+    let import = jetbrains_settings.translate();
+    if !skip_prompt {
+        let mut preview = import.preview();
+        if preview.len() > MAX_PREVIEW_LINES {
+            let more = preview.len() - MAX_PREVIEW_LINES;
+            preview.truncate(MAX_PREVIEW_LINES);
+            preview.push(format!("…and {more} more"));
+        }
+        let prompt = cx.prompt(
+            gpui::PromptLevel::Warning,
+            &format!(
+                "Importing JetBrains settings from {} will make the following changes",
+                truncate_and_remove_front(&jetbrains_settings.path.to_string_lossy(), 128),
+            ),
+            Some(preview.join("\n").as_str()),
+            &["Import", "Cancel"],
+        );
+        let result = cx.spawn(async move |_| prompt.await.ok()).await;
+        if result != Some(0) {
+            return;
+        }
+    }
+
+    for skipped in &import.skipped {
+        zlog::warn!("Skipped JetBrains setting: {skipped}");
+    }
+    let Ok(settings_result) = cx.update(|_, cx| {
+        cx.global::<SettingsStore>()
+            .import_settings_content(fs.clone(), import.settings.clone())
+    }) else {
+        return;
+    };
+    let result = match settings_result.await {
+        Ok(Ok(())) => import.keymap.write_to_user_keymap(fs).await,
+        Ok(Err(err)) => Err(err),
+        Err(err) => Err(err.into()),
+    };
+    zlog::info!(
+        "Imported JetBrains settings and {} keybindings from {}",
+        import.keymap.translated_count(),
+        jetbrains_settings.path.display()
+    );
+
+    workspace
+        .update_in(cx, |workspace, _, cx| match result {
+            Ok(()) if import.skipped.is_empty() => {
+                let confirmation_toast = StatusToast::new(
+                    "Your JetBrains settings were successfully imported.",
+                    cx,
+                    |this, _| {
+                        this.icon(ToastIcon::new(IconName::Check).color(Color::Success))
+                            .dismiss_button(true)
+                    },
+                );
+                SettingsImportState::update(cx, |state, _| state.jetbrains = true);
+                workspace.toggle_status_toast(confirmation_toast, cx);
+            }
+            Ok(()) => {
+                let partial_toast = StatusToast::new(
+                    format!(
+                        "Imported JetBrains settings, {} could not be translated",
+                        import.skipped.len()
+                    ),
+                    cx,
+                    |this, _| {
+                        this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning))
+                            .action("Open Log", |window, cx| {
+                                window.dispatch_action(workspace::OpenLog.boxed_clone(), cx)
+                            })
+                            .dismiss_button(true)
+                    },
+                );
+                SettingsImportState::update(cx, |state, _| state.jetbrains = true);
+                workspace.toggle_status_toast(partial_toast, cx);
+            }
+            Err(err) => {
+                zlog::error!("{err:?}");
+                let error_toast = StatusToast::new(
+                    "Failed to import settings. See log for details",
+                    cx,
+                    |this, _| {
+                        this.icon(ToastIcon::new(IconName::Close).color(Color::Error))
+                            .action("Open Log", |window, cx| {
+                                window.dispatch_action(workspace::OpenLog.boxed_clone(), cx)
+                            })
+                            .dismiss_button(true)
+                    },
+                );
+                workspace.toggle_status_toast(error_toast, cx);
+            }
+        })
+        .ok();
+}
+
 #[derive(Default, Copy, Clone)]
 pub struct SettingsImportState {
     pub cursor: bool,
     pub vscode: bool,
+    pub jetbrains: bool,
 }
 
 impl Global for SettingsImportState {}
@@ -197,6 +197,12 @@ pub fn database_dir() -> &'static PathBuf {
     DATABASE_DIR.get_or_init(|| data_dir().join("db"))
 }
 
+/// Returns the path to the local history directory, where snapshots of saved files are kept.
+pub fn local_history_dir() -> &'static PathBuf {
+    static LOCAL_HISTORY_DIR: OnceLock<PathBuf> = OnceLock::new();
+    LOCAL_HISTORY_DIR.get_or_init(|| data_dir().join("local_history"))
+}
+
 /// Returns the path to the crashes directory, if it exists for the current platform.
 pub fn crashes_dir() -> &'static Option<PathBuf> {
     static CRASHES_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
@@ -291,6 +297,18 @@ pub fn snippets_dir() -> &'static PathBuf {
     SNIPPETS_DIR.get_or_init(|| config_dir().join("snippets"))
 }
 
+/// Returns the path to the directory spell check dictionaries are loaded from.
+pub fn dictionaries_dir() -> &'static PathBuf {
+    static DICTIONARIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DICTIONARIES_DIR.get_or_init(|| config_dir().join("dictionaries"))
+}
+
+/// Returns the path to the user's spell check dictionary, which lists one word per line.
+pub fn user_dictionary_file() -> &'static PathBuf {
+    static USER_DICTIONARY_FILE: OnceLock<PathBuf> = OnceLock::new();
+    USER_DICTIONARY_FILE.get_or_init(|| config_dir().join("dictionary.txt"))
+}
+
 /// Returns the path to the contexts directory.
 ///
 /// This is where the saved contexts from the Assistant are stored.
@@ -438,6 +456,13 @@ pub fn local_tasks_file_relative_path() -> &'static RelPath {
     *CACHED
 }
 
+/// Returns the relative path to a project's spell check dictionary.
+pub fn local_dictionary_file_relative_path() -> &'static RelPath {
+    static CACHED: LazyLock<&'static RelPath> =
+        LazyLock::new(|| RelPath::unix(".zed/dictionary.txt").unwrap());
+    *CACHED
+}
+
 /// Returns the relative path to a `.vscode/tasks.json` file within a project.
 pub fn local_vscode_tasks_file_relative_path() -> &'static RelPath {
     static CACHED: LazyLock<&'static RelPath> =
@@ -498,6 +523,40 @@ pub fn cursor_settings_file_paths() -> Vec<PathBuf> {
     paths
 }
 
+/// Returns candidate paths for the vscode user keybindings file
+pub fn vscode_keybindings_file_paths() -> Vec<PathBuf> {
+    let mut paths = vscode_user_data_paths();
+    for path in paths.iter_mut() {
+        path.push("User/keybindings.json");
+    }
+    paths
+}
+
+/// Returns candidate paths for the cursor user keybindings file
+pub fn cursor_keybindings_file_paths() -> Vec<PathBuf> {
+    let mut paths = cursor_user_data_paths();
+    for path in paths.iter_mut() {
+        path.push("User/keybindings.json");
+    }
+    paths
+}
+
+/// Returns the directory containing the configuration directories of JetBrains IDEs,
+/// which has one subdirectory per installed product and version (e.g. `IntelliJIdea2024.3`).
+pub fn jetbrains_config_dir() -> PathBuf {
+    if cfg!(target_os = "macos") {
+        home_dir().join("Library/Application Support/JetBrains")
+    } else if cfg!(target_os = "windows") {
+        dirs::data_dir()
+            .unwrap_or(home_dir().join("AppData").join("Roaming"))
+            .join("JetBrains")
+    } else {
+        dirs::config_dir()
+            .unwrap_or(home_dir().join(".config"))
+            .join("JetBrains")
+    }
+}
+
 fn vscode_user_data_paths() -> Vec<PathBuf> {
     // https://github.com/microsoft/vscode/blob/23e7148cdb6d8a27f0109ff77e5b1e019f8da051/src/vs/platform/environment/node/userDataPath.ts#L45
     const VSCODE_PRODUCT_NAMES: &[&str] = &[
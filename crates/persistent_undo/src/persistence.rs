@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use db::{
+    query,
+    sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection},
+    sqlez_macros::sql,
+};
+
+pub struct PersistentUndoDb(ThreadSafeConnection);
+
+impl Domain for PersistentUndoDb {
+    const NAME: &str = stringify!(PersistentUndoDb);
+
+    const MIGRATIONS: &[&str] = &[sql!(
+        CREATE TABLE undo_histories (
+            path BLOB NOT NULL PRIMARY KEY,
+            content_hash TEXT NOT NULL,
+            history BLOB NOT NULL,
+            used_at INTEGER DEFAULT (unixepoch()) NOT NULL
+        ) STRICT;
+    )];
+}
+
+db::static_connection!(DB, PersistentUndoDb, []);
+
+impl PersistentUndoDb {
+    // Returns the hash of the content that the file's history belongs to, and the history.
+    query! {
+        pub(crate) fn get_undo_history(path: &Path) -> Result<Option<(String, Vec<u8>)>> {
+            SELECT content_hash, history FROM undo_histories
+            WHERE path = ?
+        }
+    }
+
+    query! {
+        pub(crate) async fn save_undo_history(path: PathBuf, content_hash: String, history: Vec<u8>) -> Result<()> {
+            INSERT INTO undo_histories
+                (path, content_hash, history, used_at)
+            VALUES
+                (?1, ?2, ?3, unixepoch())
+            ON CONFLICT DO UPDATE SET
+                content_hash = ?2,
+                history = ?3,
+                used_at = unixepoch()
+        }
+    }
+
+    query! {
+        pub(crate) async fn touch_undo_history(path: PathBuf) -> Result<()> {
+            UPDATE undo_histories SET used_at = unixepoch()
+            WHERE path = ?
+        }
+    }
+
+    query! {
+        pub(crate) async fn delete_undo_history(path: PathBuf) -> Result<()> {
+            DELETE FROM undo_histories
+            WHERE path = ?
+        }
+    }
+
+    // Removes the histories that weren't used for the given number of seconds, and the
+    // least recently used ones beyond the given count.
+    query! {
+        pub(crate) async fn prune_undo_histories(max_age_seconds: i64, max_count: usize) -> Result<()> {
+            DELETE FROM undo_histories
+            WHERE used_at < unixepoch() - ?1
+            OR path NOT IN (
+                SELECT path FROM undo_histories
+                ORDER BY used_at DESC
+                LIMIT ?2
+            )
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Keeps the undo history of files after their buffers are released, as Vim's `undofile`
+//! does. The history is stored in the database when a file is saved or reloaded, as plain
+//! text edits, together with a hash of the content it belongs to. When the file is opened
+//! again and its content has the same hash, the history is restored into the new buffer.
+
+mod persistence;
+
+use std::{ops::Range, time::Duration};
+
+use anyhow::{Context as _, Result};
+use gpui::{App, AppContext as _, Context, Entity};
+use language::Buffer;
+use project::{
+    Project,
+    local_files::{self, LocalFileEvent, hash_text, local_abs_path},
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use text::{PlainEdits, PlainHistory};
+use util::ResultExt as _;
+use workspace::WorkspaceSettings;
+
+use crate::persistence::DB;
+
+/// Once the edits of a file's history contain more text than this, its oldest transactions
+/// are dropped before storing it.
+const MAX_HISTORY_TEXT_LEN: usize = 4 * 1024 * 1024;
+
+/// The number of files whose history is kept, dropping the least recently used ones.
+const MAX_HISTORIES: usize = 1000;
+
+/// Histories of files that weren't saved or opened for this long are dropped.
+const MAX_HISTORY_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+const COMPRESSION_LEVEL: i32 = 3;
+
+pub fn init(cx: &mut App) {
+    local_files::observe_local_files(cx, |buffer, event, cx| match event {
+        LocalFileEvent::Opened => restore_history(buffer, cx),
+        LocalFileEvent::Saved | LocalFileEvent::Reloaded => store_history(buffer, cx),
+    });
+}
+
+fn is_enabled(cx: &App) -> bool {
+    WorkspaceSettings::get_global(cx).persistent_undo
+}
+
+/// Restores the stored history of a newly opened buffer if it belongs to the buffer's content.
+fn restore_history(buffer: &Entity<Buffer>, cx: &mut Context<Project>) {
+    // The restored transactions aren't sent to collaborators.
+    if !is_enabled(cx) || cx.entity().read(cx).is_shared() {
+        return;
+    }
+    let buffer_ref = buffer.read(cx);
+    if buffer_ref.is_dirty() || buffer_ref.peek_undo_stack().is_some() {
+        return;
+    }
+    let Some(path) = local_abs_path(buffer_ref, cx) else {
+        return;
+    };
+    let version = buffer_ref.version();
+    let text = buffer_ref.as_rope().clone();
+
+    let buffer = buffer.downgrade();
+    cx.spawn(async move |project, cx| {
+        let history = cx
+            .background_spawn({
+                let path = path.clone();
+                async move {
+                    let Some((content_hash, history)) = DB.get_undo_history(&path)? else {
+                        return anyhow::Ok(None);
+                    };
+                    if content_hash != hash_text(&text) {
+                        return Ok(None);
+                    }
+                    Ok(Some(deserialize_history(&history)?))
+                }
+            })
+            .await
+            .with_context(|| format!("loading undo history of {path:?}"))
+            .log_err()
+            .flatten()?;
+
+        if project
+            .read_with(cx, |project, _| project.is_shared())
+            .ok()?
+        {
+            return None;
+        }
+        let restored = buffer
+            .update(cx, |buffer, cx| {
+                // Edits made while the history was loaded would be undone by the
+                // restored transactions.
+                if buffer.version() != version || buffer.peek_undo_stack().is_some() {
+                    return false;
+                }
+                buffer
+                    .restore_plain_history(&history, cx)
+                    .with_context(|| format!("restoring undo history of {path:?}"))
+                    .log_err()
+                    .is_some()
+            })
+            .ok()?;
+        if restored {
+            DB.touch_undo_history(path).await.log_err();
+        }
+        Some(())
+    })
+    .detach();
+}
+
+/// Stores the history of a buffer whose content was just written to or read from disk.
+fn store_history(buffer: &Entity<Buffer>, cx: &mut Context<Project>) {
+    if !is_enabled(cx) {
+        return;
+    }
+    let buffer = buffer.read(cx);
+    if buffer.is_dirty() {
+        return;
+    }
+    let Some(path) = local_abs_path(buffer, cx) else {
+        return;
+    };
+    // Getting the plain history undoes and redoes every transaction, so it's done on a copy
+    // of the buffer in the background.
+    let text_buffer = buffer.clone_with_history();
+
+    cx.background_spawn(async move {
+        let history = text_buffer.plain_history();
+        if history.is_empty() {
+            return DB.delete_undo_history(path).await;
+        }
+        let history = serialize_history(truncate_history(history))?;
+        DB.save_undo_history(path, hash_text(text_buffer.as_rope()), history)
+            .await?;
+        DB.prune_undo_histories(MAX_HISTORY_AGE.as_secs() as i64, MAX_HISTORIES)
+            .await
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Drops transactions until the history's text fits into [`MAX_HISTORY_TEXT_LEN`], starting
+/// with the redo stack, as undone edits are less likely to be needed, and then with the
+/// oldest transactions.
+fn truncate_history(mut history: PlainHistory) -> PlainHistory {
+    let mut text_len = history.text_len();
+    while text_len > MAX_HISTORY_TEXT_LEN {
+        let Some(edits) = history.redo.pop().or_else(|| history.undo.pop()) else {
+            break;
+        };
+        text_len -= edits.iter().map(|(_, text)| text.len()).sum::<usize>();
+    }
+    history
+}
+
+type SerializedEdits = Vec<(Range<usize>, String)>;
+
+#[derive(Serialize, Deserialize)]
+struct SerializedHistory {
+    undo: Vec<SerializedEdits>,
+    redo: Vec<SerializedEdits>,
+}
+
+fn serialize_history(history: PlainHistory) -> Result<Vec<u8>> {
+    fn serialize_transactions(transactions: Vec<PlainEdits>) -> Vec<SerializedEdits> {
+        transactions
+            .into_iter()
+            .map(|edits| {
+                edits
+                    .into_iter()
+                    .map(|(range, text)| (range, text.to_string()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    let json = serde_json::to_vec(&SerializedHistory {
+        undo: serialize_transactions(history.undo),
+        redo: serialize_transactions(history.redo),
+    })?;
+    Ok(zstd::encode_all(json.as_slice(), COMPRESSION_LEVEL)?)
+}
+
+fn deserialize_history(data: &[u8]) -> Result<PlainHistory> {
+    fn deserialize_transactions(transactions: Vec<SerializedEdits>) -> Vec<PlainEdits> {
+        transactions
+            .into_iter()
+            .map(|edits| {
+                edits
+                    .into_iter()
+                    .map(|(range, text)| (range, text.into()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    let json = zstd::decode_all(data)?;
+    let history: SerializedHistory = serde_json::from_slice(&json)?;
+    Ok(PlainHistory {
+        undo: deserialize_transactions(history.undo),
+        redo: deserialize_transactions(history.redo),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_serialize_history() {
+        let history = PlainHistory {
+            undo: vec![
+                vec![(0..3, Arc::from("one")), (5..5, Arc::from(""))],
+                vec![(2..2, Arc::from("two\nthree"))],
+            ],
+            redo: vec![vec![(0..0, Arc::from("four"))]],
+        };
+        let data = serialize_history(history.clone()).unwrap();
+        assert_eq!(deserialize_history(&data).unwrap(), history);
+        assert!(deserialize_history(b"not a history").is_err());
+    }
+
+    #[test]
+    fn test_truncate_history() {
+        let large_text = Arc::<str>::from("a".repeat(MAX_HISTORY_TEXT_LEN / 2));
+        let history = PlainHistory {
+            undo: vec![
+                vec![(0..0, Arc::from("newest"))],
+                vec![(0..0, large_text.clone())],
+                vec![(0..0, large_text.clone())],
+            ],
+            redo: vec![
+                vec![(0..0, Arc::from("next"))],
+                vec![(0..0, Arc::from("last"))],
+            ],
+        };
+        let truncated = truncate_history(history);
+        assert_eq!(
+            truncated,
+            PlainHistory {
+                undo: vec![vec![(0..0, Arc::from("newest"))], vec![(0..0, large_text)],],
+                redo: Vec::new(),
+            }
+        );
+    }
+}
@@ -110,6 +110,7 @@ impl DapLocator for CargoLocator {
             }),
             config,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
@@ -187,6 +187,7 @@ impl DapLocator for GoLocator {
                     build: None,
                     config,
                     tcp_connection: None,
+                    inputs: Vec::new(),
                 })
             }
             "run" => {
@@ -231,6 +232,7 @@ impl DapLocator for GoLocator {
                     build: None,
                     config,
                     tcp_connection: None,
+                    inputs: Vec::new(),
                 })
             }
             _ => None,
@@ -270,6 +272,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
+            inputs: vec![],
         };
 
         let scenario = locator
@@ -297,6 +303,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
+            inputs: vec![],
         };
 
         let scenario = locator
@@ -435,6 +445,10 @@ mod tests {
             tags: vec![],
             show_summary: true,
             show_command: true,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
+            inputs: vec![],
         };
 
         let scenario = locator
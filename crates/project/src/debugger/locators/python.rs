@@ -87,6 +87,7 @@ impl DapLocator for PythonLocator {
             build: None,
             config,
             tcp_connection: None,
+            inputs: Vec::new(),
         })
     }
 
@@ -119,6 +120,10 @@ mod test {
             shell: task::Shell::System,
             show_summary: false,
             show_command: false,
+            depends_on: vec![],
+            depends_order: Default::default(),
+            problem_matchers: vec![],
+            inputs: vec![],
         };
 
         let expected_scenario = DebugScenario {
@@ -133,6 +138,7 @@ mod test {
                 "module": "$ZED_CUSTOM_PYTHON_MODULE_NAME",
             }),
             tcp_connection: None,
+            inputs: Vec::new(),
         };
 
         assert_eq!(
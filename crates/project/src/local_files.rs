@@ -0,0 +1,68 @@
+//! Helpers for features that keep data about local files across sessions, such as their undo
+//! history or earlier revisions, and need to know when a file's content is read or written.
+
+use std::{path::PathBuf, rc::Rc};
+
+use gpui::{App, Context, Entity};
+use language::{Buffer, BufferEvent};
+use sha2::{Digest as _, Sha256};
+use text::Rope;
+
+use crate::{Project, buffer_store::BufferStoreEvent};
+
+/// When the content of a local file's buffer matches the file on disk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalFileEvent {
+    /// The buffer was opened with the file's content.
+    Opened,
+    /// The buffer was saved to the file.
+    Saved,
+    /// The buffer was reloaded after the file changed on disk.
+    Reloaded,
+}
+
+/// Calls `on_event` whenever a buffer of a local project matches the content of its file on
+/// disk, from the time it is opened.
+pub fn observe_local_files(
+    cx: &mut App,
+    on_event: impl Fn(&Entity<Buffer>, LocalFileEvent, &mut Context<Project>) + 'static,
+) {
+    let on_event = Rc::new(on_event);
+    cx.observe_new(move |project: &mut Project, _, cx| {
+        if !project.is_local() {
+            return;
+        }
+        let buffer_store = project.buffer_store().clone();
+        let on_event = on_event.clone();
+        cx.subscribe(&buffer_store, move |_, _, event, cx| {
+            let BufferStoreEvent::BufferAdded(buffer) = event else {
+                return;
+            };
+            let on_buffer_event = on_event.clone();
+            cx.subscribe(buffer, move |_, buffer, event, cx| match event {
+                BufferEvent::Saved => on_buffer_event(&buffer, LocalFileEvent::Saved, cx),
+                BufferEvent::Reloaded => on_buffer_event(&buffer, LocalFileEvent::Reloaded, cx),
+                _ => {}
+            })
+            .detach();
+            on_event(buffer, LocalFileEvent::Opened, cx);
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Returns the absolute path of the buffer's file, if it is a local file.
+pub fn local_abs_path(buffer: &Buffer, cx: &App) -> Option<PathBuf> {
+    Some(buffer.file()?.as_local()?.abs_path(cx))
+}
+
+/// Returns a hash identifying the text, such as to tell whether a file still has the content
+/// that data about it was stored for.
+pub fn hash_text(text: &Rope) -> String {
+    let mut hasher = Sha256::new();
+    for chunk in text.chunks() {
+        hasher.update(chunk.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
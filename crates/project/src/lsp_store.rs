@@ -276,6 +276,12 @@ pub struct LocalLspStore {
         HashMap<LanguageServerId, (LanguageServerName, Arc<LanguageServer>)>,
     prettier_store: Entity<PrettierStore>,
     next_diagnostic_group_id: usize,
+    /// The id under which diagnostics found by tasks' problem matchers are stored. It is taken
+    /// from the same sequence as language servers' ids, so it never belongs to a language server.
+    task_diagnostics_source: Option<LanguageServerId>,
+    /// Diagnostics found by tasks' problem matchers, by task label and absolute path.
+    task_diagnostics:
+        HashMap<String, HashMap<PathBuf, Vec<DiagnosticEntry<Unclipped<PointUtf16>>>>>,
     diagnostics: HashMap<
         WorktreeId,
         HashMap<
@@ -4061,6 +4067,8 @@ impl LspStore {
                 fs,
                 yarn,
                 next_diagnostic_group_id: Default::default(),
+                task_diagnostics_source: None,
+                task_diagnostics: Default::default(),
                 diagnostics: Default::default(),
                 _subscription: cx.on_app_quit(|this, cx| {
                     this.as_local_mut()
@@ -8592,6 +8600,122 @@ impl LspStore {
         Ok(())
     }
 
+    /// Replaces the task's diagnostics with the problems its problem matchers found in the output of its current run.
+    /// Relative problem paths are resolved against `cwd`, and problems in files outside of the project are skipped.
+    pub fn update_task_diagnostics(
+        &mut self,
+        task_label: &str,
+        cwd: Option<&Path>,
+        problems: Vec<task::Problem>,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let worktree_store = self.worktree_store.clone();
+        let local = self
+            .as_local_mut()
+            .context("cannot update task diagnostics on a remote LspStore")?;
+        let languages = local.languages.clone();
+        let source_id = *local
+            .task_diagnostics_source
+            .get_or_insert_with(|| languages.next_language_server_id());
+
+        let mut diagnostics_by_path = HashMap::<PathBuf, Vec<_>>::default();
+        for problem in problems {
+            let path = Path::new(&problem.file);
+            let abs_path = match cwd {
+                Some(cwd) if path.is_relative() => cwd.join(path),
+                _ => path.to_path_buf(),
+            };
+            if worktree_store
+                .read(cx)
+                .find_worktree(&abs_path, cx)
+                .is_none()
+            {
+                continue;
+            }
+
+            let row = problem.line.saturating_sub(1);
+            let start = PointUtf16::new(
+                row,
+                problem.column.map_or(0, |column| column.saturating_sub(1)),
+            );
+            let end = match (problem.end_line, problem.end_column) {
+                (end_line, Some(end_column)) => PointUtf16::new(
+                    end_line.map_or(row, |end_line| end_line.saturating_sub(1)),
+                    end_column.saturating_sub(1),
+                ),
+                (Some(end_line), None) => PointUtf16::new(end_line.saturating_sub(1), u32::MAX),
+                // Highlight the whole line when the problem has no column.
+                (None, None) if problem.column.is_none() => PointUtf16::new(row, u32::MAX),
+                (None, None) => start,
+            };
+            let severity = match problem.severity {
+                task::ProblemSeverity::Error => DiagnosticSeverity::ERROR,
+                task::ProblemSeverity::Warning => DiagnosticSeverity::WARNING,
+                task::ProblemSeverity::Info => DiagnosticSeverity::INFORMATION,
+                task::ProblemSeverity::Hint => DiagnosticSeverity::HINT,
+            };
+            diagnostics_by_path
+                .entry(abs_path)
+                .or_default()
+                .push(DiagnosticEntry {
+                    range: Unclipped(start)..Unclipped(end),
+                    diagnostic: Diagnostic {
+                        source: problem.source,
+                        code: problem.code.map(lsp::NumberOrString::String),
+                        severity,
+                        message: problem.message,
+                        group_id: post_inc(&mut local.next_diagnostic_group_id),
+                        is_primary: true,
+                        // Tasks report problems in the files on disk, so adjust them for unsaved edits.
+                        is_disk_based: true,
+                        source_kind: DiagnosticSourceKind::Other,
+                        ..Diagnostic::default()
+                    },
+                });
+        }
+
+        let previous_paths = local
+            .task_diagnostics
+            .remove(task_label)
+            .map(|diagnostics| diagnostics.into_keys().collect::<Vec<_>>())
+            .unwrap_or_default();
+        let updated_paths = previous_paths
+            .into_iter()
+            .chain(diagnostics_by_path.keys().cloned())
+            .collect::<BTreeSet<_>>();
+        if !diagnostics_by_path.is_empty() {
+            local
+                .task_diagnostics
+                .insert(task_label.to_string(), diagnostics_by_path);
+        }
+
+        // All tasks share a diagnostics source, so each path gets the diagnostics of every task that reported problems in it.
+        let updates = updated_paths
+            .into_iter()
+            .map(|abs_path| {
+                let diagnostics = local
+                    .task_diagnostics
+                    .values()
+                    .filter_map(|diagnostics_by_path| diagnostics_by_path.get(&abs_path))
+                    .flatten()
+                    .cloned()
+                    .collect();
+                DocumentDiagnosticsUpdate {
+                    diagnostics: DocumentDiagnostics {
+                        diagnostics,
+                        document_abs_path: abs_path,
+                        version: None,
+                    },
+                    result_id: None,
+                    registration_id: None,
+                    server_id: source_id,
+                    disk_based_sources: Cow::Borrowed(&[]),
+                }
+            })
+            .collect();
+        self.merge_diagnostic_entries(updates, |_, _, _| false, cx)
+    }
+
     fn update_worktree_diagnostics(
         &mut self,
         worktree_id: WorktreeId,
@@ -7,6 +7,7 @@ pub mod debounced_delay;
 pub mod debugger;
 pub mod git_store;
 pub mod image_store;
+pub mod local_files;
 pub mod lsp_command;
 pub mod lsp_store;
 mod manifest_tree;
@@ -148,7 +149,7 @@ pub use language::Location;
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, DebugScenarioContext, Inventory, TaskContexts,
-    TaskSourceKind,
+    TaskDependencies, TaskDependency, TaskSourceKind,
 };
 
 pub use buffer_store::ProjectTransaction;
@@ -26,7 +26,9 @@ use language::{
     DiskState, FakeLspAdapter, LanguageConfig, LanguageMatcher, LanguageName, LineEnding,
     ManifestName, ManifestProvider, ManifestQuery, OffsetRangeExt, Point, ToPoint, ToolchainList,
     ToolchainLister,
-    language_settings::{LanguageSettingsContent, language_settings},
+    language_settings::{
+        LanguageSettingsContent, Ruler, RulerContent, SoftWrap, language_settings,
+    },
     markdown_lang, rust_lang, tree_sitter_typescript,
 };
 use lsp::{
@@ -239,11 +241,94 @@ async fn test_editorconfig_support(cx: &mut gpui::TestAppContext) {
         assert_eq!(settings_b.preferred_line_length, 64);
         assert_eq!(settings_c.preferred_line_length, 64);
 
+        // max_line_length also adds a ruler
+        assert_eq!(
+            settings_a.rulers,
+            [Ruler {
+                column: 120,
+                color: None
+            }]
+        );
+        assert!(settings_b.rulers.is_empty());
+
         // README.md should not be affected by .editorconfig's globe "*.rs"
         assert_eq!(Some(settings_readme.tab_size), NonZeroU32::new(8));
     });
 }
 
+#[gpui::test]
+async fn test_editorconfig_ruler(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".editorconfig": "[*.rs]\nmax_line_length = 100\n",
+            "a.rs": "fn a() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let worktree = project.update(cx, |project, cx| project.worktrees(cx).next().unwrap());
+    cx.executor().run_until_parked();
+
+    let rulers = |cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            let file_entry = worktree
+                .read(cx)
+                .entry_for_path(rel_path("a.rs"))
+                .unwrap()
+                .clone();
+            let file = File::for_entry(file_entry, worktree.clone()) as _;
+            language_settings(Some("Rust".into()), Some(&file), cx)
+                .rulers
+                .iter()
+                .map(|ruler| ruler.column)
+                .collect::<Vec<_>>()
+        })
+    };
+    fn update_settings(cx: &mut gpui::TestAppContext, update: fn(&mut LanguageSettingsContent)) {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |settings, cx| {
+                settings.update_user_settings(cx, |settings| {
+                    update(&mut settings.project.all_languages.defaults);
+                });
+            })
+        });
+    }
+
+    assert_eq!(rulers(cx), [100]);
+
+    // No ruler is added where a ruler or a wrap guide is already shown.
+    update_settings(cx, |language| {
+        language.rulers = Some(vec![RulerContent::Column(100)]);
+    });
+    assert_eq!(rulers(cx), [100]);
+    update_settings(cx, |language| {
+        language.rulers = None;
+        language.wrap_guides = Some(vec![100]);
+    });
+    assert!(rulers(cx).is_empty());
+    update_settings(cx, |language| {
+        language.wrap_guides = None;
+        language.soft_wrap = Some(SoftWrap::PreferredLineLength);
+    });
+    assert!(rulers(cx).is_empty());
+    update_settings(cx, |language| {
+        language.show_wrap_guides = Some(false);
+    });
+    assert_eq!(rulers(cx), [100]);
+
+    update_settings(cx, |language| {
+        language.show_editorconfig_ruler = Some(false);
+    });
+    assert!(rulers(cx).is_empty());
+}
+
 #[gpui::test]
 async fn test_external_editorconfig_support(cx: &mut gpui::TestAppContext) {
     init_test(cx);
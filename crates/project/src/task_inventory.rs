@@ -8,7 +8,7 @@ use std::{
     sync::Arc,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use collections::{HashMap, HashSet, VecDeque};
 use dap::DapRegistry;
 use gpui::{App, AppContext as _, Context, Entity, SharedString, Task, WeakEntity};
@@ -21,8 +21,8 @@ use lsp::{LanguageServerId, LanguageServerName};
 use paths::{debug_task_file_name, task_file_name};
 use settings::{InvalidSettingsError, parse_json_with_comments};
 use task::{
-    DebugScenario, ResolvedTask, TaskContext, TaskId, TaskTemplate, TaskTemplates, TaskVariables,
-    VariableName,
+    DebugScenario, DependsOrder, ResolvedTask, TaskContext, TaskId, TaskInput, TaskTemplate,
+    TaskTemplates, TaskVariables, VariableName,
 };
 use text::{BufferId, Point, ToPoint};
 use util::{NumericPrefixWithSuffix, ResultExt as _, post_inc, rel_path::RelPath};
@@ -43,6 +43,8 @@ pub struct Inventory {
     last_scheduled_scenarios: VecDeque<(DebugScenario, DebugScenarioContext)>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    /// Values entered for task inputs the last time, by input id.
+    last_input_values: HashMap<String, String>,
 }
 
 impl std::fmt::Debug for Inventory {
@@ -52,6 +54,7 @@ impl std::fmt::Debug for Inventory {
             .field("last_scheduled_scenarios", &self.last_scheduled_scenarios)
             .field("templates_from_settings", &self.templates_from_settings)
             .field("scenarios_from_settings", &self.scenarios_from_settings)
+            .field("last_input_values", &self.last_input_values)
             .finish()
     }
 }
@@ -130,6 +133,59 @@ impl<T> Default for InventoryFor<T> {
     }
 }
 
+/// Tasks that have to finish successfully before a task is spawned.
+#[derive(Debug, Clone, Default)]
+pub struct TaskDependencies {
+    /// Whether to run the `tasks` all at once or one after another.
+    pub order: DependsOrder,
+    pub tasks: Vec<TaskDependency>,
+}
+
+impl TaskDependencies {
+    /// Inputs referenced by the dependencies and their own dependencies, without duplicates.
+    pub fn inputs(&self) -> Vec<TaskInput> {
+        let mut inputs = Vec::<TaskInput>::new();
+        for dependency in &self.tasks {
+            for input in dependency
+                .task
+                .inputs()
+                .into_iter()
+                .chain(dependency.dependencies.inputs())
+            {
+                if !inputs.iter().any(|known_input| known_input.id == input.id) {
+                    inputs.push(input);
+                }
+            }
+        }
+        inputs
+    }
+
+    /// Returns the dependencies with their `${input:id}` references replaced with the entered values.
+    pub fn with_input_values(self, values: &HashMap<String, String>) -> Self {
+        Self {
+            order: self.order,
+            tasks: self
+                .tasks
+                .into_iter()
+                .map(|dependency| TaskDependency {
+                    source_kind: dependency.source_kind,
+                    task: dependency.task.with_input_values(values),
+                    dependencies: dependency.dependencies.with_input_values(values),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A resolved task from another task's [`TaskTemplate::depends_on`].
+#[derive(Debug, Clone)]
+pub struct TaskDependency {
+    pub source_kind: TaskSourceKind,
+    pub task: ResolvedTask,
+    /// Tasks that have to finish successfully before this dependency is spawned.
+    pub dependencies: TaskDependencies,
+}
+
 /// Kind of a source the tasks are fetched from, used to display more source information in the UI.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum TaskSourceKind {
@@ -246,6 +302,7 @@ impl Inventory {
             last_scheduled_scenarios: VecDeque::default(),
             templates_from_settings: InventoryFor::default(),
             scenarios_from_settings: InventoryFor::default(),
+            last_input_values: HashMap::default(),
         })
     }
 
@@ -373,6 +430,29 @@ impl Inventory {
         })
     }
 
+    /// Resolves the tasks that the given task depends on, recursively, with the context the task was resolved with.
+    /// Dependencies are looked up by their labels among the worktree's tasks first, the language's tasks second,
+    /// and the global tasks last.
+    ///
+    /// Fails if a dependency does not exist or cannot be resolved, or if the dependencies form a cycle.
+    pub fn resolve_dependencies(
+        &self,
+        task: &ResolvedTask,
+        file: Option<Arc<dyn File>>,
+        language: Option<Arc<Language>>,
+        worktree: Option<WorktreeId>,
+        cx: &App,
+    ) -> Task<Result<TaskDependencies>> {
+        let templates = self.list_tasks(file, language, worktree, cx);
+        let template = task.original_task().clone();
+        let task_context = task.task_context().clone();
+        cx.background_spawn(async move {
+            let templates = templates.await;
+            let mut dependency_chain = vec![template.label.clone()];
+            resolve_dependencies(&template, &task_context, &templates, &mut dependency_chain)
+        })
+    }
+
     /// Pulls its task sources relevant to the worktree and the language given,
     /// returns all task templates with their source kinds, worktree tasks first, language tasks second
     /// and global tasks last. No specific order inside source kinds groups.
@@ -603,6 +683,16 @@ impl Inventory {
         }
     }
 
+    /// The value entered for the task input with the given id the last time.
+    pub fn last_input_value(&self, input_id: &str) -> Option<&str> {
+        self.last_input_values.get(input_id).map(String::as_str)
+    }
+
+    /// Remembers the values entered for task inputs, to propose them when the inputs are asked for again.
+    pub fn input_values_entered(&mut self, values: HashMap<String, String>) {
+        self.last_input_values.extend(values);
+    }
+
     /// Deletes a resolved task from history, using its id.
     /// A similar may still resurface in `used_and_current_resolved_tasks` when its [`TaskTemplate`] is resolved again.
     pub fn delete_previously_used(&mut self, id: &TaskId) {
@@ -802,6 +892,55 @@ impl Inventory {
     }
 }
 
+fn resolve_dependencies(
+    template: &TaskTemplate,
+    task_context: &TaskContext,
+    templates: &[(TaskSourceKind, TaskTemplate)],
+    dependency_chain: &mut Vec<String>,
+) -> Result<TaskDependencies> {
+    let mut tasks = Vec::with_capacity(template.depends_on.len());
+    for label in &template.depends_on {
+        if dependency_chain.contains(label) {
+            anyhow::bail!(
+                "Task dependency cycle: {} → {label}",
+                dependency_chain.join(" → ")
+            );
+        }
+        let (source_kind, dependency) = templates
+            .iter()
+            .find(|(_, template)| &template.label == label)
+            .with_context(|| {
+                format!(
+                    "Task `{}` depends on unknown task `{label}`",
+                    template.label
+                )
+            })?;
+        let task = dependency
+            .resolve_task(&source_kind.to_id_base(), task_context)
+            .with_context(|| {
+                format!(
+                    "Failed to resolve task `{label}`, a dependency of `{}`",
+                    template.label
+                )
+            })?;
+
+        dependency_chain.push(label.clone());
+        let dependencies =
+            resolve_dependencies(dependency, task_context, templates, dependency_chain)?;
+        dependency_chain.pop();
+
+        tasks.push(TaskDependency {
+            source_kind: source_kind.clone(),
+            task,
+            dependencies,
+        });
+    }
+    Ok(TaskDependencies {
+        order: template.depends_order,
+        tasks,
+    })
+}
+
 fn task_lru_comparator(
     (kind_a, task_a, lru_score_a): &(TaskSourceKind, ResolvedTask, u32),
     (kind_b, task_b, lru_score_b): &(TaskSourceKind, ResolvedTask, u32),
@@ -1083,10 +1222,11 @@ impl ContextProvider for ContextProviderWithTasks {
 #[cfg(test)]
 mod tests {
     use gpui::TestAppContext;
+    use language::LanguageConfig;
     use paths::tasks_file;
     use pretty_assertions::assert_eq;
     use serde_json::json;
-    use settings::SettingsLocation;
+    use settings::{SettingsLocation, SettingsStore};
     use std::path::Path;
     use util::rel_path::rel_path;
 
@@ -1535,6 +1675,174 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_resolving_task_dependencies(cx: &mut TestAppContext) {
+        init_test(cx);
+        let inventory = cx.update(|cx| Inventory::new(cx));
+        let worktree_id = WorktreeId::from_usize(1);
+
+        inventory.update(cx, |inventory, _| {
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Global(tasks_file()),
+                    Some(
+                        &json!([
+                            { "label": "codegen", "command": "make codegen" },
+                            { "label": "cycle a", "command": "a", "depends_on": ["cycle b"] },
+                            { "label": "cycle b", "command": "b", "depends_on": ["cycle a"] },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+            inventory
+                .update_file_based_tasks(
+                    TaskSettingsLocation::Worktree(SettingsLocation {
+                        worktree_id,
+                        path: rel_path(".zed"),
+                    }),
+                    Some(
+                        &json!([
+                            { "label": "build", "command": "make", "depends_on": ["codegen"] },
+                            { "label": "test", "command": "make test" },
+                        ])
+                        .to_string(),
+                    ),
+                )
+                .unwrap();
+        });
+
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+        let rust = Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    ..LanguageConfig::default()
+                },
+                None,
+            )
+            .with_context_provider(Some(Arc::new(ContextProviderWithTasks::new(
+                TaskTemplates(vec![TaskTemplate {
+                    label: "clippy".into(),
+                    command: "cargo clippy".into(),
+                    ..TaskTemplate::default()
+                }]),
+            )))),
+        );
+
+        let dependencies = resolve_dependencies_of(
+            &inventory,
+            json!({
+                "label": "run",
+                "depends_on": ["build", "test"],
+                "depends_order": "sequence",
+            }),
+            None,
+            Some(worktree_id),
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(dependencies.order, DependsOrder::Sequence);
+        let labels = dependencies
+            .tasks
+            .iter()
+            .map(|dependency| dependency.task.resolved_label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["build", "test"]);
+        let build = &dependencies.tasks[0];
+        assert_eq!(build.dependencies.order, DependsOrder::Parallel);
+        assert_eq!(build.dependencies.tasks.len(), 1);
+        assert_eq!(build.dependencies.tasks[0].task.resolved_label, "codegen");
+        assert!(build.dependencies.tasks[0].dependencies.tasks.is_empty());
+
+        let dependencies = resolve_dependencies_of(
+            &inventory,
+            json!({ "label": "run", "depends_on": ["clippy", "codegen"] }),
+            Some(rust.clone()),
+            None,
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            dependencies
+                .tasks
+                .iter()
+                .map(|dependency| (
+                    &dependency.source_kind,
+                    dependency.task.resolved_label.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    &TaskSourceKind::Language {
+                        name: "Rust".into()
+                    },
+                    "clippy"
+                ),
+                (
+                    &TaskSourceKind::AbsPath {
+                        id_base: "global tasks.json".into(),
+                        abs_path: tasks_file().clone(),
+                    },
+                    "codegen"
+                ),
+            ],
+            "Language tasks should be available as dependencies"
+        );
+
+        let error = resolve_dependencies_of(
+            &inventory,
+            json!({ "label": "run", "depends_on": ["build"] }),
+            None,
+            None,
+            cx,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task `run` depends on unknown task `build`",
+            "Worktree tasks should not be available without a worktree"
+        );
+
+        let error = resolve_dependencies_of(
+            &inventory,
+            json!({ "label": "run", "depends_on": ["cycle a"] }),
+            None,
+            None,
+            cx,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Task dependency cycle: run → cycle a → cycle b → cycle a"
+        );
+    }
+
+    async fn resolve_dependencies_of(
+        inventory: &Entity<Inventory>,
+        template: serde_json::Value,
+        language: Option<Arc<Language>>,
+        worktree: Option<WorktreeId>,
+        cx: &mut TestAppContext,
+    ) -> Result<TaskDependencies> {
+        let template = serde_json::from_value::<TaskTemplate>(template).unwrap();
+        let task = template
+            .resolve_task("test", &TaskContext::default())
+            .unwrap();
+        inventory
+            .read_with(cx, |inventory, cx| {
+                inventory.resolve_dependencies(&task, None, language, worktree, cx)
+            })
+            .await
+    }
+
     fn init_test(_cx: &mut TestAppContext) {
         zlog::init_test();
         TaskStore::init(None);
@@ -781,6 +781,7 @@ impl PickerDelegate for RecentProjectsDelegate {
                                         RemoteConnectionOptions::Ssh { .. } => IconName::Server,
                                         RemoteConnectionOptions::Wsl { .. } => IconName::Linux,
                                         RemoteConnectionOptions::Docker(_) => IconName::Box,
+                                        RemoteConnectionOptions::Command(_) => IconName::Server,
                                         #[cfg(any(test, feature = "test-support"))]
                                         RemoteConnectionOptions::Mock(_) => IconName::Server,
                                     })
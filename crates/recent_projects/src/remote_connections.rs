@@ -19,12 +19,15 @@ use markdown::{Markdown, MarkdownElement, MarkdownStyle};
 use project::trusted_worktrees;
 use release_channel::ReleaseChannel;
 use remote::{
-    ConnectionIdentifier, DockerConnectionOptions, Interactive, RemoteClient, RemoteConnection,
-    RemoteConnectionOptions, RemotePlatform, SshConnectionOptions,
+    CommandConnectionOptions, ConnectionIdentifier, DockerConnectionOptions, Interactive,
+    RemoteClient, RemoteConnection, RemoteConnectionOptions, RemotePlatform, SshConnectionOptions,
 };
 use semver::Version;
 pub use settings::SshConnection;
-use settings::{DevContainerConnection, ExtendingVec, RegisterSetting, Settings, WslConnection};
+use settings::{
+    CommandConnection, DevContainerConnection, ExtendingVec, RegisterSetting, Settings,
+    WslConnection,
+};
 use theme::ThemeSettings;
 use ui::{
     ActiveTheme, Color, CommonAnimationExt, Context, InteractiveElement, IntoElement, KeyBinding,
@@ -37,6 +40,7 @@ use workspace::{AppState, ModalView, Workspace};
 pub struct RemoteSettings {
     pub ssh_connections: ExtendingVec<SshConnection>,
     pub wsl_connections: ExtendingVec<WslConnection>,
+    pub command_connections: ExtendingVec<CommandConnection>,
     /// Whether to read ~/.ssh/config for ssh connection sources.
     pub read_ssh_config: bool,
 }
@@ -50,6 +54,10 @@ impl RemoteSettings {
         self.wsl_connections.clone().0.into_iter()
     }
 
+    pub fn command_connections(&self) -> impl Iterator<Item = CommandConnection> + use<> {
+        self.command_connections.clone().0.into_iter()
+    }
+
     pub fn fill_connection_options_from_settings(&self, options: &mut SshConnectionOptions) {
         for conn in self.ssh_connections() {
             if conn.host == options.host.to_string()
@@ -87,6 +95,7 @@ pub enum Connection {
     Ssh(SshConnection),
     Wsl(WslConnection),
     DevContainer(DevContainerConnection),
+    Command(CommandConnection),
 }
 
 impl From<Connection> for RemoteConnectionOptions {
@@ -102,6 +111,9 @@ impl From<Connection> for RemoteConnectionOptions {
                     use_podman: conn.use_podman,
                 })
             }
+            Connection::Command(conn) => {
+                RemoteConnectionOptions::Command(CommandConnectionOptions::from(conn))
+            }
         }
     }
 }
@@ -118,12 +130,23 @@ impl From<WslConnection> for Connection {
     }
 }
 
+impl From<CommandConnection> for Connection {
+    fn from(val: CommandConnection) -> Self {
+        Connection::Command(val)
+    }
+}
+
 impl Settings for RemoteSettings {
     fn from_settings(content: &settings::SettingsContent) -> Self {
         let remote = &content.remote;
         Self {
             ssh_connections: remote.ssh_connections.clone().unwrap_or_default().into(),
             wsl_connections: remote.wsl_connections.clone().unwrap_or_default().into(),
+            command_connections: remote
+                .command_connections
+                .clone()
+                .unwrap_or_default()
+                .into(),
             read_ssh_config: remote.read_ssh_config.unwrap(),
         }
     }
@@ -311,6 +334,15 @@ impl RemoteConnectionModal {
                 (options.distro_name.clone(), None, true, false)
             }
             RemoteConnectionOptions::Docker(options) => (options.name.clone(), None, false, true),
+            RemoteConnectionOptions::Command(options) => (
+                std::iter::once(options.command.as_str())
+                    .chain(options.args.iter().map(String::as_str))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                Some(options.name.clone()),
+                false,
+                false,
+            ),
             #[cfg(any(test, feature = "test-support"))]
             RemoteConnectionOptions::Mock(options) => {
                 (format!("mock-{}", options.id), None, false, false)
@@ -747,6 +779,9 @@ pub async fn open_remote_project(
                                 RemoteConnectionOptions::Docker(_) => {
                                     "Failed to connect to Dev Container"
                                 }
+                                RemoteConnectionOptions::Command(_) => {
+                                    "Failed to connect through command"
+                                }
                                 #[cfg(any(test, feature = "test-support"))]
                                 RemoteConnectionOptions::Mock(_) => {
                                     "Failed to connect to mock server"
@@ -810,6 +845,9 @@ pub async fn open_remote_project(
                                 RemoteConnectionOptions::Docker(_) => {
                                     "Failed to connect to Dev Container"
                                 }
+                                RemoteConnectionOptions::Command(_) => {
+                                    "Failed to connect through command"
+                                }
                                 #[cfg(any(test, feature = "test-support"))]
                                 RemoteConnectionOptions::Mock(_) => {
                                     "Failed to connect to mock server"
@@ -0,0 +1,349 @@
+use crate::{
+    RemoteClientDelegate,
+    remote_client::{CommandTemplate, Interactive, RemoteConnection, RemoteConnectionOptions},
+    transport::exec::{self, ExecCommandBuilder, RUN_FROM_HOME_SCRIPT},
+};
+use anyhow::{Context as _, Result, anyhow};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{App, AppContext as _, AsyncApp, Task};
+use parking_lot::Mutex;
+use rpc::proto::Envelope;
+use smol::process;
+use std::{fmt::Write as _, path::PathBuf, sync::Arc};
+use util::{
+    paths::{PathStyle, RemotePathBuf},
+    rel_path::RelPath,
+    shell::ShellKind,
+};
+
+/// A connection to a target reached through an arbitrary command, e.g. `kubectl exec -i my-pod --`
+/// or `distrobox enter my-box --`, which runs the program given as its trailing arguments on the target
+/// with its stdio connected to ours.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CommandConnectionOptions {
+    /// Name to use for the target in the UI.
+    pub name: String,
+    /// The program that runs its trailing arguments on the target.
+    pub command: String,
+    /// Arguments passed to the program, before the command to run on the target.
+    pub args: Vec<String>,
+    /// Arguments used instead of `args` for interactive commands, such as terminals, which need
+    /// a TTY on the target, e.g. `kubectl exec -it my-pod --`. Defaults to `args`.
+    pub interactive_args: Option<Vec<String>>,
+}
+
+impl From<settings::CommandConnection> for CommandConnectionOptions {
+    fn from(val: settings::CommandConnection) -> Self {
+        CommandConnectionOptions {
+            name: val.name,
+            command: val.command,
+            args: val.args,
+            interactive_args: val.interactive_args,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct CommandRemoteConnection {
+    remote_binary_path: Option<Arc<RelPath>>,
+    shell: String,
+    connection_options: CommandConnectionOptions,
+    proxy_process: Mutex<Option<u32>>,
+}
+
+impl CommandRemoteConnection {
+    pub(crate) async fn new(
+        connection_options: CommandConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        log::info!(
+            "Connecting to {} with {:?} {:?}",
+            connection_options.name,
+            connection_options.command,
+            connection_options.args
+        );
+        delegate.set_status(Some("Detecting remote environment"), cx);
+        let shell = exec::detect_shell(&connection_options).await;
+        log::info!("Remote shell discovered: {}", shell);
+        let platform = exec::detect_platform(&connection_options)
+            .await
+            .context("failed detecting platform")?;
+        log::info!("Remote platform discovered: {:?}", platform);
+        let remote_binary_path =
+            exec::ensure_server_binary(&connection_options, platform, true, &delegate, cx)
+                .await
+                .context("failed ensuring server binary")?;
+
+        Ok(Self {
+            remote_binary_path: Some(remote_binary_path),
+            shell,
+            connection_options,
+            proxy_process: Mutex::new(None),
+        })
+    }
+}
+
+impl ExecCommandBuilder for CommandConnectionOptions {
+    fn command(&self, program: &str, args: &[&str]) -> process::Command {
+        let mut command = util::command::new_smol_command(&self.command);
+        command
+            .args(&self.args)
+            .args(["sh", "-c", RUN_FROM_HOME_SCRIPT, "sh", program])
+            .args(args);
+        command
+    }
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for CommandRemoteConnection {
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_path) = &self.remote_binary_path else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let proxy_process = match exec::spawn_proxy(
+            &self.connection_options,
+            remote_binary_path,
+            &unique_identifier,
+            reconnect,
+        ) {
+            Ok(process) => process,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        *self.proxy_process.lock() = Some(proxy_process.id());
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            proxy_process,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let options = self.connection_options.clone();
+        cx.background_spawn(async move {
+            exec::upload_directory(&options, &src_path, &dest_path.to_string()).await
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        let Some(pid) = self.proxy_process.lock().take() else {
+            return Ok(());
+        };
+        let mut command = if cfg!(target_os = "windows") {
+            let mut command = util::command::new_smol_command("taskkill");
+            command.args(["/F", "/PID", &pid.to_string()]);
+            command
+        } else {
+            let mut command = util::command::new_smol_command("kill");
+            command.arg(pid.to_string());
+            command
+        };
+        let status = command
+            .status()
+            .await
+            .context("failed to kill the remote server proxy")?;
+        anyhow::ensure!(
+            status.success(),
+            "failed to kill the remote server proxy (pid {pid})"
+        );
+        Ok(())
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.proxy_process.lock().is_none()
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        _port_forward: Option<(u16, String, u16)>,
+        interactive: Interactive,
+    ) -> Result<CommandTemplate> {
+        let shell_kind = ShellKind::Posix;
+        let mut script = match working_dir {
+            Some(working_dir) => {
+                let working_dir = RemotePathBuf::new(working_dir, PathStyle::Posix).to_string();
+                if working_dir == "~" {
+                    "cd ~".to_string()
+                } else if let Some(relative) = working_dir.strip_prefix("~/") {
+                    format!(
+                        "cd ~/{}",
+                        shell_kind.try_quote(relative).context("shell quoting")?
+                    )
+                } else {
+                    format!(
+                        "cd {}",
+                        shell_kind
+                            .try_quote(&working_dir)
+                            .context("shell quoting")?
+                    )
+                }
+            }
+            None => "cd ~".to_string(),
+        };
+
+        script.push_str(" && exec env");
+        for (k, v) in env.iter() {
+            write!(
+                script,
+                " {}={}",
+                k,
+                shell_kind.try_quote(v).context("shell quoting")?
+            )?;
+        }
+        if let Some(program) = program {
+            write!(
+                script,
+                " {}",
+                shell_kind
+                    .try_quote_prefix_aware(&program)
+                    .context("shell quoting")?
+            )?;
+            for arg in args {
+                write!(
+                    script,
+                    " {}",
+                    shell_kind.try_quote(arg).context("shell quoting")?
+                )?;
+            }
+        } else {
+            write!(script, " {} -l", self.shell)?;
+        }
+
+        let options = &self.connection_options;
+        let mut command_args = match (interactive, &options.interactive_args) {
+            (Interactive::Yes, Some(interactive_args)) => interactive_args.clone(),
+            _ => options.args.clone(),
+        };
+        command_args.extend(["sh".to_string(), "-c".to_string(), script]);
+        Ok(CommandTemplate {
+            program: self.connection_options.command.clone(),
+            args: command_args,
+            env: HashMap::default(),
+        })
+    }
+
+    fn build_forward_ports_command(
+        &self,
+        _forwards: Vec<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        Err(anyhow!(
+            "Port forwarding is not supported for command connections"
+        ))
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Command(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        PathStyle::Posix
+    }
+
+    fn shell(&self) -> String {
+        self.shell.clone()
+    }
+
+    fn default_system_shell(&self) -> String {
+        String::from("/bin/sh")
+    }
+
+    fn has_wsl_interop(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_command() {
+        let connection = CommandRemoteConnection {
+            remote_binary_path: None,
+            shell: "/bin/bash".to_string(),
+            connection_options: CommandConnectionOptions {
+                name: "dev pod".to_string(),
+                command: "kubectl".to_string(),
+                args: vec![
+                    "exec".to_string(),
+                    "-i".to_string(),
+                    "dev-pod".to_string(),
+                    "--".to_string(),
+                ],
+                interactive_args: Some(vec![
+                    "exec".to_string(),
+                    "-it".to_string(),
+                    "dev-pod".to_string(),
+                    "--".to_string(),
+                ]),
+            },
+            proxy_process: Mutex::new(None),
+        };
+
+        let command = connection
+            .build_command(
+                Some("cargo".to_string()),
+                &["test".to_string(), "my test".to_string()],
+                &HashMap::from_iter([("RUST_LOG".to_string(), "info".to_string())]),
+                Some("~/projects/zed".to_string()),
+                None,
+                Interactive::No,
+            )
+            .unwrap();
+        assert_eq!(command.program, "kubectl");
+        assert_eq!(
+            command.args,
+            vec![
+                "exec",
+                "-i",
+                "dev-pod",
+                "--",
+                "sh",
+                "-c",
+                "cd ~/projects/zed && exec env RUST_LOG=info cargo test 'my test'",
+            ]
+        );
+
+        let command = connection
+            .build_command(None, &[], &HashMap::default(), None, None, Interactive::Yes)
+            .unwrap();
+        assert_eq!(
+            command.args,
+            vec![
+                "exec",
+                "-it",
+                "dev-pod",
+                "--",
+                "sh",
+                "-c",
+                "cd ~ && exec env /bin/bash -l",
+            ]
+        );
+    }
+}
@@ -0,0 +1,367 @@
+//! Setup shared by the transports that run each program on the remote through a local command,
+//! such as `docker exec` or a user-configured command.
+
+use crate::{
+    RemoteClientDelegate, RemotePlatform,
+    transport::{parse_platform, parse_shell},
+};
+use anyhow::{Context as _, Result, anyhow};
+use futures::AsyncWriteExt as _;
+use gpui::AsyncApp;
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use smol::{
+    fs,
+    process::{self, Child},
+};
+use std::{path::Path, process::Stdio, sync::Arc, time::Instant};
+use util::{paths::PathStyle, rel_path::RelPath, shell::ShellKind};
+
+/// Runs the program given as `"$@"` from the home directory of the remote user,
+/// so that paths relative to the home directory can be used regardless of
+/// where the command places us.
+pub(super) const RUN_FROM_HOME_SCRIPT: &str = "cd ~ 2>/dev/null; exec \"$@\"";
+
+/// Builds the local commands that run programs on the remote.
+pub(super) trait ExecCommandBuilder: Clone + Send + 'static {
+    /// Creates a command that runs `program` with `args` on the remote, from the home directory
+    /// of the remote user, with its stdio connected to the command's.
+    fn command(&self, program: &str, args: &[&str]) -> process::Command;
+}
+
+fn exec_command(
+    builder: &impl ExecCommandBuilder,
+    program: &str,
+    args: &[&str],
+) -> process::Command {
+    let mut command = builder.command(program, args);
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    log::debug!("remote exec {:?}", command);
+    command
+}
+
+async fn run_command(
+    builder: &impl ExecCommandBuilder,
+    program: &str,
+    args: &[&str],
+) -> Result<String> {
+    let mut command = exec_command(builder, program, args);
+    let output = command
+        .output()
+        .await
+        .with_context(|| format!("Failed to run command '{:?}'", command))?;
+    anyhow::ensure!(
+        output.status.success(),
+        "Command '{:?}' failed: {}",
+        command,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+async fn run_command_with_stdin(
+    builder: &impl ExecCommandBuilder,
+    program: &str,
+    args: &[&str],
+    stdin: Vec<u8>,
+) -> Result<()> {
+    let mut command = exec_command(builder, program, args);
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to run command '{:?}'", command))?;
+    let mut child_stdin = child.stdin.take().context("no stdin")?;
+    child_stdin.write_all(&stdin).await?;
+    child_stdin.close().await?;
+    drop(child_stdin);
+    let output = child.output().await?;
+    anyhow::ensure!(
+        output.status.success(),
+        "Command '{:?}' failed: {}",
+        command,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(())
+}
+
+pub(super) async fn detect_platform(builder: &impl ExecCommandBuilder) -> Result<RemotePlatform> {
+    let output = run_command(builder, "uname", &["-sm"]).await?;
+    parse_platform(&output)
+}
+
+pub(super) async fn detect_shell(builder: &impl ExecCommandBuilder) -> String {
+    const DEFAULT_SHELL: &str = "sh";
+    match run_command(builder, "sh", &["-c", "echo $SHELL"]).await {
+        Ok(output) => parse_shell(&output, DEFAULT_SHELL),
+        Err(e) => {
+            log::error!("Failed to detect remote shell: {e}");
+            DEFAULT_SHELL.to_owned()
+        }
+    }
+}
+
+/// Makes sure that the remote server matching this version of Zed is installed on the remote,
+/// returning its path relative to the home directory.
+///
+/// Unless `download_on_remote` is false, the remote tries downloading the server itself first,
+/// before it's downloaded locally and uploaded.
+pub(super) async fn ensure_server_binary(
+    builder: &impl ExecCommandBuilder,
+    platform: RemotePlatform,
+    download_on_remote: bool,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<Arc<RelPath>> {
+    let (release_channel, version, commit) = cx.update(|cx| {
+        (
+            ReleaseChannel::global(cx),
+            AppVersion::global(cx),
+            AppCommitSha::try_global(cx),
+        )
+    });
+    let version_str = match release_channel {
+        ReleaseChannel::Nightly => {
+            let commit = commit.map(|s| s.full()).unwrap_or_default();
+            format!("{}-{}", version, commit)
+        }
+        ReleaseChannel::Dev => "build".to_string(),
+        _ => version.to_string(),
+    };
+    let binary_name = format!(
+        "zed-remote-server-{}-{}",
+        release_channel.dev_name(),
+        version_str
+    );
+    let dst_path = paths::remote_server_dir_relative().join(RelPath::unix(&binary_name).unwrap());
+
+    if let Some(parent) = dst_path.parent() {
+        run_command(builder, "mkdir", &["-p", &parent.display(PathStyle::Posix)])
+            .await
+            .context("failed to create the remote server directory")?;
+    }
+
+    #[cfg(any(debug_assertions, feature = "build-remote-server-binary"))]
+    if let Some(remote_server_path) =
+        super::build_remote_server_from_source(&platform, delegate.as_ref(), cx).await?
+    {
+        let tmp_path = paths::remote_server_dir_relative().join(
+            RelPath::unix(&format!(
+                "download-{}-{}",
+                std::process::id(),
+                remote_server_path.file_name().unwrap().to_string_lossy()
+            ))
+            .unwrap(),
+        );
+        upload_file(builder, &remote_server_path, &tmp_path, delegate, cx).await?;
+        extract_and_install(builder, &tmp_path, &dst_path, delegate, cx).await?;
+        return Ok(dst_path);
+    }
+
+    if run_command(builder, &dst_path.display(PathStyle::Posix), &["version"])
+        .await
+        .is_ok()
+    {
+        return Ok(dst_path);
+    }
+
+    let wanted_version = cx.update(|cx| match release_channel {
+        ReleaseChannel::Nightly => Ok(None),
+        ReleaseChannel::Dev => {
+            anyhow::bail!(
+                "ZED_BUILD_REMOTE_SERVER is not set and no remote server exists at ({:?})",
+                dst_path
+            )
+        }
+        _ => Ok(Some(AppVersion::global(cx))),
+    })?;
+
+    let tmp_path = RelPath::unix(&format!(
+        "{}.{}.gz",
+        dst_path.display(PathStyle::Posix),
+        std::process::id()
+    ))
+    .unwrap()
+    .into_arc();
+
+    if download_on_remote
+        && let Some(url) = delegate
+            .get_download_url(platform, release_channel, wanted_version.clone(), cx)
+            .await?
+    {
+        match download_binary_on_remote(builder, &url, &tmp_path, delegate, cx).await {
+            Ok(()) => {
+                extract_and_install(builder, &tmp_path, &dst_path, delegate, cx).await?;
+                return Ok(dst_path);
+            }
+            Err(e) => log::error!(
+                "Failed to download binary on the remote, attempting to download locally and then upload it: {e:#}"
+            ),
+        }
+    }
+
+    let src_path = delegate
+        .download_server_binary_locally(platform, release_channel, wanted_version, cx)
+        .await
+        .context("downloading server binary locally")?;
+    upload_file(builder, &src_path, &tmp_path, delegate, cx)
+        .await
+        .context("uploading server binary")?;
+    extract_and_install(builder, &tmp_path, &dst_path, delegate, cx).await?;
+    Ok(dst_path)
+}
+
+async fn download_binary_on_remote(
+    builder: &impl ExecCommandBuilder,
+    url: &str,
+    tmp_path: &RelPath,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    delegate.set_status(Some("Downloading remote development server on host"), cx);
+    let tmp_path = tmp_path.display(PathStyle::Posix);
+    match run_command(builder, "curl", &["-f", "-L", url, "-o", &tmp_path]).await {
+        Ok(_) => Ok(()),
+        Err(curl_error) => {
+            log::info!("Downloading with curl failed, trying wget: {curl_error:#}");
+            run_command(builder, "wget", &[url, "-O", &tmp_path])
+                .await
+                .map(|_| ())
+                .map_err(|wget_error| wget_error.context(curl_error))
+        }
+    }
+}
+
+async fn upload_file(
+    builder: &impl ExecCommandBuilder,
+    src_path: &Path,
+    dst_path: &RelPath,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    delegate.set_status(Some("Uploading remote development server"), cx);
+
+    let t0 = Instant::now();
+    let contents = fs::read(src_path)
+        .await
+        .with_context(|| format!("reading {}", src_path.display()))?;
+    log::info!(
+        "uploading remote development server to {:?} ({}kb)",
+        dst_path,
+        contents.len() / 1024
+    );
+    let dst_path = dst_path.display(PathStyle::Posix);
+    run_command_with_stdin(
+        builder,
+        "sh",
+        &["-c", "cat > \"$1\"", "sh", &dst_path],
+        contents,
+    )
+    .await
+    .with_context(|| format!("failed to upload {} to {}", src_path.display(), dst_path))?;
+    log::info!("uploaded remote development server in {:?}", t0.elapsed());
+    Ok(())
+}
+
+async fn extract_and_install(
+    builder: &impl ExecCommandBuilder,
+    tmp_path: &RelPath,
+    dst_path: &RelPath,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    delegate.set_status(Some("Extracting remote development server"), cx);
+
+    let shell_kind = ShellKind::Posix;
+    let quote = |path: &str| -> Result<String> {
+        Ok(shell_kind
+            .try_quote(path)
+            .context("shell quoting")?
+            .into_owned())
+    };
+    let tmp_path = tmp_path.display(PathStyle::Posix);
+    let dst_path = quote(&dst_path.display(PathStyle::Posix))?;
+    let script = if let Some(uncompressed) = tmp_path.strip_suffix(".gz") {
+        let uncompressed = quote(uncompressed)?;
+        format!(
+            "set -e; gunzip -f {} && chmod 755 {uncompressed} && mv -f {uncompressed} {dst_path}",
+            quote(&tmp_path)?
+        )
+    } else {
+        let tmp_path = quote(&tmp_path)?;
+        format!("set -e; chmod 755 {tmp_path} && mv -f {tmp_path} {dst_path}")
+    };
+    run_command(builder, "sh", &["-c", &script])
+        .await
+        .context("failed to extract server binary")?;
+    Ok(())
+}
+
+/// Starts the remote server's proxy, whose stdio carries the messages to and from the server.
+pub(super) fn spawn_proxy(
+    builder: &impl ExecCommandBuilder,
+    remote_binary_path: &RelPath,
+    unique_identifier: &str,
+    reconnect: bool,
+) -> Result<Child> {
+    let mut proxy_args = vec![];
+    for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+        if let Some(value) = std::env::var(env_var).ok() {
+            proxy_args.push(format!("{}={}", env_var, value));
+        }
+    }
+    proxy_args.push(remote_binary_path.display(PathStyle::Posix).into_owned());
+    proxy_args.push("proxy".to_owned());
+    proxy_args.push("--identifier".to_owned());
+    proxy_args.push(unique_identifier.to_owned());
+    if reconnect {
+        proxy_args.push("--reconnect".to_owned());
+    }
+    let proxy_args = proxy_args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    exec_command(builder, "env", &proxy_args)
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|error| anyhow!("failed to spawn remote server: {}", error))
+}
+
+/// Copies the local directory's contents into `dest_path` on the remote, as an archive streamed
+/// through the command's stdin.
+pub(super) async fn upload_directory(
+    builder: &impl ExecCommandBuilder,
+    src_path: &Path,
+    dest_path: &str,
+) -> Result<()> {
+    let mut tar = util::command::new_smol_command("tar");
+    tar.arg("-C").arg(src_path).arg("-cf").arg("-").arg(".");
+    let archive = tar
+        .output()
+        .await
+        .with_context(|| format!("failed to archive {}", src_path.display()))?;
+    anyhow::ensure!(
+        archive.status.success(),
+        "failed to archive {}: {}",
+        src_path.display(),
+        String::from_utf8_lossy(&archive.stderr)
+    );
+
+    run_command_with_stdin(
+        builder,
+        "sh",
+        &[
+            "-c",
+            "mkdir -p \"$1\" && tar -xf - -C \"$1\"",
+            "sh",
+            dest_path,
+        ],
+        archive.stdout,
+    )
+    .await
+    .with_context(|| {
+        format!(
+            "failed to upload directory {} -> {}",
+            src_path.display(),
+            dest_path
+        )
+    })
+}
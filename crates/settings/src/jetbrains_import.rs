@@ -0,0 +1,815 @@
+use crate::*;
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashMap;
+use fs::Fs;
+use futures::StreamExt as _;
+use serde_json::{Value, json};
+use std::{
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Settings exported from, or stored by, an IntelliJ-based IDE (IntelliJ IDEA, CLion, PyCharm, …).
+///
+/// Reads the `options/*.xml` files, the active custom keymap from `keymaps/`, and the active code
+/// style scheme from `codestyles/` of a configuration directory.
+pub struct JetBrainsSettings {
+    pub path: Arc<Path>,
+    /// `<component>` elements of all `options/*.xml` files, by component name.
+    components: HashMap<String, XmlElement>,
+    keymap: Option<XmlElement>,
+    code_style: Option<XmlElement>,
+}
+
+/// The Zed equivalent of [`JetBrainsSettings`].
+pub struct JetBrainsImport {
+    pub settings: SettingsContent,
+    pub keymap: TranslatedKeymap,
+    /// Descriptions of the settings and shortcuts that have no Zed equivalent.
+    pub skipped: Vec<String>,
+}
+
+impl JetBrainsSettings {
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn from_str(
+        options: &[&str],
+        keymap: Option<&str>,
+        code_style: Option<&str>,
+    ) -> Result<Self> {
+        let mut components = HashMap::default();
+        for options in options {
+            collect_components(parse_xml(options)?, &mut components);
+        }
+        Ok(Self {
+            path: Path::new("/example-path/JetBrains/IntelliJIdea2024.3").into(),
+            components,
+            keymap: keymap.map(parse_xml).transpose()?,
+            code_style: code_style.map(parse_xml).transpose()?,
+        })
+    }
+
+    /// Loads the settings of the most recent JetBrains IDE found in [`paths::jetbrains_config_dir`].
+    pub async fn load_user_settings(fs: Arc<dyn Fs>) -> Result<Self> {
+        let config_dir = paths::jetbrains_config_dir();
+        let mut product_dirs = Vec::new();
+        if let Ok(mut entries) = fs.read_dir(&config_dir).await {
+            while let Some(entry) = entries.next().await {
+                let Ok(entry) = entry else { continue };
+                if fs.is_dir(&entry.join("options")).await {
+                    product_dirs.push(entry);
+                }
+            }
+        }
+        let Some(path) = product_dirs
+            .into_iter()
+            .max_by_key(|path| product_version(path))
+        else {
+            return Err(anyhow!(
+                "No JetBrains IDE settings found, expected to find them in {}",
+                config_dir.display()
+            ));
+        };
+        Self::load(&path, fs).await
+    }
+
+    /// Loads the settings from a JetBrains configuration directory, or from an extracted
+    /// `settings.zip` produced by "File | Manage IDE Settings | Export Settings".
+    pub async fn load(path: &Path, fs: Arc<dyn Fs>) -> Result<Self> {
+        let options_dir = path.join("options");
+        anyhow::ensure!(
+            fs.is_dir(&options_dir).await,
+            "{} does not contain an `options` directory",
+            path.display()
+        );
+
+        let mut components = HashMap::default();
+        // Some options, such as the active keymap, live in a per-OS subdirectory.
+        for options_file in xml_files(&fs, &options_dir, 1).await {
+            match load_xml(&fs, &options_file).await {
+                Ok(root) => collect_components(root, &mut components),
+                Err(error) => log::warn!("{error:?}"),
+            }
+        }
+
+        let keymap_name = components
+            .get("KeymapManager")
+            .and_then(|manager| manager.child("active_keymap"))
+            .and_then(|keymap| keymap.attribute("name"))
+            .map(str::to_string);
+        let mut keymap = None;
+        if let Some(keymap_name) = keymap_name {
+            // Bundled keymaps have no file, and file names of custom keymaps may be sanitized.
+            for keymap_file in xml_files(&fs, &path.join("keymaps"), 0).await {
+                if let Ok(root) = load_xml(&fs, &keymap_file).await
+                    && root.attribute("name") == Some(keymap_name.as_str())
+                {
+                    keymap = Some(root);
+                    break;
+                }
+            }
+        }
+
+        let code_style_name = components
+            .get("CodeStyleSchemeSettings")
+            .and_then(|settings| settings.option_value("CURRENT_SCHEME_NAME"))
+            .unwrap_or("Default")
+            .to_string();
+        let code_style_path = path
+            .join("codestyles")
+            .join(format!("{code_style_name}.xml"));
+        let code_style = if fs.is_file(&code_style_path).await {
+            Some(load_xml(&fs, &code_style_path).await?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            path: path.into(),
+            components,
+            keymap,
+            code_style,
+        })
+    }
+
+    pub fn translate(&self) -> JetBrainsImport {
+        let mut import = JetBrainsImport {
+            settings: SettingsContent {
+                base_keymap: Some(BaseKeymapContent::JetBrains),
+                ..Default::default()
+            },
+            keymap: TranslatedKeymap::default(),
+            skipped: Vec::new(),
+        };
+        self.translate_editor_settings(&mut import.settings);
+        self.translate_font_settings(&mut import.settings);
+        self.translate_general_settings(&mut import.settings);
+        if let Some(code_style) = &self.code_style {
+            translate_code_style(code_style, &mut import);
+        }
+        if let Some(keymap) = &self.keymap {
+            translate_keymap(keymap, &mut import);
+        }
+        import
+    }
+
+    fn option(&self, component: &str, option: &str) -> Option<&str> {
+        self.components.get(component)?.option_value(option)
+    }
+
+    fn option_bool(&self, component: &str, option: &str) -> Option<bool> {
+        self.option(component, option)?.parse().ok()
+    }
+
+    fn translate_editor_settings(&self, settings: &mut SettingsContent) {
+        let defaults = &mut settings.project.all_languages.defaults;
+        defaults.show_whitespaces = self
+            .option_bool("EditorSettings", "IS_WHITESPACES_SHOWN")
+            .map(|shown| {
+                if shown {
+                    ShowWhitespaceSetting::All
+                } else {
+                    ShowWhitespaceSetting::None
+                }
+            });
+        defaults.ensure_final_newline_on_save =
+            self.option_bool("EditorSettings", "IS_ENSURE_NEWLINE_AT_EOF");
+        defaults.remove_trailing_whitespace_on_save = self
+            .option("EditorSettings", "STRIP_TRAILING_SPACES")
+            .map(|strip| strip != "None");
+        defaults.soft_wrap = self
+            .option("EditorSettings", "USE_SOFT_WRAPS")
+            .map(|editors| {
+                if editors.contains("MAIN_EDITOR") {
+                    SoftWrap::EditorWidth
+                } else {
+                    SoftWrap::None
+                }
+            });
+        defaults.indent_guides = self
+            .option_bool("EditorSettings", "IS_INDENT_GUIDES_SHOWN")
+            .map(|enabled| IndentGuideSettingsContent {
+                enabled: Some(enabled),
+                ..Default::default()
+            });
+        defaults.use_autoclose = self.option_bool("CodeInsightSettings", "AUTOINSERT_PAIR_BRACKET");
+        defaults.show_completions_on_input =
+            self.option_bool("CodeInsightSettings", "AUTO_POPUP_COMPLETION_LOOKUP");
+
+        settings.editor.cursor_blink = self.option_bool("EditorSettings", "IS_CARET_BLINKING");
+        settings.editor.gutter = self
+            .option_bool("EditorSettings", "ARE_LINE_NUMBERS_SHOWN")
+            .map(|line_numbers| GutterContent {
+                line_numbers: Some(line_numbers),
+                ..Default::default()
+            });
+    }
+
+    fn translate_font_settings(&self, settings: &mut SettingsContent) {
+        settings.theme.buffer_font_family = self
+            .option("DefaultFont", "FONT_FAMILY")
+            .map(|family| FontFamilyName(family.into()));
+        settings.theme.buffer_font_size = self
+            .option("DefaultFont", "FONT_SIZE_2D")
+            .or_else(|| self.option("DefaultFont", "FONT_SIZE"))
+            .and_then(|size| size.parse::<f32>().ok())
+            .map(FontSize::from);
+        settings.theme.buffer_line_height = self
+            .option("DefaultFont", "LINE_SPACING")
+            .and_then(|spacing| spacing.parse::<f32>().ok())
+            .map(|spacing| BufferLineHeight::Custom(spacing.max(1.0)));
+    }
+
+    fn translate_general_settings(&self, settings: &mut SettingsContent) {
+        settings.workspace.confirm_quit = self.option_bool("GeneralSettings", "confirmExit");
+        if self.option_bool("GeneralSettings", "autoSaveIfInactive") == Some(true) {
+            let seconds = self
+                .option("GeneralSettings", "inactiveTimeout")
+                .and_then(|seconds| seconds.parse::<u64>().ok())
+                .unwrap_or(15);
+            settings.workspace.autosave = Some(AutosaveSetting::AfterDelay {
+                milliseconds: (seconds * 1000).into(),
+            });
+        }
+    }
+}
+
+impl JetBrainsImport {
+    /// Describes every setting and keybinding that importing will write, one per line.
+    pub fn preview(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let (Ok(default), Ok(settings)) = (
+            serde_json::to_value(SettingsContent::default()),
+            serde_json::to_value(&self.settings),
+        ) {
+            preview_settings(&mut String::new(), &default, &settings, &mut lines);
+        }
+        lines.sort();
+        for (context, bindings) in &self.keymap.sections {
+            for (keystrokes, action) in bindings {
+                lines.push(match context {
+                    Some(context) => format!("{keystrokes} ({context}): {action}"),
+                    None => format!("{keystrokes}: {action}"),
+                });
+            }
+        }
+        lines
+    }
+}
+
+fn preview_settings(key_path: &mut String, old: &Value, new: &Value, lines: &mut Vec<String>) {
+    match new {
+        Value::Object(object) => {
+            for (key, value) in object {
+                let len = key_path.len();
+                if !key_path.is_empty() {
+                    key_path.push('.');
+                }
+                key_path.push_str(key);
+                preview_settings(key_path, old.get(key).unwrap_or(&Value::Null), value, lines);
+                key_path.truncate(len);
+            }
+        }
+        Value::Null => {}
+        new if new != old => lines.push(format!("{key_path}: {new}")),
+        _ => {}
+    }
+}
+
+/// Orders product directories such as `CLion2023.3` and `IntelliJIdea2024.1` by their version.
+fn product_version(path: &Path) -> Vec<u32> {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let version_start = name
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(name.len());
+    name[version_start..]
+        .split('.')
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+async fn xml_files(fs: &Arc<dyn Fs>, dir: &Path, depth: usize) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut dirs = vec![(dir.to_path_buf(), depth)];
+    while let Some((dir, depth)) = dirs.pop() {
+        let Ok(mut entries) = fs.read_dir(&dir).await else {
+            continue;
+        };
+        while let Some(entry) = entries.next().await {
+            let Ok(entry) = entry else { continue };
+            if entry
+                .extension()
+                .is_some_and(|extension| extension == "xml")
+            {
+                files.push(entry);
+            } else if depth > 0 && fs.is_dir(&entry).await {
+                dirs.push((entry, depth - 1));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+async fn load_xml(fs: &Arc<dyn Fs>, path: &Path) -> Result<XmlElement> {
+    let content = fs
+        .load(path)
+        .await
+        .with_context(|| format!("Error loading JetBrains settings file {}", path.display()))?;
+    parse_xml(&content)
+        .with_context(|| format!("Error parsing JetBrains settings file {}", path.display()))
+}
+
+fn collect_components(root: XmlElement, components: &mut HashMap<String, XmlElement>) {
+    for component in root.children {
+        if component.name == "component"
+            && let Some(name) = component.attribute("name")
+        {
+            components.insert(name.to_string(), component);
+        }
+    }
+}
+
+fn translate_code_style(code_style: &XmlElement, import: &mut JetBrainsImport) {
+    let defaults = &mut import.settings.project.all_languages.defaults;
+    defaults.preferred_line_length = code_style
+        .option_value("RIGHT_MARGIN")
+        .and_then(|margin| margin.parse().ok());
+    if let Some(indent_options) = code_style
+        .option("OTHER_INDENT_OPTIONS")
+        .and_then(|option| option.child("value"))
+    {
+        translate_indent_options(indent_options, defaults);
+    }
+
+    for language_style in code_style.children_named("codeStyleSettings") {
+        let Some(language) = language_style.attribute("language") else {
+            continue;
+        };
+        let mut language_settings = LanguageSettingsContent {
+            preferred_line_length: language_style
+                .option_value("RIGHT_MARGIN")
+                .and_then(|margin| margin.parse().ok()),
+            ..Default::default()
+        };
+        if let Some(indent_options) = language_style.child("indentOptions") {
+            translate_indent_options(indent_options, &mut language_settings);
+        }
+        if language_settings == LanguageSettingsContent::default() {
+            continue;
+        }
+        let Some(zed_languages) = zed_language_names(language) else {
+            import
+                .skipped
+                .push(format!("Code style for language `{language}`"));
+            continue;
+        };
+        for zed_language in zed_languages {
+            import
+                .settings
+                .languages_mut()
+                .entry(zed_language.to_string())
+                .or_default()
+                .merge_from(&language_settings);
+        }
+    }
+}
+
+fn translate_indent_options(indent_options: &XmlElement, settings: &mut LanguageSettingsContent) {
+    settings.tab_size = indent_options
+        .option_value("INDENT_SIZE")
+        .and_then(|size| size.parse().ok())
+        .and_then(NonZeroU32::new);
+    settings.hard_tabs = indent_options
+        .option_value("USE_TAB_CHARACTER")
+        .and_then(|use_tabs| use_tabs.parse().ok());
+}
+
+fn zed_language_names(jetbrains_language: &str) -> Option<&'static [&'static str]> {
+    Some(match jetbrains_language.to_lowercase().as_str() {
+        "c#" => &["C#"],
+        "css" => &["CSS"],
+        "dart" => &["Dart"],
+        "go" => &["Go"],
+        "html" => &["HTML"],
+        "java" => &["Java"],
+        "javascript" => &["JavaScript"],
+        "json" => &["JSON", "JSONC"],
+        "kotlin" => &["Kotlin"],
+        "lua" => &["Lua"],
+        "markdown" => &["Markdown"],
+        // CLion stores the C and C++ code style under this language.
+        "objectivec" => &["C", "C++"],
+        "php" => &["PHP"],
+        "python" => &["Python"],
+        "ruby" => &["Ruby"],
+        "rust" => &["Rust"],
+        "scala" => &["Scala"],
+        "shell script" => &["Shell Script"],
+        "sql" => &["SQL"],
+        "toml" => &["TOML"],
+        "typescript" => &["TypeScript", "TSX"],
+        "xml" => &["XML"],
+        "yaml" => &["YAML"],
+        _ => return None,
+    })
+}
+
+fn translate_keymap(keymap: &XmlElement, import: &mut JetBrainsImport) {
+    for action in keymap.children_named("action") {
+        let Some(id) = action.attribute("id") else {
+            continue;
+        };
+        let shortcuts = action
+            .children_named("keyboard-shortcut")
+            .collect::<Vec<_>>();
+        if shortcuts.is_empty() {
+            if action.child("mouse-shortcut").is_some() {
+                import
+                    .skipped
+                    .push(format!("`{id}`: mouse shortcuts are not supported"));
+            } else {
+                import.skipped.push(format!(
+                    "`{id}`: removing the shortcuts of the parent keymap is not supported"
+                ));
+            }
+            continue;
+        }
+        let Some((context, zed_action)) = translate_action(id) else {
+            import
+                .skipped
+                .push(format!("`{id}`: no Zed action corresponds to it"));
+            continue;
+        };
+        for shortcut in shortcuts {
+            let keystrokes = [
+                shortcut.attribute("first-keystroke"),
+                shortcut.attribute("second-keystroke"),
+            ]
+            .into_iter()
+            .flatten()
+            .map(translate_keystroke)
+            .collect::<Result<Vec<_>, _>>();
+            match keystrokes {
+                Ok(keystrokes) if !keystrokes.is_empty() => import.keymap.insert(
+                    context.map(str::to_string),
+                    keystrokes.join(" "),
+                    zed_action.clone(),
+                ),
+                Ok(_) => {}
+                Err(reason) => import.skipped.push(format!("`{id}`: {reason}")),
+            }
+        }
+    }
+}
+
+/// Translates a Java `KeyStroke` description such as `ctrl shift BACK_SPACE`.
+fn translate_keystroke(keystroke: &str) -> Result<String, String> {
+    let mut parts = keystroke.split_whitespace().collect::<Vec<_>>();
+    let key = parts.pop().ok_or_else(|| "empty keystroke".to_string())?;
+    let mut translated = String::new();
+    for modifier in parts {
+        translated.push_str(match modifier {
+            "ctrl" | "control" => "ctrl",
+            "shift" => "shift",
+            "alt" => "alt",
+            "meta" if cfg!(target_os = "macos") => "cmd",
+            "meta" => "super",
+            _ => return Err(format!("unsupported modifier `{modifier}`")),
+        });
+        translated.push('-');
+    }
+    let key = match key {
+        "ENTER" => "enter",
+        "BACK_SPACE" => "backspace",
+        "DELETE" => "delete",
+        "TAB" => "tab",
+        "ESCAPE" => "escape",
+        "SPACE" => "space",
+        "UP" => "up",
+        "DOWN" => "down",
+        "LEFT" => "left",
+        "RIGHT" => "right",
+        "HOME" => "home",
+        "END" => "end",
+        "PAGE_UP" => "pageup",
+        "PAGE_DOWN" => "pagedown",
+        "INSERT" => "insert",
+        "COMMA" => ",",
+        "PERIOD" => ".",
+        "SLASH" => "/",
+        "BACK_SLASH" => "\\",
+        "SEMICOLON" => ";",
+        "QUOTE" => "'",
+        "BACK_QUOTE" => "`",
+        "MINUS" => "-",
+        "EQUALS" => "=",
+        "OPEN_BRACKET" => "[",
+        "CLOSE_BRACKET" => "]",
+        key if key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            translated.push_str(&key.to_ascii_lowercase());
+            return Ok(translated);
+        }
+        key if key.strip_prefix('F').is_some_and(|number| {
+            number
+                .parse::<u8>()
+                .is_ok_and(|number| (1..=24).contains(&number))
+        }) =>
+        {
+            translated.push_str(&key.to_ascii_lowercase());
+            return Ok(translated);
+        }
+        _ => return Err(format!("unsupported key `{key}`")),
+    };
+    translated.push_str(key);
+    Ok(translated)
+}
+
+/// Returns the context and the Zed action corresponding to an IntelliJ action ID.
+fn translate_action(id: &str) -> Option<(Option<&'static str>, Value)> {
+    let editor_action = |action: Value| Some((Some("Editor"), action));
+    let workspace_action = |action: Value| Some((None, action));
+    match id {
+        "$Copy" => editor_action(json!("editor::Copy")),
+        "$Cut" => editor_action(json!("editor::Cut")),
+        "$Paste" => editor_action(json!("editor::Paste")),
+        "$Redo" => editor_action(json!("editor::Redo")),
+        "$SelectAll" => editor_action(json!("editor::SelectAll")),
+        "$Undo" => editor_action(json!("editor::Undo")),
+        "CodeCompletion" => editor_action(json!("editor::ShowCompletions")),
+        "CollapseAllRegions" => editor_action(json!("editor::FoldAll")),
+        "CollapseRegion" => editor_action(json!("editor::Fold")),
+        "CommentByLineComment" => editor_action(json!("editor::ToggleComments")),
+        "EditorCloneCaretAbove" => editor_action(json!("editor::AddSelectionAbove")),
+        "EditorCloneCaretBelow" => editor_action(json!("editor::AddSelectionBelow")),
+        "EditorDeleteLine" => editor_action(json!("editor::DeleteLine")),
+        "EditorDeleteToLineEnd" => editor_action(json!("editor::DeleteToEndOfLine")),
+        "EditorDuplicate" => editor_action(json!("editor::DuplicateSelection")),
+        "EditorJoinLines" => editor_action(json!("editor::JoinLines")),
+        "EditorSelectLine" => editor_action(json!("editor::SelectLine")),
+        "EditorSelectWord" => editor_action(json!("editor::SelectLargerSyntaxNode")),
+        "EditorStartNewLine" => editor_action(json!("editor::NewlineBelow")),
+        "EditorStartNewLineBefore" => editor_action(json!("editor::NewlineAbove")),
+        "EditorToggleCase" => editor_action(json!("editor::ToggleCase")),
+        "EditorToggleUseSoftWraps" => editor_action(json!("editor::ToggleSoftWrap")),
+        "EditorUnSelectWord" => editor_action(json!("editor::SelectSmallerSyntaxNode")),
+        "ExpandAllRegions" => editor_action(json!("editor::UnfoldAll")),
+        "ExpandRegion" => editor_action(json!("editor::UnfoldLines")),
+        "FindUsages" | "ShowUsages" => editor_action(json!("editor::FindAllReferences")),
+        "GotoDeclaration" => editor_action(json!("editor::GoToDefinition")),
+        "GotoImplementation" => editor_action(json!("editor::GoToImplementation")),
+        "GotoNextError" => editor_action(json!("editor::GoToDiagnostic")),
+        "GotoPreviousError" => editor_action(json!("editor::GoToPreviousDiagnostic")),
+        "GotoTypeDeclaration" => editor_action(json!("editor::GoToTypeDefinition")),
+        "MethodUp" | "MoveStatementUp" | "MoveLineUp" => editor_action(json!("editor::MoveLineUp")),
+        "MethodDown" | "MoveStatementDown" | "MoveLineDown" => {
+            editor_action(json!("editor::MoveLineDown"))
+        }
+        "OptimizeImports" => editor_action(json!("editor::OrganizeImports")),
+        "ParameterInfo" => editor_action(json!("editor::ShowSignatureHelp")),
+        "QuickJavaDoc" => editor_action(json!("editor::Hover")),
+        "ReformatCode" => editor_action(json!("editor::Format")),
+        "RenameElement" => editor_action(json!("editor::Rename")),
+        "SelectAllOccurrences" => editor_action(json!("editor::SelectAllMatches")),
+        "SelectNextOccurrence" => {
+            editor_action(json!(["editor::SelectNext", { "replace_newest": false }]))
+        }
+        "ShowIntentionActions" => editor_action(json!("editor::ToggleCodeActions")),
+        "ToggleLineBreakpoint" => editor_action(json!("editor::ToggleBreakpoint")),
+        "VcsShowNextChangeMarker" => editor_action(json!("editor::GoToHunk")),
+        "VcsShowPrevChangeMarker" => editor_action(json!("editor::GoToPreviousHunk")),
+
+        "ActivateCommitToolWindow" | "ActivateVersionControlToolWindow" => {
+            workspace_action(json!("git_panel::ToggleFocus"))
+        }
+        "ActivateProblemsViewToolWindow" => workspace_action(json!("diagnostics::Deploy")),
+        "ActivateProjectToolWindow" => workspace_action(json!("project_panel::ToggleFocus")),
+        "ActivateTerminalToolWindow" => workspace_action(json!("terminal_panel::Toggle")),
+        "Back" => workspace_action(json!("pane::GoBack")),
+        "CloseAllEditors" => workspace_action(json!("pane::CloseAllItems")),
+        "CloseContent" => workspace_action(json!("pane::CloseActiveItem")),
+        "Debug" => workspace_action(json!("debugger::Start")),
+        "Find" => workspace_action(json!("buffer_search::Deploy")),
+        "FindInPath" => workspace_action(json!("pane::DeploySearch")),
+        "FindNext" => workspace_action(json!("search::SelectNextMatch")),
+        "FindPrevious" => workspace_action(json!("search::SelectPreviousMatch")),
+        "FileStructurePopup" => workspace_action(json!("outline::Toggle")),
+        "Forward" => workspace_action(json!("pane::GoForward")),
+        "GotoAction" => workspace_action(json!("command_palette::Toggle")),
+        "GotoFile" | "SearchEverywhere" => workspace_action(json!("file_finder::Toggle")),
+        "GotoLine" => workspace_action(json!("go_to_line::Toggle")),
+        "GotoSymbol" => workspace_action(json!("project_symbols::Toggle")),
+        "HideAllWindows" => workspace_action(json!("workspace::ToggleAllDocks")),
+        "NextTab" => workspace_action(json!("pane::ActivateNextItem")),
+        "PreviousTab" => workspace_action(json!("pane::ActivatePreviousItem")),
+        "RecentFiles" | "Switcher" => workspace_action(json!("tab_switcher::Toggle")),
+        "ReopenClosedTab" => workspace_action(json!("pane::ReopenClosedItem")),
+        "Replace" => workspace_action(json!("buffer_search::DeployReplace")),
+        "Resume" => workspace_action(json!("debugger::Continue")),
+        "Rerun" => workspace_action(json!("task::Rerun")),
+        "Run" | "RunClass" => workspace_action(json!("task::Spawn")),
+        "SaveAll" => workspace_action(json!("workspace::SaveAll")),
+        "ShowSettings" => workspace_action(json!("zed::OpenSettings")),
+        "SplitHorizontally" => workspace_action(json!("pane::SplitDown")),
+        "SplitVertically" => workspace_action(json!("pane::SplitRight")),
+        "StepInto" => workspace_action(json!("debugger::StepInto")),
+        "StepOut" => workspace_action(json!("debugger::StepOut")),
+        "StepOver" => workspace_action(json!("debugger::StepOver")),
+        "Stop" => workspace_action(json!("debugger::Stop")),
+        "ToggleFullScreen" => workspace_action(json!("zed::ToggleFullScreen")),
+        _ => None,
+    }
+}
+
+/// An element of the XML files written by JetBrains IDEs. Text content is ignored, since these
+/// files store all values in attributes.
+#[derive(Debug, PartialEq)]
+struct XmlElement {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    fn from_node(node: roxmltree::Node) -> Self {
+        Self {
+            name: node.tag_name().name().to_string(),
+            attributes: node
+                .attributes()
+                .map(|attribute| (attribute.name().to_string(), attribute.value().to_string()))
+                .collect(),
+            children: node
+                .children()
+                .filter(|child| child.is_element())
+                .map(Self::from_node)
+                .collect(),
+        }
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children_named(name).next()
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Returns the `<option name="…">` child with the given name.
+    fn option(&self, name: &str) -> Option<&XmlElement> {
+        self.children_named("option")
+            .find(|option| option.attribute("name") == Some(name))
+    }
+
+    fn option_value(&self, name: &str) -> Option<&str> {
+        self.option(name)?.attribute("value")
+    }
+}
+
+fn parse_xml(source: &str) -> Result<XmlElement> {
+    let document = roxmltree::Document::parse(source)?;
+    Ok(XmlElement::from_node(document.root_element()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_xml() {
+        let root = parse_xml(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- A comment with <tags> -->
+            <application>
+              <component name="EditorSettings">
+                <option name="SOFT_WRAP_FILE_MASKS" value="*.md; *.txt &amp; &quot;more&quot;" />
+                <option name='IS_CARET_BLINKING' value='false'/>
+              </component>
+            </application>"#,
+        )
+        .unwrap();
+        let component = root.child("component").unwrap();
+        assert_eq!(component.attribute("name"), Some("EditorSettings"));
+        assert_eq!(
+            component.option_value("SOFT_WRAP_FILE_MASKS"),
+            Some(r#"*.md; *.txt & "more""#)
+        );
+        assert_eq!(component.option_value("IS_CARET_BLINKING"), Some("false"));
+
+        assert!(parse_xml("<application><component></application>").is_err());
+    }
+
+    #[test]
+    fn test_translate_jetbrains_settings() {
+        let settings = JetBrainsSettings::from_str(
+            &[
+                r#"<application>
+                  <component name="EditorSettings">
+                    <option name="IS_WHITESPACES_SHOWN" value="true" />
+                    <option name="STRIP_TRAILING_SPACES" value="None" />
+                    <option name="ARE_LINE_NUMBERS_SHOWN" value="false" />
+                  </component>
+                  <component name="CodeInsightSettings">
+                    <option name="AUTOINSERT_PAIR_BRACKET" value="false" />
+                  </component>
+                </application>"#,
+                r#"<application>
+                  <component name="DefaultFont">
+                    <option name="FONT_SIZE" value="13" />
+                    <option name="FONT_SIZE_2D" value="13.5" />
+                    <option name="FONT_FAMILY" value="JetBrains Mono" />
+                    <option name="LINE_SPACING" value="1.2" />
+                  </component>
+                </application>"#,
+            ],
+            Some(
+                r#"<keymap version="1" name="Custom" parent="$default">
+                  <action id="EditorDuplicate">
+                    <keyboard-shortcut first-keystroke="ctrl D" />
+                    <keyboard-shortcut first-keystroke="ctrl K" second-keystroke="shift D" />
+                  </action>
+                  <action id="GotoFile">
+                    <keyboard-shortcut first-keystroke="ctrl shift N" />
+                  </action>
+                  <action id="GotoDeclaration">
+                    <mouse-shortcut keystroke="ctrl button1" />
+                  </action>
+                  <action id="$Delete" />
+                  <action id="com.example.plugin.Action">
+                    <keyboard-shortcut first-keystroke="alt F12" />
+                  </action>
+                  <action id="ReformatCode">
+                    <keyboard-shortcut first-keystroke="ctrl alt NUMPAD1" />
+                  </action>
+                </keymap>"#,
+            ),
+            Some(
+                r#"<code_scheme name="Project" version="173">
+                  <option name="RIGHT_MARGIN" value="100" />
+                  <option name="OTHER_INDENT_OPTIONS">
+                    <value>
+                      <option name="INDENT_SIZE" value="2" />
+                    </value>
+                  </option>
+                  <codeStyleSettings language="ObjectiveC">
+                    <option name="RIGHT_MARGIN" value="120" />
+                    <indentOptions>
+                      <option name="INDENT_SIZE" value="8" />
+                      <option name="USE_TAB_CHARACTER" value="true" />
+                    </indentOptions>
+                  </codeStyleSettings>
+                  <codeStyleSettings language="Haxe">
+                    <option name="RIGHT_MARGIN" value="80" />
+                  </codeStyleSettings>
+                </code_scheme>"#,
+            ),
+        )
+        .unwrap();
+        let import = settings.translate();
+
+        assert_eq!(
+            import.preview(),
+            vec![
+                "base_keymap: \"JetBrains\"",
+                "buffer_font_family: \"JetBrains Mono\"",
+                "buffer_font_size: 13.5",
+                "buffer_line_height.custom: 1.2",
+                "gutter.line_numbers: false",
+                "languages.C++.hard_tabs: true",
+                "languages.C++.preferred_line_length: 120",
+                "languages.C++.tab_size: 8",
+                "languages.C.hard_tabs: true",
+                "languages.C.preferred_line_length: 120",
+                "languages.C.tab_size: 8",
+                "preferred_line_length: 100",
+                "remove_trailing_whitespace_on_save: false",
+                "show_whitespaces: \"all\"",
+                "tab_size: 2",
+                "use_autoclose: false",
+                "ctrl-d (Editor): \"editor::DuplicateSelection\"",
+                "ctrl-k shift-d (Editor): \"editor::DuplicateSelection\"",
+                "ctrl-shift-n: \"file_finder::Toggle\"",
+            ]
+        );
+        assert_eq!(
+            import.skipped,
+            vec![
+                "Code style for language `Haxe`",
+                "`GotoDeclaration`: mouse shortcuts are not supported",
+                "`$Delete`: removing the shortcuts of the parent keymap is not supported",
+                "`com.example.plugin.Action`: no Zed action corresponds to it",
+                "`ReformatCode`: unsupported key `NUMPAD1`",
+            ]
+        );
+    }
+}
@@ -695,6 +695,47 @@ impl KeymapFile {
         }
     }
 
+    /// Appends a section with the given bindings to the keymap, leaving out the bindings that a
+    /// section with the same context already has.
+    pub fn append_section(
+        mut keymap_contents: String,
+        context: Option<&str>,
+        mut bindings: IndexMap<String, Value>,
+        tab_size: usize,
+    ) -> Result<String> {
+        let keymap = Self::parse(&keymap_contents).context("Failed to parse keymap")?;
+        let context_parsed = KeyBindingContextPredicate::parse(context.unwrap_or("")).ok();
+        for section in keymap.sections() {
+            if KeyBindingContextPredicate::parse(&section.context).ok() != context_parsed {
+                continue;
+            }
+            for (keystrokes, action) in section.bindings() {
+                if bindings.get(keystrokes) == Some(&action.0) {
+                    bindings.shift_remove(keystrokes);
+                }
+            }
+        }
+        if bindings.is_empty() {
+            return Ok(keymap_contents);
+        }
+
+        let mut value = serde_json::Map::with_capacity(2);
+        if let Some(context) = context {
+            value.insert("context".to_string(), context.into());
+        }
+        value.insert(
+            "bindings".to_string(),
+            bindings
+                .into_iter()
+                .collect::<serde_json::Map<_, _>>()
+                .into(),
+        );
+        let (replace_range, replace_value) =
+            append_top_level_array_value_in_json_text(&keymap_contents, &value.into(), tab_size);
+        keymap_contents.replace_range(replace_range, &replace_value);
+        Ok(keymap_contents)
+    }
+
     pub fn update_keybinding<'a>(
         mut operation: KeybindUpdateOperation<'a>,
         mut keymap_contents: String,
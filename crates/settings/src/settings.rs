@@ -2,10 +2,12 @@ mod base_keymap_setting;
 mod content_into_gpui;
 mod editable_setting_control;
 mod editorconfig_store;
+mod jetbrains_import;
 mod keymap_file;
 mod settings_file;
 mod settings_store;
 mod vscode_import;
+mod vscode_keymap_import;
 
 pub use settings_macros::RegisterSetting;
 
@@ -37,6 +39,7 @@ pub use editable_setting_control::*;
 pub use editorconfig_store::{
     Editorconfig, EditorconfigEvent, EditorconfigProperties, EditorconfigStore,
 };
+pub use jetbrains_import::{JetBrainsImport, JetBrainsSettings};
 pub use keymap_file::{
     KeyBindingValidator, KeyBindingValidatorRegistration, KeybindSource, KeybindUpdateOperation,
     KeybindUpdateTarget, KeymapFile, KeymapFileLoadResult,
@@ -50,6 +53,9 @@ pub use settings_store::{
 };
 
 pub use vscode_import::{VsCodeSettings, VsCodeSettingsSource};
+pub use vscode_keymap_import::{
+    TranslatedKeymap, UntranslatedKeybinding, VsCodeKeybinding, VsCodeKeymap,
+};
 
 pub use keymap_file::ActionSequence;
 
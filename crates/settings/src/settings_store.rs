@@ -536,6 +536,19 @@ impl SettingsStore {
         })
     }
 
+    /// Merges settings translated from another editor into the user settings file.
+    pub fn import_settings_content(
+        &self,
+        fs: Arc<dyn Fs>,
+        settings_content: SettingsContent,
+    ) -> oneshot::Receiver<Result<()>> {
+        self.update_settings_file_inner(fs, move |old_text: String, cx: AsyncApp| {
+            Ok(cx.read_global(|store: &SettingsStore, _cx| {
+                store.new_text_for_update(old_text, |content| content.merge_from(&settings_content))
+            }))
+        })
+    }
+
     pub fn get_all_files(&self) -> Vec<SettingsFile> {
         let mut files = Vec::from_iter(
             self.local_settings
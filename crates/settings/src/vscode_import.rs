@@ -191,6 +191,7 @@ impl VsCodeSettings {
             image_viewer: None,
             journal: None,
             language_models: None,
+            large_file_viewer: None,
             line_indicator_format: None,
             log: None,
             message_editor: None,
@@ -206,6 +207,7 @@ impl VsCodeSettings {
             repl: None,
             server_url: None,
             session: None,
+            spell_check: None,
             status_bar: self.status_bar_settings_content(),
             tab_bar: self.tab_bar_settings_content(),
             tabs: self.item_settings_content(),
@@ -213,6 +215,7 @@ impl VsCodeSettings {
             terminal: self.terminal_settings_content(),
             theme: Box::new(self.theme_settings_content()),
             title_bar: None,
+            undo_tree_panel: None,
             vim: None,
             vim_mode: None,
             workspace: self.workspace_settings_content(),
@@ -455,6 +458,14 @@ impl VsCodeSettings {
             preferred_line_length: self.read_u32("editor.wordWrapColumn"),
             prettier: None,
             remove_trailing_whitespace_on_save: self.read_bool("editor.trimAutoWhitespace"),
+            rulers: self
+                .read_value("editor.rulers")
+                .and_then(|v| v.as_array())
+                .map(|v| {
+                    v.iter()
+                        .flat_map(|ruler| serde_json::from_value(ruler.clone()).ok())
+                        .collect()
+                }),
             show_completion_documentation: None,
             colorize_brackets: self.read_bool("editor.bracketPairColorization.enabled"),
             show_completions_on_input: self.read_bool("editor.suggestOnTriggerCharacters"),
@@ -468,6 +479,8 @@ impl VsCodeSettings {
                     _ => ShowWhitespaceSetting::None,
                 })
             }),
+            show_editorconfig_ruler: None,
+            show_rulers: None,
             show_wrap_guides: None,
             soft_wrap: self.read_enum("editor.wordWrap", |s| match s {
                 "on" => Some(SoftWrap::EditorWidth),
@@ -488,14 +501,7 @@ impl VsCodeSettings {
             use_autoclose: None,
             use_on_type_format: self.read_bool("editor.formatOnType"),
             whitespace_map: None,
-            wrap_guides: self
-                .read_value("editor.rulers")
-                .and_then(|v| v.as_array())
-                .map(|v| {
-                    v.iter()
-                        .flat_map(|n| n.as_u64().map(|n| n as usize))
-                        .collect()
-                }),
+            wrap_guides: None,
             word_diff_enabled: None,
         }
     }
@@ -845,6 +851,7 @@ impl VsCodeSettings {
                 _ => None,
             }),
             drop_target_size: None,
+            local_history: self.read_bool("workbench.localHistory.enabled"),
             // workbench.editor.limit contains "enabled", "value", and "perEditorGroup"
             // our semantics match if those are set to true, some N, and true respectively.
             // we'll ignore "perEditorGroup" for now since we only support a global max
@@ -857,6 +864,7 @@ impl VsCodeSettings {
             on_last_window_closed: None,
             pane_split_direction_horizontal: None,
             pane_split_direction_vertical: None,
+            persistent_undo: None,
             resize_all_panels_in_dock: None,
             restore_on_file_reopen: self.read_bool("workbench.editor.restoreViewState"),
             restore_on_startup: None,
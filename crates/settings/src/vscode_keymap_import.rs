@@ -0,0 +1,804 @@
+use crate::{KeymapFile, VsCodeSettingsSource, infer_json_indent_size};
+use anyhow::{Context as _, Result, anyhow};
+use collections::IndexMap;
+use fs::Fs;
+use gpui::{KeyBindingContextPredicate, Keystroke};
+use paths::{cursor_keybindings_file_paths, vscode_keybindings_file_paths};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{path::Path, sync::Arc};
+
+/// A binding from a VS Code `keybindings.json` file.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct VsCodeKeybinding {
+    pub key: String,
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub when: Option<String>,
+    #[serde(default)]
+    pub args: Option<Value>,
+}
+
+/// A VS Code binding that could not be translated into a Zed binding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UntranslatedKeybinding {
+    pub binding: VsCodeKeybinding,
+    pub reason: String,
+}
+
+/// The Zed equivalent of a VS Code `keybindings.json` file.
+#[derive(Debug, Default, PartialEq)]
+pub struct TranslatedKeymap {
+    /// Translated bindings by context, in the order their contexts first appeared in.
+    pub(crate) sections: IndexMap<Option<String>, IndexMap<String, Value>>,
+    pub untranslated: Vec<UntranslatedKeybinding>,
+}
+
+impl TranslatedKeymap {
+    pub fn translated_count(&self) -> usize {
+        self.sections.values().map(IndexMap::len).sum()
+    }
+
+    pub(crate) fn insert(&mut self, context: Option<String>, keystrokes: String, action: Value) {
+        let bindings = self.sections.entry(context).or_default();
+        if action.is_null() {
+            // Removing a default binding must not undo a binding made earlier in the file.
+            bindings.entry(keystrokes).or_insert(action);
+        } else {
+            bindings.insert(keystrokes, action);
+        }
+    }
+
+    /// Appends the translated bindings to the given keymap file contents.
+    pub fn apply(&self, mut keymap_contents: String, tab_size: usize) -> Result<String> {
+        for (context, bindings) in &self.sections {
+            keymap_contents = KeymapFile::append_section(
+                keymap_contents,
+                context.as_deref(),
+                bindings.clone(),
+                tab_size,
+            )?;
+        }
+        Ok(keymap_contents)
+    }
+
+    /// Appends the translated bindings to the user keymap file.
+    pub async fn write_to_user_keymap(&self, fs: Arc<dyn Fs>) -> Result<()> {
+        let keymap_contents = KeymapFile::load_keymap_file(&fs)
+            .await
+            .context("Failed to load keymap file")?;
+        let tab_size = infer_json_indent_size(&keymap_contents);
+        let keymap_contents = self.apply(keymap_contents, tab_size)?;
+        fs.write(paths::keymap_file(), keymap_contents.as_bytes())
+            .await
+            .context("Failed to write keymap file")
+    }
+}
+
+pub struct VsCodeKeymap {
+    pub source: VsCodeSettingsSource,
+    pub path: Arc<Path>,
+    bindings: Vec<VsCodeKeybinding>,
+}
+
+impl VsCodeKeymap {
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn from_str(content: &str, source: VsCodeSettingsSource) -> Result<Self> {
+        Ok(Self {
+            source,
+            path: Path::new("/example-path/Code/User/keybindings.json").into(),
+            bindings: serde_json_lenient::from_str(content)?,
+        })
+    }
+
+    pub async fn load_user_keymap(source: VsCodeSettingsSource, fs: Arc<dyn Fs>) -> Result<Self> {
+        let candidate_paths = match source {
+            VsCodeSettingsSource::VsCode => vscode_keybindings_file_paths(),
+            VsCodeSettingsSource::Cursor => cursor_keybindings_file_paths(),
+        };
+        let mut path = None;
+        for candidate_path in candidate_paths.iter() {
+            if fs.is_file(candidate_path).await {
+                path = Some(candidate_path.clone());
+            }
+        }
+        let Some(path) = path else {
+            return Err(anyhow!(
+                "No keybindings file found, expected to find it in one of the following paths:\n{}",
+                candidate_paths
+                    .into_iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ));
+        };
+        let content = fs.load(&path).await.with_context(|| {
+            format!(
+                "Error loading {} keybindings file from {}",
+                source,
+                path.display()
+            )
+        })?;
+        let bindings = serde_json_lenient::from_str(&content).with_context(|| {
+            format!(
+                "Error parsing {} keybindings file from {}",
+                source,
+                path.display()
+            )
+        })?;
+        Ok(Self {
+            source,
+            path: path.into(),
+            bindings,
+        })
+    }
+
+    pub fn translate(&self) -> TranslatedKeymap {
+        let mut keymap = TranslatedKeymap::default();
+        for binding in &self.bindings {
+            match translate_binding(binding) {
+                Ok((context, keystrokes, action)) => keymap.insert(context, keystrokes, action),
+                Err(reason) => keymap.untranslated.push(UntranslatedKeybinding {
+                    binding: binding.clone(),
+                    reason,
+                }),
+            }
+        }
+        keymap
+    }
+}
+
+fn translate_binding(
+    binding: &VsCodeKeybinding,
+) -> Result<(Option<String>, String, Value), String> {
+    let keystrokes = translate_keystrokes(&binding.key)?;
+    // A leading `-` removes the default binding of the command.
+    let action = match binding.command.strip_prefix('-') {
+        Some(command) => {
+            translate_command(command, None)?;
+            Value::Null
+        }
+        None => translate_command(&binding.command, binding.args.as_ref())?,
+    };
+    let context = match binding.when.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(when) => match translate_when(&parse_when(when)?)? {
+            Condition::Always => None,
+            Condition::Never => {
+                return Err("its `when` clause never holds in Zed".to_string());
+            }
+            Condition::Predicate(predicate) => Some(predicate_to_string(&predicate)),
+        },
+    };
+    Ok((context, keystrokes, action))
+}
+
+fn translate_keystrokes(key: &str) -> Result<String, String> {
+    let mut keystrokes = Vec::new();
+    for chord in key.split_whitespace() {
+        let chord = chord.to_lowercase();
+        let (modifiers, key) = if chord == "+" {
+            ("", "+")
+        } else if let Some(modifiers) = chord.strip_suffix("++") {
+            (modifiers, "+")
+        } else {
+            chord.rsplit_once('+').unwrap_or(("", chord.as_str()))
+        };
+
+        let mut keystroke = String::new();
+        for modifier in modifiers.split('+').filter(|modifier| !modifier.is_empty()) {
+            let modifier = match modifier {
+                "ctrl" | "shift" | "alt" | "cmd" => modifier,
+                "meta" if cfg!(target_os = "macos") => "cmd",
+                "meta" | "win" => "super",
+                _ => return Err(format!("unknown modifier `{modifier}`")),
+            };
+            keystroke.push_str(modifier);
+            keystroke.push('-');
+        }
+
+        let is_known_key = match key {
+            "escape" | "enter" | "tab" | "space" | "backspace" | "delete" | "insert" | "home"
+            | "end" | "pageup" | "pagedown" | "up" | "down" | "left" | "right" => true,
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c.is_ascii_graphic(),
+                    _ => key
+                        .strip_prefix('f')
+                        .and_then(|number| number.parse::<u8>().ok())
+                        .is_some_and(|number| (1..=24).contains(&number)),
+                }
+            }
+        };
+        if !is_known_key {
+            return Err(format!("unsupported key `{key}`"));
+        }
+        keystroke.push_str(key);
+
+        Keystroke::parse(&keystroke).map_err(|error| error.to_string())?;
+        keystrokes.push(keystroke);
+    }
+    if keystrokes.is_empty() {
+        return Err("it has no keys".to_string());
+    }
+    Ok(keystrokes.join(" "))
+}
+
+fn translate_command(command: &str, args: Option<&Value>) -> Result<Value, String> {
+    if let Some(args) = args {
+        return match (command, args) {
+            ("workbench.action.terminal.sendSequence", Value::Object(args)) => {
+                match args.get("text") {
+                    Some(Value::String(text)) => Ok(json!(["terminal::SendText", text])),
+                    _ => Err("its arguments have no `text`".to_string()),
+                }
+            }
+            ("workbench.action.tasks.runTask", Value::String(task_name)) => {
+                Ok(json!(["task::Spawn", { "task_name": task_name }]))
+            }
+            _ => Err(format!("arguments of `{command}` are not supported")),
+        };
+    }
+
+    let action = match command {
+        "workbench.action.quickOpen" => "file_finder::Toggle",
+        "workbench.action.showCommands" => "command_palette::Toggle",
+        "workbench.action.files.save" => "workspace::Save",
+        "workbench.action.files.saveAll" => "workspace::SaveAll",
+        "workbench.action.files.newUntitledFile" => "workspace::NewFile",
+        "workbench.action.newWindow" => "workspace::NewWindow",
+        "workbench.action.closeWindow" => "workspace::CloseWindow",
+        "workbench.action.reloadWindow" => "workspace::Reload",
+        "workbench.action.openRecent" | "workbench.action.quickOpenRecent" => {
+            "projects::OpenRecent"
+        }
+        "workbench.action.closeActiveEditor" => "pane::CloseActiveItem",
+        "workbench.action.closeAllEditors" => "pane::CloseAllItems",
+        "workbench.action.reopenClosedEditor" => "pane::ReopenClosedItem",
+        "workbench.action.nextEditor" => "pane::ActivateNextItem",
+        "workbench.action.previousEditor" => "pane::ActivatePreviousItem",
+        "workbench.action.splitEditor" | "workbench.action.splitEditorRight" => "pane::SplitRight",
+        "workbench.action.splitEditorDown" => "pane::SplitDown",
+        "workbench.action.focusNextGroup" => "workspace::ActivateNextPane",
+        "workbench.action.focusPreviousGroup" => "workspace::ActivatePreviousPane",
+        "workbench.action.navigateBack" => "pane::GoBack",
+        "workbench.action.navigateForward" => "pane::GoForward",
+        "workbench.action.toggleSidebarVisibility" => "workspace::ToggleLeftDock",
+        "workbench.action.toggleAuxiliaryBar" => "workspace::ToggleRightDock",
+        "workbench.action.togglePanel" => "workspace::ToggleBottomDock",
+        "workbench.action.toggleFullScreen" => "zed::ToggleFullScreen",
+        "workbench.action.zoomIn" => "zed::IncreaseUiFontSize",
+        "workbench.action.zoomOut" => "zed::DecreaseUiFontSize",
+        "workbench.action.zoomReset" => "zed::ResetUiFontSize",
+        "workbench.action.openSettings" => "zed::OpenSettings",
+        "workbench.action.openGlobalKeybindings" => "zed::OpenKeymap",
+        "workbench.action.terminal.toggleTerminal" => "terminal_panel::Toggle",
+        "workbench.action.terminal.new" => "workspace::NewTerminal",
+        "workbench.view.explorer" => "project_panel::ToggleFocus",
+        "workbench.view.scm" => "git_panel::ToggleFocus",
+        "workbench.actions.view.problems" => "diagnostics::Deploy",
+        "workbench.action.findInFiles" => "pane::DeploySearch",
+        "workbench.action.gotoLine" => "go_to_line::Toggle",
+        "workbench.action.gotoSymbol" => "outline::Toggle",
+        "workbench.action.showAllSymbols" => "project_symbols::Toggle",
+        "workbench.action.tasks.runTask" => "task::Spawn",
+        "workbench.action.tasks.reRunTask" => "task::Rerun",
+        "workbench.action.debug.start" => "debugger::Start",
+        "workbench.action.debug.continue" => "debugger::Continue",
+        "workbench.action.debug.stepOver" => "debugger::StepOver",
+        "workbench.action.debug.stepInto" => "debugger::StepInto",
+        "workbench.action.debug.stepOut" => "debugger::StepOut",
+        "workbench.action.debug.stop" => "debugger::Stop",
+        "editor.debug.action.toggleBreakpoint" => "editor::ToggleBreakpoint",
+        "undo" => "editor::Undo",
+        "redo" => "editor::Redo",
+        "cursorUndo" => "editor::UndoSelection",
+        "editor.action.clipboardCopyAction" => "editor::Copy",
+        "editor.action.clipboardCutAction" => "editor::Cut",
+        "editor.action.clipboardPasteAction" => "editor::Paste",
+        "editor.action.selectAll" => "editor::SelectAll",
+        "expandLineSelection" => "editor::SelectLine",
+        "cursorHome" => "editor::MoveToBeginningOfLine",
+        "cursorEnd" => "editor::MoveToEndOfLine",
+        "cursorTop" => "editor::MoveToBeginning",
+        "cursorBottom" => "editor::MoveToEnd",
+        "cursorWordLeft" => "editor::MoveToPreviousWordStart",
+        "cursorWordRight" | "cursorWordEndRight" => "editor::MoveToNextWordEnd",
+        "deleteWordLeft" => "editor::DeleteToPreviousWordStart",
+        "deleteWordRight" => "editor::DeleteToNextWordEnd",
+        "editor.action.commentLine" => "editor::ToggleComments",
+        "editor.action.copyLinesDownAction" => "editor::DuplicateLineDown",
+        "editor.action.copyLinesUpAction" => "editor::DuplicateLineUp",
+        "editor.action.moveLinesDownAction" => "editor::MoveLineDown",
+        "editor.action.moveLinesUpAction" => "editor::MoveLineUp",
+        "editor.action.deleteLines" => "editor::DeleteLine",
+        "editor.action.joinLines" => "editor::JoinLines",
+        "editor.action.insertLineAfter" => "editor::NewlineBelow",
+        "editor.action.insertLineBefore" => "editor::NewlineAbove",
+        "editor.action.indentLines" => "editor::Indent",
+        "editor.action.outdentLines" => "editor::Outdent",
+        "editor.action.sortLinesAscending" => "editor::SortLinesCaseSensitive",
+        "editor.action.transformToUppercase" => "editor::ConvertToUpperCase",
+        "editor.action.transformToLowercase" => "editor::ConvertToLowerCase",
+        "editor.action.addSelectionToNextFindMatch" => {
+            return Ok(json!(["editor::SelectNext", { "replace_newest": false }]));
+        }
+        "editor.action.moveSelectionToNextFindMatch" => {
+            return Ok(json!(["editor::SelectNext", { "replace_newest": true }]));
+        }
+        "editor.action.selectHighlights" => "editor::SelectAllMatches",
+        "editor.action.insertCursorAbove" => "editor::AddSelectionAbove",
+        "editor.action.insertCursorBelow" => "editor::AddSelectionBelow",
+        "editor.action.smartSelect.expand" => "editor::SelectLargerSyntaxNode",
+        "editor.action.smartSelect.shrink" => "editor::SelectSmallerSyntaxNode",
+        "editor.action.jumpToBracket" => "editor::MoveToEnclosingBracket",
+        "editor.action.formatDocument" => "editor::Format",
+        "editor.action.rename" => "editor::Rename",
+        "editor.action.revealDefinition" => "editor::GoToDefinition",
+        "editor.action.goToDeclaration" => "editor::GoToDeclaration",
+        "editor.action.revealDefinitionAside" => "editor::GoToDefinitionSplit",
+        "editor.action.goToImplementation" => "editor::GoToImplementation",
+        "editor.action.goToTypeDefinition" => "editor::GoToTypeDefinition",
+        "editor.action.goToReferences" | "references-view.findReferences" => {
+            "editor::FindAllReferences"
+        }
+        "editor.action.showHover" => "editor::Hover",
+        "editor.action.quickFix" => "editor::ToggleCodeActions",
+        "editor.action.triggerSuggest" => "editor::ShowCompletions",
+        "editor.action.triggerParameterHints" => "editor::ShowSignatureHelp",
+        "editor.action.marker.next" | "editor.action.marker.nextInFiles" => {
+            "editor::GoToDiagnostic"
+        }
+        "editor.action.marker.prev" | "editor.action.marker.prevInFiles" => {
+            "editor::GoToPreviousDiagnostic"
+        }
+        "editor.action.dirtydiff.next" | "workbench.action.editor.nextChange" => "editor::GoToHunk",
+        "editor.action.dirtydiff.previous" | "workbench.action.editor.previousChange" => {
+            "editor::GoToPreviousHunk"
+        }
+        "editor.action.toggleWordWrap" => "editor::ToggleSoftWrap",
+        "editor.action.inlayHints.toggle" => "editor::ToggleInlayHints",
+        "editor.fold" => "editor::Fold",
+        "editor.unfold" => "editor::UnfoldLines",
+        "editor.toggleFold" => "editor::ToggleFold",
+        "editor.foldAll" => "editor::FoldAll",
+        "editor.unfoldAll" => "editor::UnfoldAll",
+        "actions.find" => "buffer_search::Deploy",
+        "editor.action.startFindReplaceAction" => "buffer_search::DeployReplace",
+        "editor.action.nextMatchFindAction" => "search::SelectNextMatch",
+        "editor.action.previousMatchFindAction" => "search::SelectPreviousMatch",
+        _ => {
+            if let Some(index) = command
+                .strip_prefix("workbench.action.openEditorAtIndex")
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|index| (1..=9).contains(index))
+            {
+                return Ok(json!(["pane::ActivateItem", index - 1]));
+            }
+            const GROUPS: [&str; 8] = [
+                "First", "Second", "Third", "Fourth", "Fifth", "Sixth", "Seventh", "Eighth",
+            ];
+            if let Some(index) = command
+                .strip_prefix("workbench.action.focus")
+                .and_then(|group| group.strip_suffix("EditorGroup"))
+                .and_then(|group| GROUPS.iter().position(|name| *name == group))
+            {
+                return Ok(json!(["workspace::ActivatePane", index]));
+            }
+            return Err(format!("no Zed action corresponds to `{command}`"));
+        }
+    };
+    Ok(Value::String(action.to_string()))
+}
+
+/// A parsed VS Code `when` clause.
+#[derive(Debug, PartialEq)]
+enum When {
+    Key(String),
+    Equal(String, String),
+    NotEqual(String, String),
+    Not(Box<When>),
+    And(Box<When>, Box<When>),
+    Or(Box<When>, Box<When>),
+}
+
+fn parse_when(source: &str) -> Result<When, String> {
+    let tokens = tokenize_when(source)?;
+    let mut parser = WhenParser {
+        tokens: &tokens,
+        position: 0,
+    };
+    let expression = parser.parse_or()?;
+    match parser.tokens.get(parser.position) {
+        None => Ok(expression),
+        Some(token) => Err(format!("unexpected `{token}` in its `when` clause")),
+    }
+}
+
+fn tokenize_when(source: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' | ')' => tokens.push(c.to_string()),
+            '\'' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '\'')) => break,
+                        Some((_, c)) => value.push(c),
+                        None => return Err("unterminated string in its `when` clause".into()),
+                    }
+                }
+                // Quoted values are always compared against, never operators.
+                tokens.push(format!("'{value}"));
+            }
+            '&' | '|' | '=' | '!' | '<' | '>' => {
+                let mut end = start + c.len_utf8();
+                while let Some((index, next)) = chars.peek().copied() {
+                    if !matches!(next, '&' | '|' | '=' | '~' | '<' | '>') {
+                        break;
+                    }
+                    // `!` only starts a negation, as in `a && !b`.
+                    if c == '!' && next != '=' {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+                tokens.push(source[start..end].to_string());
+            }
+            _ => {
+                let mut end = start + c.len_utf8();
+                while let Some((index, next)) = chars.peek().copied() {
+                    if next.is_whitespace() || "()&|=!<>'".contains(next) {
+                        break;
+                    }
+                    end = index + next.len_utf8();
+                    chars.next();
+                }
+                tokens.push(source[start..end].to_string());
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct WhenParser<'a> {
+    tokens: &'a [String],
+    position: usize,
+}
+
+impl<'a> WhenParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn parse_or(&mut self) -> Result<When, String> {
+        let mut expression = self.parse_and()?;
+        while self.peek() == Some("||") {
+            self.position += 1;
+            expression = When::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<When, String> {
+        let mut expression = self.parse_unary()?;
+        while self.peek() == Some("&&") {
+            self.position += 1;
+            expression = When::And(Box::new(expression), Box::new(self.parse_unary()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_unary(&mut self) -> Result<When, String> {
+        match self.next() {
+            Some("!") => Ok(When::Not(Box::new(self.parse_unary()?))),
+            Some("(") => {
+                let expression = self.parse_or()?;
+                match self.next() {
+                    Some(")") => Ok(expression),
+                    _ => Err("unbalanced parentheses in its `when` clause".to_string()),
+                }
+            }
+            Some(token) if is_operand(token) => {
+                let key = token.to_string();
+                match self.peek() {
+                    Some("==" | "===") => {
+                        self.position += 1;
+                        Ok(When::Equal(key, self.parse_value()?))
+                    }
+                    Some("!=" | "!==") => {
+                        self.position += 1;
+                        Ok(When::NotEqual(key, self.parse_value()?))
+                    }
+                    Some(operator @ ("=~" | "<" | ">" | "<=" | ">=" | "in" | "not")) => Err(
+                        format!("the `{operator}` operator in its `when` clause is not supported"),
+                    ),
+                    _ => Ok(When::Key(key)),
+                }
+            }
+            Some(token) => Err(format!("unexpected `{token}` in its `when` clause")),
+            None => Err("its `when` clause is incomplete".to_string()),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(token) if is_operand(token) => {
+                Ok(token.strip_prefix('\'').unwrap_or(token).to_string())
+            }
+            _ => Err("a comparison in its `when` clause has no value".to_string()),
+        }
+    }
+}
+
+fn is_operand(token: &str) -> bool {
+    !matches!(token, "(" | ")") && !token.starts_with(['&', '|', '=', '!', '<', '>'])
+}
+
+enum Condition {
+    Always,
+    Never,
+    Predicate(KeyBindingContextPredicate),
+}
+
+fn translate_when(when: &When) -> Result<Condition, String> {
+    Ok(match when {
+        When::Key(key) => translate_when_key(key)?,
+        When::Equal(key, value) => translate_when_equal(key, value)?,
+        When::NotEqual(key, value) => negate(translate_when_equal(key, value)?),
+        When::Not(when) => negate(translate_when(when)?),
+        When::And(left, right) => match (translate_when(left)?, translate_when(right)?) {
+            (Condition::Never, _) | (_, Condition::Never) => Condition::Never,
+            (Condition::Always, condition) | (condition, Condition::Always) => condition,
+            (Condition::Predicate(left), Condition::Predicate(right)) => Condition::Predicate(
+                KeyBindingContextPredicate::And(Box::new(left), Box::new(right)),
+            ),
+        },
+        When::Or(left, right) => match (translate_when(left)?, translate_when(right)?) {
+            (Condition::Always, _) | (_, Condition::Always) => Condition::Always,
+            (Condition::Never, condition) | (condition, Condition::Never) => condition,
+            (Condition::Predicate(left), Condition::Predicate(right)) => Condition::Predicate(
+                KeyBindingContextPredicate::Or(Box::new(left), Box::new(right)),
+            ),
+        },
+    })
+}
+
+fn negate(condition: Condition) -> Condition {
+    match condition {
+        Condition::Always => Condition::Never,
+        Condition::Never => Condition::Always,
+        Condition::Predicate(predicate) => {
+            Condition::Predicate(KeyBindingContextPredicate::Not(Box::new(predicate)))
+        }
+    }
+}
+
+fn translate_when_key(key: &str) -> Result<Condition, String> {
+    let predicate = match key {
+        "true" => return Ok(Condition::Always),
+        "false" => return Ok(Condition::Never),
+        // Zed keeps edits out of read-only editors rather than disabling their bindings.
+        "editorReadonly" => return Ok(Condition::Never),
+        "editorTextFocus" | "editorFocus" => "Editor && mode == full",
+        "textInputFocus" | "inputFocus" => "Editor",
+        "terminalFocus" => "Terminal",
+        "filesExplorerFocus" | "explorerViewletFocus" => "ProjectPanel",
+        "searchViewletFocus" => "ProjectSearchBar",
+        "findWidgetVisible" | "findInputFocussed" => "BufferSearchBar",
+        "inQuickOpen" => "Picker",
+        "suggestWidgetVisible" => "Editor && showing_completions",
+        "codeActionMenuVisible" => "Editor && showing_code_actions",
+        "parameterHintsVisible" => "Editor && showing_signature_help",
+        "renameInputVisible" => "Editor && renaming",
+        "inSnippetMode" => "Editor && in_snippet",
+        "hasNextTabstop" => "Editor && has_next_tabstop",
+        "hasPrevTabstop" => "Editor && has_previous_tabstop",
+        "inlineSuggestionVisible" | "inlineEditIsVisible" => "Editor && edit_prediction",
+        "isMac" => "os == macos",
+        "isLinux" => "os == linux",
+        "isWindows" => "os == windows",
+        // Whether a language server provides a feature is decided by Zed when the action runs.
+        _ if key.starts_with("editorHas") && key.ends_with("Provider") => {
+            return Ok(Condition::Always);
+        }
+        _ => return Err(format!("its `when` clause uses the unsupported `{key}`")),
+    };
+    Ok(Condition::Predicate(
+        KeyBindingContextPredicate::parse(predicate).map_err(|error| error.to_string())?,
+    ))
+}
+
+fn translate_when_equal(key: &str, value: &str) -> Result<Condition, String> {
+    let extensions: &[&str] = match (key, value) {
+        ("resourceExtname", extension) => &[extension.trim_start_matches('.')],
+        ("editorLangId", "rust") => &["rs"],
+        ("editorLangId", "python") => &["py"],
+        ("editorLangId", "javascript") => &["js", "mjs", "cjs"],
+        ("editorLangId", "javascriptreact") => &["jsx"],
+        ("editorLangId", "typescript") => &["ts", "mts", "cts"],
+        ("editorLangId", "typescriptreact") => &["tsx"],
+        ("editorLangId", "go") => &["go"],
+        ("editorLangId", "c") => &["c", "h"],
+        ("editorLangId", "cpp") => &["cpp", "cc", "cxx", "hpp", "hh"],
+        ("editorLangId", "csharp") => &["cs"],
+        ("editorLangId", "java") => &["java"],
+        ("editorLangId", "kotlin") => &["kt", "kts"],
+        ("editorLangId", "swift") => &["swift"],
+        ("editorLangId", "ruby") => &["rb"],
+        ("editorLangId", "php") => &["php"],
+        ("editorLangId", "lua") => &["lua"],
+        ("editorLangId", "elixir") => &["ex", "exs"],
+        ("editorLangId", "haskell") => &["hs"],
+        ("editorLangId", "zig") => &["zig"],
+        ("editorLangId", "shellscript") => &["sh", "bash", "zsh"],
+        ("editorLangId", "html") => &["html", "htm"],
+        ("editorLangId", "css") => &["css"],
+        ("editorLangId", "json" | "jsonc") => &["json", "jsonc"],
+        ("editorLangId", "markdown") => &["md", "markdown"],
+        ("editorLangId", "yaml") => &["yaml", "yml"],
+        ("editorLangId", "toml") => &["toml"],
+        ("editorLangId", "sql") => &["sql"],
+        _ => {
+            return Err(format!(
+                "its `when` clause compares the unsupported `{key}` to `{value}`"
+            ));
+        }
+    };
+    let extensions = extensions
+        .iter()
+        .map(|extension| format!("extension == {extension}"))
+        .collect::<Vec<_>>()
+        .join(" || ");
+    let predicate = KeyBindingContextPredicate::parse(&format!("Editor && ({extensions})"))
+        .map_err(|error| error.to_string())?;
+    Ok(Condition::Predicate(predicate))
+}
+
+fn predicate_to_string(predicate: &KeyBindingContextPredicate) -> String {
+    let text = predicate.to_string();
+    match predicate {
+        KeyBindingContextPredicate::And(..) | KeyBindingContextPredicate::Or(..) => {
+            text[1..text.len() - 1].to_string()
+        }
+        _ => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use unindent::Unindent;
+
+    #[test]
+    fn test_translate_vscode_keymap() {
+        let keymap = VsCodeKeymap::from_str(
+            r#"[
+                // Comments are allowed.
+                { "key": "ctrl+shift+k", "command": "editor.action.deleteLines", "when": "editorTextFocus && !editorReadonly" },
+                { "key": "ctrl+k ctrl+c", "command": "editor.action.commentLine" },
+                { "key": "ctrl+f12", "command": "editor.action.goToDeclaration" },
+                { "key": "ctrl+d", "command": "-editor.action.addSelectionToNextFindMatch", "when": "editorFocus" },
+                { "key": "ctrl+d", "command": "editor.action.copyLinesDownAction", "when": "editorFocus" },
+                { "key": "ctrl+k", "command": "workbench.action.terminal.sendSequence", "args": { "text": "clear\n" }, "when": "terminalFocus" },
+                { "key": "alt+r", "command": "editor.action.formatDocument", "when": "editorLangId == rust || resourceExtname == '.toml'" },
+                { "key": "f5", "command": "myExtension.run" },
+                { "key": "numpad1", "command": "workbench.action.quickOpen" },
+                { "key": "ctrl+q", "command": "workbench.action.quickOpen", "when": "config.editor.enabled" },
+                { "key": "ctrl+w", "command": "workbench.action.closeActiveEditor", "when": "editorReadonly" },
+            ]"#,
+            VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+        let translated = keymap.translate();
+
+        assert_eq!(
+            translated
+                .sections
+                .iter()
+                .map(|(context, bindings)| (
+                    context.as_deref(),
+                    bindings
+                        .iter()
+                        .map(|(keystrokes, action)| (keystrokes.as_str(), action.to_string()))
+                        .collect::<Vec<_>>()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Some("Editor && mode == full"),
+                    vec![
+                        ("ctrl-shift-k", r#""editor::DeleteLine""#.to_string()),
+                        ("ctrl-d", r#""editor::DuplicateLineDown""#.to_string()),
+                    ]
+                ),
+                (
+                    None,
+                    vec![
+                        ("ctrl-k ctrl-c", r#""editor::ToggleComments""#.to_string()),
+                        ("ctrl-f12", r#""editor::GoToDeclaration""#.to_string()),
+                    ]
+                ),
+                (
+                    Some("Terminal"),
+                    vec![("ctrl-k", r#"["terminal::SendText","clear\n"]"#.to_string())]
+                ),
+                (
+                    Some("(Editor && extension == rs) || (Editor && extension == toml)"),
+                    vec![("alt-r", r#""editor::Format""#.to_string())]
+                ),
+            ]
+        );
+        assert_eq!(translated.translated_count(), 6);
+        assert_eq!(
+            translated
+                .untranslated
+                .iter()
+                .map(|untranslated| (
+                    untranslated.binding.key.as_str(),
+                    untranslated.reason.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("f5", "no Zed action corresponds to `myExtension.run`"),
+                ("numpad1", "unsupported key `numpad1`"),
+                (
+                    "ctrl+q",
+                    "its `when` clause uses the unsupported `config.editor.enabled`"
+                ),
+                ("ctrl+w", "its `when` clause never holds in Zed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_translated_keymap() {
+        let keymap = VsCodeKeymap::from_str(
+            r#"[
+                { "key": "ctrl+shift+k", "command": "editor.action.deleteLines", "when": "editorTextFocus" },
+                { "key": "ctrl+alt+p", "command": "workbench.action.showCommands" },
+            ]"#,
+            VsCodeSettingsSource::VsCode,
+        )
+        .unwrap();
+        let existing = r#"[
+            {
+                "context": "Editor && mode == full",
+                "bindings": {
+                    "ctrl-shift-k": "editor::DeleteLine"
+                }
+            }
+        ]"#
+        .unindent();
+        assert_eq!(
+            keymap.translate().apply(existing, 4).unwrap(),
+            r#"[
+                {
+                    "context": "Editor && mode == full",
+                    "bindings": {
+                        "ctrl-shift-k": "editor::DeleteLine"
+                    }
+                },
+                {
+                    "bindings": {
+                        "ctrl-alt-p": "command_palette::Toggle"
+                    }
+                }
+            ]"#
+            .unindent()
+        );
+    }
+}
@@ -126,6 +126,18 @@ pub struct AgentSettingsContent {
     /// This setting only applies to the native Zed agent. External agent servers (Claude Code, Gemini CLI, etc.)
     /// have their own permission systems and are not affected by these settings.
     pub tool_permissions: Option<ToolPermissionsContent>,
+    /// Shell commands to run at points in the native agent's lifecycle.
+    ///
+    /// Each hook receives a JSON description of the event on stdin. Hooks can
+    /// allow, deny or rewrite tool calls, and anything they print is fed back
+    /// into the thread.
+    ///
+    /// This setting only applies to the native Zed agent.
+    pub hooks: Option<AgentHooksContent>,
+    /// Confines commands run by the native agent's terminal tool to the project.
+    ///
+    /// Only supported on Linux, where it requires `bwrap` (bubblewrap) to be installed.
+    pub terminal_sandbox: Option<TerminalSandboxContent>,
 }
 
 impl AgentSettingsContent {
@@ -549,6 +561,64 @@ pub struct ToolRegexRule {
     pub case_sensitive: Option<bool>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHooksContent {
+    /// Hooks run after the model requests a tool call, before the tool runs.
+    /// They can allow the call without confirmation, deny it, or replace its input.
+    /// Default: []
+    pub pre_tool_use: Option<ExtendingVec<AgentHookContent>>,
+
+    /// Hooks run after a tool call completes.
+    /// Default: []
+    pub post_tool_use: Option<ExtendingVec<AgentHookContent>>,
+
+    /// Hooks run when a user message is submitted, before it is sent to the model.
+    /// Default: []
+    pub user_prompt_submit: Option<ExtendingVec<AgentHookContent>>,
+
+    /// Hooks run when the agent is about to end its turn.
+    /// They can block the turn from ending, sending their reason back to the model.
+    /// Default: []
+    pub stop: Option<ExtendingVec<AgentHookContent>>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHookContent {
+    /// The shell command to run. The event is written to its stdin as JSON.
+    #[serde(default)]
+    pub command: String,
+
+    /// Regex matched against the tool name. Only used by tool hooks.
+    /// Default: matches every tool
+    pub matcher: Option<String>,
+
+    /// How long to wait for the command before giving up, in seconds.
+    /// Default: 60
+    pub timeout: Option<u64>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct TerminalSandboxContent {
+    /// Whether to run agent terminal commands in a sandbox, where only system
+    /// directories can be read, and only the project's worktrees and
+    /// `writable_paths` can be written to.
+    /// Default: false
+    pub enabled: Option<bool>,
+
+    /// Whether sandboxed commands can access the network.
+    /// Default: false
+    pub allow_network: Option<bool>,
+
+    /// Additional paths sandboxed commands can read and write, such as
+    /// toolchains and package manager caches in the home directory, which is
+    /// otherwise empty. A leading `~` is expanded to the home directory.
+    /// Default: []
+    pub writable_paths: Option<ExtendingVec<String>>,
+}
+
 #[derive(
     Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, MergeFrom,
 )]
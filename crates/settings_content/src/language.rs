@@ -319,6 +319,21 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: []
     pub wrap_guides: Option<Vec<usize>>,
+    /// Columns at which to show vertical rulers in the editor, each with an
+    /// optional color. Unlike wrap guides, rulers are shown regardless of the
+    /// soft wrap mode.
+    ///
+    /// Default: []
+    pub rulers: Option<Vec<RulerContent>>,
+    /// Whether to show the rulers in the editor.
+    ///
+    /// Default: true
+    pub show_rulers: Option<bool>,
+    /// Whether to show a ruler at the `max_line_length` set in `.editorconfig`,
+    /// unless a ruler or wrap guide is already shown at that column.
+    ///
+    /// Default: true
+    pub show_editorconfig_ruler: Option<bool>,
     /// Indent guide related settings.
     pub indent_guides: Option<IndentGuideSettingsContent>,
     /// Whether or not to perform a buffer format before saving.
@@ -513,6 +528,29 @@ pub struct WhitespaceMapContent {
     pub tab: Option<char>,
 }
 
+/// A vertical ruler in the editor, given either as a column or as an object
+/// with a column and a color.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema, MergeFrom)]
+#[serde(untagged)]
+pub enum RulerContent {
+    /// A ruler at the given column, in the theme's wrap guide color.
+    Column(usize),
+    /// A ruler at the given column, in its own color.
+    Colored {
+        column: usize,
+        /// A color in hex format, such as `#ff000080`.
+        color: Option<String>,
+    },
+}
+
+impl RulerContent {
+    pub fn column(&self) -> usize {
+        match self {
+            RulerContent::Column(column) | RulerContent::Colored { column, .. } => *column,
+        }
+    }
+}
+
 /// The behavior of `editor::Rewrap`.
 #[derive(
     Debug,
@@ -655,6 +693,11 @@ pub struct CompletionSettingsContent {
     ///
     /// Default: "replace_suffix"
     pub lsp_insert_mode: Option<LspInsertMode>,
+    /// Whether to offer expansions of Emmet abbreviations, such as `ul>li*3`, as completions
+    /// in languages that support them.
+    ///
+    /// Default: true
+    pub emmet: Option<bool>,
 }
 
 #[derive(
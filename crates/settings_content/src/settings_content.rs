@@ -111,6 +111,10 @@ pub struct SettingsContent {
     /// The settings for the image viewer.
     pub image_viewer: Option<ImageViewerSettingsContent>,
 
+    pub large_file_viewer: Option<LargeFileViewerSettingsContent>,
+
+    pub spell_check: Option<SpellCheckSettingsContent>,
+
     pub repl: Option<ReplSettingsContent>,
 
     /// Whether or not to enable Helix mode.
@@ -160,6 +164,9 @@ pub struct SettingsContent {
 
     pub title_bar: Option<TitleBarSettingsContent>,
 
+    /// Configuration for the undo tree panel.
+    pub undo_tree_panel: Option<PanelSettingsContent>,
+
     /// Whether or not to enable Vim mode.
     ///
     /// Default: false
@@ -948,6 +955,41 @@ pub struct ImageViewerSettingsContent {
     pub unit: Option<ImageFileSizeUnit>,
 }
 
+/// The settings for the large file viewer.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct LargeFileViewerSettingsContent {
+    /// Files at least this many megabytes large are opened in a read-only viewer
+    /// that doesn't load them into memory, instead of in an editor.
+    ///
+    /// Default: 256
+    pub min_file_size_mb: Option<u64>,
+}
+
+/// The settings for spell checking.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to check the spelling of comments, strings and prose.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The Hunspell dictionaries to check words against, by name.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+    /// Directories to look for Hunspell dictionaries (`.aff` and `.dic` files) in,
+    /// in addition to Zed's `dictionaries` directory and the system's.
+    ///
+    /// Default: []
+    pub dictionary_directories: Option<Vec<String>>,
+    /// Languages whose text is checked in full, rather than only in comments
+    /// and strings.
+    ///
+    /// Default: ["Markdown", "Markdown-Inline", "Plain Text", "Git Commit"]
+    pub prose_languages: Option<Vec<String>>,
+}
+
 #[with_fallible_options]
 #[derive(
     Clone,
@@ -977,6 +1019,7 @@ pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
     pub wsl_connections: Option<Vec<WslConnection>>,
     pub dev_container_connections: Option<Vec<DevContainerConnection>>,
+    pub command_connections: Option<Vec<CommandConnection>>,
     pub read_ssh_config: Option<bool>,
     pub use_podman: Option<bool>,
 }
@@ -1023,6 +1066,26 @@ pub struct WslConnection {
     pub projects: BTreeSet<RemoteProject>,
 }
 
+/// A target reached by running a command that executes its trailing arguments there,
+/// such as `kubectl exec -i my-pod --` or `distrobox enter my-box --`.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom, Debug)]
+pub struct CommandConnection {
+    /// Name to use for this target in UI.
+    pub name: String,
+    /// The program to run. It must run the command given as its trailing arguments on the target,
+    /// with its standard input and output connected to Zed.
+    pub command: String,
+    /// Arguments passed to the program before the command to run on the target.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Arguments passed to the program instead of `args` for interactive commands, like
+    /// terminals, which need a TTY on the target. Defaults to `args`.
+    #[serde(default)]
+    pub interactive_args: Option<Vec<String>>,
+    #[serde(default)]
+    pub projects: BTreeSet<RemoteProject>,
+}
+
 #[with_fallible_options]
 #[derive(
     Clone, Debug, Default, Serialize, PartialEq, Eq, PartialOrd, Ord, Deserialize, JsonSchema,
@@ -58,6 +58,16 @@ pub struct WorkspaceSettingsContent {
     ///
     /// Default: true
     pub restore_on_file_reopen: Option<bool>,
+    /// Whether to keep the undo history of files after closing them, and restore it when
+    /// they are opened again with the same content, even after restarting.
+    ///
+    /// Default: false
+    pub persistent_undo: Option<bool>,
+    /// Whether to keep snapshots of files when they are opened, saved or changed on disk, to
+    /// compare them with and restore them from later.
+    ///
+    /// Default: true
+    pub local_history: Option<bool>,
     /// The size of the workspace split drop targets on the outer edges.
     /// Given as a fraction that will be multiplied by the smaller dimension of the workspace.
     ///
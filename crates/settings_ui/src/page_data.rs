@@ -1159,7 +1159,7 @@ fn appearance_page() -> SettingsPage {
         ]
     }
 
-    fn guides_section() -> [SettingsPageItem; 3] {
+    fn guides_section() -> [SettingsPageItem; 6] {
         [
             SettingsPageItem::SectionHeader("Guides"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -1210,6 +1210,74 @@ fn appearance_page() -> SettingsPage {
                 metadata: None,
                 files: USER | PROJECT,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Show Rulers",
+                description: "Show rulers (vertical lines at custom columns).",
+                field: Box::new(SettingField {
+                    json_path: Some("show_rulers"),
+                    pick: |settings_content| {
+                        settings_content
+                            .project
+                            .all_languages
+                            .defaults
+                            .show_rulers
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content.project.all_languages.defaults.show_rulers = value;
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Show Editorconfig Ruler",
+                description: "Show a ruler at the `max_line_length` set in `.editorconfig`.",
+                field: Box::new(SettingField {
+                    json_path: Some("show_editorconfig_ruler"),
+                    pick: |settings_content| {
+                        settings_content
+                            .project
+                            .all_languages
+                            .defaults
+                            .show_editorconfig_ruler
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .project
+                            .all_languages
+                            .defaults
+                            .show_editorconfig_ruler = value;
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+            // todo(settings_ui): This needs a custom component
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Rulers",
+                description: "Columns at which to show vertical rulers, with optional colors.",
+                field: Box::new(
+                    SettingField {
+                        json_path: Some("rulers"),
+                        pick: |settings_content| {
+                            settings_content
+                                .project
+                                .all_languages
+                                .defaults
+                                .rulers
+                                .as_ref()
+                        },
+                        write: |settings_content, value| {
+                            settings_content.project.all_languages.defaults.rulers = value;
+                        },
+                    }
+                    .unimplemented(),
+                ),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
         ]
     }
 
@@ -3224,7 +3292,7 @@ fn search_and_files_page() -> SettingsPage {
         ]
     }
 
-    fn file_scan_section() -> [SettingsPageItem; 5] {
+    fn file_scan_section() -> [SettingsPageItem; 7] {
         [
             SettingsPageItem::SectionHeader("File Scan"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -3286,6 +3354,32 @@ fn search_and_files_page() -> SettingsPage {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Persistent Undo",
+                description: "Keep the undo history of files after closing them.",
+                field: Box::new(SettingField {
+                    json_path: Some("persistent_undo"),
+                    pick: |settings_content| settings_content.workspace.persistent_undo.as_ref(),
+                    write: |settings_content, value| {
+                        settings_content.workspace.persistent_undo = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Local History",
+                description: "Keep snapshots of files when they are opened, saved or changed on disk.",
+                field: Box::new(SettingField {
+                    json_path: Some("local_history"),
+                    pick: |settings_content| settings_content.workspace.local_history.as_ref(),
+                    write: |settings_content, value| {
+                        settings_content.workspace.local_history = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Close on File Delete",
                 description: "Automatically close files that have been deleted.",
@@ -5284,6 +5378,70 @@ fn panels_page() -> SettingsPage {
         ]
     }
 
+    fn undo_tree_panel_section() -> [SettingsPageItem; 4] {
+        [
+            SettingsPageItem::SectionHeader("Undo Tree Panel"),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Undo Tree Panel Button",
+                description: "Show the undo tree panel button in the status bar.",
+                field: Box::new(SettingField {
+                    json_path: Some("undo_tree_panel.button"),
+                    pick: |settings_content| {
+                        settings_content.undo_tree_panel.as_ref()?.button.as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .undo_tree_panel
+                            .get_or_insert_default()
+                            .button = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Undo Tree Panel Dock",
+                description: "Where to dock the undo tree panel.",
+                field: Box::new(SettingField {
+                    json_path: Some("undo_tree_panel.dock"),
+                    pick: |settings_content| {
+                        settings_content.undo_tree_panel.as_ref()?.dock.as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .undo_tree_panel
+                            .get_or_insert_default()
+                            .dock = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Undo Tree Panel Default Width",
+                description: "Default width of the undo tree panel in pixels.",
+                field: Box::new(SettingField {
+                    json_path: Some("undo_tree_panel.default_width"),
+                    pick: |settings_content| {
+                        settings_content
+                            .undo_tree_panel
+                            .as_ref()?
+                            .default_width
+                            .as_ref()
+                    },
+                    write: |settings_content, value| {
+                        settings_content
+                            .undo_tree_panel
+                            .get_or_insert_default()
+                            .default_width = value;
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+        ]
+    }
+
     fn agent_panel_section() -> [SettingsPageItem; 5] {
         [
             SettingsPageItem::SectionHeader("Agent Panel"),
@@ -5360,6 +5518,7 @@ fn panels_page() -> SettingsPage {
             debugger_panel_section(),
             notification_panel_section(),
             collaboration_panel_section(),
+            undo_tree_panel_section(),
             agent_panel_section(),
         ],
     }
@@ -7293,7 +7452,7 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
         ]
     }
 
-    fn wrapping_section() -> [SettingsPageItem; 6] {
+    fn wrapping_section() -> [SettingsPageItem; 9] {
         [
             SettingsPageItem::SectionHeader("Wrapping"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -7379,6 +7538,70 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
                 metadata: None,
                 files: USER | PROJECT,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Show Rulers",
+                description: "Show rulers in the editor.",
+                field: Box::new(SettingField {
+                    json_path: Some("languages.$(language).show_rulers"),
+                    pick: |settings_content| {
+                        language_settings_field(settings_content, |language| {
+                            language.show_rulers.as_ref()
+                        })
+                    },
+                    write: |settings_content, value| {
+                        language_settings_field_mut(settings_content, value, |language, value| {
+                            language.show_rulers = value;
+                        })
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Show Editorconfig Ruler",
+                description: "Show a ruler at the `max_line_length` set in `.editorconfig`.",
+                field: Box::new(SettingField {
+                    json_path: Some("languages.$(language).show_editorconfig_ruler"),
+                    pick: |settings_content| {
+                        language_settings_field(settings_content, |language| {
+                            language.show_editorconfig_ruler.as_ref()
+                        })
+                    },
+                    write: |settings_content, value| {
+                        language_settings_field_mut(settings_content, value, |language, value| {
+                            language.show_editorconfig_ruler = value;
+                        })
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Rulers",
+                description: "Columns at which to show vertical rulers in the editor, with optional colors.",
+                field: Box::new(
+                    SettingField {
+                        json_path: Some("languages.$(language).rulers"),
+                        pick: |settings_content| {
+                            language_settings_field(settings_content, |language| {
+                                language.rulers.as_ref()
+                            })
+                        },
+                        write: |settings_content, value| {
+                            language_settings_field_mut(
+                                settings_content,
+                                value,
+                                |language, value| {
+                                    language.rulers = value;
+                                },
+                            )
+                        },
+                    }
+                    .unimplemented(),
+                ),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Allow Rewrap",
                 description: "Controls where the `editor::rewrap` action is allowed for this language.",
@@ -7823,7 +8046,7 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
         ]
     }
 
-    fn completions_section() -> [SettingsPageItem; 7] {
+    fn completions_section() -> [SettingsPageItem; 8] {
         [
             SettingsPageItem::SectionHeader("Completions"),
             SettingsPageItem::SettingItem(SettingItem {
@@ -7905,6 +8128,25 @@ fn language_settings_data() -> Box<[SettingsPageItem]> {
                 metadata: None,
                 files: USER | PROJECT,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Emmet",
+                description: "Whether to offer expansions of Emmet abbreviations as completions.",
+                field: Box::new(SettingField {
+                    json_path: Some("languages.$(language).completions.emmet"),
+                    pick: |settings_content| {
+                        language_settings_field(settings_content, |language| {
+                            language.completions.as_ref()?.emmet.as_ref()
+                        })
+                    },
+                    write: |settings_content, value| {
+                        language_settings_field_mut(settings_content, value, |language, value| {
+                            language.completions.get_or_insert_default().emmet = value;
+                        })
+                    },
+                }),
+                metadata: None,
+                files: USER | PROJECT,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Completion Menu Scrollbar",
                 description: "When to show the scrollbar in the completion menu.",
@@ -0,0 +1,590 @@
+use collections::{HashMap, HashSet};
+
+use anyhow::{Context as _, Result};
+
+/// A dictionary in the Hunspell format, made of an affix file (`.aff`) and a word list (`.dic`).
+///
+/// The words of the list are expanded with their prefixes and suffixes when the dictionary is
+/// loaded, so checking a word is a single lookup. Compounding rules are not supported, so
+/// dictionaries of languages that rely on them will report valid compound words as misspelled.
+pub struct Dictionary {
+    words: HashSet<String>,
+    no_suggest: HashSet<String>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum FlagFormat {
+    /// Every character is a flag.
+    Char,
+    /// Every two characters are a flag.
+    Long,
+    /// Flags are comma-separated numbers.
+    Numeric,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AffixKind {
+    Prefix,
+    Suffix,
+}
+
+struct AffixRule {
+    strip: String,
+    add: String,
+    continuation_flags: Vec<String>,
+    condition: Vec<CharClass>,
+}
+
+struct AffixGroup {
+    kind: AffixKind,
+    cross_product: bool,
+    rules: Vec<AffixRule>,
+}
+
+enum CharClass {
+    Any,
+    OneOf(Vec<char>),
+    NoneOf(Vec<char>),
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::OneOf(chars) => chars.contains(&c),
+            CharClass::NoneOf(chars) => !chars.contains(&c),
+        }
+    }
+}
+
+struct Affixes {
+    flag_format: FlagFormat,
+    flag_aliases: Vec<Vec<String>>,
+    groups: HashMap<String, AffixGroup>,
+    need_affix: Option<String>,
+    only_in_compound: Option<String>,
+    forbidden_word: Option<String>,
+    no_suggest: Option<String>,
+    try_chars: Vec<char>,
+    replacements: Vec<(String, String)>,
+}
+
+impl Dictionary {
+    /// Parses a dictionary from the contents of its affix and word list files.
+    pub fn parse(aff: &[u8], dic: &[u8]) -> Result<Self> {
+        let is_utf8 = aff_encoding_is_utf8(aff);
+        let aff = decode(aff, is_utf8);
+        let dic = decode(dic, is_utf8);
+        let affixes = Affixes::parse(&aff)?;
+
+        let mut words = HashSet::default();
+        let mut no_suggest = HashSet::default();
+        let mut forbidden = HashSet::default();
+        let mut lines = dic.lines();
+        // The first line holds the approximate number of words.
+        lines.next().context("empty word list")?;
+        for line in lines {
+            let Some(entry) = line.split(['\t', ' ']).next() else {
+                continue;
+            };
+            if entry.is_empty() || entry.starts_with('/') {
+                continue;
+            }
+            let (stem, flags) = split_dic_entry(entry);
+            let flags = affixes.parse_flags(&flags);
+            let has_flag =
+                |flag: &Option<String>| flag.as_ref().is_some_and(|flag| flags.contains(flag));
+
+            if has_flag(&affixes.forbidden_word) {
+                forbidden.insert(stem);
+                continue;
+            }
+            if has_flag(&affixes.only_in_compound) {
+                continue;
+            }
+
+            let mut forms = Vec::new();
+            if !has_flag(&affixes.need_affix) {
+                forms.push(stem.clone());
+            }
+            affixes.expand(&stem, &flags, &mut forms);
+            if has_flag(&affixes.no_suggest) {
+                no_suggest.extend(forms.iter().cloned());
+            }
+            words.extend(forms);
+        }
+        for word in &forbidden {
+            words.remove(word);
+        }
+
+        let try_chars = if affixes.try_chars.is_empty() {
+            ('a'..='z').collect()
+        } else {
+            affixes.try_chars
+        };
+        Ok(Self {
+            words,
+            no_suggest,
+            try_chars,
+            replacements: affixes.replacements,
+        })
+    }
+
+    /// Returns whether the word is spelled correctly.
+    ///
+    /// Words are also accepted in uppercase or capitalized, as they would be written at the start
+    /// of a sentence or in a heading.
+    pub fn contains(&self, word: &str) -> bool {
+        if self.words.contains(word) {
+            return true;
+        }
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else {
+            return true;
+        };
+        if !first.is_uppercase() {
+            return false;
+        }
+        let lowercase = word.to_lowercase();
+        if self.words.contains(&lowercase) {
+            return true;
+        }
+        let capitalized = first.to_string() + &chars.as_str().to_lowercase();
+        self.words.contains(&capitalized)
+    }
+
+    /// Returns up to `limit` correctly spelled words that are similar to the given one.
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let lowercase = word.to_lowercase();
+        let mut suggestions = Vec::new();
+        let push = |candidate: String, suggestions: &mut Vec<String>| {
+            if self.words.contains(&candidate)
+                && !self.no_suggest.contains(&candidate)
+                && !suggestions.contains(&candidate)
+            {
+                suggestions.push(candidate);
+            }
+        };
+
+        for (from, to) in &self.replacements {
+            for (ix, _) in lowercase.match_indices(from.as_str()) {
+                let mut candidate = lowercase.clone();
+                candidate.replace_range(ix..ix + from.len(), to);
+                push(candidate, &mut suggestions);
+            }
+        }
+
+        let edits = self.edits(&lowercase);
+        for candidate in &edits {
+            push(candidate.clone(), &mut suggestions);
+        }
+        // Words two edits away are only considered when there are no closer ones.
+        if suggestions.is_empty() {
+            for edit in &edits {
+                for candidate in self.edits(edit) {
+                    push(candidate, &mut suggestions);
+                    if suggestions.len() >= limit {
+                        break;
+                    }
+                }
+                if suggestions.len() >= limit {
+                    break;
+                }
+            }
+        }
+
+        suggestions.truncate(limit);
+        if word.chars().next().is_some_and(char::is_uppercase) {
+            for suggestion in &mut suggestions {
+                let mut chars = suggestion.chars();
+                if let Some(first) = chars.next() {
+                    *suggestion = first.to_uppercase().chain(chars).collect();
+                }
+            }
+        }
+        suggestions
+    }
+
+    /// Returns all the words one deletion, transposition, replacement or insertion away from the
+    /// given one.
+    fn edits(&self, word: &str) -> Vec<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+        let mut edits = Vec::new();
+        for ix in 0..chars.len() {
+            let mut deleted = chars.clone();
+            deleted.remove(ix);
+            edits.push(deleted.into_iter().collect());
+
+            if ix + 1 < chars.len() {
+                let mut transposed = chars.clone();
+                transposed.swap(ix, ix + 1);
+                edits.push(transposed.into_iter().collect());
+            }
+
+            for c in &self.try_chars {
+                if *c != chars[ix] {
+                    let mut replaced = chars.clone();
+                    replaced[ix] = *c;
+                    edits.push(replaced.into_iter().collect());
+                }
+            }
+        }
+        for ix in 0..=chars.len() {
+            for c in &self.try_chars {
+                let mut inserted = chars.clone();
+                inserted.insert(ix, *c);
+                edits.push(inserted.into_iter().collect());
+            }
+        }
+        edits
+    }
+}
+
+impl Affixes {
+    fn parse(aff: &str) -> Result<Self> {
+        let mut affixes = Self {
+            flag_format: FlagFormat::Char,
+            flag_aliases: Vec::new(),
+            groups: HashMap::default(),
+            need_affix: None,
+            only_in_compound: None,
+            forbidden_word: None,
+            no_suggest: None,
+            try_chars: Vec::new(),
+            replacements: Vec::new(),
+        };
+
+        for line in aff.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(keyword) = fields.next() else {
+                continue;
+            };
+            match keyword {
+                "FLAG" => {
+                    affixes.flag_format = match fields.next() {
+                        Some("long") => FlagFormat::Long,
+                        Some("num") => FlagFormat::Numeric,
+                        _ => FlagFormat::Char,
+                    };
+                }
+                "AF" => {
+                    // The first `AF` line holds the number of aliases.
+                    if let Some(flags) = fields.next()
+                        && flags.parse::<usize>().is_err()
+                    {
+                        let flags = affixes.parse_flags(flags);
+                        affixes.flag_aliases.push(flags);
+                    }
+                }
+                "NEEDAFFIX" | "PSEUDOROOT" => affixes.need_affix = fields.next().map(Into::into),
+                "ONLYINCOMPOUND" => affixes.only_in_compound = fields.next().map(Into::into),
+                "FORBIDDENWORD" => affixes.forbidden_word = fields.next().map(Into::into),
+                "NOSUGGEST" => affixes.no_suggest = fields.next().map(Into::into),
+                "TRY" => affixes.try_chars = fields.next().unwrap_or_default().chars().collect(),
+                "REP" => {
+                    if let (Some(from), Some(to)) = (fields.next(), fields.next()) {
+                        affixes
+                            .replacements
+                            .push((from.replace('_', " "), to.replace('_', " ")));
+                    }
+                }
+                "PFX" | "SFX" => {
+                    let kind = if keyword == "PFX" {
+                        AffixKind::Prefix
+                    } else {
+                        AffixKind::Suffix
+                    };
+                    let fields = fields.collect::<Vec<_>>();
+                    let [flag, second, third, rest @ ..] = fields.as_slice() else {
+                        continue;
+                    };
+                    // Each group starts with a header: `SFX flag cross_product count`.
+                    if rest.is_empty() && (*second == "Y" || *second == "N") {
+                        affixes.groups.insert(
+                            (*flag).into(),
+                            AffixGroup {
+                                kind,
+                                cross_product: *second == "Y",
+                                rules: Vec::new(),
+                            },
+                        );
+                        continue;
+                    }
+
+                    let (add, continuation_flags) = match third.split_once('/') {
+                        Some((add, flags)) => (add, affixes.parse_flags(flags)),
+                        None => (*third, Vec::new()),
+                    };
+                    let rule = AffixRule {
+                        strip: if *second == "0" {
+                            String::new()
+                        } else {
+                            (*second).into()
+                        },
+                        add: if add == "0" {
+                            String::new()
+                        } else {
+                            add.into()
+                        },
+                        continuation_flags,
+                        condition: parse_condition(rest.first().copied().unwrap_or(".")),
+                    };
+                    if let Some(group) = affixes.groups.get_mut(*flag) {
+                        group.rules.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(affixes)
+    }
+
+    fn parse_flags(&self, flags: &str) -> Vec<String> {
+        if !self.flag_aliases.is_empty()
+            && let Ok(alias) = flags.parse::<usize>()
+        {
+            return alias
+                .checked_sub(1)
+                .and_then(|ix| self.flag_aliases.get(ix))
+                .cloned()
+                .unwrap_or_default();
+        }
+
+        match self.flag_format {
+            FlagFormat::Char => flags.chars().map(String::from).collect(),
+            FlagFormat::Long => {
+                let chars = flags.chars().collect::<Vec<_>>();
+                chars.chunks(2).map(|pair| pair.iter().collect()).collect()
+            }
+            FlagFormat::Numeric => flags
+                .split(',')
+                .filter(|flag| !flag.is_empty())
+                .map(Into::into)
+                .collect(),
+        }
+    }
+
+    /// Appends all the forms of `stem` produced by the affixes with the given flags.
+    fn expand(&self, stem: &str, flags: &[String], forms: &mut Vec<String>) {
+        let groups = flags
+            .iter()
+            .filter_map(|flag| self.groups.get(flag))
+            .collect::<Vec<_>>();
+
+        let mut suffixed = Vec::new();
+        for group in groups
+            .iter()
+            .filter(|group| group.kind == AffixKind::Suffix)
+        {
+            for rule in &group.rules {
+                if let Some(form) = rule.apply(stem, AffixKind::Suffix) {
+                    for flag in &rule.continuation_flags {
+                        if let Some(continuation) = self.groups.get(flag)
+                            && continuation.kind == AffixKind::Suffix
+                        {
+                            forms.extend(
+                                continuation
+                                    .rules
+                                    .iter()
+                                    .filter_map(|rule| rule.apply(&form, AffixKind::Suffix)),
+                            );
+                        }
+                    }
+                    if group.cross_product {
+                        suffixed.push(form.clone());
+                    }
+                    forms.push(form);
+                }
+            }
+        }
+
+        for group in groups
+            .iter()
+            .filter(|group| group.kind == AffixKind::Prefix)
+        {
+            for rule in &group.rules {
+                forms.extend(rule.apply(stem, AffixKind::Prefix));
+                if group.cross_product {
+                    forms.extend(
+                        suffixed
+                            .iter()
+                            .filter_map(|form| rule.apply(form, AffixKind::Prefix)),
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl AffixRule {
+    fn apply(&self, word: &str, kind: AffixKind) -> Option<String> {
+        let chars = word.chars().collect::<Vec<_>>();
+        if chars.len() < self.condition.len() {
+            return None;
+        }
+        match kind {
+            AffixKind::Suffix => {
+                let tail = &chars[chars.len() - self.condition.len()..];
+                if !tail
+                    .iter()
+                    .zip(&self.condition)
+                    .all(|(c, class)| class.matches(*c))
+                {
+                    return None;
+                }
+                let stem = word.strip_suffix(self.strip.as_str())?;
+                Some(format!("{stem}{}", self.add))
+            }
+            AffixKind::Prefix => {
+                if !chars
+                    .iter()
+                    .zip(&self.condition)
+                    .all(|(c, class)| class.matches(*c))
+                {
+                    return None;
+                }
+                let stem = word.strip_prefix(self.strip.as_str())?;
+                Some(format!("{}{stem}", self.add))
+            }
+        }
+    }
+}
+
+fn parse_condition(condition: &str) -> Vec<CharClass> {
+    if condition == "." {
+        return Vec::new();
+    }
+    let mut classes = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => classes.push(CharClass::Any),
+            '[' => {
+                let mut set = Vec::new();
+                let mut negated = false;
+                for c in chars.by_ref() {
+                    match c {
+                        ']' => break,
+                        '^' if set.is_empty() && !negated => negated = true,
+                        c => set.push(c),
+                    }
+                }
+                classes.push(if negated {
+                    CharClass::NoneOf(set)
+                } else {
+                    CharClass::OneOf(set)
+                });
+            }
+            c => classes.push(CharClass::OneOf(vec![c])),
+        }
+    }
+    classes
+}
+
+/// Splits a word list entry into its word and flags, which are separated by the first slash that
+/// isn't escaped.
+fn split_dic_entry(entry: &str) -> (String, String) {
+    let mut word = String::new();
+    let mut chars = entry.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+            }
+            '/' => return (word, chars.collect()),
+            c => word.push(c),
+        }
+    }
+    (word, String::new())
+}
+
+fn aff_encoding_is_utf8(aff: &[u8]) -> bool {
+    String::from_utf8_lossy(aff).lines().any(|line| {
+        let mut fields = line.split_whitespace();
+        fields.next() == Some("SET")
+            && fields
+                .next()
+                .is_some_and(|encoding| encoding.eq_ignore_ascii_case("UTF-8"))
+    })
+}
+
+/// Decodes a dictionary file, which is either UTF-8 or, for older dictionaries, ISO 8859-1.
+fn decode(bytes: &[u8], is_utf8: bool) -> String {
+    if is_utf8 {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes.iter().map(|byte| *byte as char).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AFF: &str = "
+SET UTF-8
+TRY esianrtolcdugmphbyfvkwz
+NOSUGGEST !
+REP 1
+REP f ph
+
+PFX U Y 1
+PFX U   0     un         .
+
+SFX S Y 2
+SFX S   y     ies        [^aeiou]y
+SFX S   0     s          [^y]
+
+SFX D Y 2
+SFX D   0     ed         [^ey]
+SFX D   0     d          e
+";
+
+    const DIC: &str = "6
+hello
+world/S
+try/SD
+lock/UD
+phone/S
+darn/!
+";
+
+    fn dictionary() -> Dictionary {
+        Dictionary::parse(AFF.as_bytes(), DIC.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn test_affix_expansion() {
+        let dictionary = dictionary();
+        for word in [
+            "hello", "world", "worlds", "try", "tries", "locked", "unlock", "unlocked", "phones",
+        ] {
+            assert!(dictionary.contains(word), "{word} should be correct");
+        }
+        for word in ["helo", "trys", "unworld", "tryed", "unlocks"] {
+            assert!(!dictionary.contains(word), "{word} should be misspelled");
+        }
+    }
+
+    #[test]
+    fn test_case() {
+        let dictionary = dictionary();
+        assert!(dictionary.contains("Hello"));
+        assert!(dictionary.contains("HELLO"));
+        assert!(!dictionary.contains("hELLO"));
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let dictionary = dictionary();
+        assert_eq!(dictionary.suggestions("wrold", 3), ["world"]);
+        assert_eq!(dictionary.suggestions("Helo", 3), ["Hello"]);
+        assert_eq!(dictionary.suggestions("fone", 3), ["phone"]);
+        assert!(dictionary.contains("darn"));
+        assert!(dictionary.suggestions("dran", 3).is_empty());
+    }
+}
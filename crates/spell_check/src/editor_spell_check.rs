@@ -0,0 +1,332 @@
+use std::{ops::Range, path::PathBuf, rc::Rc, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use editor::{Addon, CodeActionProvider, Editor, EditorEvent, ExcerptId};
+use gpui::{
+    App, AppContext as _, Context, Entity, HighlightStyle, Subscription, Task, UnderlineStyle,
+    WeakEntity, Window, px,
+};
+use language::{Buffer, BufferSnapshot, Point};
+use project::{CodeAction, LspAction, ProjectTransaction};
+use serde::{Deserialize, Serialize};
+use settings::{Settings as _, SettingsStore};
+use theme::ActiveTheme as _;
+
+use crate::{SpellCheckSettings, SpellChecker, SpellCheckerEvent};
+
+const SPELL_CHECK_CODE_ACTION_PROVIDER_ID: &str = "spell_check";
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(250);
+const MAX_SUGGESTIONS: usize = 5;
+
+/// The highlight type of misspelled words.
+enum SpellCheckHighlights {}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|editor: &mut Editor, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        if !editor.mode().is_full() || editor.project().is_none() {
+            return;
+        }
+
+        let editor_entity = cx.entity();
+        let spell_check = cx.new(|cx| EditorSpellCheck::new(editor_entity, cx));
+        editor.register_addon(SpellCheckAddon {
+            _spell_check: spell_check,
+        });
+        editor.add_code_action_provider(Rc::new(SpellCheckCodeActionProvider), window, cx);
+    })
+    .detach();
+}
+
+struct SpellCheckAddon {
+    _spell_check: Entity<EditorSpellCheck>,
+}
+
+impl Addon for SpellCheckAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Underlines the misspelled words within the visible part of an editor.
+struct EditorSpellCheck {
+    editor: WeakEntity<Editor>,
+    _refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EditorSpellCheck {
+    fn new(editor: Entity<Editor>, cx: &mut Context<Self>) -> Self {
+        let subscriptions = vec![
+            cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| match event {
+                EditorEvent::BufferEdited
+                | EditorEvent::Reparsed(_)
+                | EditorEvent::ExcerptsAdded { .. }
+                | EditorEvent::ExcerptsEdited { .. }
+                | EditorEvent::ExcerptsExpanded { .. }
+                | EditorEvent::ScrollPositionChanged { .. } => this.schedule_refresh(cx),
+                _ => {}
+            }),
+            cx.subscribe(
+                &SpellChecker::global(cx),
+                |this, _, event: &SpellCheckerEvent, cx| match event {
+                    SpellCheckerEvent::WordsChanged => this.schedule_refresh(cx),
+                },
+            ),
+            cx.observe_global::<SettingsStore>(|this, cx| this.schedule_refresh(cx)),
+        ];
+
+        let mut this = Self {
+            editor: editor.downgrade(),
+            _refresh_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.schedule_refresh(cx);
+        this
+    }
+
+    fn schedule_refresh(&mut self, cx: &mut Context<Self>) {
+        self._refresh_task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            let Some(task) = this.update(cx, |this, cx| this.refresh(cx)).ok().flatten() else {
+                return;
+            };
+            task.await;
+        });
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) -> Option<Task<()>> {
+        let editor = self.editor.upgrade()?;
+        let settings = SpellCheckSettings::get_global(cx);
+        if !settings.enabled {
+            editor.update(cx, |editor, cx| {
+                editor.clear_highlights::<SpellCheckHighlights>(cx)
+            });
+            return None;
+        }
+
+        let prose_languages = settings.prose_languages.clone();
+        let spell_checker = SpellChecker::global(cx);
+        let visible_excerpts = editor.update(cx, |editor, cx| editor.visible_excerpts(false, cx));
+        let excerpts = visible_excerpts
+            .into_iter()
+            .map(|(excerpt_id, (buffer, _, range))| {
+                let buffer = buffer.read(cx);
+                let snapshot = buffer.snapshot();
+                let project_dictionary = project_dictionary_path(buffer, cx);
+                let speller = spell_checker.update(cx, |spell_checker, cx| {
+                    spell_checker.speller(project_dictionary.as_deref(), cx)
+                });
+                (excerpt_id, snapshot, range, speller)
+            })
+            .collect::<Vec<_>>();
+
+        let editor = editor.downgrade();
+        Some(cx.spawn(async move |_, cx| {
+            let misspelled_words = cx
+                .background_spawn(async move {
+                    excerpts
+                        .into_iter()
+                        .map(|(excerpt_id, snapshot, range, speller)| {
+                            let range = line_range(&snapshot, range);
+                            let words =
+                                speller.misspelled_words(&snapshot, range, &prose_languages);
+                            let words = words
+                                .into_iter()
+                                .map(|word| {
+                                    snapshot.anchor_after(word.start)
+                                        ..snapshot.anchor_before(word.end)
+                                })
+                                .collect::<Vec<_>>();
+                            (excerpt_id, words)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+
+            editor
+                .update(cx, |editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let ranges = misspelled_words
+                        .into_iter()
+                        .flat_map(|(excerpt_id, words)| {
+                            words.into_iter().filter_map(move |word| {
+                                snapshot.anchor_range_in_excerpt(excerpt_id, word)
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    let style = HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            thickness: px(1.),
+                            color: Some(cx.theme().status().info),
+                            wavy: true,
+                        }),
+                        ..HighlightStyle::default()
+                    };
+                    editor.highlight_text::<SpellCheckHighlights>(ranges, style, cx);
+                })
+                .ok();
+        }))
+    }
+}
+
+/// Offers replacements for a misspelled word, and adding it to the user's or the project's
+/// dictionary.
+struct SpellCheckCodeActionProvider;
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum SpellCheckAction {
+    Replace { text: String },
+    AddWord { word: String, dictionary: PathBuf },
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        SPELL_CHECK_CODE_ACTION_PROVIDER_ID.into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let settings = SpellCheckSettings::get_global(cx);
+        if !settings.enabled {
+            return Task::ready(Ok(Vec::new()));
+        }
+
+        let prose_languages = settings.prose_languages.clone();
+        let buffer = buffer.read(cx);
+        let snapshot = buffer.snapshot();
+        let project_dictionary = project_dictionary_path(buffer, cx);
+        let speller = SpellChecker::global(cx).update(cx, |spell_checker, cx| {
+            spell_checker.speller(project_dictionary.as_deref(), cx)
+        });
+        let offset = text::ToOffset::to_offset(&range.start, &snapshot);
+
+        cx.background_spawn(async move {
+            let range = line_range(&snapshot, offset..offset);
+            let Some(word_range) = speller
+                .misspelled_words(&snapshot, range, &prose_languages)
+                .into_iter()
+                .find(|word| word.start <= offset && offset <= word.end)
+            else {
+                return Ok(Vec::new());
+            };
+            let word = snapshot
+                .text_for_range(word_range.clone())
+                .collect::<String>();
+            let range =
+                snapshot.anchor_before(word_range.start)..snapshot.anchor_after(word_range.end);
+
+            let mut actions = speller
+                .suggestions(&word, MAX_SUGGESTIONS)
+                .into_iter()
+                .map(|suggestion| {
+                    (
+                        format!("Change to \"{suggestion}\""),
+                        SpellCheckAction::Replace { text: suggestion },
+                    )
+                })
+                .collect::<Vec<_>>();
+            if let Some(dictionary) = project_dictionary {
+                actions.push((
+                    format!("Add \"{word}\" to Project Dictionary"),
+                    SpellCheckAction::AddWord {
+                        word: word.clone(),
+                        dictionary,
+                    },
+                ));
+            }
+            actions.push((
+                format!("Add \"{word}\" to User Dictionary"),
+                SpellCheckAction::AddWord {
+                    word,
+                    dictionary: paths::user_dictionary_file().clone(),
+                },
+            ));
+
+            actions
+                .into_iter()
+                .map(|(title, action)| -> Result<CodeAction> {
+                    Ok(CodeAction {
+                        server_id: language::LanguageServerId(0),
+                        range: range.clone(),
+                        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+                            title,
+                            data: Some(serde_json::to_value(action)?),
+                            ..Default::default()
+                        })),
+                        resolved: true,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _excerpt_id: ExcerptId,
+        _push_to_history: bool,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let LspAction::Action(lsp_action) = &action.lsp_action else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+        let spell_check_action = match lsp_action
+            .data
+            .clone()
+            .map(serde_json::from_value::<SpellCheckAction>)
+        {
+            Some(Ok(spell_check_action)) => spell_check_action,
+            Some(Err(error)) => return Task::ready(Err(error.into())),
+            None => return Task::ready(Ok(ProjectTransaction::default())),
+        };
+
+        match spell_check_action {
+            SpellCheckAction::Replace { text } => {
+                buffer.update(cx, |buffer, cx| {
+                    buffer.edit([(action.range, text)], None, cx);
+                });
+                Task::ready(Ok(ProjectTransaction::default()))
+            }
+            SpellCheckAction::AddWord { word, dictionary } => {
+                let add_word = SpellChecker::global(cx).update(cx, |spell_checker, cx| {
+                    spell_checker.add_word(&word, dictionary, cx)
+                });
+                cx.background_spawn(async move {
+                    add_word.await?;
+                    Ok(ProjectTransaction::default())
+                })
+            }
+        }
+    }
+}
+
+/// Expands the range to whole lines, so that words at its edges are not cut off.
+fn line_range(snapshot: &BufferSnapshot, range: Range<usize>) -> Range<usize> {
+    let start = snapshot.offset_to_point(range.start);
+    let end = snapshot.offset_to_point(range.end);
+    let start = Point::new(start.row, 0);
+    let end = Point::new(end.row, snapshot.line_len(end.row));
+    snapshot.point_to_offset(start)..snapshot.point_to_offset(end)
+}
+
+/// Returns the path of the dictionary of the local project containing the buffer.
+fn project_dictionary_path(buffer: &Buffer, cx: &App) -> Option<PathBuf> {
+    let file = project::File::from_dyn(buffer.file())?;
+    let worktree = file.worktree.read(cx);
+    worktree.is_local().then(|| {
+        worktree
+            .abs_path()
+            .join(paths::local_dictionary_file_relative_path().as_std_path())
+    })
+}
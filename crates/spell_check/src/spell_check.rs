@@ -0,0 +1,335 @@
+mod dictionary;
+mod editor_spell_check;
+mod spell_check_settings;
+mod words;
+
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, Global, Subscription, Task};
+use language::BufferSnapshot;
+use settings::{Settings, SettingsStore};
+
+pub use crate::dictionary::Dictionary;
+pub use crate::spell_check_settings::*;
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    let spell_checker = cx.new(|cx| SpellChecker::new(fs, cx));
+    cx.set_global(GlobalSpellChecker(spell_checker));
+    editor_spell_check::init(cx);
+}
+
+struct GlobalSpellChecker(Entity<SpellChecker>);
+
+impl Global for GlobalSpellChecker {}
+
+pub enum SpellCheckerEvent {
+    /// Dictionaries or word lists were loaded or changed.
+    WordsChanged,
+}
+
+/// Loads the dictionaries configured in the settings, along with the words the user added to their
+/// own or a project's dictionary.
+pub struct SpellChecker {
+    fs: Arc<dyn Fs>,
+    dictionaries: Arc<Vec<Dictionary>>,
+    /// The dictionary names and directories the dictionaries were loaded from.
+    loaded_dictionaries: Option<(Vec<String>, Vec<PathBuf>)>,
+    /// Word lists by path, which are `None` while loading.
+    word_lists: HashMap<PathBuf, Option<Arc<HashSet<String>>>>,
+    _load_dictionaries_task: Task<()>,
+    _settings_subscription: Subscription,
+}
+
+impl EventEmitter<SpellCheckerEvent> for SpellChecker {}
+
+impl SpellChecker {
+    pub fn global(cx: &App) -> Entity<Self> {
+        cx.global::<GlobalSpellChecker>().0.clone()
+    }
+
+    fn new(fs: Arc<dyn Fs>, cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            fs,
+            dictionaries: Arc::default(),
+            loaded_dictionaries: None,
+            word_lists: HashMap::default(),
+            _load_dictionaries_task: Task::ready(()),
+            _settings_subscription: cx
+                .observe_global::<SettingsStore>(|this, cx| this.load_dictionaries(cx)),
+        };
+        this.load_dictionaries(cx);
+        this
+    }
+
+    fn load_dictionaries(&mut self, cx: &mut Context<Self>) {
+        let settings = SpellCheckSettings::get_global(cx);
+        if !settings.enabled {
+            return;
+        }
+        let requested = (
+            settings.dictionaries.clone(),
+            settings.dictionary_directories.clone(),
+        );
+        if self.loaded_dictionaries.as_ref() == Some(&requested) {
+            return;
+        }
+        self.loaded_dictionaries = Some(requested.clone());
+
+        let (names, directories) = requested;
+        let directories = [paths::dictionaries_dir().clone()]
+            .into_iter()
+            .chain(directories)
+            .chain(system_dictionary_directories())
+            .collect::<Vec<_>>();
+        let fs = self.fs.clone();
+        self._load_dictionaries_task = cx.spawn(async move |this, cx| {
+            let mut dictionaries = Vec::new();
+            for name in names {
+                match load_dictionary(fs.as_ref(), &name, &directories, cx).await {
+                    Ok(dictionary) => dictionaries.push(dictionary),
+                    Err(error) => log::error!("failed to load dictionary {name:?}: {error:#}"),
+                }
+            }
+            this.update(cx, |this, cx| {
+                this.dictionaries = Arc::new(dictionaries);
+                cx.emit(SpellCheckerEvent::WordsChanged);
+            })
+            .ok();
+        });
+    }
+
+    /// Returns a [`Speller`] for the current dictionaries, the user's words and those of the
+    /// given project dictionary.
+    pub fn speller(
+        &mut self,
+        project_dictionary: Option<&Path>,
+        cx: &mut Context<Self>,
+    ) -> Speller {
+        let word_lists = [
+            Some(paths::user_dictionary_file().as_path()),
+            project_dictionary,
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|path| self.word_list(path, cx))
+        .collect();
+        Speller {
+            dictionaries: self.dictionaries.clone(),
+            word_lists,
+        }
+    }
+
+    fn word_list(&mut self, path: &Path, cx: &mut Context<Self>) -> Option<Arc<HashSet<String>>> {
+        if let Some(words) = self.word_lists.get(path) {
+            return words.clone();
+        }
+
+        self.word_lists.insert(path.to_path_buf(), None);
+        let fs = self.fs.clone();
+        let path = path.to_path_buf();
+        cx.spawn(async move |this, cx| {
+            let mut words = if fs.is_file(&path).await {
+                match fs.load(&path).await {
+                    Ok(contents) => parse_word_list(&contents),
+                    Err(error) => {
+                        log::error!("failed to load word list {path:?}: {error:#}");
+                        HashSet::default()
+                    }
+                }
+            } else {
+                HashSet::default()
+            };
+            this.update(cx, |this, cx| {
+                let word_list = this.word_lists.entry(path).or_default();
+                // Keep the words that were added while the list was loading.
+                if let Some(added_words) = word_list {
+                    words.extend(added_words.iter().cloned());
+                }
+                *word_list = Some(Arc::new(words));
+                cx.emit(SpellCheckerEvent::WordsChanged);
+            })
+            .ok();
+        })
+        .detach();
+        None
+    }
+
+    /// Adds a word to the word list at the given path, which is either the user's dictionary or a
+    /// project's.
+    pub fn add_word(
+        &mut self,
+        word: &str,
+        word_list_path: PathBuf,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let word = normalize_word(word);
+        let word_list = self.word_lists.entry(word_list_path.clone()).or_default();
+        let mut words = word_list.as_deref().cloned().unwrap_or_default();
+        words.insert(word.to_lowercase());
+        *word_list = Some(Arc::new(words));
+        cx.emit(SpellCheckerEvent::WordsChanged);
+
+        let fs = self.fs.clone();
+        cx.background_spawn(async move {
+            if let Some(parent) = word_list_path.parent() {
+                fs.create_dir(parent).await?;
+            }
+            let mut contents = if fs.is_file(&word_list_path).await {
+                fs.load(&word_list_path).await?
+            } else {
+                String::new()
+            };
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                contents.push('\n');
+            }
+            contents.push_str(&word);
+            contents.push('\n');
+            fs.atomic_write(word_list_path, contents).await
+        })
+    }
+}
+
+/// A snapshot of the words known to the [`SpellChecker`], which can be used on a background
+/// thread.
+#[derive(Clone)]
+pub struct Speller {
+    dictionaries: Arc<Vec<Dictionary>>,
+    word_lists: Vec<Arc<HashSet<String>>>,
+}
+
+impl Speller {
+    pub fn has_dictionaries(&self) -> bool {
+        !self.dictionaries.is_empty()
+    }
+
+    pub fn is_correct(&self, word: &str) -> bool {
+        let word = normalize_word(word);
+        let lowercase = word.to_lowercase();
+        self.word_lists
+            .iter()
+            .any(|words| words.contains(&lowercase))
+            || self
+                .dictionaries
+                .iter()
+                .any(|dictionary| dictionary.contains(&word))
+    }
+
+    pub fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let word = normalize_word(word);
+        let mut suggestions = Vec::new();
+        for dictionary in self.dictionaries.iter() {
+            for suggestion in dictionary.suggestions(&word, limit) {
+                if !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        suggestions.truncate(limit);
+        suggestions
+    }
+
+    /// Returns the ranges of the misspelled words within the given range of the buffer.
+    ///
+    /// Only comments and strings are checked, as well as text in the given prose languages that
+    /// isn't code, according to the language's tree-sitter override queries.
+    pub fn misspelled_words(
+        &self,
+        snapshot: &BufferSnapshot,
+        range: Range<usize>,
+        prose_languages: &[String],
+    ) -> Vec<Range<usize>> {
+        if !self.has_dictionaries() {
+            return Vec::new();
+        }
+
+        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+        words::words(&text)
+            .into_iter()
+            .filter(|word| {
+                is_checked(snapshot, range.start + word.start, prose_languages)
+                    && !self.is_correct(&text[word.clone()])
+            })
+            .map(|word| range.start + word.start..range.start + word.end)
+            .collect()
+    }
+}
+
+fn is_checked(snapshot: &BufferSnapshot, offset: usize, prose_languages: &[String]) -> bool {
+    let Some(scope) = snapshot.language_scope_at(offset) else {
+        return true;
+    };
+    match scope.override_name() {
+        Some("comment" | "string") => true,
+        Some(_) => false,
+        None => {
+            let language_name = scope.language_name();
+            prose_languages
+                .iter()
+                .any(|name| name.as_str() == language_name.as_ref())
+        }
+    }
+}
+
+async fn load_dictionary(
+    fs: &dyn Fs,
+    name: &str,
+    directories: &[PathBuf],
+    cx: &mut gpui::AsyncApp,
+) -> Result<Dictionary> {
+    for directory in directories {
+        let aff_path = directory.join(format!("{name}.aff"));
+        let dic_path = directory.join(format!("{name}.dic"));
+        if fs.is_file(&aff_path).await && fs.is_file(&dic_path).await {
+            let aff = fs.load_bytes(&aff_path).await?;
+            let dic = fs.load_bytes(&dic_path).await?;
+            return cx
+                .background_spawn(async move { Dictionary::parse(&aff, &dic) })
+                .await
+                .with_context(|| format!("parsing {dic_path:?}"));
+        }
+    }
+    anyhow::bail!("no {name}.aff and {name}.dic files found in {directories:?}")
+}
+
+fn system_dictionary_directories() -> Vec<PathBuf> {
+    if cfg!(target_os = "macos") {
+        vec![
+            util::paths::home_dir().join("Library/Spelling"),
+            PathBuf::from("/Library/Spelling"),
+            PathBuf::from("/opt/homebrew/share/hunspell"),
+            PathBuf::from("/usr/local/share/hunspell"),
+        ]
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        vec![
+            PathBuf::from("/usr/share/hunspell"),
+            PathBuf::from("/usr/share/myspell"),
+            PathBuf::from("/usr/share/myspell/dicts"),
+            PathBuf::from("/usr/local/share/hunspell"),
+        ]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Parses a word list with one word per line, which is how words added to the user's or a
+/// project's dictionary are stored.
+fn parse_word_list(contents: &str) -> HashSet<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|word| normalize_word(word).to_lowercase())
+        .collect()
+}
+
+/// Replaces typographic apostrophes, which dictionaries don't use.
+fn normalize_word(word: &str) -> String {
+    word.replace('’', "'")
+}
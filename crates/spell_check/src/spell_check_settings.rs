@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+use settings::{RegisterSetting, Settings};
+
+/// The settings for spell checking.
+#[derive(Clone, Debug, RegisterSetting)]
+pub struct SpellCheckSettings {
+    /// Whether to check the spelling of comments, strings and prose.
+    pub enabled: bool,
+    /// The Hunspell dictionaries to check words against, by name.
+    pub dictionaries: Vec<String>,
+    /// Directories to look for Hunspell dictionaries in.
+    pub dictionary_directories: Vec<PathBuf>,
+    /// Languages whose text is checked in full, rather than only in comments and strings.
+    pub prose_languages: Vec<String>,
+}
+
+impl Settings for SpellCheckSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let spell_check = content.spell_check.clone().unwrap();
+        Self {
+            enabled: spell_check.enabled.unwrap(),
+            dictionaries: spell_check.dictionaries.unwrap(),
+            dictionary_directories: spell_check
+                .dictionary_directories
+                .unwrap()
+                .into_iter()
+                .map(|directory| PathBuf::from(shellexpand::tilde(&directory).as_ref()))
+                .collect(),
+            prose_languages: spell_check.prose_languages.unwrap(),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::ops::Range;
+
+/// Words shorter than this many characters are not checked, as they're mostly abbreviations.
+const MIN_WORD_LEN: usize = 3;
+
+/// Returns the byte ranges of the words to check in the given text.
+///
+/// Identifiers are split into the words they're made of, so `parseHttpResponse` and
+/// `parse_http_response` are both checked as `parse`, `http` and `response`. Words written in
+/// uppercase or containing digits are skipped, as they're usually acronyms or codes, and so are
+/// URLs, paths, email addresses and qualified names like `std::fs` or `Cargo.toml`.
+pub fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut run_start = None;
+    for (ix, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        if c.is_whitespace() {
+            if let Some(start) = run_start.take() {
+                push_run_words(text, start..ix, &mut words);
+            }
+        } else if run_start.is_none() {
+            run_start = Some(ix);
+        }
+    }
+    words
+}
+
+/// Pushes the words of a run of text that contains no whitespace.
+fn push_run_words(text: &str, run: Range<usize>, words: &mut Vec<Range<usize>>) {
+    let run_text = &text[run.clone()];
+    if run_text.contains("://") || run_text.contains(['@', '/', '\\']) {
+        return;
+    }
+
+    let mut tokens = Vec::new();
+    let mut token_start = None;
+    for (ix, c) in run_text.char_indices().chain([(run_text.len(), ' ')]) {
+        if is_word_char(c) {
+            token_start.get_or_insert(ix);
+        } else if let Some(start) = token_start.take() {
+            tokens.push(run.start + start..run.start + ix);
+        }
+    }
+
+    for (ix, token) in tokens.iter().enumerate() {
+        let is_qualified = (ix > 0 && is_qualifier(&text[tokens[ix - 1].end..token.start]))
+            || tokens
+                .get(ix + 1)
+                .is_some_and(|next| is_qualifier(&text[token.end..next.start]));
+        if is_qualified || text[token.clone()].chars().any(char::is_numeric) {
+            continue;
+        }
+        push_identifier_words(text, token.clone(), words);
+    }
+}
+
+/// Pushes the words of an identifier, split at underscores and case changes.
+fn push_identifier_words(text: &str, token: Range<usize>, words: &mut Vec<Range<usize>>) {
+    let chars = text[token.clone()].char_indices().collect::<Vec<_>>();
+    let mut push_part = |range: Range<usize>| {
+        let part = &text[token.start + range.start..token.start + range.end];
+        let trimmed = part.trim_start_matches(is_apostrophe);
+        let start = token.start + range.start + (part.len() - trimmed.len());
+        let trimmed = trimmed.trim_end_matches(is_apostrophe);
+        let is_uppercase = !trimmed.chars().any(char::is_lowercase);
+        if trimmed.chars().count() >= MIN_WORD_LEN && !is_uppercase {
+            words.push(start..start + trimmed.len());
+        }
+    };
+
+    let mut part_start = 0;
+    for (char_ix, (ix, c)) in chars.iter().copied().enumerate() {
+        if c == '_' {
+            push_part(part_start..ix);
+            part_start = ix + 1;
+        } else if char_ix > 0 && c.is_uppercase() {
+            let previous = chars[char_ix - 1].1;
+            let next_is_lowercase = chars
+                .get(char_ix + 1)
+                .is_some_and(|(_, next)| next.is_lowercase());
+            if previous.is_lowercase() || (previous.is_uppercase() && next_is_lowercase) {
+                push_part(part_start..ix);
+                part_start = ix;
+            }
+        }
+    }
+    push_part(part_start..token.len());
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || is_apostrophe(c)
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+/// Returns whether the text between two tokens joins them into a qualified name.
+fn is_qualifier(separator: &str) -> bool {
+    !separator.is_empty() && separator.chars().all(|c| c == '.' || c == ':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_in(text: &str) -> Vec<&str> {
+        words(text).into_iter().map(|range| &text[range]).collect()
+    }
+
+    #[test]
+    fn test_prose() {
+        assert_eq!(
+            words_in("Teh quick, brwn fox (isn't) 'jumping'."),
+            ["Teh", "quick", "brwn", "fox", "isn't", "jumping"]
+        );
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(
+            words_in("parseHttpResponse snake_case_word HTTPServer MAX_LEN"),
+            [
+                "parse", "Http", "Response", "snake", "case", "word", "Server"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skipped_tokens() {
+        assert_eq!(
+            words_in(
+                "see https://example.com, src/main.rs, Cargo.toml, std::fs::read, me@example.com utf8 ok"
+            ),
+            ["see"]
+        );
+    }
+}
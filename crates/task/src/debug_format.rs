@@ -1,5 +1,5 @@
 use anyhow::{Context as _, Result};
-use collections::FxHashMap;
+use collections::{FxHashMap, HashMap};
 use gpui::SharedString;
 use log as _;
 use schemars::JsonSchema;
@@ -8,7 +8,11 @@ use std::net::Ipv4Addr;
 use std::path::PathBuf;
 use util::{debug_panic, schemars::add_new_subschema};
 
-use crate::{TaskTemplate, adapter_schema::AdapterSchemas};
+use crate::{
+    TaskInput, TaskTemplate,
+    adapter_schema::AdapterSchemas,
+    task_input::{referenced_inputs, substitute_inputs},
+};
 
 /// Represents the host information of the debug adapter
 #[derive(Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, Clone, Debug)]
@@ -278,6 +282,59 @@ pub struct DebugScenario {
     /// that is already running or is started by another process.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tcp_connection: Option<TcpArgumentsTemplate>,
+    /// Values to ask for when the debug session starts, referenced as `${input:id}` in the configuration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<TaskInput>,
+}
+
+impl DebugScenario {
+    /// Inputs referenced as `${input:id}` by the scenario, whose values have to be entered before it starts.
+    pub fn inputs(&self) -> Vec<TaskInput> {
+        let mut texts = vec![self.label.as_ref()];
+        collect_strings(&self.config, &mut texts);
+        referenced_inputs(texts, &self.inputs)
+    }
+
+    /// Returns the scenario with its `${input:id}` references replaced with the entered values.
+    pub fn with_input_values(mut self, values: &HashMap<String, String>) -> Self {
+        self.label = substitute_inputs(&self.label, values).into();
+        substitute_inputs_in_value(&mut self.config, values);
+        self
+    }
+}
+
+fn collect_strings<'a>(value: &'a serde_json::Value, strings: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(string) => strings.push(string),
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_strings(value, strings);
+            }
+        }
+        serde_json::Value::Object(object) => {
+            for value in object.values() {
+                collect_strings(value, strings);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn substitute_inputs_in_value(value: &mut serde_json::Value, values: &HashMap<String, String>) {
+    match value {
+        serde_json::Value::String(string) => *string = substitute_inputs(string, values),
+        serde_json::Value::Array(array) => {
+            for value in array {
+                substitute_inputs_in_value(value, values);
+            }
+        }
+        serde_json::Value::Object(object) => {
+            for value in object.values_mut() {
+                substitute_inputs_in_value(value, values);
+            }
+        }
+        _ => {}
+    }
 }
 
 /// A group of Debug Tasks defined in a JSON file.
@@ -345,6 +402,7 @@ impl DebugTaskFile {
             BuildTaskDefinition::schema_name().as_ref(),
             build_task_value,
         );
+        let task_input_ref = generator.subschema_for::<TaskInput>();
 
         let meta_schema = generator
             .settings()
@@ -379,6 +437,11 @@ impl DebugTaskFile {
                         "description": "The name of the debug configuration"
                     },
                     "build": build_task_definition_ref,
+                    "inputs": {
+                        "type": "array",
+                        "description": "Values to ask for when the debug session starts, referenced as `${input:id}` in the configuration",
+                        "items": task_input_ref
+                    },
                     "tcp_connection": {
                         "type": "object",
                         "description": "Optional TCP connection information for connecting to an already running debug adapter",
@@ -0,0 +1,570 @@
+use anyhow::Context as _;
+use regex::{Captures, Regex};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A way to find problems (errors and warnings) in a task's output:
+/// either the name of a built-in problem matcher, like `$rustc`, or a custom one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ProblemMatcherDefinition {
+    /// One of the built-in problem matchers:
+    /// * `$rustc` — errors and warnings from `rustc` and `cargo`
+    /// * `$tsc` — errors and warnings from the TypeScript compiler (also available as `$tsc-watch`)
+    /// * `$gcc` — errors and warnings from `gcc` and `clang`
+    /// * `$eslint-compact` — ESLint's `compact` output format
+    /// * `$eslint-stylish` — ESLint's default `stylish` output format
+    Builtin(String),
+    Custom(ProblemMatcher),
+}
+
+impl ProblemMatcherDefinition {
+    /// Returns the problem matcher this definition refers to, or `None` for an unknown built-in matcher name.
+    pub fn resolve(&self) -> Option<ProblemMatcher> {
+        match self {
+            Self::Builtin(name) => builtin_problem_matcher(name),
+            Self::Custom(matcher) => Some(matcher.clone()),
+        }
+    }
+}
+
+/// A regex-based definition of how to find problems in a task's output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemMatcher {
+    /// The name of the tool that reports the problems, shown as the source of the diagnostics.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// The severity of the problems whose patterns don't capture one.
+    #[serde(default)]
+    pub severity: ProblemSeverity,
+    /// Patterns that match consecutive lines of the output, which together describe one problem.
+    /// Each pattern's captures are combined, so a problem's message and location may come from different lines.
+    pub pattern: Vec<ProblemPattern>,
+}
+
+/// A regular expression matching a line of a task's output,
+/// with the indices of the capture groups that contain each part of the problem.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ProblemPattern {
+    pub regexp: String,
+    /// The file path, either absolute or relative to the task's working directory.
+    #[serde(default)]
+    pub file: Option<usize>,
+    /// The location of the problem, as `line`, `line,column` or `line,column,end_line,end_column`.
+    /// An alternative to the separate `line`, `column`, `end_line` and `end_column` groups.
+    #[serde(default)]
+    pub location: Option<usize>,
+    /// The 1-based line of the problem.
+    #[serde(default)]
+    pub line: Option<usize>,
+    /// The 1-based column of the problem.
+    #[serde(default)]
+    pub column: Option<usize>,
+    #[serde(default)]
+    pub end_line: Option<usize>,
+    #[serde(default)]
+    pub end_column: Option<usize>,
+    /// The severity of the problem, such as `error` or `warning`.
+    #[serde(default)]
+    pub severity: Option<usize>,
+    #[serde(default)]
+    pub code: Option<usize>,
+    #[serde(default)]
+    pub message: Option<usize>,
+    /// Whether the last pattern may match several consecutive lines, each of them reporting a problem.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProblemSeverity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl ProblemSeverity {
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        let text = text.to_ascii_lowercase();
+        if text.starts_with("err") || text == "fatal" {
+            Some(Self::Error)
+        } else if text.starts_with("warn") {
+            Some(Self::Warning)
+        } else if text.starts_with("info") || text == "note" {
+            Some(Self::Info)
+        } else if text == "hint" || text == "help" {
+            Some(Self::Hint)
+        } else {
+            None
+        }
+    }
+}
+
+/// A problem found in a task's output.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Problem {
+    /// The file path, as printed by the task.
+    pub file: String,
+    /// The 1-based line of the problem.
+    pub line: u32,
+    /// The 1-based column of the problem, if known.
+    pub column: Option<u32>,
+    pub end_line: Option<u32>,
+    pub end_column: Option<u32>,
+    pub severity: ProblemSeverity,
+    pub code: Option<String>,
+    pub message: String,
+    pub source: Option<String>,
+}
+
+impl ProblemMatcher {
+    /// Finds all problems in the given task output.
+    pub fn find_problems(&self, output: &str) -> anyhow::Result<Vec<Problem>> {
+        let mut scanner = self.scanner()?;
+        Ok(output
+            .lines()
+            .filter_map(|line| scanner.scan_line(line))
+            .collect())
+    }
+
+    /// Returns a scanner that finds the problems in a task's output one line at a time,
+    /// as the output arrives.
+    pub fn scanner(&self) -> anyhow::Result<ProblemScanner> {
+        let patterns = self
+            .pattern
+            .iter()
+            .map(|pattern| {
+                Regex::new(&pattern.regexp)
+                    .with_context(|| format!("invalid problem pattern {:?}", pattern.regexp))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let empty = Problem {
+            severity: self.severity,
+            ..Problem::default()
+        };
+        Ok(ProblemScanner {
+            matcher: self.clone(),
+            patterns,
+            partial: empty.clone(),
+            before_last: empty.clone(),
+            empty,
+            next: 0,
+        })
+    }
+
+    fn complete(&self, mut problem: Problem) -> Option<Problem> {
+        if problem.file.is_empty() || problem.line == 0 || problem.message.is_empty() {
+            return None;
+        }
+        if problem.source.is_none() {
+            problem.source = self.source.clone();
+        }
+        Some(problem)
+    }
+}
+
+/// Matches the lines of a task's output against a [`ProblemMatcher`], keeping track of the
+/// problems described by several lines.
+pub struct ProblemScanner {
+    matcher: ProblemMatcher,
+    patterns: Vec<Regex>,
+    empty: Problem,
+    /// The captures of the patterns matched so far.
+    partial: Problem,
+    /// The captures of all patterns but the last one, which may match several lines in a row.
+    before_last: Problem,
+    /// The index of the pattern expected to match the next line.
+    next: usize,
+}
+
+impl ProblemScanner {
+    /// Matches the next line of the output, returning the problem it completes, if any.
+    pub fn scan_line(&mut self, line: &str) -> Option<Problem> {
+        let last = self.patterns.len().checked_sub(1)?;
+        let line = line.trim_end();
+        if self.next > 0 {
+            let next = self.next;
+            if let Some(captures) = self.patterns[next].captures(line) {
+                if next == last {
+                    let mut problem = self.before_last.clone();
+                    self.matcher.pattern[next].apply(&captures, &mut problem);
+                    if !self.matcher.pattern[next].repeat {
+                        self.next = 0;
+                    }
+                    return self.matcher.complete(problem);
+                }
+                self.matcher.pattern[next].apply(&captures, &mut self.partial);
+                self.next += 1;
+                if self.next == last {
+                    self.before_last = self.partial.clone();
+                }
+                return None;
+            }
+            // The line doesn't continue the problem, so it might start a new one.
+            self.next = 0;
+        }
+
+        let captures = self.patterns[0].captures(line)?;
+        self.partial = self.empty.clone();
+        self.matcher.pattern[0].apply(&captures, &mut self.partial);
+        if last == 0 {
+            return self.matcher.complete(self.partial.clone());
+        }
+        self.next = 1;
+        if self.next == last {
+            self.before_last = self.partial.clone();
+        }
+        None
+    }
+}
+
+impl ProblemPattern {
+    fn apply(&self, captures: &Captures, problem: &mut Problem) {
+        let text = |group: Option<usize>| {
+            let text = captures.get(group?)?.as_str().trim();
+            (!text.is_empty()).then(|| text.to_string())
+        };
+        let number = |group: Option<usize>| text(group)?.parse::<u32>().ok();
+
+        if let Some(file) = text(self.file) {
+            problem.file = file;
+        }
+        if let Some(location) = text(self.location) {
+            let mut numbers = location
+                .split(',')
+                .map(|number| number.trim().parse::<u32>().ok());
+            if let Some(line) = numbers.next().flatten() {
+                problem.line = line;
+            }
+            problem.column = numbers.next().flatten().or(problem.column);
+            problem.end_line = numbers.next().flatten().or(problem.end_line);
+            problem.end_column = numbers.next().flatten().or(problem.end_column);
+        }
+        if let Some(line) = number(self.line) {
+            problem.line = line;
+        }
+        if let Some(column) = number(self.column) {
+            problem.column = Some(column);
+        }
+        if let Some(end_line) = number(self.end_line) {
+            problem.end_line = Some(end_line);
+        }
+        if let Some(end_column) = number(self.end_column) {
+            problem.end_column = Some(end_column);
+        }
+        if let Some(severity) = text(self.severity).and_then(|text| ProblemSeverity::parse(&text)) {
+            problem.severity = severity;
+        }
+        if let Some(code) = text(self.code) {
+            problem.code = Some(code);
+        }
+        if let Some(message) = text(self.message) {
+            problem.message = message;
+        }
+    }
+}
+
+fn builtin_problem_matcher(name: &str) -> Option<ProblemMatcher> {
+    let (source, pattern) = match name {
+        "$rustc" => (
+            "rustc",
+            vec![
+                ProblemPattern {
+                    regexp: r"^(warning|error)(?:\[(\S+)\])?: (.*)$".to_string(),
+                    severity: Some(1),
+                    code: Some(2),
+                    message: Some(3),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s*-->\s*(.+):(\d+):(\d+)$".to_string(),
+                    file: Some(1),
+                    line: Some(2),
+                    column: Some(3),
+                    ..ProblemPattern::default()
+                },
+            ],
+        ),
+        "$tsc" | "$tsc-watch" => (
+            "tsc",
+            vec![ProblemPattern {
+                regexp: r"^([^\s].*?)[\(:](\d+)[,:](\d+)(?:\):\s+|\s+-\s+)(error|warning|info)\s+(TS\d+)\s*:\s*(.*)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                code: Some(5),
+                message: Some(6),
+                ..ProblemPattern::default()
+            }],
+        ),
+        "$gcc" => (
+            "gcc",
+            vec![ProblemPattern {
+                regexp: r"^(.+?):(\d+):(\d+)?:?\s+(?:fatal\s+)?(warning|error):\s+(.*)$".to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                ..ProblemPattern::default()
+            }],
+        ),
+        "$eslint-compact" => (
+            "eslint",
+            vec![ProblemPattern {
+                regexp: r"^(.+):\sline\s(\d+),\scol\s(\d+),\s(Error|Warning|Info)\s-\s(.+)\s\((.+)\)$"
+                    .to_string(),
+                file: Some(1),
+                line: Some(2),
+                column: Some(3),
+                severity: Some(4),
+                message: Some(5),
+                code: Some(6),
+                ..ProblemPattern::default()
+            }],
+        ),
+        "$eslint-stylish" => (
+            "eslint",
+            vec![
+                ProblemPattern {
+                    regexp: r"^((?:[a-zA-Z]:)?[./\\]+.*?)$".to_string(),
+                    file: Some(1),
+                    ..ProblemPattern::default()
+                },
+                ProblemPattern {
+                    regexp: r"^\s+(\d+):(\d+)\s+(error|warning|info)\s+(.*?)(?:\s\s+(\S+))?$"
+                        .to_string(),
+                    line: Some(1),
+                    column: Some(2),
+                    severity: Some(3),
+                    message: Some(4),
+                    code: Some(5),
+                    repeat: true,
+                    ..ProblemPattern::default()
+                },
+            ],
+        ),
+        _ => return None,
+    };
+    Some(ProblemMatcher {
+        source: Some(source.to_string()),
+        severity: ProblemSeverity::Error,
+        pattern,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn find_problems(name: &str, output: &str) -> Vec<Problem> {
+        ProblemMatcherDefinition::Builtin(name.to_string())
+            .resolve()
+            .unwrap()
+            .find_problems(output)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rustc_problem_matcher() {
+        let output = "\
+   Compiling example v0.1.0 (/home/user/example)
+error[E0425]: cannot find value `y` in this scope
+ --> src/main.rs:3:20
+  |
+3 |     println!(\"{}\", y);
+  |                    ^ not found in this scope
+
+warning: unused variable: `x`
+  --> src/lib.rs:10:9
+   |
+error: aborting due to 1 previous error
+";
+        assert_eq!(
+            find_problems("$rustc", output),
+            vec![
+                Problem {
+                    file: "src/main.rs".to_string(),
+                    line: 3,
+                    column: Some(20),
+                    severity: ProblemSeverity::Error,
+                    code: Some("E0425".to_string()),
+                    message: "cannot find value `y` in this scope".to_string(),
+                    source: Some("rustc".to_string()),
+                    ..Problem::default()
+                },
+                Problem {
+                    file: "src/lib.rs".to_string(),
+                    line: 10,
+                    column: Some(9),
+                    severity: ProblemSeverity::Warning,
+                    message: "unused variable: `x`".to_string(),
+                    source: Some("rustc".to_string()),
+                    ..Problem::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_problem_matchers() {
+        assert_eq!(
+            find_problems(
+                "$tsc",
+                "src/index.ts(12,5): error TS2322: Type 'string' is not assignable to type 'number'."
+            ),
+            vec![Problem {
+                file: "src/index.ts".to_string(),
+                line: 12,
+                column: Some(5),
+                severity: ProblemSeverity::Error,
+                code: Some("TS2322".to_string()),
+                message: "Type 'string' is not assignable to type 'number'.".to_string(),
+                source: Some("tsc".to_string()),
+                ..Problem::default()
+            }]
+        );
+        assert_eq!(
+            find_problems(
+                "$gcc",
+                "main.c:4:12: warning: unused variable 'x' [-Wunused-variable]\nmain.c: In function 'main':"
+            ),
+            vec![Problem {
+                file: "main.c".to_string(),
+                line: 4,
+                column: Some(12),
+                severity: ProblemSeverity::Warning,
+                message: "unused variable 'x' [-Wunused-variable]".to_string(),
+                source: Some("gcc".to_string()),
+                ..Problem::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_looping_problem_matcher() {
+        let output = "\
+/home/user/project/src/app.js
+  1:10  error    'foo' is defined but never used  no-unused-vars
+  3:1   warning  Unexpected console statement     no-console
+
+/home/user/project/src/other.js
+  7:2  error  Missing semicolon  semi
+
+✖ 3 problems (2 errors, 1 warning)
+";
+        let problems = find_problems("$eslint-stylish", output)
+            .into_iter()
+            .map(|problem| {
+                (
+                    problem.file,
+                    problem.line,
+                    problem.severity,
+                    problem.message,
+                    problem.code,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            vec![
+                (
+                    "/home/user/project/src/app.js".to_string(),
+                    1,
+                    ProblemSeverity::Error,
+                    "'foo' is defined but never used".to_string(),
+                    Some("no-unused-vars".to_string()),
+                ),
+                (
+                    "/home/user/project/src/app.js".to_string(),
+                    3,
+                    ProblemSeverity::Warning,
+                    "Unexpected console statement".to_string(),
+                    Some("no-console".to_string()),
+                ),
+                (
+                    "/home/user/project/src/other.js".to_string(),
+                    7,
+                    ProblemSeverity::Error,
+                    "Missing semicolon".to_string(),
+                    Some("semi".to_string()),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_problem_matcher() {
+        let definition: ProblemMatcherDefinition = serde_json::from_value(serde_json::json!({
+            "source": "lint",
+            "severity": "warning",
+            "pattern": [{
+                "regexp": "^(.*):(\\d+): (.*)$",
+                "file": 1,
+                "line": 2,
+                "message": 3
+            }]
+        }))
+        .unwrap();
+        assert_eq!(
+            definition
+                .resolve()
+                .unwrap()
+                .find_problems("README.md:7: line too long\nall done")
+                .unwrap(),
+            vec![Problem {
+                file: "README.md".to_string(),
+                line: 7,
+                severity: ProblemSeverity::Warning,
+                message: "line too long".to_string(),
+                source: Some("lint".to_string()),
+                ..Problem::default()
+            }]
+        );
+        let with_location = ProblemMatcher {
+            source: None,
+            severity: ProblemSeverity::Error,
+            pattern: vec![ProblemPattern {
+                regexp: r"^(.*)\((\d+(?:,\d+)*)\): (.*)$".to_string(),
+                file: Some(1),
+                location: Some(2),
+                message: Some(3),
+                ..ProblemPattern::default()
+            }],
+        };
+        assert_eq!(
+            with_location
+                .find_problems("a.txt(2,3,4,5): first\nb.txt(6): second")
+                .unwrap(),
+            vec![
+                Problem {
+                    file: "a.txt".to_string(),
+                    line: 2,
+                    column: Some(3),
+                    end_line: Some(4),
+                    end_column: Some(5),
+                    message: "first".to_string(),
+                    ..Problem::default()
+                },
+                Problem {
+                    file: "b.txt".to_string(),
+                    line: 6,
+                    message: "second".to_string(),
+                    ..Problem::default()
+                },
+            ]
+        );
+        assert_eq!(
+            ProblemMatcherDefinition::Builtin("$unknown".to_string()).resolve(),
+            None
+        );
+    }
+}
@@ -2,8 +2,10 @@
 
 mod adapter_schema;
 mod debug_format;
+mod problem_matcher;
 mod serde_helpers;
 pub mod static_source;
+mod task_input;
 mod task_template;
 mod vscode_debug_format;
 mod vscode_format;
@@ -21,8 +23,13 @@ pub use debug_format::{
     AttachRequest, BuildTaskDefinition, DebugRequest, DebugScenario, DebugTaskFile, LaunchRequest,
     Request, TcpArgumentsTemplate, ZedDebugConfig,
 };
+pub use problem_matcher::{
+    Problem, ProblemMatcher, ProblemMatcherDefinition, ProblemPattern, ProblemScanner,
+    ProblemSeverity,
+};
+pub use task_input::TaskInput;
 pub use task_template::{
-    DebugArgsRequest, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
+    DebugArgsRequest, DependsOrder, HideStrategy, RevealStrategy, TaskTemplate, TaskTemplates,
     substitute_variables_in_map, substitute_variables_in_str,
 };
 pub use util::shell::{Shell, ShellKind};
@@ -74,6 +81,8 @@ pub struct SpawnInTerminal {
     pub show_command: bool,
     /// Whether to show the rerun button in the terminal tab.
     pub show_rerun: bool,
+    /// How to find problems in the task's output, to show them as diagnostics.
+    pub problem_matchers: Vec<ProblemMatcher>,
 }
 
 impl SpawnInTerminal {
@@ -117,6 +126,8 @@ pub struct ResolvedTask {
     pub id: TaskId,
     /// A template the task got resolved from.
     original_task: TaskTemplate,
+    /// A context the task got resolved with.
+    task_context: TaskContext,
     /// Full, unshortened label of the task after all resolutions are made.
     pub resolved_label: String,
     /// Variables that were substituted during the task template resolution.
@@ -132,6 +143,11 @@ impl ResolvedTask {
         &self.original_task
     }
 
+    /// A context the task got resolved with, used to resolve the tasks it depends on.
+    pub fn task_context(&self) -> &TaskContext {
+        &self.task_context
+    }
+
     /// Variables that were substituted during the task template resolution.
     pub fn substituted_variables(&self) -> &HashSet<VariableName> {
         &self.substituted_variables
@@ -141,6 +157,41 @@ impl ResolvedTask {
     pub fn display_label(&self) -> &str {
         self.resolved.label.as_str()
     }
+
+    /// Inputs referenced as `${input:id}` by the task, whose values have to be entered before it is spawned.
+    pub fn inputs(&self) -> Vec<TaskInput> {
+        let resolved = &self.resolved;
+        let texts = resolved
+            .command
+            .iter()
+            .chain(&resolved.args)
+            .chain(resolved.env.values())
+            .map(String::as_str)
+            .chain(resolved.cwd.as_deref().and_then(|cwd| cwd.to_str()))
+            .chain([resolved.full_label.as_str()]);
+        task_input::referenced_inputs(texts, &self.original_task.inputs)
+    }
+
+    /// Returns the task with its `${input:id}` references replaced with the entered values.
+    pub fn with_input_values(&self, values: &HashMap<String, String>) -> Self {
+        let substitute = |text: &str| task_input::substitute_inputs(text, values);
+        let mut task = self.clone();
+        task.resolved_label = substitute(&task.resolved_label);
+        let resolved = &mut task.resolved;
+        resolved.full_label = substitute(&resolved.full_label);
+        resolved.label = substitute(&resolved.label);
+        resolved.command_label = substitute(&resolved.command_label);
+        resolved.command = resolved.command.as_deref().map(substitute);
+        resolved.args = resolved.args.iter().map(|arg| substitute(arg)).collect();
+        resolved.cwd = resolved
+            .cwd
+            .as_ref()
+            .map(|cwd| PathBuf::from(substitute(&cwd.to_string_lossy())));
+        for value in resolved.env.values_mut() {
+            *value = substitute(value);
+        }
+        task
+    }
 }
 
 /// Variables, available for use in [`TaskContext`] when a Zed's [`TaskTemplate`] gets resolved into a [`ResolvedTask`].
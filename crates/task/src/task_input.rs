@@ -0,0 +1,131 @@
+use collections::HashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const INPUT_REFERENCE_PREFIX: &str = "${input:";
+
+/// A value to ask the user for when a task is spawned, referenced as `${input:id}` in the task's fields.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TaskInput {
+    /// The name of the input, used to reference it as `${input:id}`.
+    pub id: String,
+    /// The text shown when asking for the value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The value to propose, until another one gets entered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    /// Values to pick from. When empty, any text can be entered.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+}
+
+impl TaskInput {
+    /// Whether the value has to be picked from the [`TaskInput::options`], rather than typed in.
+    pub fn is_pick_list(&self) -> bool {
+        !self.options.is_empty()
+    }
+}
+
+/// Ids of the inputs referenced as `${input:id}` in the text, in order of appearance.
+pub(crate) fn input_references(text: &str) -> impl Iterator<Item = &str> {
+    text.match_indices(INPUT_REFERENCE_PREFIX)
+        .filter_map(move |(start, _)| {
+            let id_start = start + INPUT_REFERENCE_PREFIX.len();
+            let id_len = text[id_start..].find('}')?;
+            Some(&text[id_start..id_start + id_len])
+        })
+        .filter(|id| !id.is_empty())
+}
+
+/// Replaces `${input:id}` references with the inputs' values, keeping the references to inputs without one.
+pub(crate) fn substitute_inputs(text: &str, values: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(INPUT_REFERENCE_PREFIX) {
+        let id_start = start + INPUT_REFERENCE_PREFIX.len();
+        let Some(id_len) = rest[id_start..].find('}') else {
+            break;
+        };
+        let reference_end = id_start + id_len + 1;
+        result.push_str(&rest[..start]);
+        match values.get(&rest[id_start..id_start + id_len]) {
+            Some(value) => result.push_str(value),
+            None => result.push_str(&rest[start..reference_end]),
+        }
+        rest = &rest[reference_end..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Collects the inputs referenced in the texts, in order of appearance and without duplicates.
+/// Inputs that are referenced but not declared are asked for as free text.
+pub(crate) fn referenced_inputs<'a>(
+    texts: impl IntoIterator<Item = &'a str>,
+    declared_inputs: &[TaskInput],
+) -> Vec<TaskInput> {
+    let mut inputs = Vec::<TaskInput>::new();
+    for text in texts {
+        for id in input_references(text) {
+            if inputs.iter().any(|input| input.id == id) {
+                continue;
+            }
+            inputs.push(
+                declared_inputs
+                    .iter()
+                    .find(|input| input.id == id)
+                    .cloned()
+                    .unwrap_or_else(|| TaskInput {
+                        id: id.to_string(),
+                        ..TaskInput::default()
+                    }),
+            );
+        }
+    }
+    inputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_substitution() {
+        let text = "deploy ${input:target} --filter=${input:filter} ${input:target} ${input:unset} ${input:";
+        assert_eq!(
+            input_references(text).collect::<Vec<_>>(),
+            vec!["target", "filter", "target", "unset"]
+        );
+
+        let values = HashMap::from_iter([
+            ("target".to_string(), "staging".to_string()),
+            ("filter".to_string(), String::new()),
+        ]);
+        assert_eq!(
+            substitute_inputs(text, &values),
+            "deploy staging --filter= staging ${input:unset} ${input:"
+        );
+
+        let declared = vec![TaskInput {
+            id: "filter".to_string(),
+            options: vec!["unit".to_string(), "integration".to_string()],
+            ..TaskInput::default()
+        }];
+        assert_eq!(
+            referenced_inputs([text], &declared),
+            vec![
+                TaskInput {
+                    id: "target".to_string(),
+                    ..TaskInput::default()
+                },
+                declared[0].clone(),
+                TaskInput {
+                    id: "unset".to_string(),
+                    ..TaskInput::default()
+                },
+            ]
+        );
+    }
+}
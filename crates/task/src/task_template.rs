@@ -9,8 +9,9 @@ use util::serde::default_true;
 use util::{ResultExt, truncate_and_remove_front};
 
 use crate::{
-    AttachRequest, ResolvedTask, RevealTarget, Shell, SpawnInTerminal, TaskContext, TaskId,
-    VariableName, ZED_VARIABLE_NAME_PREFIX, serde_helpers::non_empty_string_vec,
+    AttachRequest, ProblemMatcherDefinition, ResolvedTask, RevealTarget, Shell, SpawnInTerminal,
+    TaskContext, TaskId, TaskInput, VariableName, ZED_VARIABLE_NAME_PREFIX,
+    serde_helpers::non_empty_string_vec,
 };
 
 /// A template definition of a Zed task to run.
@@ -25,6 +26,7 @@ pub struct TaskTemplate {
     /// Human readable name of the task to display in the UI.
     pub label: String,
     /// Executable command to spawn.
+    #[serde(default)]
     pub command: String,
     /// Arguments to the command.
     #[serde(default)]
@@ -72,6 +74,24 @@ pub struct TaskTemplate {
     /// Whether to show the command line in the task output.
     #[serde(default = "default_true")]
     pub show_command: bool,
+    /// Labels of the tasks that have to finish successfully before this task is spawned.
+    /// A task with dependencies may omit its `command` to only run the tasks it depends on.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// How to run the tasks from `depends_on`:
+    /// * `parallel` — spawn all of them at once (default)
+    /// * `sequence` — spawn them one after another, in the order they are listed
+    #[serde(default)]
+    pub depends_order: DependsOrder,
+    /// How to find problems (errors and warnings) in the task's output, to show them as diagnostics.
+    /// Either names of built-in problem matchers, like `$rustc`, `$tsc`, `$gcc`, `$eslint-compact`
+    /// and `$eslint-stylish`, or custom regex-based problem matchers.
+    #[serde(default)]
+    pub problem_matchers: Vec<ProblemMatcherDefinition>,
+    /// Values to ask for when the task is spawned, referenced as `${input:id}` in the task's fields.
+    /// Inputs with `options` are picked from a list, other ones are typed in.
+    #[serde(default)]
+    pub inputs: Vec<TaskInput>,
 }
 
 #[derive(Deserialize, Eq, PartialEq, Clone, Debug)]
@@ -109,6 +129,17 @@ pub enum HideStrategy {
     OnSuccess,
 }
 
+/// How to run the tasks that a task depends on.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOrder {
+    /// Spawn all dependencies at once, and wait for all of them to finish.
+    #[default]
+    Parallel,
+    /// Spawn the dependencies one after another, stopping at the first one that fails.
+    Sequence,
+}
+
 /// A group of Tasks defined in a JSON file.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub struct TaskTemplates(pub Vec<TaskTemplate>);
@@ -127,6 +158,11 @@ impl TaskTemplates {
 }
 
 impl TaskTemplate {
+    /// Whether the task spawns a command of its own, rather than only running the tasks it depends on.
+    pub fn has_command(&self) -> bool {
+        !self.command.trim().is_empty()
+    }
+
     /// Replaces all `VariableName` task variables in the task template string fields.
     /// If any replacement fails or the new string substitutions still have [`ZED_VARIABLE_NAME_PREFIX`],
     /// `None` is returned.
@@ -134,7 +170,7 @@ impl TaskTemplate {
     /// Every [`ResolvedTask`] gets a [`TaskId`], based on the `id_base` (to avoid collision with various task sources),
     /// and hashes of its template and [`TaskContext`], see [`ResolvedTask`] fields' documentation for more details.
     pub fn resolve_task(&self, id_base: &str, cx: &TaskContext) -> Option<ResolvedTask> {
-        if self.label.trim().is_empty() || self.command.trim().is_empty() {
+        if self.label.trim().is_empty() || (!self.has_command() && self.depends_on.is_empty()) {
             return None;
         }
 
@@ -246,6 +282,7 @@ impl TaskTemplate {
             id: id.clone(),
             substituted_variables,
             original_task: self.clone(),
+            task_context: cx.clone(),
             resolved_label: full_label.clone(),
             resolved: SpawnInTerminal {
                 id,
@@ -272,6 +309,20 @@ impl TaskTemplate {
                 show_summary: self.show_summary,
                 show_command: self.show_command,
                 show_rerun: true,
+                problem_matchers: self
+                    .problem_matchers
+                    .iter()
+                    .filter_map(|definition| {
+                        let matcher = definition.resolve();
+                        if matcher.is_none() {
+                            log::warn!(
+                                "Unknown problem matcher {definition:?} in task `{}`",
+                                self.label
+                            );
+                        }
+                        matcher
+                    })
+                    .collect(),
             },
         })
     }
@@ -470,6 +521,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_resolving_templates_with_dependencies() {
+        let compound_task = TaskTemplate {
+            label: "build and run".to_string(),
+            depends_on: vec!["build".to_string(), "run".to_string()],
+            depends_order: DependsOrder::Sequence,
+            ..TaskTemplate::default()
+        };
+        let context = TaskContext {
+            cwd: Some(PathBuf::from("/project")),
+            ..TaskContext::default()
+        };
+
+        let resolved = compound_task
+            .resolve_task(TEST_ID_BASE, &context)
+            .expect("should resolve a task without a command if it has dependencies");
+        assert!(!resolved.original_task().has_command());
+        assert_eq!(resolved.task_context(), &context);
+
+        let parsed: TaskTemplate = serde_json::from_value(serde_json::json!({
+            "label": "build and run",
+            "depends_on": ["build", "run"],
+            "depends_order": "sequence"
+        }))
+        .unwrap();
+        assert_eq!(parsed, compound_task);
+    }
+
     #[test]
     fn test_template_cwd_resolution() {
         let task_without_cwd = TaskTemplate {
@@ -971,4 +1050,61 @@ mod tests {
             "Should fail when ZED variable has no default and doesn't exist"
         );
     }
+
+    #[test]
+    fn test_resolving_templates_with_inputs() {
+        let target = TaskInput {
+            id: "target".to_string(),
+            options: vec!["staging".to_string(), "production".to_string()],
+            ..TaskInput::default()
+        };
+        let task = TaskTemplate {
+            label: "deploy to ${input:target}".to_string(),
+            command: "./deploy.sh".to_string(),
+            args: vec![
+                "--target=${input:target}".to_string(),
+                "${input:version}".to_string(),
+            ],
+            env: HashMap::from_iter([("FILE".to_string(), VariableName::File.template_value())]),
+            inputs: vec![target.clone()],
+            ..TaskTemplate::default()
+        };
+        let context = TaskContext {
+            task_variables: TaskVariables::from_iter([(
+                VariableName::File,
+                "/project/main.rs".to_string(),
+            )]),
+            ..TaskContext::default()
+        };
+
+        let resolved = task.resolve_task(TEST_ID_BASE, &context).unwrap();
+        assert_eq!(
+            resolved.inputs(),
+            vec![
+                target,
+                TaskInput {
+                    id: "version".to_string(),
+                    ..TaskInput::default()
+                },
+            ]
+        );
+
+        let values = HashMap::from_iter([
+            ("target".to_string(), "staging".to_string()),
+            ("version".to_string(), "1.2.3".to_string()),
+        ]);
+        let resolved = resolved.with_input_values(&values);
+        assert_eq!(resolved.inputs(), Vec::new());
+        assert_eq!(resolved.resolved_label, "deploy to staging");
+        assert_eq!(resolved.resolved.label, "deploy to staging");
+        assert_eq!(
+            resolved.resolved.args,
+            vec!["--target=staging".to_string(), "1.2.3".to_string()]
+        );
+        assert_eq!(
+            resolved.resolved.command_label,
+            "./deploy.sh --target=staging 1.2.3"
+        );
+        assert_eq!(resolved.resolved.env["FILE"], "/project/main.rs");
+    }
 }
@@ -3,7 +3,9 @@ use serde::Deserialize;
 use util::ResultExt as _;
 
 use crate::{
-    DebugScenario, DebugTaskFile, EnvVariableReplacer, TcpArgumentsTemplate, VariableName,
+    DebugScenario, DebugTaskFile, EnvVariableReplacer, TaskInput, TcpArgumentsTemplate,
+    VariableName,
+    vscode_format::{VsCodeTaskInput, convert_inputs},
 };
 
 // TODO support preLaunchTask linkage with other tasks
@@ -19,7 +21,11 @@ struct VsCodeDebugTaskDefinition {
 }
 
 impl VsCodeDebugTaskDefinition {
-    fn try_to_zed(mut self, replacer: &EnvVariableReplacer) -> anyhow::Result<DebugScenario> {
+    fn try_to_zed(
+        mut self,
+        replacer: &EnvVariableReplacer,
+        inputs: &[TaskInput],
+    ) -> anyhow::Result<DebugScenario> {
         let label = replacer.replace(&self.name);
         let mut config = replacer.replace_value(self.other_attributes);
         let adapter = task_type_to_adapter_name(&self.r#type);
@@ -31,7 +37,7 @@ impl VsCodeDebugTaskDefinition {
                 config.insert("port".to_owned(), port.into());
             }
         }
-        let definition = DebugScenario {
+        let mut definition = DebugScenario {
             label: label.into(),
             build: None,
             adapter: adapter.into(),
@@ -41,7 +47,12 @@ impl VsCodeDebugTaskDefinition {
                 timeout: None,
             }),
             config,
+            inputs: inputs.to_vec(),
         };
+        let referenced_inputs = definition.inputs();
+        definition
+            .inputs
+            .retain(|input| referenced_inputs.contains(input));
         Ok(definition)
     }
 }
@@ -52,6 +63,8 @@ pub struct VsCodeDebugTaskFile {
     #[serde(default)]
     version: Option<String>,
     configurations: Vec<VsCodeDebugTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeTaskInput>,
 }
 
 impl TryFrom<VsCodeDebugTaskFile> for DebugTaskFile {
@@ -73,10 +86,11 @@ impl TryFrom<VsCodeDebugTaskFile> for DebugTaskFile {
             "pickMyProcess".to_owned(),
             VariableName::PickProcessId.to_string(),
         )]);
+        let inputs = convert_inputs(file.inputs);
         let templates = file
             .configurations
             .into_iter()
-            .filter_map(|config| config.try_to_zed(&replacer).log_err())
+            .filter_map(|config| config.try_to_zed(&replacer, &inputs).log_err())
             .collect::<Vec<_>>();
         Ok(DebugTaskFile(templates))
     }
@@ -100,7 +114,9 @@ fn task_type_to_adapter_name(task_type: &str) -> String {
 mod tests {
     use serde_json::json;
 
-    use crate::{DebugScenario, DebugTaskFile, VariableName};
+    use collections::HashMap;
+
+    use crate::{DebugScenario, DebugTaskFile, TaskInput, VariableName};
 
     use super::VsCodeDebugTaskFile;
 
@@ -152,7 +168,8 @@ mod tests {
                     "port": 17,
                 }),
                 tcp_connection: None,
-                build: None
+                build: None,
+                inputs: Vec::new(),
             }])
         );
     }
@@ -187,8 +204,65 @@ mod tests {
                     "processId": expected_placeholder,
                 }),
                 tcp_connection: None,
-                build: None
+                build: None,
+                inputs: Vec::new(),
             }])
         );
     }
+
+    #[test]
+    fn test_inputs_conversion() {
+        let raw = r#"
+            {
+                "version": "0.2.0",
+                "configurations": [
+                    {
+                        "name": "Debug ${input:target}",
+                        "request": "launch",
+                        "type": "lldb",
+                        "program": "${workspaceFolder}/target/debug/${input:target}"
+                    },
+                    {
+                        "name": "Debug tests",
+                        "request": "launch",
+                        "type": "lldb",
+                        "program": "${workspaceFolder}/target/debug/tests"
+                    }
+                ],
+                "inputs": [
+                    {
+                        "id": "target",
+                        "type": "pickString",
+                        "description": "Binary to debug",
+                        "options": ["server", { "label": "Command line client", "value": "cli" }],
+                        "default": "server"
+                    },
+                    { "id": "unused", "type": "promptString" }
+                ]
+            }
+        "#;
+        let parsed: VsCodeDebugTaskFile =
+            serde_json_lenient::from_str(raw).expect("deserializing launch.json");
+        let zed = DebugTaskFile::try_from(parsed).expect("converting to Zed debug templates");
+
+        let target = TaskInput {
+            id: "target".to_string(),
+            description: Some("Binary to debug".to_string()),
+            default: Some("server".to_string()),
+            options: vec!["server".to_string(), "cli".to_string()],
+        };
+        assert_eq!(zed.0[0].inputs, vec![target.clone()]);
+        assert_eq!(zed.0[1].inputs, Vec::new());
+
+        let scenario = zed.0[0].clone().with_input_values(&HashMap::from_iter([(
+            "target".to_string(),
+            "cli".to_string(),
+        )]));
+        assert_eq!(scenario.label.as_ref(), "Debug cli");
+        assert_eq!(
+            scenario.config["program"],
+            json!("${ZED_WORKTREE_ROOT}/target/debug/cli")
+        );
+        assert_eq!(scenario.inputs(), Vec::new());
+    }
 }
@@ -1,9 +1,13 @@
-use anyhow::bail;
+use anyhow::{Context as _, bail};
 use collections::HashMap;
 use serde::Deserialize;
 use util::ResultExt;
 
-use crate::{EnvVariableReplacer, TaskTemplate, TaskTemplates, VariableName};
+use crate::{
+    DependsOrder, EnvVariableReplacer, ProblemMatcher, ProblemMatcherDefinition, ProblemPattern,
+    ProblemSeverity, TaskInput, TaskTemplate, TaskTemplates, VariableName,
+    task_input::input_references,
+};
 
 #[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -42,23 +46,88 @@ enum Command {
 }
 
 impl VsCodeTaskDefinition {
+    /// Labels of the tasks from `dependsOn`, which is either a single label or a list of them.
+    fn depends_on(&self) -> anyhow::Result<Vec<String>> {
+        let Some(depends_on) = self.other_attributes.get("dependsOn") else {
+            return Ok(Vec::new());
+        };
+        let labels = match depends_on {
+            serde_json_lenient::Value::String(label) => vec![label.clone()],
+            serde_json_lenient::Value::Array(labels) => labels
+                .iter()
+                .map(|label| label.as_str().map(ToOwned::to_owned))
+                .collect::<Option<Vec<_>>>()
+                .with_context(|| {
+                    format!(
+                        "Task `{}` has a `dependsOn` entry that is not a task label",
+                        self.label
+                    )
+                })?,
+            _ => bail!("Task `{}` has an unsupported `dependsOn` value", self.label),
+        };
+        Ok(labels)
+    }
+
+    /// Problem matchers from `problemMatcher`, which is either a single matcher or a list of them.
+    fn problem_matchers(&self) -> anyhow::Result<Vec<ProblemMatcherDefinition>> {
+        let Some(problem_matcher) = self.other_attributes.get("problemMatcher") else {
+            return Ok(Vec::new());
+        };
+        let matchers = match problem_matcher {
+            serde_json_lenient::Value::Array(matchers) => matchers.clone(),
+            matcher => vec![matcher.clone()],
+        };
+        matchers
+            .into_iter()
+            .map(|matcher| {
+                let definition = match matcher {
+                    serde_json_lenient::Value::String(name) => {
+                        ProblemMatcherDefinition::Builtin(name)
+                    }
+                    matcher => {
+                        let matcher =
+                            serde_json_lenient::from_value::<VsCodeProblemMatcher>(matcher)
+                                .with_context(|| {
+                                    format!(
+                                        "Task `{}` has an unsupported `problemMatcher` value",
+                                        self.label
+                                    )
+                                })?;
+                        matcher.into_zed_format()
+                    }
+                };
+                Ok(definition)
+            })
+            .collect()
+    }
+
     fn into_zed_format(
         self,
         replacer: &EnvVariableReplacer,
+        inputs: &[TaskInput],
     ) -> anyhow::Result<Option<TaskTemplate>> {
-        if self.other_attributes.contains_key("dependsOn") {
-            log::warn!(
-                "Skipping deserializing of a task `{}` with the unsupported `dependsOn` key",
-                self.label
-            );
-            return Ok(None);
-        }
+        let depends_on = self.depends_on()?;
+        let problem_matchers = self.problem_matchers()?;
+        let depends_order = match self
+            .other_attributes
+            .get("dependsOrder")
+            .and_then(|order| order.as_str())
+        {
+            Some("sequence") => DependsOrder::Sequence,
+            _ => DependsOrder::Parallel,
+        };
         // `type` might not be set in e.g. tasks that use `dependsOn`; we still want to deserialize the whole object though (hence command is an Option),
-        // as that way we can provide more specific description of why deserialization failed.
-        // E.g. if the command is missing due to `dependsOn` presence, we can check other_attributes first before doing this (and provide nice error message)
-        // catch-all if on value.command presence.
+        // so that such tasks can be converted into tasks that only run their dependencies.
         let Some(command) = self.command else {
-            bail!("Missing `type` field in task");
+            if depends_on.is_empty() {
+                bail!("Missing `type` field in task");
+            }
+            return Ok(Some(TaskTemplate {
+                label: self.label,
+                depends_on,
+                depends_order,
+                ..TaskTemplate::default()
+            }));
         };
 
         let (command, args) = match command {
@@ -73,20 +142,177 @@ impl VsCodeTaskDefinition {
             label: self.label,
             command,
             args,
+            depends_on,
+            depends_order,
+            problem_matchers,
             ..TaskTemplate::default()
         };
         if let Some(options) = self.options {
             template.cwd = options.cwd.map(|cwd| replacer.replace(&cwd));
             template.env = options.env;
         }
+        let referenced_ids = [&template.label, &template.command]
+            .into_iter()
+            .chain(&template.args)
+            .chain(&template.cwd)
+            .chain(template.env.values())
+            .flat_map(|text| input_references(text))
+            .collect::<Vec<_>>();
+        template.inputs = inputs
+            .iter()
+            .filter(|input| referenced_ids.contains(&input.id.as_str()))
+            .cloned()
+            .collect();
         Ok(Some(template))
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemMatcher {
+    /// Extends one of the built-in matchers, like `$tsc`, overriding some of its properties.
+    base: Option<String>,
+    source: Option<String>,
+    owner: Option<String>,
+    severity: Option<String>,
+    pattern: Option<VsCodeProblemPatterns>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodeProblemPatterns {
+    Single(VsCodeProblemPattern),
+    Multiple(Vec<VsCodeProblemPattern>),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeProblemPattern {
+    regexp: String,
+    file: Option<usize>,
+    location: Option<usize>,
+    line: Option<usize>,
+    column: Option<usize>,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    severity: Option<usize>,
+    code: Option<usize>,
+    message: Option<usize>,
+    #[serde(default, rename = "loop")]
+    repeat: bool,
+}
+
+impl VsCodeProblemMatcher {
+    fn into_zed_format(self) -> ProblemMatcherDefinition {
+        let base = match self.base {
+            Some(base) if self.pattern.is_none() && self.severity.is_none() => {
+                // Keep referring to the built-in matcher, so that unknown ones are reported when the task is resolved.
+                if self.source.is_none() && self.owner.is_none() {
+                    return ProblemMatcherDefinition::Builtin(base);
+                }
+                ProblemMatcherDefinition::Builtin(base).resolve()
+            }
+            Some(base) => ProblemMatcherDefinition::Builtin(base).resolve(),
+            None => None,
+        };
+        let pattern = match self.pattern {
+            Some(VsCodeProblemPatterns::Single(pattern)) => vec![pattern.into_zed_format()],
+            Some(VsCodeProblemPatterns::Multiple(patterns)) => patterns
+                .into_iter()
+                .map(VsCodeProblemPattern::into_zed_format)
+                .collect(),
+            None => base
+                .as_ref()
+                .map(|base| base.pattern.clone())
+                .unwrap_or_default(),
+        };
+        let severity = self
+            .severity
+            .as_deref()
+            .and_then(ProblemSeverity::parse)
+            .or_else(|| Some(base.as_ref()?.severity))
+            .unwrap_or_default();
+        ProblemMatcherDefinition::Custom(ProblemMatcher {
+            source: self.source.or(self.owner).or_else(|| base?.source),
+            severity,
+            pattern,
+        })
+    }
+}
+
+impl VsCodeProblemPattern {
+    fn into_zed_format(self) -> ProblemPattern {
+        ProblemPattern {
+            regexp: self.regexp,
+            file: self.file,
+            location: self.location,
+            line: self.line,
+            column: self.column,
+            end_line: self.end_line,
+            end_column: self.end_column,
+            severity: self.severity,
+            code: self.code,
+            message: self.message,
+            repeat: self.repeat,
+        }
+    }
+}
+
+/// A value that VS Code asks for when a task or a debug configuration references it as `${input:id}`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct VsCodeTaskInput {
+    id: String,
+    r#type: String,
+    description: Option<String>,
+    default: Option<String>,
+    #[serde(default)]
+    options: Vec<VsCodePickOption>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+enum VsCodePickOption {
+    Value(String),
+    Labeled { value: String },
+}
+
+impl VsCodeTaskInput {
+    fn into_zed_format(self) -> anyhow::Result<TaskInput> {
+        let options = match self.r#type.as_str() {
+            "promptString" => Vec::new(),
+            "pickString" => self
+                .options
+                .into_iter()
+                .map(|option| match option {
+                    VsCodePickOption::Value(value) | VsCodePickOption::Labeled { value } => value,
+                })
+                .collect(),
+            other => bail!("Input `{}` has an unsupported type `{other}`", self.id),
+        };
+        Ok(TaskInput {
+            id: self.id,
+            description: self.description,
+            default: self.default,
+            options,
+        })
+    }
+}
+
+/// Converts the inputs that can be asked for in Zed, skipping the other ones.
+pub(crate) fn convert_inputs(inputs: Vec<VsCodeTaskInput>) -> Vec<TaskInput> {
+    inputs
+        .into_iter()
+        .filter_map(|input| input.into_zed_format().log_err())
+        .collect()
+}
+
 /// [`VsCodeTaskFile`] is a superset of Code's task definition format.
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct VsCodeTaskFile {
     tasks: Vec<VsCodeTaskDefinition>,
+    #[serde(default)]
+    inputs: Vec<VsCodeTaskInput>,
 }
 
 impl TryFrom<VsCodeTaskFile> for TaskTemplates {
@@ -105,12 +331,13 @@ impl TryFrom<VsCodeTaskFile> for TaskTemplates {
                 VariableName::SelectedText.to_string(),
             ),
         ]));
+        let inputs = convert_inputs(value.inputs);
         let templates = value
             .tasks
             .into_iter()
             .filter_map(|vscode_definition| {
                 vscode_definition
-                    .into_zed_format(&replacer)
+                    .into_zed_format(&replacer, &inputs)
                     .log_err()
                     .flatten()
             })
@@ -124,7 +351,8 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::{
-        TaskTemplate, TaskTemplates, VsCodeTaskFile,
+        DependsOrder, ProblemMatcher, ProblemMatcherDefinition, ProblemPattern, ProblemSeverity,
+        TaskInput, TaskTemplate, TaskTemplates, VsCodeTaskFile,
         vscode_format::{Command, VsCodeTaskDefinition},
     };
 
@@ -227,6 +455,7 @@ mod tests {
                 label: "gulp: tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
@@ -238,18 +467,21 @@ mod tests {
                     "${ZED_WORKTREE_ROOT}/src".to_string(),
                     "--watch".to_string(),
                 ],
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:compiler".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:compiler".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "npm: build:tests".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build:tests:notypecheck".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$tsc".to_string())],
                 ..Default::default()
             },
         ];
@@ -330,32 +562,224 @@ mod tests {
                 label: "Build Extension in Background".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "watch".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$tsc-watch".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Extension".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "build".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$tsc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server".to_string(),
                 command: "cargo build --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Build Server (Release)".to_string(),
                 command: "cargo build --release --package rust-analyzer".to_string(),
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$rustc".to_string())],
                 ..Default::default()
             },
             TaskTemplate {
                 label: "Pretest".to_string(),
                 command: "npm".to_string(),
                 args: vec!["run".to_string(), "pretest".to_string()],
+                problem_matchers: vec![ProblemMatcherDefinition::Builtin("$tsc".to_string())],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server and Extension".to_string(),
+                depends_on: vec!["Build Server".to_string(), "Build Extension".to_string()],
+                ..Default::default()
+            },
+            TaskTemplate {
+                label: "Build Server (Release) and Extension".to_string(),
+                depends_on: vec![
+                    "Build Server (Release)".to_string(),
+                    "Build Extension".to_string(),
+                ],
                 ..Default::default()
             },
         ];
         let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
         assert_eq!(tasks.0, expected);
     }
+
+    #[test]
+    fn can_deserialize_sequential_dependencies() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    { "label": "codegen", "type": "shell", "command": "make codegen" },
+                    {
+                        "label": "build",
+                        "type": "shell",
+                        "command": "make",
+                        "dependsOn": "codegen"
+                    },
+                    {
+                        "label": "run",
+                        "dependsOn": ["build", "serve"],
+                        "dependsOrder": "sequence"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "codegen".to_string(),
+                    command: "make codegen".to_string(),
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "build".to_string(),
+                    command: "make".to_string(),
+                    depends_on: vec!["codegen".to_string()],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "run".to_string(),
+                    depends_on: vec!["build".to_string(), "serve".to_string()],
+                    depends_order: DependsOrder::Sequence,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_problem_matchers() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "lint",
+                        "type": "shell",
+                        "command": "lint",
+                        "problemMatcher": [
+                            "$eslint-compact",
+                            { "base": "$gcc", "owner": "clang" },
+                            {
+                                "owner": "lint",
+                                "severity": "warning",
+                                "pattern": {
+                                    "regexp": "^(.*):(\\d+,\\d+): (.*)$",
+                                    "file": 1,
+                                    "location": 2,
+                                    "message": 3
+                                }
+                            }
+                        ]
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        let gcc = ProblemMatcherDefinition::Builtin("$gcc".to_string())
+            .resolve()
+            .unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![TaskTemplate {
+                label: "lint".to_string(),
+                command: "lint".to_string(),
+                problem_matchers: vec![
+                    ProblemMatcherDefinition::Builtin("$eslint-compact".to_string()),
+                    ProblemMatcherDefinition::Custom(ProblemMatcher {
+                        source: Some("clang".to_string()),
+                        ..gcc
+                    }),
+                    ProblemMatcherDefinition::Custom(ProblemMatcher {
+                        source: Some("lint".to_string()),
+                        severity: ProblemSeverity::Warning,
+                        pattern: vec![ProblemPattern {
+                            regexp: r"^(.*):(\d+,\d+): (.*)$".to_string(),
+                            file: Some(1),
+                            location: Some(2),
+                            message: Some(3),
+                            ..ProblemPattern::default()
+                        }],
+                    }),
+                ],
+                ..Default::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn can_deserialize_inputs() {
+        let vscode_definitions: VsCodeTaskFile = serde_json_lenient::from_str(
+            r#"{
+                "tasks": [
+                    {
+                        "label": "test",
+                        "type": "shell",
+                        "command": "cargo test -- ${input:filter}"
+                    },
+                    {
+                        "label": "deploy",
+                        "type": "shell",
+                        "command": "./deploy.sh",
+                        "options": { "env": { "TARGET": "${input:target}" } }
+                    }
+                ],
+                "inputs": [
+                    { "id": "filter", "type": "promptString", "description": "Test filter" },
+                    {
+                        "id": "target",
+                        "type": "pickString",
+                        "options": ["staging", { "label": "Production", "value": "production" }],
+                        "default": "staging"
+                    },
+                    { "id": "version", "type": "command", "command": "extension.pickVersion" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let tasks: TaskTemplates = vscode_definitions.try_into().unwrap();
+        assert_eq!(
+            tasks.0,
+            vec![
+                TaskTemplate {
+                    label: "test".to_string(),
+                    command: "cargo test -- ${input:filter}".to_string(),
+                    inputs: vec![TaskInput {
+                        id: "filter".to_string(),
+                        description: Some("Test filter".to_string()),
+                        ..TaskInput::default()
+                    }],
+                    ..Default::default()
+                },
+                TaskTemplate {
+                    label: "deploy".to_string(),
+                    command: "./deploy.sh".to_string(),
+                    env: HashMap::from_iter([(
+                        "TARGET".to_string(),
+                        "${input:target}".to_string()
+                    )])
+                    .into_iter()
+                    .collect(),
+                    inputs: vec![TaskInput {
+                        id: "target".to_string(),
+                        default: Some("staging".to_string()),
+                        options: vec!["staging".to_string(), "production".to_string()],
+                        ..TaskInput::default()
+                    }],
+                    ..Default::default()
+                },
+            ]
+        );
+    }
 }
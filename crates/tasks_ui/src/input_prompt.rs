@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use futures::channel::oneshot;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{Context, DismissEvent, Task, Window, rems};
+use picker::{Picker, PickerDelegate};
+use task::TaskInput;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::Workspace;
+
+/// Asks for the value of a task input, either picked from its options or typed in.
+pub(crate) fn prompt_for_task_input(
+    workspace: &mut Workspace,
+    input: TaskInput,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> oneshot::Receiver<Option<String>> {
+    let (tx, rx) = oneshot::channel();
+    workspace.toggle_modal(window, cx, |window, cx| {
+        let query = (!input.is_pick_list())
+            .then(|| input.default.clone())
+            .flatten();
+        let picker =
+            Picker::uniform_list(TaskInputDelegate::new(input, tx), window, cx).width(rems(34.));
+        if let Some(query) = query {
+            picker.set_query(query, window, cx);
+        }
+        picker
+    });
+    rx
+}
+
+pub(crate) struct TaskInputDelegate {
+    input: TaskInput,
+    tx: Option<oneshot::Sender<Option<String>>>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+    query: String,
+    placeholder_text: Arc<str>,
+}
+
+impl TaskInputDelegate {
+    fn new(input: TaskInput, tx: oneshot::Sender<Option<String>>) -> Self {
+        let placeholder_text = match &input.description {
+            Some(description) => Arc::from(description.as_str()),
+            None if input.is_pick_list() => Arc::from(format!("Pick a value for `{}`", input.id)),
+            None => Arc::from(format!("Enter a value for `{}`", input.id)),
+        };
+        Self {
+            input,
+            tx: Some(tx),
+            matches: Vec::new(),
+            selected_index: 0,
+            query: String::new(),
+            placeholder_text,
+        }
+    }
+}
+
+impl PickerDelegate for TaskInputDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        if self.input.is_pick_list() {
+            self.matches.len()
+        } else {
+            1
+        }
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        self.placeholder_text.clone()
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        if !self.input.is_pick_list() {
+            self.query = query;
+            return Task::ready(());
+        }
+
+        let candidates = self
+            .input
+            .options
+            .iter()
+            .enumerate()
+            .map(|(id, option)| StringMatchCandidate::new(id, option))
+            .collect::<Vec<_>>();
+        cx.spawn_in(window, async move |picker, cx| {
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                true,
+                true,
+                1000,
+                &Default::default(),
+                cx.background_executor().clone(),
+            )
+            .await;
+            picker
+                .update(cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    // Until something is typed, propose the default option.
+                    delegate.selected_index = if query.is_empty() {
+                        delegate
+                            .input
+                            .default
+                            .as_ref()
+                            .and_then(|default| {
+                                matches.iter().position(|hit| &hit.string == default)
+                            })
+                            .unwrap_or(0)
+                    } else {
+                        0
+                    };
+                    delegate.matches = matches;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let value = if self.input.is_pick_list() {
+            let Some(hit) = self.matches.get(self.selected_index) else {
+                return;
+            };
+            hit.string.clone()
+        } else {
+            self.query.clone()
+        };
+        if let Some(tx) = self.tx.take() {
+            tx.send(Some(value)).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, _window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(None).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _window: &mut Window,
+        _cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let label = if self.input.is_pick_list() {
+            let hit = self.matches.get(ix)?;
+            HighlightedLabel::new(hit.string.clone(), hit.positions.clone()).into_any_element()
+        } else if self.query.is_empty() {
+            Label::new("Use an empty value")
+                .color(Color::Muted)
+                .into_any_element()
+        } else {
+            Label::new(format!("Use \"{}\"", self.query)).into_any_element()
+        };
+        Some(
+            ListItem::new(format!("task-input-{ix}"))
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(label),
+        )
+    }
+}
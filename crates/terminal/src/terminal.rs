@@ -860,6 +860,11 @@ pub struct Terminal {
     event_loop_task: Task<Result<(), anyhow::Error>>,
 }
 
+/// Marks the grid rows that [`Terminal::read_new_output_lines`] has read. The mark moves with the
+/// row as the output scrolls into the scrollback, and is cleared when the row is reused, so it
+/// keeps pointing at the read position without comparing any text. Alacritty doesn't use this bit.
+const READ_OUTPUT_LINE: Flags = Flags::from_bits_retain(1 << 15);
+
 struct CopyTemplate {
     shell: Shell,
     env: HashMap<String, String>,
@@ -1658,6 +1663,49 @@ impl Terminal {
         lines
     }
 
+    /// Returns the lines of output added since the previous call, so that the output can be
+    /// processed as it arrives, including the lines that no longer fit in the scrollback by the
+    /// time it ends. The line with the cursor may still be written to, so it's only returned
+    /// when `include_cursor_line` is set, once the output is complete.
+    pub fn read_new_output_lines(&mut self, include_cursor_line: bool) -> Vec<String> {
+        let mut term = self.term.lock_unfair();
+        let grid = term.grid_mut();
+        let topmost_line = grid.topmost_line().0;
+        let cursor_line = grid.cursor.point.line.0;
+        let end_line = if include_cursor_line {
+            cursor_line + 1
+        } else {
+            self.find_logical_line_start(grid, cursor_line, topmost_line)
+        };
+
+        // Continue after the last row that was read. When no read row is left, all of them were
+        // dropped from the scrollback, so all the remaining rows are new.
+        let start_line = (topmost_line..end_line)
+            .rev()
+            .find(|&line| grid[Line(line)][Column(0)].flags.contains(READ_OUTPUT_LINE))
+            .map_or(topmost_line, |line| line + 1);
+
+        let mut lines = Vec::new();
+        let mut line_start = start_line;
+        while line_start < end_line {
+            let mut line_end = line_start;
+            while line_end + 1 < end_line
+                && grid[Line(line_end)][Column(grid.columns() - 1)]
+                    .flags
+                    .contains(Flags::WRAPLINE)
+            {
+                line_end += 1;
+            }
+            let line = self.construct_logical_line(grid, line_start, line_end);
+            lines.push(line.trim_end().to_string());
+            line_start = line_end + 1;
+        }
+        for line in start_line..end_line {
+            grid[Line(line)][Column(0)].flags.insert(READ_OUTPUT_LINE);
+        }
+        lines
+    }
+
     fn find_logical_line_start(&self, grid: &Grid<Cell>, current: i32, topmost: i32) -> i32 {
         let mut line_start = current;
         while line_start > topmost {
@@ -2917,6 +2965,77 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_read_new_output_lines(cx: &mut TestAppContext) {
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(CursorShape::default(), AlternateScroll::On, None, 0)
+                .unwrap()
+                .subscribe(cx)
+        });
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"first\nsecond\nthi", cx);
+            assert_eq!(terminal.read_new_output_lines(false), ["first", "second"]);
+            assert!(terminal.read_new_output_lines(false).is_empty());
+
+            terminal.write_output(b"rd\nfourth", cx);
+            assert_eq!(terminal.read_new_output_lines(false), ["third"]);
+            assert_eq!(terminal.read_new_output_lines(true), ["fourth"]);
+        });
+
+        // Without scrollback, the lines that scrolled out of the screen between two reads are
+        // lost, but the reading continues after the last line that was read.
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                Some(0),
+                0,
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output(b"1\n2\n", cx);
+            assert_eq!(terminal.read_new_output_lines(false), ["1", "2"]);
+
+            let output = (3..=200)
+                .map(|line| format!("{line}\n"))
+                .collect::<String>();
+            terminal.write_output(output.as_bytes(), cx);
+            let lines = terminal.read_new_output_lines(false);
+            assert_eq!(lines.last().map(String::as_str), Some("200"));
+            assert!(!lines.contains(&"2".to_string()));
+
+            terminal.write_output(b"201\n202\n", cx);
+            assert_eq!(terminal.read_new_output_lines(false), ["201", "202"]);
+        });
+
+        // Once the scrollback is full, repeated and blank lines don't move the read position.
+        let terminal = cx.new(|cx| {
+            TerminalBuilder::new_display_only(
+                CursorShape::default(),
+                AlternateScroll::On,
+                Some(2),
+                0,
+            )
+            .unwrap()
+            .subscribe(cx)
+        });
+        terminal.update(cx, |terminal, cx| {
+            terminal.write_output("a\n".repeat(8).as_bytes(), cx);
+            assert_eq!(terminal.read_new_output_lines(false), ["a"; 7]);
+
+            terminal.write_output(b"b\na\n", cx);
+            assert_eq!(terminal.read_new_output_lines(false), ["b", "a"]);
+
+            terminal.write_output(b"\n\n", cx);
+            assert_eq!(terminal.read_new_output_lines(false), ["", ""]);
+
+            terminal.write_output(b"c\n\n", cx);
+            assert_eq!(terminal.read_new_output_lines(false), ["c", ""]);
+        });
+    }
+
     #[gpui::test]
     async fn test_write_output_converts_lf_to_crlf(cx: &mut TestAppContext) {
         let terminal = cx.new(|cx| {
@@ -1,4 +1,11 @@
-use std::{cmp, ops::ControlFlow, path::PathBuf, process::ExitStatus, sync::Arc, time::Duration};
+use std::{
+    cmp,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+    sync::Arc,
+    time::Duration,
+};
 
 use crate::{
     TerminalView, default_working_directory,
@@ -9,17 +16,24 @@ use crate::{
 use breadcrumbs::Breadcrumbs;
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use futures::{channel::oneshot, future::join_all};
+use futures::{
+    FutureExt as _, StreamExt as _,
+    channel::{mpsc, oneshot},
+    future::join_all,
+};
 use gpui::{
     Action, AnyView, App, AsyncApp, AsyncWindowContext, Context, Corner, Entity, EventEmitter,
     ExternalPaths, FocusHandle, Focusable, IntoElement, ParentElement, Pixels, Render, Styled,
     Task, WeakEntity, Window, actions,
 };
 use itertools::Itertools;
-use project::{Fs, Project, ProjectEntryId};
+use project::{Fs, Project, ProjectEntryId, lsp_store::LspStore};
 use search::{BufferSearchBar, buffer_search::DivRegistrar};
 use settings::{Settings, TerminalDockPosition};
-use task::{RevealStrategy, RevealTarget, Shell, ShellBuilder, SpawnInTerminal, TaskId};
+use task::{
+    Problem, ProblemScanner, RevealStrategy, RevealTarget, Shell, ShellBuilder, SpawnInTerminal,
+    TaskId,
+};
 use terminal::{Terminal, terminal_settings::TerminalSettings};
 use ui::{
     ButtonLike, Clickable, ContextMenu, FluentBuilder, PopoverMenu, SplitButton, Toggleable,
@@ -1773,6 +1787,37 @@ impl workspace::TerminalProvider for TerminalProvider {
     ) -> Task<Option<Result<ExitStatus>>> {
         let terminal_panel = self.0.clone();
         window.spawn(cx, async move |cx| {
+            let problem_reporting = if task.problem_matchers.is_empty() {
+                None
+            } else {
+                terminal_panel
+                    .read_with(cx, |terminal_panel, cx| {
+                        let project = terminal_panel.workspace.upgrade()?.read(cx).project();
+                        let project = project.read(cx);
+                        // Tasks without a cwd run in the same worktree root as their terminal.
+                        let cwd = task.cwd.clone().or_else(|| {
+                            project
+                                .active_project_directory(cx)
+                                .map(|directory| directory.to_path_buf())
+                        });
+                        Some((project.lsp_store(), cwd))
+                    })
+                    .ok()
+                    .flatten()
+            };
+            // Problems reported by the previous run are stale once the task is rerun.
+            if let Some((lsp_store, cwd)) = &problem_reporting {
+                lsp_store
+                    .update(cx, |lsp_store, cx| {
+                        lsp_store.update_task_diagnostics(
+                            &task.label,
+                            cwd.as_deref(),
+                            Vec::new(),
+                            cx,
+                        )
+                    })
+                    .log_err();
+            }
             let terminal = terminal_panel
                 .update_in(cx, |terminal_panel, window, cx| {
                     terminal_panel.spawn_task(&task, window, cx)
@@ -1781,11 +1826,24 @@ impl workspace::TerminalProvider for TerminalProvider {
                 .await;
             match terminal {
                 Ok(terminal) => {
-                    let exit_status = terminal
+                    let completion = terminal
                         .read_with(cx, |terminal, cx| terminal.wait_for_completed_task(cx))
-                        .ok()?
-                        .await?;
-                    Some(Ok(exit_status))
+                        .ok()?;
+                    let exit_status = match problem_reporting {
+                        Some((lsp_store, cwd)) => {
+                            report_task_problems(
+                                &task,
+                                cwd.as_deref(),
+                                &terminal,
+                                lsp_store,
+                                completion,
+                                cx,
+                            )
+                            .await
+                        }
+                        None => completion.await,
+                    };
+                    Some(Ok(exit_status?))
                 }
                 Err(e) => Some(Err(e)),
             }
@@ -1793,6 +1851,100 @@ impl workspace::TerminalProvider for TerminalProvider {
     }
 }
 
+/// Matches the task's output against its problem matchers as the output arrives, until the task
+/// completes, and shows the problems found so far as diagnostics.
+async fn report_task_problems(
+    task: &SpawnInTerminal,
+    cwd: Option<&Path>,
+    terminal: &Entity<Terminal>,
+    lsp_store: Entity<LspStore>,
+    completion: Task<Option<ExitStatus>>,
+    cx: &mut AsyncWindowContext,
+) -> Option<ExitStatus> {
+    let scanners = task
+        .problem_matchers
+        .iter()
+        .map(|problem_matcher| problem_matcher.scanner())
+        .collect::<Result<Vec<_>>>();
+    let Some(mut scanners) = scanners.log_err() else {
+        return completion.await;
+    };
+
+    let (lines_tx, mut lines_rx) = mpsc::unbounded();
+    let subscription = cx
+        .update(|_, cx| {
+            cx.subscribe(terminal, move |terminal, event, cx| {
+                if let terminal::Event::Wakeup = event {
+                    let lines =
+                        terminal.update(cx, |terminal, _| terminal.read_new_output_lines(false));
+                    if !lines.is_empty() {
+                        lines_tx.unbounded_send(lines).ok();
+                    }
+                }
+            })
+        })
+        .ok()?;
+
+    let mut problems = Vec::new();
+    let mut completion = completion.fuse();
+    let exit_status = loop {
+        let lines = futures::select_biased! {
+            lines = lines_rx.next() => lines.unwrap_or_default(),
+            exit_status = completion => break exit_status,
+        };
+        scanners =
+            scan_task_output(task, cwd, &lsp_store, scanners, lines, &mut problems, cx).await;
+    };
+
+    drop(subscription);
+    let mut lines = Vec::new();
+    while let Ok(Some(pending_lines)) = lines_rx.try_next() {
+        lines.extend(pending_lines);
+    }
+    if let Ok(last_lines) = terminal.update(cx, |terminal, _| terminal.read_new_output_lines(true))
+    {
+        lines.extend(last_lines);
+    }
+    scan_task_output(task, cwd, &lsp_store, scanners, lines, &mut problems, cx).await;
+    exit_status
+}
+
+/// Finds the problems in the next lines of the task's output in the background, and updates
+/// the task's diagnostics when there are new ones.
+async fn scan_task_output(
+    task: &SpawnInTerminal,
+    cwd: Option<&Path>,
+    lsp_store: &Entity<LspStore>,
+    mut scanners: Vec<ProblemScanner>,
+    lines: Vec<String>,
+    problems: &mut Vec<Problem>,
+    cx: &mut AsyncWindowContext,
+) -> Vec<ProblemScanner> {
+    if lines.is_empty() {
+        return scanners;
+    }
+    let (scanners, new_problems) = cx
+        .background_spawn(async move {
+            let mut new_problems = Vec::new();
+            for line in &lines {
+                for scanner in &mut scanners {
+                    new_problems.extend(scanner.scan_line(line));
+                }
+            }
+            (scanners, new_problems)
+        })
+        .await;
+    if !new_problems.is_empty() {
+        problems.extend(new_problems);
+        lsp_store
+            .update(cx, |lsp_store, cx| {
+                lsp_store.update_task_diagnostics(&task.label, cwd, problems.clone(), cx)
+            })
+            .log_err();
+    }
+    scanners
+}
+
 struct InlineAssistTabBarButton {
     focus_handle: FocusHandle,
 }
@@ -0,0 +1,161 @@
+use crate::{Buffer, Operation, Rope};
+use anyhow::{Result, ensure};
+use std::{ops::Range, sync::Arc};
+
+/// Edits of one transaction, as sorted, non-overlapping ranges of the text before the
+/// transaction and the text replacing them.
+pub type PlainEdits = Vec<(Range<usize>, Arc<str>)>;
+
+/// The undo and redo stacks of a buffer as plain text edits. Unlike transactions, these
+/// don't refer to the buffer's operations, so they can be restored into another buffer
+/// with the same text, such as one opened in a later session.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PlainHistory {
+    /// For each transaction on the undo stack, starting with the most recent one, the
+    /// edits that undo it.
+    pub undo: Vec<PlainEdits>,
+    /// For each transaction on the redo stack, starting with the next one to redo, the
+    /// edits that redo it.
+    pub redo: Vec<PlainEdits>,
+}
+
+impl PlainHistory {
+    pub fn is_empty(&self) -> bool {
+        self.undo.is_empty() && self.redo.is_empty()
+    }
+
+    /// The number of bytes of text in the edits.
+    pub fn text_len(&self) -> usize {
+        self.undo
+            .iter()
+            .chain(&self.redo)
+            .flatten()
+            .map(|(_, text)| text.len())
+            .sum()
+    }
+}
+
+impl Buffer {
+    /// Returns the undo and redo stacks as plain text edits, by undoing and redoing the
+    /// transactions on a copy of the buffer.
+    pub fn plain_history(&self) -> PlainHistory {
+        PlainHistory {
+            undo: self.plain_transactions(Buffer::undo),
+            redo: self.plain_transactions(Buffer::redo),
+        }
+    }
+
+    fn plain_transactions(
+        &self,
+        step: fn(&mut Buffer) -> Option<(clock::Lamport, Operation)>,
+    ) -> Vec<PlainEdits> {
+        let mut buffer = self.clone_with_history();
+
+        let mut transactions = Vec::new();
+        loop {
+            let version = buffer.version();
+            if step(&mut buffer).is_none() {
+                break;
+            }
+            let edits = buffer
+                .edits_since::<usize>(&version)
+                .map(|edit| {
+                    let new_text = buffer.text_for_range(edit.new).collect::<String>();
+                    (edit.old, Arc::from(new_text))
+                })
+                .collect::<PlainEdits>();
+            if !edits.is_empty() {
+                transactions.push(edits);
+            }
+        }
+        transactions
+    }
+
+    /// Restores history returned by [`Buffer::plain_history`] while the buffer had its
+    /// current text. The text is the same afterwards, but the edits are replayed as new
+    /// transactions, whose operations are returned.
+    pub fn restore_plain_history(&mut self, history: &PlainHistory) -> Result<Vec<Operation>> {
+        ensure!(
+            self.history.undo_stack.is_empty()
+                && self.history.redo_stack.is_empty()
+                && self.history.transaction_depth == 0,
+            "buffer already has history"
+        );
+
+        // Check all the edits before changing the buffer, so that history that doesn't
+        // match the text can't leave it half restored.
+        let mut text = self.visible_text.clone();
+        let mut redo_to_current = Vec::with_capacity(history.undo.len());
+        for edits in &history.undo {
+            redo_to_current.push(apply_plain_edits(&mut text, edits)?);
+        }
+        let mut text = self.visible_text.clone();
+        for edits in &history.redo {
+            apply_plain_edits(&mut text, edits)?;
+        }
+
+        let mut operations = Vec::new();
+        if !history.undo.is_empty() {
+            // Revert to the text before the oldest transaction in one transaction, which
+            // is forgotten afterwards, so that it can't be undone.
+            self.start_transaction();
+            for edits in &history.undo {
+                operations.push(self.edit(edits.iter().cloned()));
+            }
+            let base_transaction_id = self.end_transaction().map(|(id, _)| id);
+            self.finalize_last_transaction();
+
+            for edits in redo_to_current.iter().rev() {
+                operations.push(self.edit_in_own_transaction(edits));
+            }
+            if let Some(base_transaction_id) = base_transaction_id {
+                self.forget_transaction(base_transaction_id);
+            }
+        }
+
+        for edits in &history.redo {
+            operations.push(self.edit_in_own_transaction(edits));
+        }
+        for _ in &history.redo {
+            operations.extend(self.undo().map(|(_, operation)| operation));
+        }
+        Ok(operations)
+    }
+
+    fn edit_in_own_transaction(&mut self, edits: &PlainEdits) -> Operation {
+        self.start_transaction();
+        let operation = self.edit(edits.iter().cloned());
+        self.end_transaction();
+        self.finalize_last_transaction();
+        operation
+    }
+}
+
+/// Applies edits to the text if they are valid for it, returning the edits that revert them.
+fn apply_plain_edits(text: &mut Rope, edits: &PlainEdits) -> Result<PlainEdits> {
+    let mut previous_end = 0;
+    for (range, _) in edits {
+        ensure!(
+            previous_end <= range.start
+                && range.start <= range.end
+                && range.end <= text.len()
+                && text.is_char_boundary(range.start)
+                && text.is_char_boundary(range.end),
+            "invalid edit range {range:?}"
+        );
+        previous_end = range.end;
+    }
+
+    let mut inverse = Vec::with_capacity(edits.len());
+    let mut delta = 0_isize;
+    for (range, new_text) in edits {
+        let start = (range.start as isize + delta) as usize;
+        let old_text = text.chunks_in_range(range.clone()).collect::<String>();
+        inverse.push((start..start + new_text.len(), Arc::from(old_text)));
+        delta += new_text.len() as isize - range.len() as isize;
+    }
+    for (range, new_text) in edits.iter().rev() {
+        text.replace(range.clone(), new_text);
+    }
+    Ok(inverse)
+}
@@ -630,6 +630,131 @@ fn test_history() {
     assert_eq!(buffer.text(), "X12cde6");
 }
 
+#[test]
+fn test_plain_history() {
+    let mut buffer = Buffer::new(ReplicaId::LOCAL, BufferId::new(1).unwrap(), "one two");
+    buffer.edit([(0..3, "1")]);
+    buffer.edit([(2..2, "three "), (5..5, "!")]);
+    buffer.edit([(0..0, "zero ")]);
+    buffer.edit([(0..0, "α")]);
+    buffer.undo();
+    assert_eq!(buffer.text(), "zero 1 three two!");
+
+    let history = buffer.plain_history();
+    assert_eq!(history.undo.len(), 3);
+    assert_eq!(history.redo.len(), 1);
+    // Capturing the history doesn't change the buffer.
+    assert_eq!(buffer.text(), "zero 1 three two!");
+
+    let mut restored = Buffer::new(ReplicaId::LOCAL, BufferId::new(2).unwrap(), buffer.text());
+    restored.restore_plain_history(&history).unwrap();
+    assert_eq!(restored.text(), "zero 1 three two!");
+    assert_eq!(restored.plain_history(), history);
+
+    restored.redo();
+    assert_eq!(restored.text(), "αzero 1 three two!");
+    restored.undo();
+    restored.undo();
+    assert_eq!(restored.text(), "1 three two!");
+    restored.undo();
+    assert_eq!(restored.text(), "1 two");
+    restored.undo();
+    assert_eq!(restored.text(), "one two");
+    assert!(restored.undo().is_none());
+    assert_eq!(restored.text(), "one two");
+
+    // History that doesn't match the text is rejected without changing the buffer.
+    let mut other = Buffer::new(ReplicaId::LOCAL, BufferId::new(3).unwrap(), "short");
+    assert!(other.restore_plain_history(&history).is_err());
+    assert_eq!(other.text(), "short");
+    assert!(other.undo().is_none());
+}
+
+#[test]
+fn test_undo_tree() {
+    let mut buffer = Buffer::new(ReplicaId::LOCAL, BufferId::new(1).unwrap(), "");
+    buffer.edit([(0..0, "a")]);
+    let a = buffer.peek_undo_stack().unwrap().transaction_id();
+    buffer.edit([(1..1, "b")]);
+    let b = buffer.peek_undo_stack().unwrap().transaction_id();
+    buffer.undo();
+    buffer.edit([(1..1, "c")]);
+    let c = buffer.peek_undo_stack().unwrap().transaction_id();
+    assert_eq!(buffer.text(), "ac");
+
+    // The replaced transaction can't be redone, but is kept in the tree.
+    buffer.redo();
+    assert_eq!(buffer.text(), "ac");
+    let tree = buffer.undo_tree();
+    assert_eq!(tree.current, Some(c));
+    assert_eq!(
+        tree.nodes
+            .iter()
+            .map(|node| (node.transaction_id, node.parent))
+            .collect::<Vec<_>>(),
+        [(a, None), (b, Some(a)), (c, Some(a))]
+    );
+    assert_eq!(tree.path(Some(b)), Some(vec![a, b]));
+    assert_eq!(tree.earlier(1), Some(b));
+    assert_eq!(tree.earlier(2), Some(a));
+    assert_eq!(tree.earlier(3), None);
+    assert_eq!(tree.later(1), Some(c));
+    assert_eq!(
+        buffer.transaction_changes(c),
+        [TransactionChange {
+            row: 0,
+            old_text: String::new(),
+            new_text: "c".into(),
+        }]
+    );
+
+    buffer.go_to_undo_state(Some(b));
+    assert_eq!(buffer.text(), "ab");
+    assert_eq!(buffer.undo_tree().current, Some(b));
+    buffer.undo();
+    assert_eq!(buffer.text(), "a");
+    buffer.redo();
+    assert_eq!(buffer.text(), "ab");
+
+    buffer.go_to_undo_state(Some(c));
+    assert_eq!(buffer.text(), "ac");
+    buffer.go_to_undo_state(None);
+    assert_eq!(buffer.text(), "");
+    assert_eq!(buffer.undo_tree().current, None);
+
+    // Redoing continues along the branch that was visited last.
+    buffer.redo();
+    assert_eq!(buffer.text(), "a");
+    buffer.redo();
+    assert_eq!(buffer.text(), "ac");
+    assert_eq!(buffer.undo_tree().nodes.len(), 3);
+}
+
+#[test]
+fn test_trim_abandoned_transactions() {
+    let mut buffer = Buffer::new(ReplicaId::LOCAL, BufferId::new(1).unwrap(), "");
+    buffer.edit([(0..0, "a")]);
+    let a = buffer.peek_undo_stack().unwrap().transaction_id();
+    buffer.edit([(1..1, "b")]);
+    let b = buffer.peek_undo_stack().unwrap().transaction_id();
+    buffer.undo();
+    buffer.undo();
+
+    // The oldest branch is dropped together with the transactions after it.
+    for _ in 0..crate::undo_tree::MAX_ABANDONED_TRANSACTIONS {
+        buffer.edit([(0..0, "c")]);
+        buffer.undo();
+    }
+    let tree = buffer.undo_tree();
+    assert_eq!(
+        tree.nodes.len(),
+        crate::undo_tree::MAX_ABANDONED_TRANSACTIONS
+    );
+    assert!(tree.node(a).is_none());
+    assert!(tree.node(b).is_none());
+    assert!(tree.nodes.iter().all(|node| node.parent.is_none()));
+}
+
 #[test]
 fn test_finalize_last_transaction() {
     let now = Instant::now();
@@ -4,11 +4,13 @@ pub mod locator;
 pub mod network;
 pub mod operation_queue;
 mod patch;
+mod plain_history;
 mod selection;
 pub mod subscription;
 #[cfg(test)]
 mod tests;
 mod undo_map;
+mod undo_tree;
 
 pub use anchor::*;
 use anyhow::{Context as _, Result};
@@ -18,6 +20,7 @@ use collections::{HashMap, HashSet};
 use locator::Locator;
 use operation_queue::OperationQueue;
 pub use patch::Patch;
+pub use plain_history::*;
 use postage::{oneshot, prelude::*};
 
 use regex::Regex;
@@ -39,6 +42,7 @@ pub use subscription::*;
 pub use sum_tree::Bias;
 use sum_tree::{Dimensions, FilterCursor, SumTree, TreeMap, TreeSet};
 use undo_map::UndoMap;
+pub use undo_tree::*;
 use util::debug_panic;
 
 #[cfg(any(test, feature = "test-support"))]
@@ -148,6 +152,9 @@ struct History {
     operations: TreeMap<clock::Lamport, Operation>,
     undo_stack: Vec<HistoryEntry>,
     redo_stack: Vec<HistoryEntry>,
+    /// Transactions that were on the redo stack when new edits were made, which are kept as
+    /// branches of the undo tree.
+    abandoned: Vec<undo_tree::AbandonedEntry>,
     transaction_depth: usize,
     group_interval: Duration,
 }
@@ -192,6 +199,7 @@ impl History {
             operations: Default::default(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            abandoned: Vec::new(),
             transaction_depth: 0,
             // Don't group transactions in tests unless we opt in, because it's a footgun.
             #[cfg(any(test, feature = "test-support"))]
@@ -245,7 +253,13 @@ impl History {
                 self.undo_stack.pop();
                 None
             } else {
-                self.redo_stack.clear();
+                let fork = self
+                    .undo_stack
+                    .len()
+                    .checked_sub(2)
+                    .map(|ix| self.undo_stack[ix].transaction.id);
+                self.abandon_redo_stack(fork);
+                self.trim_abandoned();
                 let entry = self.undo_stack.last_mut().unwrap();
                 entry.last_edit_at = now;
                 Some(entry)
@@ -300,6 +314,12 @@ impl History {
             if let Some(entry) = entries_to_merge.last_mut() {
                 last_entry.last_edit_at = entry.last_edit_at;
             }
+
+            let last_entry_id = last_entry.transaction.id;
+            for ix in new_len..self.undo_stack.len() {
+                let merged_id = self.undo_stack[ix].transaction.id;
+                self.reparent_abandoned(merged_id, Some(last_entry_id));
+            }
         }
 
         self.undo_stack.truncate(new_len);
@@ -408,12 +428,22 @@ impl History {
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
+            let parent = entry_ix
+                .checked_sub(1)
+                .map(|ix| self.undo_stack[ix].transaction.id);
+            self.reparent_abandoned(transaction_id, parent);
             Some(self.undo_stack.remove(entry_ix).transaction)
         } else if let Some(entry_ix) = self
             .redo_stack
             .iter()
             .rposition(|entry| entry.transaction.id == transaction_id)
         {
+            let parent = self
+                .redo_stack
+                .get(entry_ix + 1)
+                .or(self.undo_stack.last())
+                .map(|entry| entry.transaction.id);
+            self.reparent_abandoned(transaction_id, parent);
             Some(self.redo_stack.remove(entry_ix).transaction)
         } else {
             None
@@ -797,6 +827,25 @@ impl Buffer {
         }
     }
 
+    /// Returns a copy of the buffer with the same undo and redo stacks, in which transactions
+    /// can be undone or redone without affecting this buffer.
+    pub fn clone_with_history(&self) -> Self {
+        Self {
+            snapshot: self.snapshot.clone(),
+            history: History {
+                undo_stack: self.history.undo_stack.clone(),
+                redo_stack: self.history.redo_stack.clone(),
+                ..History::new(self.history.base_text.clone())
+            },
+            deferred_ops: OperationQueue::new(),
+            deferred_replicas: HashSet::default(),
+            lamport_clock: self.lamport_clock,
+            subscriptions: Default::default(),
+            edit_id_resolvers: Default::default(),
+            wait_for_version_txs: Default::default(),
+        }
+    }
+
     pub fn replica_id(&self) -> ReplicaId {
         self.lamport_clock.replica_id
     }
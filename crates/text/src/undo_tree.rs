@@ -0,0 +1,337 @@
+use crate::{Buffer, History, HistoryEntry, Operation, TransactionId};
+use std::time::{Duration, Instant};
+
+/// The number of abandoned transactions that are kept as branches of the undo tree. They
+/// aren't stored with the persistent undo history, so once there are more of them, the
+/// oldest branches are dropped instead of letting the history grow for the whole session.
+pub(crate) const MAX_ABANDONED_TRANSACTIONS: usize = 1000;
+
+/// A transaction that was undone and then replaced by new edits.
+#[derive(Clone, Debug)]
+pub(crate) struct AbandonedEntry {
+    pub(crate) entry: HistoryEntry,
+    /// The transaction that was applied before this one, or `None` if it was the first.
+    pub(crate) parent: Option<TransactionId>,
+}
+
+/// A state of the buffer's undo tree, reached by applying a transaction in the state of its
+/// parent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UndoTreeNode {
+    pub transaction_id: TransactionId,
+    /// The state this one was reached from, or `None` for the state before any transaction.
+    pub parent: Option<TransactionId>,
+    pub first_edit_at: Instant,
+    pub last_edit_at: Instant,
+}
+
+/// The states the buffer's text went through, including those that were undone and then
+/// replaced by new edits, which the undo stack alone can't return to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UndoTree {
+    /// The states in the order their transactions were started.
+    pub nodes: Vec<UndoTreeNode>,
+    /// The current state, or `None` for the state before any transaction.
+    pub current: Option<TransactionId>,
+}
+
+/// A change made by a transaction, for previewing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionChange {
+    /// The row of the change in the current text.
+    pub row: u32,
+    pub old_text: String,
+    pub new_text: String,
+}
+
+impl UndoTree {
+    pub fn node(&self, transaction_id: TransactionId) -> Option<&UndoTreeNode> {
+        let ix = self.index(transaction_id)?;
+        Some(&self.nodes[ix])
+    }
+
+    fn index(&self, transaction_id: TransactionId) -> Option<usize> {
+        self.nodes
+            .binary_search_by_key(&transaction_id, |node| node.transaction_id)
+            .ok()
+    }
+
+    pub fn children(
+        &self,
+        parent: Option<TransactionId>,
+    ) -> impl Iterator<Item = &UndoTreeNode> + '_ {
+        self.nodes.iter().filter(move |node| node.parent == parent)
+    }
+
+    /// Returns the transactions leading from the state before any transaction to the given one.
+    pub fn path(&self, state: Option<TransactionId>) -> Option<Vec<TransactionId>> {
+        let mut path = Vec::new();
+        let mut next = state;
+        while let Some(transaction_id) = next {
+            if path.len() == self.nodes.len() {
+                return None;
+            }
+            path.push(transaction_id);
+            next = self.node(transaction_id)?.parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Returns the state that was created `steps` states before the current one, regardless
+    /// of the branch it is on, as Vim's `:earlier {count}` does.
+    pub fn earlier(&self, steps: usize) -> Option<TransactionId> {
+        let current_ix = self.current.and_then(|id| self.index(id))?;
+        let ix = current_ix.checked_sub(steps)?;
+        Some(self.nodes[ix].transaction_id)
+    }
+
+    /// Returns the state that was created `steps` states after the current one, regardless
+    /// of the branch it is on, as Vim's `:later {count}` does.
+    pub fn later(&self, steps: usize) -> Option<TransactionId> {
+        let ix = match self.current.and_then(|id| self.index(id)) {
+            Some(current_ix) => current_ix + steps,
+            None => steps.checked_sub(1)?,
+        };
+        let ix = ix.min(self.nodes.len().checked_sub(1)?);
+        Some(self.nodes[ix].transaction_id)
+    }
+
+    /// Returns the last state that the text was in `duration` before the current state was
+    /// last edited, as Vim's `:earlier {N}m` does.
+    pub fn earlier_by(&self, duration: Duration) -> Option<TransactionId> {
+        let current_ix = self.current.and_then(|id| self.index(id))?;
+        let time = self.nodes[current_ix].last_edit_at.checked_sub(duration)?;
+        self.nodes[..current_ix]
+            .iter()
+            .rfind(|node| node.last_edit_at <= time)
+            .map(|node| node.transaction_id)
+    }
+
+    /// Returns the last state that the text was in `duration` after the current state was
+    /// last edited, as Vim's `:later {N}m` does.
+    pub fn later_by(&self, duration: Duration) -> Option<TransactionId> {
+        let current_ix = self.current.and_then(|id| self.index(id));
+        let time = match current_ix {
+            Some(ix) => self.nodes[ix].last_edit_at,
+            None => self.nodes.first()?.first_edit_at,
+        } + duration;
+        let start_ix = current_ix.map_or(0, |ix| ix + 1);
+        self.nodes[start_ix..]
+            .iter()
+            .rfind(|node| node.last_edit_at <= time)
+            .or(current_ix.map(|ix| &self.nodes[ix]))
+            .map(|node| node.transaction_id)
+    }
+}
+
+impl History {
+    /// Keeps the transactions on the redo stack as a branch of the undo tree after `fork`,
+    /// as they are about to be replaced by new edits.
+    pub(crate) fn abandon_redo_stack(&mut self, fork: Option<TransactionId>) {
+        let mut parent = fork;
+        for entry in self.redo_stack.drain(..).rev() {
+            let transaction_id = entry.transaction.id;
+            self.abandoned.push(AbandonedEntry { entry, parent });
+            parent = Some(transaction_id);
+        }
+    }
+
+    /// Drops the oldest abandoned transactions, together with the branches continuing after
+    /// them, until at most [`MAX_ABANDONED_TRANSACTIONS`] are left.
+    pub(crate) fn trim_abandoned(&mut self) {
+        while self.abandoned.len() > MAX_ABANDONED_TRANSACTIONS {
+            let Some(oldest_ix) = self
+                .abandoned
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, abandoned)| abandoned.entry.transaction.id)
+                .map(|(ix, _)| ix)
+            else {
+                break;
+            };
+            let mut dropped = vec![self.abandoned.remove(oldest_ix).entry.transaction.id];
+            while let Some(parent) = dropped.pop() {
+                self.abandoned.retain(|abandoned| {
+                    let is_child = abandoned.parent == Some(parent);
+                    if is_child {
+                        dropped.push(abandoned.entry.transaction.id);
+                    }
+                    !is_child
+                });
+            }
+        }
+    }
+
+    /// Attaches the branches after a transaction that no longer exists to another one.
+    pub(crate) fn reparent_abandoned(
+        &mut self,
+        transaction_id: TransactionId,
+        parent: Option<TransactionId>,
+    ) {
+        for abandoned in &mut self.abandoned {
+            if abandoned.parent == Some(transaction_id) {
+                abandoned.parent = parent;
+            }
+        }
+    }
+
+    /// Moves the most recent branch after the given state to the redo stack, so that redoing
+    /// continues along it.
+    fn restore_latest_branch(&mut self, mut parent: Option<TransactionId>) {
+        let mut entries = Vec::new();
+        while let Some(ix) = self
+            .abandoned
+            .iter()
+            .enumerate()
+            .filter(|(_, abandoned)| abandoned.parent == parent)
+            .max_by_key(|(_, abandoned)| abandoned.entry.transaction.id)
+            .map(|(ix, _)| ix)
+        {
+            let entry = self.abandoned.remove(ix).entry;
+            parent = Some(entry.transaction.id);
+            entries.push(entry);
+        }
+        entries.reverse();
+        self.redo_stack = entries;
+    }
+}
+
+impl Buffer {
+    pub fn undo_tree(&self) -> UndoTree {
+        let mut nodes = Vec::new();
+        let mut parent = None;
+        for entry in self
+            .history
+            .undo_stack
+            .iter()
+            .chain(self.history.redo_stack.iter().rev())
+        {
+            nodes.push(undo_tree_node(entry, parent));
+            parent = Some(entry.transaction.id);
+        }
+        for abandoned in &self.history.abandoned {
+            nodes.push(undo_tree_node(&abandoned.entry, abandoned.parent));
+        }
+        nodes.sort_by_key(|node| node.transaction_id);
+
+        UndoTree {
+            nodes,
+            current: self
+                .history
+                .undo_stack
+                .last()
+                .map(|entry| entry.transaction.id),
+        }
+    }
+
+    /// Undoes and redoes transactions to get to the state of the undo tree after the given
+    /// transaction, or to the state before any transaction if it is `None`. Returns the
+    /// operations of the undos and redos.
+    pub fn go_to_undo_state(&mut self, target: Option<TransactionId>) -> Vec<Operation> {
+        let mut operations = Vec::new();
+        if self.history.transaction_depth > 0 {
+            return operations;
+        }
+        let Some(target_path) = self.undo_tree().path(target) else {
+            return operations;
+        };
+        let common_len = self
+            .history
+            .undo_stack
+            .iter()
+            .zip(&target_path)
+            .take_while(|(entry, transaction_id)| entry.transaction.id == **transaction_id)
+            .count();
+
+        while self.history.undo_stack.len() > common_len {
+            let Some((_, operation)) = self.undo() else {
+                break;
+            };
+            operations.push(operation);
+        }
+
+        for &transaction_id in &target_path[common_len..] {
+            if self
+                .history
+                .redo_stack
+                .last()
+                .is_some_and(|entry| entry.transaction.id == transaction_id)
+            {
+                operations.extend(self.redo().map(|(_, operation)| operation));
+            } else if let Some(ix) = self
+                .history
+                .abandoned
+                .iter()
+                .position(|abandoned| abandoned.entry.transaction.id == transaction_id)
+            {
+                // Switching branches abandons the one that could be redone until now.
+                let fork = self.history.undo_stack.last().map(|e| e.transaction.id);
+                self.history.abandon_redo_stack(fork);
+                let entry = self.history.abandoned.remove(ix).entry;
+                operations.push(self.undo_or_redo(entry.transaction.clone()));
+                self.history.undo_stack.push(entry);
+            } else {
+                break;
+            }
+        }
+
+        if self.history.redo_stack.is_empty() {
+            let current = self.history.undo_stack.last().map(|e| e.transaction.id);
+            self.history.restore_latest_branch(current);
+        }
+        self.history.finalize_last_transaction();
+        operations
+    }
+
+    /// Returns the changes made by a transaction in the undo tree, by undoing or redoing it
+    /// on a copy of the buffer.
+    pub fn transaction_changes(&self, transaction_id: TransactionId) -> Vec<TransactionChange> {
+        let history = &self.history;
+        let Some((transaction, is_applied)) = history
+            .undo_stack
+            .iter()
+            .map(|entry| (entry, true))
+            .chain(history.redo_stack.iter().map(|entry| (entry, false)))
+            .chain(
+                history
+                    .abandoned
+                    .iter()
+                    .map(|abandoned| (&abandoned.entry, false)),
+            )
+            .find(|(entry, _)| entry.transaction.id == transaction_id)
+            .map(|(entry, is_applied)| (entry.transaction.clone(), is_applied))
+        else {
+            return Vec::new();
+        };
+
+        let mut buffer = self.clone_with_history();
+        buffer.undo_or_redo(transaction);
+        buffer
+            .edits_since::<usize>(&self.version)
+            .map(|edit| {
+                let current_text = self.text_for_range(edit.old.clone()).collect::<String>();
+                let toggled_text = buffer.text_for_range(edit.new).collect::<String>();
+                let (old_text, new_text) = if is_applied {
+                    (toggled_text, current_text)
+                } else {
+                    (current_text, toggled_text)
+                };
+                TransactionChange {
+                    row: self.offset_to_point(edit.old.start).row,
+                    old_text,
+                    new_text,
+                }
+            })
+            .collect()
+    }
+}
+
+fn undo_tree_node(entry: &HistoryEntry, parent: Option<TransactionId>) -> UndoTreeNode {
+    UndoTreeNode {
+        transaction_id: entry.transaction.id,
+        parent,
+        first_edit_at: entry.first_edit_at,
+        last_edit_at: entry.last_edit_at,
+    }
+}
@@ -0,0 +1,332 @@
+use anyhow::Result;
+use indexmap::IndexMap;
+use strum::IntoEnumIterator;
+use theme::{
+    FontStyleContent, FontWeightContent, HighlightStyleContent, PlayerColorContent,
+    StatusColorsContent, ThemeColorsContent, ThemeContent, ThemeStyleContent,
+    WindowBackgroundContent,
+};
+
+use crate::ThemeMetadata;
+use crate::jetbrains::{JetBrainsColorScheme, JetBrainsTextAttributes};
+use crate::vscode::ZedSyntaxToken;
+
+/// The `<colors>` that are converted to Zed theme colors.
+const CONVERTED_COLORS: &[&str] = &[
+    "ADDED_LINES_COLOR",
+    "CARET_COLOR",
+    "CARET_ROW_COLOR",
+    "CONSOLE_BACKGROUND_KEY",
+    "DELETED_LINES_COLOR",
+    "GUTTER_BACKGROUND",
+    "INDENT_GUIDE",
+    "LINE_NUMBERS_COLOR",
+    "LINE_NUMBER_ON_CARET_ROW_COLOR",
+    "MODIFIED_LINES_COLOR",
+    "RIGHT_MARGIN_COLOR",
+    "SELECTED_INDENT_GUIDE",
+    "SELECTION_BACKGROUND",
+    "WHITESPACES",
+];
+
+/// The `<attributes>` that are converted to Zed theme colors, rather than syntax highlights.
+const CONVERTED_UI_ATTRIBUTES: &[&str] = &[
+    "ERRORS_ATTRIBUTES",
+    "INFO_ATTRIBUTES",
+    "SEARCH_RESULT_ATTRIBUTES",
+    "TEXT",
+    "WARNING_ATTRIBUTES",
+];
+
+pub struct JetBrainsThemeConverter {
+    scheme: JetBrainsColorScheme,
+    theme_metadata: ThemeMetadata,
+}
+
+impl JetBrainsThemeConverter {
+    pub fn new(scheme: JetBrainsColorScheme, theme_metadata: ThemeMetadata) -> Self {
+        Self {
+            scheme,
+            theme_metadata,
+        }
+    }
+
+    /// Describes the parts of the scheme that have no equivalent in the converted Zed theme.
+    pub fn unmapped(&self) -> Vec<String> {
+        let mut unmapped = Vec::new();
+        for name in self.scheme.colors.keys() {
+            if !CONVERTED_COLORS.contains(&name.as_str()) {
+                unmapped.push(format!("color `{name}`"));
+            }
+        }
+        for name in self.scheme.attributes.keys() {
+            let is_converted = CONVERTED_UI_ATTRIBUTES.contains(&name.as_str())
+                || ZedSyntaxToken::iter()
+                    .any(|token| jetbrains_attributes(token).contains(&name.as_str()));
+            if !is_converted {
+                unmapped.push(format!("attribute `{name}`"));
+            }
+        }
+        if let Some(parent_scheme) = &self.scheme.parent_scheme {
+            unmapped.push(format!(
+                "values inherited from the `{parent_scheme}` scheme, which aren't part of the file"
+            ));
+        }
+        unmapped
+    }
+
+    pub fn convert(self) -> Result<ThemeContent> {
+        let appearance = self.theme_metadata.appearance.into();
+
+        Ok(ThemeContent {
+            name: self.theme_metadata.name.clone(),
+            appearance,
+            style: ThemeStyleContent {
+                window_background_appearance: Some(WindowBackgroundContent::Opaque),
+                accents: Vec::new(),
+                colors: self.convert_theme_colors(),
+                status: self.convert_status_colors(),
+                players: self.convert_player_colors(),
+                syntax: self.convert_syntax_theme(),
+            },
+        })
+    }
+
+    fn color(&self, name: &str) -> Option<String> {
+        self.scheme.colors.get(name).cloned()
+    }
+
+    fn attributes(&self, name: &str) -> Option<&JetBrainsTextAttributes> {
+        self.scheme.attributes.get(name)
+    }
+
+    fn convert_theme_colors(&self) -> ThemeColorsContent {
+        let text = self.scheme.text();
+        let background = text.and_then(|text| text.background.clone());
+        let foreground = text.and_then(|text| text.foreground.clone());
+
+        ThemeColorsContent {
+            background: background.clone(),
+            text: foreground.clone(),
+            editor_background: background.clone(),
+            editor_foreground: foreground.clone(),
+            editor_gutter_background: self.color("GUTTER_BACKGROUND").or(background.clone()),
+            editor_active_line_background: self.color("CARET_ROW_COLOR"),
+            editor_line_number: self.color("LINE_NUMBERS_COLOR"),
+            editor_active_line_number: self
+                .color("LINE_NUMBER_ON_CARET_ROW_COLOR")
+                .or(foreground.clone()),
+            editor_invisible: self.color("WHITESPACES"),
+            editor_indent_guide: self.color("INDENT_GUIDE"),
+            editor_indent_guide_active: self.color("SELECTED_INDENT_GUIDE"),
+            editor_wrap_guide: self.color("RIGHT_MARGIN_COLOR"),
+            search_match_background: self
+                .attributes("SEARCH_RESULT_ATTRIBUTES")
+                .and_then(|attributes| attributes.background.clone()),
+            terminal_background: self.color("CONSOLE_BACKGROUND_KEY").or(background),
+            terminal_foreground: foreground,
+            ..Default::default()
+        }
+    }
+
+    fn convert_status_colors(&self) -> StatusColorsContent {
+        // Problems are drawn as underlines in the effect color.
+        let effect_color = |name: &str| {
+            self.attributes(name)
+                .and_then(|attributes| attributes.effect_color.clone())
+        };
+
+        StatusColorsContent {
+            created: self.color("ADDED_LINES_COLOR"),
+            modified: self.color("MODIFIED_LINES_COLOR"),
+            deleted: self.color("DELETED_LINES_COLOR"),
+            error: effect_color("ERRORS_ATTRIBUTES"),
+            warning: effect_color("WARNING_ATTRIBUTES"),
+            info: effect_color("INFO_ATTRIBUTES"),
+            ..Default::default()
+        }
+    }
+
+    fn convert_player_colors(&self) -> Vec<PlayerColorContent> {
+        let cursor = self.color("CARET_COLOR");
+        let selection = self.color("SELECTION_BACKGROUND");
+        if cursor.is_none() && selection.is_none() {
+            return Vec::new();
+        }
+        vec![PlayerColorContent {
+            cursor: cursor.clone(),
+            background: cursor,
+            selection,
+        }]
+    }
+
+    fn convert_syntax_theme(&self) -> IndexMap<String, HighlightStyleContent> {
+        let mut highlight_styles = IndexMap::default();
+
+        for syntax_token in ZedSyntaxToken::iter() {
+            let find_attributes = |token: ZedSyntaxToken| {
+                jetbrains_attributes(token)
+                    .iter()
+                    .find_map(|name| self.attributes(name))
+            };
+            let Some(attributes) = find_attributes(syntax_token).or_else(|| {
+                syntax_token
+                    .fallbacks()
+                    .iter()
+                    .find_map(|fallback| find_attributes(*fallback))
+            }) else {
+                log::warn!("No matching attributes found for '{syntax_token}'");
+                continue;
+            };
+
+            let highlight_style = HighlightStyleContent {
+                color: attributes.foreground.clone(),
+                background_color: attributes.background.clone(),
+                font_style: attributes.italic.then_some(FontStyleContent::Italic),
+                font_weight: attributes.bold.then_some(FontWeightContent::BOLD),
+            };
+
+            if highlight_style.is_empty() {
+                continue;
+            }
+
+            highlight_styles.insert(syntax_token.to_string(), highlight_style);
+        }
+
+        highlight_styles
+    }
+}
+
+/// Returns the attributes of a JetBrains color scheme that style a [`ZedSyntaxToken`], in order of
+/// preference.
+fn jetbrains_attributes(token: ZedSyntaxToken) -> &'static [&'static str] {
+    match token {
+        ZedSyntaxToken::Attribute => &[
+            "DEFAULT_METADATA",
+            "DEFAULT_ATTRIBUTE",
+            "HTML_ATTRIBUTE_NAME",
+            "XML_ATTRIBUTE_NAME",
+        ],
+        ZedSyntaxToken::Boolean => &["DEFAULT_KEYWORD"],
+        ZedSyntaxToken::Comment => &["DEFAULT_LINE_COMMENT", "DEFAULT_BLOCK_COMMENT"],
+        ZedSyntaxToken::CommentDoc => &["DEFAULT_DOC_COMMENT"],
+        ZedSyntaxToken::Constant => &["DEFAULT_CONSTANT", "DEFAULT_STATIC_FIELD"],
+        ZedSyntaxToken::Emphasis => &["MARKDOWN_ITALIC"],
+        ZedSyntaxToken::EmphasisStrong => &["MARKDOWN_BOLD"],
+        ZedSyntaxToken::Function => &[
+            "DEFAULT_FUNCTION_DECLARATION",
+            "DEFAULT_FUNCTION_CALL",
+            "DEFAULT_INSTANCE_METHOD",
+            "DEFAULT_STATIC_METHOD",
+        ],
+        ZedSyntaxToken::Keyword => &["DEFAULT_KEYWORD"],
+        ZedSyntaxToken::Label => &["DEFAULT_LABEL"],
+        ZedSyntaxToken::LinkText => &["MARKDOWN_LINK_TEXT"],
+        ZedSyntaxToken::LinkUri => &["MARKDOWN_LINK_DESTINATION"],
+        ZedSyntaxToken::Number => &["DEFAULT_NUMBER"],
+        ZedSyntaxToken::Operator => &["DEFAULT_OPERATION_SIGN"],
+        ZedSyntaxToken::Property => &["DEFAULT_INSTANCE_FIELD", "DEFAULT_STATIC_FIELD"],
+        ZedSyntaxToken::PunctuationBracket => {
+            &["DEFAULT_BRACES", "DEFAULT_BRACKETS", "DEFAULT_PARENTHS"]
+        }
+        ZedSyntaxToken::PunctuationDelimiter => {
+            &["DEFAULT_COMMA", "DEFAULT_SEMICOLON", "DEFAULT_DOT"]
+        }
+        ZedSyntaxToken::String => &["DEFAULT_STRING"],
+        ZedSyntaxToken::StringEscape => &["DEFAULT_VALID_STRING_ESCAPE"],
+        ZedSyntaxToken::Tag => &["DEFAULT_TAG", "HTML_TAG_NAME", "XML_TAG_NAME"],
+        ZedSyntaxToken::Title => &["MARKDOWN_HEADER_LEVEL_1", "MARKDOWN_HEADER"],
+        ZedSyntaxToken::Type => &[
+            "DEFAULT_CLASS_NAME",
+            "DEFAULT_INTERFACE_NAME",
+            "DEFAULT_CLASS_REFERENCE",
+        ],
+        ZedSyntaxToken::Variable => &[
+            "DEFAULT_LOCAL_VARIABLE",
+            "DEFAULT_IDENTIFIER",
+            "DEFAULT_PARAMETER",
+        ],
+        ZedSyntaxToken::VariableSpecial => &["DEFAULT_PREDEFINED_SYMBOL"],
+        ZedSyntaxToken::Variant => &["ENUM_CONST"],
+        ZedSyntaxToken::Constructor
+        | ZedSyntaxToken::Embedded
+        | ZedSyntaxToken::Enum
+        | ZedSyntaxToken::Hint
+        | ZedSyntaxToken::Predictive
+        | ZedSyntaxToken::Preproc
+        | ZedSyntaxToken::Primary
+        | ZedSyntaxToken::Punctuation
+        | ZedSyntaxToken::PunctuationListMarker
+        | ZedSyntaxToken::PunctuationSpecial
+        | ZedSyntaxToken::StringRegex
+        | ZedSyntaxToken::StringSpecial
+        | ZedSyntaxToken::StringSpecialSymbol
+        | ZedSyntaxToken::TextLiteral => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ThemeAppearanceJson;
+
+    #[test]
+    fn test_convert_icls() {
+        let scheme = JetBrainsColorScheme::from_icls(
+            r#"<scheme name="Classic" version="142">
+              <colors>
+                <option name="CARET_COLOR" value="bbbbbb" />
+                <option name="FILESTATUS_ADDED" value="629755" />
+              </colors>
+              <attributes>
+                <option name="TEXT">
+                  <value>
+                    <option name="FOREGROUND" value="a9b7c6" />
+                    <option name="BACKGROUND" value="2b2b2b" />
+                  </value>
+                </option>
+                <option name="DEFAULT_LINE_COMMENT">
+                  <value>
+                    <option name="FOREGROUND" value="808080" />
+                    <option name="FONT_TYPE" value="2" />
+                  </value>
+                </option>
+                <option name="KOTLIN_LABEL">
+                  <value>
+                    <option name="FOREGROUND" value="20999d" />
+                  </value>
+                </option>
+              </attributes>
+            </scheme>"#,
+        )
+        .unwrap();
+        let converter = JetBrainsThemeConverter::new(
+            scheme,
+            ThemeMetadata {
+                name: "Classic".to_string(),
+                file_name: String::new(),
+                appearance: ThemeAppearanceJson::Dark,
+            },
+        );
+
+        assert_eq!(
+            converter.unmapped(),
+            vec!["color `FILESTATUS_ADDED`", "attribute `KOTLIN_LABEL`"]
+        );
+
+        let theme = converter.convert().unwrap();
+        assert_eq!(
+            theme.style.colors.editor_background.as_deref(),
+            Some("#2b2b2b")
+        );
+        assert_eq!(theme.style.players[0].cursor.as_deref(), Some("#bbbbbb"));
+        let comment = &theme.style.syntax["comment"];
+        assert_eq!(comment.color.as_deref(), Some("#808080"));
+        assert_eq!(comment.font_style, Some(FontStyleContent::Italic));
+        // `comment.doc` falls back to the style of `comment`.
+        assert_eq!(
+            theme.style.syntax["comment.doc"].color.as_deref(),
+            Some("#808080")
+        );
+    }
+}
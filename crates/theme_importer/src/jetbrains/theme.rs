@@ -0,0 +1,212 @@
+use anyhow::{Context as _, Result};
+use indexmap::IndexMap;
+
+use crate::ThemeAppearanceJson;
+use crate::color::try_parse_color;
+
+/// A JetBrains IDE color scheme, exported as an `.icls` file.
+#[derive(Debug)]
+pub struct JetBrainsColorScheme {
+    pub name: String,
+    /// The bundled scheme this one is based on, e.g. `Darcula`.
+    ///
+    /// Values the scheme doesn't override are inherited from it, so they are missing from the file.
+    pub parent_scheme: Option<String>,
+    /// The `<colors>` of the scheme, such as `CARET_ROW_COLOR`.
+    pub colors: IndexMap<String, String>,
+    /// The `<attributes>` of the scheme, such as `DEFAULT_KEYWORD`.
+    pub attributes: IndexMap<String, JetBrainsTextAttributes>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct JetBrainsTextAttributes {
+    pub foreground: Option<String>,
+    pub background: Option<String>,
+    /// The color of the underline, wave, or box drawn around the text.
+    pub effect_color: Option<String>,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl JetBrainsColorScheme {
+    pub fn from_icls(source: &str) -> Result<Self> {
+        let document = roxmltree::Document::parse(source).context("invalid .icls XML")?;
+        let scheme = document.root_element();
+        anyhow::ensure!(
+            scheme.has_tag_name("scheme"),
+            "expected a <scheme> element, found <{}>",
+            scheme.tag_name().name()
+        );
+
+        let mut colors = IndexMap::default();
+        let mut attributes = IndexMap::default();
+        let mut base_attributes = IndexMap::default();
+        for section in scheme.children().filter(|node| node.is_element()) {
+            match section.tag_name().name() {
+                "colors" => {
+                    for option in options(section) {
+                        if let Some((name, color)) = option
+                            .attribute("name")
+                            .zip(option.attribute("value").and_then(parse_color))
+                        {
+                            colors.insert(name.to_string(), color);
+                        }
+                    }
+                }
+                "attributes" => {
+                    for option in options(section) {
+                        let Some(name) = option.attribute("name") else {
+                            continue;
+                        };
+                        if let Some(base) = option.attribute("baseAttributes") {
+                            base_attributes.insert(name.to_string(), base.to_string());
+                        }
+                        let Some(value) = option.children().find(|node| node.has_tag_name("value"))
+                        else {
+                            continue;
+                        };
+                        let mut text_attributes = JetBrainsTextAttributes::default();
+                        for option in options(value) {
+                            let value = option.attribute("value").unwrap_or_default();
+                            match option.attribute("name") {
+                                Some("FOREGROUND") => {
+                                    text_attributes.foreground = parse_color(value)
+                                }
+                                Some("BACKGROUND") => {
+                                    text_attributes.background = parse_color(value)
+                                }
+                                Some("EFFECT_COLOR") => {
+                                    text_attributes.effect_color = parse_color(value)
+                                }
+                                Some("FONT_TYPE") => {
+                                    let font_type = value.parse::<u8>().unwrap_or_default();
+                                    text_attributes.bold = font_type & 1 != 0;
+                                    text_attributes.italic = font_type & 2 != 0;
+                                }
+                                _ => {}
+                            }
+                        }
+                        attributes.insert(name.to_string(), text_attributes);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Attributes without a value of their own inherit the one of their base attributes.
+        for (name, base) in &base_attributes {
+            if attributes.contains_key(name) {
+                continue;
+            }
+            let mut base = base.as_str();
+            for _ in 0..base_attributes.len() {
+                if let Some(base_value) = attributes.get(base).cloned() {
+                    attributes.insert(name.clone(), base_value);
+                    break;
+                }
+                let Some(next) = base_attributes.get(base) else {
+                    break;
+                };
+                base = next;
+            }
+        }
+
+        Ok(Self {
+            name: scheme.attribute("name").unwrap_or_default().to_string(),
+            parent_scheme: scheme.attribute("parent_scheme").map(str::to_string),
+            colors,
+            attributes,
+        })
+    }
+
+    /// The editor's default text colors.
+    pub fn text(&self) -> Option<&JetBrainsTextAttributes> {
+        self.attributes.get("TEXT")
+    }
+
+    pub fn appearance(&self) -> ThemeAppearanceJson {
+        let background = self
+            .text()
+            .and_then(|text| text.background.as_deref())
+            .and_then(|background| try_parse_color(background).ok());
+        let is_light = match background {
+            Some(background) => background.l > 0.5,
+            None => self.parent_scheme.as_deref() != Some("Darcula"),
+        };
+        if is_light {
+            ThemeAppearanceJson::Light
+        } else {
+            ThemeAppearanceJson::Dark
+        }
+    }
+}
+
+fn options<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    node.children().filter(|node| node.has_tag_name("option"))
+}
+
+/// Parses colors such as `cc7832`, which omit the leading `#` as well as leading zeros.
+fn parse_color(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match value.len() {
+        1..=6 => Some(format!("#{value:0>6}")),
+        8 => Some(format!("#{value}")),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_icls() {
+        let scheme = JetBrainsColorScheme::from_icls(
+            r#"<scheme name="Classic" version="142" parent_scheme="Darcula">
+              <metaInfo>
+                <property name="ide">idea</property>
+              </metaInfo>
+              <colors>
+                <option name="CARET_ROW_COLOR" value="323232" />
+                <option name="LINE_NUMBERS_COLOR" value="" />
+                <option name="GUTTER_BACKGROUND" value="0" />
+              </colors>
+              <attributes>
+                <option name="DEFAULT_KEYWORD">
+                  <value>
+                    <option name="FOREGROUND" value="cc7832" />
+                    <option name="FONT_TYPE" value="3" />
+                  </value>
+                </option>
+                <option name="DEFAULT_CONSTANT" baseAttributes="DEFAULT_KEYWORD" />
+                <option name="ENUM_CONST" baseAttributes="DEFAULT_CONSTANT" />
+              </attributes>
+            </scheme>"#,
+        )
+        .unwrap();
+
+        assert_eq!(scheme.name, "Classic");
+        assert_eq!(
+            scheme.colors,
+            IndexMap::from_iter([
+                ("CARET_ROW_COLOR".to_string(), "#323232".to_string()),
+                ("GUTTER_BACKGROUND".to_string(), "#000000".to_string()),
+            ])
+        );
+        let keyword = JetBrainsTextAttributes {
+            foreground: Some("#cc7832".to_string()),
+            bold: true,
+            italic: true,
+            ..Default::default()
+        };
+        assert_eq!(scheme.attributes.get("DEFAULT_KEYWORD"), Some(&keyword));
+        assert_eq!(scheme.attributes.get("DEFAULT_CONSTANT"), Some(&keyword));
+        assert_eq!(scheme.attributes.get("ENUM_CONST"), Some(&keyword));
+        assert_eq!(scheme.appearance(), ThemeAppearanceJson::Dark);
+    }
+}
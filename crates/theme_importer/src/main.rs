@@ -1,4 +1,6 @@
 mod color;
+mod jetbrains;
+mod textmate;
 mod vscode;
 
 use std::fs::File;
@@ -14,12 +16,14 @@ use simplelog::ColorChoice;
 use simplelog::{TermLogger, TerminalMode};
 use theme::{Appearance, AppearanceContent};
 
+use crate::jetbrains::{JetBrainsColorScheme, JetBrainsThemeConverter};
+use crate::textmate::{TextMateTheme, TextMateThemeConverter};
 use crate::vscode::VsCodeTheme;
 use crate::vscode::VsCodeThemeConverter;
 
 const ZED_THEME_SCHEMA_URL: &str = "https://zed.dev/schema/themes/v0.2.0.json";
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ThemeAppearanceJson {
     Light,
@@ -55,6 +59,9 @@ pub struct ThemeMetadata {
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// The path to the theme to import.
+    ///
+    /// VS Code JSON themes, TextMate `.tmTheme` themes, Sublime Text `.sublime-color-scheme`
+    /// files, and JetBrains `.icls` color schemes are supported, based on the file extension.
     theme_path: PathBuf,
 
     /// Whether to warn when values are missing from the theme.
@@ -98,18 +105,76 @@ fn main() -> Result<()> {
         }
     };
 
-    let vscode_theme: VsCodeTheme = serde_json_lenient::from_slice(&buffer)
-        .context(format!("failed to parse theme {theme_file_path:?}"))?;
-
-    let theme_metadata = ThemeMetadata {
-        name: vscode_theme.name.clone().unwrap_or("".to_string()),
-        appearance: ThemeAppearanceJson::Dark,
-        file_name: "".to_string(),
+    let file_stem = theme_file_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = theme_file_path
+        .extension()
+        .and_then(|extension| extension.to_str());
+
+    let (theme, unmapped) = match extension {
+        Some("tmTheme") | Some("sublime-color-scheme") => {
+            let textmate_theme = if extension == Some("tmTheme") {
+                TextMateTheme::from_tm_theme(&buffer)
+            } else {
+                TextMateTheme::from_sublime_color_scheme(&buffer)
+            }
+            .context(format!("failed to parse theme {theme_file_path:?}"))?;
+
+            let theme_metadata = ThemeMetadata {
+                name: textmate_theme.name.clone().unwrap_or(file_stem),
+                appearance: textmate_theme.appearance(),
+                file_name: "".to_string(),
+            };
+
+            let converter = TextMateThemeConverter::new(textmate_theme, theme_metadata);
+            let unmapped = converter.unmapped();
+            (converter.convert()?, unmapped)
+        }
+        Some("icls") => {
+            let source = String::from_utf8(buffer)?;
+            let color_scheme = JetBrainsColorScheme::from_icls(&source)
+                .context(format!("failed to parse theme {theme_file_path:?}"))?;
+
+            let theme_metadata = ThemeMetadata {
+                name: Some(color_scheme.name.clone())
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or(file_stem),
+                appearance: color_scheme.appearance(),
+                file_name: "".to_string(),
+            };
+
+            let converter = JetBrainsThemeConverter::new(color_scheme, theme_metadata);
+            let unmapped = converter.unmapped();
+            (converter.convert()?, unmapped)
+        }
+        _ => {
+            let vscode_theme: VsCodeTheme = serde_json_lenient::from_slice(&buffer)
+                .context(format!("failed to parse theme {theme_file_path:?}"))?;
+
+            let theme_metadata = ThemeMetadata {
+                name: vscode_theme.name.clone().unwrap_or("".to_string()),
+                appearance: ThemeAppearanceJson::Dark,
+                file_name: "".to_string(),
+            };
+
+            let converter =
+                VsCodeThemeConverter::new(vscode_theme, theme_metadata, IndexMap::default());
+            (converter.convert()?, Vec::new())
+        }
     };
 
-    let converter = VsCodeThemeConverter::new(vscode_theme, theme_metadata, IndexMap::default());
+    if !unmapped.is_empty() {
+        log::warn!(
+            "{} parts of the theme have no Zed equivalent and were not imported:",
+            unmapped.len()
+        );
+        for entry in &unmapped {
+            log::warn!("  {entry}");
+        }
+    }
 
-    let theme = converter.convert()?;
     let mut theme = serde_json::to_value(theme).unwrap();
     theme.as_object_mut().unwrap().insert(
         "$schema".to_string(),
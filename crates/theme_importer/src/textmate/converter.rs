@@ -0,0 +1,183 @@
+use anyhow::Result;
+use collections::HashSet;
+use indexmap::IndexMap;
+use theme::{
+    PlayerColorContent, ThemeColorsContent, ThemeContent, ThemeStyleContent,
+    WindowBackgroundContent,
+};
+
+use crate::ThemeMetadata;
+use crate::textmate::TextMateTheme;
+use crate::vscode::{VsCodeTokenScope, convert_syntax_theme, match_syntax_tokens};
+
+/// The globals that are converted to Zed theme colors.
+const CONVERTED_GLOBALS: &[&str] = &[
+    "active_guide",
+    "background",
+    "caret",
+    "find_highlight",
+    "foreground",
+    "gutter",
+    "gutter_foreground",
+    "gutter_foreground_highlight",
+    "guide",
+    "invisibles",
+    "line_highlight",
+    "rulers",
+    "selection",
+];
+
+pub struct TextMateThemeConverter {
+    theme: TextMateTheme,
+    theme_metadata: ThemeMetadata,
+}
+
+impl TextMateThemeConverter {
+    pub fn new(theme: TextMateTheme, theme_metadata: ThemeMetadata) -> Self {
+        Self {
+            theme,
+            theme_metadata,
+        }
+    }
+
+    /// Describes the parts of the theme that have no equivalent in the converted Zed theme.
+    pub fn unmapped(&self) -> Vec<String> {
+        let matched_rules = match_syntax_tokens(&self.theme.rules, &IndexMap::default())
+            .into_iter()
+            .map(|(_, rule)| rule as *const _)
+            .collect::<HashSet<_>>();
+
+        let mut unmapped = Vec::new();
+        for rule in &self.theme.rules {
+            if matched_rules.contains(&(rule as *const _)) {
+                continue;
+            }
+            if let Some(VsCodeTokenScope::One(scope)) = &rule.scope {
+                unmapped.push(format!("scope `{scope}`"));
+            }
+        }
+        for key in self.theme.globals.keys() {
+            if !CONVERTED_GLOBALS.contains(&key.as_str()) {
+                unmapped.push(format!("global `{key}`"));
+            }
+        }
+        for key in &self.theme.unresolved_globals {
+            if CONVERTED_GLOBALS.contains(&key.as_str()) {
+                unmapped.push(format!("global `{key}` (unsupported color)"));
+            } else {
+                unmapped.push(format!("global `{key}`"));
+            }
+        }
+        for color in &self.theme.unresolved_colors {
+            unmapped.push(format!("color `{color}`"));
+        }
+        unmapped
+    }
+
+    pub fn convert(self) -> Result<ThemeContent> {
+        let appearance = self.theme_metadata.appearance.into();
+        let globals = &self.theme.globals;
+        let global = |key: &str| globals.get(key).cloned();
+
+        let background = global("background");
+        let foreground = global("foreground");
+        let guide = global("guide");
+        let colors = ThemeColorsContent {
+            background: background.clone(),
+            text: foreground.clone(),
+            editor_background: background.clone(),
+            editor_foreground: foreground.clone(),
+            editor_gutter_background: global("gutter").or(background),
+            editor_active_line_background: global("line_highlight"),
+            editor_line_number: global("gutter_foreground"),
+            editor_active_line_number: global("gutter_foreground_highlight").or(foreground),
+            editor_invisible: global("invisibles"),
+            editor_indent_guide: guide.clone(),
+            editor_indent_guide_active: global("active_guide").or(guide),
+            editor_wrap_guide: global("rulers"),
+            search_match_background: global("find_highlight"),
+            ..Default::default()
+        };
+
+        let players = if global("caret").is_some() || global("selection").is_some() {
+            vec![PlayerColorContent {
+                cursor: global("caret"),
+                background: global("caret"),
+                selection: global("selection"),
+            }]
+        } else {
+            Vec::new()
+        };
+
+        Ok(ThemeContent {
+            name: self.theme_metadata.name,
+            appearance,
+            style: ThemeStyleContent {
+                window_background_appearance: Some(WindowBackgroundContent::Opaque),
+                accents: Vec::new(),
+                colors,
+                status: Default::default(),
+                players,
+                syntax: convert_syntax_theme(&self.theme.rules, &IndexMap::default()),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ThemeAppearanceJson;
+
+    #[test]
+    fn test_convert_tm_theme() {
+        let theme = TextMateTheme::from_sublime_color_scheme(
+            br##"{
+                "globals": {
+                    "background": "#272822",
+                    "caret": "#f8f8f0",
+                    "selection": "hsl(0, 100%, 50%)",
+                    "popup_css": "html { color: red; }",
+                },
+                "rules": [
+                    { "scope": "comment", "foreground": "#75715e", "font_style": "italic" },
+                    { "scope": "keyword", "foreground": "#f92672" },
+                    { "scope": "markup.deleted.diff", "foreground": "#f92672" },
+                ],
+            }"##,
+        )
+        .unwrap();
+        let converter = TextMateThemeConverter::new(
+            theme,
+            ThemeMetadata {
+                name: "Classic".to_string(),
+                file_name: String::new(),
+                appearance: ThemeAppearanceJson::Dark,
+            },
+        );
+
+        assert_eq!(
+            converter.unmapped(),
+            vec![
+                "scope `markup.deleted.diff`",
+                "global `selection` (unsupported color)",
+                "global `popup_css`",
+            ]
+        );
+
+        let theme = converter.convert().unwrap();
+        assert_eq!(
+            theme.style.colors.editor_background.as_deref(),
+            Some("#272822")
+        );
+        assert_eq!(theme.style.players[0].cursor.as_deref(), Some("#f8f8f0"));
+        assert_eq!(
+            theme.style.syntax["comment"].color.as_deref(),
+            Some("#75715e")
+        );
+        assert_eq!(
+            theme.style.syntax["keyword"].color.as_deref(),
+            Some("#f92672")
+        );
+    }
+}
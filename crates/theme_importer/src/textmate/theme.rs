@@ -0,0 +1,386 @@
+use anyhow::{Context as _, Result};
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::ThemeAppearanceJson;
+use crate::color::try_parse_color;
+use crate::vscode::{VsCodeTokenColor, VsCodeTokenColorSettings, VsCodeTokenScope};
+
+/// A TextMate `.tmTheme` or Sublime Text `.sublime-color-scheme` theme.
+///
+/// Both formats style syntax through TextMate scope selectors, the same way VS Code themes do,
+/// so their rules are represented as [`VsCodeTokenColor`]s.
+#[derive(Debug)]
+pub struct TextMateTheme {
+    pub name: Option<String>,
+    /// The editor-wide colors, keyed by their `.sublime-color-scheme` name (e.g. `line_highlight`).
+    pub globals: IndexMap<String, String>,
+    pub rules: Vec<VsCodeTokenColor>,
+    /// Globals whose value is not a color, or a color that could not be resolved.
+    pub unresolved_globals: Vec<String>,
+    /// Colors of rules that could not be resolved to a hex color.
+    pub unresolved_colors: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TmTheme {
+    name: Option<String>,
+    settings: Vec<TmThemeSetting>,
+}
+
+#[derive(Deserialize)]
+struct TmThemeSetting {
+    name: Option<String>,
+    scope: Option<String>,
+    #[serde(default)]
+    settings: IndexMap<String, plist::Value>,
+}
+
+#[derive(Deserialize)]
+struct SublimeColorScheme {
+    name: Option<String>,
+    #[serde(default)]
+    variables: IndexMap<String, String>,
+    #[serde(default)]
+    globals: IndexMap<String, String>,
+    #[serde(default)]
+    rules: Vec<SublimeColorSchemeRule>,
+}
+
+#[derive(Deserialize)]
+struct SublimeColorSchemeRule {
+    name: Option<String>,
+    scope: Option<String>,
+    /// Either a color, or a list of colors for hashed syntax highlighting.
+    foreground: Option<serde_json::Value>,
+    background: Option<String>,
+    font_style: Option<String>,
+}
+
+impl TextMateTheme {
+    pub fn from_tm_theme(bytes: &[u8]) -> Result<Self> {
+        let tm_theme: TmTheme = plist::from_bytes(bytes).context("invalid .tmTheme plist")?;
+
+        let mut theme = TextMateTheme {
+            name: tm_theme.name,
+            globals: IndexMap::default(),
+            rules: Vec::new(),
+            unresolved_globals: Vec::new(),
+            unresolved_colors: Vec::new(),
+        };
+        for setting in tm_theme.settings {
+            let values = setting
+                .settings
+                .iter()
+                .filter_map(|(key, value)| Some((key.as_str(), value.as_string()?)));
+            match setting.scope {
+                // The first entry, without a scope, holds the global settings.
+                None => {
+                    for (key, value) in values {
+                        theme
+                            .globals
+                            .insert(snake_case(key), value.trim().to_string());
+                    }
+                }
+                Some(scope) => {
+                    let mut settings = VsCodeTokenColorSettings {
+                        foreground: None,
+                        background: None,
+                        font_style: None,
+                    };
+                    for (key, value) in values {
+                        let value =
+                            Some(value.trim().to_string()).filter(|value| !value.is_empty());
+                        match key {
+                            "foreground" => settings.foreground = value,
+                            "background" => settings.background = value,
+                            "fontStyle" => settings.font_style = value,
+                            _ => {}
+                        }
+                    }
+                    theme.rules.push(VsCodeTokenColor {
+                        name: setting.name,
+                        scope: Some(VsCodeTokenScope::One(scope)),
+                        settings,
+                    });
+                }
+            }
+        }
+        Ok(theme)
+    }
+
+    pub fn from_sublime_color_scheme(bytes: &[u8]) -> Result<Self> {
+        let color_scheme: SublimeColorScheme =
+            serde_json_lenient::from_slice(bytes).context("invalid .sublime-color-scheme JSON")?;
+
+        let mut globals = IndexMap::default();
+        let mut unresolved_globals = Vec::new();
+        for (key, value) in &color_scheme.globals {
+            match resolve_sublime_color(value, &color_scheme.variables, 0) {
+                Some(color) => {
+                    globals.insert(key.clone(), color);
+                }
+                None => unresolved_globals.push(key.clone()),
+            }
+        }
+
+        let mut unresolved_colors = Vec::new();
+        let mut resolve = |color: &str| {
+            let resolved = resolve_sublime_color(color, &color_scheme.variables, 0);
+            if resolved.is_none() {
+                unresolved_colors.push(color.to_string());
+            }
+            resolved
+        };
+        let rules = color_scheme
+            .rules
+            .into_iter()
+            .map(|rule| VsCodeTokenColor {
+                name: rule.name,
+                scope: rule.scope.map(VsCodeTokenScope::One),
+                settings: VsCodeTokenColorSettings {
+                    foreground: rule
+                        .foreground
+                        .as_ref()
+                        .and_then(|foreground| match foreground {
+                            serde_json::Value::String(color) => Some(color.as_str()),
+                            // Hashed highlighting cycles through the colors, use the first one.
+                            serde_json::Value::Array(colors) => colors.first()?.as_str(),
+                            _ => None,
+                        })
+                        .and_then(&mut resolve),
+                    background: rule.background.as_deref().and_then(&mut resolve),
+                    font_style: rule.font_style,
+                },
+            })
+            .collect();
+
+        Ok(TextMateTheme {
+            name: color_scheme.name,
+            globals,
+            rules,
+            unresolved_globals,
+            unresolved_colors,
+        })
+    }
+
+    pub fn appearance(&self) -> ThemeAppearanceJson {
+        let is_light = self
+            .globals
+            .get("background")
+            .and_then(|background| try_parse_color(background).ok())
+            .is_some_and(|background| background.l > 0.5);
+        if is_light {
+            ThemeAppearanceJson::Light
+        } else {
+            ThemeAppearanceJson::Dark
+        }
+    }
+}
+
+/// Converts `.tmTheme` keys such as `lineHighlight` to their `.sublime-color-scheme` equivalent.
+fn snake_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            result.push('_');
+            result.push(c.to_ascii_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Resolves a color of a `.sublime-color-scheme` to a hex color.
+///
+/// Supports hex colors, `rgb()` and `rgba()`, `var()` references to the scheme's variables, and
+/// the `alpha()` adjuster of the `color()` function.
+fn resolve_sublime_color(
+    color: &str,
+    variables: &IndexMap<String, String>,
+    depth: usize,
+) -> Option<String> {
+    const MAX_DEPTH: usize = 16;
+
+    let color = color.trim();
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    if color.starts_with('#') {
+        return gpui::Rgba::try_from(color).ok().map(|_| color.to_string());
+    }
+    if let Some(name) = color
+        .strip_prefix("var(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return resolve_sublime_color(variables.get(name.trim())?, variables, depth + 1);
+    }
+    if let Some(components) = color
+        .strip_prefix("rgba(")
+        .or_else(|| color.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let components = components
+            .split(',')
+            .map(|component| component.trim().parse::<f32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        return match components.as_slice() {
+            [r, g, b] => Some(format_rgba(*r / 255., *g / 255., *b / 255., 1.)),
+            [r, g, b, a] => Some(format_rgba(*r / 255., *g / 255., *b / 255., *a)),
+            _ => None,
+        };
+    }
+    if let Some(arguments) = color
+        .strip_prefix("color(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        // `color(var(blue) alpha(0.5))`: the base color, followed by adjusters.
+        let base_end = matching_paren_end(arguments)?;
+        let base = resolve_sublime_color(&arguments[..base_end], variables, depth + 1)?;
+        let mut rgba = gpui::Rgba::try_from(base.as_str()).ok()?;
+        for adjuster in arguments[base_end..].split_whitespace() {
+            let alpha = adjuster
+                .strip_prefix("alpha(")
+                .or_else(|| adjuster.strip_prefix("a("))
+                .and_then(|rest| rest.strip_suffix(')'))?;
+            rgba.a = alpha.parse().ok()?;
+        }
+        return Some(format_rgba(rgba.r, rgba.g, rgba.b, rgba.a));
+    }
+    match color {
+        "black" => Some("#000000".to_string()),
+        "white" => Some("#ffffff".to_string()),
+        "transparent" => Some("#00000000".to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the end of the first argument of a CSS function, which may itself be a function.
+fn matching_paren_end(arguments: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (ix, c) in arguments.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => return Some(ix),
+            _ => {}
+        }
+    }
+    (depth == 0).then_some(arguments.len())
+}
+
+fn format_rgba(r: f32, g: f32, b: f32, a: f32) -> String {
+    let component = |value: f32| (value.clamp(0., 1.) * 255.).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}{:02x}",
+        component(r),
+        component(g),
+        component(b),
+        component(a)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tm_theme() {
+        let theme = TextMateTheme::from_tm_theme(
+            br#"<?xml version="1.0" encoding="UTF-8"?>
+            <!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+            <plist version="1.0">
+            <dict>
+                <key>name</key>
+                <string>Classic</string>
+                <key>settings</key>
+                <array>
+                    <dict>
+                        <key>settings</key>
+                        <dict>
+                            <key>background</key>
+                            <string>#272822</string>
+                            <key>lineHighlight</key>
+                            <string>#3E3D32</string>
+                        </dict>
+                    </dict>
+                    <dict>
+                        <key>name</key>
+                        <string>Comment</string>
+                        <key>scope</key>
+                        <string>comment</string>
+                        <key>settings</key>
+                        <dict>
+                            <key>foreground</key>
+                            <string>#75715E</string>
+                            <key>fontStyle</key>
+                            <string></string>
+                        </dict>
+                    </dict>
+                </array>
+            </dict>
+            </plist>"#,
+        )
+        .unwrap();
+
+        assert_eq!(theme.name.as_deref(), Some("Classic"));
+        assert_eq!(
+            theme.globals,
+            IndexMap::from_iter([
+                ("background".to_string(), "#272822".to_string()),
+                ("line_highlight".to_string(), "#3E3D32".to_string()),
+            ])
+        );
+        assert_eq!(theme.rules.len(), 1);
+        assert_eq!(
+            theme.rules[0].scope,
+            Some(VsCodeTokenScope::One("comment".to_string()))
+        );
+        assert_eq!(
+            theme.rules[0].settings.foreground.as_deref(),
+            Some("#75715E")
+        );
+        assert_eq!(theme.rules[0].settings.font_style, None);
+        assert_eq!(theme.appearance(), ThemeAppearanceJson::Dark);
+    }
+
+    #[test]
+    fn test_resolve_sublime_colors() {
+        let theme = TextMateTheme::from_sublime_color_scheme(
+            br##"{
+                // Comments are allowed.
+                "name": "Mariana",
+                "variables": {
+                    "blue": "#5fb4b4",
+                    "accent": "var(blue)",
+                },
+                "globals": {
+                    "background": "rgb(255, 255, 255)",
+                    "selection": "color(var(accent) alpha(0.5))",
+                    "caret": "hsl(0, 100%, 50%)",
+                },
+                "rules": [
+                    { "scope": "string", "foreground": ["var(blue)", "white"] },
+                    { "scope": "comment", "foreground": "var(grey)" },
+                ],
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(
+            theme.globals,
+            IndexMap::from_iter([
+                ("background".to_string(), "#ffffffff".to_string()),
+                ("selection".to_string(), "#5fb4b480".to_string()),
+            ])
+        );
+        assert_eq!(
+            theme.rules[0].settings.foreground.as_deref(),
+            Some("#5fb4b4")
+        );
+        assert_eq!(theme.unresolved_globals, vec!["caret"]);
+        assert_eq!(theme.unresolved_colors, vec!["var(grey)"]);
+        assert_eq!(theme.appearance(), ThemeAppearanceJson::Light);
+    }
+}
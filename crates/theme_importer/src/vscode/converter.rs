@@ -7,7 +7,7 @@ use theme::{
 };
 
 use crate::ThemeMetadata;
-use crate::vscode::{VsCodeTheme, VsCodeTokenScope};
+use crate::vscode::{VsCodeTheme, VsCodeTokenColor, VsCodeTokenScope};
 
 use super::ZedSyntaxToken;
 
@@ -212,65 +212,89 @@ impl VsCodeThemeConverter {
     }
 
     fn convert_syntax_theme(&self) -> Result<IndexMap<String, HighlightStyleContent>> {
-        let mut highlight_styles = IndexMap::default();
+        Ok(convert_syntax_theme(
+            &self.theme.token_colors,
+            &self.syntax_overrides,
+        ))
+    }
+}
 
-        for syntax_token in ZedSyntaxToken::iter() {
-            let override_match = self
-                .syntax_overrides
-                .get(&syntax_token.to_string())
-                .and_then(|scope| {
-                    self.theme.token_colors.iter().find(|token_color| {
-                        token_color.scope == Some(VsCodeTokenScope::Many(scope.clone()))
-                    })
-                });
+/// Returns the token color that best matches each [`ZedSyntaxToken`].
+pub(crate) fn match_syntax_tokens<'a>(
+    token_colors: &'a [VsCodeTokenColor],
+    syntax_overrides: &IndexMap<String, Vec<String>>,
+) -> Vec<(ZedSyntaxToken, &'a VsCodeTokenColor)> {
+    let mut matches = Vec::new();
 
-            let best_match = override_match
-                .or_else(|| syntax_token.find_best_token_color_match(&self.theme.token_colors))
-                .or_else(|| {
-                    syntax_token.fallbacks().iter().find_map(|fallback| {
-                        fallback.find_best_token_color_match(&self.theme.token_colors)
-                    })
-                });
+    for syntax_token in ZedSyntaxToken::iter() {
+        let override_match = syntax_overrides
+            .get(&syntax_token.to_string())
+            .and_then(|scope| {
+                token_colors.iter().find(|token_color| {
+                    token_color.scope == Some(VsCodeTokenScope::Many(scope.clone()))
+                })
+            });
 
-            let Some(token_color) = best_match else {
-                log::warn!("No matching token color found for '{syntax_token}'");
-                continue;
-            };
+        let best_match = override_match
+            .or_else(|| syntax_token.find_best_token_color_match(token_colors))
+            .or_else(|| {
+                syntax_token
+                    .fallbacks()
+                    .iter()
+                    .find_map(|fallback| fallback.find_best_token_color_match(token_colors))
+            });
 
-            log::info!(
-                "Matched '{syntax_token}' to '{}'",
-                token_color
-                    .name
-                    .clone()
-                    .or_else(|| token_color
-                        .scope
-                        .as_ref()
-                        .map(|scope| format!("{:?}", scope)))
-                    .unwrap_or_else(|| "no identifier".to_string())
-            );
+        let Some(token_color) = best_match else {
+            log::warn!("No matching token color found for '{syntax_token}'");
+            continue;
+        };
 
-            let highlight_style = HighlightStyleContent {
-                color: token_color.settings.foreground.clone(),
-                background_color: token_color.settings.background.clone(),
-                font_style: token_color
-                    .settings
-                    .font_style
-                    .as_ref()
-                    .and_then(|style| try_parse_font_style(style)),
-                font_weight: token_color
-                    .settings
-                    .font_style
+        log::info!(
+            "Matched '{syntax_token}' to '{}'",
+            token_color
+                .name
+                .clone()
+                .or_else(|| token_color
+                    .scope
                     .as_ref()
-                    .and_then(|style| try_parse_font_weight(style)),
-            };
+                    .map(|scope| format!("{:?}", scope)))
+                .unwrap_or_else(|| "no identifier".to_string())
+        );
+
+        matches.push((syntax_token, token_color));
+    }
+
+    matches
+}
 
-            if highlight_style.is_empty() {
-                continue;
-            }
+pub(crate) fn convert_syntax_theme(
+    token_colors: &[VsCodeTokenColor],
+    syntax_overrides: &IndexMap<String, Vec<String>>,
+) -> IndexMap<String, HighlightStyleContent> {
+    let mut highlight_styles = IndexMap::default();
 
-            highlight_styles.insert(syntax_token.to_string(), highlight_style);
+    for (syntax_token, token_color) in match_syntax_tokens(token_colors, syntax_overrides) {
+        let highlight_style = HighlightStyleContent {
+            color: token_color.settings.foreground.clone(),
+            background_color: token_color.settings.background.clone(),
+            font_style: token_color
+                .settings
+                .font_style
+                .as_ref()
+                .and_then(|style| try_parse_font_style(style)),
+            font_weight: token_color
+                .settings
+                .font_style
+                .as_ref()
+                .and_then(|style| try_parse_font_weight(style)),
+        };
+
+        if highlight_style.is_empty() {
+            continue;
         }
 
-        Ok(highlight_styles)
+        highlight_styles.insert(syntax_token.to_string(), highlight_style);
     }
+
+    highlight_styles
 }
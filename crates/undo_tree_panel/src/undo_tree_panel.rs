@@ -0,0 +1,513 @@
+//! A panel showing the undo tree of the active editor's buffer, including the branches that
+//! were undone and then replaced by new edits. Selecting a state previews the changes made by
+//! the transaction leading to it, and confirming or clicking it moves the buffer to it.
+
+mod undo_tree_panel_settings;
+
+use std::{ops::Range, sync::Arc};
+
+use collections::HashMap;
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    ScrollStrategy, Subscription, Task, UniformListScrollHandle, WeakEntity, Window, actions,
+    uniform_list,
+};
+use language::{Buffer, BufferEvent};
+use settings::Settings;
+use text::{TransactionChange, TransactionId, UndoTree};
+use time::OffsetDateTime;
+use time_format::TimestampFormat;
+use ui::{ListItem, ListItemSpacing, WithScrollbar, prelude::*};
+use undo_tree_panel_settings::UndoTreePanelSettings;
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+actions!(
+    undo_tree_panel,
+    [
+        /// Toggles focus on the undo tree panel.
+        ToggleFocus
+    ]
+);
+
+const UNDO_TREE_PANEL_KEY: &str = "UndoTreePanel";
+
+/// The number of lines of the selected transaction's changes that are previewed.
+const MAX_PREVIEW_LINES: usize = 40;
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<UndoTreePanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+pub struct UndoTreePanel {
+    focus_handle: FocusHandle,
+    fs: Arc<dyn Fs>,
+    width: Option<Pixels>,
+    active: bool,
+    active_editor: Option<ActiveEditor>,
+    tree: UndoTree,
+    rows: Vec<UndoTreeRow>,
+    selected_ix: Option<usize>,
+    preview: Vec<TransactionChange>,
+    scroll_handle: UniformListScrollHandle,
+    _workspace_subscription: Subscription,
+}
+
+struct ActiveEditor {
+    editor: WeakEntity<Editor>,
+    buffer: Entity<Buffer>,
+    _buffer_subscription: Subscription,
+}
+
+/// A state of the undo tree, in the order the panel lists them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct UndoTreeRow {
+    state: Option<TransactionId>,
+    /// The number of branches this state is nested in.
+    depth: usize,
+    /// The position of the state in the order the states were created, starting with 0 for
+    /// the state before any transaction.
+    number: usize,
+}
+
+impl UndoTreePanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<anyhow::Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            workspace.update_in(cx, |workspace, window, cx| {
+                cx.new(|cx| Self::new(workspace, window, cx))
+            })
+        })
+    }
+
+    fn new(workspace: &Workspace, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let workspace_subscription = cx.subscribe_in(
+            &workspace
+                .weak_handle()
+                .upgrade()
+                .expect("have a &mut Workspace"),
+            window,
+            |this, workspace, event, _, cx| {
+                if let workspace::Event::ActiveItemChanged = event {
+                    this.set_active_editor(workspace_active_editor(workspace.read(cx), cx), cx);
+                }
+            },
+        );
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            fs: workspace.app_state().fs.clone(),
+            width: None,
+            active: false,
+            active_editor: None,
+            tree: UndoTree::default(),
+            rows: Vec::new(),
+            selected_ix: None,
+            preview: Vec::new(),
+            scroll_handle: UniformListScrollHandle::new(),
+            _workspace_subscription: workspace_subscription,
+        };
+        this.set_active_editor(workspace_active_editor(workspace, cx), cx);
+        this
+    }
+
+    fn set_active_editor(&mut self, editor: Option<Entity<Editor>>, cx: &mut Context<Self>) {
+        let buffer = editor
+            .as_ref()
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton());
+        self.active_editor = editor.zip(buffer).map(|(editor, buffer)| ActiveEditor {
+            editor: editor.downgrade(),
+            _buffer_subscription: cx.subscribe(&buffer, |this, _, event, cx| {
+                if let BufferEvent::Edited = event {
+                    this.refresh(cx);
+                }
+            }),
+            buffer,
+        });
+        self.refresh(cx);
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        if !self.active {
+            return;
+        }
+        self.tree = self
+            .active_editor
+            .as_ref()
+            .map(|active_editor| active_editor.buffer.read(cx).undo_tree())
+            .unwrap_or_default();
+        self.rows = tree_rows(&self.tree);
+        let current_ix = self
+            .rows
+            .iter()
+            .position(|row| row.state == self.tree.current);
+        self.select_row(current_ix, cx);
+    }
+
+    /// Selects a row and previews the changes made by the transaction leading to its state,
+    /// without moving the buffer to it.
+    fn select_row(&mut self, ix: Option<usize>, cx: &mut Context<Self>) {
+        self.selected_ix = ix;
+        let state = ix.and_then(|ix| self.rows.get(ix)?.state);
+        self.preview = self
+            .active_editor
+            .as_ref()
+            .zip(state)
+            .map(|(active_editor, transaction_id)| {
+                active_editor
+                    .buffer
+                    .read(cx)
+                    .transaction_changes(transaction_id)
+            })
+            .unwrap_or_default();
+        if let Some(ix) = ix {
+            self.scroll_handle
+                .scroll_to_item(ix, ScrollStrategy::Nearest);
+        }
+        cx.notify();
+    }
+
+    fn go_to_row(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(row) = self.rows.get(ix) else {
+            return;
+        };
+        let state = row.state;
+        if let Some(editor) = self
+            .active_editor
+            .as_ref()
+            .and_then(|active_editor| active_editor.editor.upgrade())
+        {
+            editor.update(cx, |editor, cx| {
+                editor.go_to_undo_state(state, window, cx);
+            });
+        }
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix
+            && ix + 1 < self.rows.len()
+        {
+            self.select_row(Some(ix + 1), cx);
+        }
+    }
+
+    fn select_previous(
+        &mut self,
+        _: &menu::SelectPrevious,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(ix) = self.selected_ix.and_then(|ix| ix.checked_sub(1)) {
+            self.select_row(Some(ix), cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(ix) = self.selected_ix {
+            self.go_to_row(ix, window, cx);
+        }
+        if let Some(editor) = self
+            .active_editor
+            .as_ref()
+            .and_then(|active_editor| active_editor.editor.upgrade())
+        {
+            window.focus(&editor.focus_handle(cx), cx);
+        }
+    }
+
+    fn render_rows(&mut self, range: Range<usize>, cx: &mut Context<Self>) -> Vec<AnyElement> {
+        let now = OffsetDateTime::now_utc();
+        range
+            .filter_map(|ix| {
+                let row = self.rows.get(ix)?;
+                let node = row
+                    .state
+                    .and_then(|transaction_id| self.tree.node(transaction_id));
+                let is_current = row.state == self.tree.current;
+                let is_selected = self.selected_ix == Some(ix);
+                let label = match node {
+                    Some(node) => {
+                        let edited_at = now - node.last_edit_at.elapsed();
+                        time_format::format_local_timestamp(
+                            edited_at,
+                            now,
+                            TimestampFormat::Relative,
+                        )
+                    }
+                    None => "Original".to_string(),
+                };
+
+                Some(
+                    ListItem::new(ix)
+                        .spacing(ListItemSpacing::Dense)
+                        .indent_level(row.depth)
+                        .toggle_state(is_selected)
+                        .start_slot(
+                            Icon::new(if is_current {
+                                IconName::Circle
+                            } else {
+                                IconName::Dash
+                            })
+                            .size(IconSize::XSmall)
+                            .color(if is_current {
+                                Color::Accent
+                            } else {
+                                Color::Muted
+                            }),
+                        )
+                        .child(Label::new(label).single_line())
+                        .end_slot(
+                            Label::new(row.number.to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.select_row(Some(ix), cx);
+                            this.go_to_row(ix, window, cx);
+                        }))
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+
+    fn render_preview(&self, cx: &App) -> impl IntoElement {
+        let mut lines = Vec::new();
+        for change in &self.preview {
+            lines.push((format!("Line {}", change.row + 1), Color::Muted));
+            lines.extend(
+                change
+                    .old_text
+                    .lines()
+                    .map(|line| (format!("- {line}"), Color::Deleted)),
+            );
+            lines.extend(
+                change
+                    .new_text
+                    .lines()
+                    .map(|line| (format!("+ {line}"), Color::Created)),
+            );
+        }
+        let is_truncated = lines.len() > MAX_PREVIEW_LINES;
+        lines.truncate(MAX_PREVIEW_LINES);
+
+        v_flex()
+            .p_2()
+            .gap_0p5()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .children(lines.into_iter().map(|(line, color)| {
+                Label::new(line)
+                    .size(LabelSize::Small)
+                    .color(color)
+                    .buffer_font(cx)
+                    .single_line()
+            }))
+            .when(is_truncated, |this| {
+                this.child(Label::new("…").size(LabelSize::Small).color(Color::Muted))
+            })
+    }
+}
+
+/// Lists the states of the tree depth first. The most recent branch after a state continues
+/// at its depth, and the older ones are nested and listed before it.
+fn tree_rows(tree: &UndoTree) -> Vec<UndoTreeRow> {
+    let mut children = HashMap::<Option<TransactionId>, Vec<usize>>::default();
+    for (ix, node) in tree.nodes.iter().enumerate() {
+        children.entry(node.parent).or_default().push(ix);
+    }
+    let push_children =
+        |stack: &mut Vec<(usize, usize)>, parent: Option<TransactionId>, depth: usize| {
+            if let Some((&newest, older)) = children
+                .get(&parent)
+                .and_then(|child_ixs| child_ixs.split_last())
+            {
+                stack.push((newest, depth));
+                stack.extend(older.iter().rev().map(|&ix| (ix, depth + 1)));
+            }
+        };
+
+    let mut rows = vec![UndoTreeRow {
+        state: None,
+        depth: 0,
+        number: 0,
+    }];
+    let mut stack = Vec::new();
+    push_children(&mut stack, None, 0);
+    while let Some((ix, depth)) = stack.pop() {
+        let transaction_id = tree.nodes[ix].transaction_id;
+        rows.push(UndoTreeRow {
+            state: Some(transaction_id),
+            depth,
+            number: ix + 1,
+        });
+        push_children(&mut stack, Some(transaction_id), depth);
+    }
+    rows
+}
+
+fn workspace_active_editor(workspace: &Workspace, cx: &App) -> Option<Entity<Editor>> {
+    workspace
+        .active_item(cx)?
+        .act_as::<Editor>(cx)
+        .filter(|editor| editor.read(cx).mode().is_full())
+}
+
+impl EventEmitter<PanelEvent> for UndoTreePanel {}
+
+impl Focusable for UndoTreePanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for UndoTreePanel {
+    fn persistent_name() -> &'static str {
+        "Undo Tree Panel"
+    }
+
+    fn panel_key() -> &'static str {
+        UNDO_TREE_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        UndoTreePanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.undo_tree_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| UndoTreePanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        UndoTreePanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::HistoryRerun)
+    }
+
+    fn icon_tooltip(&self, _: &Window, _: &App) -> Option<&'static str> {
+        Some("Undo Tree Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn set_active(&mut self, active: bool, _: &mut Window, cx: &mut Context<Self>) {
+        self.active = active;
+        self.refresh(cx);
+    }
+
+    fn activation_priority(&self) -> u32 {
+        7
+    }
+}
+
+impl Render for UndoTreePanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = if self.active_editor.is_none() {
+            v_flex()
+                .flex_1()
+                .p_2()
+                .child(
+                    Label::new("Open a file to see its undo tree")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element()
+        } else {
+            v_flex()
+                .flex_1()
+                .min_h_0()
+                .child(
+                    div()
+                        .flex_1()
+                        .min_h_0()
+                        .child(
+                            uniform_list(
+                                "undo-tree-rows",
+                                self.rows.len(),
+                                cx.processor(|this, range: Range<usize>, _, cx| {
+                                    this.render_rows(range, cx)
+                                }),
+                            )
+                            .size_full()
+                            .track_scroll(&self.scroll_handle),
+                        )
+                        .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+                )
+                .when(!self.preview.is_empty(), |this| {
+                    this.child(self.render_preview(cx))
+                })
+                .into_any_element()
+        };
+
+        v_flex()
+            .id("undo-tree-panel")
+            .key_context("UndoTreePanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use text::{BufferId, ReplicaId};
+
+    #[test]
+    fn test_tree_rows() {
+        let mut buffer = text::Buffer::new(ReplicaId::LOCAL, BufferId::new(1).unwrap(), "");
+        buffer.edit([(0..0, "a")]);
+        buffer.edit([(1..1, "b")]);
+        buffer.undo();
+        buffer.edit([(1..1, "c")]);
+        buffer.edit([(2..2, "d")]);
+        buffer.undo();
+        buffer.undo();
+        buffer.edit([(1..1, "e")]);
+
+        let tree = buffer.undo_tree();
+        let rows = tree_rows(&tree);
+        let state = |number: usize| Some(tree.nodes[number - 1].transaction_id);
+        assert_eq!(
+            rows.iter()
+                .map(|row| (row.number, row.depth))
+                .collect::<Vec<_>>(),
+            [(0, 0), (1, 0), (2, 1), (3, 1), (4, 1), (5, 0)]
+        );
+        assert_eq!(rows[2].state, state(2));
+        assert_eq!(rows[5].state, tree.current);
+    }
+}
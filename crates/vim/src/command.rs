@@ -26,7 +26,7 @@ use std::{
     process::Stdio,
     str::Chars,
     sync::OnceLock,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use task::{HideStrategy, RevealStrategy, SpawnInTerminal, TaskId};
 use ui::ActiveTheme;
@@ -202,6 +202,43 @@ struct VimSplit {
     pub filename: String,
 }
 
+/// Moves to an earlier or later state of the buffer's undo tree, regardless of its branch.
+#[derive(Clone, PartialEq, Action)]
+#[action(namespace = vim, no_json, no_register)]
+struct UndoTimeTravel {
+    pub later: bool,
+    pub distance: UndoDistance,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum UndoDistance {
+    Steps(usize),
+    Duration(Duration),
+}
+
+impl UndoDistance {
+    /// Parses the argument of `:earlier` and `:later`, a count optionally followed by `s`,
+    /// `m`, `h` or `d` for a duration.
+    fn parse(args: &str) -> Option<Self> {
+        let args = args.trim();
+        let count_len = args
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(args.len());
+        let (count, unit) = args.split_at(count_len);
+        let count = count.parse::<u64>().ok()?;
+        let unit_seconds = match unit {
+            "" => return Some(Self::Steps(count as usize)),
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            _ => return None,
+        };
+        let seconds = count.checked_mul(unit_seconds)?;
+        Some(Self::Duration(Duration::from_secs(seconds)))
+    }
+}
+
 #[derive(Clone, PartialEq, Action)]
 #[action(namespace = vim, no_json, no_register)]
 enum DeleteMarks {
@@ -755,6 +792,24 @@ pub fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
         });
     });
 
+    Vim::action(editor, cx, |vim, action: &UndoTimeTravel, window, cx| {
+        vim.update_editor(cx, |_, editor, cx| {
+            let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+                return;
+            };
+            let tree = buffer.read(cx).undo_tree();
+            let target = match (action.later, action.distance) {
+                (false, UndoDistance::Steps(steps)) => tree.earlier(steps),
+                (true, UndoDistance::Steps(steps)) => tree.later(steps),
+                (false, UndoDistance::Duration(duration)) => tree.earlier_by(duration),
+                (true, UndoDistance::Duration(duration)) => tree.later_by(duration),
+            };
+            if target != tree.current {
+                editor.go_to_undo_state(target, window, cx);
+            }
+        });
+    });
+
     Vim::action(editor, cx, |vim, action: &VimNorm, window, cx| {
         let keystrokes = action
             .command
@@ -1731,6 +1786,38 @@ fn generate_commands(_: &App) -> Vec<VimCommand> {
         VimCommand::new(("delm", "arks"), ArgumentRequired)
             .bang(DeleteMarks::AllLocal)
             .args(|_, args| Some(DeleteMarks::Marks(args).boxed_clone())),
+        VimCommand::new(
+            ("ea", "rlier"),
+            UndoTimeTravel {
+                later: false,
+                distance: UndoDistance::Steps(1),
+            },
+        )
+        .args(|_, args| {
+            Some(
+                UndoTimeTravel {
+                    later: false,
+                    distance: UndoDistance::parse(&args)?,
+                }
+                .boxed_clone(),
+            )
+        }),
+        VimCommand::new(
+            ("lat", "er"),
+            UndoTimeTravel {
+                later: true,
+                distance: UndoDistance::Steps(1),
+            },
+        )
+        .args(|_, args| {
+            Some(
+                UndoTimeTravel {
+                    later: true,
+                    distance: UndoDistance::parse(&args)?,
+                }
+                .boxed_clone(),
+            )
+        }),
         VimCommand::new(("sor", "t"), SortLinesCaseSensitive)
             .range(select_range)
             .default_range(CommandRange::buffer()),
@@ -2460,6 +2547,7 @@ impl ShellExec {
                     show_summary: false,
                     show_command: false,
                     show_rerun: false,
+                    problem_matchers: Vec::new(),
                 };
 
                 let task_status = workspace.spawn_in_terminal(spawn_in_terminal, window, cx);
@@ -2609,8 +2697,12 @@ impl ShellExec {
 
 #[cfg(test)]
 mod test {
-    use std::path::{Path, PathBuf};
+    use std::{
+        path::{Path, PathBuf},
+        time::Duration,
+    };
 
+    use super::UndoDistance;
     use crate::{
         VimAddon,
         state::Mode,
@@ -2811,6 +2903,42 @@ mod test {
         cx.assert_state("one\nˇtwo\nthree", Mode::Normal);
     }
 
+    #[gpui::test]
+    async fn test_earlier_later(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.simulate_keystrokes("A a escape");
+        cx.simulate_keystrokes("A b escape");
+        cx.simulate_keystrokes("u");
+        cx.simulate_keystrokes("A c escape");
+        assert_eq!(cx.buffer_text(), "ac");
+
+        // Undo can't get back to "ab", but :earlier goes through the replaced branch.
+        cx.simulate_keystrokes(": e a r l i e r enter");
+        assert_eq!(cx.buffer_text(), "ab");
+        cx.simulate_keystrokes(": e a r l i e r space 1 enter");
+        assert_eq!(cx.buffer_text(), "a");
+        cx.simulate_keystrokes(": l a t e r space 2 enter");
+        assert_eq!(cx.buffer_text(), "ac");
+        cx.simulate_keystrokes(": l a t e r enter");
+        assert_eq!(cx.buffer_text(), "ac");
+    }
+
+    #[test]
+    fn test_parse_undo_distance() {
+        assert_eq!(UndoDistance::parse("3"), Some(UndoDistance::Steps(3)));
+        assert_eq!(
+            UndoDistance::parse(" 10m "),
+            Some(UndoDistance::Duration(Duration::from_secs(600)))
+        );
+        assert_eq!(
+            UndoDistance::parse("2d"),
+            Some(UndoDistance::Duration(Duration::from_secs(172800)))
+        );
+        assert_eq!(UndoDistance::parse("1f"), None);
+        assert_eq!(UndoDistance::parse("m"), None);
+    }
+
     #[gpui::test]
     async fn test_command_quit(cx: &mut TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
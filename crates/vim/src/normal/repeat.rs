@@ -1,5 +1,3 @@
-use std::{cell::RefCell, rc::Rc};
-
 use crate::{
     Vim,
     insert::NormalBefore,
@@ -7,9 +5,11 @@ use crate::{
     normal::InsertBefore,
     state::{Mode, Operator, RecordedSelection, ReplayableAction, VimGlobals},
 };
-use editor::Editor;
+use editor::{
+    Editor,
+    replay::{self, Replayable, Replayer},
+};
 use gpui::{Action, App, Context, Window, actions};
-use workspace::Workspace;
 
 actions!(
     vim,
@@ -25,14 +25,6 @@ actions!(
     ]
 );
 
-fn should_replay(action: &dyn Action) -> bool {
-    // skip so that we don't leave the character palette open
-    if editor::actions::ShowCharacterPalette.partial_eq(action) {
-        return false;
-    }
-    true
-}
-
 fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
     match action {
         ReplayableAction::Action(action) => {
@@ -83,43 +75,43 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut Context<Vim>) {
     });
 }
 
-pub struct ReplayerState {
-    actions: Vec<ReplayableAction>,
-    running: bool,
-    ix: usize,
+impl Replayable for ReplayableAction {
+    fn replay(self, window: &mut Window, cx: &mut App) {
+        match self {
+            ReplayableAction::Action(action) => {
+                if replay::should_replay(&*action) {
+                    window.dispatch_action(action.boxed_clone(), cx);
+                    cx.defer(move |cx| Vim::globals(cx).observe_action(action.boxed_clone()));
+                }
+            }
+            ReplayableAction::Insertion {
+                text,
+                utf16_range_to_replace,
+            } => replay::replay_insertion(&text, utf16_range_to_replace, window, cx),
+        }
+    }
 }
 
-#[derive(Clone)]
-pub struct Replayer(Rc<RefCell<ReplayerState>>);
-
-impl Replayer {
-    pub fn new() -> Self {
-        Self(Rc::new(RefCell::new(ReplayerState {
-            actions: vec![],
-            running: false,
-            ix: 0,
-        })))
-    }
+pub(crate) fn new_replayer() -> Replayer<ReplayableAction> {
+    Replayer::new(|cx| {
+        // The `globals.dot_replaying = false` is a fail-safe to ensure that
+        // this value is always reset, in the case that the focus is moved
+        // away from the editor, effectively preventing the `EndRepeat`
+        // action from being handled.
+        let globals = Vim::globals(cx);
+        globals.replayer.take();
+        globals.dot_replaying = false;
+    })
+}
 
-    pub fn replay(&mut self, actions: Vec<ReplayableAction>, window: &mut Window, cx: &mut App) {
-        let mut lock = self.0.borrow_mut();
-        let range = lock.ix..lock.ix;
-        lock.actions.splice(range, actions);
-        if lock.running {
-            return;
-        }
-        lock.running = true;
-        let this = self.clone();
-        window.defer(cx, move |window, cx| {
-            this.next(window, cx);
-            let Some(Some(workspace)) = window.root::<Workspace>() else {
-                return;
-            };
-            let Some(editor) = workspace
-                .read(cx)
-                .active_item(cx)
-                .and_then(|item| item.act_as::<Editor>(cx))
-            else {
+fn replay(actions: Vec<ReplayableAction>, window: &mut Window, cx: &mut App) {
+    let replayer = Vim::globals(cx)
+        .replayer
+        .get_or_insert_with(new_replayer)
+        .clone();
+    if replayer.replay(actions, window, cx) {
+        window.defer(cx, |window, cx| {
+            let Some(editor) = replay::active_editor(window, cx) else {
                 return;
             };
             editor.update(cx, |editor, cx| {
@@ -127,60 +119,7 @@ impl Replayer {
                     .buffer()
                     .update(cx, |multi, cx| multi.finalize_last_transaction(cx))
             });
-        })
-    }
-
-    pub fn stop(self) {
-        self.0.borrow_mut().actions.clear()
-    }
-
-    pub fn next(self, window: &mut Window, cx: &mut App) {
-        let mut lock = self.0.borrow_mut();
-        let action = if lock.ix < 10000 {
-            lock.actions.get(lock.ix).cloned()
-        } else {
-            log::error!("Aborting replay after 10000 actions");
-            None
-        };
-        lock.ix += 1;
-        drop(lock);
-        let Some(action) = action else {
-            // The `globals.dot_replaying = false` is a fail-safe to ensure that
-            // this value is always reset, in the case that the focus is moved
-            // away from the editor, effectively preventing the `EndRepeat`
-            // action from being handled.
-            let globals = Vim::globals(cx);
-            globals.replayer.take();
-            globals.dot_replaying = false;
-            return;
-        };
-        match action {
-            ReplayableAction::Action(action) => {
-                if should_replay(&*action) {
-                    window.dispatch_action(action.boxed_clone(), cx);
-                    cx.defer(move |cx| Vim::globals(cx).observe_action(action.boxed_clone()));
-                }
-            }
-            ReplayableAction::Insertion {
-                text,
-                utf16_range_to_replace,
-            } => {
-                let Some(Some(workspace)) = window.root::<Workspace>() else {
-                    return;
-                };
-                let Some(editor) = workspace
-                    .read(cx)
-                    .active_item(cx)
-                    .and_then(|item| item.act_as::<Editor>(cx))
-                else {
-                    return;
-                };
-                editor.update(cx, |editor, cx| {
-                    editor.replay_insert_event(&text, utf16_range_to_replace.clone(), window, cx)
-                })
-            }
-        }
-        window.defer(cx, move |window, cx| self.next(window, cx));
+        });
     }
 }
 
@@ -226,8 +165,7 @@ impl Vim {
         }
 
         globals.last_replayed_register = Some(register);
-        let mut replayer = globals.replayer.get_or_insert_with(Replayer::new).clone();
-        replayer.replay(repeated_actions, window, cx);
+        replay(repeated_actions, window, cx);
     }
 
     pub(crate) fn repeat(
@@ -387,9 +325,8 @@ impl Vim {
 
         let globals = Vim::globals(cx);
         globals.dot_replaying = true;
-        let mut replayer = globals.replayer.get_or_insert_with(Replayer::new).clone();
 
-        replayer.replay(actions, window, cx);
+        replay(actions, window, cx);
     }
 }
 
@@ -1,6 +1,5 @@
 use crate::command::command_interceptor;
 use crate::motion::MotionKind;
-use crate::normal::repeat::Replayer;
 use crate::surrounds::SurroundsType;
 use crate::{ToggleMarksView, ToggleRegistersView, UseSystemClipboard, Vim, VimAddon, VimSettings};
 use crate::{motion::Motion, object::Object};
@@ -12,6 +11,7 @@ use db::{
     sqlez_macros::sql,
 };
 use editor::display_map::{is_invisible, replacement};
+use editor::replay::Replayer;
 use editor::{Anchor, ClipboardSelection, Editor, MultiBuffer, ToPoint as EditorToPoint};
 use gpui::{
     Action, App, AppContext, BorrowAppContext, ClipboardEntry, ClipboardItem, DismissEvent, Entity,
@@ -231,7 +231,7 @@ pub struct VimGlobals {
     pub recording_register: Option<char>,
     pub last_recorded_register: Option<char>,
     pub last_replayed_register: Option<char>,
-    pub replayer: Option<Replayer>,
+    pub replayer: Option<Replayer<ReplayableAction>>,
 
     pub last_yank: Option<SharedString>,
     pub registers: HashMap<char, Register>,
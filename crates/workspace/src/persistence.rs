@@ -24,7 +24,8 @@ use project::{
 
 use language::{LanguageName, Toolchain, ToolchainScope};
 use remote::{
-    DockerConnectionOptions, RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions,
+    CommandConnectionOptions, DockerConnectionOptions, RemoteConnectionOptions,
+    SshConnectionOptions, WslConnectionOptions,
 };
 use serde::{Deserialize, Serialize};
 use sqlez::{
@@ -55,6 +56,15 @@ use self::model::{DockStructure, SerializedWorkspaceLocation};
 // > which defaults to <..> 32766 for SQLite versions after 3.32.0.
 const MAX_QUERY_PLACEHOLDERS: usize = 32000;
 
+/// The command of a command connection, as stored in the `command` column of `remote_connections`.
+#[derive(Serialize, Deserialize)]
+struct SerializedCommand {
+    program: String,
+    args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    interactive_args: Option<Vec<String>>,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub(crate) struct SerializedAxis(pub(crate) gpui::Axis);
 impl sqlez::bindable::StaticColumnCount for SerializedAxis {}
@@ -884,6 +894,9 @@ impl Domain for WorkspaceDb {
         sql!(
             ALTER TABLE remote_connections ADD COLUMN use_podman BOOLEAN;
         ),
+        sql!(
+            ALTER TABLE remote_connections ADD COLUMN command TEXT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1312,6 +1325,7 @@ impl WorkspaceDb {
         let mut name = None;
         let mut container_id = None;
         let mut use_podman = None;
+        let mut command = None;
         match options {
             RemoteConnectionOptions::Ssh(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1330,6 +1344,15 @@ impl WorkspaceDb {
                 name = Some(options.name);
                 use_podman = Some(options.use_podman)
             }
+            RemoteConnectionOptions::Command(options) => {
+                kind = RemoteConnectionKind::Command;
+                name = Some(options.name);
+                command = Some(serde_json::to_string(&SerializedCommand {
+                    program: options.command,
+                    args: options.args,
+                    interactive_args: options.interactive_args,
+                })?);
+            }
             #[cfg(any(test, feature = "test-support"))]
             RemoteConnectionOptions::Mock(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1346,6 +1369,7 @@ impl WorkspaceDb {
             name,
             container_id,
             use_podman,
+            command,
         )
     }
 
@@ -1359,6 +1383,7 @@ impl WorkspaceDb {
         name: Option<String>,
         container_id: Option<String>,
         use_podman: Option<bool>,
+        command: Option<String>,
     ) -> Result<RemoteConnectionId> {
         if let Some(id) = this.select_row_bound(sql!(
             SELECT id
@@ -1370,7 +1395,8 @@ impl WorkspaceDb {
                 user IS ? AND
                 distro IS ? AND
                 name IS ? AND
-                container_id IS ?
+                container_id IS ? AND
+                command IS ?
             LIMIT 1
         ))?((
             kind.serialize(),
@@ -1380,6 +1406,7 @@ impl WorkspaceDb {
             distro.clone(),
             name.clone(),
             container_id.clone(),
+            command.clone(),
         ))? {
             Ok(RemoteConnectionId(id))
         } else {
@@ -1392,8 +1419,9 @@ impl WorkspaceDb {
                     distro,
                     name,
                     container_id,
-                    use_podman
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                    use_podman,
+                    command
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
                 RETURNING id
             ))?((
                 kind.serialize(),
@@ -1404,6 +1432,7 @@ impl WorkspaceDb {
                 name,
                 container_id,
                 use_podman,
+                command,
             ))?
             .context("failed to insert remote project")?;
             Ok(RemoteConnectionId(id))
@@ -1487,13 +1516,13 @@ impl WorkspaceDb {
     fn remote_connections(&self) -> Result<HashMap<RemoteConnectionId, RemoteConnectionOptions>> {
         Ok(self.select(sql!(
             SELECT
-                id, kind, host, port, user, distro, container_id, name, use_podman
+                id, kind, host, port, user, distro, container_id, name, use_podman, command
             FROM
                 remote_connections
         ))?()?
         .into_iter()
         .filter_map(
-            |(id, kind, host, port, user, distro, container_id, name, use_podman)| {
+            |(id, kind, host, port, user, distro, container_id, name, use_podman, command)| {
                 Some((
                     RemoteConnectionId(id),
                     Self::remote_connection_from_row(
@@ -1505,6 +1534,7 @@ impl WorkspaceDb {
                         container_id,
                         name,
                         use_podman,
+                        command,
                     )?,
                 ))
             },
@@ -1516,9 +1546,9 @@ impl WorkspaceDb {
         &self,
         id: RemoteConnectionId,
     ) -> Result<RemoteConnectionOptions> {
-        let (kind, host, port, user, distro, container_id, name, use_podman) =
+        let (kind, host, port, user, distro, container_id, name, use_podman, command) =
             self.select_row_bound(sql!(
-                SELECT kind, host, port, user, distro, container_id, name, use_podman
+                SELECT kind, host, port, user, distro, container_id, name, use_podman, command
                 FROM remote_connections
                 WHERE id = ?
             ))?(id.0)?
@@ -1532,6 +1562,7 @@ impl WorkspaceDb {
             container_id,
             name,
             use_podman,
+            command,
         )
         .context("invalid remote_connection row")
     }
@@ -1545,6 +1576,7 @@ impl WorkspaceDb {
         container_id: Option<String>,
         name: Option<String>,
         use_podman: Option<bool>,
+        command: Option<String>,
     ) -> Option<RemoteConnectionOptions> {
         match RemoteConnectionKind::deserialize(&kind)? {
             RemoteConnectionKind::Wsl => Some(RemoteConnectionOptions::Wsl(WslConnectionOptions {
@@ -1565,6 +1597,15 @@ impl WorkspaceDb {
                     use_podman: use_podman?,
                 }))
             }
+            RemoteConnectionKind::Command => {
+                let command = serde_json::from_str::<SerializedCommand>(&command?).log_err()?;
+                Some(RemoteConnectionOptions::Command(CommandConnectionOptions {
+                    name: name?,
+                    command: command.program,
+                    args: command.args,
+                    interactive_args: command.interactive_args,
+                }))
+            }
         }
     }
 
@@ -33,6 +33,7 @@ pub(crate) enum RemoteConnectionKind {
     Ssh,
     Wsl,
     Docker,
+    Command,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -77,6 +78,7 @@ impl RemoteConnectionKind {
             RemoteConnectionKind::Ssh => "ssh",
             RemoteConnectionKind::Wsl => "wsl",
             RemoteConnectionKind::Docker => "docker",
+            RemoteConnectionKind::Command => "command",
         }
     }
 
@@ -85,6 +87,7 @@ impl RemoteConnectionKind {
             "ssh" => Some(Self::Ssh),
             "wsl" => Some(Self::Wsl),
             "docker" => Some(Self::Docker),
+            "command" => Some(Self::Command),
             _ => None,
         }
     }
@@ -1,15 +1,25 @@
-use std::process::ExitStatus;
+use std::{cell::RefCell, process::ExitStatus, rc::Rc, sync::Arc};
 
-use anyhow::Result;
-use gpui::{AppContext, Context, Entity, Task};
+use anyhow::{Result, anyhow, bail};
+use collections::HashMap;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{App, AppContext, Context, Entity, Task};
 use language::Buffer;
-use project::{TaskSourceKind, WorktreeId};
+use project::{TaskDependencies, TaskDependency, TaskSourceKind, WorktreeId};
 use remote::ConnectionState;
-use task::{DebugScenario, ResolvedTask, SpawnInTerminal, TaskContext, TaskTemplate};
+use task::{
+    DebugScenario, DependsOrder, ResolvedTask, SpawnInTerminal, TaskContext, TaskId, TaskInput,
+    TaskTemplate,
+};
 use ui::Window;
 
 use crate::{Toast, Workspace, notifications::NotificationId};
 
+type SharedDependencyRun = Shared<Task<Result<(), Arc<anyhow::Error>>>>;
+
+/// The dependencies spawned while running a task, so that each of them only runs once.
+type DependencyRuns = Rc<RefCell<HashMap<TaskId, SharedDependencyRun>>>;
+
 impl Workspace {
     pub fn schedule_task(
         self: &mut Workspace,
@@ -54,12 +64,71 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
-        let spawn_in_terminal = resolved_task.resolved.clone();
+        if resolved_task.original_task().depends_on.is_empty() {
+            self.schedule_task_with_dependencies(
+                task_source_kind,
+                resolved_task,
+                None,
+                omit_history,
+                window,
+                cx,
+            );
+            return;
+        }
+
+        let dependencies = self.resolve_task_dependencies(&task_source_kind, &resolved_task, cx);
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let dependencies = match dependencies.await {
+                Ok(dependencies) => dependencies,
+                Err(e) => {
+                    log::error!("Task dependencies resolution failed: {e:#}");
+                    _ = workspace.update(cx, |workspace, cx| {
+                        let id = NotificationId::unique::<ResolvedTask>();
+                        workspace.show_toast(Toast::new(id, format!("{e:#}")), cx);
+                    });
+                    return;
+                }
+            };
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.schedule_task_with_dependencies(
+                        task_source_kind,
+                        resolved_task,
+                        Some(dependencies),
+                        omit_history,
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+        });
+        self.scheduled_tasks.push(task);
+    }
+
+    fn schedule_task_with_dependencies(
+        &mut self,
+        task_source_kind: TaskSourceKind,
+        resolved_task: ResolvedTask,
+        dependencies: Option<TaskDependencies>,
+        omit_history: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut inputs = resolved_task.inputs();
+        if let Some(dependencies) = &dependencies {
+            for input in dependencies.inputs() {
+                if !inputs.iter().any(|known_input| known_input.id == input.id) {
+                    inputs.push(input);
+                }
+            }
+        }
         if !omit_history {
             if let Some(debugger_provider) = self.debugger_provider.as_ref() {
                 debugger_provider.task_scheduled(cx);
             }
 
+            // Keep the inputs unresolved in the history, so that they are asked for again on rerun.
+            let resolved_task = resolved_task.clone();
             self.project().update(cx, |project, cx| {
                 if let Some(task_inventory) =
                     project.task_store().read(cx).task_inventory().cloned()
@@ -71,7 +140,111 @@ impl Workspace {
             });
         }
 
-        if let Some(terminal_provider) = self.terminal_provider.as_ref() {
+        if inputs.is_empty() {
+            self.spawn_resolved_task(resolved_task, dependencies, window, cx);
+            return;
+        }
+        let input_values = self.prompt_for_task_inputs(inputs, window, cx);
+        let task = cx.spawn_in(window, async move |workspace, cx| {
+            let Some(input_values) = input_values.await else {
+                log::debug!("Task spawn got cancelled: task inputs were not entered");
+                return;
+            };
+            workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.spawn_resolved_task(
+                        resolved_task.with_input_values(&input_values),
+                        dependencies
+                            .map(|dependencies| dependencies.with_input_values(&input_values)),
+                        window,
+                        cx,
+                    );
+                })
+                .ok();
+        });
+        self.scheduled_tasks.push(task);
+    }
+
+    /// Asks for the values of the task inputs one after another, proposing the values entered the last time.
+    /// Resolves with `None` if any of the prompts is dismissed.
+    pub fn prompt_for_task_inputs(
+        &mut self,
+        mut inputs: Vec<TaskInput>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<HashMap<String, String>>> {
+        let task_inventory = self
+            .project
+            .read(cx)
+            .task_store()
+            .read(cx)
+            .task_inventory()
+            .cloned();
+        if let Some(task_inventory) = &task_inventory {
+            let task_inventory = task_inventory.read(cx);
+            for input in &mut inputs {
+                if let Some(last_value) = task_inventory.last_input_value(&input.id) {
+                    input.default = Some(last_value.to_string());
+                }
+            }
+        }
+
+        cx.spawn_in(window, async move |workspace, cx| {
+            let mut values = HashMap::default();
+            for input in inputs {
+                let id = input.id.clone();
+                let value = workspace
+                    .update_in(cx, |workspace, window, cx| {
+                        let Some(prompt) = workspace.on_prompt_for_task_input.take() else {
+                            log::error!("Cannot ask for the value of task input `{id}`");
+                            return None;
+                        };
+                        let value = prompt(workspace, input, window, cx);
+                        workspace.on_prompt_for_task_input = Some(prompt);
+                        Some(value)
+                    })
+                    .ok()??;
+                values.insert(id, value.await.ok()??);
+            }
+            if let Some(task_inventory) = task_inventory {
+                let values = values.clone();
+                task_inventory
+                    .update(cx, |inventory, _| inventory.input_values_entered(values))
+                    .ok();
+            }
+            Some(values)
+        })
+    }
+
+    fn spawn_resolved_task(
+        &mut self,
+        resolved_task: ResolvedTask,
+        dependencies: Option<TaskDependencies>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let has_command = resolved_task.original_task().has_command();
+        let spawn_in_terminal = resolved_task.resolved;
+        if let Some(dependencies) = dependencies {
+            let label = spawn_in_terminal.label.clone();
+            let run = self.spawn_with_dependencies(
+                has_command.then_some(spawn_in_terminal),
+                dependencies,
+                DependencyRuns::default(),
+                window,
+                cx,
+            );
+            let task = cx.spawn(async move |w, cx| {
+                if let Err(e) = run.await {
+                    log::error!("Task `{label}` stopped: {e:#}");
+                    _ = w.update(cx, |w, cx| {
+                        let id = NotificationId::unique::<ResolvedTask>();
+                        w.show_toast(Toast::new(id, format!("Task `{label}` stopped: {e}")), cx);
+                    })
+                }
+            });
+            self.scheduled_tasks.push(task);
+        } else if let Some(terminal_provider) = self.terminal_provider.as_ref() {
             let task_status = terminal_provider.spawn(spawn_in_terminal, window, cx);
 
             let task = cx.spawn(async |w, cx| {
@@ -98,6 +271,127 @@ impl Workspace {
         }
     }
 
+    fn resolve_task_dependencies(
+        &self,
+        task_source_kind: &TaskSourceKind,
+        resolved_task: &ResolvedTask,
+        cx: &App,
+    ) -> Task<Result<TaskDependencies>> {
+        let project = self.project.read(cx);
+        let Some(inventory) = project.task_store().read(cx).task_inventory() else {
+            return Task::ready(Err(anyhow!("Tasks aren't available in this project")));
+        };
+        let worktree = match task_source_kind {
+            TaskSourceKind::Worktree { id, .. } => Some(*id),
+            _ => project
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).id()),
+        };
+        // Language tasks are only listed for the file they apply to, so dependencies are looked
+        // up among the tasks of the active buffer, like the tasks in the modal.
+        let buffer = self
+            .active_item(cx)
+            .and_then(|item| item.project_path(cx))
+            .and_then(|project_path| project.get_open_buffer(&project_path, cx))
+            .map(|buffer| buffer.read(cx));
+        let file = buffer.and_then(|buffer| buffer.file().cloned());
+        let language = buffer.and_then(|buffer| buffer.language().cloned());
+        inventory
+            .read(cx)
+            .resolve_dependencies(resolved_task, file, language, worktree, cx)
+    }
+
+    /// Runs the dependencies in their order, then spawns the task itself, if it has a command.
+    /// Stops at the first task that fails, and resolves with its error.
+    ///
+    /// A task that several others depend on is only run once, and they all wait for that run.
+    fn spawn_with_dependencies(
+        &mut self,
+        spawn_in_terminal: Option<SpawnInTerminal>,
+        dependencies: TaskDependencies,
+        runs: DependencyRuns,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn_in(window, async move |workspace, cx| {
+            match dependencies.order {
+                DependsOrder::Sequence => {
+                    for dependency in dependencies.tasks {
+                        let run = workspace.update_in(cx, |workspace, window, cx| {
+                            workspace.spawn_dependency(dependency, runs.clone(), window, cx)
+                        })?;
+                        run.await.map_err(|e| anyhow!("{e:#}"))?;
+                    }
+                }
+                DependsOrder::Parallel => {
+                    let runs = workspace.update_in(cx, |workspace, window, cx| {
+                        dependencies
+                            .tasks
+                            .into_iter()
+                            .map(|dependency| {
+                                workspace.spawn_dependency(dependency, runs.clone(), window, cx)
+                            })
+                            .collect::<Vec<_>>()
+                    })?;
+                    futures::future::try_join_all(
+                        runs.into_iter()
+                            .map(|run| async move { run.await.map_err(|e| anyhow!("{e:#}")) }),
+                    )
+                    .await?;
+                }
+            }
+
+            let Some(spawn_in_terminal) = spawn_in_terminal else {
+                return Ok(());
+            };
+            let label = spawn_in_terminal.label.clone();
+            let status = workspace
+                .update_in(cx, |workspace, window, cx| {
+                    workspace.spawn_in_terminal(spawn_in_terminal, window, cx)
+                })?
+                .await;
+            match status {
+                Some(Ok(status)) if status.success() => Ok(()),
+                Some(Ok(status)) => match status.code() {
+                    Some(code) => bail!("task `{label}` failed with exit code {code}"),
+                    None => bail!("task `{label}` was terminated"),
+                },
+                Some(Err(e)) => Err(e.context(format!("failed to spawn task `{label}`"))),
+                None => bail!("task `{label}` was cancelled"),
+            }
+        })
+    }
+
+    fn spawn_dependency(
+        &mut self,
+        dependency: TaskDependency,
+        runs: DependencyRuns,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> SharedDependencyRun {
+        if let Some(run) = runs.borrow().get(&dependency.task.id) {
+            return run.clone();
+        }
+        let spawn_in_terminal = dependency
+            .task
+            .original_task()
+            .has_command()
+            .then(|| dependency.task.resolved.clone());
+        let run = self.spawn_with_dependencies(
+            spawn_in_terminal,
+            dependency.dependencies,
+            runs.clone(),
+            window,
+            cx,
+        );
+        let run = cx
+            .spawn(async move |_, _| run.await.map_err(Arc::new))
+            .shared();
+        runs.borrow_mut().insert(dependency.task.id, run.clone());
+        run
+    }
+
     pub fn start_debug_session(
         &mut self,
         scenario: DebugScenario,
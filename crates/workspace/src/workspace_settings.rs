@@ -21,6 +21,8 @@ pub struct WorkspaceSettings {
     pub autosave: AutosaveSetting,
     pub restore_on_startup: settings::RestoreOnStartupBehavior,
     pub restore_on_file_reopen: bool,
+    pub persistent_undo: bool,
+    pub local_history: bool,
     pub drop_target_size: f32,
     pub use_system_path_prompts: bool,
     pub use_system_prompts: bool,
@@ -92,6 +94,8 @@ impl Settings for WorkspaceSettings {
             autosave: workspace.autosave.unwrap(),
             restore_on_startup: workspace.restore_on_startup.unwrap(),
             restore_on_file_reopen: workspace.restore_on_file_reopen.unwrap(),
+            persistent_undo: workspace.persistent_undo.unwrap(),
+            local_history: workspace.local_history.unwrap(),
             drop_target_size: workspace.drop_target_size.unwrap(),
             use_system_path_prompts: workspace.use_system_path_prompts.unwrap(),
             use_system_prompts: workspace.use_system_prompts.unwrap(),
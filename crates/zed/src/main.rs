@@ -633,6 +633,10 @@ fn main() {
         load_embedded_fonts(cx);
 
         editor::init(cx);
+        // Openers registered later are tried first, so files large enough for the large file
+        // viewer are opened in it rather than the hex editor.
+        hex_editor::init(cx);
+        large_file_viewer::init(cx);
         image_viewer::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
@@ -642,6 +646,9 @@ fn main() {
         ui_prompt::init(cx);
 
         go_to_line::init(cx);
+        bookmarks::init(cx);
+        persistent_undo::init(cx);
+        local_history::init(app_state.fs.clone(), cx);
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
@@ -649,8 +656,10 @@ fn main() {
         project_panel::init(cx);
         outline_panel::init(cx);
         worktrees_panel::init(cx);
+        undo_tree_panel::init(cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
+        spell_check::init(app_state.fs.clone(), cx);
         channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
         search::init(cx);
         vim::init(cx);
@@ -672,6 +681,7 @@ fn main() {
         onboarding::init(cx);
         settings_ui::init(cx);
         keymap_editor::init(cx);
+        keyboard_macros::init(cx);
         extensions_ui::init(cx);
         edit_prediction::init(cx);
         inspector_ui::init(app_state.clone(), cx);
@@ -56,6 +56,7 @@ use paths::{
 };
 use project::{DirectoryLister, DisableAiSettings, ProjectItem};
 use project_panel::ProjectPanel;
+use undo_tree_panel::UndoTreePanel;
 use worktrees_panel::WorktreesPanel;
 use prompt_store::PromptBuilder;
 use quick_action_bar::QuickActionBar;
@@ -434,6 +435,7 @@ pub fn initialize_workspace(
         let active_toolchain_language =
             cx.new(|cx| toolchain_selector::ActiveToolchain::new(workspace, window, cx));
         let vim_mode_indicator = cx.new(|cx| vim::ModeIndicator::new(window, cx));
+        let macro_recording_indicator = cx.new(keyboard_macros::RecordingIndicator::new);
         let image_info = cx.new(|_cx| ImageInfo::new(workspace));
 
         let lsp_button_menu_handle = PopoverMenuHandle::default();
@@ -460,6 +462,7 @@ pub fn initialize_workspace(
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(line_ending_indicator, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
+            status_bar.add_right_item(macro_recording_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
         });
@@ -660,6 +663,7 @@ fn initialize_panels(
         );
         let mut debug_cx = cx.clone();
         let debug_panel = DebugPanel::load(workspace_handle.clone(), &mut debug_cx);
+        let undo_tree_panel = UndoTreePanel::load(workspace_handle.clone(), cx.clone());
         let worktrees_panel = WorktreesPanel::load(workspace_handle.clone(), cx);
 
         async fn add_panel_when_ready(
@@ -685,6 +689,7 @@ fn initialize_panels(
             add_panel_when_ready(channels_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(notification_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(debug_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(undo_tree_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(worktrees_panel, workspace_handle.clone(), cx.clone()),
             initialize_agent_panel(workspace_handle.clone(), prompt_builder, cx.clone()).map(|r| r.log_err()),
             initialize_agents_panel(workspace_handle, cx.clone()).map(|r| r.log_err())
@@ -4822,6 +4827,7 @@ mod tests {
                 "theme_selector",
                 "toast",
                 "toolchain",
+                "undo_tree_panel",
                 "variable_list",
                 "vim",
                 "window",
@@ -4959,6 +4965,7 @@ mod tests {
             git_ui::init(cx);
             project_panel::init(cx);
             worktrees_panel::init(cx);
+            undo_tree_panel::init(cx);
             outline_panel::init(cx);
             terminal_view::init(cx);
             copilot_chat::init(
@@ -257,6 +257,11 @@ pub fn app_menus(cx: &mut App) -> Vec<Menu> {
                     "Previous Problem",
                     editor::actions::GoToPreviousDiagnostic::default(),
                 ),
+                MenuItem::separator(),
+                MenuItem::action("Toggle Bookmark", bookmarks::ToggleBookmark),
+                MenuItem::action("Next Bookmark", bookmarks::GoToNextBookmark),
+                MenuItem::action("Previous Bookmark", bookmarks::GoToPreviousBookmark),
+                MenuItem::action("Go to Bookmark...", bookmarks::ToggleBookmarkList),
             ],
         },
         Menu {
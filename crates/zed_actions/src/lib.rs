@@ -588,6 +588,18 @@ actions!(
     ]
 );
 
+pub mod hex_editor {
+    use gpui::actions;
+
+    actions!(
+        hex_editor,
+        [
+            /// Opens the active file in the hex editor.
+            OpenInHexEditor
+        ]
+    );
+}
+
 pub mod vim {
     use gpui::actions;
 